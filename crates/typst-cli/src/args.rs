@@ -1,4 +1,5 @@
 use std::fmt::{self, Display, Formatter};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 
 use clap::builder::ValueParser;
@@ -91,6 +92,32 @@ pub struct CompileCommand {
     /// apart from file names and line numbers.
     #[arg(long = "timings", value_name = "OUTPUT_JSON")]
     pub timings: Option<Option<PathBuf>>,
+
+    /// Bounds the memory used for compilation, e.g. `512M` or `2G`
+    #[cfg_attr(not(feature = "memory-budget"), doc = " (disabled)")]
+    ///
+    /// Memoized results are evicted when the memory a compilation keeps
+    /// allocated exceeds the budget. If that doesn't free enough memory,
+    /// compilation fails with an error.
+    #[arg(
+        long = "memory-budget",
+        value_name = "SIZE",
+        value_parser = ValueParser::new(parse_memory_size),
+    )]
+    pub memory_budget: Option<usize>,
+
+    /// Includes the pages of an existing PDF file in the exported PDF, e.g.
+    /// `start=cover.pdf` or `end=appendix.pdf`
+    ///
+    /// The position is `start`, `end`, or the number of the page after which
+    /// the file's pages are inserted. Can be given multiple times.
+    #[arg(
+        long = "import-pdf",
+        value_name = "POSITION=PATH",
+        action = ArgAction::Append,
+        value_parser = ValueParser::new(parse_pdf_import),
+    )]
+    pub pdf_imports: Vec<(PdfImportPosition, PathBuf)>,
 }
 
 /// Initializes a new project from a template
@@ -116,7 +143,12 @@ pub struct QueryCommand {
     pub common: SharedArgs,
 
     /// Defines which elements to retrieve
-    pub selector: String,
+    #[clap(required_unless_present = "outline")]
+    pub selector: Option<String>,
+
+    /// Retrieves the document's resolved outline instead of elements
+    #[clap(long = "outline", conflicts_with_all = ["selector", "field", "one"])]
+    pub outline: bool,
 
     /// Extracts just one field from all retrieved elements
     #[clap(long = "field")]
@@ -226,6 +258,45 @@ fn output_value_parser(value: &str) -> Result<Output, clap::error::Error> {
     }
 }
 
+/// Parses a number of bytes with an optional binary unit (`K`, `M`, or `G`).
+fn parse_memory_size(raw: &str) -> Result<usize, String> {
+    let raw = raw.trim();
+    let split = raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len());
+    let (number, unit) = raw.split_at(split);
+    let number: usize = number.parse().map_err(|_| "expected a number of bytes")?;
+    let factor = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        _ => return Err(format!("unknown unit `{unit}`, expected `K`, `M`, or `G`")),
+    };
+    number
+        .checked_mul(factor)
+        .ok_or_else(|| "memory size is too large".into())
+}
+
+/// Parses a position and a path split by the first equal sign.
+fn parse_pdf_import(raw: &str) -> Result<(PdfImportPosition, PathBuf), String> {
+    let (position, path) = raw
+        .split_once('=')
+        .ok_or("import must be a position and a path separated by an equal sign")?;
+    let position = match position.trim() {
+        "start" => PdfImportPosition::Start,
+        "end" => PdfImportPosition::End,
+        page => PdfImportPosition::After(page.parse().map_err(|_| {
+            format!(
+                "invalid position `{page}`, expected `start`, `end`, or a page number"
+            )
+        })?),
+    };
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("the path was missing or empty".to_owned());
+    }
+    Ok((position, path.into()))
+}
+
 /// Parses key/value pairs split by the first equal sign.
 ///
 /// This function will return an error if the argument contains no equals sign
@@ -307,3 +378,14 @@ impl Display for OutputFormat {
             .fmt(f)
     }
 }
+
+/// Where the pages of an imported PDF file are placed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PdfImportPosition {
+    /// Before the document's first page.
+    Start,
+    /// After the document's last page.
+    End,
+    /// After the document's page with the given number.
+    After(NonZeroUsize),
+}
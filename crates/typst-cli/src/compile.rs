@@ -10,14 +10,17 @@ use parking_lot::RwLock;
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use typst::diag::{bail, At, Severity, SourceDiagnostic, StrResult};
 use typst::eval::Tracer;
-use typst::foundations::{Datetime, Smart};
+use typst::foundations::{Bytes, Datetime, Smart};
 use typst::layout::Frame;
 use typst::model::Document;
 use typst::syntax::{FileId, Source, Span};
 use typst::visualize::Color;
 use typst::{World, WorldExt};
+use typst_pdf::{ImportPosition, PdfImport, PdfSource};
 
-use crate::args::{CompileCommand, DiagnosticFormat, Input, Output, OutputFormat};
+use crate::args::{
+    CompileCommand, DiagnosticFormat, Input, Output, OutputFormat, PdfImportPosition,
+};
 use crate::timings::Timer;
 use crate::watch::Status;
 use crate::world::SystemWorld;
@@ -97,6 +100,9 @@ pub fn compile_once(
         return Ok(());
     }
 
+    check_memory_budget(command)?;
+    world.set_memory_budget(command.memory_budget);
+
     let mut tracer = Tracer::new();
     let result = typst::compile(world, &mut tracer);
     let warnings = tracer.warnings();
@@ -146,6 +152,17 @@ pub fn compile_once(
     Ok(())
 }
 
+/// Rejects the `--memory-budget` option if allocations aren't counted.
+fn check_memory_budget(command: &CompileCommand) -> StrResult<()> {
+    if !cfg!(feature = "memory-budget") && command.memory_budget.is_some() {
+        bail!(
+            "memory budgets are not enabled for this executable, \
+             please build it with the `memory-budget` feature"
+        );
+    }
+    Ok(())
+}
+
 /// Export into the target format.
 fn export(
     world: &mut SystemWorld,
@@ -166,7 +183,27 @@ fn export(
 
 /// Export to a PDF.
 fn export_pdf(document: &Document, command: &CompileCommand) -> StrResult<()> {
-    let buffer = typst_pdf::pdf(document, Smart::Auto, now());
+    let imports = command
+        .pdf_imports
+        .iter()
+        .map(|(position, path)| {
+            let data = fs::read(path).map_err(|err| {
+                eco_format!("failed to read PDF file {} ({err})", path.display())
+            })?;
+            let source = PdfSource::new(Bytes::from(data)).map_err(|err| {
+                eco_format!("failed to import PDF file {} ({err})", path.display())
+            })?;
+            let position = match *position {
+                PdfImportPosition::Start => ImportPosition::Start,
+                PdfImportPosition::End => ImportPosition::End,
+                PdfImportPosition::After(page) => ImportPosition::After(page),
+            };
+            Ok(PdfImport { source, pages: None, position })
+        })
+        .collect::<StrResult<Vec<_>>>()?;
+
+    let buffer =
+        typst_pdf::pdf_with_imports(document, Smart::Auto, now(), None, &imports);
     command
         .output()
         .write(&buffer)
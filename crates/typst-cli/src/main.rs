@@ -24,6 +24,12 @@ use once_cell::sync::Lazy;
 use crate::args::{CliArguments, Command};
 use crate::timings::Timer;
 
+/// Counts allocations for the `--memory-budget` option.
+#[cfg(feature = "memory-budget")]
+#[global_allocator]
+static ALLOCATOR: typst::util::memory::CountingAllocator =
+    typst::util::memory::CountingAllocator;
+
 thread_local! {
     /// The CLI's exit code.
     static EXIT: Cell<ExitCode> = const { Cell::new(ExitCode::SUCCESS) };
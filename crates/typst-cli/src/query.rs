@@ -28,8 +28,12 @@ pub fn query(command: &QueryCommand) -> StrResult<()> {
     match result {
         // Retrieve and print query results.
         Ok(document) => {
-            let data = retrieve(&world, command, &document)?;
-            let serialized = format(data, command)?;
+            let serialized = if command.outline {
+                serialize(&document.outline().into_value(), command.format)?
+            } else {
+                let data = retrieve(&world, command, &document)?;
+                format(data, command)?
+            };
             println!("{serialized}");
             print_diagnostics(&world, &[], &warnings, command.common.diagnostic_format)
                 .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
@@ -57,9 +61,13 @@ fn retrieve(
     command: &QueryCommand,
     document: &Document,
 ) -> StrResult<Vec<Content>> {
+    let Some(selector) = &command.selector else {
+        bail!("expected a selector");
+    };
+
     let selector = eval_string(
         world.track(),
-        &command.selector,
+        selector,
         Span::detached(),
         EvalMode::Code,
         Scope::default(),
@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 use std::{fmt, fs, io, mem};
 
-use chrono::{DateTime, Datelike, Local};
+use chrono::{DateTime, Datelike, Local, Timelike};
 use comemo::Prehashed;
 use ecow::{eco_format, EcoString};
 use once_cell::sync::Lazy;
@@ -47,6 +47,8 @@ pub struct SystemWorld {
     /// The export cache, used for caching output files in `typst watch`
     /// sessions.
     export_cache: ExportCache,
+    /// The maximum number of bytes a compilation may keep allocated.
+    memory_budget: Option<usize>,
 }
 
 impl SystemWorld {
@@ -114,6 +116,7 @@ impl SystemWorld {
             slots: Mutex::new(HashMap::new()),
             now: OnceLock::new(),
             export_cache: ExportCache::new(),
+            memory_budget: None,
         })
     }
 
@@ -132,6 +135,11 @@ impl SystemWorld {
         self.workdir.as_deref().unwrap_or(Path::new("."))
     }
 
+    /// Set the memory budget of the following compilations.
+    pub fn set_memory_budget(&mut self, budget: Option<usize>) {
+        self.memory_budget = budget;
+    }
+
     /// Return all paths the last compilation depended on.
     pub fn dependencies(&mut self) -> impl Iterator<Item = PathBuf> + '_ {
         self.slots
@@ -200,6 +208,23 @@ impl World for SystemWorld {
             naive.day().try_into().ok()?,
         )
     }
+
+    fn modified(&self, id: FileId) -> Option<Datetime> {
+        let path = system_path(&self.root, id).ok()?;
+        let time: DateTime<Local> = fs::metadata(path).ok()?.modified().ok()?.into();
+        Datetime::from_ymd_hms(
+            time.year(),
+            time.month().try_into().ok()?,
+            time.day().try_into().ok()?,
+            time.hour().try_into().ok()?,
+            time.minute().try_into().ok()?,
+            time.second().try_into().ok()?,
+        )
+    }
+
+    fn memory_budget(&self) -> Option<usize> {
+        self.memory_budget
+    }
 }
 
 impl SystemWorld {
@@ -1,16 +1,18 @@
 use once_cell::sync::Lazy;
 use pdf_writer::types::DeviceNSubtype;
 use pdf_writer::{writers, Chunk, Dict, Filter, Name, Ref};
-use typst::visualize::{Color, ColorSpace, Paint};
+use typst::visualize::{Color, ColorSpace, IccProfile, Paint};
 
 use crate::deflate;
 use crate::page::{PageContext, Transforms};
 
 // The names of the color spaces.
 pub const SRGB: Name<'static> = Name(b"srgb");
+pub const DISPLAY_P3: Name<'static> = Name(b"displayp3");
 pub const D65_GRAY: Name<'static> = Name(b"d65gray");
 pub const OKLAB: Name<'static> = Name(b"oklab");
 pub const LINEAR_SRGB: Name<'static> = Name(b"linearrgb");
+pub const LAB: Name<'static> = Name(b"lab");
 
 // The names of the color components.
 const OKLAB_L: Name<'static> = Name(b"L");
@@ -22,6 +24,7 @@ static SRGB_ICC_DEFLATED: Lazy<Vec<u8>> =
     Lazy::new(|| deflate(typst_assets::icc::S_RGB_V4));
 static GRAY_ICC_DEFLATED: Lazy<Vec<u8>> =
     Lazy::new(|| deflate(typst_assets::icc::S_GREY_V4));
+static DISPLAY_P3_ICC_DEFLATED: Lazy<Vec<u8>> = Lazy::new(|| deflate(&display_p3_icc()));
 
 // The PostScript functions for color spaces.
 static OKLAB_DEFLATED: Lazy<Vec<u8>> =
@@ -32,8 +35,11 @@ static OKLAB_DEFLATED: Lazy<Vec<u8>> =
 pub struct ColorSpaces {
     oklab: Option<Ref>,
     srgb: Option<Ref>,
+    display_p3: Option<Ref>,
     d65_gray: Option<Ref>,
+    icc: Vec<(IccProfile, Ref)>,
     use_linear_rgb: bool,
+    use_lab: bool,
 }
 
 impl ColorSpaces {
@@ -51,16 +57,36 @@ impl ColorSpaces {
         *self.srgb.get_or_insert_with(|| alloc.bump())
     }
 
+    /// Get a reference to the Display P3 color space.
+    pub fn display_p3(&mut self, alloc: &mut Ref) -> Ref {
+        *self.display_p3.get_or_insert_with(|| alloc.bump())
+    }
+
     /// Get a reference to the gray color space.
     pub fn d65_gray(&mut self, alloc: &mut Ref) -> Ref {
         *self.d65_gray.get_or_insert_with(|| alloc.bump())
     }
 
+    /// Get the name of the color space of a user-supplied ICC profile.
+    pub fn icc(&mut self, profile: IccProfile, alloc: &mut Ref) -> Name<'static> {
+        // Profiles that only differ in their rendering intent share a color
+        // space. The intent is set through the graphics state instead.
+        if !self.icc.iter().any(|(p, _)| p.name() == profile.name()) {
+            self.icc.push((profile, alloc.bump()));
+        }
+        Name(profile.name().as_bytes())
+    }
+
     /// Mark linear RGB as used.
     pub fn linear_rgb(&mut self) {
         self.use_linear_rgb = true;
     }
 
+    /// Mark CIELAB as used.
+    pub fn lab(&mut self) {
+        self.use_lab = true;
+    }
+
     /// Write the color space on usage.
     pub fn write(
         &mut self,
@@ -69,7 +95,12 @@ impl ColorSpaces {
         alloc: &mut Ref,
     ) {
         match color_space {
-            ColorSpace::Oklab | ColorSpace::Hsl | ColorSpace::Hsv => {
+            ColorSpace::Oklab
+            | ColorSpace::Hsl
+            | ColorSpace::Hsv
+            | ColorSpace::Hwb
+            | ColorSpace::Okhsl
+            | ColorSpace::Okhsv => {
                 let mut oklab = writer.device_n([OKLAB_L, OKLAB_A, OKLAB_B]);
                 self.write(ColorSpace::LinearRgb, oklab.alternate_color_space(), alloc);
                 oklab.tint_ref(self.oklab(alloc));
@@ -77,6 +108,7 @@ impl ColorSpaces {
             }
             ColorSpace::Oklch => self.write(ColorSpace::Oklab, writer, alloc),
             ColorSpace::Srgb => writer.icc_based(self.srgb(alloc)),
+            ColorSpace::DisplayP3 => writer.icc_based(self.display_p3(alloc)),
             ColorSpace::D65Gray => writer.icc_based(self.d65_gray(alloc)),
             ColorSpace::LinearRgb => {
                 writer.cal_rgb(
@@ -89,6 +121,13 @@ impl ColorSpaces {
                     ]),
                 );
             }
+            ColorSpace::Lab | ColorSpace::Lch | ColorSpace::Xyz(_) => {
+                writer.lab(
+                    [0.9642, 1.0, 0.8249],
+                    None,
+                    Some([-128.0, 127.0, -128.0, 127.0]),
+                );
+            }
             ColorSpace::Cmyk => writer.device_cmyk(),
         }
     }
@@ -103,6 +142,10 @@ impl ColorSpaces {
             self.write(ColorSpace::Srgb, spaces.insert(SRGB).start(), alloc);
         }
 
+        if self.display_p3.is_some() {
+            self.write(ColorSpace::DisplayP3, spaces.insert(DISPLAY_P3).start(), alloc);
+        }
+
         if self.d65_gray.is_some() {
             self.write(ColorSpace::D65Gray, spaces.insert(D65_GRAY).start(), alloc);
         }
@@ -110,6 +153,17 @@ impl ColorSpaces {
         if self.use_linear_rgb {
             self.write(ColorSpace::LinearRgb, spaces.insert(LINEAR_SRGB).start(), alloc);
         }
+
+        if self.use_lab {
+            self.write(ColorSpace::Lab, spaces.insert(LAB).start(), alloc);
+        }
+
+        for &(profile, icc) in &self.icc {
+            spaces
+                .insert(Name(profile.name().as_bytes()))
+                .start::<writers::ColorSpace>()
+                .icc_based(icc);
+        }
     }
 
     /// Write the necessary color spaces functions and ICC profiles to the
@@ -133,6 +187,15 @@ impl ColorSpaces {
                 .filter(Filter::FlateDecode);
         }
 
+        // Write the Display P3 color space.
+        if let Some(display_p3) = self.display_p3 {
+            chunk
+                .icc_profile(display_p3, &DISPLAY_P3_ICC_DEFLATED)
+                .n(3)
+                .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
+                .filter(Filter::FlateDecode);
+        }
+
         // Write the gray color space.
         if let Some(gray) = self.d65_gray {
             chunk
@@ -141,9 +204,56 @@ impl ColorSpaces {
                 .range([0.0, 1.0])
                 .filter(Filter::FlateDecode);
         }
+
+        // Write the user-supplied ICC profiles.
+        for &(profile, icc) in &self.icc {
+            let channels = profile.space().channels();
+            chunk
+                .icc_profile(icc, &deflate(profile.data()))
+                .n(channels as i32)
+                .range([0.0, 1.0].repeat(channels))
+                .filter(Filter::FlateDecode);
+        }
     }
 }
 
+/// Builds an ICC profile for Display P3.
+///
+/// Display P3 shares its white point and transfer function with sRGB, so the
+/// profile is derived from the sRGB profile by replacing the primaries and the
+/// description.
+fn display_p3_icc() -> Vec<u8> {
+    // The D50-adapted primaries of Display P3.
+    const PRIMARIES: [(usize, [f64; 3]); 3] = [
+        (388, [0.515102, 0.241182, -0.001050]),
+        (408, [0.291965, 0.692236, 0.041882]),
+        (428, [0.157153, 0.066582, 0.784378]),
+    ];
+
+    let mut profile = typst_assets::icc::S_RGB_V4.to_vec();
+
+    // Replace the XYZ values of the rXYZ, gXYZ, and bXYZ tags, which are
+    // encoded as s15Fixed16Number.
+    for (offset, xyz) in PRIMARIES {
+        for (i, v) in xyz.into_iter().enumerate() {
+            let start = offset + 8 + 4 * i;
+            let fixed = (v * 65536.0).round() as i32;
+            profile[start..start + 4].copy_from_slice(&fixed.to_be_bytes());
+        }
+    }
+
+    // Replace the "sRGB" description with "P3" (in UTF-16BE).
+    let desc = 252;
+    profile[desc + 20..desc + 24].copy_from_slice(&4u32.to_be_bytes());
+    profile[desc + 28..desc + 32].copy_from_slice(&[0, b'P', 0, b'3']);
+
+    // The profile ID is an MD5 checksum of the profile. A zero ID signals
+    // that it has not been computed.
+    profile[84..100].fill(0);
+
+    profile
+}
+
 /// This function removes comments, line spaces and carriage returns from a
 /// PostScript program. This is necessary to optimize the size of the PDF file.
 fn minify(source: &str) -> String {
@@ -174,9 +284,11 @@ fn minify(source: &str) -> String {
 ///   specifies (and some readers enforce) that all color values be in the range
 ///   [0.0, 1.0]. This means that the PostScript function and the encoded color
 ///   must be offset by 0.5.
-/// - HSV/HSL: The hue component is in the range [0.0, 360.0] and the PDF format
-///   specifies that it must be in the range [0.0, 1.0]. This means that the
-///   PostScript function and the encoded color must be divided by 360.0.
+/// - HSV/HSL/HWB: The hue component is in the range [0.0, 360.0] and the PDF
+///   format specifies that it must be in the range [0.0, 1.0]. This means that
+///   the PostScript function and the encoded color must be divided by 360.0.
+/// - CIELAB/CIELCh/CIE XYZ: All are written as CIELAB, whose a and b components must
+///   lie within the range declared for the color space.
 pub trait ColorEncode {
     /// Performs the color to PDF f32 array conversion.
     fn encode(&self, color: Color) -> [f32; 4];
@@ -185,7 +297,13 @@ pub trait ColorEncode {
 impl ColorEncode for ColorSpace {
     fn encode(&self, color: Color) -> [f32; 4] {
         match self {
-            ColorSpace::Oklab | ColorSpace::Oklch | ColorSpace::Hsl | ColorSpace::Hsv => {
+            ColorSpace::Oklab
+            | ColorSpace::Oklch
+            | ColorSpace::Hsl
+            | ColorSpace::Hsv
+            | ColorSpace::Hwb
+            | ColorSpace::Okhsl
+            | ColorSpace::Okhsv => {
                 let [l, c, h, alpha] = color.to_oklch().to_vec4();
                 // Clamp on Oklch's chroma, not Oklab's a\* and b\* as to not distort hue.
                 let c = c.clamp(0.0, 0.5);
@@ -194,6 +312,10 @@ impl ColorEncode for ColorSpace {
                 let b = c * h.to_radians().sin();
                 [l, a + 0.5, b + 0.5, alpha]
             }
+            ColorSpace::Lab | ColorSpace::Lch | ColorSpace::Xyz(_) => {
+                let [l, a, b, alpha] = color.to_lab().to_vec4();
+                [l, a.clamp(-128.0, 127.0), b.clamp(-128.0, 127.0), alpha]
+            }
             _ => color.to_space(*self).to_vec4(),
         }
     }
@@ -242,13 +364,27 @@ impl PaintEncode for Color {
                 ctx.content.set_fill_color([l]);
             }
             // Oklch is converted to Oklab.
-            Color::Oklab(_) | Color::Oklch(_) | Color::Hsl(_) | Color::Hsv(_) => {
+            Color::Oklab(_)
+            | Color::Oklch(_)
+            | Color::Hsl(_)
+            | Color::Hsv(_)
+            | Color::Hwb(_)
+            | Color::Okhsl(_)
+            | Color::Okhsv(_) => {
                 ctx.parent.colors.oklab(&mut ctx.parent.alloc);
                 ctx.set_fill_color_space(OKLAB);
 
                 let [l, a, b, _] = ColorSpace::Oklab.encode(*self);
                 ctx.content.set_fill_color([l, a, b]);
             }
+            // CIELCh and CIE XYZ are converted to CIELAB.
+            Color::Lab(_) | Color::Lch(_) | Color::Xyz(_) => {
+                ctx.parent.colors.lab();
+                ctx.set_fill_color_space(LAB);
+
+                let [l, a, b, _] = ColorSpace::Lab.encode(*self);
+                ctx.content.set_fill_color([l, a, b]);
+            }
             Color::LinearRgb(_) => {
                 ctx.parent.colors.linear_rgb();
                 ctx.set_fill_color_space(LINEAR_SRGB);
@@ -263,12 +399,24 @@ impl PaintEncode for Color {
                 let [r, g, b, _] = ColorSpace::Srgb.encode(*self);
                 ctx.content.set_fill_color([r, g, b]);
             }
+            Color::P3(_) => {
+                ctx.parent.colors.display_p3(&mut ctx.parent.alloc);
+                ctx.set_fill_color_space(DISPLAY_P3);
+
+                let [r, g, b, _] = ColorSpace::DisplayP3.encode(*self);
+                ctx.content.set_fill_color([r, g, b]);
+            }
             Color::Cmyk(_) => {
                 ctx.reset_fill_color_space();
 
                 let [c, m, y, k] = ColorSpace::Cmyk.encode(*self);
                 ctx.content.set_fill_cmyk(c, m, y, k);
             }
+            Color::Icc(c) => {
+                let space = ctx.parent.colors.icc(c.profile, &mut ctx.parent.alloc);
+                ctx.set_fill_color_space(space);
+                ctx.content.set_fill_color(c.channels().iter().copied());
+            }
         }
     }
 
@@ -282,13 +430,27 @@ impl PaintEncode for Color {
                 ctx.content.set_stroke_color([l]);
             }
             // Oklch is converted to Oklab.
-            Color::Oklab(_) | Color::Oklch(_) | Color::Hsl(_) | Color::Hsv(_) => {
+            Color::Oklab(_)
+            | Color::Oklch(_)
+            | Color::Hsl(_)
+            | Color::Hsv(_)
+            | Color::Hwb(_)
+            | Color::Okhsl(_)
+            | Color::Okhsv(_) => {
                 ctx.parent.colors.oklab(&mut ctx.parent.alloc);
                 ctx.set_stroke_color_space(OKLAB);
 
                 let [l, a, b, _] = ColorSpace::Oklab.encode(*self);
                 ctx.content.set_stroke_color([l, a, b]);
             }
+            // CIELCh and CIE XYZ are converted to CIELAB.
+            Color::Lab(_) | Color::Lch(_) | Color::Xyz(_) => {
+                ctx.parent.colors.lab();
+                ctx.set_stroke_color_space(LAB);
+
+                let [l, a, b, _] = ColorSpace::Lab.encode(*self);
+                ctx.content.set_stroke_color([l, a, b]);
+            }
             Color::LinearRgb(_) => {
                 ctx.parent.colors.linear_rgb();
                 ctx.set_stroke_color_space(LINEAR_SRGB);
@@ -303,12 +465,24 @@ impl PaintEncode for Color {
                 let [r, g, b, _] = ColorSpace::Srgb.encode(*self);
                 ctx.content.set_stroke_color([r, g, b]);
             }
+            Color::P3(_) => {
+                ctx.parent.colors.display_p3(&mut ctx.parent.alloc);
+                ctx.set_stroke_color_space(DISPLAY_P3);
+
+                let [r, g, b, _] = ColorSpace::DisplayP3.encode(*self);
+                ctx.content.set_stroke_color([r, g, b]);
+            }
             Color::Cmyk(_) => {
                 ctx.reset_stroke_color_space();
 
                 let [c, m, y, k] = ColorSpace::Cmyk.encode(*self);
                 ctx.content.set_stroke_cmyk(c, m, y, k);
             }
+            Color::Icc(c) => {
+                let space = ctx.parent.colors.icc(c.profile, &mut ctx.parent.alloc);
+                ctx.set_stroke_color_space(space);
+                ctx.content.set_stroke_color(c.channels().iter().copied());
+            }
         }
     }
 }
@@ -324,7 +498,12 @@ pub(super) trait ColorSpaceExt {
 
 impl ColorSpaceExt for ColorSpace {
     fn range(self) -> [f32; 6] {
-        [0.0, 1.0, 0.0, 1.0, 0.0, 1.0]
+        match self {
+            ColorSpace::Lab | ColorSpace::Lch | ColorSpace::Xyz(_) => {
+                [0.0, 100.0, -128.0, 127.0, -128.0, 127.0]
+            }
+            _ => [0.0, 1.0, 0.0, 1.0, 0.0, 1.0],
+        }
     }
 
     fn convert<U: QuantizedColor>(self, color: Color) -> [U; 3] {
@@ -1,3 +1,8 @@
+use pdf_writer::types::{
+    BlendMode as PdfBlendMode, OverprintMode, RenderingIntent as PdfRenderingIntent,
+};
+use typst::visualize::{BlendMode, RenderingIntent};
+
 use crate::PdfContext;
 
 /// A PDF external graphics state.
@@ -7,11 +12,22 @@ pub struct ExtGState {
     pub stroke_opacity: u8,
     // In the range 0-255, needs to be divided before being written into the graphics state!
     pub fill_opacity: u8,
+    pub blend_mode: BlendMode,
+    pub stroke_overprint: bool,
+    pub fill_overprint: bool,
+    pub rendering_intent: Option<RenderingIntent>,
 }
 
 impl Default for ExtGState {
     fn default() -> Self {
-        Self { stroke_opacity: 255, fill_opacity: 255 }
+        Self {
+            stroke_opacity: 255,
+            fill_opacity: 255,
+            blend_mode: BlendMode::Normal,
+            stroke_overprint: false,
+            fill_overprint: false,
+            rendering_intent: None,
+        }
     }
 }
 
@@ -19,6 +35,10 @@ impl ExtGState {
     pub fn uses_opacities(&self) -> bool {
         self.stroke_opacity != 255 || self.fill_opacity != 255
     }
+
+    pub fn uses_overprint(&self) -> bool {
+        self.stroke_overprint || self.fill_overprint
+    }
 }
 
 /// Embed all used external graphics states into the PDF.
@@ -26,9 +46,53 @@ pub(crate) fn write_external_graphics_states(ctx: &mut PdfContext) {
     for external_gs in ctx.extg_map.items() {
         let id = ctx.alloc.bump();
         ctx.ext_gs_refs.push(id);
-        ctx.pdf
-            .ext_graphics(id)
-            .non_stroking_alpha(external_gs.fill_opacity as f32 / 255.0)
-            .stroking_alpha(external_gs.stroke_opacity as f32 / 255.0);
+        let mut gs = ctx.pdf.ext_graphics(id);
+        gs.non_stroking_alpha(external_gs.fill_opacity as f32 / 255.0)
+            .stroking_alpha(external_gs.stroke_opacity as f32 / 255.0)
+            .blend_mode(to_pdf_blend_mode(external_gs.blend_mode));
+
+        // With the nonzero overprint mode, inks that a CMYK color doesn't use
+        // leave the ones below untouched, which is what print shops expect.
+        if external_gs.uses_overprint() {
+            gs.overprint(external_gs.stroke_overprint)
+                .overprint_fill(external_gs.fill_overprint)
+                .overprint_mode(OverprintMode::IgnoreZeroChannel);
+        }
+
+        if let Some(intent) = external_gs.rendering_intent {
+            gs.rendering_intent(to_pdf_rendering_intent(intent));
+        }
+    }
+}
+
+/// Convert a blend mode to its PDF equivalent.
+fn to_pdf_blend_mode(mode: BlendMode) -> PdfBlendMode {
+    match mode {
+        BlendMode::Normal => PdfBlendMode::Normal,
+        BlendMode::Multiply => PdfBlendMode::Multiply,
+        BlendMode::Screen => PdfBlendMode::Screen,
+        BlendMode::Overlay => PdfBlendMode::Overlay,
+        BlendMode::Darken => PdfBlendMode::Darken,
+        BlendMode::Lighten => PdfBlendMode::Lighten,
+        BlendMode::ColorDodge => PdfBlendMode::ColorDodge,
+        BlendMode::ColorBurn => PdfBlendMode::ColorBurn,
+        BlendMode::HardLight => PdfBlendMode::HardLight,
+        BlendMode::SoftLight => PdfBlendMode::SoftLight,
+        BlendMode::Difference => PdfBlendMode::Difference,
+        BlendMode::Exclusion => PdfBlendMode::Exclusion,
+        BlendMode::Hue => PdfBlendMode::Hue,
+        BlendMode::Saturation => PdfBlendMode::Saturation,
+        BlendMode::Color => PdfBlendMode::Color,
+        BlendMode::Luminosity => PdfBlendMode::Luminosity,
+    }
+}
+
+/// Convert a rendering intent to its PDF equivalent.
+fn to_pdf_rendering_intent(intent: RenderingIntent) -> PdfRenderingIntent {
+    match intent {
+        RenderingIntent::Perceptual => PdfRenderingIntent::Perceptual,
+        RenderingIntent::RelativeColorimetric => PdfRenderingIntent::RelativeColorimetric,
+        RenderingIntent::Saturation => PdfRenderingIntent::Saturation,
+        RenderingIntent::AbsoluteColorimetric => PdfRenderingIntent::AbsoluteColorimetric,
     }
 }
@@ -153,14 +153,17 @@ fn shading_function(
     let mut encode = vec![];
 
     // Create the individual gradient functions for each pair of stops.
-    for window in gradient.stops_ref().windows(2) {
+    for (i, window) in gradient.stops_ref().windows(2).enumerate() {
         let (first, second) = (window[0], window[1]);
 
-        // If we have a hue index, we will create several stops in-between
-        // to make the gradient smoother without interpolation issues with
-        // native color spaces.
+        // If we have a hue index, an easing, or a segment interpolated in
+        // another color space, we will create several stops in-between to
+        // make the gradient smoother without interpolation issues with native
+        // color spaces.
+        let (space, easing) = gradient.interpolation(i);
         let mut last_c = first.0;
-        if gradient.space().hue_index().is_some() {
+        if space != gradient.space() || space.hue_index().is_some() || !easing.is_linear()
+        {
             for i in 0..=32 {
                 let t = i as f64 / 32.0;
                 let real_t = first.1.get() * (1.0 - t) + second.1.get() * t;
@@ -295,6 +298,7 @@ fn register_gradient(
     let pdf_gradient = PdfGradient {
         aspect_ratio: size.aspect_ratio(),
         transform: transform
+            .pre_concat(gradient.transformation().in_size(size))
             .pre_concat(Transform::translate(
                 offset_x * scale_offset,
                 offset_y * scale_offset,
@@ -396,16 +400,21 @@ fn compute_vertex_stream(gradient: &Gradient, aspect_ratio: Ratio) -> Arc<Vec<u8
     // Correct the gradient's angle
     let angle = Gradient::correct_aspect_ratio(conic.angle, aspect_ratio);
 
-    for window in conic.stops.windows(2) {
+    for (i, window) in conic.stops.windows(2).enumerate() {
         let ((c0, t0), (c1, t1)) = (window[0], window[1]);
+        let (space, easing) = gradient.interpolation(i);
 
         // Precision:
         // - On an even color, insert a stop every 90deg
-        // - For a hue-based color space, insert 200 stops minimum
+        // - For a hue-based or different color space or an easing, insert 200
+        //   stops minimum
         // - On any other, insert 20 stops minimum
         let max_dt = if c0 == c1 {
             0.25
-        } else if conic.space.hue_index().is_some() {
+        } else if space != conic.space
+            || space.hue_index().is_some()
+            || !easing.is_linear()
+        {
             0.005
         } else {
             0.05
@@ -435,10 +444,11 @@ fn compute_vertex_stream(gradient: &Gradient, aspect_ratio: Ratio) -> Arc<Vec<u8
             let t_next = (t_x + dt).min(t1.get());
 
             // The current progress in the current window.
-            let t = |t| (t - t0.get()) / (t1.get() - t0.get());
+            let t = |t| easing.apply((t - t0.get()) / (t1.get() - t0.get()));
             let c = Color::mix_iter(
                 [WeightedColor::new(c0, 1.0 - t(t_x)), WeightedColor::new(c1, t(t_x))],
-                conic.space,
+                space,
+                conic.hue,
             )
             .unwrap();
 
@@ -447,7 +457,8 @@ fn compute_vertex_stream(gradient: &Gradient, aspect_ratio: Ratio) -> Arc<Vec<u8
                     WeightedColor::new(c0, 1.0 - t(t_next)),
                     WeightedColor::new(c1, t(t_next)),
                 ],
-                conic.space,
+                space,
+                conic.hue,
             )
             .unwrap();
 
@@ -0,0 +1,35 @@
+use pdf_writer::{Filter, Finish, Name, Rect};
+use typst::layout::Abs;
+
+use crate::{AbsExt, PdfContext};
+
+/// Writes the isolated transparency groups as form XObjects to the PDF.
+/// This is performed once after writing all pages.
+pub(crate) fn write_groups(ctx: &mut PdfContext) {
+    for PdfGroup { content, bbox } in ctx.group_map.items() {
+        let id = ctx.alloc.bump();
+        ctx.group_refs.push(id);
+
+        let [x1, y1, x2, y2] = bbox.map(|v| v.to_f32());
+        let mut form = ctx.pdf.form_xobject(id, content);
+        form.filter(Filter::FlateDecode);
+        form.bbox(Rect::new(x1, y1, x2, y2));
+        form.pair(Name(b"Resources"), ctx.global_resources_ref);
+        form.group()
+            .transparency()
+            .isolated(true)
+            .knockout(false)
+            .color_space()
+            .srgb();
+        form.finish();
+    }
+}
+
+/// An isolated transparency group.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PdfGroup {
+    /// The compressed content stream of the group.
+    pub content: Vec<u8>,
+    /// The bounding box of the group in its own coordinate system.
+    pub bbox: [Abs; 4],
+}
@@ -0,0 +1,1218 @@
+//! Importing pages from existing PDF files.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Debug, Formatter};
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::sync::Arc;
+
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+use pdf_writer::{Dict as DictWriter, Name, Null, Obj, Ref, Str};
+use typst::diag::{bail, StrResult};
+use typst::foundations::Bytes;
+use typst::layout::PageRanges;
+
+use crate::PdfContext;
+
+/// Page attributes that may be inherited from the page tree.
+const INHERITABLE: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+/// Page attributes that are not carried over into the exported document.
+const DROPPED: [&[u8]; 3] = [b"Parent", b"StructParents", b"B"];
+
+/// The maximum nesting depth of parsed objects and page trees.
+const MAX_DEPTH: usize = 256;
+
+/// Pages of an existing PDF file to include in an exported document.
+#[derive(Debug, Clone)]
+pub struct PdfImport {
+    /// The file to take the pages from.
+    pub source: PdfSource,
+    /// Which of the file's pages to include. If `None`, all pages are
+    /// included.
+    pub pages: Option<PageRanges>,
+    /// Where to place the pages in the exported document.
+    pub position: ImportPosition,
+}
+
+/// Where imported pages are placed in the exported document.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ImportPosition {
+    /// Before the document's first page.
+    Start,
+    /// After the document's last page.
+    End,
+    /// After the document's page with the given physical number. If the
+    /// document has fewer pages, this is the same as `End`.
+    After(NonZeroUsize),
+}
+
+/// A parsed PDF file whose pages can be imported.
+///
+/// This is cheap to clone.
+#[derive(Clone)]
+pub struct PdfSource(Arc<Repr>);
+
+/// The internal representation of a [`PdfSource`].
+struct Repr {
+    /// The raw file. Stream data is not copied out of it.
+    data: Bytes,
+    /// All objects of the file by their ID.
+    objects: HashMap<u32, Object>,
+    /// The pages in order.
+    pages: Vec<SourcePage>,
+    /// The IDs of all nodes of the page tree, including the pages.
+    tree: HashSet<u32>,
+}
+
+/// A page of a parsed PDF file.
+struct SourcePage {
+    /// The ID of the page object.
+    id: u32,
+    /// Attributes the page inherits from the page tree.
+    inherited: Vec<(Vec<u8>, Object)>,
+}
+
+impl PdfSource {
+    /// Parse a PDF file.
+    ///
+    /// Damaged cross-reference tables are recovered from by scanning the file
+    /// for objects. Encrypted files are not supported.
+    pub fn new(data: Bytes) -> StrResult<Self> {
+        if find(&data[..data.len().min(1024)], b"%PDF-").is_none() {
+            bail!("file is not a valid PDF file");
+        }
+
+        let repr = match read_xref(&data) {
+            Some(xref) => {
+                load(data.clone(), xref).or_else(|_| load(data.clone(), scan_xref(&data)))
+            }
+            None => load(data.clone(), scan_xref(&data)),
+        }?;
+
+        Ok(Self(Arc::new(repr)))
+    }
+
+    /// The number of pages in the file.
+    pub fn page_count(&self) -> usize {
+        self.0.pages.len()
+    }
+}
+
+impl Debug for PdfSource {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("PdfSource")
+            .field("pages", &self.page_count())
+            .finish()
+    }
+}
+
+/// Write the pages of imported PDF files and insert them into the page order.
+#[typst_macros::time(name = "write imports")]
+pub(crate) fn write_imports(ctx: &mut PdfContext, imports: &[PdfImport]) {
+    if imports.is_empty() {
+        return;
+    }
+
+    let written: Vec<Vec<Ref>> =
+        imports.iter().map(|import| write_import(ctx, import)).collect();
+
+    let count = ctx.pages.len();
+    let slot = |position| match position {
+        ImportPosition::Start => 0,
+        ImportPosition::End => count,
+        ImportPosition::After(n) => n.get().min(count),
+    };
+
+    let mut page_refs = vec![];
+    for i in 0..=count {
+        for (import, refs) in imports.iter().zip(&written) {
+            if slot(import.position) == i {
+                page_refs.extend(refs);
+            }
+        }
+
+        if let Some(Some(page)) = ctx.pages.get(i) {
+            page_refs.push(page.id);
+        }
+    }
+
+    ctx.page_refs = page_refs;
+}
+
+/// Copy the selected pages of an imported file and everything they reference.
+///
+/// Returns the IDs of the written pages.
+fn write_import(ctx: &mut PdfContext, import: &PdfImport) -> Vec<Ref> {
+    let source = &*import.source.0;
+    let pages: Vec<&SourcePage> = source
+        .pages
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| {
+            import
+                .pages
+                .as_ref()
+                .map_or(true, |ranges| ranges.includes_page_index(*i))
+        })
+        .map(|(_, page)| page)
+        .collect();
+
+    // References to the page tree are only kept for the imported pages
+    // themselves. All others turn into null.
+    let mut refs = HashMap::new();
+    for page in &pages {
+        refs.insert(page.id, ctx.alloc.bump());
+    }
+
+    // Allocate IDs for all objects reachable from the imported pages.
+    let mut queue = vec![];
+    for page in &pages {
+        if let Some(dict) = source.objects.get(&page.id).and_then(Object::as_dict) {
+            for (key, value) in &dict.0 {
+                if !DROPPED.contains(&key.as_slice()) {
+                    value.visit_refs(&mut |id| queue.push(id));
+                }
+            }
+        }
+
+        for (_, value) in &page.inherited {
+            value.visit_refs(&mut |id| queue.push(id));
+        }
+    }
+
+    let mut reachable = vec![];
+    while let Some(id) = queue.pop() {
+        if source.tree.contains(&id) || refs.contains_key(&id) {
+            continue;
+        }
+
+        let Some(object) = source.objects.get(&id) else { continue };
+        refs.insert(id, ctx.alloc.bump());
+        reachable.push((id, object));
+        object.visit_refs(&mut |id| queue.push(id));
+    }
+
+    // Write the pages.
+    for page in &pages {
+        let mut writer = ctx.pdf.indirect(refs[&page.id]).dict();
+        writer.pair(Name(b"Type"), Name(b"Page"));
+        writer.pair(Name(b"Parent"), ctx.page_tree_ref);
+
+        if let Some(dict) = source.objects.get(&page.id).and_then(Object::as_dict) {
+            for (key, value) in &dict.0 {
+                if key != b"Type" && !DROPPED.contains(&key.as_slice()) {
+                    write_object(writer.insert(Name(key)), value, &refs);
+                }
+            }
+        }
+
+        for (key, value) in &page.inherited {
+            write_object(writer.insert(Name(key)), value, &refs);
+        }
+    }
+
+    // Write the objects referenced by them.
+    for (id, object) in reachable {
+        let new = refs[&id];
+        match object {
+            Object::Stream(dict, range) => {
+                let mut stream = ctx.pdf.stream(new, &source.data[range.clone()]);
+                write_entries(&mut stream, dict, &refs, &[b"Length"]);
+            }
+            _ => write_object(ctx.pdf.indirect(new), object, &refs),
+        }
+    }
+
+    pages.iter().map(|page| refs[&page.id]).collect()
+}
+
+/// Write a parsed object with remapped references.
+fn write_object(obj: Obj, object: &Object, refs: &HashMap<u32, Ref>) {
+    match object {
+        Object::Null => obj.primitive(Null),
+        Object::Bool(v) => obj.primitive(*v),
+        Object::Int(v) => match i32::try_from(*v) {
+            Ok(v) => obj.primitive(v),
+            Err(_) => obj.primitive(*v as f32),
+        },
+        Object::Real(v) => obj.primitive(*v as f32),
+        Object::Str(v) => obj.primitive(Str(v)),
+        Object::Name(v) => obj.primitive(Name(v)),
+        Object::Array(items) => {
+            let mut array = obj.array();
+            for item in items {
+                write_object(array.push(), item, refs);
+            }
+        }
+        Object::Dict(dict) | Object::Stream(dict, _) => {
+            write_entries(&mut obj.dict(), dict, refs, &[]);
+        }
+        Object::Ref(id) => match refs.get(id) {
+            Some(&new) => obj.primitive(new),
+            None => obj.primitive(Null),
+        },
+    }
+}
+
+/// Write the entries of a parsed dictionary, except for the given keys.
+fn write_entries(
+    writer: &mut DictWriter,
+    dict: &Dict,
+    refs: &HashMap<u32, Ref>,
+    skip: &[&[u8]],
+) {
+    for (key, value) in &dict.0 {
+        if !skip.contains(&key.as_slice()) {
+            write_object(writer.insert(Name(key)), value, refs);
+        }
+    }
+}
+
+/// A parsed PDF object.
+#[derive(Debug, Clone, PartialEq)]
+enum Object {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Real(f64),
+    Str(Vec<u8>),
+    Name(Vec<u8>),
+    Array(Vec<Object>),
+    Dict(Dict),
+    /// A stream with its dictionary and the range of its still encoded data
+    /// in the file.
+    Stream(Dict, Range<usize>),
+    Ref(u32),
+}
+
+impl Object {
+    fn as_int(&self) -> Option<i64> {
+        match *self {
+            Self::Int(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    fn as_usize(&self) -> Option<usize> {
+        self.as_int().and_then(|v| usize::try_from(v).ok())
+    }
+
+    fn as_name(&self) -> Option<&[u8]> {
+        match self {
+            Self::Name(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    fn as_array(&self) -> Option<&[Object]> {
+        match self {
+            Self::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    fn as_dict(&self) -> Option<&Dict> {
+        match self {
+            Self::Dict(dict) | Self::Stream(dict, _) => Some(dict),
+            _ => None,
+        }
+    }
+
+    fn as_ref(&self) -> Option<u32> {
+        match *self {
+            Self::Ref(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Call `f` with every reference contained in the object.
+    fn visit_refs(&self, f: &mut impl FnMut(u32)) {
+        match self {
+            Self::Ref(id) => f(*id),
+            Self::Array(items) => items.iter().for_each(|item| item.visit_refs(f)),
+            Self::Dict(dict) | Self::Stream(dict, _) => {
+                dict.0.iter().for_each(|(_, value)| value.visit_refs(f))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A parsed PDF dictionary, in the order its entries appeared in the file.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Dict(Vec<(Vec<u8>, Object)>);
+
+impl Dict {
+    fn get(&self, key: &[u8]) -> Option<&Object> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    fn is_type(&self, name: &[u8]) -> bool {
+        self.get(b"Type").and_then(Object::as_name) == Some(name)
+    }
+}
+
+/// The cross-reference information of a file.
+struct Xref {
+    /// Where to find the objects.
+    entries: HashMap<u32, Entry>,
+    /// The trailer dictionary of the latest revision.
+    trailer: Dict,
+}
+
+/// Where to find an object.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Entry {
+    /// At a byte offset in the file.
+    Offset(usize),
+    /// At an index in an object stream.
+    Compressed(u32, usize),
+}
+
+/// Load all objects and the page tree of a file.
+fn load(data: Bytes, xref: Xref) -> StrResult<Repr> {
+    if xref.trailer.get(b"Encrypt").is_some() {
+        bail!("encrypted PDF files are not supported");
+    }
+
+    let mut objects = HashMap::new();
+    for (&id, &entry) in &xref.entries {
+        let Entry::Offset(offset) = entry else { continue };
+        let length = |id| direct_length(&data, &xref, id);
+        if let Some((found, object)) = parse_indirect(&data, offset, &length) {
+            if found == id {
+                objects.insert(id, object);
+            }
+        }
+    }
+
+    // Unpack object streams. Their objects are used if the cross-reference
+    // information points to them or doesn't know about them at all.
+    let mut unpacked = vec![];
+    for (&stream, object) in &objects {
+        for (id, object) in parse_object_stream(&data, object)? {
+            match xref.entries.get(&id) {
+                Some(&Entry::Compressed(s, _)) if s == stream => {}
+                None => {}
+                _ => continue,
+            }
+            unpacked.push((id, object));
+        }
+    }
+    objects.extend(unpacked);
+
+    let root = match xref.trailer.get(b"Root").and_then(Object::as_ref) {
+        Some(id) => id,
+        None => match objects
+            .iter()
+            .filter(|(_, object)| {
+                object.as_dict().is_some_and(|dict| dict.is_type(b"Catalog"))
+            })
+            .map(|(&id, _)| id)
+            .min()
+        {
+            Some(id) => id,
+            None => bail!("PDF file has no document catalog"),
+        },
+    };
+
+    let Some(pages_id) = objects
+        .get(&root)
+        .and_then(Object::as_dict)
+        .and_then(|catalog| catalog.get(b"Pages"))
+        .and_then(Object::as_ref)
+    else {
+        bail!("PDF file has no page tree");
+    };
+
+    let mut pages = vec![];
+    let mut tree = HashSet::new();
+    collect_pages(&objects, pages_id, &[], &mut pages, &mut tree, 0);
+    if pages.is_empty() {
+        bail!("PDF file contains no pages");
+    }
+
+    Ok(Repr { data, objects, pages, tree })
+}
+
+/// Walk the page tree and collect the pages in order.
+fn collect_pages(
+    objects: &HashMap<u32, Object>,
+    id: u32,
+    inherited: &[(Vec<u8>, Object)],
+    pages: &mut Vec<SourcePage>,
+    tree: &mut HashSet<u32>,
+    depth: usize,
+) {
+    if depth > MAX_DEPTH || !tree.insert(id) {
+        return;
+    }
+
+    let Some(dict) = objects.get(&id).and_then(Object::as_dict) else { return };
+    let kids = dict.get(b"Kids").and_then(Object::as_array);
+
+    if dict.is_type(b"Pages") || (kids.is_some() && !dict.is_type(b"Page")) {
+        let mut inherited = inherited.to_vec();
+        for key in INHERITABLE {
+            if let Some(value) = dict.get(key) {
+                inherited.retain(|(k, _)| k != key);
+                inherited.push((key.to_vec(), value.clone()));
+            }
+        }
+
+        for kid in kids.unwrap_or_default().iter().filter_map(Object::as_ref) {
+            collect_pages(objects, kid, &inherited, pages, tree, depth + 1);
+        }
+    } else {
+        let inherited = inherited
+            .iter()
+            .filter(|(key, _)| dict.get(key).is_none())
+            .cloned()
+            .collect();
+        pages.push(SourcePage { id, inherited });
+    }
+}
+
+/// Read the cross-reference information starting at the offset given at the
+/// end of the file.
+fn read_xref(data: &[u8]) -> Option<Xref> {
+    let tail = data.len().saturating_sub(1024);
+    let pos = tail + rfind(&data[tail..], b"startxref")?;
+    let start = Parser::new(data, pos + b"startxref".len()).int()?;
+
+    let mut entries = HashMap::new();
+    let mut trailer = None;
+    let mut visited = HashSet::new();
+    let mut queue = vec![usize::try_from(start).ok()?];
+
+    while let Some(offset) = queue.pop() {
+        if !visited.insert(offset) {
+            continue;
+        }
+
+        let dict = read_xref_table(data, offset, &mut entries)
+            .or_else(|| read_xref_stream(data, offset, &mut entries))?;
+
+        // Entries of earlier revisions are only used if later ones don't
+        // define the object. The stream of a hybrid file belongs to the same
+        // revision as the table, so it comes first.
+        queue.extend(dict.get(b"Prev").and_then(Object::as_usize));
+        queue.extend(dict.get(b"XRefStm").and_then(Object::as_usize));
+        trailer.get_or_insert(dict);
+    }
+
+    Some(Xref { entries, trailer: trailer? })
+}
+
+/// Read a classic cross-reference table and return its trailer.
+fn read_xref_table(
+    data: &[u8],
+    offset: usize,
+    entries: &mut HashMap<u32, Entry>,
+) -> Option<Dict> {
+    let mut p = Parser::new(data, offset);
+    if !p.eat_keyword(b"xref") {
+        return None;
+    }
+
+    loop {
+        if p.eat_keyword(b"trailer") {
+            return match p.object()? {
+                Object::Dict(dict) => Some(dict),
+                _ => None,
+            };
+        }
+
+        let start = u32::try_from(p.int()?).ok()?;
+        let count = u32::try_from(p.int()?).ok()?;
+        for i in 0..count {
+            let offset = usize::try_from(p.int()?).ok()?;
+            p.int()?;
+            p.skip_ws();
+            let kind = p.next()?;
+            if kind == b'n' && offset > 0 {
+                entries.entry(start + i).or_insert(Entry::Offset(offset));
+            }
+        }
+    }
+}
+
+/// Read a cross-reference stream and return its dictionary.
+fn read_xref_stream(
+    data: &[u8],
+    offset: usize,
+    entries: &mut HashMap<u32, Entry>,
+) -> Option<Dict> {
+    let (_, Object::Stream(dict, range)) = parse_indirect(data, offset, &|_| None)?
+    else {
+        return None;
+    };
+
+    if !dict.is_type(b"XRef") {
+        return None;
+    }
+
+    let decoded = decode(&dict, &data[range]).ok()?;
+    let widths = dict
+        .get(b"W")?
+        .as_array()?
+        .iter()
+        .map(Object::as_usize)
+        .collect::<Option<Vec<_>>>()?;
+    let [w0, w1, w2] = widths[..] else { return None };
+
+    let index = match dict.get(b"Index").and_then(Object::as_array) {
+        Some(index) => index.iter().map(Object::as_usize).collect::<Option<Vec<_>>>()?,
+        None => vec![0, dict.get(b"Size")?.as_usize()?],
+    };
+
+    let width = w0 + w1 + w2;
+    if width == 0 {
+        return None;
+    }
+
+    let mut rows = decoded.chunks_exact(width);
+    for pair in index.chunks_exact(2) {
+        for id in pair[0]..pair[0].saturating_add(pair[1]) {
+            let row = rows.next()?;
+            let field = |range: Range<usize>| {
+                row[range].iter().fold(0usize, |acc, &b| (acc << 8) | b as usize)
+            };
+
+            let kind = if w0 == 0 { 1 } else { field(0..w0) };
+            let a = field(w0..w0 + w1);
+            let b = field(w0 + w1..width);
+            let entry = match kind {
+                1 if a > 0 => Entry::Offset(a),
+                2 => Entry::Compressed(u32::try_from(a).ok()?, b),
+                _ => continue,
+            };
+
+            entries.entry(u32::try_from(id).ok()?).or_insert(entry);
+        }
+    }
+
+    Some(dict)
+}
+
+/// Find objects by scanning the whole file. Used if the cross-reference
+/// information is missing or damaged.
+fn scan_xref(data: &[u8]) -> Xref {
+    let mut entries = HashMap::new();
+    let mut pos = 0;
+    while let Some(i) = find(&data[pos..], b"obj") {
+        let at = pos + i;
+        pos = at + 3;
+        if data.get(pos).is_some_and(|&c| is_regular(c)) {
+            continue;
+        }
+
+        // Later definitions replace earlier ones, as in incremental updates.
+        if let Some(start) = object_start(data, at) {
+            if let Some(id) = Parser::new(data, start).int() {
+                if let Ok(id) = u32::try_from(id) {
+                    entries.insert(id, Entry::Offset(start));
+                }
+            }
+        }
+    }
+
+    let trailer = rfind(data, b"trailer")
+        .and_then(|i| Parser::new(data, i + b"trailer".len()).object())
+        .and_then(|object| match object {
+            Object::Dict(dict) => Some(dict),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    Xref { entries, trailer }
+}
+
+/// Find the start of an `<id> <gen> obj` header, given the position of `obj`.
+fn object_start(data: &[u8], at: usize) -> Option<usize> {
+    let mut i = at;
+    for _ in 0..2 {
+        let end = i;
+        while i > 0 && is_whitespace(data[i - 1]) {
+            i -= 1;
+        }
+        if i == end {
+            return None;
+        }
+
+        let end = i;
+        while i > 0 && data[i - 1].is_ascii_digit() {
+            i -= 1;
+        }
+        if i == end {
+            return None;
+        }
+    }
+
+    (i == 0 || !is_regular(data[i - 1])).then_some(i)
+}
+
+/// Determine a stream length given as an indirect reference.
+fn direct_length(data: &[u8], xref: &Xref, id: u32) -> Option<usize> {
+    let &Entry::Offset(offset) = xref.entries.get(&id)? else { return None };
+    let (found, object) = parse_indirect(data, offset, &|_| None)?;
+    (found == id).then_some(())?;
+    object.as_usize()
+}
+
+/// Parse an indirect object at an offset.
+///
+/// The `length` function resolves stream lengths given as references. If the
+/// length is unknown or wrong, the stream extends to the `endstream` keyword.
+fn parse_indirect(
+    data: &[u8],
+    offset: usize,
+    length: &dyn Fn(u32) -> Option<usize>,
+) -> Option<(u32, Object)> {
+    let mut p = Parser::new(data, offset);
+    let id = u32::try_from(p.int()?).ok()?;
+    p.int()?;
+    if !p.eat_keyword(b"obj") {
+        return None;
+    }
+
+    let object = p.object()?;
+    let Object::Dict(dict) = object else { return Some((id, object)) };
+    if !p.eat_keyword(b"stream") {
+        return Some((id, Object::Dict(dict)));
+    }
+
+    if !p.eat(b"\r\n") && !p.eat(b"\n") {
+        p.eat(b"\r");
+    }
+
+    let start = p.pos;
+    let declared = match dict.get(b"Length") {
+        Some(&Object::Ref(id)) => length(id),
+        Some(object) => object.as_usize(),
+        None => None,
+    };
+
+    let end = declared
+        .and_then(|len| start.checked_add(len))
+        .filter(|&end| end <= data.len() && ends_stream(data, end))
+        .or_else(|| {
+            let mut end = start + find(data.get(start..)?, b"endstream")?;
+            if data[..end].ends_with(b"\r\n") {
+                end -= 2;
+            } else if data[..end].ends_with(b"\n") || data[..end].ends_with(b"\r") {
+                end -= 1;
+            }
+            Some(end.max(start))
+        })?;
+
+    Some((id, Object::Stream(dict, start..end)))
+}
+
+/// Whether the `endstream` keyword follows at the given position.
+fn ends_stream(data: &[u8], end: usize) -> bool {
+    let mut p = Parser::new(data, end);
+    p.eat_keyword(b"endstream")
+}
+
+/// Parse the objects of an object stream.
+fn parse_object_stream(data: &[u8], object: &Object) -> StrResult<Vec<(u32, Object)>> {
+    let Object::Stream(dict, range) = object else { return Ok(vec![]) };
+    if !dict.is_type(b"ObjStm") {
+        return Ok(vec![]);
+    }
+
+    let decoded = decode(dict, &data[range.clone()])?;
+    Ok(parse_object_stream_data(dict, &decoded).unwrap_or_default())
+}
+
+/// Parse the objects in the decoded data of an object stream.
+fn parse_object_stream_data(dict: &Dict, decoded: &[u8]) -> Option<Vec<(u32, Object)>> {
+    let n = dict.get(b"N")?.as_usize()?;
+    let first = dict.get(b"First")?.as_usize()?;
+
+    let mut p = Parser::new(decoded, 0);
+    let mut headers = vec![];
+    for _ in 0..n {
+        let id = u32::try_from(p.int()?).ok()?;
+        let offset = p.int().and_then(|v| usize::try_from(v).ok())?;
+        headers.push((id, offset));
+    }
+
+    Some(
+        headers
+            .into_iter()
+            .filter_map(|(id, offset)| {
+                Some((id, Parser::new(decoded, first.checked_add(offset)?).object()?))
+            })
+            .collect(),
+    )
+}
+
+/// Decode stream data. Only supports what's needed for cross-reference and
+/// object streams.
+fn decode(dict: &Dict, raw: &[u8]) -> StrResult<Vec<u8>> {
+    let filters: Option<Vec<&[u8]>> = match dict.get(b"Filter") {
+        None => Some(vec![]),
+        Some(Object::Name(name)) => Some(vec![name]),
+        Some(Object::Array(items)) => items.iter().map(Object::as_name).collect(),
+        Some(_) => None,
+    };
+
+    let decoded = match filters.as_deref() {
+        Some([]) => return Ok(raw.to_vec()),
+        Some([b"FlateDecode"]) => decompress_to_vec_zlib(raw)
+            .map_err(|_| "PDF file contains a corrupted stream")?,
+        _ => bail!("PDF file uses an unsupported stream filter"),
+    };
+
+    let params = match dict.get(b"DecodeParms") {
+        Some(Object::Array(items)) => items.first().and_then(Object::as_dict),
+        Some(object) => object.as_dict(),
+        None => None,
+    };
+
+    let param = |key: &[u8], default| {
+        params
+            .and_then(|p| p.get(key))
+            .and_then(Object::as_usize)
+            .unwrap_or(default)
+    };
+
+    match param(b"Predictor", 1) {
+        1 => Ok(decoded),
+        10.. => {
+            // The parameters are untrusted, so they are checked against the
+            // ranges allowed by the specification and a row may not be
+            // larger than the data.
+            let colors = param(b"Colors", 1);
+            let depth = param(b"BitsPerComponent", 8);
+            if !matches!(colors, 1..=32) || !matches!(depth, 1 | 2 | 4 | 8 | 16) {
+                bail!("PDF file contains a stream with invalid predictor parameters");
+            }
+
+            let bits = colors * depth;
+            let Some(row) = param(b"Columns", 1)
+                .checked_mul(bits)
+                .map(|bits| bits.div_ceil(8))
+                .filter(|row| (1..decoded.len()).contains(row))
+            else {
+                bail!("PDF file contains a stream with invalid predictor parameters");
+            };
+
+            unpredict_png(&decoded, row, bits.div_ceil(8))
+                .ok_or_else(|| "PDF file contains a corrupted stream".into())
+        }
+        _ => bail!("PDF file uses an unsupported stream predictor"),
+    }
+}
+
+/// Undo PNG prediction with row-wise filter types.
+fn unpredict_png(data: &[u8], row: usize, bpp: usize) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut prev = vec![0u8; row];
+    for chunk in data.chunks_exact(row + 1) {
+        let mut cur = chunk[1..].to_vec();
+        for i in 0..row {
+            let a = if i >= bpp { cur[i - bpp] } else { 0 };
+            let b = prev[i];
+            let c = if i >= bpp { prev[i - bpp] } else { 0 };
+            let predicted = match chunk[0] {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => return None,
+            };
+            cur[i] = cur[i].wrapping_add(predicted);
+        }
+        out.extend_from_slice(&cur);
+        prev = cur;
+    }
+
+    Some(out)
+}
+
+/// The Paeth predictor.
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let pa = (p - a as i16).abs();
+    let pb = (p - b as i16).abs();
+    let pc = (p - c as i16).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// Parses PDF objects.
+struct Parser<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(data: &'a [u8], pos: usize) -> Self {
+        Self { data, pos }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        self.data.get(self.pos..).unwrap_or_default()
+    }
+
+    fn eat(&mut self, s: &[u8]) -> bool {
+        let matches = self.rest().starts_with(s);
+        if matches {
+            self.pos += s.len();
+        }
+        matches
+    }
+
+    /// Skip whitespace and comments.
+    fn skip_ws(&mut self) {
+        while let Some(c) = self.peek() {
+            if is_whitespace(c) {
+                self.pos += 1;
+            } else if c == b'%' {
+                while self.peek().is_some_and(|c| c != b'\r' && c != b'\n') {
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Eat a keyword that isn't directly followed by other regular characters.
+    fn eat_keyword(&mut self, keyword: &[u8]) -> bool {
+        self.skip_ws();
+        let rest = self.rest();
+        let matches = rest.starts_with(keyword)
+            && rest.get(keyword.len()).map_or(true, |&c| !is_regular(c));
+        if matches {
+            self.pos += keyword.len();
+        }
+        matches
+    }
+
+    /// Parse an integer.
+    fn int(&mut self) -> Option<i64> {
+        self.skip_ws();
+        match self.number()? {
+            Object::Int(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Parse a direct object.
+    fn object(&mut self) -> Option<Object> {
+        self.object_at(0)
+    }
+
+    fn object_at(&mut self, depth: usize) -> Option<Object> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        self.skip_ws();
+        match self.peek()? {
+            b'/' => {
+                self.pos += 1;
+                Some(Object::Name(self.name()))
+            }
+            b'(' => {
+                self.pos += 1;
+                self.literal_string().map(Object::Str)
+            }
+            b'<' if self.rest().starts_with(b"<<") => {
+                self.pos += 2;
+                self.dict(depth).map(Object::Dict)
+            }
+            b'<' => {
+                self.pos += 1;
+                self.hex_string().map(Object::Str)
+            }
+            b'[' => {
+                self.pos += 1;
+                let mut items = vec![];
+                loop {
+                    self.skip_ws();
+                    if self.eat(b"]") {
+                        return Some(Object::Array(items));
+                    }
+                    items.push(self.object_at(depth + 1)?);
+                }
+            }
+            b'0'..=b'9' | b'+' | b'-' | b'.' => {
+                let number = self.number()?;
+                if let Object::Int(id) = number {
+                    if let Some(id) = self.reference(id) {
+                        return Some(Object::Ref(id));
+                    }
+                }
+                Some(number)
+            }
+            _ if self.eat_keyword(b"true") => Some(Object::Bool(true)),
+            _ if self.eat_keyword(b"false") => Some(Object::Bool(false)),
+            _ if self.eat_keyword(b"null") => Some(Object::Null),
+            _ => None,
+        }
+    }
+
+    /// Try to complete a reference whose ID was already parsed.
+    fn reference(&mut self, id: i64) -> Option<u32> {
+        let id = u32::try_from(id).ok()?;
+        let start = self.pos;
+        self.skip_ws();
+        if self.peek().is_some_and(|c| c.is_ascii_digit())
+            && self.int().is_some()
+            && self.eat_keyword(b"R")
+        {
+            return Some(id);
+        }
+        self.pos = start;
+        None
+    }
+
+    fn number(&mut self) -> Option<Object> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, b'+' | b'-' | b'.'))
+        {
+            self.pos += 1;
+        }
+
+        let text = std::str::from_utf8(&self.data[start..self.pos]).ok()?;
+        if text.contains('.') {
+            text.parse().ok().map(Object::Real)
+        } else {
+            text.parse().ok().map(Object::Int)
+        }
+    }
+
+    fn name(&mut self) -> Vec<u8> {
+        let mut name = vec![];
+        while let Some(c) = self.peek().filter(|&c| is_regular(c)) {
+            self.pos += 1;
+            let escaped = (c == b'#')
+                .then(|| self.rest().get(..2))
+                .flatten()
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            match escaped {
+                Some(byte) => {
+                    name.push(byte);
+                    self.pos += 2;
+                }
+                None => name.push(c),
+            }
+        }
+        name
+    }
+
+    fn literal_string(&mut self) -> Option<Vec<u8>> {
+        let mut string = vec![];
+        let mut depth = 0;
+        loop {
+            match self.next()? {
+                b'(' => {
+                    depth += 1;
+                    string.push(b'(');
+                }
+                b')' if depth == 0 => return Some(string),
+                b')' => {
+                    depth -= 1;
+                    string.push(b')');
+                }
+                b'\\' => match self.next()? {
+                    b'n' => string.push(b'\n'),
+                    b'r' => string.push(b'\r'),
+                    b't' => string.push(b'\t'),
+                    b'b' => string.push(0x08),
+                    b'f' => string.push(0x0C),
+                    b'\r' => {
+                        self.eat(b"\n");
+                    }
+                    b'\n' => {}
+                    c @ b'0'..=b'7' => {
+                        let mut value = (c - b'0') as u32;
+                        for _ in 0..2 {
+                            match self.peek() {
+                                Some(d @ b'0'..=b'7') => {
+                                    value = value * 8 + (d - b'0') as u32;
+                                    self.pos += 1;
+                                }
+                                _ => break,
+                            }
+                        }
+                        string.push(value as u8);
+                    }
+                    c => string.push(c),
+                },
+                b'\r' => {
+                    self.eat(b"\n");
+                    string.push(b'\n');
+                }
+                c => string.push(c),
+            }
+        }
+    }
+
+    fn hex_string(&mut self) -> Option<Vec<u8>> {
+        let mut digits = vec![];
+        loop {
+            match self.next()? {
+                b'>' => break,
+                c if c.is_ascii_hexdigit() => digits.push(c),
+                c if is_whitespace(c) => {}
+                _ => return None,
+            }
+        }
+
+        if digits.len() % 2 == 1 {
+            digits.push(b'0');
+        }
+
+        digits
+            .chunks_exact(2)
+            .map(|pair| {
+                let hex = std::str::from_utf8(pair).ok()?;
+                u8::from_str_radix(hex, 16).ok()
+            })
+            .collect()
+    }
+
+    fn dict(&mut self, depth: usize) -> Option<Dict> {
+        let mut entries = vec![];
+        loop {
+            self.skip_ws();
+            if self.eat(b">>") {
+                return Some(Dict(entries));
+            }
+            if !self.eat(b"/") {
+                return None;
+            }
+            let key = self.name();
+            let value = self.object_at(depth + 1)?;
+            entries.retain(|(k, _): &(Vec<u8>, Object)| *k != key);
+            entries.push((key, value));
+        }
+    }
+}
+
+/// Whether a byte is PDF whitespace.
+fn is_whitespace(c: u8) -> bool {
+    matches!(c, b'\0' | b'\t' | b'\n' | b'\x0C' | b'\r' | b' ')
+}
+
+/// Whether a byte is neither whitespace nor a delimiter.
+fn is_regular(c: u8) -> bool {
+    !is_whitespace(c)
+        && !matches!(
+            c,
+            b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+        )
+}
+
+/// Find the first occurrence of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Find the last occurrence of `needle` in `haystack`.
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pdf_writer::{Pdf, Rect};
+    use typst::foundations::Smart;
+    use typst::model::Document;
+
+    /// Create a file whose pages inherit their media box from the page tree.
+    fn sample(count: i32) -> Vec<u8> {
+        let mut pdf = Pdf::new();
+        let catalog_ref = Ref::new(1);
+        let tree_ref = Ref::new(2);
+        pdf.catalog(catalog_ref).pages(tree_ref);
+
+        let mut kids = vec![];
+        for i in 0..count {
+            let page_ref = Ref::new(3 + 2 * i);
+            let content_ref = Ref::new(4 + 2 * i);
+            pdf.page(page_ref).parent(tree_ref).contents(content_ref);
+            pdf.stream(content_ref, b"0 0 m 10 10 l S");
+            kids.push(page_ref);
+        }
+
+        pdf.pages(tree_ref)
+            .kids(kids)
+            .count(count)
+            .media_box(Rect::new(0.0, 0.0, 100.0, 200.0));
+        pdf.finish()
+    }
+
+    #[test]
+    fn test_pdf_import_parse() {
+        let source = PdfSource::new(Bytes::from(sample(3))).unwrap();
+        assert_eq!(source.page_count(), 3);
+
+        let page = &source.0.pages[1];
+        let content = source.0.objects[&page.id]
+            .as_dict()
+            .and_then(|dict| dict.get(b"Contents"))
+            .and_then(Object::as_ref)
+            .unwrap();
+        let Object::Stream(_, range) = &source.0.objects[&content] else { panic!() };
+        assert_eq!(&source.0.data[range.clone()], b"0 0 m 10 10 l S");
+        assert_eq!(page.inherited[0].0, b"MediaBox");
+    }
+
+    #[test]
+    fn test_pdf_import_damaged_xref() {
+        let mut data = sample(2);
+        let pos = rfind(&data, b"startxref").unwrap();
+        data.truncate(pos);
+        assert_eq!(PdfSource::new(Bytes::from(data)).unwrap().page_count(), 2);
+        assert!(PdfSource::new(Bytes::from(b"%PDF-1.7\n".as_slice())).is_err());
+    }
+
+    #[test]
+    fn test_pdf_import_export() {
+        let source = PdfSource::new(Bytes::from(sample(3))).unwrap();
+        let import = PdfImport {
+            source,
+            pages: Some("2-".parse().unwrap()),
+            position: ImportPosition::End,
+        };
+
+        let document = Document::default();
+        let output =
+            crate::pdf_with_imports(&document, Smart::Auto, None, None, &[import]);
+        let exported = PdfSource::new(Bytes::from(output)).unwrap();
+        assert_eq!(exported.page_count(), 2);
+        assert!(exported.0.pages.iter().all(|page| {
+            let dict = exported.0.objects[&page.id].as_dict().unwrap();
+            dict.get(b"MediaBox").is_some() && dict.get(b"Contents").is_some()
+        }));
+    }
+}
@@ -4,25 +4,30 @@ mod color;
 mod extg;
 mod font;
 mod gradient;
+mod group;
 mod image;
+mod import;
+mod mask;
 mod outline;
 mod page;
 mod pattern;
 
 use std::cmp::Eq;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap};
 use std::hash::Hash;
 use std::sync::Arc;
 
 use base64::Engine;
-use ecow::{eco_format, EcoString};
-use pdf_writer::types::Direction;
-use pdf_writer::writers::Destination;
-use pdf_writer::{Finish, Name, Pdf, Ref, Str, TextStr};
-use typst::foundations::{Datetime, Label, NativeElement, Smart};
+use ecow::{eco_format, EcoString, EcoVec};
+use pdf_writer::types::{Direction, OutputIntentSubtype};
+use pdf_writer::writers::{Destination, OutputIntent};
+use pdf_writer::{Filter, Finish, Name, Pdf, Ref, Str, TextStr};
+use typst::diag::{warning, SourceDiagnostic};
+use typst::foundations::{Datetime, Smart};
 use typst::introspection::Location;
-use typst::layout::{Abs, Dir, Em, Transform};
-use typst::model::{Document, HeadingElem};
+use typst::layout::{Abs, Dir, Em, Frame, FrameItem, PageRanges, Transform};
+use typst::model::Document;
+use typst::syntax::Span;
 use typst::text::{Font, Lang};
 use typst::util::Deferred;
 use typst::visualize::Image;
@@ -31,10 +36,14 @@ use xmp_writer::{DateTime, LangId, RenditionClass, Timezone, XmpWriter};
 use crate::color::ColorSpaces;
 use crate::extg::ExtGState;
 use crate::gradient::PdfGradient;
+use crate::group::PdfGroup;
 use crate::image::EncodedImage;
+use crate::mask::PdfSoftMask;
 use crate::page::EncodedPage;
 use crate::pattern::PdfPattern;
 
+pub use crate::import::{ImportPosition, PdfImport, PdfSource};
+
 /// Export a document into a PDF file.
 ///
 /// Returns the raw bytes making up the PDF file.
@@ -54,18 +63,53 @@ use crate::pattern::PdfPattern;
 /// The `timestamp`, if given, is expected to be the creation date of the
 /// document as a UTC datetime. It will only be used if `set document(date: ..)`
 /// is `auto`.
-#[typst_macros::time(name = "pdf")]
 pub fn pdf(
     document: &Document,
     ident: Smart<&str>,
     timestamp: Option<Datetime>,
+) -> Vec<u8> {
+    pdf_with_imports(document, ident, timestamp, None, &[])
+}
+
+/// Export a selection of a document's pages into a PDF file.
+///
+/// This works like [`pdf`], but only exports the pages selected by the
+/// `page_ranges`. Other pages are not encoded at all, and links and outline
+/// entries pointing to them are dropped.
+pub fn pdf_pages(
+    document: &Document,
+    ident: Smart<&str>,
+    timestamp: Option<Datetime>,
+    page_ranges: &PageRanges,
+) -> Vec<u8> {
+    pdf_with_imports(document, ident, timestamp, Some(page_ranges), &[])
+}
+
+/// Export a document into a PDF file, together with pages of existing PDF
+/// files.
+///
+/// This works like [`pdf`], but additionally places the given `imports`
+/// before, after, or in between the document's pages. The imported pages are
+/// copied with all their resources, so the result is a single coherent file
+/// with the document's metadata. If `page_ranges` are given, only the selected
+/// pages of the document are exported, as with [`pdf_pages`].
+#[typst_macros::time(name = "pdf")]
+pub fn pdf_with_imports(
+    document: &Document,
+    ident: Smart<&str>,
+    timestamp: Option<Datetime>,
+    page_ranges: Option<&PageRanges>,
+    imports: &[PdfImport],
 ) -> Vec<u8> {
     let mut ctx = PdfContext::new(document);
-    page::construct_pages(&mut ctx, &document.pages);
+    page::construct_pages(&mut ctx, &document.pages, page_ranges);
+    import::write_imports(&mut ctx, imports);
     font::write_fonts(&mut ctx);
     image::write_images(&mut ctx);
     gradient::write_gradients(&mut ctx);
     extg::write_external_graphics_states(&mut ctx);
+    group::write_groups(&mut ctx);
+    mask::write_soft_masks(&mut ctx);
     pattern::write_patterns(&mut ctx);
     write_named_destinations(&mut ctx);
     page::write_page_tree(&mut ctx);
@@ -73,14 +117,42 @@ pub fn pdf(
     ctx.pdf.finish()
 }
 
+/// Report which content of a document the PDF export cannot represent as
+/// intended.
+///
+/// Such content is still exported, but differs from other export formats.
+/// Currently, this is only the case for blurred content when the crate is
+/// built without the `raster` feature: It is then exported without the blur.
+pub fn warnings(document: &Document) -> EcoVec<SourceDiagnostic> {
+    let mut warnings = EcoVec::new();
+    if !cfg!(feature = "raster")
+        && document.pages.iter().any(|page| is_blurred(&page.frame))
+    {
+        warnings.push(warning!(
+            Span::detached(), "blurred content is exported to PDF without the blur";
+            hint: "enable the `raster` feature of `typst-pdf` to blur it"
+        ));
+    }
+    warnings
+}
+
+/// Whether a frame contains blurred content.
+fn is_blurred(frame: &Frame) -> bool {
+    frame.items().any(|(_, item)| match item {
+        FrameItem::Group(group) => group.blur > Abs::zero() || is_blurred(&group.frame),
+        _ => false,
+    })
+}
+
 /// Context for exporting a whole PDF document.
 struct PdfContext<'a> {
     /// The document that we're currently exporting.
     document: &'a Document,
     /// The writer we are writing the PDF into.
     pdf: Pdf,
-    /// Content of exported pages.
-    pages: Vec<EncodedPage>,
+    /// Content of exported pages, indexed by page number. Pages that are not
+    /// exported are `None`.
+    pages: Vec<Option<EncodedPage>>,
     /// For each font a mapping from used glyphs to their text representation.
     /// May contain multiple chars in case of ligatures or similar things. The
     /// same glyph can have a different text representation within one document,
@@ -97,7 +169,10 @@ struct PdfContext<'a> {
     alloc: Ref,
     /// The ID of the page tree.
     page_tree_ref: Ref,
-    /// The IDs of written pages.
+    /// The ID of the resource dictionary shared by all pages.
+    global_resources_ref: Ref,
+    /// The IDs of written pages, in their final order. This includes pages
+    /// imported from other PDF files.
     page_refs: Vec<Ref>,
     /// The IDs of written fonts.
     font_refs: Vec<Ref>,
@@ -109,6 +184,10 @@ struct PdfContext<'a> {
     pattern_refs: Vec<Ref>,
     /// The IDs of written external graphics states.
     ext_gs_refs: Vec<Ref>,
+    /// The IDs of written isolated transparency groups.
+    group_refs: Vec<Ref>,
+    /// The IDs of written graphics states with soft masks.
+    soft_mask_refs: Vec<Ref>,
     /// Handles color space writing.
     colors: ColorSpaces,
 
@@ -124,17 +203,22 @@ struct PdfContext<'a> {
     pattern_map: Remapper<PdfPattern>,
     /// Deduplicates external graphics states used across the document.
     extg_map: Remapper<ExtGState>,
+    /// Deduplicates isolated transparency groups used across the document.
+    group_map: Remapper<PdfGroup>,
+    /// Deduplicates soft masks used across the document.
+    soft_mask_map: Remapper<PdfSoftMask>,
 
     /// A sorted list of all named destinations.
-    dests: Vec<(Label, Ref)>,
+    dests: Vec<(EcoString, Ref)>,
     /// Maps from locations to named destinations that point to them.
-    loc_to_dest: HashMap<Location, Label>,
+    loc_to_dest: HashMap<Location, EcoString>,
 }
 
 impl<'a> PdfContext<'a> {
     fn new(document: &'a Document) -> Self {
         let mut alloc = Ref::new(1);
         let page_tree_ref = alloc.bump();
+        let global_resources_ref = alloc.bump();
         Self {
             document,
             pdf: Pdf::new(),
@@ -143,12 +227,15 @@ impl<'a> PdfContext<'a> {
             languages: BTreeMap::new(),
             alloc,
             page_tree_ref,
+            global_resources_ref,
             page_refs: vec![],
             font_refs: vec![],
             image_refs: vec![],
             gradient_refs: vec![],
             pattern_refs: vec![],
             ext_gs_refs: vec![],
+            group_refs: vec![],
+            soft_mask_refs: vec![],
             colors: ColorSpaces::default(),
             font_map: Remapper::new(),
             image_map: Remapper::new(),
@@ -156,6 +243,8 @@ impl<'a> PdfContext<'a> {
             gradient_map: Remapper::new(),
             pattern_map: Remapper::new(),
             extg_map: Remapper::new(),
+            group_map: Remapper::new(),
+            soft_mask_map: Remapper::new(),
             dests: vec![],
             loc_to_dest: HashMap::new(),
         }
@@ -233,7 +322,7 @@ fn write_catalog(ctx: &mut PdfContext, ident: Smart<&str>, timestamp: Option<Dat
     }
 
     info.finish();
-    xmp.num_pages(ctx.document.pages.len() as u32);
+    xmp.num_pages(ctx.page_refs.len() as u32);
     xmp.format("application/pdf");
     xmp.language(ctx.languages.keys().map(|lang| LangId(lang.as_str())));
 
@@ -271,18 +360,42 @@ fn write_catalog(ctx: &mut PdfContext, ident: Smart<&str>, timestamp: Option<Dat
         .pair(Name(b"Type"), Name(b"Metadata"))
         .pair(Name(b"Subtype"), Name(b"XML"));
 
+    // Write the profile of the output intent.
+    let output_intent = ctx.document.output_intent.map(|profile| {
+        let id = ctx.alloc.bump();
+        let channels = profile.space().channels();
+        ctx.pdf
+            .icc_profile(id, &deflate(profile.data()))
+            .n(channels as i32)
+            .range([0.0, 1.0].repeat(channels))
+            .filter(Filter::FlateDecode);
+        id
+    });
+
     // Write the document catalog.
     let mut catalog = ctx.pdf.catalog(ctx.alloc.bump());
     catalog.pages(ctx.page_tree_ref);
     catalog.viewer_preferences().direction(dir);
     catalog.metadata(meta_ref);
 
+    // Write the output intent, which viewers use to preview device colors.
+    if let Some(profile) = output_intent {
+        catalog
+            .insert(Name(b"OutputIntents"))
+            .array()
+            .push()
+            .start::<OutputIntent>()
+            .subtype(OutputIntentSubtype::PDFX)
+            .output_condition_identifier(TextStr("Custom"))
+            .dest_output_profile(profile);
+    }
+
     // Write the named destination tree.
     let mut name_dict = catalog.names();
     let mut dests_name_tree = name_dict.destinations();
     let mut names = dests_name_tree.names();
-    for &(name, dest_ref, ..) in &ctx.dests {
-        names.insert(Str(name.as_str().as_bytes()), dest_ref);
+    for (name, dest_ref) in &ctx.dests {
+        names.insert(Str(name.as_bytes()), *dest_ref);
     }
     names.finish();
     dests_name_tree.finish();
@@ -311,33 +424,29 @@ fn write_catalog(ctx: &mut PdfContext, ident: Smart<&str>, timestamp: Option<Dat
 /// Fills in the map and vector for named destinations and writes the indirect
 /// destination objects.
 fn write_named_destinations(ctx: &mut PdfContext) {
-    let mut seen = HashSet::new();
-
-    // Find all headings that have a label and are the first among other
-    // headings with the same label.
+    // Find all labelled elements that are the first among other elements with
+    // the same label and name them by their anchors.
     let mut matches: Vec<_> = ctx
         .document
-        .introspector
-        .query(&HeadingElem::elem().select())
-        .iter()
-        .filter_map(|elem| elem.location().zip(elem.label()))
-        .filter(|&(_, label)| seen.insert(label))
+        .anchored()
+        .into_iter()
+        .map(|(loc, label)| (loc, ctx.document.anchor(label)))
         .collect();
 
     // Named destinations must be sorted by key.
-    matches.sort_by_key(|&(_, label)| label);
+    matches.sort_by(|(_, a), (_, b)| a.as_bytes().cmp(b.as_bytes()));
 
-    for (loc, label) in matches {
+    for (loc, name) in matches {
         let pos = ctx.document.introspector.position(loc);
         let index = pos.page.get() - 1;
         let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
 
-        if let Some(page) = ctx.pages.get(index) {
+        if let Some(Some(page)) = ctx.pages.get(index) {
             let dest_ref = ctx.alloc.bump();
             let x = pos.point.x.to_f32();
             let y = (page.size.y - y).to_f32();
-            ctx.dests.push((label, dest_ref));
-            ctx.loc_to_dest.insert(loc, label);
+            ctx.dests.push((name.clone(), dest_ref));
+            ctx.loc_to_dest.insert(loc, name);
             ctx.pdf
                 .indirect(dest_ref)
                 .start::<Destination>()
@@ -491,3 +600,74 @@ fn transform_to_array(ts: Transform) -> [f32; 6] {
         ts.ty.to_f32(),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use typst::layout::{Page, Point, Size};
+    use typst::visualize::{Color, Geometry};
+
+    use super::*;
+
+    /// A document with three empty pages of 10pt, 20pt, and 30pt squares.
+    fn document() -> Document {
+        let pages = [10.0, 20.0, 30.0]
+            .into_iter()
+            .enumerate()
+            .map(|(i, size)| Page {
+                frame: Frame::hard(Size::splat(Abs::pt(size))),
+                numbering: None,
+                number: i + 1,
+                repeated: false,
+            })
+            .collect();
+        Document { pages, ..Default::default() }
+    }
+
+    #[test]
+    fn test_pdf_page_ranges() {
+        #[track_caller]
+        fn test(ranges: Option<&str>, sizes: &[u32]) {
+            let ranges = ranges.map(|ranges| ranges.parse::<PageRanges>().unwrap());
+            let pdf = match ranges {
+                Some(ranges) => pdf_pages(&document(), Smart::Auto, None, &ranges),
+                None => pdf(&document(), Smart::Auto, None),
+            };
+            let pdf = String::from_utf8_lossy(&pdf);
+            assert!(pdf.contains(&format!("/Count {}", sizes.len())));
+            for size in [10, 20, 30] {
+                let media_box = format!("/MediaBox [0 0 {size} {size}]");
+                assert_eq!(pdf.contains(&media_box), sizes.contains(&size));
+            }
+        }
+
+        test(None, &[10, 20, 30]);
+        test(Some("2-3"), &[20, 30]);
+        test(Some("1,3"), &[10, 30]);
+        test(Some("even"), &[20]);
+    }
+
+    #[test]
+    fn test_pdf_blur() {
+        let size = Size::splat(Abs::pt(20.0));
+        let mut frame = Frame::soft(size);
+        let shape = Geometry::Rect(size).filled(Color::BLACK.into());
+        frame.push(Point::zero(), FrameItem::Shape(shape, Span::detached()));
+        frame.blur(Abs::pt(2.0));
+
+        let mut page = Frame::hard(size);
+        page.push_frame(Point::zero(), frame);
+        let page = Page {
+            frame: page,
+            numbering: None,
+            number: 1,
+            repeated: false,
+        };
+        let document = Document { pages: vec![page], ..Default::default() };
+
+        let pdf = pdf(&document, Smart::Auto, None);
+        let pdf = String::from_utf8_lossy(&pdf);
+        let raster = cfg!(feature = "raster");
+        assert_eq!(pdf.contains("/S /Luminosity"), raster);
+        assert_eq!(warnings(&document).is_empty(), raster);
+    }
+}
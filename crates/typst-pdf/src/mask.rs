@@ -0,0 +1,57 @@
+use pdf_writer::types::MaskType;
+use pdf_writer::{Content, Filter, Finish, Name, Rect};
+use typst::layout::Abs;
+
+use crate::{deflate, AbsExt, PdfContext};
+
+/// Writes the soft masks as graphics states whose luminosity masks draw the
+/// masks' images. This is performed once after writing all pages.
+pub(crate) fn write_soft_masks(ctx: &mut PdfContext) {
+    for PdfSoftMask { alpha, width, height, bbox } in ctx.soft_mask_map.items() {
+        let image_ref = ctx.alloc.bump();
+        let mut image = ctx.pdf.image_xobject(image_ref, alpha);
+        image.filter(Filter::FlateDecode);
+        image.width(*width as i32);
+        image.height(*height as i32);
+        image.color_space().device_gray();
+        image.bits_per_component(8);
+        image.finish();
+
+        // The group stretches the image across the bounding box. Outside of
+        // it, the mask is black and thus hides everything.
+        let [x1, y1, x2, y2] = bbox.map(|v| v.to_f32());
+        let mut content = Content::new();
+        content.transform([x2 - x1, 0.0, 0.0, y1 - y2, x1, y2]);
+        content.x_object(Name(b"Im0"));
+        let content = deflate(&content.finish());
+
+        let group_ref = ctx.alloc.bump();
+        let mut form = ctx.pdf.form_xobject(group_ref, &content);
+        form.filter(Filter::FlateDecode);
+        form.bbox(Rect::new(x1, y1, x2, y2));
+        form.resources().x_objects().pair(Name(b"Im0"), image_ref);
+        form.group().transparency().color_space().device_gray();
+        form.finish();
+
+        let id = ctx.alloc.bump();
+        ctx.soft_mask_refs.push(id);
+        ctx.pdf
+            .ext_graphics(id)
+            .soft_mask()
+            .subtype(MaskType::Luminosity)
+            .group(group_ref);
+    }
+}
+
+/// A soft mask given by a grayscale image.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PdfSoftMask {
+    /// The compressed 8-bit gray samples of the image.
+    pub alpha: Vec<u8>,
+    /// The width of the image in pixels.
+    pub width: u32,
+    /// The height of the image in pixels.
+    pub height: u32,
+    /// The area the image covers in the user space in which the mask is set.
+    pub bbox: [Abs; 4],
+}
@@ -10,31 +10,42 @@ use pdf_writer::writers::{PageLabel, Resources};
 use pdf_writer::{Content, Filter, Finish, Name, Rect, Ref, Str, TextStr};
 use typst::introspection::Meta;
 use typst::layout::{
-    Abs, Em, Frame, FrameItem, GroupItem, Page, Point, Ratio, Size, Transform,
+    Abs, Em, Frame, FrameItem, GroupItem, Page, PageRanges, Point, Ratio, Size, Transform,
 };
 use typst::model::{Destination, Numbering};
 use typst::text::{Case, Font, TextItem};
 use typst::util::{Deferred, Numeric};
 use typst::visualize::{
-    FixedStroke, Geometry, Image, LineCap, LineJoin, Paint, Path, PathItem, Shape,
+    BlendMode, Color, FixedStroke, Geometry, Image, LineCap, LineJoin, Paint, Path,
+    PathItem, Shape,
 };
 
 use crate::color::PaintEncode;
 use crate::extg::ExtGState;
+use crate::group::PdfGroup;
 use crate::image::deferred_image;
-use crate::{deflate_deferred, AbsExt, EmExt, PdfContext};
+use crate::{deflate, deflate_deferred, AbsExt, EmExt, PdfContext};
 
 /// Construct page objects.
 #[typst_macros::time(name = "construct pages")]
-pub(crate) fn construct_pages(ctx: &mut PdfContext, pages: &[Page]) {
-    for page in pages {
+pub(crate) fn construct_pages(
+    ctx: &mut PdfContext,
+    pages: &[Page],
+    page_ranges: Option<&PageRanges>,
+) {
+    for (i, page) in pages.iter().enumerate() {
+        if page_ranges.is_some_and(|ranges| !ranges.includes_page_index(i)) {
+            ctx.pages.push(None);
+            continue;
+        }
+
         let (page_ref, mut encoded) = construct_page(ctx, &page.frame);
         encoded.label = page
             .numbering
             .as_ref()
             .and_then(|num| PdfPageLabel::generate(num, page.number));
         ctx.page_refs.push(page_ref);
-        ctx.pages.push(encoded);
+        ctx.pages.push(Some(encoded));
     }
 }
 
@@ -52,6 +63,7 @@ pub(crate) fn construct_page(ctx: &mut PdfContext, frame: &Frame) -> (Ref, Encod
         state: State::new(size),
         saves: vec![],
         bottom: 0.0,
+        page_size: size,
         links: vec![],
         resources: HashMap::default(),
     };
@@ -88,7 +100,9 @@ pub(crate) fn write_page_tree(ctx: &mut PdfContext) {
     let resources_ref = write_global_resources(ctx);
 
     for i in 0..ctx.pages.len() {
-        write_page(ctx, i, resources_ref);
+        if ctx.pages[i].is_some() {
+            write_page(ctx, i, resources_ref);
+        }
     }
 
     ctx.pdf
@@ -103,7 +117,7 @@ pub(crate) fn write_page_tree(ctx: &mut PdfContext) {
 /// to the root node of the page tree because using the resource inheritance
 /// feature breaks PDF merging with Apple Preview.
 fn write_global_resources(ctx: &mut PdfContext) -> Ref {
-    let resource_ref = ctx.alloc.bump();
+    let resource_ref = ctx.global_resources_ref;
 
     let mut resources = ctx.pdf.indirect(resource_ref).start::<Resources>();
     ctx.colors
@@ -123,6 +137,11 @@ fn write_global_resources(ctx: &mut PdfContext) -> Ref {
         images.pair(Name(name.as_bytes()), image_ref);
     }
 
+    for (group_ref, g) in ctx.group_map.pdf_indices(&ctx.group_refs) {
+        let name = eco_format!("Fm{}", g);
+        images.pair(Name(name.as_bytes()), group_ref);
+    }
+
     images.finish();
 
     let mut patterns = resources.patterns();
@@ -143,6 +162,11 @@ fn write_global_resources(ctx: &mut PdfContext) -> Ref {
         let name = eco_format!("Gs{}", gs);
         ext_gs_states.pair(Name(name.as_bytes()), gs_ref);
     }
+
+    for (mask_ref, m) in ctx.soft_mask_map.pdf_indices(&ctx.soft_mask_refs) {
+        let name = eco_format!("Sm{}", m);
+        ext_gs_states.pair(Name(name.as_bytes()), mask_ref);
+    }
     ext_gs_states.finish();
 
     resources.finish();
@@ -155,7 +179,7 @@ fn write_global_resources(ctx: &mut PdfContext) -> Ref {
 
 /// Write a page tree node.
 fn write_page(ctx: &mut PdfContext, i: usize, resources_ref: Ref) {
-    let page = &ctx.pages[i];
+    let page = ctx.pages[i].as_ref().unwrap();
     let content_id = ctx.alloc.bump();
 
     let mut page_writer = ctx.pdf.page(page.id);
@@ -209,7 +233,7 @@ fn write_page(ctx: &mut PdfContext, i: usize, resources_ref: Ref) {
         let index = pos.page.get() - 1;
         let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
 
-        if let Some(page) = ctx.pages.get(index) {
+        if let Some(Some(page)) = ctx.pages.get(index) {
             annotation
                 .action()
                 .action_type(ActionType::GoTo)
@@ -231,9 +255,23 @@ fn write_page(ctx: &mut PdfContext, i: usize, resources_ref: Ref) {
 pub(crate) fn write_page_labels(ctx: &mut PdfContext) -> Vec<(NonZeroUsize, Ref)> {
     let mut result = vec![];
     let mut prev: Option<&PdfPageLabel> = None;
+    let pages: HashMap<Ref, &EncodedPage> =
+        ctx.pages.iter().flatten().map(|page| (page.id, page)).collect();
 
-    for (i, page) in ctx.pages.iter().enumerate() {
+    for (i, page_ref) in ctx.page_refs.iter().enumerate() {
         let nr = NonZeroUsize::new(1 + i).unwrap();
+
+        // Pages imported from other files have no label. End the current
+        // label range, so that it doesn't extend to them.
+        let Some(page) = pages.get(page_ref) else {
+            if prev.take().is_some() {
+                let id = ctx.alloc.bump();
+                ctx.pdf.indirect(id).start::<PageLabel>();
+                result.push((nr, id));
+            }
+            continue;
+        };
+
         let Some(label) = &page.label else { continue };
 
         // Don't create a label if neither style nor prefix are specified.
@@ -390,6 +428,11 @@ pub enum ResourceKind {
     Gradient,
     Pattern,
     ExtGState,
+    Group,
+    // Soft masks are only created for blurred content, which needs the
+    // `raster` feature.
+    #[cfg_attr(not(feature = "raster"), allow(dead_code))]
+    SoftMask,
 }
 
 impl PageResource {
@@ -422,6 +465,16 @@ impl PageResource {
     pub fn is_ext_g_state(&self) -> bool {
         matches!(self.kind, ResourceKind::ExtGState)
     }
+
+    /// Returns whether the resource is an isolated transparency group.
+    pub fn is_group(&self) -> bool {
+        matches!(self.kind, ResourceKind::Group)
+    }
+
+    /// Returns whether the resource is a graphics state with a soft mask.
+    pub fn is_soft_mask(&self) -> bool {
+        matches!(self.kind, ResourceKind::SoftMask)
+    }
 }
 
 /// An exporter for the contents of a single PDF page.
@@ -432,6 +485,7 @@ pub struct PageContext<'a, 'b> {
     state: State,
     saves: Vec<State>,
     bottom: f32,
+    page_size: Size,
     uses_opacities: bool,
     links: Vec<(Destination, Rect)>,
     /// Keep track of the resources being used in the page.
@@ -446,12 +500,16 @@ struct State {
     transform: Transform,
     /// The transform of first hard frame in the hierarchy.
     container_transform: Transform,
+    /// The transform of the coordinate system of the innermost isolated
+    /// group's content stream.
+    group_base: Transform,
     /// The size of the first hard frame in the hierarchy.
     size: Size,
     font: Option<(Font, Abs)>,
     fill: Option<Paint>,
     fill_space: Option<Name<'static>>,
     external_graphics_state: Option<ExtGState>,
+    blend_mode: BlendMode,
     stroke: Option<FixedStroke>,
     stroke_space: Option<Name<'static>>,
     text_rendering_mode: TextRenderingMode,
@@ -463,11 +521,13 @@ impl State {
         Self {
             transform: Transform::identity(),
             container_transform: Transform::identity(),
+            group_base: Transform::identity(),
             size,
             font: None,
             fill: None,
             fill_space: None,
             external_graphics_state: None,
+            blend_mode: BlendMode::Normal,
             stroke: None,
             stroke_space: None,
             text_rendering_mode: TextRenderingMode::Fill,
@@ -475,10 +535,16 @@ impl State {
     }
 
     /// Creates the [`Transforms`] structure for the current item.
+    ///
+    /// The transforms are relative to the coordinate system of the content
+    /// stream the item is written into.
     pub fn transforms(&self, size: Size, pos: Point) -> Transforms {
+        let base = self.group_base.invert().unwrap_or_default();
         Transforms {
-            transform: self.transform.pre_concat(Transform::translate(pos.x, pos.y)),
-            container_transform: self.container_transform,
+            transform: base
+                .pre_concat(self.transform)
+                .pre_concat(Transform::translate(pos.x, pos.y)),
+            container_transform: base.pre_concat(self.container_transform),
             container_size: self.size,
             size,
         }
@@ -525,27 +591,45 @@ impl PageContext<'_, '_> {
     }
 
     fn set_opacities(&mut self, stroke: Option<&FixedStroke>, fill: Option<&Paint>) {
-        let stroke_opacity = stroke
-            .map(|stroke| {
-                let color = match &stroke.paint {
-                    Paint::Solid(color) => *color,
-                    Paint::Gradient(_) | Paint::Pattern(_) => return 255,
-                };
-
-                color.alpha().map_or(255, |v| (v * 255.0).round() as u8)
-            })
-            .unwrap_or(255);
-        let fill_opacity = fill
-            .map(|paint| {
-                let color = match paint {
-                    Paint::Solid(color) => *color,
-                    Paint::Gradient(_) | Paint::Pattern(_) => return 255,
-                };
-
-                color.alpha().map_or(255, |v| (v * 255.0).round() as u8)
-            })
-            .unwrap_or(255);
-        self.set_external_graphics_state(&ExtGState { stroke_opacity, fill_opacity });
+        let stroke_color = stroke.and_then(|stroke| match &stroke.paint {
+            Paint::Solid(color) => Some(*color),
+            Paint::Gradient(_) | Paint::Pattern(_) => None,
+        });
+        let fill_color = fill.and_then(|paint| match paint {
+            Paint::Solid(color) => Some(*color),
+            Paint::Gradient(_) | Paint::Pattern(_) => None,
+        });
+
+        let opacity = |color: Option<Color>| {
+            color
+                .and_then(|color| color.alpha())
+                .map_or(255, |v| (v * 255.0).round() as u8)
+        };
+        let overprint =
+            |color: Option<Color>| matches!(color, Some(Color::Cmyk(c)) if c.overprint);
+        let intent = |color: Option<Color>| match color {
+            Some(Color::Icc(c)) => Some(c.profile.intent()),
+            _ => None,
+        };
+
+        self.set_external_graphics_state(&ExtGState {
+            stroke_opacity: opacity(stroke_color),
+            fill_opacity: opacity(fill_color),
+            blend_mode: self.state.blend_mode,
+            stroke_overprint: overprint(stroke_color),
+            fill_overprint: overprint(fill_color),
+            rendering_intent: intent(fill_color).or(intent(stroke_color)),
+        });
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        let graphics_state = ExtGState {
+            blend_mode,
+            ..self.state.external_graphics_state.unwrap_or_default()
+        };
+        self.set_external_graphics_state(&graphics_state);
+        self.state.blend_mode = blend_mode;
+        self.uses_opacities = true;
     }
 
     fn transform(&mut self, transform: Transform) {
@@ -586,7 +670,7 @@ impl PageContext<'_, '_> {
 
     fn set_fill(&mut self, fill: &Paint, on_text: bool, transforms: Transforms) {
         if self.state.fill.as_ref() != Some(fill)
-            || matches!(self.state.fill, Some(Paint::Gradient(_)))
+            || matches!(self.state.fill, Some(Paint::Gradient(_) | Paint::Pattern(_)))
         {
             fill.set_as_fill(self, on_text, transforms);
             self.state.fill = Some(fill.clone());
@@ -613,7 +697,7 @@ impl PageContext<'_, '_> {
         if self.state.stroke.as_ref() != Some(stroke)
             || matches!(
                 self.state.stroke.as_ref().map(|s| &s.paint),
-                Some(Paint::Gradient(_))
+                Some(Paint::Gradient(_) | Paint::Pattern(_))
             )
         {
             let FixedStroke { paint, thickness, cap, join, dash, miter_limit } = stroke;
@@ -676,6 +760,7 @@ fn write_frame(ctx: &mut PageContext, frame: &Frame) {
                 Meta::Link(dest) => write_link(ctx, pos, dest, *size),
                 Meta::Elem(_) => {}
                 Meta::Hide => {}
+                Meta::Reveal(_) => {}
             },
         }
     }
@@ -705,10 +790,112 @@ fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
         ctx.content.end_path();
     }
 
-    write_frame(ctx, &group.frame);
+    if group.blur > Abs::zero() {
+        write_blurred_group(ctx, group);
+    } else if group.isolated {
+        write_isolated_group(ctx, group);
+    } else {
+        if group.blend_mode != BlendMode::Normal {
+            ctx.set_blend_mode(group.blend_mode);
+        }
+        write_frame(ctx, &group.frame);
+    }
+
     ctx.restore_state();
 }
 
+/// Encode a group as an isolated transparency group, which is painted as a
+/// whole with the group's blend mode and opacity.
+fn write_isolated_group(ctx: &mut PageContext, group: &GroupItem) {
+    if group.opacity != Ratio::one() {
+        // The group is painted with the nonstroking alpha constant.
+        let opacity = (group.opacity.get() * 255.0).round() as u8;
+        ctx.set_external_graphics_state(&ExtGState {
+            stroke_opacity: opacity,
+            fill_opacity: opacity,
+            blend_mode: group.blend_mode,
+            ..ExtGState::default()
+        });
+        ctx.state.blend_mode = group.blend_mode;
+    } else {
+        ctx.set_blend_mode(group.blend_mode);
+    }
+
+    // The group's content stream lives in the current user space. Its
+    // bounding box covers the visible part of the page.
+    let page = ctx.state.transform.invert().unwrap_or_default();
+    let size = ctx.page_size;
+    let corners =
+        [Point::zero(), Point::with_x(size.x), Point::with_y(size.y), size.to_point()]
+            .map(|corner| corner.transform(page));
+    let min = corners.into_iter().reduce(Point::min).unwrap();
+    let max = corners.into_iter().reduce(Point::max).unwrap();
+
+    // Blend mode and opacities are reset at the start of the group.
+    let outer = std::mem::replace(&mut ctx.content, Content::new());
+    ctx.state.group_base = ctx.state.transform;
+    ctx.state.external_graphics_state = None;
+    ctx.state.blend_mode = BlendMode::Normal;
+    write_frame(ctx, &group.frame);
+    let inner = std::mem::replace(&mut ctx.content, outer);
+
+    let index = ctx.parent.group_map.insert(PdfGroup {
+        content: deflate(&inner.finish()),
+        bbox: [min.x, min.y, max.x, max.y],
+    });
+    let name = eco_format!("Fm{index}");
+    ctx.content.x_object(Name(name.as_bytes()));
+    ctx.resources
+        .insert(PageResource::new(ResourceKind::Group, name), index);
+}
+
+/// Encode a blurred group. Since PDF has no native support for blurring, the
+/// group's content is kept and faded out with a soft mask of its blurred
+/// shape.
+///
+/// Without the `raster` feature, the group is written without the blur. The
+/// [warnings](crate::warnings) of the export report this.
+#[cfg(not(feature = "raster"))]
+fn write_blurred_group(ctx: &mut PageContext, group: &GroupItem) {
+    ctx.set_blend_mode(group.blend_mode);
+    write_frame(ctx, &group.frame);
+}
+
+/// Encode a blurred group. Since PDF has no native support for blurring, the
+/// group's content is kept and faded out with a soft mask of its blurred
+/// shape.
+#[cfg(feature = "raster")]
+fn write_blurred_group(ctx: &mut PageContext, group: &GroupItem) {
+    use crate::mask::PdfSoftMask;
+
+    // Make room for the parts of the blur that extend beyond the frame.
+    let margin = 3.0 * group.blur;
+    let size = group.frame.size() + Size::splat(2.0 * margin);
+    let mut frame = Frame::soft(size);
+    frame.push(Point::splat(margin), FrameItem::Group(group.clone()));
+
+    // Since the blurred shape has no sharp details, a resolution at which the
+    // blur spans a few pixels suffices.
+    let pixel_per_pt = (2.0 / group.blur.to_f32()).clamp(1.0, 4.0);
+    let pixmap = typst_render::render(&frame, pixel_per_pt, Color::WHITE.with_alpha(0.0));
+    let alpha: Vec<u8> = pixmap.pixels().iter().map(|pixel| pixel.alpha()).collect();
+
+    let index = ctx.parent.soft_mask_map.insert(PdfSoftMask {
+        alpha: deflate(&alpha),
+        width: pixmap.width(),
+        height: pixmap.height(),
+        bbox: [-margin, -margin, size.x - margin, size.y - margin],
+    });
+    let name = eco_format!("Sm{index}");
+    ctx.content.set_parameters(Name(name.as_bytes()));
+    ctx.resources
+        .insert(PageResource::new(ResourceKind::SoftMask, name), index);
+    ctx.uses_opacities = true;
+
+    ctx.set_blend_mode(group.blend_mode);
+    write_frame(ctx, &group.frame);
+}
+
 /// Encode a text run into the content stream.
 fn write_text(ctx: &mut PageContext, pos: Point, text: &TextItem) {
     let x = pos.x.to_f32();
@@ -811,7 +998,8 @@ fn write_shape(ctx: &mut PageContext, pos: Point, shape: &Shape) {
         ctx.set_stroke(
             stroke,
             false,
-            ctx.state.transforms(shape.geometry.bbox_size(), pos),
+            ctx.state
+                .transforms(shape.geometry.paint_bbox_size(stroke.thickness), pos),
         );
     }
 
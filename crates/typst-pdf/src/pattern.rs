@@ -31,12 +31,20 @@ pub(crate) fn write_patterns(ctx: &mut PdfContext) {
 
         let mut resources_map = tiling_pattern.resources();
 
-        resources_map.x_objects().pairs(
-            resources
-                .iter()
-                .filter(|(res, _)| res.is_x_object())
-                .map(|(res, ref_)| (res.name(), ctx.image_refs[*ref_])),
-        );
+        resources_map
+            .x_objects()
+            .pairs(
+                resources
+                    .iter()
+                    .filter(|(res, _)| res.is_x_object())
+                    .map(|(res, ref_)| (res.name(), ctx.image_refs[*ref_])),
+            )
+            .pairs(
+                resources
+                    .iter()
+                    .filter(|(res, _)| res.is_group())
+                    .map(|(res, ref_)| (res.name(), ctx.group_refs[*ref_])),
+            );
 
         resources_map.fonts().pairs(
             resources
@@ -63,12 +71,20 @@ pub(crate) fn write_patterns(ctx: &mut PdfContext) {
                     .map(|(res, ref_)| (res.name(), ctx.gradient_refs[*ref_])),
             );
 
-        resources_map.ext_g_states().pairs(
-            resources
-                .iter()
-                .filter(|(res, _)| res.is_ext_g_state())
-                .map(|(res, ref_)| (res.name(), ctx.ext_gs_refs[*ref_])),
-        );
+        resources_map
+            .ext_g_states()
+            .pairs(
+                resources
+                    .iter()
+                    .filter(|(res, _)| res.is_ext_g_state())
+                    .map(|(res, ref_)| (res.name(), ctx.ext_gs_refs[*ref_])),
+            )
+            .pairs(
+                resources
+                    .iter()
+                    .filter(|(res, _)| res.is_soft_mask())
+                    .map(|(res, ref_)| (res.name(), ctx.soft_mask_refs[*ref_])),
+            );
 
         resources_map.finish();
         tiling_pattern
@@ -0,0 +1,29 @@
+//! Tests for the named destinations of labelled elements.
+
+mod common;
+
+use typst::foundations::Smart;
+
+use self::common::compile;
+
+#[test]
+fn test_destination_names() {
+    let document = compile(
+        "#set document(anchors: it => \"sec-\" + str(it))\n\
+         = Intro <intro>\n\
+         #figure[Chart] <chart>\n\
+         #figure[Table] <table>\n\
+         See #link(<chart>)[the chart].\n",
+    );
+
+    let pdf = typst_pdf::pdf(&document, Smart::Auto, None);
+    let text = String::from_utf8_lossy(&pdf);
+
+    // Labelled headings and link targets are named, in sorted order.
+    let names = text.split_once("/Names [").unwrap().1.split_once(']').unwrap().0;
+    let names: Vec<_> = names.split_whitespace().filter(|s| s.starts_with('(')).collect();
+    assert_eq!(names, ["(sec-chart)", "(sec-intro)"]);
+
+    // The link leads to the destination by name.
+    assert!(text.contains("/D (sec-chart)"));
+}
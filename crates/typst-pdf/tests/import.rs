@@ -0,0 +1,151 @@
+//! Tests for importing pages from existing PDF files.
+//!
+//! The fixtures cover the common ways in which producers structure files:
+//! - `classic.pdf`: A cross-reference table, uncompressed streams, attributes
+//!   inherited from the page tree, and a stream length given by reference.
+//! - `compressed.pdf`: A cross-reference stream with PNG prediction, objects
+//!   packed into an object stream, and a nested page tree.
+//! - `damaged.pdf`: The same as `classic.pdf`, but with wrong offsets in the
+//!   cross-reference table.
+
+use std::num::NonZeroUsize;
+use std::path::Path;
+
+use typst::foundations::{Bytes, Smart};
+use typst::layout::{Abs, Frame, Page, PageRanges, Size};
+use typst::model::Document;
+use typst_pdf::{pdf_with_imports, ImportPosition, PdfImport, PdfSource};
+
+#[test]
+fn test_parse_fixtures() {
+    assert_eq!(fixture("classic.pdf").page_count(), 2);
+    assert_eq!(fixture("compressed.pdf").page_count(), 3);
+    assert_eq!(fixture("damaged.pdf").page_count(), 2);
+}
+
+#[test]
+fn test_parse_invalid() {
+    #[track_caller]
+    fn test(data: &[u8], message: &str) {
+        let err = PdfSource::new(Bytes::from(data.to_vec())).unwrap_err();
+        assert_eq!(err, message);
+    }
+
+    test(b"not a PDF file", "file is not a valid PDF file");
+    test(b"%PDF-1.7\n%%EOF\n", "PDF file has no document catalog");
+    test(
+        b"%PDF-1.7\n1 0 obj\n<< /Type /ObjStm /N 1 /First 4 /Filter /FlateDecode \
+          /DecodeParms << /Predictor 12 /Columns 4611686018427387904 >> \
+          /Length 13 >>\nstream\n\x78\x9c\x33\x54\x30\x50\x30\x04\x00\x02\x7b\x00\xd3\n\
+          endstream\nendobj\n%%EOF\n",
+        "PDF file contains a stream with invalid predictor parameters",
+    );
+}
+
+#[test]
+fn test_import_positions() {
+    let pdf = export(
+        &document(&[10.0, 20.0]),
+        vec![
+            import("compressed.pdf", Some("1"), ImportPosition::Start),
+            import("classic.pdf", None, ImportPosition::After(NonZeroUsize::MIN)),
+            import("damaged.pdf", Some("2"), ImportPosition::End),
+        ],
+    );
+
+    assert_eq!(
+        media_boxes(&pdf),
+        [
+            "[0 0 200 200]",
+            "[0 0 10 10]",
+            "[0 0 612 792]",
+            "[0 0 300 400]",
+            "[0 0 20 20]",
+            "[0 0 300 400]",
+        ]
+    );
+}
+
+#[test]
+fn test_import_copies_resources() {
+    let pdf = export(
+        &document(&[]),
+        vec![
+            import("classic.pdf", None, ImportPosition::Start),
+            import("compressed.pdf", Some("3"), ImportPosition::End),
+        ],
+    );
+
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains("(Classic page one) Tj"));
+    assert!(text.contains("(Classic page two) Tj"));
+    assert!(text.contains("/BaseFont /Helvetica"));
+    assert!(text.contains("/BaseFont /Courier"));
+    assert!(text.contains("/Rotate 90"));
+    assert!(!text.contains("/ObjStm"));
+    assert_eq!(PdfSource::new(Bytes::from(pdf)).unwrap().page_count(), 3);
+}
+
+/// Load a fixture.
+fn fixture(name: &str) -> PdfSource {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    PdfSource::new(Bytes::from(std::fs::read(path).unwrap())).unwrap()
+}
+
+/// Import the selected pages of a fixture at a position.
+fn import(name: &str, pages: Option<&str>, position: ImportPosition) -> PdfImport {
+    PdfImport {
+        source: fixture(name),
+        pages: pages.map(|pages| pages.parse().unwrap()),
+        position,
+    }
+}
+
+/// A document with empty square pages of the given sizes.
+fn document(sizes: &[f64]) -> Document {
+    let pages = sizes
+        .iter()
+        .enumerate()
+        .map(|(i, &size)| Page {
+            frame: Frame::hard(Size::splat(Abs::pt(size))),
+            numbering: None,
+            number: i + 1,
+            repeated: false,
+        })
+        .collect();
+    Document { pages, ..Default::default() }
+}
+
+/// Export a document with imports.
+fn export(document: &Document, imports: Vec<PdfImport>) -> Vec<u8> {
+    pdf_with_imports(document, Smart::Auto, None, None, &imports)
+}
+
+/// The media boxes of an exported file's pages, in order.
+///
+/// Each page is extracted on its own by importing it again, so that this also
+/// checks that exported files can be imported.
+fn media_boxes(pdf: &[u8]) -> Vec<String> {
+    let source = PdfSource::new(Bytes::from(pdf.to_vec())).unwrap();
+    (1..=source.page_count())
+        .map(|i| {
+            let single = export(
+                &document(&[]),
+                vec![PdfImport {
+                    source: source.clone(),
+                    pages: Some(PageRanges::new(vec![typst::layout::PageRange::Span(
+                        NonZeroUsize::new(i),
+                        NonZeroUsize::new(i),
+                    )])),
+                    position: ImportPosition::Start,
+                }],
+            );
+            let text = String::from_utf8_lossy(&single);
+            let start = text.find("/MediaBox ").unwrap() + "/MediaBox ".len();
+            let end = start + text[start..].find(']').unwrap() + 1;
+            text[start..end].to_string()
+        })
+        .collect()
+}
@@ -0,0 +1,51 @@
+//! Tests for the document outline that is written into exported files.
+
+mod common;
+
+use typst::foundations::Smart;
+use typst::model::OutlineNode;
+
+use self::common::compile;
+
+#[test]
+fn test_outline_tree() {
+    let document = compile(
+        "#set heading(numbering: \"1.1\")\n\
+         = Intro <intro>\n\
+         #heading(level: 2, bookmarked: false)[Hidden]\n\
+         === Scope\n\
+         #pagebreak()\n\
+         #heading(numbering: none)[Results]\n",
+    );
+
+    let outline = document.outline();
+    let [intro, results] = outline.as_slice() else {
+        panic!("expected two top-level headings");
+    };
+    assert_eq!(intro.title, "Intro");
+    assert_eq!(intro.numbers.as_deref(), Some("1"));
+    assert_eq!(intro.level.get(), 1);
+    assert_eq!(intro.heading.label().map(|label| label.as_str()), Some("intro"));
+    assert_eq!(intro.position.page.get(), 1);
+    assert_eq!(intro.count(), 2);
+
+    // The hidden heading is skipped, so its subheading moves up.
+    let [scope] = intro.children.as_slice() else {
+        panic!("expected one nested heading");
+    };
+    assert_eq!(scope.title, "Scope");
+    assert_eq!(scope.numbers.as_deref(), Some("1.1.1"));
+    assert_eq!(scope.level.get(), 3);
+    assert!(scope.children.is_empty());
+
+    assert_eq!(results.title, "Results");
+    assert_eq!(results.numbers, None);
+    assert_eq!(results.position.page.get(), 2);
+    assert_eq!(outline.iter().map(OutlineNode::count).sum::<usize>(), 3);
+
+    // The root lists the top-level headings and "Intro" is collapsed.
+    let pdf = typst_pdf::pdf(&document, Smart::Auto, None);
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains("/Count 2"));
+    assert!(text.contains("/Count -1"));
+}
@@ -11,13 +11,14 @@ use tiny_skia as sk;
 use ttf_parser::{GlyphId, OutlineBuilder};
 use typst::introspection::Meta;
 use typst::layout::{
-    Abs, Axes, Frame, FrameItem, FrameKind, GroupItem, Point, Ratio, Size, Transform,
+    Abs, Axes, Frame, FrameItem, FrameKind, GroupItem, Page, PageRanges, Point, Ratio,
+    Size, Transform,
 };
 use typst::model::Document;
 use typst::text::{Font, TextItem};
 use typst::visualize::{
-    Color, DashPattern, FixedStroke, Geometry, Gradient, Image, ImageKind, LineCap,
-    LineJoin, Paint, Path, PathItem, Pattern, RasterFormat, RelativeTo, Shape,
+    BlendMode, Color, DashPattern, FixedStroke, Geometry, Gradient, Image, ImageKind,
+    LineCap, LineJoin, Paint, Path, PathItem, Pattern, RasterFormat, RelativeTo, Shape,
 };
 use usvg::TreeParsing;
 
@@ -50,9 +51,43 @@ pub fn render_merged(
     padding: Abs,
     padding_fill: Color,
 ) -> sk::Pixmap {
-    let pixmaps: Vec<_> = document
+    let pages = document.pages.iter().collect();
+    merge_pages(pages, pixel_per_pt, frame_fill, padding, padding_fill)
+}
+
+/// Export a selection of a document's pages into a single raster image.
+///
+/// This works like [`render_merged`], but only renders the pages selected by
+/// the `page_ranges`.
+pub fn render_merged_pages(
+    document: &Document,
+    pixel_per_pt: f32,
+    frame_fill: Color,
+    padding: Abs,
+    padding_fill: Color,
+    page_ranges: &PageRanges,
+) -> sk::Pixmap {
+    let pages = document
         .pages
         .iter()
+        .enumerate()
+        .filter(|&(i, _)| page_ranges.includes_page_index(i))
+        .map(|(_, page)| page)
+        .collect();
+    merge_pages(pages, pixel_per_pt, frame_fill, padding, padding_fill)
+}
+
+/// Render the given pages into a single raster image, with the padding around
+/// and between them.
+fn merge_pages(
+    pages: Vec<&Page>,
+    pixel_per_pt: f32,
+    frame_fill: Color,
+    padding: Abs,
+    padding_fill: Color,
+) -> sk::Pixmap {
+    let pixmaps: Vec<_> = pages
+        .into_iter()
         .map(|page| render(&page.frame, pixel_per_pt, frame_fill))
         .collect();
 
@@ -95,6 +130,8 @@ struct State<'a> {
     pixel_per_pt: f32,
     /// The size of the first hard frame in the hierarchy.
     size: Size,
+    /// How to blend the current item with what is behind it.
+    blend_mode: sk::BlendMode,
 }
 
 impl<'a> State<'a> {
@@ -139,6 +176,11 @@ impl<'a> State<'a> {
         Self { size, ..self }
     }
 
+    /// Sets the blend mode of the current item.
+    fn with_blend_mode(self, blend_mode: sk::BlendMode) -> Self {
+        Self { blend_mode, ..self }
+    }
+
     /// Pre concat the container's transform.
     fn pre_concat_container(self, transform: sk::Transform) -> Self {
         Self {
@@ -151,28 +193,74 @@ impl<'a> State<'a> {
 /// Render a frame into the canvas.
 fn render_frame(canvas: &mut sk::Pixmap, state: State, frame: &Frame) {
     for (pos, item) in frame.items() {
-        match item {
-            FrameItem::Group(group) => {
-                render_group(canvas, state, *pos, group);
-            }
-            FrameItem::Text(text) => {
-                render_text(canvas, state.pre_translate(*pos), text);
-            }
-            FrameItem::Shape(shape, _) => {
-                render_shape(canvas, state.pre_translate(*pos), shape);
-            }
-            FrameItem::Image(image, size, _) => {
-                render_image(canvas, state.pre_translate(*pos), image, *size);
-            }
-            FrameItem::Meta(meta, _) => match meta {
-                Meta::Link(_) => {}
-                Meta::Elem(_) => {}
-                Meta::Hide => {}
-            },
+        // Within non-isolated blended groups, each item is blended individually.
+        if state.blend_mode != sk::BlendMode::SourceOver
+            && matches!(
+                item,
+                FrameItem::Text(_) | FrameItem::Shape(..) | FrameItem::Image(..)
+            )
+        {
+            render_layer(canvas, state, state.blend_mode, 1.0, |layer, state| {
+                render_item(layer, state, *pos, item)
+            });
+        } else {
+            render_item(canvas, state, *pos, item);
         }
     }
 }
 
+/// Render a single frame item into the canvas.
+fn render_item(canvas: &mut sk::Pixmap, state: State, pos: Point, item: &FrameItem) {
+    match item {
+        FrameItem::Group(group) => {
+            render_group(canvas, state, pos, group);
+        }
+        FrameItem::Text(text) => {
+            render_text(canvas, state.pre_translate(pos), text);
+        }
+        FrameItem::Shape(shape, _) => {
+            render_shape(canvas, state.pre_translate(pos), shape);
+        }
+        FrameItem::Image(image, size, _) => {
+            render_image(canvas, state.pre_translate(pos), image, *size);
+        }
+        FrameItem::Meta(meta, _) => match meta {
+            Meta::Link(_) => {}
+            Meta::Elem(_) => {}
+            Meta::Hide => {}
+            Meta::Reveal(_) => {}
+        },
+    }
+}
+
+/// Render something into a separate layer and composite that layer onto the
+/// canvas with the given blend mode and opacity.
+fn render_layer<F>(
+    canvas: &mut sk::Pixmap,
+    state: State,
+    blend_mode: sk::BlendMode,
+    opacity: f32,
+    f: F,
+) where
+    F: FnOnce(&mut sk::Pixmap, State),
+{
+    let Some(mut layer) = sk::Pixmap::new(canvas.width(), canvas.height()) else {
+        return;
+    };
+
+    let inner = state.with_mask(None).with_blend_mode(sk::BlendMode::SourceOver);
+    f(&mut layer, inner);
+
+    canvas.draw_pixmap(
+        0,
+        0,
+        layer.as_ref(),
+        &sk::PixmapPaint { opacity, blend_mode, ..Default::default() },
+        sk::Transform::identity(),
+        state.mask,
+    );
+}
+
 /// Render a group frame with optional transform and clipping into the canvas.
 fn render_group(canvas: &mut sk::Pixmap, state: State, pos: Point, group: &GroupItem) {
     let sk_transform = to_sk_transform(&group.transform);
@@ -228,7 +316,101 @@ fn render_group(canvas: &mut sk::Pixmap, state: State, pos: Point, group: &Group
         }
     }
 
-    render_frame(canvas, state.with_mask(mask), &group.frame);
+    let state = state.with_mask(mask);
+    let blend_mode = to_sk_blend_mode(group.blend_mode);
+    let opacity = group.opacity.get() as f32;
+    if group.blur > Abs::zero() {
+        let ts = state.transform;
+        let scale = (ts.sx * ts.sy - ts.kx * ts.ky).abs().sqrt();
+        let sigma = scale * group.blur.to_f32();
+        render_layer(canvas, state, blend_mode, opacity, |layer, state| {
+            render_frame(layer, state, &group.frame);
+            blur(layer, sigma);
+        });
+    } else if group.isolated {
+        render_layer(canvas, state, blend_mode, opacity, |layer, state| {
+            render_frame(layer, state, &group.frame)
+        });
+    } else {
+        render_frame(canvas, state.with_blend_mode(blend_mode), &group.frame);
+    }
+}
+
+/// Blur a pixmap with a gaussian blur of the given standard deviation in
+/// pixels.
+///
+/// The gaussian blur is approximated with three successive box blurs.
+fn blur(pixmap: &mut sk::Pixmap, sigma: f32) {
+    const PASSES: usize = 3;
+
+    // Determine the box sizes, following "Fast Almost-Gaussian Filtering" by
+    // Peter Kovesi.
+    let n = PASSES as f32;
+    let ideal = (12.0 * sigma * sigma / n + 1.0).sqrt();
+    let mut lower = ideal.floor() as usize;
+    if lower % 2 == 0 {
+        lower = lower.saturating_sub(1);
+    }
+    let wl = lower as f32;
+    let m = ((12.0 * sigma * sigma - n * wl * wl - 4.0 * n * wl - 3.0 * n)
+        / (-4.0 * wl - 4.0))
+        .round()
+        .max(0.0) as usize;
+
+    let w = pixmap.width() as usize;
+    let h = pixmap.height() as usize;
+    let data = pixmap.data_mut();
+    let mut buf = vec![0; data.len()];
+    for pass in 0..PASSES {
+        let size = if pass < m { lower } else { lower + 2 };
+        let radius = size / 2;
+        box_blur(data, &mut buf, h, w, w, 1, radius);
+        box_blur(&buf, data, w, h, 1, w, radius);
+    }
+}
+
+/// Blur the RGBA pixels in `src` along `count` lines of `len` pixels each with
+/// a box filter and write the result into `dst`. The `i`-th pixel of the
+/// `j`-th line is at pixel index `j * stride + i * step`.
+///
+/// Pixels outside of the image are treated as transparent.
+fn box_blur(
+    src: &[u8],
+    dst: &mut [u8],
+    count: usize,
+    len: usize,
+    stride: usize,
+    step: usize,
+    radius: usize,
+) {
+    let div = 2 * radius as u32 + 1;
+    for line in 0..count {
+        let at = |i: usize| 4 * (line * stride + i * step);
+        let mut sum = [0u32; 4];
+        for i in 0..radius.min(len) {
+            for (c, s) in sum.iter_mut().enumerate() {
+                *s += src[at(i) + c] as u32;
+            }
+        }
+
+        for i in 0..len {
+            if i + radius < len {
+                for (c, s) in sum.iter_mut().enumerate() {
+                    *s += src[at(i + radius) + c] as u32;
+                }
+            }
+
+            for (c, s) in sum.iter().enumerate() {
+                dst[at(i) + c] = ((s + div / 2) / div) as u8;
+            }
+
+            if i >= radius {
+                for (c, s) in sum.iter_mut().enumerate() {
+                    *s -= src[at(i - radius) + c] as u32;
+                }
+            }
+        }
+    }
 }
 
 /// Render a text run into the canvas.
@@ -465,7 +647,7 @@ fn render_outline_glyph(
             write_bitmap(canvas, &bitmap, &state, sampler)?;
         }
         Paint::Solid(color) => {
-            write_bitmap(canvas, &bitmap, &state, to_sk_color_u8(*color).premultiply())?;
+            write_bitmap(canvas, &bitmap, &state, to_sk_premultiplied(*color))?;
         }
         Paint::Pattern(pattern) => {
             let pixmap = render_pattern_frame(&state, pattern);
@@ -605,7 +787,7 @@ fn render_shape(canvas: &mut sk::Pixmap, state: State, shape: &Shape) -> Option<
         if width > 0.0 {
             let dash = dash.as_ref().and_then(to_sk_dash_pattern);
 
-            let bbox = shape.geometry.bbox_size();
+            let bbox = shape.geometry.paint_bbox_size(*thickness);
             let offset_bbox = (!matches!(shape.geometry, Geometry::Line(..)))
                 .then(|| offset_bounding_box(bbox, *thickness))
                 .unwrap_or(bbox);
@@ -820,11 +1002,10 @@ impl PaintSampler for GradientSampler<'_> {
         self.transform_to_parent.map_point(&mut point);
 
         // Sample the gradient
-        to_sk_color_u8(self.gradient.sample_at(
+        to_sk_premultiplied(self.gradient.sample_at(
             (point.x, point.y),
             (self.container_size.x.to_f32(), self.container_size.y.to_f32()),
         ))
-        .premultiply()
     }
 }
 
@@ -904,19 +1085,22 @@ fn to_sk_paint<'a>(
         let (offset, scale) =
             gradient_map.unwrap_or_else(|| (Point::zero(), Axes::splat(Ratio::one())));
         let mut pixmap = sk::Pixmap::new(width.max(1), height.max(1)).unwrap();
-        for x in 0..width {
-            for y in 0..height {
-                let color = gradient.sample_at(
-                    (
-                        (x as f32 + offset.x.to_f32()) * scale.x.get() as f32,
-                        (y as f32 + offset.y.to_f32()) * scale.y.get() as f32,
-                    ),
-                    (width as f32, height as f32),
-                );
 
-                pixmap.pixels_mut()[(y * width + x) as usize] =
-                    to_sk_color(color).premultiply().to_color_u8();
-            }
+        // Sample all pixels at once, in the same row-major order as the
+        // pixmap's pixels, to convert them into sRGB in a single batch.
+        let positions = (0..height).flat_map(|y| {
+            (0..width).map(move |x| {
+                (
+                    (x as f32 + offset.x.to_f32()) * scale.x.get() as f32,
+                    (y as f32 + offset.y.to_f32()) * scale.y.get() as f32,
+                )
+            })
+        });
+        let mut colors = Vec::with_capacity((width * height) as usize);
+        gradient.sample_srgb_at(positions, (width as f32, height as f32), &mut colors);
+
+        for (pixel, color) in pixmap.pixels_mut().iter_mut().zip(colors) {
+            *pixel = srgb_to_sk_premultiplied(color);
         }
 
         Arc::new(pixmap)
@@ -1013,9 +1197,16 @@ fn to_sk_color(color: Color) -> sk::Color {
         .expect("components must always be in the range [0..=1]")
 }
 
-fn to_sk_color_u8(color: Color) -> sk::ColorU8 {
-    let [r, g, b, a] = color.to_rgb().to_vec4_u8();
-    sk::ColorU8::from_rgba(r, g, b, a)
+fn to_sk_premultiplied(color: Color) -> sk::PremultipliedColorU8 {
+    let [r, g, b, a] = color.to_premultiplied_u8();
+    sk::PremultipliedColorU8::from_rgba(r, g, b, a)
+        .expect("premultiplied components must never exceed the alpha")
+}
+
+fn srgb_to_sk_premultiplied(vec: [f32; 4]) -> sk::PremultipliedColorU8 {
+    let [r, g, b, a] = Color::premultiply_vec4(vec).map(|x| (x * 255.0).round() as u8);
+    sk::PremultipliedColorU8::from_rgba(r, g, b, a)
+        .expect("premultiplied components must never exceed the alpha")
 }
 
 fn to_sk_line_cap(cap: LineCap) -> sk::LineCap {
@@ -1034,6 +1225,27 @@ fn to_sk_line_join(join: LineJoin) -> sk::LineJoin {
     }
 }
 
+fn to_sk_blend_mode(mode: BlendMode) -> sk::BlendMode {
+    match mode {
+        BlendMode::Normal => sk::BlendMode::SourceOver,
+        BlendMode::Multiply => sk::BlendMode::Multiply,
+        BlendMode::Screen => sk::BlendMode::Screen,
+        BlendMode::Overlay => sk::BlendMode::Overlay,
+        BlendMode::Darken => sk::BlendMode::Darken,
+        BlendMode::Lighten => sk::BlendMode::Lighten,
+        BlendMode::ColorDodge => sk::BlendMode::ColorDodge,
+        BlendMode::ColorBurn => sk::BlendMode::ColorBurn,
+        BlendMode::HardLight => sk::BlendMode::HardLight,
+        BlendMode::SoftLight => sk::BlendMode::SoftLight,
+        BlendMode::Difference => sk::BlendMode::Difference,
+        BlendMode::Exclusion => sk::BlendMode::Exclusion,
+        BlendMode::Hue => sk::BlendMode::Hue,
+        BlendMode::Saturation => sk::BlendMode::Saturation,
+        BlendMode::Color => sk::BlendMode::Color,
+        BlendMode::Luminosity => sk::BlendMode::Luminosity,
+    }
+}
+
 fn to_sk_transform(transform: &Transform) -> sk::Transform {
     let Transform { sx, ky, kx, sy, tx, ty } = *transform;
     sk::Transform::from_row(
@@ -1047,11 +1259,9 @@ fn to_sk_transform(transform: &Transform) -> sk::Transform {
 }
 
 fn to_sk_dash_pattern(pattern: &DashPattern<Abs, Abs>) -> Option<sk::StrokeDash> {
-    // tiny-skia only allows dash patterns with an even number of elements,
-    // while pdf allows any number.
-    let pattern_len = pattern.array.len();
-    let len = if pattern_len % 2 == 1 { 2 * pattern_len } else { pattern_len };
-    let dash_array = pattern.array.iter().map(|l| l.to_f32()).cycle().take(len).collect();
+    // Finished dash patterns always have an even number of elements, as
+    // required by tiny-skia.
+    let dash_array = pattern.array.iter().map(|l| l.to_f32()).collect();
     sk::StrokeDash::new(dash_array, pattern.phase.to_f32())
 }
 
@@ -1112,3 +1322,52 @@ fn alpha_mul(color: u32, scale: u32) -> u32 {
 fn offset_bounding_box(bbox: Size, stroke_width: Abs) -> Size {
     Size::new(bbox.x + stroke_width * 2.0, bbox.y + stroke_width * 2.0)
 }
+
+#[cfg(test)]
+mod tests {
+    use typst::layout::Page;
+
+    use super::*;
+
+    /// A document with three empty pages of 10pt, 20pt, and 30pt squares.
+    fn document() -> Document {
+        let pages = [10.0, 20.0, 30.0]
+            .into_iter()
+            .enumerate()
+            .map(|(i, size)| Page {
+                frame: Frame::hard(Size::splat(Abs::pt(size))),
+                numbering: None,
+                number: i + 1,
+                repeated: false,
+            })
+            .collect();
+        Document { pages, ..Default::default() }
+    }
+
+    #[test]
+    fn test_render_merged_page_ranges() {
+        #[track_caller]
+        fn test(ranges: Option<&str>, width: u32, height: u32) {
+            let ranges = ranges.map(|ranges| ranges.parse::<PageRanges>().unwrap());
+            let (fill, padding, padding_fill) =
+                (Color::WHITE, Abs::pt(1.0), Color::BLACK);
+            let pixmap = match ranges {
+                Some(ranges) => render_merged_pages(
+                    &document(),
+                    1.0,
+                    fill,
+                    padding,
+                    padding_fill,
+                    &ranges,
+                ),
+                None => render_merged(&document(), 1.0, fill, padding, padding_fill),
+            };
+            assert_eq!((pixmap.width(), pixmap.height()), (width, height));
+        }
+
+        test(None, 32, 64);
+        test(Some("2-3"), 32, 53);
+        test(Some("1"), 12, 12);
+        test(Some("even"), 22, 22);
+    }
+}
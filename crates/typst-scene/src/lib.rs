@@ -0,0 +1,805 @@
+//! Export of Typst documents into a renderer-agnostic scene.
+//!
+//! A [`Scene`] is a plain description of what is drawn on each page: groups
+//! with transforms and clip paths, text runs with positioned glyphs, filled and
+//! stroked paths, images, and links. All types implement [`Serialize`] and
+//! [`Deserialize`], so that external renderers (game engines, GPU canvases,
+//! diffing tools) can consume layout output without linking to any of Typst's
+//! own exporters.
+//!
+//! All lengths are in points and all coordinates are relative to the top-left
+//! corner of the enclosing page or group, with the y-axis pointing downwards.
+//! Colors are non-premultiplied sRGB with components between zero and one.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use typst::foundations::{IntoValue, Label, Value};
+use typst::introspection::{Location, Meta};
+use typst::layout::{Frame, FrameItem, GroupItem, Point as TypstPoint, Size};
+use typst::model::{Destination, Document};
+use typst::text::{Font as TypstFont, TextItem};
+use typst::visualize::{
+    Color, ColorSpace, FixedStroke, Geometry, Gradient as TypstGradient,
+    Image as TypstImage, ImageFormat, LineCap, LineJoin, Paint as TypstPaint,
+    Path as TypstPath, PathItem, RelativeTo, Shape, WhitePoint,
+};
+
+/// Export a document into a scene.
+#[typst_macros::time(name = "scene")]
+pub fn scene(document: &Document) -> Scene {
+    let mut builder = Builder::new(document);
+    let pages = document
+        .pages
+        .iter()
+        .map(|page| Page {
+            width: page.frame.width().to_pt(),
+            height: page.frame.height().to_pt(),
+            items: builder.frame(&page.frame),
+        })
+        .collect();
+
+    let anchors = document
+        .anchored()
+        .into_iter()
+        .map(|(location, label)| {
+            let position = document.introspector.position(location);
+            Anchor {
+                name: document.anchor(label).into(),
+                page: position.page.get(),
+                point: position.point.into(),
+            }
+        })
+        .collect();
+
+    Scene {
+        pages,
+        fonts: builder.fonts,
+        images: builder.images,
+        anchors,
+    }
+}
+
+/// A document's pages, plus the fonts and images they refer to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+    /// The pages, in order.
+    pub pages: Vec<Page>,
+    /// The fonts used by text runs, referred to by their index.
+    pub fonts: Vec<Font>,
+    /// The images used in the scene, referred to by their index.
+    pub images: Vec<Image>,
+    /// The anchors of labelled elements, in document order.
+    pub anchors: Vec<Anchor>,
+}
+
+/// A single page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page {
+    /// The width of the page.
+    pub width: f64,
+    /// The height of the page.
+    pub height: f64,
+    /// The items on the page, from back to front.
+    pub items: Vec<Item>,
+}
+
+/// Something that is drawn on a page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Item {
+    /// A group of items with a shared transform and clip path.
+    Group(Group),
+    /// A run of glyphs in a single font.
+    Text(Text),
+    /// A filled and/or stroked path.
+    Path(PathShape),
+    /// A placed image.
+    Image(ImagePlacement),
+    /// A clickable area.
+    Link(Link),
+}
+
+/// A group of items.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Group {
+    /// The transform from the group's coordinate system into the parent's. It
+    /// includes the group's position.
+    pub transform: Transform,
+    /// A path in the group's coordinate system to which its items are clipped.
+    pub clip: Option<Vec<PathCommand>>,
+    /// How the group's items are blended with what is behind them, as one of
+    /// Typst's blend mode names (like `"normal"` or `"multiply"`).
+    pub blend_mode: String,
+    /// Whether the group is composited in isolation before being blended.
+    pub isolated: bool,
+    /// The opacity with which the isolated group is drawn, between zero and
+    /// one.
+    pub opacity: f64,
+    /// The standard deviation of a gaussian blur applied to the group. Zero
+    /// if it is not blurred.
+    pub blur: f64,
+    /// The items in the group, from back to front.
+    pub items: Vec<Item>,
+}
+
+/// A run of glyphs in a single font.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Text {
+    /// The position of the run's baseline start.
+    pub pos: Point,
+    /// The index of the font in the scene's fonts.
+    pub font: usize,
+    /// The font size.
+    pub size: f64,
+    /// How to fill the glyphs.
+    pub fill: Paint,
+    /// How to stroke the glyphs.
+    pub stroke: Option<Stroke>,
+    /// The plain text of the run.
+    pub text: String,
+    /// The glyphs, in visual order.
+    pub glyphs: Vec<Glyph>,
+}
+
+/// A glyph in a text run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Glyph {
+    /// The glyph's index in the font.
+    pub id: u16,
+    /// How far to advance after the glyph.
+    pub x_advance: f64,
+    /// The horizontal offset of the glyph from its advanced position.
+    pub x_offset: f64,
+    /// The start and end byte offsets of the glyph's text in the run's text.
+    pub range: (usize, usize),
+}
+
+/// A path with a fill and/or stroke.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathShape {
+    /// The position of the path's origin.
+    pub pos: Point,
+    /// The path's commands.
+    pub commands: Vec<PathCommand>,
+    /// How to fill the path, using the non-zero winding rule.
+    pub fill: Option<Paint>,
+    /// How to stroke the path.
+    pub stroke: Option<Stroke>,
+}
+
+/// A command in a path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum PathCommand {
+    /// Start a new subpath.
+    MoveTo { to: Point },
+    /// Draw a straight line.
+    LineTo { to: Point },
+    /// Draw a cubic bézier curve.
+    CubicTo { c1: Point, c2: Point, to: Point },
+    /// Close the current subpath.
+    Close,
+}
+
+/// How to stroke a path or glyphs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stroke {
+    /// The stroke's paint.
+    pub paint: Paint,
+    /// The stroke's thickness.
+    pub thickness: f64,
+    /// One of `"butt"`, `"round"`, and `"square"`.
+    pub cap: String,
+    /// One of `"miter"`, `"round"`, and `"bevel"`.
+    pub join: String,
+    /// Alternating lengths of dashes and gaps, if the stroke is dashed.
+    pub dash_array: Option<Vec<f64>>,
+    /// Where in the dash pattern to start.
+    pub dash_phase: f64,
+    /// The miter limit, relative to the thickness.
+    pub miter_limit: f64,
+}
+
+/// How to paint a path or glyphs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Paint {
+    /// A solid color.
+    Solid { color: Rgba },
+    /// A gradient.
+    Gradient(Gradient),
+    /// A repeated tile of items.
+    Pattern(Pattern),
+}
+
+/// A non-premultiplied sRGB color.
+pub type Rgba = [f32; 4];
+
+/// A gradient.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gradient {
+    /// The gradient's shape.
+    pub kind: GradientKind,
+    /// The color stops, with offsets between zero and one.
+    pub stops: Vec<(Rgba, f64)>,
+    /// The color space in which to interpolate between stops, as one of
+    /// Typst's color space names (like `"oklab"` or `"srgb"`).
+    pub space: String,
+    /// Whether the gradient is relative to the painted shape itself
+    /// (`"self"`) or to its parent group (`"parent"`).
+    pub relative: String,
+    /// An additional rotation in degrees around the center of the bounding
+    /// box, applied after scaling.
+    pub rotate: f64,
+    /// An additional uniform scale around the center of the bounding box.
+    pub scale: f64,
+    /// An additional offset, relative to the bounding box, applied after
+    /// rotating.
+    pub offset: Point,
+}
+
+/// The shape of a gradient. Points and radii are relative to the bounding
+/// box the gradient is relative to, with zero and one being its edges.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum GradientKind {
+    /// Colors change along a line with the given angle in degrees, measured
+    /// clockwise from the positive x-axis.
+    Linear { angle: f64 },
+    /// Colors change between a focal circle and an end circle.
+    Radial { center: Point, radius: f64, focal_center: Point, focal_radius: f64 },
+    /// Colors change around a center, starting at the given angle in degrees.
+    Conic { center: Point, angle: f64 },
+}
+
+/// A repeated tile of items.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pattern {
+    /// The width of a tile.
+    pub width: f64,
+    /// The height of a tile.
+    pub height: f64,
+    /// The horizontal gap between tiles.
+    pub spacing_x: f64,
+    /// The vertical gap between tiles.
+    pub spacing_y: f64,
+    /// Whether the pattern is relative to the painted shape itself
+    /// (`"self"`) or to its parent group (`"parent"`).
+    pub relative: String,
+    /// The items in a tile.
+    pub items: Vec<Item>,
+}
+
+/// A placed image.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImagePlacement {
+    /// The position of the image's top-left corner.
+    pub pos: Point,
+    /// The index of the image in the scene's images.
+    pub image: usize,
+    /// The width of the placed image.
+    pub width: f64,
+    /// The height of the placed image.
+    pub height: f64,
+}
+
+/// A clickable area.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    /// The position of the area's top-left corner.
+    pub pos: Point,
+    /// The width of the area.
+    pub width: f64,
+    /// The height of the area.
+    pub height: f64,
+    /// Where the link leads.
+    pub target: LinkTarget,
+}
+
+/// Where a link leads.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum LinkTarget {
+    /// An external URL.
+    Url { url: String },
+    /// A point on a page, with pages counted from one. If the point belongs
+    /// to a labelled element, its anchor is included.
+    Position {
+        page: usize,
+        point: Point,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        anchor: Option<String>,
+    },
+}
+
+/// A named point in the document that links can refer to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Anchor {
+    /// The anchor's name, suitable as a fragment identifier.
+    pub name: String,
+    /// The page, counted from one.
+    pub page: usize,
+    /// The point on the page.
+    pub point: Point,
+}
+
+/// A font used in the scene.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Font {
+    /// The font's family name.
+    pub family: String,
+    /// One of `"normal"`, `"italic"`, and `"oblique"`.
+    pub style: String,
+    /// The font's weight, between 100 and 900.
+    pub weight: u16,
+    /// The font's stretch, between 0.5 and 2.0.
+    pub stretch: f64,
+    /// The number of font units per em.
+    pub units_per_em: f64,
+    /// The index of the font in its collection file.
+    pub index: u32,
+    /// The raw data of the font file.
+    #[serde(with = "base64_bytes")]
+    pub data: Vec<u8>,
+}
+
+/// An image used in the scene.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Image {
+    /// One of `"png"`, `"jpg"`, `"gif"`, and `"svg"`.
+    pub format: String,
+    /// The natural width of the image in pixels.
+    pub width: f64,
+    /// The natural height of the image in pixels.
+    pub height: f64,
+    /// A text describing the image.
+    pub alt: Option<String>,
+    /// The raw data of the image file.
+    #[serde(with = "base64_bytes")]
+    pub data: Vec<u8>,
+}
+
+/// A point.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    /// The horizontal coordinate.
+    pub x: f64,
+    /// The vertical coordinate.
+    pub y: f64,
+}
+
+impl From<TypstPoint> for Point {
+    fn from(point: TypstPoint) -> Self {
+        Self { x: point.x.to_pt(), y: point.y.to_pt() }
+    }
+}
+
+/// An affine transform that maps `(x, y)` to
+/// `(sx * x + kx * y + tx, ky * x + sy * y + ty)`.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transform {
+    pub sx: f64,
+    pub ky: f64,
+    pub kx: f64,
+    pub sy: f64,
+    pub tx: f64,
+    pub ty: f64,
+}
+
+/// Collects the fonts and images while converting frames.
+struct Builder<'a> {
+    document: &'a Document,
+    labels: HashMap<Location, Label>,
+    fonts: Vec<Font>,
+    font_indices: HashMap<TypstFont, usize>,
+    images: Vec<Image>,
+    image_indices: HashMap<TypstImage, usize>,
+}
+
+impl<'a> Builder<'a> {
+    fn new(document: &'a Document) -> Self {
+        Self {
+            document,
+            labels: document.anchored().into_iter().collect(),
+            fonts: vec![],
+            font_indices: HashMap::new(),
+            images: vec![],
+            image_indices: HashMap::new(),
+        }
+    }
+
+    /// Convert the items of a frame.
+    fn frame(&mut self, frame: &Frame) -> Vec<Item> {
+        frame
+            .items()
+            .filter_map(|(pos, item)| self.item(*pos, item))
+            .collect()
+    }
+
+    /// Convert a single item at a position.
+    fn item(&mut self, pos: TypstPoint, item: &FrameItem) -> Option<Item> {
+        Some(match item {
+            FrameItem::Group(group) => Item::Group(self.group(pos, group)),
+            FrameItem::Text(text) => Item::Text(self.text(pos, text)),
+            FrameItem::Shape(shape, _) => Item::Path(self.shape(pos, shape)),
+            FrameItem::Image(image, size, _) => {
+                Item::Image(self.image(pos, image, *size))
+            }
+            FrameItem::Meta(Meta::Link(dest), size) => {
+                Item::Link(self.link(pos, dest, *size)?)
+            }
+            FrameItem::Meta(..) => return None,
+        })
+    }
+
+    fn group(&mut self, pos: TypstPoint, group: &GroupItem) -> Group {
+        let transform =
+            typst::layout::Transform::translate(pos.x, pos.y).pre_concat(group.transform);
+        Group {
+            transform: Transform {
+                sx: transform.sx.get(),
+                ky: transform.ky.get(),
+                kx: transform.kx.get(),
+                sy: transform.sy.get(),
+                tx: transform.tx.to_pt(),
+                ty: transform.ty.to_pt(),
+            },
+            clip: group.clip_path.as_ref().map(path),
+            blend_mode: name(group.blend_mode),
+            isolated: group.isolated,
+            opacity: group.opacity.get(),
+            blur: group.blur.to_pt(),
+            items: self.frame(&group.frame),
+        }
+    }
+
+    fn text(&mut self, pos: TypstPoint, text: &TextItem) -> Text {
+        let font = match self.font_indices.get(&text.font) {
+            Some(&index) => index,
+            None => {
+                let info = text.font.info();
+                self.fonts.push(Font {
+                    family: info.family.clone(),
+                    style: name(info.variant.style),
+                    weight: info.variant.weight.to_number(),
+                    stretch: info.variant.stretch.to_ratio().get(),
+                    units_per_em: text.font.units_per_em(),
+                    index: text.font.index(),
+                    data: text.font.data().to_vec(),
+                });
+                self.font_indices.insert(text.font.clone(), self.fonts.len() - 1);
+                self.fonts.len() - 1
+            }
+        };
+
+        Text {
+            pos: pos.into(),
+            font,
+            size: text.size.to_pt(),
+            fill: self.paint(&text.fill),
+            stroke: text.stroke.as_ref().map(|stroke| self.stroke(stroke)),
+            text: text.text.to_string(),
+            glyphs: text
+                .glyphs
+                .iter()
+                .map(|glyph| Glyph {
+                    id: glyph.id,
+                    x_advance: glyph.x_advance.at(text.size).to_pt(),
+                    x_offset: glyph.x_offset.at(text.size).to_pt(),
+                    range: (glyph.range().start, glyph.range().end),
+                })
+                .collect(),
+        }
+    }
+
+    fn shape(&mut self, pos: TypstPoint, shape: &Shape) -> PathShape {
+        let commands = match &shape.geometry {
+            Geometry::Line(to) => path(&TypstPath(vec![
+                PathItem::MoveTo(TypstPoint::zero()),
+                PathItem::LineTo(*to),
+            ])),
+            Geometry::Rect(size) => path(&TypstPath::rect(*size)),
+            Geometry::Path(p) => path(p),
+        };
+
+        PathShape {
+            pos: pos.into(),
+            commands,
+            fill: shape.fill.as_ref().map(|fill| self.paint(fill)),
+            stroke: shape.stroke.as_ref().map(|stroke| self.stroke(stroke)),
+        }
+    }
+
+    fn image(
+        &mut self,
+        pos: TypstPoint,
+        image: &TypstImage,
+        size: Size,
+    ) -> ImagePlacement {
+        let index = match self.image_indices.get(image) {
+            Some(&index) => index,
+            None => {
+                self.images.push(Image {
+                    format: match image.format() {
+                        ImageFormat::Raster(format) => name(format),
+                        ImageFormat::Vector(format) => name(format),
+                    },
+                    width: image.width(),
+                    height: image.height(),
+                    alt: image.alt().map(Into::into),
+                    data: image.data().to_vec(),
+                });
+                self.image_indices.insert(image.clone(), self.images.len() - 1);
+                self.images.len() - 1
+            }
+        };
+
+        ImagePlacement {
+            pos: pos.into(),
+            image: index,
+            width: size.x.to_pt(),
+            height: size.y.to_pt(),
+        }
+    }
+
+    fn link(&mut self, pos: TypstPoint, dest: &Destination, size: Size) -> Option<Link> {
+        let target = match dest {
+            Destination::Url(url) => LinkTarget::Url { url: url.to_string() },
+            Destination::Position(position) => LinkTarget::Position {
+                page: position.page.get(),
+                point: position.point.into(),
+                anchor: None,
+            },
+            Destination::Location(location) => {
+                let position = self.document.introspector.position(*location);
+                LinkTarget::Position {
+                    page: position.page.get(),
+                    point: position.point.into(),
+                    anchor: self
+                        .labels
+                        .get(location)
+                        .map(|&label| self.document.anchor(label).into()),
+                }
+            }
+        };
+
+        Some(Link {
+            pos: pos.into(),
+            width: size.x.to_pt(),
+            height: size.y.to_pt(),
+            target,
+        })
+    }
+
+    fn stroke(&mut self, stroke: &FixedStroke) -> Stroke {
+        Stroke {
+            paint: self.paint(&stroke.paint),
+            thickness: stroke.thickness.to_pt(),
+            cap: match stroke.cap {
+                LineCap::Butt => "butt",
+                LineCap::Round => "round",
+                LineCap::Square => "square",
+            }
+            .into(),
+            join: match stroke.join {
+                LineJoin::Miter => "miter",
+                LineJoin::Round => "round",
+                LineJoin::Bevel => "bevel",
+            }
+            .into(),
+            dash_array: stroke
+                .dash
+                .as_ref()
+                .map(|dash| dash.array.iter().map(|l| l.to_pt()).collect()),
+            dash_phase: stroke.dash.as_ref().map_or(0.0, |dash| dash.phase.to_pt()),
+            miter_limit: stroke.miter_limit.get(),
+        }
+    }
+
+    fn paint(&mut self, paint: &TypstPaint) -> Paint {
+        match paint {
+            TypstPaint::Solid(color) => Paint::Solid { color: rgba(*color) },
+            TypstPaint::Gradient(gradient) => Paint::Gradient(self.gradient(gradient)),
+            TypstPaint::Pattern(pattern) => Paint::Pattern(Pattern {
+                width: pattern.size().x.to_pt(),
+                height: pattern.size().y.to_pt(),
+                spacing_x: pattern.spacing().x.to_pt(),
+                spacing_y: pattern.spacing().y.to_pt(),
+                relative: relative(pattern.relative()),
+                items: self.frame(pattern.frame()),
+            }),
+        }
+    }
+
+    fn gradient(&mut self, gradient: &TypstGradient) -> Gradient {
+        let point = |axes: typst::layout::Axes<typst::layout::Ratio>| Point {
+            x: axes.x.get(),
+            y: axes.y.get(),
+        };
+        let kind = match gradient {
+            TypstGradient::Linear(linear) => {
+                GradientKind::Linear { angle: linear.angle.to_deg() }
+            }
+            TypstGradient::Radial(radial) => GradientKind::Radial {
+                center: point(radial.center),
+                radius: radial.radius.get(),
+                focal_center: point(radial.focal_center),
+                focal_radius: radial.focal_radius.get(),
+            },
+            TypstGradient::Conic(conic) => GradientKind::Conic {
+                center: point(conic.center),
+                angle: conic.angle.to_deg(),
+            },
+        };
+
+        let transform = gradient.transformation();
+        Gradient {
+            kind,
+            stops: gradient
+                .stops_ref()
+                .iter()
+                .map(|(color, offset)| (rgba(*color), offset.get()))
+                .collect(),
+            space: match gradient.space() {
+                ColorSpace::Oklab => "oklab",
+                ColorSpace::Oklch => "oklch",
+                ColorSpace::Lab => "lab",
+                ColorSpace::Lch => "lch",
+                ColorSpace::Xyz(WhitePoint::D50) => "xyz-d50",
+                ColorSpace::Xyz(WhitePoint::D65) => "xyz-d65",
+                ColorSpace::Srgb => "srgb",
+                ColorSpace::DisplayP3 => "display-p3",
+                ColorSpace::D65Gray => "luma",
+                ColorSpace::LinearRgb => "linear-rgb",
+                ColorSpace::Hsl => "hsl",
+                ColorSpace::Hsv => "hsv",
+                ColorSpace::Hwb => "hwb",
+                ColorSpace::Okhsl => "okhsl",
+                ColorSpace::Okhsv => "okhsv",
+                ColorSpace::Cmyk => "cmyk",
+            }
+            .into(),
+            relative: relative(gradient.relative()),
+            rotate: transform.rotate.to_deg(),
+            scale: transform.scale.get(),
+            offset: point(transform.offset),
+        }
+    }
+}
+
+/// Convert a path.
+fn path(path: &TypstPath) -> Vec<PathCommand> {
+    path.0
+        .iter()
+        .map(|item| match *item {
+            PathItem::MoveTo(to) => PathCommand::MoveTo { to: to.into() },
+            PathItem::LineTo(to) => PathCommand::LineTo { to: to.into() },
+            PathItem::CubicTo(c1, c2, to) => {
+                PathCommand::CubicTo { c1: c1.into(), c2: c2.into(), to: to.into() }
+            }
+            PathItem::ClosePath => PathCommand::Close,
+        })
+        .collect()
+}
+
+/// Convert a color into non-premultiplied sRGB.
+fn rgba(color: Color) -> Rgba {
+    color.to_space(ColorSpace::Srgb).to_vec4()
+}
+
+/// The name of a relative placement. Defaults to `"self"`.
+fn relative(relative: typst::foundations::Smart<RelativeTo>) -> String {
+    match relative.unwrap_or(RelativeTo::Self_) {
+        RelativeTo::Self_ => "self".into(),
+        RelativeTo::Parent => "parent".into(),
+    }
+}
+
+/// The name of a value that is cast from a string.
+fn name(value: impl IntoValue) -> String {
+    match value.into_value() {
+        Value::Str(string) => string.as_str().into(),
+        _ => String::new(),
+    }
+}
+
+/// Serializes bytes as base64 strings.
+mod base64_bytes {
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        data: &[u8],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        base64::engine::general_purpose::STANDARD
+            .decode(string)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use typst::layout::{Abs, Page as TypstPage};
+    use typst::syntax::Span;
+
+    use super::*;
+
+    fn document() -> Document {
+        let square = Size::splat(Abs::pt(10.0));
+        let mut inner = Frame::hard(square);
+        inner.push(
+            TypstPoint::zero(),
+            FrameItem::Shape(
+                Geometry::Rect(square).filled(Color::RED.into()),
+                Span::detached(),
+            ),
+        );
+
+        let mut frame = Frame::hard(Size::new(Abs::pt(100.0), Abs::pt(50.0)));
+        frame.push_frame(TypstPoint::new(Abs::pt(20.0), Abs::pt(5.0)), inner);
+        frame.push(
+            TypstPoint::zero(),
+            FrameItem::Meta(
+                Meta::Link(Destination::Url("https://typst.app".into())),
+                square,
+            ),
+        );
+
+        Document {
+            pages: vec![TypstPage { frame, numbering: None, number: 1, repeated: false }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_scene_structure() {
+        let scene = scene(&document());
+        assert_eq!(scene.pages.len(), 1);
+        assert!(scene.fonts.is_empty());
+        assert!(scene.images.is_empty());
+
+        let page = &scene.pages[0];
+        assert_eq!((page.width, page.height), (100.0, 50.0));
+        assert_eq!(page.items.len(), 2);
+
+        let Item::Group(group) = &page.items[0] else { panic!("expected a group") };
+        assert_eq!((group.transform.tx, group.transform.ty), (20.0, 5.0));
+        assert_eq!((group.transform.sx, group.transform.sy), (1.0, 1.0));
+        assert_eq!(group.blend_mode, "normal");
+        assert_eq!(group.opacity, 1.0);
+
+        let Item::Path(shape) = &group.items[0] else { panic!("expected a path") };
+        assert_eq!(shape.commands.len(), 5);
+        assert_eq!(
+            shape.commands[2],
+            PathCommand::LineTo { to: Point { x: 10.0, y: 10.0 } }
+        );
+        assert_eq!(shape.commands[4], PathCommand::Close);
+        assert!(shape.stroke.is_none());
+        let Some(Paint::Solid { color }) = shape.fill else { panic!("expected a color") };
+        assert_eq!(color[0], 1.0);
+        assert_eq!(color[3], 1.0);
+
+        let Item::Link(link) = &page.items[1] else { panic!("expected a link") };
+        assert_eq!(link.target, LinkTarget::Url { url: "https://typst.app".into() });
+        assert_eq!((link.width, link.height), (10.0, 10.0));
+    }
+
+    #[test]
+    fn test_scene_serde_roundtrip() {
+        let scene = scene(&document());
+        let json = serde_json::to_string(&scene).unwrap();
+        assert!(json.contains(r#""type":"group""#));
+        assert!(json.contains(r#""type":"close""#));
+        assert_eq!(serde_json::from_str::<Scene>(&json).unwrap(), scene);
+    }
+}
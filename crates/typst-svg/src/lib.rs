@@ -10,15 +10,16 @@ use ecow::{eco_format, EcoString};
 use ttf_parser::{GlyphId, OutlineBuilder};
 use typst::foundations::Repr;
 use typst::layout::{
-    Abs, Angle, Axes, Frame, FrameItem, FrameKind, GroupItem, Point, Quadrant, Ratio,
-    Size, Transform,
+    Abs, Angle, Axes, Frame, FrameItem, FrameKind, GroupItem, Page, PageRanges, Point,
+    Quadrant, Ratio, Size, Transform,
 };
 use typst::model::Document;
 use typst::text::{Font, TextItem};
 use typst::util::hash128;
 use typst::visualize::{
-    Color, FixedStroke, Geometry, Gradient, Image, ImageFormat, LineCap, LineJoin, Paint,
-    Path, PathItem, Pattern, RasterFormat, RatioOrAngle, RelativeTo, Shape, VectorFormat,
+    BlendMode, Color, FixedStroke, Geometry, Gradient, Image, ImageFormat, LineCap,
+    LineJoin, Paint, Path, PathItem, Pattern, RasterFormat, RatioOrAngle, RelativeTo,
+    Shape, VectorFormat, WhitePoint,
 };
 use xmlwriter::XmlWriter;
 
@@ -42,25 +43,41 @@ pub fn svg(frame: &Frame) -> String {
 ///
 /// The padding will be added around and between the individual frames.
 pub fn svg_merged(document: &Document, padding: Abs) -> String {
+    merge_pages(document.pages.iter().collect(), padding)
+}
+
+/// Export a selection of a document's pages into a single SVG file.
+///
+/// This works like [`svg_merged`], but only exports the pages selected by the
+/// `page_ranges`.
+pub fn svg_merged_pages(
+    document: &Document,
+    padding: Abs,
+    page_ranges: &PageRanges,
+) -> String {
+    let pages = document
+        .pages
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| page_ranges.includes_page_index(i))
+        .map(|(_, page)| page)
+        .collect();
+    merge_pages(pages, padding)
+}
+
+/// Export the given pages into a single SVG file, with the padding around and
+/// between them.
+fn merge_pages(pages: Vec<&Page>, padding: Abs) -> String {
     let width = 2.0 * padding
-        + document
-            .pages
-            .iter()
-            .map(|page| page.frame.width())
-            .max()
-            .unwrap_or_default();
-    let height = padding
-        + document
-            .pages
-            .iter()
-            .map(|page| page.frame.height() + padding)
-            .sum::<Abs>();
+        + pages.iter().map(|page| page.frame.width()).max().unwrap_or_default();
+    let height =
+        padding + pages.iter().map(|page| page.frame.height() + padding).sum::<Abs>();
 
     let mut renderer = SVGRenderer::new();
     renderer.write_header(Size::new(width, height));
 
     let [x, mut y] = [padding; 2];
-    for page in &document.pages {
+    for page in pages {
         let ts = Transform::translate(x, y);
         let state = State::new(page.frame.size(), Transform::identity());
         renderer.render_frame(state, ts, &page.frame);
@@ -81,6 +98,10 @@ struct SVGRenderer {
     /// attribute of the group. The clip path is in the format of `M x y L x y C
     /// x1 y1 x2 y2 x y Z`.
     clip_paths: Deduplicator<EcoString>,
+    /// Blur filters are used to blur a group. The filter region is given in
+    /// the group's coordinate system and covers the group's frame, enlarged
+    /// by three times the blur's standard deviation.
+    blurs: Deduplicator<(Abs, Size)>,
     /// Deduplicated gradients with transform matrices. They use a reference
     /// (`href`) to a "source" gradient instead of being defined inline.
     /// This saves a lot of space since gradients are often reused but with
@@ -117,11 +138,13 @@ struct State {
     transform: Transform,
     /// The size of the first hard frame in the hierarchy.
     size: Size,
+    /// How to blend the current item with what is behind it.
+    blend_mode: BlendMode,
 }
 
 impl State {
     fn new(size: Size, transform: Transform) -> Self {
-        Self { size, transform }
+        Self { size, transform, blend_mode: BlendMode::Normal }
     }
 
     /// Pre translate the current item's transform.
@@ -146,6 +169,11 @@ impl State {
     fn with_transform(self, transform: Transform) -> Self {
         Self { transform, ..self }
     }
+
+    /// Sets the blend mode of the current item.
+    fn with_blend_mode(self, blend_mode: BlendMode) -> Self {
+        Self { blend_mode, ..self }
+    }
 }
 
 /// A reference to a deduplicated gradient, with a transform matrix.
@@ -232,6 +260,7 @@ impl SVGRenderer {
             xml: XmlWriter::new(xmlwriter::Options::default()),
             glyphs: Deduplicator::new('g'),
             clip_paths: Deduplicator::new('c'),
+            blurs: Deduplicator::new('b'),
             gradient_refs: Deduplicator::new('g'),
             gradients: Deduplicator::new('f'),
             conic_subgradients: Deduplicator::new('s'),
@@ -292,6 +321,17 @@ impl SVGRenderer {
             self.xml
                 .write_attribute_fmt("transform", format_args!("translate({x} {y})"));
 
+            // Within non-isolated blended groups, each item is blended
+            // individually.
+            if state.blend_mode != BlendMode::Normal
+                && !matches!(item, FrameItem::Group(_))
+            {
+                self.xml.write_attribute_fmt(
+                    "style",
+                    format_args!("mix-blend-mode: {}", css_blend_mode(state.blend_mode)),
+                );
+            }
+
             match item {
                 FrameItem::Group(group) => {
                     self.render_group(state.pre_translate(*pos), group)
@@ -331,6 +371,29 @@ impl SVGRenderer {
             self.xml.write_attribute_fmt("clip-path", format_args!("url(#{id})"));
         }
 
+        if group.blur > Abs::zero() {
+            let blur = (group.blur, group.frame.size());
+            let id = self.blurs.insert_with(hash128(&blur), || blur);
+            self.xml.write_attribute_fmt("filter", format_args!("url(#{id})"));
+        }
+
+        if group.opacity != Ratio::one() {
+            self.xml.write_attribute("opacity", &group.opacity.get());
+        }
+
+        let state = if group.isolated {
+            self.xml.write_attribute_fmt(
+                "style",
+                format_args!(
+                    "isolation: isolate; mix-blend-mode: {}",
+                    css_blend_mode(group.blend_mode)
+                ),
+            );
+            state.with_blend_mode(BlendMode::Normal)
+        } else {
+            state.with_blend_mode(group.blend_mode)
+        };
+
         self.render_frame(state, group.transform, &group.frame);
         self.xml.end_element();
     }
@@ -501,20 +564,22 @@ impl SVGRenderer {
         self.xml.write_attribute("class", "typst-shape");
 
         if let Some(paint) = &shape.fill {
+            let size = shape.geometry.bbox_size();
             self.write_fill(
                 paint,
-                self.shape_fill_size(state, paint, shape),
-                self.shape_paint_transform(state, paint, shape),
+                self.shape_fill_size(state, paint, size),
+                self.shape_paint_transform(state, paint, size),
             );
         } else {
             self.xml.write_attribute("fill", "none");
         }
 
         if let Some(stroke) = &shape.stroke {
+            let size = shape.geometry.paint_bbox_size(stroke.thickness);
             self.write_stroke(
                 stroke,
-                self.shape_fill_size(state, &stroke.paint, shape),
-                self.shape_paint_transform(state, &stroke.paint, shape),
+                self.shape_fill_size(state, &stroke.paint, size),
+                self.shape_paint_transform(state, &stroke.paint, size),
             );
         }
 
@@ -523,22 +588,15 @@ impl SVGRenderer {
         self.xml.end_element();
     }
 
-    /// Calculate the transform of the shape's fill or stroke.
+    /// Calculate the transform of the shape's fill or stroke, given the size
+    /// of the box the paint is relative to.
     fn shape_paint_transform(
         &self,
         state: State,
         paint: &Paint,
-        shape: &Shape,
+        shape_size: Size,
     ) -> Transform {
-        let mut shape_size = shape.geometry.bbox_size();
-        // Edge cases for strokes.
-        if shape_size.x.to_pt() == 0.0 {
-            shape_size.x = Abs::pt(1.0);
-        }
-
-        if shape_size.y.to_pt() == 0.0 {
-            shape_size.y = Abs::pt(1.0);
-        }
+        let shape_size = nonempty_size(shape_size);
 
         if let Paint::Gradient(gradient) = paint {
             match gradient.unwrap_relative(false) {
@@ -562,17 +620,10 @@ impl SVGRenderer {
         }
     }
 
-    /// Calculate the size of the shape's fill.
-    fn shape_fill_size(&self, state: State, paint: &Paint, shape: &Shape) -> Size {
-        let mut shape_size = shape.geometry.bbox_size();
-        // Edge cases for strokes.
-        if shape_size.x.to_pt() == 0.0 {
-            shape_size.x = Abs::pt(1.0);
-        }
-
-        if shape_size.y.to_pt() == 0.0 {
-            shape_size.y = Abs::pt(1.0);
-        }
+    /// Calculate the size of the shape's fill or stroke, given the size of the
+    /// box the paint is relative to.
+    fn shape_fill_size(&self, state: State, paint: &Paint, shape_size: Size) -> Size {
+        let shape_size = nonempty_size(shape_size);
 
         if let Paint::Gradient(gradient) = paint {
             match gradient.unwrap_relative(false) {
@@ -607,6 +658,25 @@ impl SVGRenderer {
     /// matrix, the returned ID will be the ID of the "source" gradient,
     /// this is a file size optimization.
     fn push_gradient(&mut self, gradient: &Gradient, size: Size, ts: Transform) -> Id {
+        let transform = gradient.transformation();
+        let ts = if transform.is_identity() {
+            ts
+        } else {
+            // The gradient is drawn in the unit square, so the transform,
+            // which operates on the gradient's box, is conjugated with the
+            // box's scaling.
+            let size = nonempty_size(size);
+            ts.pre_concat(Transform::scale(
+                Ratio::new(1.0 / size.x.to_pt()),
+                Ratio::new(1.0 / size.y.to_pt()),
+            ))
+            .pre_concat(transform.in_size(size))
+            .pre_concat(Transform::scale(
+                Ratio::new(size.x.to_pt()),
+                Ratio::new(size.y.to_pt()),
+            ))
+        };
+
         let gradient_id = self
             .gradients
             .insert_with(hash128(&(gradient, size.aspect_ratio())), || {
@@ -715,6 +785,7 @@ impl SVGRenderer {
     fn finalize(mut self) -> String {
         self.write_glyph_defs();
         self.write_clip_path_defs();
+        self.write_blur_defs();
         self.write_gradients();
         self.write_gradient_refs();
         self.write_subgradients();
@@ -783,6 +854,33 @@ impl SVGRenderer {
         self.xml.end_element();
     }
 
+    /// Write the blur filter definitions to the SVG file.
+    fn write_blur_defs(&mut self) {
+        if self.blurs.is_empty() {
+            return;
+        }
+
+        self.xml.start_element("defs");
+        self.xml.write_attribute("id", "blur");
+
+        for (id, (radius, size)) in self.blurs.iter() {
+            let margin = 3.0 * radius.to_pt();
+            self.xml.start_element("filter");
+            self.xml.write_attribute("id", &id);
+            self.xml.write_attribute("filterUnits", "userSpaceOnUse");
+            self.xml.write_attribute("x", &-margin);
+            self.xml.write_attribute("y", &-margin);
+            self.xml.write_attribute("width", &(size.x.to_pt() + 2.0 * margin));
+            self.xml.write_attribute("height", &(size.y.to_pt() + 2.0 * margin));
+            self.xml.start_element("feGaussianBlur");
+            self.xml.write_attribute("stdDeviation", &radius.to_pt());
+            self.xml.end_element();
+            self.xml.end_element();
+        }
+
+        self.xml.end_element();
+    }
+
     /// Write the raw gradients (without transform) to the SVG file.
     fn write_gradients(&mut self) {
         if self.gradients.is_empty() {
@@ -1186,6 +1284,12 @@ fn convert_svg_glyph_to_base64_url(font: &Font, id: GlyphId) -> Option<EcoString
     Some(url)
 }
 
+/// Replace empty dimensions of a paint's box with one point, so that paints
+/// relative to it don't degenerate.
+fn nonempty_size(size: Size) -> Size {
+    size.map(|v| if v.to_pt() == 0.0 { Abs::pt(1.0) } else { v })
+}
+
 /// Convert a geometry to an SVG path.
 #[comemo::memoize]
 fn convert_geometry_to_path(geometry: &Geometry) -> EcoString {
@@ -1205,6 +1309,28 @@ fn convert_geometry_to_path(geometry: &Geometry) -> EcoString {
     builder.0
 }
 
+/// The CSS name of a blend mode.
+fn css_blend_mode(mode: BlendMode) -> &'static str {
+    match mode {
+        BlendMode::Normal => "normal",
+        BlendMode::Multiply => "multiply",
+        BlendMode::Screen => "screen",
+        BlendMode::Overlay => "overlay",
+        BlendMode::Darken => "darken",
+        BlendMode::Lighten => "lighten",
+        BlendMode::ColorDodge => "color-dodge",
+        BlendMode::ColorBurn => "color-burn",
+        BlendMode::HardLight => "hard-light",
+        BlendMode::SoftLight => "soft-light",
+        BlendMode::Difference => "difference",
+        BlendMode::Exclusion => "exclusion",
+        BlendMode::Hue => "hue",
+        BlendMode::Saturation => "saturation",
+        BlendMode::Color => "color",
+        BlendMode::Luminosity => "luminosity",
+    }
+}
+
 fn convert_path(path: &Path) -> EcoString {
     let mut builder = SvgPathBuilder::default();
     for item in &path.0 {
@@ -1432,7 +1558,11 @@ impl ColorEncode for Color {
             c @ Color::Rgb(_)
             | c @ Color::Luma(_)
             | c @ Color::Cmyk(_)
-            | c @ Color::Hsv(_) => c.to_hex(),
+            | c @ Color::Icc(_)
+            | c @ Color::Hsv(_)
+            | c @ Color::Hwb(_)
+            | c @ Color::Okhsl(_)
+            | c @ Color::Okhsv(_) => c.to_hex(),
             Color::LinearRgb(rgb) => {
                 if rgb.alpha != 1.0 {
                     eco_format!(
@@ -1451,6 +1581,24 @@ impl ColorEncode for Color {
                     )
                 }
             }
+            Color::P3(p3) => {
+                if p3.alpha != 1.0 {
+                    eco_format!(
+                        "color(display-p3 {:.5} {:.5} {:.5} / {:.5})",
+                        p3.red,
+                        p3.green,
+                        p3.blue,
+                        p3.alpha
+                    )
+                } else {
+                    eco_format!(
+                        "color(display-p3 {:.5} {:.5} {:.5})",
+                        p3.red,
+                        p3.green,
+                        p3.blue,
+                    )
+                }
+            }
             Color::Oklab(oklab) => {
                 if oklab.alpha != 1.0 {
                     eco_format!(
@@ -1487,6 +1635,54 @@ impl ColorEncode for Color {
                     )
                 }
             }
+            Color::Lab(lab) => {
+                if lab.alpha != 1.0 {
+                    eco_format!(
+                        "lab({:.3}% {:.5} {:.5} / {:.5})",
+                        lab.l,
+                        lab.a,
+                        lab.b,
+                        lab.alpha
+                    )
+                } else {
+                    eco_format!("lab({:.3}% {:.5} {:.5})", lab.l, lab.a, lab.b)
+                }
+            }
+            Color::Lch(lch) => {
+                if lch.alpha != 1.0 {
+                    eco_format!(
+                        "lch({:.3}% {:.5} {:.3}deg / {:.3})",
+                        lch.l,
+                        lch.chroma,
+                        lch.hue.into_degrees(),
+                        lch.alpha
+                    )
+                } else {
+                    eco_format!(
+                        "lch({:.3}% {:.5} {:.3}deg)",
+                        lch.l,
+                        lch.chroma,
+                        lch.hue.into_degrees(),
+                    )
+                }
+            }
+            Color::Xyz(xyz) => {
+                let space = match xyz.white {
+                    WhitePoint::D50 => "xyz-d50",
+                    WhitePoint::D65 => "xyz-d65",
+                };
+                if xyz.alpha != 1.0 {
+                    eco_format!(
+                        "color({space} {:.5} {:.5} {:.5} / {:.5})",
+                        xyz.x,
+                        xyz.y,
+                        xyz.z,
+                        xyz.alpha
+                    )
+                } else {
+                    eco_format!("color({space} {:.5} {:.5} {:.5})", xyz.x, xyz.y, xyz.z)
+                }
+            }
             Color::Hsl(hsl) => {
                 if hsl.alpha != 1.0 {
                     eco_format!(
@@ -1513,3 +1709,43 @@ impl ColorEncode for Color {
 fn correct_pattern_pos(x: f32) -> f32 {
     (x + 0.5) / 2.0
 }
+
+#[cfg(test)]
+mod tests {
+    use typst::layout::Page;
+
+    use super::*;
+
+    /// A document with three empty pages of 10pt, 20pt, and 30pt squares.
+    fn document() -> Document {
+        let pages = [10.0, 20.0, 30.0]
+            .into_iter()
+            .enumerate()
+            .map(|(i, size)| Page {
+                frame: Frame::hard(Size::splat(Abs::pt(size))),
+                numbering: None,
+                number: i + 1,
+                repeated: false,
+            })
+            .collect();
+        Document { pages, ..Default::default() }
+    }
+
+    #[test]
+    fn test_svg_merged_page_ranges() {
+        #[track_caller]
+        fn test(ranges: Option<&str>, width: f64, height: f64) {
+            let ranges = ranges.map(|ranges| ranges.parse::<PageRanges>().unwrap());
+            let svg = match ranges {
+                Some(ranges) => svg_merged_pages(&document(), Abs::pt(1.0), &ranges),
+                None => svg_merged(&document(), Abs::pt(1.0)),
+            };
+            assert!(svg.contains(&format!(r#"viewBox="0 0 {width} {height}""#)));
+        }
+
+        test(None, 32.0, 64.0);
+        test(Some("2-3"), 32.0, 53.0);
+        test(Some("1"), 12.0, 12.0);
+        test(Some("even"), 22.0, 22.0);
+    }
+}
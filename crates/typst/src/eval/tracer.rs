@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use comemo::TrackedMut;
 use ecow::EcoVec;
 
 use crate::diag::SourceDiagnostic;
@@ -46,6 +47,17 @@ impl Tracer {
     pub fn values(self) -> EcoVec<(Value, Option<Styles>)> {
         self.values
     }
+
+    /// Forward everything this tracer collected into another tracer.
+    pub fn merge_into(self, mut other: TrackedMut<Tracer>) {
+        other.delay(self.delayed);
+        for warning in self.warnings {
+            other.warn(warning);
+        }
+        for (value, styles) in self.values {
+            other.value(value, styles);
+        }
+    }
 }
 
 #[comemo::track]
@@ -73,6 +85,11 @@ impl Tracer {
         }
     }
 
+    /// The inspected span, regardless of its source file.
+    pub fn inspected_any(&self) -> Option<Span> {
+        self.inspected
+    }
+
     /// Trace a value for the span.
     pub fn value(&mut self, value: Value, styles: Option<Styles>) {
         if self.values.len() < Self::MAX_VALUES {
@@ -0,0 +1,396 @@
+use std::hash::{Hash, Hasher};
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, StrResult};
+use crate::foundations::{cast, func, scope, ty, Array, Bytes, Cast, Repr, Str, Value};
+
+/// A view into the individual bits of [bytes]($bytes).
+///
+/// Binary file formats often pack data more tightly than whole bytes: Sensor
+/// dumps store readings in 12 bits, network headers pack several flags into a
+/// single byte. A bits view lets you slice such data at bit granularity and
+/// decode integers from it.
+///
+/// You can get a view with the [`bits`]($bytes.bits) method of bytes or create
+/// one from a string of zeros and ones. Bits are numbered from the most
+/// significant bit of the first byte, which matches how binary formats are
+/// usually documented.
+///
+/// ```example
+/// #let header = bytes((0x45, 0x00, 0x01, 0x2c)).bits()
+/// #let (version, ihl) = header.fields(4, 4)
+/// Version #version, header length
+/// #(ihl * 4) bytes, total length
+/// #header.slice(16).int() bytes.
+/// ```
+#[ty(scope)]
+#[derive(Debug, Clone)]
+pub struct Bits {
+    /// The underlying bytes.
+    bytes: Bytes,
+    /// The index of the first bit in the view.
+    start: usize,
+    /// The number of bits in the view.
+    len: usize,
+}
+
+impl Bits {
+    /// Create a view of all bits of the given bytes.
+    pub fn new(bytes: Bytes) -> Self {
+        let len = bytes.len() * 8;
+        Self { bytes, start: 0, len }
+    }
+
+    /// Return `true` if the length is 0.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The bit at the given index, which must be in bounds.
+    fn get(&self, index: usize) -> bool {
+        let i = self.start + index;
+        self.bytes[i / 8] & (0x80 >> (i % 8)) != 0
+    }
+
+    /// Iterate over the bits.
+    fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(|i| self.get(i))
+    }
+
+    /// A subview of the bits, which must be in bounds.
+    fn sub(&self, start: usize, len: usize) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            start: self.start + start,
+            len,
+        }
+    }
+
+    /// Decode the bits in the given range as a big-endian integer.
+    fn decode(&self, start: usize, len: usize, signed: bool) -> StrResult<i64> {
+        if len > 64 {
+            bail!("cannot decode more than 64 bits into an integer");
+        }
+
+        let mut v = 0u64;
+        for i in start..start + len {
+            v = (v << 1) | u64::from(self.get(i));
+        }
+
+        if signed && len > 0 {
+            // Sign-extend from the highest bit of the field.
+            let shift = 64 - len as u32;
+            Ok(((v << shift) as i64) >> shift)
+        } else {
+            i64::try_from(v).map_err(|_| "integer is too large".into())
+        }
+    }
+
+    /// Decode the bits in the given range as an integer with the given byte
+    /// order.
+    fn decode_with(
+        &self,
+        start: usize,
+        len: usize,
+        signed: bool,
+        endian: Endianness,
+    ) -> StrResult<i64> {
+        match endian {
+            Endianness::Big => self.decode(start, len, signed),
+            Endianness::Little => {
+                if len % 8 != 0 {
+                    bail!("little-endian decoding requires a whole number of bytes");
+                }
+                let mut swapped = Vec::with_capacity(len / 8);
+                for k in (0..len / 8).rev() {
+                    swapped.push(self.decode(start + k * 8, 8, false)? as u8);
+                }
+                Bits::new(swapped.into()).decode(0, len, signed)
+            }
+        }
+    }
+
+    /// Resolve an index or throw an out of bounds error.
+    fn locate(&self, index: i64) -> StrResult<usize> {
+        self.locate_opt(index).ok_or_else(|| out_of_bounds(index, self.len))
+    }
+
+    /// Resolve an index, if it is within bounds.
+    ///
+    /// `index == len` is considered in bounds.
+    fn locate_opt(&self, index: i64) -> Option<usize> {
+        let wrapped =
+            if index >= 0 { Some(index) } else { (self.len as i64).checked_add(index) };
+
+        wrapped
+            .and_then(|v| usize::try_from(v).ok())
+            .filter(|&v| v <= self.len)
+    }
+}
+
+#[scope]
+impl Bits {
+    /// Creates a bits view from bytes or from a string of zeros and ones.
+    ///
+    /// In strings, spaces and underscores can be used to group the bits and
+    /// are ignored.
+    ///
+    /// ```example
+    /// #bits("1010 0001").int() \
+    /// #bits(bytes((255, 0))).slice(4, 12)
+    /// ```
+    #[func(constructor)]
+    pub fn construct(
+        /// The bytes or the string of bits.
+        value: ToBits,
+    ) -> Bits {
+        value.0
+    }
+
+    /// The number of bits.
+    #[func(title = "Length")]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the bit at the specified index as a boolean. Returns the default
+    /// value if the index is out of bounds or fails with an error if no
+    /// default value was specified.
+    #[func]
+    pub fn at(
+        &self,
+        /// The index at which to retrieve the bit.
+        index: i64,
+        /// A default value to return if the index is out of bounds.
+        #[named]
+        default: Option<Value>,
+    ) -> StrResult<Value> {
+        self.locate_opt(index)
+            .filter(|&i| i < self.len)
+            .map(|i| Value::Bool(self.get(i)))
+            .or(default)
+            .ok_or_else(|| out_of_bounds_no_default(index, self.len))
+    }
+
+    /// Extracts a subslice of the bits. Fails with an error if the start or
+    /// index is out of bounds.
+    #[func]
+    pub fn slice(
+        &self,
+        /// The start index (inclusive).
+        start: i64,
+        /// The end index (exclusive). If omitted, the whole slice until the end
+        /// is extracted.
+        #[default]
+        end: Option<i64>,
+        /// The number of bits to extract. This is equivalent to passing
+        /// `start + count` as the `end` position. Mutually exclusive with
+        /// `end`.
+        #[named]
+        count: Option<i64>,
+    ) -> StrResult<Bits> {
+        let mut end = end;
+        if end.is_none() {
+            end = count.map(|c: i64| start + c);
+        }
+        let start = self.locate(start)?;
+        let end = self.locate(end.unwrap_or(self.len as i64))?.max(start);
+        Ok(self.sub(start, end - start))
+    }
+
+    /// Decodes all bits as a single integer of at most 64 bits.
+    ///
+    /// ```example
+    /// #let data = bytes((0x01, 0x80))
+    /// #data.bits().int() \
+    /// #data.bits().int(endian: "little") \
+    /// #bits("1111").int(signed: true)
+    /// ```
+    #[func]
+    pub fn int(
+        &self,
+        /// Whether to interpret the bits as a two's complement signed integer.
+        #[named]
+        #[default(false)]
+        signed: bool,
+        /// The byte order. Little-endian decoding requires the number of bits
+        /// to be a multiple of eight.
+        #[named]
+        #[default(Endianness::Big)]
+        endian: Endianness,
+    ) -> StrResult<i64> {
+        self.decode_with(0, self.len, signed, endian)
+    }
+
+    /// Splits the bits into consecutive integers of the same width.
+    ///
+    /// This is useful for arrays of readings or samples. The number of bits
+    /// must be a multiple of the width.
+    ///
+    /// ```example
+    /// #let data = bytes((0x10, 0x00, 0xff, 0xff))
+    /// #data.bits().ints(16, endian: "little") \
+    /// #data.bits().ints(16, signed: true)
+    /// ```
+    #[func]
+    pub fn ints(
+        &self,
+        /// The width of each integer in bits.
+        width: usize,
+        /// Whether to interpret the integers as two's complement signed
+        /// integers.
+        #[named]
+        #[default(false)]
+        signed: bool,
+        /// The byte order of each integer.
+        #[named]
+        #[default(Endianness::Big)]
+        endian: Endianness,
+    ) -> StrResult<Array> {
+        if width == 0 {
+            bail!("width must be positive");
+        }
+        if self.len % width != 0 {
+            bail!(
+                "number of bits ({}) is not a multiple of the width ({width})",
+                self.len
+            );
+        }
+        (0..self.len / width)
+            .map(|i| self.decode_with(i * width, width, signed, endian).map(Value::Int))
+            .collect()
+    }
+
+    /// Extracts consecutive bitfields of the given widths, starting at the
+    /// first bit.
+    ///
+    /// Each field is decoded as a big-endian integer. Bits after the last
+    /// field are ignored.
+    ///
+    /// ```example
+    /// #let flags = bits("101 00110 1")
+    /// #flags.fields(3, 5, 1)
+    /// ```
+    #[func]
+    pub fn fields(
+        &self,
+        /// The widths of the fields in bits.
+        #[variadic]
+        widths: Vec<usize>,
+        /// Whether to interpret the fields as two's complement signed integers.
+        #[named]
+        #[default(false)]
+        signed: bool,
+    ) -> StrResult<Array> {
+        let total: usize = widths.iter().sum();
+        if total > self.len {
+            bail!("fields are wider than the bits ({total} > {})", self.len);
+        }
+
+        let mut start = 0;
+        let mut fields = Array::with_capacity(widths.len());
+        for width in widths {
+            fields.push(Value::Int(self.decode(start, width, signed)?));
+            start += width;
+        }
+        Ok(fields)
+    }
+
+    /// Converts the bits back to bytes.
+    ///
+    /// If the number of bits isn't a multiple of eight, the last byte is
+    /// padded with zeros at the end.
+    #[func]
+    pub fn bytes(&self) -> Bytes {
+        if self.start == 0 && self.len == self.bytes.len() * 8 {
+            return self.bytes.clone();
+        }
+
+        let mut out = vec![0u8; self.len.div_ceil(8)];
+        for (i, bit) in self.iter().enumerate() {
+            if bit {
+                out[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+        out.into()
+    }
+}
+
+impl Repr for Bits {
+    fn repr(&self) -> EcoString {
+        let mut digits = EcoString::new();
+        for (i, bit) in self.iter().enumerate() {
+            if i > 0 && i % 8 == 0 {
+                digits.push(' ');
+            }
+            digits.push(if bit { '1' } else { '0' });
+        }
+        eco_format!("bits({})", digits.repr())
+    }
+}
+
+impl PartialEq for Bits {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for Bits {}
+
+impl Hash for Bits {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for bit in self.iter() {
+            bit.hash(state);
+        }
+    }
+}
+
+/// The byte order of a multi-byte integer.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Endianness {
+    /// The most significant byte comes first.
+    Big,
+    /// The least significant byte comes first.
+    Little,
+}
+
+/// A value that can be cast to bits.
+pub struct ToBits(Bits);
+
+cast! {
+    ToBits,
+    v: Str => {
+        let mut bytes = vec![];
+        let mut len = 0;
+        for c in v.chars().filter(|&c| c != ' ' && c != '_') {
+            if len % 8 == 0 {
+                bytes.push(0);
+            }
+            match c {
+                '0' => {}
+                '1' => *bytes.last_mut().unwrap() |= 0x80 >> (len % 8),
+                _ => bail!("bits must only contain zeros and ones"),
+            }
+            len += 1;
+        }
+        Self(Bits { bytes: bytes.into(), start: 0, len })
+    },
+    v: Bytes => Self(Bits::new(v)),
+}
+
+/// The out of bounds access error message.
+#[cold]
+fn out_of_bounds(index: i64, len: usize) -> EcoString {
+    eco_format!("bit index out of bounds (index: {index}, len: {len})")
+}
+
+/// The out of bounds access error message when no default value was given.
+#[cold]
+fn out_of_bounds_no_default(index: i64, len: usize) -> EcoString {
+    eco_format!(
+        "bit index out of bounds (index: {index}, len: {len}) \
+         and no default value was specified",
+    )
+}
@@ -7,7 +7,7 @@ use ecow::{eco_format, EcoString};
 use serde::{Serialize, Serializer};
 
 use crate::diag::{bail, StrResult};
-use crate::foundations::{cast, func, scope, ty, Array, Reflect, Repr, Str, Value};
+use crate::foundations::{cast, func, scope, ty, Array, Bits, Reflect, Repr, Str, Value};
 use crate::util::LazyHash;
 
 /// A sequence of bytes.
@@ -152,6 +152,16 @@ impl Bytes {
         let end = self.locate(end.unwrap_or(self.len() as i64))?.max(start);
         Ok(self.0[start..end].into())
     }
+
+    /// Returns a view into the individual bits of the bytes.
+    ///
+    /// ```example
+    /// #bytes((0b1010_0001,)).bits()
+    /// ```
+    #[func]
+    pub fn bits(&self) -> Bits {
+        Bits::new(self.clone())
+    }
 }
 
 impl Debug for Bytes {
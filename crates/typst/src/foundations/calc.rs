@@ -6,7 +6,9 @@ use std::ops::{Div, Rem};
 
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::eval::ops;
-use crate::foundations::{cast, func, IntoValue, Module, Scope, Value};
+use crate::foundations::{
+    cast, func, Decimal, IntoValue, Module, RoundingMode, Scope, Value,
+};
 use crate::layout::{Angle, Fr, Length, Ratio};
 use crate::syntax::{Span, Spanned};
 
@@ -79,6 +81,7 @@ cast! {
     ToAbs,
     v: i64 => Self(v.abs().into_value()),
     v: f64 => Self(v.abs().into_value()),
+    v: Decimal => Self(Value::Decimal(v.checked_abs().ok_or_else(too_large)?)),
     v: Length => Self(Value::Length(v.try_abs()
         .ok_or("cannot take absolute value of this length")?)),
     v: Angle => Self(Value::Angle(v.abs())),
@@ -117,6 +120,16 @@ pub fn pow(
         (Num::Int(a), Num::Int(b)) if b >= 0 => {
             a.checked_pow(b as u32).map(Num::Int).ok_or_else(too_large).at(span)?
         }
+        (Num::Decimal(a), Num::Int(b)) => {
+            if a.is_zero() && b < 0 {
+                bail!(span, "cannot divide by zero");
+            }
+            a.checked_powi(b).map(Num::Decimal).ok_or_else(too_large).at(span)?
+        }
+        (Num::Decimal(_), _) => {
+            bail!(exponent.span, "decimals can only be raised to integer powers")
+        }
+        (_, Num::Decimal(_)) => bail!(exponent.span, "exponent may not be a decimal"),
         (a, b) => Num::Float(if a.float() == std::f64::consts::E {
             b.float().exp()
         } else if a.float() == 2.0 {
@@ -608,11 +621,12 @@ pub fn lcm(
 pub fn floor(
     /// The number to round down.
     value: Num,
-) -> i64 {
-    match value {
+) -> StrResult<i64> {
+    Ok(match value {
         Num::Int(n) => n,
         Num::Float(n) => n.floor() as i64,
-    }
+        Num::Decimal(n) => n.to_i64(RoundingMode::Floor).ok_or_else(too_large)?,
+    })
 }
 
 /// Rounds a number up to the nearest integer.
@@ -628,11 +642,12 @@ pub fn floor(
 pub fn ceil(
     /// The number to round up.
     value: Num,
-) -> i64 {
-    match value {
+) -> StrResult<i64> {
+    Ok(match value {
         Num::Int(n) => n,
         Num::Float(n) => n.ceil() as i64,
-    }
+        Num::Decimal(n) => n.to_i64(RoundingMode::Ceil).ok_or_else(too_large)?,
+    })
 }
 
 /// Returns the integer part of a number.
@@ -648,11 +663,12 @@ pub fn ceil(
 pub fn trunc(
     /// The number to truncate.
     value: Num,
-) -> i64 {
-    match value {
+) -> StrResult<i64> {
+    Ok(match value {
         Num::Int(n) => n,
         Num::Float(n) => n.trunc() as i64,
-    }
+        Num::Decimal(n) => n.to_i64(RoundingMode::TowardZero).ok_or_else(too_large)?,
+    })
 }
 
 /// Returns the fractional part of a number.
@@ -671,17 +687,21 @@ pub fn fract(
     match value {
         Num::Int(_) => Num::Int(0),
         Num::Float(n) => Num::Float(n.fract()),
+        Num::Decimal(n) => Num::Decimal(n.fract()),
     }
 }
 
 /// Rounds a number to the nearest integer.
 ///
-/// Optionally, a number of decimal places can be specified.
+/// Optionally, a number of decimal places can be specified. Ties are rounded
+/// away from zero. Decimals stay decimals and can also be rounded with other
+/// [rounding modes]($decimal.round).
 ///
 /// ```example
 /// #assert(calc.round(3.14) == 3)
 /// #assert(calc.round(3.5) == 4)
-/// #calc.round(3.1415, digits: 2)
+/// #calc.round(3.1415, digits: 2) \
+/// #calc.round(decimal("2.675"), digits: 2)
 /// ```
 #[func]
 pub fn round(
@@ -691,15 +711,19 @@ pub fn round(
     #[named]
     #[default(0)]
     digits: i64,
-) -> Num {
-    match value {
+) -> StrResult<Num> {
+    Ok(match value {
         Num::Int(n) if digits == 0 => Num::Int(n),
+        Num::Decimal(n) => Num::Decimal(
+            n.round_to(digits, RoundingMode::HalfAwayFromZero)
+                .ok_or_else(too_large)?,
+        ),
         _ => {
             let n = value.float();
             let factor = 10.0_f64.powi(digits as i32);
             Num::Float((n * factor).round() / factor)
         }
-    }
+    })
 }
 
 /// Clamps a number between a minimum and maximum value.
@@ -721,7 +745,9 @@ pub fn clamp(
     if max.v.float() < min.float() {
         bail!(max.span, "max must be greater than or equal to min")
     }
-    Ok(value.apply3(min, max.v, i64::clamp, f64::clamp))
+    value
+        .apply3(min, max.v, i64::clamp, f64::clamp, |a, b, c| Some(a.clamp(b, c)))
+        .at(max.span)
 }
 
 /// Determines the minimum of a sequence of values.
@@ -833,7 +859,9 @@ pub fn rem(
     if divisor.v.float() == 0.0 {
         bail!(divisor.span, "divisor must not be zero");
     }
-    Ok(dividend.apply2(divisor.v, Rem::rem, Rem::rem))
+    dividend
+        .apply2(divisor.v, Rem::rem, Rem::rem, Decimal::checked_rem)
+        .at(divisor.span)
 }
 
 /// Performs euclidean division of two numbers.
@@ -858,7 +886,9 @@ pub fn div_euclid(
     if divisor.v.float() == 0.0 {
         bail!(divisor.span, "divisor must not be zero");
     }
-    Ok(dividend.apply2(divisor.v, i64::div_euclid, f64::div_euclid))
+    dividend
+        .apply2(divisor.v, i64::div_euclid, f64::div_euclid, Decimal::checked_div_euclid)
+        .at(divisor.span)
 }
 
 /// This calculates the least nonnegative remainder of a division.
@@ -884,7 +914,9 @@ pub fn rem_euclid(
     if divisor.v.float() == 0.0 {
         bail!(divisor.span, "divisor must not be zero");
     }
-    Ok(dividend.apply2(divisor.v, i64::rem_euclid, f64::rem_euclid))
+    dividend
+        .apply2(divisor.v, i64::rem_euclid, f64::rem_euclid, Decimal::checked_rem_euclid)
+        .at(divisor.span)
 }
 
 /// Calculates the quotient (floored division) of two numbers.
@@ -905,14 +937,19 @@ pub fn quo(
         bail!(divisor.span, "divisor must not be zero");
     }
 
-    Ok(floor(dividend.apply2(divisor.v, Div::div, Div::div)))
+    let quotient = dividend
+        .apply2(divisor.v, Div::div, Div::div, Decimal::checked_div_floor)
+        .at(divisor.span)?;
+    floor(quotient).at(divisor.span)
 }
 
-/// A value which can be passed to functions that work with integers and floats.
+/// A value which can be passed to functions that work with integers, floats,
+/// and decimals.
 #[derive(Debug, Copy, Clone)]
 pub enum Num {
     Int(i64),
     Float(f64),
+    Decimal(Decimal),
 }
 
 impl Num {
@@ -921,11 +958,18 @@ impl Num {
         other: Self,
         int: impl FnOnce(i64, i64) -> i64,
         float: impl FnOnce(f64, f64) -> f64,
-    ) -> Num {
-        match (self, other) {
+        decimal: impl FnOnce(Decimal, Decimal) -> Option<Decimal>,
+    ) -> StrResult<Num> {
+        check_mix(&[self, other])?;
+        Ok(match (self, other) {
             (Self::Int(a), Self::Int(b)) => Num::Int(int(a, b)),
-            (a, b) => Num::Float(float(a.float(), b.float())),
-        }
+            (Self::Float(_), _) | (_, Self::Float(_)) => {
+                Num::Float(float(self.float(), other.float()))
+            }
+            (a, b) => {
+                Num::Decimal(decimal(a.decimal(), b.decimal()).ok_or_else(too_large)?)
+            }
+        })
     }
 
     fn apply3(
@@ -934,17 +978,35 @@ impl Num {
         third: Self,
         int: impl FnOnce(i64, i64, i64) -> i64,
         float: impl FnOnce(f64, f64, f64) -> f64,
-    ) -> Num {
-        match (self, other, third) {
+        decimal: impl FnOnce(Decimal, Decimal, Decimal) -> Option<Decimal>,
+    ) -> StrResult<Num> {
+        check_mix(&[self, other, third])?;
+        Ok(match (self, other, third) {
             (Self::Int(a), Self::Int(b), Self::Int(c)) => Num::Int(int(a, b, c)),
-            (a, b, c) => Num::Float(float(a.float(), b.float(), c.float())),
-        }
+            (Self::Float(_), _, _) | (_, Self::Float(_), _) | (_, _, Self::Float(_)) => {
+                Num::Float(float(self.float(), other.float(), third.float()))
+            }
+            (a, b, c) => Num::Decimal(
+                decimal(a.decimal(), b.decimal(), c.decimal()).ok_or_else(too_large)?,
+            ),
+        })
     }
 
     fn float(self) -> f64 {
         match self {
             Self::Int(v) => v as f64,
             Self::Float(v) => v,
+            Self::Decimal(v) => v.to_f64(),
+        }
+    }
+
+    /// Converts an integer or decimal to a decimal. Floats are never
+    /// converted, as that would be lossy.
+    fn decimal(self) -> Decimal {
+        match self {
+            Self::Int(v) => v.into(),
+            Self::Decimal(v) => v,
+            Self::Float(_) => unreachable!("floats are not converted to decimals"),
         }
     }
 }
@@ -954,9 +1016,21 @@ cast! {
     self => match self {
         Self::Int(v) => v.into_value(),
         Self::Float(v) => v.into_value(),
+        Self::Decimal(v) => v.into_value(),
     },
     v: i64 => Self::Int(v),
     v: f64 => Self::Float(v),
+    v: Decimal => Self::Decimal(v),
+}
+
+/// Ensures that decimals and floats aren't combined, which would silently
+/// lose the exactness of the decimals.
+fn check_mix(nums: &[Num]) -> StrResult<()> {
+    let has = |f: fn(&Num) -> bool| nums.iter().any(f);
+    if has(|n| matches!(n, Num::Decimal(_))) && has(|n| matches!(n, Num::Float(_))) {
+        bail!("cannot combine decimals with floats");
+    }
+    Ok(())
 }
 
 /// A value that can be passed to a trigonometric function.
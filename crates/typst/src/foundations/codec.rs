@@ -0,0 +1,1089 @@
+//! A binary encoding of content.
+//!
+//! Encoded content starts with a magic number and a format version, followed
+//! by the encoded root value. Elements are identified by their path in the
+//! standard library (e.g. `list.item`) and fields by their name, so that
+//! encoded content stays valid across compiler versions with the same format
+//! version as long as the referenced elements and fields exist. Any change to
+//! the encoding must bump the format version.
+//!
+//! Values are stored losslessly: Floating-point numbers with all their bits,
+//! colors with their color space and components, and ICC colors with the raw
+//! data of their profile.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    Arg, Args, Array, Bytes, Content, Datetime, Decimal, Dict, Duration, Element,
+    IntoValue, Label, NativeElement, Scope, SequenceElem, Smart, Style, StyleChain,
+    StyledElem, Styles, Value, Version,
+};
+use crate::layout::{
+    Abs, Alignment, Angle, Axes, Dir, Em, Fr, HAlignment, Length, Ratio, Rel, VAlignment,
+};
+use crate::math::AlignPointElem;
+use crate::syntax::{Span, Spanned};
+use crate::text::SpaceElem;
+use crate::util::Scalar;
+use crate::visualize::{
+    Color, ColorSpace, ConicGradient, Easing, Gradient, GradientSegment,
+    GradientTransform, HueInterpolation, Icc, IccProfile, LinearGradient, RadialGradient,
+    RelativeTo, RenderingIntent, Stroke, WhitePoint,
+};
+use crate::{Library, World};
+
+/// The magic number at the start of encoded content.
+const MAGIC: &[u8; 8] = b"typst-ct";
+
+/// The version of the encoding.
+const FORMAT_VERSION: u8 = 1;
+
+// The tags that identify the kind of an encoded value.
+const NONE: u8 = 0;
+const AUTO: u8 = 1;
+const BOOL: u8 = 2;
+const INT: u8 = 3;
+const FLOAT: u8 = 4;
+const STR: u8 = 5;
+const BYTES: u8 = 6;
+const LABEL: u8 = 7;
+const LENGTH: u8 = 8;
+const ANGLE: u8 = 9;
+const RATIO: u8 = 10;
+const RELATIVE: u8 = 11;
+const FRACTION: u8 = 12;
+const ARRAY: u8 = 13;
+const DICT: u8 = 14;
+const CONTENT: u8 = 15;
+const COLOR: u8 = 16;
+const GRADIENT: u8 = 17;
+const VERSION: u8 = 18;
+const DATETIME: u8 = 19;
+const DURATION: u8 = 20;
+const DECIMAL: u8 = 21;
+const ALIGNMENT: u8 = 22;
+const DIRECTION: u8 = 23;
+const STROKE: u8 = 24;
+
+/// The tag of a color from an ICC profile, in place of its color space.
+const ICC: u8 = 255;
+
+/// The path under which styled content is encoded.
+const STYLED: &str = "styled";
+
+/// Encode content into bytes.
+pub fn encode(library: &Library, content: &Content) -> StrResult<Bytes> {
+    let mut encoder = Encoder { buf: MAGIC.to_vec(), paths: element_paths(library) };
+    encoder.buf.push(FORMAT_VERSION);
+    encoder.content(content)?;
+    Ok(encoder.buf.into())
+}
+
+/// Decode content from bytes.
+pub fn decode(engine: &mut Engine, span: Span, data: &[u8]) -> SourceResult<Content> {
+    let Some(rest) = data.strip_prefix(MAGIC.as_slice()) else {
+        bail!(span, "data is not encoded content");
+    };
+    let Some((&version, rest)) = rest.split_first() else {
+        bail!(span, "data is not encoded content");
+    };
+    if version != FORMAT_VERSION {
+        bail!(span, "unsupported content encoding version {version}");
+    }
+
+    let mut decoder = Decoder { data: rest, engine, span };
+    let content = decoder.content()?;
+    if !decoder.data.is_empty() {
+        bail!(span, "encoded content has trailing data");
+    }
+    Ok(content)
+}
+
+/// Writes encoded values into a buffer.
+struct Encoder {
+    /// The encoded data.
+    buf: Vec<u8>,
+    /// The paths of the elements in the library.
+    paths: Vec<(Element, EcoString)>,
+}
+
+impl Encoder {
+    fn value(&mut self, value: &Value) -> StrResult<()> {
+        match value {
+            Value::None => self.buf.push(NONE),
+            Value::Auto => self.buf.push(AUTO),
+            Value::Bool(v) => {
+                self.buf.push(BOOL);
+                self.buf.push(*v as u8);
+            }
+            Value::Int(v) => {
+                self.buf.push(INT);
+                self.buf.extend(v.to_le_bytes());
+            }
+            Value::Float(v) => {
+                self.buf.push(FLOAT);
+                self.f64(*v);
+            }
+            Value::Str(v) => {
+                self.buf.push(STR);
+                self.str(v);
+            }
+            Value::Bytes(v) => {
+                self.buf.push(BYTES);
+                self.blob(v);
+            }
+            Value::Label(v) => {
+                self.buf.push(LABEL);
+                self.str(v.as_str());
+            }
+            Value::Length(v) => {
+                self.buf.push(LENGTH);
+                self.length(*v);
+            }
+            Value::Angle(v) => {
+                self.buf.push(ANGLE);
+                self.f64(v.to_rad());
+            }
+            Value::Ratio(v) => {
+                self.buf.push(RATIO);
+                self.f64(v.get());
+            }
+            Value::Relative(v) => {
+                self.buf.push(RELATIVE);
+                self.f64(v.rel.get());
+                self.length(v.abs);
+            }
+            Value::Fraction(v) => {
+                self.buf.push(FRACTION);
+                self.f64(v.get());
+            }
+            Value::Array(array) => {
+                self.buf.push(ARRAY);
+                self.len(array.len());
+                for item in array {
+                    self.value(item)?;
+                }
+            }
+            Value::Dict(dict) => {
+                self.buf.push(DICT);
+                self.len(dict.len());
+                for (key, value) in dict {
+                    self.str(key);
+                    self.value(value)?;
+                }
+            }
+            Value::Content(content) => {
+                self.buf.push(CONTENT);
+                self.content(content)?;
+            }
+            Value::Color(v) => {
+                self.buf.push(COLOR);
+                self.color(*v);
+            }
+            Value::Gradient(v) => {
+                self.buf.push(GRADIENT);
+                self.gradient(v)?;
+            }
+            Value::Version(v) => {
+                self.buf.push(VERSION);
+                self.len(v.values().len());
+                for component in v.values() {
+                    self.buf.extend(component.to_le_bytes());
+                }
+            }
+            Value::Datetime(v) => {
+                self.buf.push(DATETIME);
+                self.datetime(*v);
+            }
+            Value::Duration(v) => {
+                self.buf.push(DURATION);
+                let duration = time::Duration::from(*v);
+                self.buf.extend(duration.whole_seconds().to_le_bytes());
+                self.buf.extend(duration.subsec_nanoseconds().to_le_bytes());
+            }
+            Value::Decimal(v) => {
+                self.buf.push(DECIMAL);
+                self.buf.extend(v.mantissa().to_le_bytes());
+                self.buf.push(v.scale() as u8);
+            }
+            Value::Dyn(dynamic) => {
+                if let Some(&alignment) = dynamic.downcast::<Alignment>() {
+                    self.buf.push(ALIGNMENT);
+                    self.alignment(alignment);
+                } else if let Some(&dir) = dynamic.downcast::<Dir>() {
+                    self.buf.push(DIRECTION);
+                    self.buf.push(match dir {
+                        Dir::LTR => 0,
+                        Dir::RTL => 1,
+                        Dir::TTB => 2,
+                        Dir::BTT => 3,
+                    });
+                } else if let Some(stroke) = dynamic.downcast::<Stroke>() {
+                    self.buf.push(STROKE);
+                    self.stroke(stroke)?;
+                } else {
+                    bail!("cannot encode {}", value.ty().long_name());
+                }
+            }
+            _ => bail!("cannot encode {}", value.ty().long_name()),
+        }
+        Ok(())
+    }
+
+    fn content(&mut self, content: &Content) -> StrResult<()> {
+        match content.label() {
+            Some(label) => {
+                self.buf.push(1);
+                self.str(label.as_str());
+            }
+            None => self.buf.push(0),
+        }
+
+        if let Some(styled) = content.to_packed::<StyledElem>() {
+            self.str(STYLED);
+            self.content(&styled.child)?;
+            self.styles(&styled.styles)?;
+            return Ok(());
+        }
+
+        let elem = content.elem();
+        let Some(path) = self.path(elem) else {
+            bail!("cannot encode {} element", elem.name());
+        };
+
+        self.str(&path);
+        let mut fields = content.fields();
+        fields.remove("label".into(), None).ok();
+        self.len(fields.len());
+        for (name, value) in &fields {
+            self.str(name);
+            self.value(value)
+                .map_err(|err| eco_format!("{err} in field `{name}` of {path}"))?;
+        }
+
+        Ok(())
+    }
+
+    fn styles(&mut self, styles: &Styles) -> StrResult<()> {
+        self.len(styles.iter().count());
+        for style in styles.iter() {
+            let Style::Property(property) = style else {
+                bail!("cannot encode content with show rules");
+            };
+
+            let elem = property.elem();
+            let single = Styles::from(Style::Property(property.clone()));
+            let (Some(path), Some(name), Some(value)) = (
+                self.path(elem),
+                elem.field_name(property.id()),
+                elem.field_from_styles(property.id(), StyleChain::new(&single)),
+            ) else {
+                bail!("cannot encode style for {} element", elem.name());
+            };
+
+            self.str(&path);
+            self.str(name);
+            self.value(&value)
+                .map_err(|err| eco_format!("{err} in style `{name}` of {path}"))?;
+        }
+        Ok(())
+    }
+
+    fn path(&self, elem: Element) -> Option<EcoString> {
+        self.paths
+            .iter()
+            .find(|(other, _)| *other == elem)
+            .map(|(_, path)| path.clone())
+    }
+
+    fn color(&mut self, color: Color) {
+        if let Color::Icc(icc) = color {
+            self.buf.push(ICC);
+            self.blob(icc.profile.data());
+            self.buf.push(match icc.profile.intent() {
+                RenderingIntent::Perceptual => 0,
+                RenderingIntent::RelativeColorimetric => 1,
+                RenderingIntent::Saturation => 2,
+                RenderingIntent::AbsoluteColorimetric => 3,
+            });
+            for component in icc.components {
+                self.f32(component);
+            }
+            self.f32(icc.alpha);
+            return;
+        }
+
+        self.space(color.space());
+        for component in color.to_vec4() {
+            self.f32(component);
+        }
+    }
+
+    fn space(&mut self, space: ColorSpace) {
+        self.buf.push(match space {
+            ColorSpace::Oklab => 0,
+            ColorSpace::Oklch => 1,
+            ColorSpace::Lab => 2,
+            ColorSpace::Lch => 3,
+            ColorSpace::Xyz(WhitePoint::D50) => 4,
+            ColorSpace::Xyz(WhitePoint::D65) => 5,
+            ColorSpace::Srgb => 6,
+            ColorSpace::DisplayP3 => 7,
+            ColorSpace::D65Gray => 8,
+            ColorSpace::LinearRgb => 9,
+            ColorSpace::Hsl => 10,
+            ColorSpace::Hsv => 11,
+            ColorSpace::Hwb => 12,
+            ColorSpace::Okhsl => 13,
+            ColorSpace::Okhsv => 14,
+            ColorSpace::Cmyk => 15,
+        });
+    }
+
+    fn gradient(&mut self, gradient: &Gradient) -> StrResult<()> {
+        match gradient {
+            Gradient::Linear(linear) => {
+                self.buf.push(0);
+                self.f64(linear.angle.to_rad());
+            }
+            Gradient::Radial(radial) => {
+                self.buf.push(1);
+                self.ratios(radial.center);
+                self.f64(radial.radius.get());
+                self.ratios(radial.focal_center);
+                self.f64(radial.focal_radius.get());
+            }
+            Gradient::Conic(conic) => {
+                self.buf.push(2);
+                self.f64(conic.angle.to_rad());
+                self.ratios(conic.center);
+            }
+        }
+
+        self.len(gradient.stops_ref().len());
+        for &(color, offset) in gradient.stops_ref() {
+            self.color(color);
+            self.f64(offset.get());
+        }
+
+        self.space(gradient.space());
+        self.buf.push(match gradient.hue() {
+            HueInterpolation::Shorter => 0,
+            HueInterpolation::Longer => 1,
+            HueInterpolation::Increasing => 2,
+            HueInterpolation::Decreasing => 3,
+        });
+        self.easing(&gradient.easing())?;
+
+        let segments = gradient.segments();
+        self.len(segments.len());
+        for segment in segments {
+            match segment.space {
+                Some(space) => {
+                    self.buf.push(1);
+                    self.space(space);
+                }
+                None => self.buf.push(0),
+            }
+            match &segment.easing {
+                Some(easing) => {
+                    self.buf.push(1);
+                    self.easing(easing)?;
+                }
+                None => self.buf.push(0),
+            }
+        }
+
+        self.buf.push(match gradient.relative() {
+            Smart::Auto => 0,
+            Smart::Custom(RelativeTo::Self_) => 1,
+            Smart::Custom(RelativeTo::Parent) => 2,
+        });
+
+        let transform = gradient.transformation();
+        self.f64(transform.rotate.to_rad());
+        self.f64(transform.scale.get());
+        self.ratios(transform.offset);
+        self.buf.push(gradient.anti_alias() as u8);
+        Ok(())
+    }
+
+    fn easing(&mut self, easing: &Easing) -> StrResult<()> {
+        match easing {
+            Easing::Linear => self.buf.push(0),
+            Easing::CubicBezier(points) => {
+                self.buf.push(1);
+                for point in points {
+                    self.f64(point.get());
+                }
+            }
+            Easing::Func(..) => bail!("cannot encode gradient with an easing function"),
+        }
+        Ok(())
+    }
+
+    fn datetime(&mut self, datetime: Datetime) {
+        let (date, time) = match datetime {
+            Datetime::Date(date) => (Some(date), None),
+            Datetime::Time(time) => (None, Some(time)),
+            Datetime::Datetime(datetime) => {
+                (Some(datetime.date()), Some(datetime.time()))
+            }
+        };
+
+        self.buf.push(date.is_some() as u8 | (time.is_some() as u8) << 1);
+        if let Some(date) = date {
+            self.buf.extend(date.year().to_le_bytes());
+            self.buf.push(date.month() as u8);
+            self.buf.push(date.day());
+        }
+        if let Some(time) = time {
+            self.buf.push(time.hour());
+            self.buf.push(time.minute());
+            self.buf.push(time.second());
+            self.buf.extend(time.nanosecond().to_le_bytes());
+        }
+    }
+
+    fn alignment(&mut self, alignment: Alignment) {
+        self.buf.push(match alignment.x() {
+            None => 0,
+            Some(HAlignment::Start) => 1,
+            Some(HAlignment::Left) => 2,
+            Some(HAlignment::Center) => 3,
+            Some(HAlignment::Right) => 4,
+            Some(HAlignment::End) => 5,
+        });
+        self.buf.push(match alignment.y() {
+            None => 0,
+            Some(VAlignment::Top) => 1,
+            Some(VAlignment::Horizon) => 2,
+            Some(VAlignment::Bottom) => 3,
+        });
+    }
+
+    /// Strokes are stored as the values of their parts, which cast back
+    /// into the parts.
+    fn stroke(&mut self, stroke: &Stroke) -> StrResult<()> {
+        self.value(&stroke.paint.clone().into_value())?;
+        self.value(&stroke.thickness.into_value())?;
+        self.value(&stroke.cap.into_value())?;
+        self.value(&stroke.join.into_value())?;
+        self.value(&stroke.dash.clone().into_value())?;
+        self.value(&stroke.miter_limit.map(Scalar::get).into_value())
+    }
+
+    fn ratios(&mut self, ratios: Axes<Ratio>) {
+        self.f64(ratios.x.get());
+        self.f64(ratios.y.get());
+    }
+
+    fn length(&mut self, length: Length) {
+        self.f64(length.abs.to_pt());
+        self.f64(length.em.get());
+    }
+
+    fn f64(&mut self, v: f64) {
+        self.buf.extend(v.to_le_bytes());
+    }
+
+    fn f32(&mut self, v: f32) {
+        self.buf.extend(v.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.buf.extend((len as u32).to_le_bytes());
+    }
+
+    fn str(&mut self, v: &str) {
+        self.blob(v.as_bytes());
+    }
+
+    fn blob(&mut self, v: &[u8]) {
+        self.len(v.len());
+        self.buf.extend_from_slice(v);
+    }
+}
+
+/// Reads encoded values from a buffer.
+struct Decoder<'a, 'b, 'c> {
+    /// The remaining data.
+    data: &'a [u8],
+    /// The engine used to construct elements.
+    engine: &'b mut Engine<'c>,
+    /// The span of the decode call.
+    span: Span,
+}
+
+impl Decoder<'_, '_, '_> {
+    fn value(&mut self) -> SourceResult<Value> {
+        let span = self.span;
+        Ok(match self.u8().at(span)? {
+            NONE => Value::None,
+            AUTO => Value::Auto,
+            BOOL => Value::Bool(self.u8().at(span)? != 0),
+            INT => Value::Int(i64::from_le_bytes(self.array().at(span)?)),
+            FLOAT => Value::Float(self.f64().at(span)?),
+            STR => Value::Str(self.str().at(span)?.into()),
+            BYTES => Value::Bytes(self.blob().at(span)?.into()),
+            LABEL => Value::Label(Label::new(self.str().at(span)?.as_str())),
+            LENGTH => Value::Length(self.length().at(span)?),
+            ANGLE => Value::Angle(Angle::rad(self.f64().at(span)?)),
+            RATIO => Value::Ratio(Ratio::new(self.f64().at(span)?)),
+            RELATIVE => {
+                let rel = Ratio::new(self.f64().at(span)?);
+                let abs = self.length().at(span)?;
+                Value::Relative(Rel::new(rel, abs))
+            }
+            FRACTION => Value::Fraction(Fr::new(self.f64().at(span)?)),
+            ARRAY => {
+                let len = self.len().at(span)?;
+                let mut array = Array::with_capacity(len.min(self.data.len()));
+                for _ in 0..len {
+                    array.push(self.value()?);
+                }
+                Value::Array(array)
+            }
+            DICT => {
+                let len = self.len().at(span)?;
+                let mut dict = Dict::new();
+                for _ in 0..len {
+                    let key = self.str().at(span)?;
+                    dict.insert(key.into(), self.value()?);
+                }
+                Value::Dict(dict)
+            }
+            CONTENT => Value::Content(self.content()?),
+            COLOR => Value::Color(self.color().at(span)?),
+            GRADIENT => Value::Gradient(self.gradient().at(span)?),
+            VERSION => {
+                let len = self.len().at(span)?;
+                let mut components = Vec::with_capacity(len.min(self.data.len()));
+                for _ in 0..len {
+                    components.push(u32::from_le_bytes(self.array().at(span)?));
+                }
+                Value::Version(components.into_iter().collect::<Version>())
+            }
+            DATETIME => Value::Datetime(self.datetime().at(span)?),
+            DURATION => {
+                let seconds = i64::from_le_bytes(self.array().at(span)?);
+                let nanoseconds = i32::from_le_bytes(self.array().at(span)?);
+                let duration = time::Duration::new(seconds, nanoseconds);
+                Value::Duration(Duration::from(duration))
+            }
+            DECIMAL => {
+                let mantissa = i128::from_le_bytes(self.array().at(span)?);
+                let scale = self.u8().at(span)?;
+                Value::Decimal(malformed(Decimal::new(mantissa, scale.into())).at(span)?)
+            }
+            ALIGNMENT => self.alignment().at(span)?.into_value(),
+            DIRECTION => match self.u8().at(span)? {
+                0 => Dir::LTR,
+                1 => Dir::RTL,
+                2 => Dir::TTB,
+                3 => Dir::BTT,
+                _ => bail!(span, "encoded content is malformed"),
+            }
+            .into_value(),
+            STROKE => self.stroke()?.into_value(),
+            _ => bail!(span, "encoded content is malformed"),
+        })
+    }
+
+    fn content(&mut self) -> SourceResult<Content> {
+        let span = self.span;
+        let label = match self.u8().at(span)? {
+            0 => None,
+            _ => Some(Label::new(self.str().at(span)?.as_str())),
+        };
+
+        let path = self.str().at(span)?;
+        let mut content = if path == STYLED {
+            let child = self.content()?;
+            let styles = self.styles()?;
+            child.styled_with_map(styles)
+        } else {
+            let elem = self.elem(&path)?;
+            let mut fields = vec![];
+            for _ in 0..self.len().at(span)? {
+                let name = self.str().at(span)?;
+                fields.push((name, self.value()?));
+            }
+
+            let mut args = fields_to_args(elem, fields, span);
+            let content = elem.construct(self.engine, &mut args)?;
+            args.finish()?;
+            content
+        };
+
+        if let Some(label) = label {
+            content = content.labelled(label);
+        }
+
+        Ok(content)
+    }
+
+    fn styles(&mut self) -> SourceResult<Styles> {
+        let span = self.span;
+        let mut styles = Styles::new();
+        for _ in 0..self.len().at(span)? {
+            let path = self.str().at(span)?;
+            let elem = self.elem(&path)?;
+            let name = self.str().at(span)?;
+            let value = self.value()?;
+            let args = Args {
+                span,
+                items: [named(&name, value, span)].into_iter().collect(),
+            };
+            styles.apply(elem.set(self.engine, args)?);
+        }
+        Ok(styles)
+    }
+
+    fn elem(&self, path: &str) -> SourceResult<Element> {
+        find_element(self.engine.world.library(), path)
+            .ok_or_else(|| eco_format!("unknown element `{path}`"))
+            .at(self.span)
+    }
+
+    fn color(&mut self) -> StrResult<Color> {
+        let tag = self.u8()?;
+        if tag == ICC {
+            let data = Bytes::from(self.blob()?);
+            let intent = match self.u8()? {
+                0 => RenderingIntent::Perceptual,
+                1 => RenderingIntent::RelativeColorimetric,
+                2 => RenderingIntent::Saturation,
+                3 => RenderingIntent::AbsoluteColorimetric,
+                _ => bail!("encoded content is malformed"),
+            };
+            let profile = IccProfile::new(data, intent)?;
+            let components = [self.f32()?, self.f32()?, self.f32()?, self.f32()?];
+            let alpha = self.f32()?;
+            return Ok(Color::Icc(Icc { profile, components, alpha }));
+        }
+
+        let space = space_from_tag(tag)?;
+        let components = [self.f32()?, self.f32()?, self.f32()?, self.f32()?];
+        Ok(Color::from_space_vec4(space, components))
+    }
+
+    fn space(&mut self) -> StrResult<ColorSpace> {
+        let tag = self.u8()?;
+        space_from_tag(tag)
+    }
+
+    fn gradient(&mut self) -> StrResult<Gradient> {
+        let kind = self.u8()?;
+        let (angle, center, radius, focal_center, focal_radius) = match kind {
+            0 => (
+                Angle::rad(self.f64()?),
+                Axes::splat(Ratio::zero()),
+                Ratio::zero(),
+                Axes::splat(Ratio::zero()),
+                Ratio::zero(),
+            ),
+            1 => {
+                let center = self.ratios()?;
+                let radius = Ratio::new(self.f64()?);
+                let focal_center = self.ratios()?;
+                let focal_radius = Ratio::new(self.f64()?);
+                (Angle::zero(), center, radius, focal_center, focal_radius)
+            }
+            2 => {
+                let angle = Angle::rad(self.f64()?);
+                let center = self.ratios()?;
+                (angle, center, Ratio::zero(), Axes::splat(Ratio::zero()), Ratio::zero())
+            }
+            _ => bail!("encoded content is malformed"),
+        };
+
+        let len = self.len()?;
+        let mut stops = Vec::with_capacity(len.min(self.data.len()));
+        for _ in 0..len {
+            let color = self.color()?;
+            stops.push((color, Ratio::new(self.f64()?)));
+        }
+
+        let space = self.space()?;
+        let hue = match self.u8()? {
+            0 => HueInterpolation::Shorter,
+            1 => HueInterpolation::Longer,
+            2 => HueInterpolation::Increasing,
+            3 => HueInterpolation::Decreasing,
+            _ => bail!("encoded content is malformed"),
+        };
+        let easing = self.easing()?;
+
+        let len = self.len()?;
+        let mut segments = Vec::with_capacity(len.min(self.data.len()));
+        for _ in 0..len {
+            let space = match self.u8()? {
+                0 => None,
+                _ => Some(self.space()?),
+            };
+            let easing = match self.u8()? {
+                0 => None,
+                _ => Some(self.easing()?),
+            };
+            segments.push(GradientSegment { space, easing });
+        }
+
+        // Gradients always have at least two stops and either no segments or
+        // one between each pair of stops.
+        if stops.len() < 2 || !(segments.is_empty() || segments.len() + 1 == stops.len())
+        {
+            bail!("encoded content is malformed");
+        }
+
+        let relative = match self.u8()? {
+            0 => Smart::Auto,
+            1 => Smart::Custom(RelativeTo::Self_),
+            2 => Smart::Custom(RelativeTo::Parent),
+            _ => bail!("encoded content is malformed"),
+        };
+        let transform = GradientTransform {
+            rotate: Angle::rad(self.f64()?),
+            scale: Ratio::new(self.f64()?),
+            offset: self.ratios()?,
+        };
+        let anti_alias = self.u8()? != 0;
+
+        Ok(match kind {
+            0 => Gradient::Linear(Arc::new(LinearGradient {
+                stops,
+                angle,
+                space,
+                hue,
+                easing,
+                segments,
+                relative,
+                transform,
+                anti_alias,
+            })),
+            1 => Gradient::Radial(Arc::new(RadialGradient {
+                stops,
+                center,
+                radius,
+                focal_center,
+                focal_radius,
+                space,
+                hue,
+                easing,
+                segments,
+                relative,
+                transform,
+                anti_alias,
+            })),
+            _ => Gradient::Conic(Arc::new(ConicGradient {
+                stops,
+                angle,
+                center,
+                space,
+                hue,
+                easing,
+                segments,
+                relative,
+                transform,
+                anti_alias,
+            })),
+        })
+    }
+
+    fn easing(&mut self) -> StrResult<Easing> {
+        Ok(match self.u8()? {
+            0 => Easing::Linear,
+            1 => Easing::CubicBezier([
+                Scalar::new(self.f64()?),
+                Scalar::new(self.f64()?),
+                Scalar::new(self.f64()?),
+                Scalar::new(self.f64()?),
+            ]),
+            _ => bail!("encoded content is malformed"),
+        })
+    }
+
+    fn datetime(&mut self) -> StrResult<Datetime> {
+        let parts = self.u8()?;
+        let date = if parts & 1 != 0 {
+            let year = i32::from_le_bytes(self.array()?);
+            let month = malformed(time::Month::try_from(self.u8()?).ok())?;
+            let day = self.u8()?;
+            Some(malformed(time::Date::from_calendar_date(year, month, day).ok())?)
+        } else {
+            None
+        };
+        let time = if parts & 2 != 0 {
+            let hour = self.u8()?;
+            let minute = self.u8()?;
+            let second = self.u8()?;
+            let nanosecond = u32::from_le_bytes(self.array()?);
+            Some(malformed(
+                time::Time::from_hms_nano(hour, minute, second, nanosecond).ok(),
+            )?)
+        } else {
+            None
+        };
+
+        Ok(match (date, time) {
+            (Some(date), Some(time)) => {
+                Datetime::Datetime(time::PrimitiveDateTime::new(date, time))
+            }
+            (Some(date), None) => Datetime::Date(date),
+            (None, Some(time)) => Datetime::Time(time),
+            (None, None) => bail!("encoded content is malformed"),
+        })
+    }
+
+    fn alignment(&mut self) -> StrResult<Alignment> {
+        let x = match self.u8()? {
+            0 => None,
+            1 => Some(HAlignment::Start),
+            2 => Some(HAlignment::Left),
+            3 => Some(HAlignment::Center),
+            4 => Some(HAlignment::Right),
+            5 => Some(HAlignment::End),
+            _ => bail!("encoded content is malformed"),
+        };
+        let y = match self.u8()? {
+            0 => None,
+            1 => Some(VAlignment::Top),
+            2 => Some(VAlignment::Horizon),
+            3 => Some(VAlignment::Bottom),
+            _ => bail!("encoded content is malformed"),
+        };
+        Ok(match (x, y) {
+            (Some(x), Some(y)) => Alignment::Both(x, y),
+            (Some(x), None) => Alignment::H(x),
+            (None, Some(y)) => Alignment::V(y),
+            (None, None) => bail!("encoded content is malformed"),
+        })
+    }
+
+    fn stroke(&mut self) -> SourceResult<Stroke> {
+        let span = self.span;
+        Ok(Stroke {
+            paint: self.value()?.cast().at(span)?,
+            thickness: self.value()?.cast().at(span)?,
+            cap: self.value()?.cast().at(span)?,
+            join: self.value()?.cast().at(span)?,
+            dash: self.value()?.cast().at(span)?,
+            miter_limit: self.value()?.cast::<Smart<f64>>().at(span)?.map(Scalar::new),
+        })
+    }
+
+    fn ratios(&mut self) -> StrResult<Axes<Ratio>> {
+        let x = Ratio::new(self.f64()?);
+        let y = Ratio::new(self.f64()?);
+        Ok(Axes::new(x, y))
+    }
+
+    fn length(&mut self) -> StrResult<Length> {
+        let abs = Abs::pt(self.f64()?);
+        let em = Em::new(self.f64()?);
+        Ok(Length { abs, em })
+    }
+
+    fn f64(&mut self) -> StrResult<f64> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+
+    fn f32(&mut self) -> StrResult<f32> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+
+    fn u8(&mut self) -> StrResult<u8> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn len(&mut self) -> StrResult<usize> {
+        Ok(u32::from_le_bytes(self.array()?) as usize)
+    }
+
+    fn str(&mut self) -> StrResult<EcoString> {
+        std::str::from_utf8(self.blob()?)
+            .map(Into::into)
+            .map_err(|_| "encoded content is malformed".into())
+    }
+
+    fn blob(&mut self) -> StrResult<&[u8]> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn array<const N: usize>(&mut self) -> StrResult<[u8; N]> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn take(&mut self, n: usize) -> StrResult<&[u8]> {
+        if self.data.len() < n {
+            bail!("encoded content is malformed");
+        }
+        let (head, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(head)
+    }
+}
+
+/// Determine the color space with the given tag.
+fn space_from_tag(tag: u8) -> StrResult<ColorSpace> {
+    Ok(match tag {
+        0 => ColorSpace::Oklab,
+        1 => ColorSpace::Oklch,
+        2 => ColorSpace::Lab,
+        3 => ColorSpace::Lch,
+        4 => ColorSpace::Xyz(WhitePoint::D50),
+        5 => ColorSpace::Xyz(WhitePoint::D65),
+        6 => ColorSpace::Srgb,
+        7 => ColorSpace::DisplayP3,
+        8 => ColorSpace::D65Gray,
+        9 => ColorSpace::LinearRgb,
+        10 => ColorSpace::Hsl,
+        11 => ColorSpace::Hsv,
+        12 => ColorSpace::Hwb,
+        13 => ColorSpace::Okhsl,
+        14 => ColorSpace::Okhsv,
+        15 => ColorSpace::Cmyk,
+        _ => bail!("encoded content is malformed"),
+    })
+}
+
+/// Fail with a malformation error if a decoded part is invalid.
+fn malformed<T>(part: Option<T>) -> StrResult<T> {
+    part.ok_or_else(|| "encoded content is malformed".into())
+}
+
+/// Turn the fields of an element into arguments for its constructor.
+///
+/// Required and positional fields are passed positionally in the order of the
+/// element's parameters and variadic fields are spread. An absent optional
+/// positional field that precedes a present one is filled with its default so
+/// that the later argument doesn't take its place. Fields that aren't
+/// parameters, like synthesized ones, are dropped as the constructor computes
+/// them anew.
+fn fields_to_args(elem: Element, fields: Vec<(EcoString, Value)>, span: Span) -> Args {
+    let mut fields: HashMap<EcoString, Value> = fields.into_iter().collect();
+    let mut args = Args::new::<Value>(span, []);
+    let mut skipped = vec![];
+    for param in elem.params() {
+        let positional = param.positional && !param.named;
+        let Some(value) = fields.remove(param.name) else {
+            if positional && !param.required && !param.variadic {
+                skipped.push(param.name);
+            }
+            continue;
+        };
+
+        if positional {
+            for name in skipped.drain(..) {
+                let default = elem
+                    .field_id(name)
+                    .and_then(|id| elem.field_from_styles(id, StyleChain::default()))
+                    .unwrap_or(Value::None);
+                args.push(span, default);
+            }
+        }
+
+        if param.variadic {
+            match value {
+                Value::Array(array) => {
+                    for item in array {
+                        args.push(span, item);
+                    }
+                }
+                value => args.push(span, value),
+            }
+        } else if positional {
+            args.push(span, value);
+        } else {
+            args.items.push(named(param.name, value, span));
+        }
+    }
+    args
+}
+
+/// Create a named argument.
+fn named(name: &str, value: Value, span: Span) -> Arg {
+    Arg {
+        span,
+        name: Some(name.into()),
+        value: Spanned::new(value, span),
+    }
+}
+
+/// Elements that can't be referred to through the library, but appear in
+/// content created from markup.
+fn unscoped_elements() -> [Element; 3] {
+    [SequenceElem::elem(), SpaceElem::elem(), AlignPointElem::elem()]
+}
+
+/// Determine the paths of all elements in the library.
+fn element_paths(library: &Library) -> Vec<(Element, EcoString)> {
+    let mut paths: Vec<_> = unscoped_elements()
+        .into_iter()
+        .map(|elem| (elem, elem.name().into()))
+        .collect();
+    collect_paths(library.global.scope(), "", &mut paths, 0);
+    paths
+}
+
+/// Collect the paths of the elements defined in a scope and its nested
+/// scopes.
+fn collect_paths(
+    scope: &Scope,
+    prefix: &str,
+    paths: &mut Vec<(Element, EcoString)>,
+    depth: usize,
+) {
+    if depth > 2 {
+        return;
+    }
+
+    for (name, value) in scope.iter() {
+        let path = eco_format!("{prefix}{name}");
+        let nested = match value {
+            Value::Func(func) => {
+                if let Some(elem) = func.element() {
+                    if !paths.iter().any(|(other, _)| *other == elem) {
+                        paths.push((elem, path.clone()));
+                    }
+                }
+                func.scope()
+            }
+            Value::Module(module) => Some(module.scope()),
+            _ => None,
+        };
+        if let Some(nested) = nested {
+            collect_paths(nested, &eco_format!("{path}."), paths, depth + 1);
+        }
+    }
+}
+
+/// Find an element by its path in the library.
+fn find_element(library: &Library, path: &str) -> Option<Element> {
+    if let Some(elem) = unscoped_elements().into_iter().find(|elem| elem.name() == path) {
+        return Some(elem);
+    }
+
+    let mut scope = library.global.scope();
+    let mut parts = path.split('.').peekable();
+    while let Some(part) = parts.next() {
+        let value = scope.get(part)?;
+        if parts.peek().is_none() {
+            let Value::Func(func) = value else { return None };
+            return func.element();
+        }
+        scope = match value {
+            Value::Func(func) => func.scope()?,
+            Value::Module(module) => module.scope(),
+            _ => return None,
+        };
+    }
+
+    None
+}
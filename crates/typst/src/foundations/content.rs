@@ -14,9 +14,9 @@ use smallvec::smallvec;
 use crate::diag::{SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, func, scope, ty, Context, Dict, Element, Fields, IntoValue, Label,
-    NativeElement, Recipe, RecipeIndex, Repr, Selector, Str, Style, StyleChain, Styles,
-    Value,
+    codec, elem, func, scope, ty, Bytes, Context, Dict, Element, Fields, IntoValue,
+    Label, NativeElement, Recipe, RecipeIndex, Repr, Selector, Str, Style, StyleChain,
+    Styles, Value,
 };
 use crate::introspection::{Location, Meta, MetaElem};
 use crate::layout::{AlignElem, Alignment, Axes, Length, MoveElem, PadElem, Rel, Sides};
@@ -25,6 +25,7 @@ use crate::realize::{Behave, Behaviour};
 use crate::syntax::Span;
 use crate::text::UnderlineElem;
 use crate::util::{fat, BitSet, LazyHash};
+use crate::World;
 
 /// A piece of document content.
 ///
@@ -577,6 +578,42 @@ impl Content {
     pub fn location(&self) -> Option<Location> {
         self.inner.location
     }
+
+    /// Encodes this content into bytes.
+    ///
+    /// The bytes can be written to a file by a build script and decoded again
+    /// in a later run with [`content.decode`]($content.decode). This is useful
+    /// for caching content that is expensive to generate. The bytes start with
+    /// a format version. Bytes with a different version than the compiler's
+    /// fail to decode, and so does content with elements or fields that no
+    /// longer exist.
+    ///
+    /// Content with show rules or with fields holding functions, patterns,
+    /// or other values that depend on code can't be encoded.
+    ///
+    /// ```example
+    /// #let data = [*Hello* _world_].encode()
+    /// #type(data) \
+    /// #content.decode(data)
+    /// ```
+    #[func]
+    pub fn encode(&self, engine: &mut Engine) -> StrResult<Bytes> {
+        codec::encode(engine.world.library(), self)
+    }
+
+    /// Decodes content from bytes created with
+    /// [`content.encode`]($content.encode).
+    #[func]
+    pub fn decode(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite span.
+        span: Span,
+        /// The encoded content.
+        data: Bytes,
+    ) -> SourceResult<Content> {
+        codec::decode(engine, span, &data)
+    }
 }
 
 impl Default for Content {
@@ -7,11 +7,12 @@ use time::error::{Format, InvalidFormatDescription};
 use time::macros::format_description;
 use time::{format_description, Month, PrimitiveDateTime};
 
-use crate::diag::{bail, StrResult};
+use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, func, repr, scope, ty, Dict, Duration, Repr, Smart, Str, Value,
 };
+use crate::syntax::Span;
 use crate::World;
 
 /// Represents a date, a time, or a combination of both.
@@ -310,6 +311,37 @@ impl Datetime {
             .ok_or("unable to get the current date")?)
     }
 
+    /// Returns the date and time at which a file was last modified.
+    ///
+    /// This is useful to show the revision date of a document in its footer.
+    /// Not all environments in which Typst runs provide modification dates.
+    ///
+    /// ```typ
+    /// Last changed on
+    /// #datetime.modified().display().
+    /// ```
+    #[func]
+    pub fn modified(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite span.
+        span: Span,
+        /// The path to the file. If not given, the file in which this
+        /// function is called is used.
+        #[default]
+        path: Option<EcoString>,
+    ) -> SourceResult<Datetime> {
+        let id = match path {
+            Some(path) => span.resolve_path(&path).at(span)?,
+            None => span.id().ok_or("cannot determine the current file").at(span)?,
+        };
+        engine
+            .world
+            .modified(id)
+            .ok_or("unable to get the modification date")
+            .at(span)
+    }
+
     /// Displays the datetime in a specified format.
     ///
     /// Depending on whether you have defined just a date, a time or both, the
@@ -0,0 +1,741 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, StrResult};
+use crate::foundations::{cast, func, repr, scope, ty, Cast, Repr, Smart, Str};
+
+/// A fixed-point decimal number.
+///
+/// Unlike [floats]($float), decimals represent numbers like `{0.1}` exactly.
+/// This makes them suitable for computations where rounding errors are
+/// unacceptable, for instance in invoices or other financial documents.
+///
+/// A decimal has up to 28 digits after the decimal point. Addition,
+/// subtraction, and multiplication are exact as long as the result fits into
+/// this precision. Otherwise, and for divisions that don't terminate, the
+/// result is rounded to the nearest representable number, with ties going to
+/// the even neighbour (banker's rounding).
+///
+/// Decimals can be combined with integers, which are converted to decimals
+/// first. Combining a decimal with a float is an error, as it would silently
+/// lose the exactness of the decimal. Most functions in the [`calc`]($calc)
+/// module also accept decimals.
+///
+/// # Example
+/// ```example
+/// #let price = decimal("19.99")
+/// #let total = price * 3
+/// #total \
+/// #(decimal("0.1") + decimal("0.2") == decimal("0.3")) \
+/// #(0.1 + 0.2 == 0.3)
+/// ```
+#[ty(scope, cast)]
+#[derive(Copy, Clone)]
+pub struct Decimal {
+    // The mantissa is stored in two halves so that decimals don't increase
+    // the alignment (and thus the size) of values.
+    /// The upper half of the mantissa.
+    hi: i64,
+    /// The lower half of the mantissa.
+    lo: u64,
+    /// The number of digits after the decimal point.
+    scale: u8,
+}
+
+impl Decimal {
+    /// The maximum number of digits after the decimal point.
+    pub const MAX_SCALE: u32 = 28;
+
+    /// The decimal zero.
+    pub const ZERO: Self = Self { hi: 0, lo: 0, scale: 0 };
+
+    /// Create a decimal from a mantissa and a scale, i.e. the value
+    /// `mantissa / 10^scale`.
+    ///
+    /// If the scale exceeds the maximum, the value is rounded with banker's
+    /// rounding. Returns `None` if the result can't be represented.
+    pub fn new(mantissa: i128, scale: u32) -> Option<Self> {
+        if scale <= Self::MAX_SCALE {
+            return Some(Self::raw(mantissa, scale));
+        }
+
+        let excess = scale - Self::MAX_SCALE;
+        let mantissa = match pow10(excess) {
+            Some(divisor) => round_div(mantissa, divisor, RoundingMode::HalfEven)?,
+            // The value is smaller than half a unit in the last place.
+            None => 0,
+        };
+        Some(Self::raw(mantissa, Self::MAX_SCALE))
+    }
+
+    /// Create a decimal from a mantissa and a scale that is known to be in
+    /// range.
+    fn raw(mantissa: i128, scale: u32) -> Self {
+        debug_assert!(scale <= Self::MAX_SCALE);
+        Self {
+            hi: (mantissa >> 64) as i64,
+            lo: mantissa as u64,
+            scale: scale as u8,
+        }
+    }
+
+    /// Convert a float to a decimal, via its shortest representation that
+    /// converts back to the same float.
+    pub fn from_f64(value: f64) -> StrResult<Self> {
+        if !value.is_finite() {
+            bail!("cannot convert {} to a decimal", value.repr());
+        }
+        value.to_string().parse()
+    }
+
+    /// The mantissa of the decimal.
+    pub fn mantissa(self) -> i128 {
+        ((self.hi as i128) << 64) | self.lo as i128
+    }
+
+    /// The number of digits after the decimal point.
+    pub fn scale(self) -> u32 {
+        self.scale as u32
+    }
+
+    /// Whether the decimal is zero.
+    pub fn is_zero(self) -> bool {
+        self.mantissa() == 0
+    }
+
+    /// The decimal with trailing zeros after the decimal point removed.
+    pub fn normalized(self) -> Self {
+        let mut mantissa = self.mantissa();
+        let mut scale = self.scale();
+        while scale > 0 && mantissa % 10 == 0 {
+            mantissa /= 10;
+            scale -= 1;
+        }
+        Self::raw(mantissa, scale)
+    }
+
+    /// Compute the negation of the decimal.
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self::raw(self.mantissa().checked_neg()?, self.scale()))
+    }
+
+    /// Compute the absolute value of the decimal.
+    pub fn checked_abs(self) -> Option<Self> {
+        Some(Self::raw(self.mantissa().checked_abs()?, self.scale()))
+    }
+
+    /// Compute the sum of two decimals.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let (a, b, scale) = self.align(other)?;
+        Some(Self::raw(a.checked_add(b)?, scale))
+    }
+
+    /// Compute the difference of two decimals.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        let (a, b, scale) = self.align(other)?;
+        Some(Self::raw(a.checked_sub(b)?, scale))
+    }
+
+    /// Compute the product of two decimals.
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let scale = self.scale() + other.scale();
+        match self.mantissa().checked_mul(other.mantissa()) {
+            Some(mantissa) => Self::new(mantissa, scale),
+            None => {
+                // Trailing zeros may be all that's in the way.
+                let (a, b) = (self.normalized(), other.normalized());
+                let scale = a.scale() + b.scale();
+                Self::new(a.mantissa().checked_mul(b.mantissa())?, scale)
+            }
+        }
+    }
+
+    /// Compute the quotient of two decimals.
+    ///
+    /// The quotient is computed with as many digits after the decimal point
+    /// as possible and rounded with banker's rounding. Returns `None` if the
+    /// divisor is zero or the result can't be represented.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+
+        // Find the largest scale at which the quotient is representable.
+        let (a, b) = (self.normalized(), other.normalized());
+        for scale in (0..=Self::MAX_SCALE).rev() {
+            // The quotient is `a.m / b.m * 10^(b.scale - a.scale)`. Scaling it
+            // by `10^scale` gives the mantissa of the result.
+            let shift = scale as i64 + b.scale() as i64 - a.scale() as i64;
+            let (n, d) = if shift >= 0 {
+                (
+                    pow10(shift as u32).and_then(|p| a.mantissa().checked_mul(p)),
+                    Some(b.mantissa()),
+                )
+            } else {
+                (
+                    Some(a.mantissa()),
+                    pow10((-shift) as u32).and_then(|p| b.mantissa().checked_mul(p)),
+                )
+            };
+            if let (Some(n), Some(d)) = (n, d) {
+                let mantissa = round_div(n, d, RoundingMode::HalfEven)?;
+                return Some(Self::raw(mantissa, scale).normalized());
+            }
+        }
+
+        None
+    }
+
+    /// Compute the remainder of the truncated division of two decimals.
+    pub fn checked_rem(self, other: Self) -> Option<Self> {
+        let (a, b, scale) = self.align(other)?;
+        Some(Self::raw(a.checked_rem(b)?, scale))
+    }
+
+    /// Compute the quotient of the euclidean division of two decimals.
+    pub fn checked_div_euclid(self, other: Self) -> Option<Self> {
+        let (a, b, _) = self.align(other)?;
+        Some(Self::raw(a.checked_div_euclid(b)?, 0))
+    }
+
+    /// Compute the least nonnegative remainder of the division of two
+    /// decimals.
+    pub fn checked_rem_euclid(self, other: Self) -> Option<Self> {
+        let (a, b, scale) = self.align(other)?;
+        Some(Self::raw(a.checked_rem_euclid(b)?, scale))
+    }
+
+    /// Compute the quotient of two decimals, rounded down to the nearest
+    /// integer.
+    pub fn checked_div_floor(self, other: Self) -> Option<Self> {
+        let (a, b, _) = self.align(other)?;
+        if b == 0 {
+            return None;
+        }
+        Some(Self::raw(round_div(a, b, RoundingMode::Floor)?, 0))
+    }
+
+    /// Raise the decimal to an integer power.
+    pub fn checked_powi(self, exponent: i64) -> Option<Self> {
+        let mut result = Self::raw(1, 0);
+        let mut base = self;
+        let mut n = exponent.unsigned_abs();
+        while n > 0 {
+            if n % 2 == 1 {
+                result = result.checked_mul(base)?;
+            }
+            n /= 2;
+            if n > 0 {
+                base = base.checked_mul(base)?;
+            }
+        }
+
+        if exponent < 0 {
+            Self::raw(1, 0).checked_div(result)
+        } else {
+            Some(result)
+        }
+    }
+
+    /// The fractional part of the decimal, with the sign of the decimal.
+    pub fn fract(self) -> Self {
+        match pow10(self.scale()) {
+            Some(unit) => Self::raw(self.mantissa() % unit, self.scale()),
+            None => self,
+        }
+    }
+
+    /// Round the decimal to the given number of digits after the decimal
+    /// point. A negative number of digits rounds to tens, hundreds, etc.
+    ///
+    /// Returns `None` if the result can't be represented.
+    pub fn round_to(self, digits: i64, mode: RoundingMode) -> Option<Self> {
+        let drop = self.scale() as i64 - digits;
+        if drop <= 0 {
+            return Some(self);
+        }
+
+        let mantissa = match u32::try_from(drop).ok().and_then(pow10) {
+            Some(divisor) => round_div(self.mantissa(), divisor, mode)?,
+            // The value is nonzero, but smaller than half the unit we round
+            // to. Rounding a third of the unit behaves the same.
+            None => round_div(self.mantissa().signum(), 3, mode)?,
+        };
+
+        if digits >= 0 {
+            Some(Self::raw(mantissa, digits as u32))
+        } else {
+            let factor = u32::try_from(-digits).ok().and_then(pow10);
+            match factor {
+                Some(factor) => Some(Self::raw(mantissa.checked_mul(factor)?, 0)),
+                None if mantissa == 0 => Some(Self::ZERO),
+                None => None,
+            }
+        }
+    }
+
+    /// Round the decimal to an integer.
+    pub fn to_i64(self, mode: RoundingMode) -> Option<i64> {
+        i64::try_from(self.round_to(0, mode)?.mantissa()).ok()
+    }
+
+    /// Convert the decimal to the closest float.
+    pub fn to_f64(self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// Format the decimal as text, with a proper minus sign.
+    pub fn to_text(self) -> EcoString {
+        let plain = self.to_string();
+        match plain.strip_prefix('-') {
+            Some(rest) => eco_format!("{}{}", repr::MINUS_SIGN, rest),
+            None => plain.into(),
+        }
+    }
+
+    /// Bring two decimals to the same scale and return their mantissas at
+    /// that scale.
+    fn align(self, other: Self) -> Option<(i128, i128, u32)> {
+        let scale = self.scale().max(other.scale());
+        let a = self.mantissa().checked_mul(pow10(scale - self.scale())?)?;
+        let b = other.mantissa().checked_mul(pow10(scale - other.scale())?)?;
+        Some((a, b, scale))
+    }
+}
+
+#[scope]
+impl Decimal {
+    /// Converts a value to a decimal.
+    ///
+    /// - Integers are converted exactly.
+    /// - Floats are converted via their shortest representation, e.g.
+    ///   `{0.1}` becomes exactly `{decimal("0.1")}`. To avoid binary floats
+    ///   altogether, prefer to write decimal literals as strings.
+    /// - Strings are parsed in base 10. Exponential notation is supported.
+    ///   Digits beyond the 28th place after the decimal point are rounded
+    ///   away.
+    ///
+    /// ```example
+    /// #decimal(5) \
+    /// #decimal("3.14159") \
+    /// #decimal("1.5e3") \
+    /// #decimal(0.1)
+    /// ```
+    #[func(constructor)]
+    pub fn construct(
+        /// The value that should be converted to a decimal.
+        value: ToDecimal,
+    ) -> Decimal {
+        value.0
+    }
+
+    /// Rounds the decimal to a number of digits after the decimal point.
+    ///
+    /// ```example
+    /// #let amount = decimal("2.345")
+    /// #amount.round(digits: 2) \
+    /// #amount.round(digits: 2, mode: "half-even") \
+    /// #decimal("1234").round(digits: -2) \
+    /// #decimal("-2.5").round(mode: "floor")
+    /// ```
+    #[func]
+    pub fn round(
+        self,
+        /// The number of digits to keep after the decimal point. If negative,
+        /// the decimal is rounded to tens, hundreds, and so on.
+        #[named]
+        #[default(0)]
+        digits: i64,
+        /// How to round numbers that lie between two representable numbers.
+        #[named]
+        #[default]
+        mode: RoundingMode,
+    ) -> StrResult<Decimal> {
+        self.round_to(digits, mode).ok_or_else(too_large)
+    }
+
+    /// Formats the decimal as a string.
+    ///
+    /// In contrast to converting a decimal with [`str`]($str), this gives
+    /// control over the number of digits and allows for exponential notation.
+    ///
+    /// ```example
+    /// #let n = decimal("1234.5")
+    /// #n.display(digits: 2) \
+    /// #n.display(exponent: true) \
+    /// #n.display(digits: 1, exponent: true) \
+    /// #decimal("0.00125").display(digits: 1, exponent: true, mode: "half-even")
+    /// ```
+    #[func]
+    pub fn display(
+        self,
+        /// The number of digits after the decimal point. With exponential
+        /// notation, this refers to the digits of the significand. The
+        /// decimal is rounded or padded with zeros as necessary. If `{auto}`,
+        /// all digits are kept.
+        #[named]
+        #[default]
+        digits: Smart<usize>,
+        /// How to round numbers when dropping digits.
+        #[named]
+        #[default]
+        mode: RoundingMode,
+        /// Whether to use exponential notation, like `1.5e3` for `1500`.
+        #[named]
+        #[default(false)]
+        exponent: bool,
+    ) -> StrResult<Str> {
+        let digits = digits.custom().map(|d| d.min(Self::MAX_SCALE as usize));
+        let mut value = self;
+        let mut exp = 0;
+
+        if exponent {
+            // Find the exponent that leaves one digit before the decimal
+            // point. Rounding can add a digit, so check again afterwards.
+            value = value.normalized();
+            for _ in 0..2 {
+                exp = magnitude(value);
+                if let Some(digits) = digits {
+                    value =
+                        self.round_to(digits as i64 - exp, mode).ok_or_else(too_large)?;
+                }
+                if magnitude(value) == exp {
+                    break;
+                }
+            }
+        } else if let Some(digits) = digits {
+            value = value.round_to(digits as i64, mode).ok_or_else(too_large)?;
+        }
+
+        let sign = if value.mantissa() < 0 { repr::MINUS_SIGN } else { "" };
+        let mut digit_str = value.mantissa().unsigned_abs().to_string();
+
+        // The number of digits after the decimal point in `digit_str`.
+        let scale = value.scale() as i64 + exp;
+        let mut fraction_len = if scale > 0 { scale as usize } else { 0 };
+        if scale < 0 {
+            digit_str.extend(std::iter::repeat('0').take((-scale) as usize));
+        }
+        if digit_str.len() <= fraction_len {
+            let padding = fraction_len + 1 - digit_str.len();
+            digit_str.insert_str(0, &"0".repeat(padding));
+        }
+        if exponent {
+            // Rounding to a negative number of digits leaves zeros that
+            // belong to the exponent.
+            while fraction_len > digits.unwrap_or(0) && digit_str.ends_with('0') {
+                digit_str.pop();
+                fraction_len -= 1;
+            }
+        }
+        if let Some(digits) = digits {
+            digit_str
+                .extend(std::iter::repeat('0').take(digits.saturating_sub(fraction_len)));
+            fraction_len = fraction_len.max(digits);
+        }
+
+        let (int, fraction) = digit_str.split_at(digit_str.len() - fraction_len);
+        let mut out = eco_format!("{sign}{int}");
+        if !fraction.is_empty() {
+            out.push('.');
+            out.push_str(fraction);
+        }
+        if exponent {
+            let exp_sign = if exp < 0 { repr::MINUS_SIGN } else { "" };
+            out.push_str(&eco_format!("e{exp_sign}{}", exp.unsigned_abs()));
+        }
+
+        Ok(out.into())
+    }
+}
+
+impl Default for Decimal {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(value: i64) -> Self {
+        Self::raw(value as i128, 0)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || eco_format!("invalid decimal: {}", s);
+
+        let (negative, rest) = match s.strip_prefix(['-', '+']) {
+            Some(rest) => (s.starts_with('-'), rest),
+            None => match s.strip_prefix(repr::MINUS_SIGN) {
+                Some(rest) => (true, rest),
+                None => (false, s),
+            },
+        };
+
+        let (number, exp) = match rest.split_once(['e', 'E']) {
+            Some((number, exp)) => {
+                let exp = exp.replace(repr::MINUS_SIGN, "-");
+                (number, exp.parse::<i64>().map_err(|_| invalid())?)
+            }
+            None => (rest, 0),
+        };
+
+        let (int, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if int.is_empty() && fraction.is_empty()
+            || !int.bytes().chain(fraction.bytes()).all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let digits: Vec<u8> =
+            int.bytes().chain(fraction.bytes()).map(|c| c - b'0').collect();
+        let scale = (fraction.len() as i64).saturating_sub(exp);
+        let mantissa = parse_digits(&digits, scale).ok_or_else(too_large)?;
+        let mantissa = if negative { -mantissa } else { mantissa };
+        Ok(Self::raw(mantissa, scale.clamp(0, Self::MAX_SCALE as i64) as u32))
+    }
+}
+
+/// Compute the mantissa for the given digits at the given scale, limited to
+/// the maximum scale.
+fn parse_digits(digits: &[u8], scale: i64) -> Option<i128> {
+    // Determine which digits need to be rounded away.
+    let excess = scale - Decimal::MAX_SCALE as i64;
+    let keep = if excess > 0 {
+        digits.len().saturating_sub(excess.try_into().unwrap_or(usize::MAX))
+    } else {
+        digits.len()
+    };
+
+    let (kept, dropped) = digits.split_at(keep);
+    let mut mantissa: i128 = 0;
+    for &digit in kept {
+        mantissa = mantissa.checked_mul(10)?.checked_add(digit as i128)?;
+    }
+
+    if !dropped.is_empty() {
+        // Round based on the first dropped digit and whether any digits
+        // after it are nonzero. The first dropped digit may be an implicit
+        // leading zero.
+        let (first, rest) = if excess as usize > digits.len() {
+            (0, dropped)
+        } else {
+            (dropped[0], &dropped[1..])
+        };
+        let mut n = mantissa.checked_mul(10)?.checked_add(first as i128)?;
+        let mut d = 10;
+        if rest.iter().any(|&digit| digit != 0) {
+            n = n.checked_mul(10)?.checked_add(1)?;
+            d = 100;
+        }
+        mantissa = round_div(n, d, RoundingMode::HalfEven)?;
+    } else if scale < 0 {
+        mantissa = mantissa.checked_mul(pow10(u32::try_from(-scale).ok()?)?)?;
+    }
+
+    Some(mantissa)
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mantissa = self.mantissa();
+        let digits = mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if mantissa < 0 {
+            f.write_str("-")?;
+        }
+        if scale == 0 {
+            return f.write_str(&digits);
+        }
+
+        let padded = format!("{:0>width$}", digits, width = scale + 1);
+        let (int, fraction) = padded.split_at(padded.len() - scale);
+        write!(f, "{int}.{fraction}")
+    }
+}
+
+impl Debug for Decimal {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Decimal({self})")
+    }
+}
+
+impl Repr for Decimal {
+    fn repr(&self) -> EcoString {
+        eco_format!("decimal({})", self.to_string().as_str().repr())
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.align(*other) {
+            Some((a, b, _)) => a.cmp(&b),
+            // If aligning overflows, the decimal with the smaller scale has
+            // the larger magnitude.
+            None if self.scale < other.scale => self.mantissa().cmp(&0),
+            None => 0.cmp(&other.mantissa()),
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normalized = self.normalized();
+        normalized.mantissa().hash(state);
+        normalized.scale.hash(state);
+    }
+}
+
+/// How to round a number that lies between two representable numbers.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum RoundingMode {
+    /// Round to the nearest number. Ties are rounded away from zero.
+    #[default]
+    HalfAwayFromZero,
+    /// Round to the nearest number. Ties are rounded towards zero.
+    HalfTowardZero,
+    /// Round to the nearest number. Ties are rounded to the even neighbour.
+    /// This is also known as banker's rounding.
+    HalfEven,
+    /// Round towards zero, i.e. truncate.
+    TowardZero,
+    /// Round away from zero.
+    AwayFromZero,
+    /// Round towards negative infinity.
+    Floor,
+    /// Round towards positive infinity.
+    Ceil,
+}
+
+/// A value that can be cast to a decimal.
+pub struct ToDecimal(Decimal);
+
+cast! {
+    ToDecimal,
+    v: Decimal => Self(v),
+    v: i64 => Self(v.into()),
+    v: f64 => Self(Decimal::from_f64(v)?),
+    v: Str => Self(v.parse()?),
+}
+
+/// Divide two integers and round the result to an integer.
+///
+/// Returns `None` if the divisor is zero or the result overflows.
+fn round_div(n: i128, d: i128, mode: RoundingMode) -> Option<i128> {
+    let q = n.checked_div(d)?;
+    let r = n.checked_rem(d)?;
+    if r == 0 {
+        return Some(q);
+    }
+
+    let negative = (n < 0) != (d < 0);
+    let r = r.unsigned_abs();
+    let half = r.cmp(&(d.unsigned_abs() - r));
+    let away = match mode {
+        RoundingMode::HalfAwayFromZero => half != Ordering::Less,
+        RoundingMode::HalfTowardZero => half == Ordering::Greater,
+        RoundingMode::HalfEven => {
+            half == Ordering::Greater || (half == Ordering::Equal && q % 2 != 0)
+        }
+        RoundingMode::TowardZero => false,
+        RoundingMode::AwayFromZero => true,
+        RoundingMode::Floor => negative,
+        RoundingMode::Ceil => !negative,
+    };
+
+    match (away, negative) {
+        (false, _) => Some(q),
+        (true, false) => q.checked_add(1),
+        (true, true) => q.checked_sub(1),
+    }
+}
+
+/// The power of ten with the given exponent, if it fits into an `i128`.
+fn pow10(exp: u32) -> Option<i128> {
+    10_i128.checked_pow(exp)
+}
+
+/// The exponent of the leading digit of a decimal, i.e. `2` for `123.4`.
+fn magnitude(value: Decimal) -> i64 {
+    let mantissa = value.mantissa();
+    if mantissa == 0 {
+        return 0;
+    }
+    mantissa.unsigned_abs().ilog10() as i64 - value.scale() as i64
+}
+
+/// The error message when a decimal is too large to be represented.
+#[cold]
+fn too_large() -> EcoString {
+    "value is too large".into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_decimal_parse_and_display() {
+        assert_eq!(dec("1.50").to_string(), "1.50");
+        assert_eq!(dec("-0.05").to_string(), "-0.05");
+        assert_eq!(dec("1.5e3").to_string(), "1500");
+        assert_eq!(dec("15e-3").to_string(), "0.015");
+        assert_eq!(dec(".5").to_string(), "0.5");
+        assert_eq!(dec("1e-40").to_string(), "0.0000000000000000000000000000");
+        assert!("1.2.3".parse::<Decimal>().is_err());
+        assert!("e5".parse::<Decimal>().is_err());
+        assert_eq!(Decimal::from_f64(0.1).unwrap(), dec("0.1"));
+    }
+
+    #[test]
+    fn test_decimal_arithmetic() {
+        assert_eq!(dec("0.1").checked_add(dec("0.2")), Some(dec("0.3")));
+        assert_eq!(dec("1.5").checked_mul(dec("-2")), Some(dec("-3")));
+        assert_eq!(dec("1").checked_div(dec("4")), Some(dec("0.25")));
+        assert_eq!(
+            dec("2").checked_div(dec("3")),
+            Some(dec("0.6666666666666666666666666667"))
+        );
+        assert_eq!(dec("1").checked_div(dec("0")), None);
+        assert_eq!(dec("1.1").checked_powi(2), Some(dec("1.21")));
+        assert_eq!(dec("2").checked_powi(-2), Some(dec("0.25")));
+    }
+
+    #[test]
+    fn test_decimal_rounding() {
+        let round = |s, digits, mode| dec(s).round_to(digits, mode).unwrap().to_string();
+        assert_eq!(round("2.5", 0, RoundingMode::HalfEven), "2");
+        assert_eq!(round("3.5", 0, RoundingMode::HalfEven), "4");
+        assert_eq!(round("-2.5", 0, RoundingMode::HalfAwayFromZero), "-3");
+        assert_eq!(round("-2.5", 0, RoundingMode::HalfTowardZero), "-2");
+        assert_eq!(round("-2.1", 0, RoundingMode::Floor), "-3");
+        assert_eq!(round("2.1", 0, RoundingMode::Ceil), "3");
+        assert_eq!(round("1250", -2, RoundingMode::HalfEven), "1200");
+        assert_eq!(round("0.001", -3, RoundingMode::AwayFromZero), "1000");
+    }
+}
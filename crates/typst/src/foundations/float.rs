@@ -2,7 +2,7 @@ use std::num::ParseFloatError;
 
 use ecow::{eco_format, EcoString};
 
-use crate::foundations::{cast, func, repr, scope, ty, Repr, Str};
+use crate::foundations::{cast, func, repr, scope, ty, Decimal, Repr, Str};
 use crate::layout::Ratio;
 
 /// A floating-point number.
@@ -28,6 +28,7 @@ impl f64 {
     ///
     /// - Booleans are converted to `0.0` or `1.0`.
     /// - Integers are converted to the closest 64-bit float.
+    /// - Decimals are converted to the closest 64-bit float.
     /// - Ratios are divided by 100%.
     /// - Strings are parsed in base 10 to the closest 64-bit float.
     ///   Exponential notation is supported.
@@ -110,6 +111,7 @@ cast! {
     v: f64 => Self(v),
     v: bool => Self(v as i64 as f64),
     v: i64 => Self(v as f64),
+    v: Decimal => Self(v.to_f64()),
     v: Ratio => Self(v.get()),
     v: Str => Self(
         parse_float(v.clone().into())
@@ -1,4 +1,5 @@
 use std::fmt::{self, Debug, Formatter};
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 use comemo::{Tracked, TrackedMut};
@@ -316,6 +317,13 @@ impl Func {
         }
         self
     }
+
+    /// Turn an element function into a selector for a selector method.
+    fn to_selector(&self, method: &str) -> StrResult<Selector> {
+        self.element().map(Element::select).ok_or_else(|| {
+            eco_format!("`{method}()` can only be called on element functions")
+        })
+    }
 }
 
 #[scope]
@@ -381,6 +389,38 @@ impl Func {
 
         Ok(element.where_(fields))
     }
+
+    /// Returns a selector for elements belonging to this function that are
+    /// nested within an element matching `ancestor`. See
+    /// [`selector.within`]($selector.within) for details.
+    #[func]
+    pub fn within(
+        self,
+        /// The selector the matched elements must be nested within.
+        ancestor: Selector,
+    ) -> StrResult<Selector> {
+        Ok(self.to_selector("within")?.within(ancestor))
+    }
+
+    /// Returns a selector for elements belonging to this function that are
+    /// nested within exactly `depth - 1` other such elements. See
+    /// [`selector.at-depth`]($selector.at-depth) for details.
+    #[func]
+    pub fn at_depth(
+        self,
+        /// The nesting depth, starting at `{1}`.
+        depth: NonZeroUsize,
+    ) -> StrResult<Selector> {
+        Ok(self.to_selector("at-depth")?.at_depth(depth))
+    }
+
+    /// Returns a selector for the first element belonging to this function on
+    /// each page. See [`selector.first-on-page`]($selector.first-on-page) for
+    /// details.
+    #[func]
+    pub fn first_on_page(self) -> StrResult<Selector> {
+        Ok(self.to_selector("first-on-page")?.first_on_page())
+    }
 }
 
 impl Debug for Func {
@@ -4,7 +4,7 @@ use ecow::{eco_format, EcoString};
 
 use crate::{
     diag::StrResult,
-    foundations::{cast, func, repr, scope, ty, Repr, Str, Value},
+    foundations::{cast, func, repr, scope, ty, Decimal, Repr, RoundingMode, Str, Value},
 };
 
 /// A whole number.
@@ -37,6 +37,7 @@ impl i64 {
     ///
     /// - Booleans are converted to `0` or `1`.
     /// - Floats are floored to the next 64-bit integer.
+    /// - Decimals are truncated to the next 64-bit integer.
     /// - Strings are parsed in base 10.
     ///
     /// ```example
@@ -232,6 +233,7 @@ cast! {
     v: i64 => Self(v),
     v: bool => Self(v as i64),
     v: f64 => Self(v as i64),
+    v: Decimal => Self(v.to_i64(RoundingMode::TowardZero).ok_or("decimal is too large")?),
     v: Str => Self(parse_int(&v).map_err(|_| eco_format!("invalid integer: {}", v))?),
 }
 
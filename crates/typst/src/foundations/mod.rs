@@ -7,12 +7,15 @@ pub mod sys;
 mod args;
 mod array;
 mod auto;
+mod bits;
 mod bool;
 mod bytes;
 mod cast;
+mod codec;
 mod content;
 mod context;
 mod datetime;
+mod decimal;
 mod dict;
 mod duration;
 mod element;
@@ -36,11 +39,13 @@ mod version;
 pub use self::args::*;
 pub use self::array::*;
 pub use self::auto::*;
+pub use self::bits::*;
 pub use self::bytes::*;
 pub use self::cast::*;
 pub use self::content::*;
 pub use self::context::*;
 pub use self::datetime::*;
+pub use self::decimal::*;
 pub use self::dict::*;
 pub use self::duration::*;
 pub use self::element::*;
@@ -93,6 +98,7 @@ pub(super) fn define(global: &mut Scope, inputs: Dict) {
     global.define_type::<Str>();
     global.define_type::<Label>();
     global.define_type::<Bytes>();
+    global.define_type::<Bits>();
     global.define_type::<Content>();
     global.define_type::<Array>();
     global.define_type::<Dict>();
@@ -103,6 +109,7 @@ pub(super) fn define(global: &mut Scope, inputs: Dict) {
     global.define_type::<Regex>();
     global.define_type::<Selector>();
     global.define_type::<Datetime>();
+    global.define_type::<Decimal>();
     global.define_type::<Duration>();
     global.define_type::<Version>();
     global.define_type::<Plugin>();
@@ -1,4 +1,5 @@
 use std::any::{Any, TypeId};
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 use comemo::Tracked;
@@ -10,7 +11,7 @@ use crate::foundations::{
     cast, func, repr, scope, ty, CastInfo, Content, Context, Dict, Element, FromValue,
     Func, Label, Reflect, Regex, Repr, Str, StyleChain, Type, Value,
 };
-use crate::introspection::{Introspector, Locatable, Location};
+use crate::introspection::{Introspector, Locatable, Location, Meta, MetaElem};
 use crate::symbols::Symbol;
 use crate::text::TextElem;
 
@@ -99,6 +100,13 @@ pub enum Selector {
     Before { selector: Arc<Self>, end: Arc<Self>, inclusive: bool },
     /// Matches all matches of `selector` after `start`.
     After { selector: Arc<Self>, start: Arc<Self>, inclusive: bool },
+    /// Matches all matches of `selector` nested within a match of `ancestor`.
+    Within { selector: Arc<Self>, ancestor: Arc<Self> },
+    /// Matches all matches of `selector` nested within exactly `depth - 1`
+    /// other matches of `selector`.
+    AtDepth { selector: Arc<Self>, depth: NonZeroUsize },
+    /// Matches the first match of `selector` on each page.
+    FirstOnPage(Arc<Self>),
 }
 
 impl Selector {
@@ -148,12 +156,52 @@ impl Selector {
                 selectors.iter().all(move |sel| sel.matches(target, styles))
             }
             Self::Location(location) => target.location() == Some(*location),
+            Self::Within { selector, ancestor } => {
+                selector.matches(target, styles)
+                    && styles.is_some_and(|styles| {
+                        ancestors(target, styles)
+                            .any(|elem| ancestor.matches(&elem, None))
+                    })
+            }
+            Self::AtDepth { selector, depth } => {
+                selector.matches(target, styles)
+                    && styles.is_some_and(|styles| {
+                        let outer = ancestors(target, styles)
+                            .filter(|elem| selector.matches(elem, None))
+                            .count();
+                        outer + 1 == depth.get()
+                    })
+            }
             // Not supported here.
-            Self::Before { .. } | Self::After { .. } => false,
+            Self::Before { .. } | Self::After { .. } | Self::FirstOnPage(_) => false,
         }
     }
 }
 
+/// The elements that the target is nested in, as recorded in the style chain
+/// during realization.
+///
+/// Only elements that are locatable or labelled are recorded, from the
+/// outermost to the innermost.
+fn ancestors<'a>(
+    target: &'a Content,
+    styles: StyleChain,
+) -> impl Iterator<Item = Content> + 'a {
+    MetaElem::data_in(styles)
+        .into_iter()
+        .filter_map(move |meta| match meta {
+            // When a show rule returns the element itself, its own metadata is
+            // already part of the style chain.
+            Meta::Elem(elem)
+                if target.location().is_none()
+                    || elem.location() != target.location() =>
+            {
+                Some(elem)
+            }
+            _ => None,
+        })
+}
+
 #[scope]
 impl Selector {
     /// Turns a value into a selector. The following values are accepted:
@@ -231,6 +279,82 @@ impl Selector {
             inclusive,
         }
     }
+
+    /// Returns a modified selector that will only match elements that are
+    /// nested within an element matching `ancestor`.
+    ///
+    /// The ancestor must be locatable (like a heading or figure) or have a
+    /// label. This selector can only be used with show rules.
+    ///
+    /// ```example
+    /// #show figure.within(<appendix>): set figure(numbering: "A")
+    ///
+    /// #figure(rect(), caption: [Main])
+    /// #block[
+    ///   #figure(rect(), caption: [Extra])
+    /// ] <appendix>
+    /// ```
+    #[func]
+    pub fn within(
+        self,
+        /// The selector the matched elements must be nested within.
+        ancestor: Selector,
+    ) -> Selector {
+        Self::Within {
+            selector: Arc::new(self),
+            ancestor: Arc::new(ancestor),
+        }
+    }
+
+    /// Returns a modified selector that will only match elements that are
+    /// nested within exactly `depth - 1` other elements matching this selector.
+    ///
+    /// Like with [`within`]($selector.within), the elements must be locatable
+    /// or have a label and this selector can only be used with show rules.
+    ///
+    /// ```example
+    /// #show figure.at-depth(2): set figure(numbering: "(a)")
+    ///
+    /// #figure(
+    ///   grid(
+    ///     columns: 2,
+    ///     gutter: 1em,
+    ///     figure(rect(), caption: [Left]),
+    ///     figure(rect(), caption: [Right]),
+    ///   ),
+    ///   caption: [Both],
+    /// )
+    /// ```
+    #[func]
+    pub fn at_depth(
+        self,
+        /// The nesting depth, starting at `{1}` for elements that aren't
+        /// nested in any other matching element.
+        depth: NonZeroUsize,
+    ) -> Selector {
+        Self::AtDepth { selector: Arc::new(self), depth }
+    }
+
+    /// Returns a modified selector that will only match the first matching
+    /// element on each page.
+    ///
+    /// This selector can only be used with [queries]($query).
+    ///
+    /// ```example
+    /// #set page(height: 80pt)
+    /// #context query(heading.first-on-page())
+    ///   .map(it => it.body)
+    ///   .join[, ]
+    ///
+    /// = One
+    /// = Two
+    /// #pagebreak()
+    /// = Three
+    /// ```
+    #[func]
+    pub fn first_on_page(self) -> Selector {
+        Self::FirstOnPage(Arc::new(self))
+    }
 }
 
 impl From<Location> for Selector {
@@ -276,6 +400,15 @@ impl Repr for Selector {
                     inclusive_arg
                 )
             }
+            Self::Within { selector, ancestor } => {
+                eco_format!("{}.within({})", selector.repr(), ancestor.repr())
+            }
+            Self::AtDepth { selector, depth } => {
+                eco_format!("{}.at-depth({depth})", selector.repr())
+            }
+            Self::FirstOnPage(selector) => {
+                eco_format!("{}.first-on-page()", selector.repr())
+            }
         }
     }
 }
@@ -367,6 +500,10 @@ impl FromValue for LocatableSelector {
                         validate(selector)?;
                     }
                 }
+                Selector::FirstOnPage(selector) => validate(selector)?,
+                Selector::Within { .. } | Selector::AtDepth { .. } => {
+                    bail!("this selector can only be used with show rules")
+                }
             }
             Ok(())
         }
@@ -437,11 +574,17 @@ impl FromValue for ShowableSelector {
                         validate(selector, true)?;
                     }
                 }
+                Selector::Within { selector, ancestor } => {
+                    validate(selector, true)?;
+                    validate(ancestor, true)?;
+                }
+                Selector::AtDepth { selector, .. } => validate(selector, true)?,
                 Selector::Regex(_)
                 | Selector::Location(_)
                 | Selector::Can(_)
                 | Selector::Before { .. }
-                | Selector::After { .. } => {
+                | Selector::After { .. }
+                | Selector::FirstOnPage(_) => {
                     bail!("this selector cannot be used with show")
                 }
             }
@@ -252,6 +252,16 @@ impl Property {
         }
     }
 
+    /// The element the property belongs to.
+    pub fn elem(&self) -> Element {
+        self.elem
+    }
+
+    /// The ID of the field the property sets.
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
     /// Whether this property is the given one.
     pub fn is(&self, elem: Element, id: u8) -> bool {
         self.elem == elem && self.id == id
@@ -12,9 +12,10 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use crate::diag::StrResult;
 use crate::eval::ops;
 use crate::foundations::{
-    fields, repr, Args, Array, AutoValue, Bytes, CastInfo, Content, Datetime, Dict,
-    Duration, Fold, FromValue, Func, IntoValue, Label, Module, NativeElement, NativeType,
-    NoneValue, Plugin, Reflect, Repr, Resolve, Scope, Str, Styles, Type, Version,
+    fields, repr, Args, Array, AutoValue, Bytes, CastInfo, Content, Datetime, Decimal,
+    Dict, Duration, Fold, FromValue, Func, IntoValue, Label, Module, NativeElement,
+    NativeType, NoneValue, Plugin, Reflect, Repr, Resolve, Scope, Str, Styles, Type,
+    Version,
 };
 use crate::layout::{Abs, Angle, Em, Fr, Length, Ratio, Rel};
 use crate::symbols::Symbol;
@@ -37,6 +38,8 @@ pub enum Value {
     Int(i64),
     /// A floating-point number: `1.2`, `10e-4`.
     Float(f64),
+    /// A fixed-point decimal number: `decimal("1.5")`.
+    Decimal(Decimal),
     /// A length: `12pt`, `3cm`, `1.5em`, `1em - 2pt`.
     Length(Length),
     /// An angle: `1.5rad`, `90deg`.
@@ -122,6 +125,7 @@ impl Value {
             Self::Bool(_) => Type::of::<bool>(),
             Self::Int(_) => Type::of::<i64>(),
             Self::Float(_) => Type::of::<f64>(),
+            Self::Decimal(_) => Type::of::<Decimal>(),
             Self::Length(_) => Type::of::<Length>(),
             Self::Angle(_) => Type::of::<Angle>(),
             Self::Ratio(_) => Type::of::<Ratio>(),
@@ -204,6 +208,7 @@ impl Value {
             Self::None => Content::empty(),
             Self::Int(v) => TextElem::packed(repr::format_int_with_base(v, 10)),
             Self::Float(v) => TextElem::packed(repr::display_float(v)),
+            Self::Decimal(v) => TextElem::packed(v.to_text()),
             Self::Str(v) => TextElem::packed(v),
             Self::Version(v) => TextElem::packed(eco_format!("{v}")),
             Self::Symbol(v) => TextElem::packed(v.get()),
@@ -234,6 +239,7 @@ impl Debug for Value {
             Self::Bool(v) => Debug::fmt(v, f),
             Self::Int(v) => Debug::fmt(v, f),
             Self::Float(v) => Debug::fmt(v, f),
+            Self::Decimal(v) => Debug::fmt(v, f),
             Self::Length(v) => Debug::fmt(v, f),
             Self::Angle(v) => Debug::fmt(v, f),
             Self::Ratio(v) => Debug::fmt(v, f),
@@ -271,6 +277,7 @@ impl Repr for Value {
             Self::Bool(v) => v.repr(),
             Self::Int(v) => v.repr(),
             Self::Float(v) => v.repr(),
+            Self::Decimal(v) => v.repr(),
             Self::Length(v) => v.repr(),
             Self::Angle(v) => v.repr(),
             Self::Ratio(v) => v.repr(),
@@ -321,6 +328,7 @@ impl Hash for Value {
             Self::Bool(v) => v.hash(state),
             Self::Int(v) => v.hash(state),
             Self::Float(v) => v.to_bits().hash(state),
+            Self::Decimal(v) => v.hash(state),
             Self::Length(v) => v.hash(state),
             Self::Angle(v) => v.hash(state),
             Self::Ratio(v) => v.hash(state),
@@ -649,6 +657,7 @@ primitive! {
 primitive! { Bytes: "bytes", Bytes }
 primitive! { Label: "label", Label }
 primitive! { Datetime: "datetime", Datetime }
+primitive! { Decimal: "decimal", Decimal, Int(v) => v.into() }
 primitive! { Duration: "duration", Duration }
 primitive! { Content: "content",
     Content,
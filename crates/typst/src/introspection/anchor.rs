@@ -0,0 +1,93 @@
+use crate::diag::{SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, elem, Content, Dict, Label, NativeElement, Packed, Resolve, Show, Str,
+    StyleChain,
+};
+use crate::introspection::{Locatable, Meta};
+use crate::layout::{Axes, Frame, FrameItem, Length, Rel, Size};
+use crate::syntax::Span;
+use crate::util::hash128;
+
+/// A named point on an element.
+///
+/// Anchors are defined through the `anchors` parameter of [boxes]($box.anchors)
+/// and [blocks]($block.anchors). They don't produce any visible content, but
+/// you can [query] them to find out where exactly they ended up in the
+/// document. This way, diagrams and annotations can attach to precise points
+/// of an element. [Connectors]($connector.start-anchor) can attach to anchors
+/// directly.
+///
+/// To find the anchors of a specific element, give the element a label and
+/// select the anchors with that label as their [`of`]($anchor.of) field.
+///
+/// ```example
+/// #box(
+///   width: 60pt,
+///   height: 20pt,
+///   stroke: 1pt,
+///   anchors: (input: (0%, 50%), output: (100%, 50%)),
+/// ) <gate>
+///
+/// #context {
+///   let output = query(anchor.where(of: <gate>, name: "output")).first()
+///   output.location().position()
+/// }
+/// ```
+#[elem(Locatable, Show)]
+pub struct AnchorElem {
+    /// The name of the anchor.
+    #[required]
+    pub name: Str,
+
+    /// The label of the element the anchor belongs to, if it has one.
+    pub of: Option<Label>,
+}
+
+impl Show for Packed<AnchorElem> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// Named points on an element, relative to its top-left corner.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct Anchors(Vec<(Str, Axes<Rel<Length>>)>);
+
+impl Anchors {
+    /// Adds the anchors to the frame of an element with the given label.
+    pub fn apply(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        frame: &mut Frame,
+        of: Option<Label>,
+        span: Span,
+    ) {
+        for (name, point) in &self.0 {
+            let point = point
+                .resolve(styles)
+                .zip_map(frame.size(), Rel::relative_to)
+                .to_point();
+            let mut anchor =
+                AnchorElem::new(name.clone()).with_of(of).pack().spanned(span);
+            anchor.set_location(engine.locator.locate(hash128(&anchor)));
+            frame.push(point, FrameItem::Meta(Meta::Elem(anchor), Size::zero()));
+        }
+    }
+}
+
+cast! {
+    Anchors,
+    self => self
+        .0
+        .into_iter()
+        .map(|(name, point)| (name, point.into_value()))
+        .collect::<Dict>()
+        .into_value(),
+    dict: Dict => Self(
+        dict.into_iter()
+            .map(|(name, point)| Ok((name, point.cast()?)))
+            .collect::<StrResult<_>>()?,
+    ),
+}
@@ -10,8 +10,8 @@ use crate::engine::{Engine, Route};
 use crate::eval::Tracer;
 use crate::foundations::{
     cast, elem, func, scope, select_where, ty, Args, Array, Construct, Content, Context,
-    Element, Func, IntoValue, Label, LocatableSelector, NativeElement, Packed, Repr,
-    Selector, Show, Smart, Str, StyleChain, Value,
+    Dict, Element, Func, IntoValue, Label, LocatableSelector, NativeElement, Packed,
+    Repr, Selector, Show, Smart, Str, StyleChain, Value,
 };
 use crate::introspection::{Introspector, Locatable, Location, Locator, Meta};
 use crate::layout::{Frame, FrameItem, PageElem};
@@ -197,6 +197,32 @@ use crate::World;
 /// #mine.step()
 /// ```
 ///
+/// # Continuing across documents { #continuing }
+/// When a book is split into parts that are compiled separately, each part
+/// should continue the numbering of the previous one. With
+/// [`snapshot`]($counter.snapshot), a part records the final values of its
+/// counters in a [metadata] element. You can then export them to a JSON file
+/// with `typst query`. The next part loads that file and continues from there
+/// with [`resume`]($counter.resume).
+///
+/// ```typ
+/// #let counters = (
+///   page: page,
+///   heading: heading,
+///   figure: figure.where(kind: image),
+///   footnote: footnote,
+/// )
+///
+/// // In the first part:
+/// #context metadata(counter.snapshot(counters)) <counters>
+///
+/// // In the second part:
+/// #counter.resume(json("part-1.json"), counters)
+/// ```
+///
+/// The JSON file is created with
+/// `typst query part-1.typ "<counters>" --field value --one > part-1.json`.
+///
 /// # Other kinds of state { #other-state }
 /// The `counter` type is closely related to [state] type. Read its
 /// documentation for more details on state management in Typst and why it
@@ -230,11 +256,17 @@ impl Counter {
         let (mut at_state, at_page) = sequence[offset].clone();
         let (mut final_state, final_page) = sequence.last().unwrap().clone();
         if self.is_page() {
-            let at_delta =
-                engine.introspector.page(location).get().saturating_sub(at_page.get());
+            let at_delta = engine
+                .introspector
+                .distinct_page(location)
+                .get()
+                .saturating_sub(at_page.get());
             at_state.step(NonZeroUsize::ONE, at_delta);
-            let final_delta =
-                engine.introspector.pages().get().saturating_sub(final_page.get());
+            let final_delta = engine
+                .introspector
+                .distinct_pages()
+                .get()
+                .saturating_sub(final_page.get());
             final_state.step(NonZeroUsize::ONE, final_delta);
         }
         Ok(CounterState(smallvec![at_state.first(), final_state.first()]))
@@ -254,7 +286,11 @@ impl Counter {
             .len();
         let (mut state, page) = sequence[offset].clone();
         if self.is_page() {
-            let delta = engine.introspector.page(loc).get().saturating_sub(page.get());
+            let delta = engine
+                .introspector
+                .distinct_page(loc)
+                .get()
+                .saturating_sub(page.get());
             state.step(NonZeroUsize::ONE, delta);
         }
         Ok(state)
@@ -318,7 +354,7 @@ impl Counter {
         for elem in introspector.query(&self.selector()) {
             if self.is_page() {
                 let prev = page;
-                page = introspector.page(elem.location().unwrap());
+                page = introspector.distinct_page(elem.location().unwrap());
 
                 let delta = page.get() - prev.get();
                 if delta > 0 {
@@ -527,12 +563,89 @@ impl Counter {
         let sequence = self.sequence(engine)?;
         let (mut state, page) = sequence.last().unwrap().clone();
         if self.is_page() {
-            let delta = engine.introspector.pages().get().saturating_sub(page.get());
+            let delta =
+                engine.introspector.distinct_pages().get().saturating_sub(page.get());
             state.step(NonZeroUsize::ONE, delta);
         }
         Ok(state)
     }
 
+    /// Retrieves the final values of multiple counters at once.
+    ///
+    /// Returns a dictionary with the same keys as `counters`, where each value
+    /// is the final value of the respective counter. This is the format that
+    /// [`resume`]($counter.resume) expects.
+    ///
+    /// ```example
+    /// = Introduction
+    /// #figure([A], caption: [First])
+    /// = Conclusion
+    ///
+    /// #context counter.snapshot(
+    ///   (heading: heading, figure: figure.where(kind: image)),
+    /// )
+    /// ```
+    #[func(contextual)]
+    pub fn snapshot(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// The counters to retrieve, given by their [keys]($counter/#constructor)
+        /// under arbitrary names.
+        counters: Dict,
+    ) -> SourceResult<Dict> {
+        let mut states = Dict::new();
+        for (name, counter) in named_counters(counters).at(span)? {
+            let state = counter.final_(engine, context, span, None)?;
+            states.insert(name, state.into_value());
+        }
+        Ok(states)
+    }
+
+    /// Continues multiple counters from the given values, typically ones
+    /// retrieved from another document with [`snapshot`]($counter.snapshot).
+    ///
+    /// Each counter is set to the value with the same name. The page counter
+    /// continues on the page after the recorded one.
+    ///
+    /// ```example
+    /// #counter.resume(
+    ///   (heading: (2,), figure: (5,)),
+    ///   (heading: heading, figure: figure.where(kind: image)),
+    /// )
+    ///
+    /// #set heading(numbering: "1.")
+    /// = Next chapter
+    /// #figure([A], caption: [Continued])
+    /// ```
+    #[func]
+    pub fn resume(
+        /// The call span of the updates.
+        span: Span,
+        /// The counter values, by name.
+        states: Dict,
+        /// The counters to update, given by their [keys]($counter/#constructor)
+        /// under the same names as in `states`.
+        counters: Dict,
+    ) -> SourceResult<Content> {
+        let mut updates = vec![];
+        for (name, counter) in named_counters(counters).at(span)? {
+            let Some(state) = states.get(&name).ok() else {
+                bail!(span, "missing value for counter `{name}`");
+            };
+            let state = state.clone().cast::<CounterState>().at(span)?;
+            let is_page = counter.is_page();
+            updates.push(counter.clone().update(span, CounterUpdate::Set(state)));
+            if is_page {
+                updates.push(counter.step(span, NonZeroUsize::ONE));
+            }
+        }
+        Ok(Content::sequence(updates))
+    }
+
     /// Increases the value of the counter by one.
     ///
     /// The update will be in effect at the position where the returned content
@@ -579,6 +692,14 @@ impl Repr for Counter {
     }
 }
 
+/// Casts the values of a dictionary to counters.
+fn named_counters(counters: Dict) -> StrResult<Vec<(Str, Counter)>> {
+    counters
+        .into_iter()
+        .map(|(name, key)| Ok((name, Counter::new(key.cast()?))))
+        .collect()
+}
+
 /// Identifies a counter.
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub enum CounterKey {
@@ -11,7 +11,7 @@ use smallvec::SmallVec;
 use crate::diag::{bail, StrResult};
 use crate::foundations::{Content, Label, Repr, Selector};
 use crate::introspection::{Location, Meta};
-use crate::layout::{Frame, FrameItem, Page, Point, Position, Transform};
+use crate::layout::{Frame, FrameItem, Page, Point, Position, Size, Transform};
 use crate::model::Numbering;
 use crate::util::NonZeroExt;
 
@@ -20,13 +20,16 @@ use crate::util::NonZeroExt;
 pub struct Introspector {
     /// The number of pages in the document.
     pages: usize,
-    /// All introspectable elements.
-    elems: IndexMap<Location, (Content, Position)>,
+    /// All introspectable elements along with their positions and the sizes
+    /// of the frames they were attached to.
+    elems: IndexMap<Location, (Content, Position, Size)>,
     /// Maps labels to their indices in the element list. We use a smallvec such
     /// that if the label is unique, we don't need to allocate.
     labels: HashMap<Label, SmallVec<[usize; 1]>>,
     /// The page numberings, indexed by page number minus 1.
     page_numberings: Vec<Option<Numbering>>,
+    /// The page numbers without repeated pages, indexed by page number minus 1.
+    distinct_pages: Vec<NonZeroUsize>,
     /// Caches queries done on the introspector. This is important because
     /// even if all top-level queries are distinct, they often have shared
     /// subqueries. Example: Individual counter queries with `before` that
@@ -42,12 +45,18 @@ impl Introspector {
         self.elems.clear();
         self.labels.clear();
         self.page_numberings.clear();
+        self.distinct_pages.clear();
         self.queries.clear();
 
+        let mut distinct = NonZeroUsize::ONE;
         for (i, page) in pages.iter().enumerate() {
             let page_nr = NonZeroUsize::new(1 + i).unwrap();
             self.extract(&page.frame, page_nr, Transform::identity());
             self.page_numberings.push(page.numbering.clone());
+            if i > 0 && !page.repeated {
+                distinct = distinct.saturating_add(1);
+            }
+            self.distinct_pages.push(distinct);
         }
     }
 
@@ -61,13 +70,13 @@ impl Introspector {
                         .pre_concat(group.transform);
                     self.extract(&group.frame, page, ts);
                 }
-                FrameItem::Meta(Meta::Elem(content), _)
+                FrameItem::Meta(Meta::Elem(content), size)
                     if !self.elems.contains_key(&content.location().unwrap()) =>
                 {
                     let pos = pos.transform(ts);
                     let ret = self.elems.insert(
                         content.location().unwrap(),
-                        (content.clone(), Position { page, point: pos }),
+                        (content.clone(), Position { page, point: pos }, *size),
                     );
                     assert!(ret.is_none(), "duplicate locations");
 
@@ -83,12 +92,12 @@ impl Introspector {
 
     /// Iterate over all locatable elements.
     pub fn all(&self) -> impl Iterator<Item = &Content> + '_ {
-        self.elems.values().map(|(c, _)| c)
+        self.elems.values().map(|(c, _, _)| c)
     }
 
     /// Get an element by its location.
     fn get(&self, location: &Location) -> Option<&Content> {
-        self.elems.get(location).map(|(elem, _)| elem)
+        self.elems.get(location).map(|(elem, _, _)| elem)
     }
 
     /// Get the index of this element among all.
@@ -121,7 +130,11 @@ impl Introspector {
                     indices.iter().map(|&index| self.elems[index].0.clone()).collect()
                 })
                 .unwrap_or_default(),
-            Selector::Elem(..) | Selector::Regex(_) | Selector::Can(_) => self
+            Selector::Elem(..)
+            | Selector::Regex(_)
+            | Selector::Can(_)
+            | Selector::Within { .. }
+            | Selector::AtDepth { .. } => self
                 .all()
                 .filter(|elem| selector.matches(elem, None))
                 .cloned()
@@ -157,6 +170,16 @@ impl Introspector {
                 }
                 list
             }
+            Selector::FirstOnPage(selector) => {
+                let mut last = None;
+                self.query(selector)
+                    .into_iter()
+                    .filter(|elem| {
+                        let page = self.elems[&elem.location().unwrap()].1.page;
+                        last.replace(page) != Some(page)
+                    })
+                    .collect()
+            }
             Selector::And(selectors) => {
                 let mut results: Vec<_> =
                     selectors.iter().map(|sel| self.query(sel)).collect();
@@ -223,6 +246,17 @@ impl Introspector {
         }
     }
 
+    /// All labels in the document, ordered by their first occurrence.
+    pub fn labels(&self) -> EcoVec<Label> {
+        let mut labels: Vec<_> = self
+            .labels
+            .iter()
+            .map(|(&label, indices)| (indices[0], label))
+            .collect();
+        labels.sort_unstable_by_key(|&(index, _)| index);
+        labels.into_iter().map(|(_, label)| label).collect()
+    }
+
     /// Query for a unique element with the label.
     pub fn query_label(&self, label: Label) -> StrResult<&Content> {
         let indices = self.labels.get(&label).ok_or_else(|| {
@@ -254,13 +288,35 @@ impl Introspector {
         self.position(location).page
     }
 
+    /// Find the page number for the given location, without counting pages
+    /// that repeat a slide to reveal its content in steps.
+    pub fn distinct_page(&self, location: Location) -> NonZeroUsize {
+        let page = self.page(location);
+        self.distinct_pages.get(page.get() - 1).copied().unwrap_or(page)
+    }
+
+    /// The total number of pages, without counting pages that repeat a slide
+    /// to reveal its content in steps.
+    pub fn distinct_pages(&self) -> NonZeroUsize {
+        self.distinct_pages.last().copied().unwrap_or(NonZeroUsize::ONE)
+    }
+
     /// Find the position for the given location.
     pub fn position(&self, location: Location) -> Position {
         self.elems
             .get(&location)
-            .map(|(_, loc)| *loc)
+            .map(|(_, loc, _)| *loc)
             .unwrap_or(Position { page: NonZeroUsize::ONE, point: Point::zero() })
     }
+
+    /// Find the size of the frame the element at the given location was
+    /// attached to.
+    pub fn size(&self, location: Location) -> Size {
+        self.elems
+            .get(&location)
+            .map(|(_, _, size)| *size)
+            .unwrap_or_default()
+    }
 }
 
 impl Default for Introspector {
@@ -270,6 +326,7 @@ impl Default for Introspector {
             elems: IndexMap::new(),
             labels: HashMap::new(),
             page_numberings: vec![],
+            distinct_pages: vec![],
             queries: QueryCache::default(),
         }
     }
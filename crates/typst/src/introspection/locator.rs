@@ -5,6 +5,7 @@ use comemo::{Track, Tracked, Validate};
 
 use crate::introspection::{Location, Meta};
 use crate::layout::{Frame, FrameItem};
+use crate::util::hash128;
 
 /// Provides locations for elements in the document.
 ///
@@ -21,6 +22,12 @@ use crate::layout::{Frame, FrameItem};
 /// the other side of the memoization boundary, we
 /// [reconstruct](Self::visit_frame) them from the resulting [frames](Frame).
 ///
+/// Layout runs that happen in parallel cannot share a locator. Instead, each
+/// of them gets a [salted](Self::salted) locator, which mixes its salt into all
+/// hashes. The salts are derived from locations of the parent locator. This
+/// way, the runs' locations can't collide and stay the same no matter in which
+/// order the runs are finished.
+///
 /// [^1]: Well, we could with [`TrackedMut`](comemo::TrackedMut), but the
 /// overhead is quite high, especially since we need to save & undo the counting
 /// when only measuring.
@@ -36,6 +43,9 @@ pub struct Locator<'a> {
     /// covariant over the constraint. If it becomes invariant, we're in for a
     /// world of lifetime pain.
     outer: Option<Tracked<'a, Self, <Locator<'static> as Validate>::Constraint>>,
+    /// Mixed into the hashes of all produced locations, unless it is zero.
+    /// Inherited from the outer locator.
+    salt: u128,
 }
 
 impl<'a> Locator<'a> {
@@ -46,7 +56,17 @@ impl<'a> Locator<'a> {
 
     /// Create a new chained locator.
     pub fn chained(outer: Tracked<'a, Self>) -> Self {
-        Self { outer: Some(outer), ..Default::default() }
+        Self {
+            salt: outer.salt(),
+            outer: Some(outer),
+            ..Default::default()
+        }
+    }
+
+    /// Create a new locator whose locations are distinct from those of all
+    /// locators with a different salt.
+    pub fn salted(salt: u128) -> Self {
+        Self { salt, ..Default::default() }
     }
 
     /// Start tracking this locator.
@@ -62,6 +82,11 @@ impl<'a> Locator<'a> {
 
     /// Produce a stable identifier for this call site.
     pub fn locate(&mut self, hash: u128) -> Location {
+        let hash = match self.salt {
+            0 => hash,
+            salt => hash128(&(hash, salt)),
+        };
+
         // Get the current disambiguator for this hash.
         let disambiguator = self.disambiguator_impl(hash);
 
@@ -114,4 +139,9 @@ impl<'a> Locator<'a> {
     fn disambiguator(&self, hash: u128) -> usize {
         self.disambiguator_impl(hash)
     }
+
+    /// The salt of this locator.
+    fn salt(&self) -> u128 {
+        self.salt
+    }
 }
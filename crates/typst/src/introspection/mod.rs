@@ -1,5 +1,6 @@
 //! Interaction between document parts.
 
+mod anchor;
 mod counter;
 #[path = "here.rs"]
 mod here_;
@@ -9,10 +10,15 @@ mod locate_;
 mod location;
 mod locator;
 mod metadata;
+#[path = "page_count.rs"]
+mod page_count_;
+#[path = "page_marks.rs"]
+mod page_marks_;
 #[path = "query.rs"]
 mod query_;
 mod state;
 
+pub use self::anchor::*;
 pub use self::counter::*;
 pub use self::here_::*;
 pub use self::introspector::*;
@@ -20,10 +26,13 @@ pub use self::locate_::*;
 pub use self::location::*;
 pub use self::locator::*;
 pub use self::metadata::*;
+pub use self::page_count_::*;
+pub use self::page_marks_::*;
 pub use self::query_::*;
 pub use self::state::*;
 
 use std::fmt::{self, Debug, Formatter};
+use std::num::NonZeroUsize;
 
 use ecow::{eco_format, EcoString};
 use smallvec::SmallVec;
@@ -54,9 +63,12 @@ pub fn define(global: &mut Scope) {
     global.define_type::<Counter>();
     global.define_type::<State>();
     global.define_elem::<MetadataElem>();
+    global.define_elem::<AnchorElem>();
     global.define_func::<here>();
     global.define_func::<query>();
     global.define_func::<locate>();
+    global.define_func::<page_count>();
+    global.define_func::<page_marks>();
 }
 
 /// Hosts metadata and ensures metadata is produced even for empty elements.
@@ -98,6 +110,9 @@ pub enum Meta {
     /// in the final frames as it is removed alongside the content that should
     /// be hidden.
     Hide,
+    /// Indicates that content is only revealed from the given step of a slide
+    /// onwards.
+    Reveal(NonZeroUsize),
 }
 
 impl Debug for Meta {
@@ -106,6 +121,7 @@ impl Debug for Meta {
             Self::Link(dest) => write!(f, "Link({dest:?})"),
             Self::Elem(content) => write!(f, "Elem({:?})", content.func()),
             Self::Hide => f.pad("Hide"),
+            Self::Reveal(step) => write!(f, "Reveal({step})"),
         }
     }
 }
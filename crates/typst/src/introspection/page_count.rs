@@ -0,0 +1,41 @@
+use comemo::Tracked;
+
+use crate::diag::{At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{func, Context};
+use crate::syntax::Span;
+
+/// Provides the total number of pages in the document.
+///
+/// In contrast to the final value of the [page counter]($counter), this is
+/// not affected by updates of the counter, for instance to restart the
+/// numbering after a preface. Pages that repeat a [slide]($slide) to reveal
+/// its content in steps are not counted.
+///
+/// To display the page count in a specific format, combine it with the
+/// [`numbering`] function.
+///
+/// # Example
+/// ```example
+/// #set page(
+///   height: 80pt,
+///   footer: context [
+///     Page #counter(page).display()
+///     of #numbering("I", page-count())
+///   ],
+/// )
+///
+/// #lorem(20)
+/// ```
+#[func(contextual)]
+pub fn page_count(
+    /// The engine.
+    engine: &mut Engine,
+    /// The callsite context.
+    context: Tracked<Context>,
+    /// The callsite span.
+    span: Span,
+) -> SourceResult<usize> {
+    context.introspect().at(span)?;
+    Ok(engine.introspector.distinct_pages().get())
+}
@@ -0,0 +1,110 @@
+use std::num::NonZeroUsize;
+
+use comemo::Tracked;
+
+use crate::diag::HintedStrResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    dict, func, Content, Context, Dict, LocatableSelector, NativeElement, Selector,
+};
+use crate::introspection::Introspector;
+use crate::model::HeadingElem;
+
+/// Finds the first and last marks on the current page.
+///
+/// Running headers typically show the chapter or section the reader is
+/// currently in. Instead of querying for headings and comparing page numbers
+/// yourself, you can ask for the marks of the current page. By default, the
+/// marks are headings, but any [locatable]($location/#locatable) selector
+/// works, e.g. a label attached to invisible [metadata].
+///
+/// The result is a dictionary with three entries, each of which is the
+/// element or `{none}`:
+///
+/// - `first`: The first mark on the current page.
+/// - `last`: The last mark on the current page.
+/// - `previous`: The last mark on any of the preceding pages. This is the
+///   mark that is still "active" at the top of the current page.
+///
+/// # Example
+/// ```example
+/// >>> #set page(
+/// >>>   width: 200pt,
+/// >>>   height: 120pt,
+/// >>>   margin: (top: 35pt, rest: 15pt),
+/// >>>   header-ascent: 12pt,
+/// >>> )
+/// #set page(header: context {
+///   let marks = page-marks(heading)
+///   let current = marks.first
+///   if current == none { current = marks.previous }
+///   if current != none [
+///     #h(1fr) _#current.body _
+///   ]
+/// })
+///
+/// = Introduction
+/// #lorem(20)
+///
+/// = Background
+/// #lorem(30)
+/// ```
+#[func(contextual)]
+pub fn page_marks(
+    /// The engine.
+    engine: &mut Engine,
+    /// The callsite context.
+    context: Tracked<Context>,
+    /// The elements that serve as marks.
+    ///
+    /// Only [locatable]($location/#locatable) element functions are supported.
+    #[default(LocatableSelector(Selector::Elem(HeadingElem::elem(), None)))]
+    target: LocatableSelector,
+) -> HintedStrResult<Dict> {
+    let page = engine.introspector.page(context.location()?);
+    Ok(PageMarks::new(engine.introspector, page, &target.0).into_dict())
+}
+
+/// The first and last marks on a page and the last mark before it.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct PageMarks {
+    /// The first mark on the page.
+    pub first: Option<Content>,
+    /// The last mark on the page.
+    pub last: Option<Content>,
+    /// The last mark on any of the preceding pages.
+    pub previous: Option<Content>,
+}
+
+impl PageMarks {
+    /// Find the elements matching the selector that serve as the marks of
+    /// the given page.
+    pub fn new(
+        introspector: Tracked<Introspector>,
+        page: NonZeroUsize,
+        selector: &Selector,
+    ) -> Self {
+        let mut marks = Self::default();
+        for elem in introspector.query(selector) {
+            let Some(loc) = elem.location() else { continue };
+            let other = introspector.page(loc);
+            if other < page {
+                marks.previous = Some(elem);
+            } else if other == page {
+                marks.first.get_or_insert_with(|| elem.clone());
+                marks.last = Some(elem);
+            }
+        }
+        marks
+    }
+
+    /// Turn the marks into a dictionary with the `first`, `last`, and
+    /// `previous` entries.
+    pub fn into_dict(self) -> Dict {
+        dict! {
+            "first" => self.first,
+            "last" => self.last,
+            "previous" => self.previous,
+        }
+    }
+}
@@ -160,6 +160,24 @@ use crate::World;
 /// #compute("x - 5")
 /// ```
 ///
+/// # Scoped state { #scoped }
+/// Sometimes, a setting should only apply to a part of the document, for
+/// example a single chapter. With [`scope`]($state.scope), all updates within
+/// a piece of content are undone once it ends, so that they can't leak into
+/// the rest of the document. To go back to the value a state had at some
+/// earlier point, you can instead use [`restore`]($state.restore).
+///
+/// ```example
+/// #let lang = state("lang", "English")
+///
+/// #lang.scope[
+///   #lang.update("German")
+///   Inside: #context lang.get()
+/// ]
+///
+/// Outside: #context lang.get()
+/// ```
+///
 /// # A word of caution { #caution }
 /// To resolve the values of all states, Typst evaluates parts of your code
 /// multiple times. However, there is no guarantee that your state manipulation
@@ -243,6 +261,7 @@ impl State {
         };
         let mut state = self.init.clone();
         let mut stops = eco_vec![state.clone()];
+        let mut saved = vec![];
 
         for elem in introspector.query(&self.selector()) {
             let elem = elem.to_packed::<StateUpdateElem>().unwrap();
@@ -251,6 +270,28 @@ impl State {
                 StateUpdate::Func(func) => {
                     state = func.call(&mut engine, Context::none().track(), [state])?
                 }
+                StateUpdate::Save => saved.push(state.clone()),
+                StateUpdate::Discard => {
+                    if let Some(value) = saved.pop() {
+                        state = value;
+                    }
+                }
+                StateUpdate::Restore(target) => {
+                    let span = elem.span();
+                    let target = introspector.query_unique(target).at(span)?;
+                    let offset = introspector
+                        .query(
+                            &self
+                                .selector()
+                                .before(target.location().unwrap().into(), true),
+                        )
+                        .len();
+                    state = stops
+                        .get(offset)
+                        .ok_or("cannot restore state from a later location")
+                        .at(span)?
+                        .clone();
+                }
             }
             stops.push(state.clone());
         }
@@ -364,6 +405,66 @@ impl State {
         StateUpdateElem::new(self.key, update).pack().spanned(span)
     }
 
+    /// Confines all updates of the state within the given content to it.
+    ///
+    /// Once the content ends, the state takes on the value it had before the
+    /// content again. Updates to other states are not affected.
+    ///
+    /// ```example
+    /// #let s = state("x", 1)
+    /// #s.scope[
+    ///   #s.update(x => x + 1)
+    ///   #context s.get()
+    /// ]
+    /// #context s.get()
+    /// ```
+    #[func]
+    pub fn scope(
+        self,
+        /// The span of the `scope` call.
+        span: Span,
+        /// The content within which updates should stay confined.
+        body: Content,
+    ) -> Content {
+        Content::sequence([
+            StateUpdateElem::new(self.key.clone(), StateUpdate::Save)
+                .pack()
+                .spanned(span),
+            body,
+            StateUpdateElem::new(self.key, StateUpdate::Discard)
+                .pack()
+                .spanned(span),
+        ])
+    }
+
+    /// Resets the state to the value it had at the given selector's unique
+    /// match.
+    ///
+    /// Like [`update`]($state.update), this takes effect where the returned
+    /// content is inserted into the document. The `target` must lie before
+    /// that position.
+    ///
+    /// ```example
+    /// #let s = state("x", 1)
+    /// #s.update(2) <start>
+    /// #s.update(5)
+    /// #s.restore(<start>)
+    /// #context s.get()
+    /// ```
+    #[func]
+    pub fn restore(
+        self,
+        /// The span of the `restore` call.
+        span: Span,
+        /// The place whose value to go back to. The state is reset to its value
+        /// after all updates up to and including this place.
+        target: LocatableSelector,
+    ) -> Content {
+        StateUpdateElem::new(self.key, StateUpdate::Restore(target.0))
+            .pack()
+            .spanned(span)
+    }
+
     /// Displays the current value of the state.
     ///
     /// **Deprecation planned:** Use [`get`]($state.get) instead.
@@ -395,6 +496,12 @@ pub enum StateUpdate {
     Set(Value),
     /// Apply the given function to the state.
     Func(Func),
+    /// Remember the current value of the state at the start of a scope.
+    Save,
+    /// Go back to the value remembered at the start of the innermost scope.
+    Discard,
+    /// Go back to the value the state had at the given selector's match.
+    Restore(Selector),
 }
 
 cast! {
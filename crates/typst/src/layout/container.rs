@@ -1,12 +1,15 @@
 use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, AutoValue, Content, Packed, Resolve, Smart, StyleChain, Value,
+    cast, elem, AutoValue, Cast, Content, NativeElement, Packed, Resolve, Smart,
+    StyleChain, Value,
 };
+use crate::introspection::Anchors;
 use crate::layout::{
-    Abs, Axes, Corners, Em, Fr, Fragment, Frame, FrameKind, LayoutMultiple, Length,
-    Ratio, Regions, Rel, Sides, Size, Spacing, VElem,
+    Abs, Axes, Corners, Em, Fr, Fragment, Frame, FrameKind, LayoutMultiple, LayoutSingle,
+    Length, Ratio, Regions, Rel, Sides, Size, Spacing, Transform, VElem,
 };
+use crate::text::TextElem;
 use crate::util::Numeric;
 use crate::visualize::{clip_rect, Paint, Stroke};
 
@@ -104,6 +107,23 @@ pub struct BoxElem {
     #[default(false)]
     pub clip: bool,
 
+    /// Named points on the box, which can be [queried]($anchor).
+    ///
+    /// Each anchor is given as a pair of relative lengths, which are resolved
+    /// relative to the box's size, starting from its top-left corner.
+    ///
+    /// ```example
+    /// #box(
+    ///   width: 40pt,
+    ///   height: 20pt,
+    ///   fill: aqua,
+    ///   anchors: (tip: (100%, 0%)),
+    /// ) <mark>
+    ///
+    /// #context query(anchor.where(of: <mark>)).first().name
+    /// ```
+    pub anchors: Anchors,
+
     /// The contents of the box.
     #[positional]
     pub body: Option<Content>,
@@ -175,7 +195,9 @@ impl Packed<BoxElem> {
             frame.fill_and_stroke(fill, stroke, outset, radius, self.span());
         }
 
-        // Apply metadata.
+        // Apply anchors and metadata.
+        self.anchors(styles)
+            .apply(engine, styles, &mut frame, self.label(), self.span());
         frame.set_kind(FrameKind::Hard);
 
         Ok(frame)
@@ -332,6 +354,59 @@ pub struct BlockElem {
     #[default(false)]
     pub clip: bool,
 
+    /// What to do if the block's contents don't fit into it.
+    ///
+    /// With `{"shrink"}`, the contents of the block are scaled down until they
+    /// fit into the block's width and height, but not further than the
+    /// [`min-text-size`]($block.min-text-size) allows. Text reflows to make use
+    /// of the space gained by scaling it down. This is useful for badges,
+    /// labels, and table cells with a fixed size. Table and grid cells also
+    /// have a [`fit`]($table.cell.fit) property.
+    ///
+    /// ```example
+    /// #let badge(body) = block(
+    ///   width: 60pt,
+    ///   height: 20pt,
+    ///   inset: 4pt,
+    ///   fill: aqua,
+    ///   fit: "shrink",
+    ///   align(horizon, body),
+    /// )
+    ///
+    /// #badge[Typst]
+    /// #badge[Typesetting system]
+    /// ```
+    #[default(Fit::Overflow)]
+    pub fit: Fit,
+
+    /// The smallest text size to shrink text to when the block's
+    /// [`fit`]($block.fit) is `{"shrink"}`.
+    ///
+    /// Since the contents are scaled as a whole, this limits how far the
+    /// block's own text size is scaled down. If the contents don't fit even at
+    /// this size, they overflow the block.
+    #[resolve]
+    #[default(Abs::pt(4.0).into())]
+    pub min_text_size: Length,
+
+    /// Named points on the block, which can be [queried]($anchor).
+    ///
+    /// Each anchor is given as a pair of relative lengths, which are resolved
+    /// relative to the block's size, starting from its top-left corner. If the
+    /// block breaks across pages, the anchors are placed on its first part.
+    ///
+    /// ```example
+    /// #block(
+    ///   width: 40pt,
+    ///   height: 20pt,
+    ///   fill: aqua,
+    ///   anchors: (tip: (100%, 0%)),
+    /// ) <mark>
+    ///
+    /// #context query(anchor.where(of: <mark>)).first().name
+    /// ```
+    pub anchors: Anchors,
+
     /// The contents of the block.
     #[positional]
     pub body: Option<Content>,
@@ -353,12 +428,8 @@ impl LayoutMultiple for Packed<BlockElem> {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
-        // Apply inset.
         let mut body = self.body(styles).unwrap_or_default();
         let inset = self.inset(styles).unwrap_or_default();
-        if inset.iter().any(|v| !v.is_zero()) {
-            body = body.clone().padded(inset.map(|side| side.map(Length::from)));
-        }
 
         // Resolve the sizing to a concrete size.
         let sizing = Axes::new(self.width(styles), self.height(styles));
@@ -368,6 +439,17 @@ impl LayoutMultiple for Packed<BlockElem> {
             .zip_map(regions.base(), |s, b| s.map(|v| v.relative_to(b)))
             .unwrap_or(regions.base());
 
+        // Scale the body down until it fits, if requested.
+        if self.fit(styles) == Fit::Shrink {
+            let min = self.min_text_size(styles);
+            body = ShrinkElem::new(min.into(), body).pack().spanned(self.span());
+        }
+
+        // Apply inset.
+        if inset.iter().any(|v| !v.is_zero()) {
+            body = body.clone().padded(inset.map(|side| side.map(Length::from)));
+        }
+
         // Layout the child.
         let mut frames = if self.breakable(styles) {
             // Measure to ensure frames for all regions have the same width.
@@ -439,13 +521,14 @@ impl LayoutMultiple for Packed<BlockElem> {
             }
         }
 
+        // Skip the first frame if the block was moved to the next region.
+        let mut skip = false;
+        if let [first, rest @ ..] = frames.as_slice() {
+            skip = first.is_empty() && rest.iter().any(|frame| !frame.is_empty());
+        }
+
         // Add fill and/or stroke.
         if fill.is_some() || stroke.iter().any(Option::is_some) {
-            let mut skip = false;
-            if let [first, rest @ ..] = frames.as_slice() {
-                skip = first.is_empty() && rest.iter().any(|frame| !frame.is_empty());
-            }
-
             let outset = self.outset(styles).unwrap_or_default();
             let radius = self.radius(styles).unwrap_or_default();
             for frame in frames.iter_mut().skip(skip as usize) {
@@ -459,7 +542,15 @@ impl LayoutMultiple for Packed<BlockElem> {
             }
         }
 
-        // Apply metadata.
+        // Apply anchors and metadata.
+        let anchors = self.anchors(styles);
+        anchors.apply(
+            engine,
+            styles,
+            &mut frames[skip as usize],
+            self.label(),
+            self.span(),
+        );
         for frame in &mut frames {
             frame.set_kind(FrameKind::Hard);
         }
@@ -468,6 +559,86 @@ impl LayoutMultiple for Packed<BlockElem> {
     }
 }
 
+/// What to do with contents that don't fit into their container.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Fit {
+    /// The contents overflow the container.
+    #[default]
+    Overflow,
+    /// The contents are scaled down until they fit.
+    Shrink,
+}
+
+/// Scales its body down until it fits into the available space.
+#[elem(LayoutSingle)]
+struct ShrinkElem {
+    /// The text size below which the body is not scaled down further.
+    #[required]
+    min_text_size: Length,
+
+    /// The content to shrink.
+    #[required]
+    body: Content,
+}
+
+impl LayoutSingle for Packed<ShrinkElem> {
+    #[typst_macros::time(name = "shrink", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Frame> {
+        let size = regions.base();
+        let body = self.body();
+
+        // Measures the body when it is scaled by the given factor. It then
+        // reflows to fill the available width once scaled.
+        let measure = |engine: &mut Engine, factor: f64| -> SourceResult<Size> {
+            let pod =
+                Regions::one(Size::new(size.x / factor, Abs::inf()), Axes::splat(false));
+            Ok(body.measure(engine, styles, pod)?.into_frame().size())
+        };
+
+        // Whether a frame of the given size fits into the available space.
+        let fits = |natural: Size| size.x.fits(natural.x) && size.y.fits(natural.y);
+
+        let mut factor = 1.0;
+        if size.x.is_finite() {
+            let min = self.min_text_size().resolve(styles);
+            let lower = (min / TextElem::size_in(styles)).min(1.0);
+
+            // Estimate the factor from the overflow, assuming that the body's
+            // area stays about the same when it reflows. Because the estimate
+            // is refined with each attempt, a few layouts suffice.
+            let mut natural = measure(engine, factor)?;
+            for _ in 0..4 {
+                if fits(natural * factor) || factor <= lower {
+                    break;
+                }
+                let scaled = natural * factor;
+                let x = size.x / scaled.x;
+                let y = (size.y / scaled.y).sqrt();
+                factor = (factor * x.min(y)).max(lower);
+                natural = measure(engine, factor)?;
+            }
+
+            // Ensure that the last attempt fits without reflowing it again.
+            factor = factor.min(size.x / natural.x).min(size.y / natural.y).max(lower);
+        }
+
+        // Lay the body out in the enlarged region and scale it down.
+        let pod = Regions::one(size / factor, regions.expand);
+        let mut frame = body.layout(engine, styles, pod)?.into_frame();
+        if factor < 1.0 {
+            let size = frame.size() * factor;
+            frame.transform(Transform::scale(Ratio::new(factor), Ratio::new(factor)));
+            frame.set_size(size);
+        }
+        Ok(frame)
+    }
+}
+
 /// Defines how to size a grid cell along an axis.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Sizing {
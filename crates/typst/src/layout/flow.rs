@@ -363,7 +363,7 @@ impl<'a> FlowLayouter<'a> {
         for (i, mut frame) in fragment.into_iter().enumerate() {
             // Find footnotes in the frame.
             if self.root {
-                find_footnotes(&mut notes, &frame);
+                find_footnotes(&mut notes, &frame, false);
             }
 
             if i > 0 {
@@ -414,7 +414,7 @@ impl<'a> FlowLayouter<'a> {
                 self.regions.size.y -= height;
                 if self.root && movable {
                     let mut notes = Vec::new();
-                    find_footnotes(&mut notes, frame);
+                    find_footnotes(&mut notes, frame, false);
                     self.items.push(item);
                     if !self.handle_footnotes(engine, &mut notes, true, false)? {
                         let item = self.items.pop();
@@ -426,7 +426,16 @@ impl<'a> FlowLayouter<'a> {
                     return Ok(());
                 }
             }
-            FlowItem::Placed { float: false, .. } => {}
+            FlowItem::Placed { ref frame, float: false, .. } => {
+                // Find footnotes in the frame.
+                if self.root {
+                    let mut notes = vec![];
+                    find_footnotes(&mut notes, frame, false);
+                    self.items.push(item);
+                    self.try_handle_footnotes(engine, notes)?;
+                    return Ok(());
+                }
+            }
             FlowItem::Placed {
                 ref mut frame,
                 ref mut y_align,
@@ -464,13 +473,20 @@ impl<'a> FlowLayouter<'a> {
                     frame.translate(Point::with_y(clearance));
                 }
 
+                // Find footnotes in the frame. If they don't fit, move the
+                // float to the next region to keep it together with them.
+                let force = self.regions.in_last();
+                let prev = (self.regions.size.y, self.has_footnotes);
                 self.regions.size.y -= frame.height();
-
-                // Find footnotes in the frame.
                 if self.root {
                     let mut notes = vec![];
-                    find_footnotes(&mut notes, frame);
-                    self.try_handle_footnotes(engine, notes)?;
+                    find_footnotes(&mut notes, frame, false);
+                    self.items.push(item);
+                    if !self.handle_footnotes(engine, &mut notes, true, force)? {
+                        (self.regions.size.y, self.has_footnotes) = prev;
+                        self.pending_floats.extend(self.items.pop());
+                    }
+                    return Ok(());
                 }
             }
             FlowItem::Footnote(_) => {}
@@ -711,7 +727,7 @@ impl FlowLayouter<'_> {
 
             let prev = notes.len();
             for (i, frame) in frames.into_iter().enumerate() {
-                find_footnotes(notes, &frame);
+                find_footnotes(notes, &frame, false);
                 if i > 0 {
                     self.finish_region(engine, false)?;
                     self.layout_footnote_separator(engine)?;
@@ -753,17 +769,27 @@ impl FlowLayouter<'_> {
 }
 
 /// Finds all footnotes in the frame.
-fn find_footnotes(notes: &mut Vec<Packed<FootnoteElem>>, frame: &Frame) {
+///
+/// Depending on `table_notes`, this either finds only the footnotes that are
+/// listed below a table or only those that are listed at the bottom of the
+/// page.
+pub(crate) fn find_footnotes(
+    notes: &mut Vec<Packed<FootnoteElem>>,
+    frame: &Frame,
+    table_notes: bool,
+) {
     for (_, item) in frame.items() {
         match item {
-            FrameItem::Group(group) => find_footnotes(notes, &group.frame),
+            FrameItem::Group(group) => find_footnotes(notes, &group.frame, table_notes),
             FrameItem::Meta(Meta::Elem(content), _)
                 if !notes.iter().any(|note| note.location() == content.location()) =>
             {
                 let Some(footnote) = content.to_packed::<FootnoteElem>() else {
                     continue;
                 };
-                notes.push(footnote.clone());
+                if footnote.table_note(StyleChain::default()) == table_notes {
+                    notes.push(footnote.clone());
+                }
             }
             _ => {}
         }
@@ -7,13 +7,14 @@ use std::sync::Arc;
 use crate::foundations::{cast, dict, Dict, StyleChain, Value};
 use crate::introspection::{Meta, MetaElem};
 use crate::layout::{
-    Abs, Axes, Corners, FixedAlignment, Length, Point, Rel, Sides, Size, Transform,
+    Abs, Axes, Corners, FixedAlignment, Length, Point, Ratio, Rel, Sides, Size, Transform,
 };
 use crate::syntax::Span;
 use crate::text::TextItem;
 use crate::util::Numeric;
 use crate::visualize::{
-    ellipse, styled_rect, Color, FixedStroke, Geometry, Image, Paint, Path, Shape,
+    ellipse, styled_rect, BlendMode, Color, FixedStroke, Geometry, Image, Paint, Path,
+    Shape,
 };
 
 /// A finished layout with items at fixed positions.
@@ -291,17 +292,62 @@ impl Frame {
     /// Attach metadata from an iterator.
     pub fn meta_iter(&mut self, iter: impl IntoIterator<Item = Meta>) {
         let mut hide = false;
+        let mut reveal = None;
         let size = self.size;
-        self.prepend_multiple(iter.into_iter().filter_map(|meta| {
-            if matches!(meta, Meta::Hide) {
+        self.prepend_multiple(iter.into_iter().filter_map(|meta| match meta {
+            Meta::Hide => {
                 hide = true;
                 None
-            } else {
-                Some((Point::zero(), FrameItem::Meta(meta, size)))
             }
+            Meta::Reveal(step) => {
+                reveal = reveal.max(Some(step));
+                None
+            }
+            meta => Some((Point::zero(), FrameItem::Meta(meta, size))),
         }));
         if hide {
             self.hide();
+        } else if let Some(step) = reveal {
+            // Wrap the content into a hard frame so that it stays together
+            // when this frame is inlined into its parent.
+            let mut inner = Frame::hard(size);
+            inner.baseline = self.baseline;
+            inner.items = std::mem::take(&mut self.items);
+            inner.prepend(Point::zero(), FrameItem::Meta(Meta::Reveal(step), size));
+            self.push(Point::zero(), FrameItem::Group(GroupItem::new(inner)));
+        }
+    }
+
+    /// The number of steps in which the frame's content is revealed.
+    pub fn steps(&self) -> usize {
+        self.items()
+            .map(|(_, item)| match item {
+                FrameItem::Group(group) => group.frame.steps(),
+                FrameItem::Meta(Meta::Reveal(step), _) => step.get(),
+                _ => 1,
+            })
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Hide all content that is only revealed after the given step, but keep
+    /// metadata.
+    pub fn reveal(&mut self, step: usize) {
+        if self.steps() <= step {
+            return;
+        }
+
+        if self.items().any(|(_, item)| {
+            matches!(item, FrameItem::Meta(Meta::Reveal(s), _) if s.get() > step)
+        }) {
+            self.hide();
+            return;
+        }
+
+        for (_, item) in Arc::make_mut(&mut self.items) {
+            if let FrameItem::Group(group) = item {
+                group.frame.reveal(step);
+            }
         }
     }
 
@@ -362,6 +408,42 @@ impl Frame {
         }
     }
 
+    /// Blend the contents of the frame with what is behind it.
+    ///
+    /// If `isolated` is true, the contents are first composited into a single
+    /// layer, which is then blended as a whole. Otherwise, each item is
+    /// blended individually.
+    pub fn blend(&mut self, mode: BlendMode, isolated: bool) {
+        if !self.is_empty() && (mode != BlendMode::Normal || isolated) {
+            self.group(|g| {
+                g.blend_mode = mode;
+                g.isolated = isolated;
+            });
+        }
+    }
+
+    /// Make the contents of the frame translucent as a whole.
+    ///
+    /// The contents are first composited into a single layer, which is then
+    /// drawn with the given opacity. Overlapping parts of the contents thus
+    /// don't shine through each other.
+    pub fn opacity(&mut self, opacity: Ratio) {
+        if !self.is_empty() && opacity != Ratio::one() {
+            self.group(|g| {
+                g.opacity = opacity;
+                g.isolated = true;
+            });
+        }
+    }
+
+    /// Blur the contents of the frame with a gaussian blur of the given
+    /// standard deviation.
+    pub fn blur(&mut self, radius: Abs) {
+        if !self.is_empty() && radius > Abs::zero() {
+            self.group(|g| g.blur = radius);
+        }
+    }
+
     /// Wrap the frame's contents in a group and modify that group with `f`.
     fn group<F>(&mut self, f: F)
     where
@@ -504,6 +586,17 @@ pub struct GroupItem {
     pub transform: Transform,
     /// Whether the frame should be a clipping boundary.
     pub clip_path: Option<Path>,
+    /// How to blend the group's contents with what is behind it.
+    pub blend_mode: BlendMode,
+    /// Whether the group's contents are composited in isolation before being
+    /// blended with what is behind it.
+    pub isolated: bool,
+    /// The opacity with which the group's composited contents are drawn.
+    /// Only has an effect if the group is isolated.
+    pub opacity: Ratio,
+    /// The standard deviation of a gaussian blur to apply to the group's
+    /// contents. Zero if the group is not blurred.
+    pub blur: Abs,
 }
 
 impl GroupItem {
@@ -513,6 +606,10 @@ impl GroupItem {
             frame,
             transform: Transform::identity(),
             clip_path: None,
+            blend_mode: BlendMode::Normal,
+            isolated: false,
+            opacity: Ratio::one(),
+            blur: Abs::zero(),
         }
     }
 }
@@ -17,16 +17,18 @@ use smallvec::{smallvec, SmallVec};
 use crate::diag::{bail, SourceResult, StrResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Array, Content, Fold, Packed, Show, Smart, StyleChain, Value,
+    cast, elem, scope, Array, Content, Fold, NativeElement, Packed, Show, Smart,
+    StyleChain, Value,
 };
 use crate::layout::{
-    Abs, AlignElem, Alignment, Axes, Dir, Fragment, LayoutMultiple, Length,
-    OuterHAlignment, OuterVAlignment, Regions, Rel, Sides, Sizing,
+    Abs, AlignElem, Alignment, Angle, Axes, BlockElem, Dir, Fit, Fragment,
+    LayoutMultiple, Length, OuterHAlignment, OuterVAlignment, Regions, Rel, RotateElem,
+    Sides, Sizing,
 };
 use crate::model::{TableCell, TableFooter, TableHLine, TableHeader, TableVLine};
 use crate::syntax::Span;
 use crate::text::TextElem;
-use crate::util::NonZeroExt;
+use crate::util::{NonZeroExt, Numeric};
 use crate::visualize::{Paint, Stroke};
 
 /// Arranges content in a grid.
@@ -819,6 +821,41 @@ pub struct GridCell {
     /// unbreakable, while a cell spanning at least one `{auto}`-sized row is
     /// breakable.
     pub breakable: Smart<bool>,
+
+    /// How much to rotate the cell's content.
+    ///
+    /// The cell is sized to fit the rotated content, so that vertical header
+    /// cells in wide tables take up just as much space as they need. The
+    /// rotated content may still span multiple lines.
+    ///
+    /// ```example
+    /// #grid(
+    ///   columns: 4,
+    ///   align: bottom,
+    ///   [],
+    ///   ..([Red], [Green], [Blue]).map(grid.cell.with(rotate: -90deg)),
+    ///   [Apple], [Yes], [Yes], [No],
+    /// )
+    /// ```
+    #[default(Angle::zero())]
+    pub rotate: Angle,
+
+    /// What to do if the cell's content doesn't fit into it.
+    ///
+    /// With `{"shrink"}`, the cell's content is scaled down until it fits
+    /// into the cell, which is most useful for rows with a fixed height. See
+    /// the [block's `fit`]($block.fit) for more details.
+    ///
+    /// ```example
+    /// #grid(
+    ///   columns: (60pt, 60pt),
+    ///   rows: 20pt,
+    ///   [Overflowing content],
+    ///   grid.cell(fit: "shrink")[Shrunk content],
+    /// )
+    /// ```
+    #[default(Fit::Overflow)]
+    pub fit: Fit,
 }
 
 cast! {
@@ -927,7 +964,13 @@ impl ResolvableCell for Packed<GridCell> {
 
 impl Show for Packed<GridCell> {
     fn show(&self, _engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
-        show_grid_cell(self.body().clone(), self.inset(styles), self.align(styles))
+        show_grid_cell(
+            self.body().clone(),
+            self.inset(styles),
+            self.align(styles),
+            self.rotate(styles),
+            self.fit(styles),
+        )
     }
 }
 
@@ -943,7 +986,17 @@ pub fn show_grid_cell(
     mut body: Content,
     inset: Smart<Sides<Option<Rel<Length>>>>,
     align: Smart<Alignment>,
+    rotate: Angle,
+    fit: Fit,
 ) -> SourceResult<Content> {
+    if !rotate.is_zero() {
+        body = RotateElem::new(body).with_angle(rotate).with_reflow(true).pack();
+    }
+
+    if fit == Fit::Shrink {
+        body = BlockElem::new().with_body(Some(body)).with_fit(fit).pack();
+    }
+
     let inset = inset.unwrap_or_default().map(Option::unwrap_or_default);
 
     if inset != Sides::default() {
@@ -23,7 +23,8 @@ use crate::math::{EquationElem, MathParItem};
 use crate::model::{Linebreaks, ParElem};
 use crate::syntax::Span;
 use crate::text::{
-    Lang, LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes, SpaceElem, TextElem,
+    CjkLatinSpacing, Lang, LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes,
+    SpaceElem, TextElem,
 };
 use crate::util::Numeric;
 use crate::World;
@@ -69,8 +70,15 @@ pub(crate) fn layout_inline(
         // each and every line from scratch.
         let p = prepare(&mut engine, children, &text, segments, spans, styles, region)?;
 
-        // Break the paragraph into lines.
-        let lines = linebreak(&engine, &p, region.x - p.hang);
+        // Break the paragraph into lines. On a character grid, lines only
+        // hold whole cells.
+        let mut width = region.x - p.hang;
+        if let Some(pitch) = p.char_grid.filter(|pitch| *pitch > Abs::zero()) {
+            if width.is_finite() {
+                width = pitch * (width / pitch).floor();
+            }
+        }
+        let lines = linebreak(&engine, &p, width);
 
         // Stack the lines into one frame per region.
         finalize(&mut engine, &p, &lines, region, expand)
@@ -126,6 +134,8 @@ struct Preparation<'a> {
     hang: Abs,
     /// Whether to add spacing between CJK and Latin characters.
     cjk_latin_spacing: bool,
+    /// The width of the cells if CJK text is laid out on a character grid.
+    char_grid: Option<Abs>,
     /// Whether font fallback is enabled for this paragraph.
     fallback: bool,
     /// The leading of the paragraph.
@@ -624,9 +634,14 @@ fn prepare<'a>(
         cursor = end;
     }
 
-    let cjk_latin_spacing = TextElem::cjk_latin_spacing_in(styles).is_auto();
-    if cjk_latin_spacing {
-        add_cjk_latin_spacing(&mut items);
+    let char_grid = ParElem::char_grid_in(styles);
+    let cjk_latin_spacing = match TextElem::cjk_latin_spacing_in(styles) {
+        Smart::Auto => Some(CjkLatinSpacing::default()),
+        Smart::Custom(spacing) => spacing,
+    }
+    .filter(|_| char_grid.is_none());
+    if let Some(spacing) = &cjk_latin_spacing {
+        add_cjk_latin_spacing(&mut items, spacing);
     }
 
     Ok(Preparation {
@@ -636,9 +651,10 @@ fn prepare<'a>(
         hyphenate: shared_get(styles, children, TextElem::hyphenate_in),
         lang: shared_get(styles, children, TextElem::lang_in),
         align: AlignElem::alignment_in(styles).resolve(styles).x,
-        justify: ParElem::justify_in(styles),
+        justify: ParElem::justify_in(styles) && char_grid.is_none(),
         hang: ParElem::hanging_indent_in(styles),
-        cjk_latin_spacing,
+        cjk_latin_spacing: cjk_latin_spacing.is_some(),
+        char_grid,
         fallback: TextElem::fallback_in(styles),
         leading: ParElem::leading_in(styles),
         linebreaks: ParElem::linebreaks_in(styles),
@@ -649,7 +665,7 @@ fn prepare<'a>(
 /// Add some spacing between Han characters and western characters.
 /// See Requirements for Chinese Text Layout, Section 3.2.2 Mixed Text Composition in Horizontal
 /// Written Mode
-fn add_cjk_latin_spacing(items: &mut [Item]) {
+fn add_cjk_latin_spacing(items: &mut [Item], spacing: &CjkLatinSpacing) {
     let mut items = items.iter_mut().filter(|x| !matches!(x, Item::Meta(_))).peekable();
     let mut prev: Option<&ShapedGlyph> = None;
     while let Some(item) = items.next() {
@@ -658,6 +674,14 @@ fn add_cjk_latin_spacing(items: &mut [Item]) {
             continue;
         };
 
+        // The spacing can be shrunk to half of its width.
+        let width = Em::from_length(spacing.width.at(text.size), text.size);
+        let spaced = |a: &ShapedGlyph, b: &ShapedGlyph| {
+            b.is_letter_or_number()
+                && !spacing.except.contains(a.c)
+                && !spacing.except.contains(b.c)
+        };
+
         // Since we only call this function in [`prepare`], we can assume
         // that the Cow is owned, and `to_mut` can be called without overhead.
         debug_assert!(matches!(text.glyphs, std::borrow::Cow::Owned(_)));
@@ -672,19 +696,18 @@ fn add_cjk_latin_spacing(items: &mut [Item]) {
             });
 
             // Case 1: CJ followed by a Latin character
-            if glyph.is_cj_script() && next.is_some_and(|g| g.is_letter_or_number()) {
-                // The spacing is default to 1/4 em, and can be shrunk to 1/8 em.
-                glyph.x_advance += Em::new(0.25);
-                glyph.adjustability.shrinkability.1 += Em::new(0.125);
-                text.width += Em::new(0.25).at(text.size);
+            if glyph.is_cj_script() && next.is_some_and(|g| spaced(glyph, g)) {
+                glyph.x_advance += width;
+                glyph.adjustability.shrinkability.1 += width / 2.0;
+                text.width += width.at(text.size);
             }
 
             // Case 2: Latin followed by a CJ character
-            if glyph.is_cj_script() && prev.is_some_and(|g| g.is_letter_or_number()) {
-                glyph.x_advance += Em::new(0.25);
-                glyph.x_offset += Em::new(0.25);
-                glyph.adjustability.shrinkability.0 += Em::new(0.125);
-                text.width += Em::new(0.25).at(text.size);
+            if glyph.is_cj_script() && prev.is_some_and(|g| spaced(glyph, g)) {
+                glyph.x_advance += width;
+                glyph.x_offset += width;
+                glyph.adjustability.shrinkability.0 += width / 2.0;
+                text.width += width.at(text.size);
             }
 
             prev = Some(glyph);
@@ -1079,7 +1102,17 @@ fn line<'a>(
                 }
 
                 if let Some(last_glyph) = reshaped.glyphs.last() {
-                    if last_glyph.is_cjk_left_aligned_punctuation(gb_style) {
+                    if p.char_grid.is_some()
+                        && matches!(last_glyph.c, '、' | '。' | '，' | '．')
+                    {
+                        // On a character grid, commas and full stops at the
+                        // line end hang into the margin instead of taking up
+                        // a cell (burasage in JIS X 4051).
+                        let punct = reshaped.glyphs.to_mut().last_mut().unwrap();
+                        let hang = punct.x_advance;
+                        punct.x_advance = Em::zero();
+                        reshaped.width -= hang.at(reshaped.size);
+                    } else if last_glyph.is_cjk_left_aligned_punctuation(gb_style) {
                         // If the last glyph is a CJK punctuation, we want to shrink it.
                         // See Requirements for Chinese Text Layout, Section 3.1.6.3
                         // Compression of punctuation marks at line start or line end
@@ -6,19 +6,20 @@ use std::sync::Arc;
 
 use az::SaturatingAs;
 use ecow::EcoString;
-use rustybuzz::{ShapePlan, Tag, UnicodeBuffer};
+use rustybuzz::{GlyphBuffer, ShapePlan, Tag, UnicodeBuffer};
 use unicode_script::{Script, UnicodeScript};
 
 use super::SpanMapper;
 use crate::engine::Engine;
 use crate::foundations::StyleChain;
 use crate::layout::{Abs, Dir, Em, Frame, FrameItem, Point, Size};
+use crate::model::ParElem;
 use crate::syntax::Span;
 use crate::text::{
-    decorate, families, features, variant, Font, FontVariant, Glyph, Lang, Region,
-    TextElem, TextItem,
+    decorate, families, features, font_adjustment, variant, Case, Font, FontVariant,
+    Glyph, Lang, Region, TextElem, TextItem,
 };
-use crate::util::SliceExt;
+use crate::util::{Numeric, SliceExt};
 use crate::World;
 
 /// The result of shaping text.
@@ -240,6 +241,9 @@ impl<'a> ShapedText<'a> {
                 range.end = range.end.max(glyph.range.end);
             }
 
+            // Glyphs of fonts with a size adjustment are positioned in units
+            // of the unadjusted size, but rendered at the adjusted one.
+            let (scale, _) = font_adjustment(self.styles, &font, self.size);
             let pos = Point::new(offset, top + shift - y_offset.at(self.size));
             let glyphs: Vec<Glyph> = group
                 .iter()
@@ -292,10 +296,11 @@ impl<'a> ShapedText<'a> {
                     // A+B+C+D: Glyph's x_advance
                     Glyph {
                         id: shaped.glyph_id,
-                        x_advance: shaped.x_advance
+                        x_advance: (shaped.x_advance
                             + justification_left
-                            + justification_right,
-                        x_offset: shaped.x_offset + justification_left,
+                            + justification_right)
+                            / scale,
+                        x_offset: (shaped.x_offset + justification_left) / scale,
                         range: (shaped.range.start - range.start).saturating_as()
                             ..(shaped.range.end - range.start).saturating_as(),
                         span,
@@ -305,7 +310,7 @@ impl<'a> ShapedText<'a> {
 
             let item = TextItem {
                 font,
-                size: self.size,
+                size: self.size * scale,
                 lang,
                 fill: fill.clone(),
                 stroke: stroke.clone().map(|s| s.unwrap_or_default()),
@@ -340,8 +345,11 @@ impl<'a> ShapedText<'a> {
 
         // Expand top and bottom by reading the font's vertical metrics.
         let mut expand = |font: &Font, bbox: Option<ttf_parser::Rect>| {
-            top.set_max(top_edge.resolve(self.size, font, bbox));
-            bottom.set_max(-bottom_edge.resolve(self.size, font, bbox));
+            let (scale, shift) = font_adjustment(self.styles, font, self.size);
+            let size = self.size * scale;
+            let shift = shift.at(self.size);
+            top.set_max(top_edge.resolve(size, font, bbox) + shift);
+            bottom.set_max(-bottom_edge.resolve(size, font, bbox) - shift);
         };
 
         if self.glyphs.is_empty() {
@@ -463,7 +471,8 @@ impl<'a> ShapedText<'a> {
             let font = world.font(id)?;
             let ttf = font.ttf();
             let glyph_id = ttf.glyph_index('-')?;
-            let x_advance = font.to_em(ttf.glyph_hor_advance(glyph_id)?);
+            let (scale, shift) = font_adjustment(self.styles, &font, self.size);
+            let x_advance = font.to_em(ttf.glyph_hor_advance(glyph_id)?) * scale;
             let range = self
                 .glyphs
                 .last()
@@ -479,7 +488,7 @@ impl<'a> ShapedText<'a> {
                 glyph_id: glyph_id.0,
                 x_advance,
                 x_offset: Em::zero(),
-                y_offset: Em::zero(),
+                y_offset: shift,
                 adjustability: Adjustability::default(),
                 range,
                 safe_to_break: true,
@@ -620,7 +629,11 @@ pub(super) fn shape<'a>(
     }
 
     track_and_space(&mut ctx);
-    calculate_adjustability(&mut ctx, lang, region);
+    if let Some(pitch) = ParElem::char_grid_in(styles) {
+        snap_to_grid(&mut ctx, pitch, lang, region);
+    } else {
+        calculate_adjustability(&mut ctx, lang, region);
+    }
 
     #[cfg(debug_assertions)]
     assert_all_glyphs_in_range(&ctx.glyphs, text, base..(base + text.len()));
@@ -680,8 +693,9 @@ fn shape_segment<'a>(
     };
 
     ctx.used.push(font.clone());
+    let (scale, shift) = font_adjustment(ctx.styles, &font, ctx.size);
 
-    // Fill the buffer with our text.
+    // Fill a buffer with our text to determine its segment properties.
     let mut buffer = UnicodeBuffer::new();
     buffer.push_str(text);
     buffer.set_language(language(ctx.styles));
@@ -697,19 +711,15 @@ fn shape_segment<'a>(
     });
     buffer.guess_segment_properties();
 
-    // Prepare the shape plan. This plan depends on direction, script, language,
-    // and features, but is independent from the text and can thus be
-    // memoized.
-    let plan = create_shape_plan(
+    // Shape!
+    let buffer = shape_run(
         &font,
+        text,
         buffer.direction(),
         buffer.script(),
         buffer.language().as_ref(),
         &ctx.features,
     );
-
-    // Shape!
-    let buffer = rustybuzz::shape_with_plan(font.rusty(), &plan, buffer);
     let infos = buffer.glyph_infos();
     let pos = buffer.glyph_positions();
     let ltr = ctx.dir.is_positive();
@@ -732,14 +742,14 @@ fn shape_segment<'a>(
 
             let c = text[cluster..].chars().next().unwrap();
             let script = c.script();
-            let x_advance = font.to_em(pos[i].x_advance);
+            let x_advance = font.to_em(pos[i].x_advance) * scale;
             ctx.glyphs.push(ShapedGlyph {
                 font: font.clone(),
                 glyph_id: info.glyph_id as u16,
                 // TODO: Don't ignore y_advance.
                 x_advance,
-                x_offset: font.to_em(pos[i].x_offset),
-                y_offset: font.to_em(pos[i].y_offset),
+                x_offset: font.to_em(pos[i].x_offset) * scale,
+                y_offset: font.to_em(pos[i].y_offset) * scale + shift,
                 adjustability: Adjustability::default(),
                 range: start..end,
                 safe_to_break: !info.unsafe_to_break(),
@@ -800,6 +810,36 @@ fn shape_segment<'a>(
     ctx.used.pop();
 }
 
+/// Shape a run of text with a single font.
+///
+/// The same strings (table cells, headers, boilerplate) are frequently shaped
+/// over and over with the same font and properties, so this is memoized. Like
+/// all memoized results, shaped runs are also reused across compilations until
+/// they are evicted.
+#[comemo::memoize]
+fn shape_run(
+    font: &Font,
+    text: &str,
+    direction: rustybuzz::Direction,
+    script: rustybuzz::Script,
+    language: Option<&rustybuzz::Language>,
+    features: &[rustybuzz::Feature],
+) -> Arc<GlyphBuffer> {
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(text);
+    buffer.set_direction(direction);
+    buffer.set_script(script);
+    if let Some(language) = language {
+        buffer.set_language(language.clone());
+    }
+
+    // Prepare the shape plan. This plan depends on direction, script, language,
+    // and features, but is independent from the text and can thus be
+    // memoized separately.
+    let plan = create_shape_plan(font, direction, script, language, features);
+    Arc::new(rustybuzz::shape_with_plan(font.rusty(), &plan, buffer))
+}
+
 /// Create a shape plan.
 #[comemo::memoize]
 fn create_shape_plan(
@@ -820,7 +860,8 @@ fn create_shape_plan(
 
 /// Shape the text with tofus from the given font.
 fn shape_tofus(ctx: &mut ShapingContext, base: usize, text: &str, font: Font) {
-    let x_advance = font.advance(0).unwrap_or_default();
+    let (scale, shift) = font_adjustment(ctx.styles, &font, ctx.size);
+    let x_advance = font.advance(0).unwrap_or_default() * scale;
     let add_glyph = |(cluster, c): (usize, char)| {
         let start = base + cluster;
         let end = start + c.len_utf8();
@@ -830,7 +871,7 @@ fn shape_tofus(ctx: &mut ShapingContext, base: usize, text: &str, font: Font) {
             glyph_id: 0,
             x_advance,
             x_offset: Em::zero(),
-            y_offset: Em::zero(),
+            y_offset: shift,
             adjustability: Adjustability::default(),
             range: start..end,
             safe_to_break: true,
@@ -855,6 +896,9 @@ fn shape_tofus(ctx: &mut ShapingContext, base: usize, text: &str, font: Font) {
 /// Apply tracking and spacing to the shaped glyphs.
 fn track_and_space(ctx: &mut ShapingContext) {
     let tracking = Em::from_length(TextElem::tracking_in(ctx.styles), ctx.size);
+    let caps_tracking = Em::from_length(TextElem::caps_tracking_in(ctx.styles), ctx.size);
+    let all_caps = TextElem::smallcaps_in(ctx.styles)
+        || TextElem::case_in(ctx.styles) == Some(Case::Upper);
     let spacing =
         TextElem::spacing_in(ctx.styles).map(|abs| Em::from_length(abs, ctx.size));
 
@@ -869,11 +913,14 @@ fn track_and_space(ctx: &mut ShapingContext) {
             glyph.x_advance = spacing.relative_to(glyph.x_advance);
         }
 
-        if glyphs
-            .peek()
-            .is_some_and(|next| glyph.range.start != next.range.start)
-        {
+        let Some(next) = glyphs.peek() else { continue };
+        if glyph.range.start != next.range.start {
             glyph.x_advance += tracking;
+            if !caps_tracking.is_zero()
+                && (all_caps || (glyph.c.is_uppercase() && next.c.is_uppercase()))
+            {
+                glyph.x_advance += caps_tracking;
+            }
         }
     }
 }
@@ -911,6 +958,76 @@ fn calculate_adjustability(ctx: &mut ShapingContext, lang: Lang, region: Option<
     }
 }
 
+/// Place CJK characters on a character grid with cells of the given width.
+///
+/// Punctuation is composed according to the basic rules of JIS X 4051:
+/// closing marks followed by another mark and opening marks preceded by one
+/// take up only half a cell. Runs of other text are centered in the smallest
+/// whole number of cells they fit into. Since the grid must not be distorted,
+/// no glyph is adjustable.
+fn snap_to_grid(
+    ctx: &mut ShapingContext,
+    pitch: Abs,
+    lang: Lang,
+    region: Option<Region>,
+) {
+    let style = cjk_punct_style(lang, region);
+    let cell = Em::from_length(pitch, ctx.size);
+    if cell <= Em::zero() {
+        return;
+    }
+
+    let on_grid =
+        |g: &ShapedGlyph| g.is_cj_script() || g.is_cjk_punctuation() || g.c == '\u{3000}';
+
+    let glyphs = &mut ctx.glyphs;
+    let mut compressed = false;
+    let mut i = 0;
+    while i < glyphs.len() {
+        glyphs[i].adjustability = Adjustability::default();
+
+        if !on_grid(&glyphs[i]) {
+            let start = i;
+            while i < glyphs.len() && !on_grid(&glyphs[i]) {
+                glyphs[i].adjustability = Adjustability::default();
+                i += 1;
+            }
+
+            let run = &mut glyphs[start..i];
+            let width = run.iter().map(|g| g.x_advance).sum::<Em>();
+            let padding = (cell * (width / cell).ceil() - width) / 2.0;
+            run[0].x_advance += padding;
+            run[0].x_offset += padding;
+            run[run.len() - 1].x_advance += padding;
+            compressed = false;
+            continue;
+        }
+
+        let prev_punct = i > 0 && glyphs[i - 1].is_cjk_punctuation();
+        let next_punct = glyphs.get(i + 1).is_some_and(|g| g.is_cjk_punctuation());
+        let glyph = &mut glyphs[i];
+        let width = glyph.x_advance;
+        let left = glyph.is_cjk_left_aligned_punctuation(style);
+        let right = glyph.is_cjk_right_aligned_punctuation();
+
+        let space = if (left && next_punct) || (right && prev_punct && !compressed) {
+            cell / 2.0
+        } else {
+            cell
+        };
+
+        if right {
+            glyph.x_offset += space - width;
+        } else if !left {
+            glyph.x_offset += (space - width) / 2.0;
+        }
+
+        glyph.x_advance = space;
+        compressed = left && next_punct;
+        i += 1;
+    }
+}
+
 /// Difference between non-breaking and normal space.
 fn nbsp_delta(font: &Font) -> Option<Em> {
     let space = font.ttf().glyph_index(' ')?.0;
@@ -3,25 +3,30 @@ use std::num::NonZeroUsize;
 use std::ptr;
 use std::str::FromStr;
 
-use comemo::Track;
+use comemo::{Track, Tracked};
+use ecow::{eco_format, EcoString};
 
 use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, AutoValue, Cast, Content, Context, Dict, Fold, Func, NativeElement,
-    Packed, Resolve, Smart, StyleChain, Value,
+    cast, elem, scope, AutoValue, Cast, Content, Context, Dict, Fold, Func, IntoValue,
+    Label, NativeElement, Packed, Resolve, Show, Smart, Style, StyleChain, Styles, Value,
+};
+use crate::introspection::{
+    Counter, CounterDisplayElem, CounterKey, Introspector, ManualPageCounter, Meta,
+    PageMarks,
 };
-use crate::introspection::{Counter, CounterDisplayElem, CounterKey, ManualPageCounter};
 use crate::layout::{
-    Abs, AlignElem, Alignment, Axes, ColumnsElem, Dir, Frame, HAlignment, LayoutMultiple,
-    Length, OuterVAlignment, Point, Ratio, Regions, Rel, Sides, Size, SpecificAlignment,
-    VAlignment,
+    Abs, AlignElem, Alignment, Axes, ColumnsElem, Dir, Frame, FrameItem, HAlignment,
+    LayoutMultiple, Length, OuterVAlignment, Point, Ratio, Regions, Rel, Sides, Size,
+    SpecificAlignment, VAlignment,
 };
 
-use crate::model::Numbering;
+use crate::model::{draw_change_bars, HeadingElem, Numbering};
 use crate::text::TextElem;
 use crate::util::{NonZeroExt, Numeric, Scalar};
 use crate::visualize::Paint;
+use crate::World;
 
 /// Layouts its child onto one or multiple pages.
 ///
@@ -42,7 +47,7 @@ use crate::visualize::Paint;
 ///
 /// There you go, US friends!
 /// ```
-#[elem]
+#[elem(scope)]
 pub struct PageElem {
     /// A standard paper size to set width and height.
     #[external]
@@ -285,6 +290,26 @@ pub struct PageElem {
     /// This content will be placed behind the page's body. It can be
     /// used to place a background image or a watermark.
     ///
+    /// Instead of content, you can also pass a function. It is called once
+    /// per page, after the page's body has been laid out, with a dictionary
+    /// describing the finished page:
+    ///
+    /// - `number`: The physical page number, starting at `{1}`.
+    /// - `margin`: A dictionary with the `top`, `right`, `bottom`, and `left`
+    ///   margins of the page, with left and right already swapped for
+    ///   [two-sided]($page.margin) documents.
+    /// - `outside`: The horizontal side of the page that faces away from the
+    ///   [binding]($page.binding), either `{left}` or `{right}`.
+    /// - `headings`: The headings of the page, as found by
+    ///   [`page-marks`]($page-marks): A dictionary with the `first` and `last`
+    ///   heading on the page and the `previous` heading before it, each of
+    ///   which can be `{none}`.
+    /// - `labels`: An array with the labels of all labelled elements on the
+    ///   page.
+    ///
+    /// This way, thumb tabs and similar per-page decorations can react to the
+    /// page's contents without a second compilation.
+    ///
     /// ```example
     /// #set page(background: rotate(24deg,
     ///   text(18pt, fill: rgb("FFCBC4"))[
@@ -296,12 +321,32 @@ pub struct PageElem {
     /// In the year 2023, we plan to take
     /// over the world (of typesetting).
     /// ```
+    ///
+    /// ```example
+    /// #set page(height: 80pt, background: page => {
+    ///   let heading = page.headings.last
+    ///   if heading != none {
+    ///     let dx = if page.outside == left { 0pt } else { 100% - 12pt }
+    ///     place(top + left, dx: dx, dy: 12pt, rect(
+    ///       width: 12pt,
+    ///       height: 40pt,
+    ///       fill: aqua,
+    ///       rotate(90deg, reflow: true, heading.body),
+    ///     ))
+    ///   }
+    /// })
+    ///
+    /// = Intro
+    /// #lorem(8)
+    /// ```
     #[borrowed]
-    pub background: Option<Content>,
+    pub background: Option<Marginal>,
 
     /// Content in the page's foreground.
     ///
-    /// This content will overlay the page's body.
+    /// This content will overlay the page's body. Like the
+    /// [`background`]($page.background), it can also be a function that
+    /// receives a dictionary describing the finished page.
     ///
     /// ```example
     /// #set page(foreground: text(24pt)[🥸])
@@ -311,7 +356,33 @@ pub struct PageElem {
     /// not understand our approach...
     /// ```
     #[borrowed]
-    pub foreground: Option<Content>,
+    pub foreground: Option<Marginal>,
+
+    /// Whether the header, footer, background, and foreground are isolated
+    /// from the styles of the page's body.
+    ///
+    /// By default, all set and show rules that are active where the page is
+    /// configured also apply to its marginals. If this is `{true}`, only the
+    /// default styles, the page's own properties, and show rules for
+    /// [page marginals]($page.marginal) apply to them. This way, rules that
+    /// are meant for the body, like a show rule for all text, don't leak into
+    /// the page furniture.
+    ///
+    /// ```example
+    /// #set page(
+    ///   height: 80pt,
+    ///   header: [Typst Academy],
+    ///   isolate-marginals: true,
+    /// )
+    /// #set text(fill: blue)
+    /// #show "Typst": smallcaps
+    /// #show page.marginal: set text(8pt)
+    ///
+    /// Typst is a markup-based
+    /// typesetting system.
+    /// ```
+    #[default(false)]
+    pub isolate_marginals: bool,
 
     /// The contents of the page(s).
     ///
@@ -342,37 +413,22 @@ impl Packed<PageElem> {
         page_counter: &mut ManualPageCounter,
         extend_to: Option<Parity>,
     ) -> SourceResult<Vec<Page>> {
-        // When one of the lengths is infinite the page fits its content along
-        // that axis.
-        let width = self.width(styles).unwrap_or(Abs::inf());
-        let height = self.height(styles).unwrap_or(Abs::inf());
-        let mut size = Size::new(width, height);
-        if self.flipped(styles) {
-            std::mem::swap(&mut size.x, &mut size.y);
-        }
-
-        let mut min = width.min(height);
-        if !min.is_finite() {
-            min = Paper::A4.width();
-        }
-
-        // Determine the margins.
-        let default = Rel::<Length>::from((2.5 / 21.0) * min);
-        let margin = self.margin(styles);
-        let two_sided = margin.two_sided.unwrap_or(false);
-        let margin = margin
-            .sides
-            .map(|side| side.and_then(Smart::custom).unwrap_or(default))
-            .resolve(styles)
-            .relative_to(size);
+        let frames = self.layout_body(engine, styles)?;
+        self.finalize(engine, styles, frames, page_counter, extend_to)
+    }
 
-        // Determine the binding.
-        let binding =
-            self.binding(styles)
-                .unwrap_or_else(|| match TextElem::dir_in(styles) {
-                    Dir::LTR => Binding::Left,
-                    _ => Binding::Right,
-                });
+    /// Lays out the body of this page run into one frame per page, without
+    /// margins and marginals.
+    ///
+    /// This does not depend on any preceding page runs and can thus happen in
+    /// parallel for multiple runs.
+    #[typst_macros::time(name = "page body", span = self.span())]
+    pub fn layout_body(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<Vec<Frame>> {
+        let (size, margin, _) = self.geometry(styles);
 
         // Realize columns.
         let mut child = self.body().clone();
@@ -389,7 +445,30 @@ impl Packed<PageElem> {
         regions.root = true;
 
         // Layout the child.
-        let mut frames = child.layout(engine, styles, regions)?.into_frames();
+        Ok(child.layout(engine, styles, regions)?.into_frames())
+    }
+
+    /// Turns the frames produced by [`layout_body`](Self::layout_body) into
+    /// pages by adding margins, marginals, and page numbers.
+    #[typst_macros::time(name = "page finalize", span = self.span())]
+    pub fn finalize(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        mut frames: Vec<Frame>,
+        page_counter: &mut ManualPageCounter,
+        extend_to: Option<Parity>,
+    ) -> SourceResult<Vec<Page>> {
+        let (size, margin, two_sided) = self.geometry(styles);
+        let area = size - margin.sum_by_axis();
+
+        // Determine the binding.
+        let binding =
+            self.binding(styles)
+                .unwrap_or_else(|| match TextElem::dir_in(styles) {
+                    Dir::LTR => Binding::Left,
+                    _ => Binding::Right,
+                });
 
         // Align the child to the pagebreak's parity.
         // Check for page count after adding the pending frames
@@ -402,8 +481,8 @@ impl Packed<PageElem> {
         }
 
         let fill = self.fill(styles);
-        let foreground = Cow::Borrowed(self.foreground(styles));
-        let background = Cow::Borrowed(self.background(styles));
+        let foreground = self.foreground(styles);
+        let background = self.background(styles);
         let header_ascent = self.header_ascent(styles);
         let footer_descent = self.footer_descent(styles);
         let numbering = self.numbering(styles);
@@ -441,6 +520,18 @@ impl Packed<PageElem> {
             footer = if footer.is_some() { footer } else { numbering_marginal };
         }
 
+        // Determine the styles for the marginals, which are cut off from the
+        // body's styles if they are isolated.
+        let (library, isolated, base);
+        let marginal_styles = if self.isolate_marginals(styles) {
+            library = engine.world.library().styles.clone();
+            isolated = isolated_marginal_styles(styles);
+            base = StyleChain::new(&library);
+            base.chain(&isolated)
+        } else {
+            styles
+        };
+
         // Post-process pages.
         let mut pages = Vec::with_capacity(frames.len());
         for mut frame in frames {
@@ -458,36 +549,82 @@ impl Packed<PageElem> {
             // Realize margins.
             frame.set_size(frame.size() + margin.sum_by_axis());
             frame.translate(Point::new(margin.left, margin.top));
+            draw_change_bars(&mut frame, margin.left / 2.0);
 
             // The page size with margins.
             let size = frame.size();
 
+            // The side facing away from the binding.
+            let even = page_counter.physical().get() % 2 == 0;
+            let outside = match (binding, two_sided && even) {
+                (Binding::Left, false) | (Binding::Right, true) => Alignment::RIGHT,
+                _ => Alignment::LEFT,
+            };
+
+            // Resolve the background and foreground, which may depend on the
+            // finished page.
+            let introspector = engine.introspector;
+            let mut info = None;
+            let mut info = || {
+                info.get_or_insert_with(|| {
+                    page_info(
+                        introspector,
+                        &frame,
+                        page_counter.physical(),
+                        margin,
+                        outside,
+                    )
+                })
+                .clone()
+            };
+            // The headings on the page are only known once the introspection
+            // converges, so errors are delayed until then.
+            let mut resolve = |marginal: &Option<Marginal>| {
+                marginal.as_ref().map(|marginal| {
+                    engine.delayed(|engine| {
+                        marginal
+                            .resolve(engine, marginal_styles, &mut info)
+                            .map(Cow::into_owned)
+                    })
+                })
+            };
+            let background = Cow::Owned(resolve(background));
+            let foreground = Cow::Owned(resolve(foreground));
+
             // Realize overlays.
             for marginal in [&header, &footer, &background, &foreground] {
                 let Some(content) = &**marginal else { continue };
 
-                let (pos, area, align);
+                let (kind, pos, area, align);
                 if ptr::eq(marginal, &header) {
                     let ascent = header_ascent.relative_to(margin.top);
+                    kind = MarginalKind::Header;
                     pos = Point::with_x(margin.left);
                     area = Size::new(pw, margin.top - ascent);
                     align = Alignment::BOTTOM;
                 } else if ptr::eq(marginal, &footer) {
                     let descent = footer_descent.relative_to(margin.bottom);
+                    kind = MarginalKind::Footer;
                     pos = Point::new(margin.left, size.y - margin.bottom + descent);
                     area = Size::new(pw, margin.bottom - descent);
                     align = Alignment::TOP;
                 } else {
+                    kind = if ptr::eq(marginal, &background) {
+                        MarginalKind::Background
+                    } else {
+                        MarginalKind::Foreground
+                    };
                     pos = Point::zero();
                     area = size;
                     align = HAlignment::Center + VAlignment::Horizon;
                 };
 
                 let pod = Regions::one(area, Axes::splat(true));
-                let sub = content
-                    .clone()
+                let sub = PageMarginal::new(kind, content.clone())
+                    .pack()
+                    .spanned(self.span())
                     .styled(AlignElem::set_alignment(align))
-                    .layout(engine, styles, pod)?
+                    .layout(engine, marginal_styles, pod)?
                     .into_frame();
 
                 if ptr::eq(marginal, &header) || ptr::eq(marginal, &background) {
@@ -506,6 +643,7 @@ impl Packed<PageElem> {
                 frame,
                 numbering: numbering.clone(),
                 number: page_counter.logical(),
+                repeated: false,
             });
 
             page_counter.step();
@@ -513,6 +651,36 @@ impl Packed<PageElem> {
 
         Ok(pages)
     }
+
+    /// Determines the size of the pages, their margins, and whether the
+    /// margins are two-sided.
+    fn geometry(&self, styles: StyleChain) -> (Size, Sides<Abs>, bool) {
+        // When one of the lengths is infinite the page fits its content along
+        // that axis.
+        let width = self.width(styles).unwrap_or(Abs::inf());
+        let height = self.height(styles).unwrap_or(Abs::inf());
+        let mut size = Size::new(width, height);
+        if self.flipped(styles) {
+            std::mem::swap(&mut size.x, &mut size.y);
+        }
+
+        let mut min = width.min(height);
+        if !min.is_finite() {
+            min = Paper::A4.width();
+        }
+
+        // Determine the margins.
+        let default = Rel::<Length>::from((2.5 / 21.0) * min);
+        let margin = self.margin(styles);
+        let two_sided = margin.two_sided.unwrap_or(false);
+        let margin = margin
+            .sides
+            .map(|side| side.and_then(Smart::custom).unwrap_or(default))
+            .resolve(styles)
+            .relative_to(size);
+
+        (size, margin, two_sided)
+    }
 }
 
 /// A finished page.
@@ -525,6 +693,102 @@ pub struct Page {
     /// The logical page number (controlled by `counter(page)` and may thus not
     /// match the physical number).
     pub number: usize,
+    /// Whether the page repeats the preceding one to reveal more of a slide's
+    /// content. Such pages don't step the page counter.
+    pub repeated: bool,
+}
+
+/// A selection of physical pages, for example to only export a part of a
+/// document.
+///
+/// Laying out a document always produces all of its pages, because counters,
+/// references, and other introspection can depend on any of them. Exporters
+/// that receive page ranges only encode the selected pages, which is where most
+/// of the time goes for large documents.
+///
+/// Page ranges can be parsed from strings like `1-3,5,8-` or `odd`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PageRanges(Vec<PageRange>);
+
+/// A part of a page selection.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PageRange {
+    /// The pages from the first to the second number, inclusive. Missing
+    /// bounds extend to the start or end of the document.
+    Span(Option<NonZeroUsize>, Option<NonZeroUsize>),
+    /// All pages with odd or even physical page numbers.
+    Parity(Parity),
+}
+
+impl PageRanges {
+    /// Create a selection from its parts. A page is selected if any of the
+    /// parts includes it.
+    pub fn new(ranges: Vec<PageRange>) -> Self {
+        Self(ranges)
+    }
+
+    /// Whether the page with the given (one-based) physical number is selected.
+    pub fn includes_page(&self, page: NonZeroUsize) -> bool {
+        self.0.iter().any(|range| match *range {
+            PageRange::Span(start, end) => {
+                start.map_or(true, |start| start <= page)
+                    && end.map_or(true, |end| page <= end)
+            }
+            PageRange::Parity(parity) => parity.matches(page.get()),
+        })
+    }
+
+    /// Whether the page with the given (zero-based) index is selected.
+    pub fn includes_page_index(&self, index: usize) -> bool {
+        self.includes_page(NonZeroUsize::new(index + 1).unwrap())
+    }
+}
+
+impl FromStr for PageRanges {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |part: &str, text: &str| -> Result<_, EcoString> {
+            let text = text.trim();
+            if text.is_empty() {
+                return Ok(None);
+            }
+            text.parse::<NonZeroUsize>()
+                .map(Some)
+                .map_err(|_| eco_format!("invalid page range `{}`", part.trim()))
+        };
+
+        let mut ranges = vec![];
+        for part in s.split(',') {
+            let range = match part.trim() {
+                "odd" => PageRange::Parity(Parity::Odd),
+                "even" => PageRange::Parity(Parity::Even),
+                trimmed => match trimmed.split_once('-') {
+                    Some((start, end)) => {
+                        let range =
+                            PageRange::Span(number(part, start)?, number(part, end)?);
+                        if let PageRange::Span(Some(start), Some(end)) = range {
+                            if start > end {
+                                return Err(eco_format!(
+                                    "page range `{trimmed}` ends before it starts"
+                                ));
+                            }
+                        }
+                        range
+                    }
+                    None => {
+                        let Some(page) = number(part, trimmed)? else {
+                            return Err("page ranges must not be empty".into());
+                        };
+                        PageRange::Span(Some(page), Some(page))
+                    }
+                },
+            };
+            ranges.push(range);
+        }
+
+        Ok(Self(ranges))
+    }
 }
 
 /// Specification of the page's margins.
@@ -648,7 +912,7 @@ pub enum Binding {
 
 impl Binding {
     /// Whether to swap left and right margin for the page with this number.
-    fn swap(self, number: NonZeroUsize) -> bool {
+    pub(crate) fn swap(self, number: NonZeroUsize) -> bool {
         match self {
             // Left-bound must swap on even pages
             // (because it is correct on the first page).
@@ -673,8 +937,79 @@ cast! {
     },
 }
 
+#[scope]
+impl PageElem {
+    #[elem]
+    type PageMarginal;
+}
+
+/// The header, footer, background, or foreground of a page.
+///
+/// The page wraps each of its marginals in this element. You don't create it
+/// yourself, but you can target it with show rules to style all marginals or,
+/// by filtering on the `kind`, specific ones. Such show rules also apply when
+/// the marginals are [isolated]($page.isolate-marginals) from the body.
+///
+/// ```example
+/// #set page(
+///   height: 80pt,
+///   header: [Exercise Sheet 3],
+///   numbering: "1",
+/// )
+/// #show page.marginal: set text(8pt)
+/// #show page.marginal.where(kind: "footer"): set text(fill: gray)
+///
+/// #lorem(12)
+/// ```
+#[elem(name = "marginal", title = "Page Marginal", Show)]
+pub struct PageMarginal {
+    /// Which of the page's marginals this is.
+    #[required]
+    pub kind: MarginalKind,
+
+    /// The marginal's contents.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<PageMarginal> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(self.body().clone())
+    }
+}
+
+/// Which of a page's marginals a [`PageMarginal`] is.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum MarginalKind {
+    /// The page's header.
+    Header,
+    /// The page's footer.
+    Footer,
+    /// The page's background.
+    Background,
+    /// The page's foreground.
+    Foreground,
+}
+
+/// Collects the styles that still apply to isolated marginals: The page's own
+/// properties and show rules for page marginals.
+fn isolated_marginal_styles(styles: StyleChain) -> Styles {
+    let mut isolated = Styles::new();
+    for style in styles.entries() {
+        let keep = match style {
+            Style::Property(property) => property.is_of(PageElem::elem()),
+            Style::Recipe(recipe) => recipe.is_of(PageMarginal::elem()),
+            Style::Revocation(_) => false,
+        };
+        if keep {
+            isolated.apply_one(style.clone());
+        }
+    }
+    isolated
+}
+
 /// A header, footer, foreground or background definition.
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 pub enum Marginal {
     /// Bare content.
     Content(Content),
@@ -683,23 +1018,68 @@ pub enum Marginal {
 }
 
 impl Marginal {
-    /// Resolve the marginal based on the page number.
+    /// Resolve the marginal based on information about the page.
     pub fn resolve(
         &self,
         engine: &mut Engine,
         styles: StyleChain,
-        page: usize,
+        info: impl FnOnce() -> Dict,
     ) -> SourceResult<Cow<'_, Content>> {
         Ok(match self {
             Self::Content(content) => Cow::Borrowed(content),
             Self::Func(func) => Cow::Owned(
-                func.call(engine, Context::new(None, Some(styles)).track(), [page])?
+                func.call(engine, Context::new(None, Some(styles)).track(), [info()])?
                     .display(),
             ),
         })
     }
 }
 
+/// Describes a finished page for a marginal function.
+fn page_info(
+    introspector: Tracked<Introspector>,
+    frame: &Frame,
+    number: NonZeroUsize,
+    margin: Sides<Abs>,
+    outside: Alignment,
+) -> Dict {
+    let headings = PageMarks::new(introspector, number, &HeadingElem::elem().select());
+    let mut labels = vec![];
+    collect_labels(frame, &mut labels);
+
+    let margin = Dict::from_iter([
+        ("top".into(), margin.top.into_value()),
+        ("right".into(), margin.right.into_value()),
+        ("bottom".into(), margin.bottom.into_value()),
+        ("left".into(), margin.left.into_value()),
+    ]);
+
+    Dict::from_iter([
+        ("number".into(), number.get().into_value()),
+        ("margin".into(), margin.into_value()),
+        ("outside".into(), outside.into_value()),
+        ("headings".into(), headings.into_dict().into_value()),
+        ("labels".into(), labels.into_value()),
+    ])
+}
+
+/// Collect the labels in a frame, in order.
+fn collect_labels(frame: &Frame, labels: &mut Vec<Label>) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => collect_labels(&group.frame, labels),
+            FrameItem::Meta(Meta::Elem(content), _) => {
+                if let Some(label) = content.label() {
+                    if !labels.contains(&label) {
+                        labels.push(label);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 cast! {
     Marginal,
     self => match self {
@@ -975,3 +1355,42 @@ papers! {
     (PRESENTATION_16_9:    297.0, 167.0625, "presentation-16-9")
     (PRESENTATION_4_3:     280.0,    210.0, "presentation-4-3")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_page_ranges() {
+        #[track_caller]
+        fn test(ranges: &str, included: &[usize], excluded: &[usize]) {
+            let ranges: PageRanges = ranges.parse().unwrap();
+            for &page in included {
+                assert!(ranges.includes_page(NonZeroUsize::new(page).unwrap()));
+            }
+            for &page in excluded {
+                assert!(!ranges.includes_page(NonZeroUsize::new(page).unwrap()));
+            }
+        }
+
+        test("3", &[3], &[1, 2, 4]);
+        test("2-4", &[2, 3, 4], &[1, 5]);
+        test("-2, 5-", &[1, 2, 5, 100], &[3, 4]);
+        test("odd", &[1, 3, 5], &[2, 4]);
+        test("even,1", &[1, 2, 4], &[3, 5]);
+    }
+
+    #[test]
+    fn test_parse_invalid_page_ranges() {
+        #[track_caller]
+        fn test(ranges: &str, message: &str) {
+            assert_eq!(ranges.parse::<PageRanges>().unwrap_err(), message);
+        }
+
+        test("", "page ranges must not be empty");
+        test("1,,2", "page ranges must not be empty");
+        test("0", "invalid page range `0`");
+        test("a-3", "invalid page range `a-3`");
+        test("5-2", "page range `5-2` ends before it starts");
+    }
+}
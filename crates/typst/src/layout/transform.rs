@@ -1,10 +1,11 @@
 use crate::diag::SourceResult;
 use crate::engine::Engine;
-use crate::foundations::{elem, Content, Packed, Resolve, StyleChain};
+use crate::foundations::{elem, Content, Packed, Resolve, Smart, StyleChain};
 use crate::layout::{
     Abs, Alignment, Angle, Axes, FixedAlignment, Frame, HAlignment, LayoutMultiple,
     LayoutSingle, Length, Point, Ratio, Regions, Rel, Size, VAlignment,
 };
+use crate::util::Numeric;
 
 /// Moves content without affecting layout.
 ///
@@ -234,6 +235,97 @@ impl LayoutSingle for Packed<ScaleElem> {
     }
 }
 
+/// Scales content so that it fits into a box.
+///
+/// The content is first laid out at its natural size and then scaled
+/// uniformly, such that it is as large as possible while still fitting into
+/// the given width and height. Unlike [`scale`]($scale), this affects layout:
+/// The result takes up exactly the space of the scaled content. This is
+/// useful for wide tables, long titles, or diagrams that should fill the
+/// available space.
+///
+/// # Example
+/// ```example
+/// #set page(width: 140pt)
+/// #scale-to-fit[This headline is far too long]
+/// #scale-to-fit(height: 30pt)[Tall]
+/// #scale-to-fit(shrink-only: true)[Short]
+/// ```
+#[elem(title = "Scale to Fit", LayoutSingle)]
+pub struct ScaleToFitElem {
+    /// The width to fit the content into.
+    ///
+    /// If this is `{auto}`, the content is fitted to the width of the
+    /// surrounding container.
+    pub width: Smart<Rel<Length>>,
+
+    /// The height to fit the content into.
+    ///
+    /// If this is `{auto}`, the content is fitted to the height of the
+    /// surrounding container, or, on a page, to the height of the page's
+    /// content area. Content that would not fit on a page thus moves to the
+    /// next one at full size if it fits there and is only scaled down if it
+    /// is taller than a whole page.
+    pub height: Smart<Rel<Length>>,
+
+    /// Whether the content may only be scaled down.
+    ///
+    /// If this is `{true}`, content that already fits keeps its natural size
+    /// instead of being enlarged.
+    ///
+    /// ```example
+    /// #box(width: 60pt, stroke: 0.5pt, scale-to-fit[Fit])
+    /// #box(width: 60pt, stroke: 0.5pt, scale-to-fit(shrink-only: true)[Fit])
+    /// ```
+    #[default(false)]
+    pub shrink_only: bool,
+
+    /// The content to scale.
+    #[required]
+    pub body: Content,
+}
+
+impl LayoutSingle for Packed<ScaleToFitElem> {
+    #[typst_macros::time(name = "scale-to-fit", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Frame> {
+        // Determine the box to fit the content into.
+        let base = regions.base();
+        let target = Axes::new(self.width(styles), self.height(styles))
+            .zip_map(base, |s, b| s.map(|v| v.resolve(styles).relative_to(b)))
+            .zip_map(base, Smart::unwrap_or);
+
+        // Measure the natural size of the body.
+        let pod = Regions::one(Size::splat(Abs::inf()), Axes::splat(false));
+        let natural = self.body().measure(engine, styles, pod)?.into_frame().size();
+
+        // Find the largest factor with which the body still fits along every
+        // constrained axis.
+        let factors = target
+            .zip_map(natural, |t, n| (t.is_finite() && n > Abs::zero()).then(|| t / n));
+        let mut factor = match (factors.x, factors.y) {
+            (Some(x), Some(y)) => x.min(y),
+            (Some(f), None) | (None, Some(f)) => f,
+            (None, None) => 1.0,
+        };
+        if self.shrink_only(styles) {
+            factor = factor.min(1.0);
+        }
+
+        // Lay out the body at its natural size and scale it.
+        let pod = Regions::one(natural, Axes::splat(true));
+        let mut frame = self.body().layout(engine, styles, pod)?.into_frame();
+        let size = frame.size() * factor;
+        frame.transform(Transform::scale(Ratio::new(factor), Ratio::new(factor)));
+        frame.set_size(size);
+        Ok(frame)
+    }
+}
+
 /// A scale-skew-translate transformation.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Transform {
@@ -63,7 +63,7 @@ use comemo::{Prehashed, Track, Tracked, Validate};
 use ecow::{EcoString, EcoVec};
 use typst_timing::{timed, TimingScope};
 
-use crate::diag::{warning, FileResult, SourceDiagnostic, SourceResult};
+use crate::diag::{warning, At, FileResult, SourceDiagnostic, SourceResult, StrResult};
 use crate::engine::{Engine, Route};
 use crate::eval::Tracer;
 use crate::foundations::{
@@ -75,6 +75,7 @@ use crate::model::Document;
 use crate::syntax::package::PackageSpec;
 use crate::syntax::{FileId, Source, Span};
 use crate::text::{Font, FontBook};
+use crate::util::memory::Budget;
 use crate::visualize::Color;
 
 /// Compile a source file into a fully layouted document.
@@ -87,6 +88,14 @@ use crate::visualize::Color;
 /// `tracer.warnings()` after compilation will return all compiler warnings.
 #[typst_macros::time(name = "compile")]
 pub fn compile(world: &dyn World, tracer: &mut Tracer) -> SourceResult<Document> {
+    // Count this compilation's allocations if it has a memory budget.
+    let budget = world
+        .memory_budget()
+        .map(Budget::new)
+        .transpose()
+        .at(Span::detached())?;
+    let _scope = budget.as_ref().map(|budget| budget.scope().enter());
+
     // Call `track` on the world just once to keep comemo's ID stable.
     let world = world.track();
 
@@ -100,13 +109,14 @@ pub fn compile(world: &dyn World, tracer: &mut Tracer) -> SourceResult<Document>
     .map_err(deduplicate)?;
 
     // Typeset the module's content, relayouting until convergence.
-    typeset(world, tracer, &module.content()).map_err(deduplicate)
+    typeset(world, tracer, budget.as_ref(), &module.content()).map_err(deduplicate)
 }
 
 /// Relayout until introspection converges.
 fn typeset(
     world: Tracked<dyn World + '_>,
     tracer: &mut Tracer,
+    budget: Option<&Budget>,
     content: &Content,
 ) -> SourceResult<Document> {
     // The name of the iterations for timing scopes.
@@ -142,6 +152,12 @@ fn typeset(
         document.introspector.rebuild(&document.pages);
         iter += 1;
 
+        // This happens outside of memoized functions, so their results can be
+        // evicted to stay within the memory budget.
+        if let Some(budget) = budget {
+            budget.check(content.span())?;
+        }
+
         if timed!("check stabilized", document.introspector.validate(&constraint)) {
             break;
         }
@@ -189,8 +205,14 @@ fn deduplicate(mut diags: EcoVec<SourceDiagnostic>) -> EcoVec<SourceDiagnostic>
 /// clients like language servers can also retain the source files and
 /// [edit](Source::edit) them in-place to benefit from better incremental
 /// performance.
+///
+/// # Thread safety
+/// The bodies of page runs that are separated by explicit page breaks are laid
+/// out in parallel, so the world is accessed from multiple threads and must be
+/// `Send + Sync`. Interior mutability should be guarded by thread-safe
+/// primitives like `Mutex` or `OnceLock` instead of `RefCell` or `OnceCell`.
 #[comemo::track]
-pub trait World {
+pub trait World: Send + Sync {
     /// The standard library.
     ///
     /// Can be created through `Library::build()`.
@@ -220,6 +242,15 @@ pub trait World {
     /// return an error.
     fn today(&self, offset: Option<i64>) -> Option<Datetime>;
 
+    /// Get the date and time at which the specified file was last modified.
+    ///
+    /// This function is optional to implement. If it returns `None`, Typst's
+    /// `datetime.modified` function will return an error.
+    fn modified(&self, id: FileId) -> Option<Datetime> {
+        let _ = id;
+        None
+    }
+
     /// A list of all available packages and optionally descriptions for them.
     ///
     /// This function is optional to implement. It enhances the user experience
@@ -229,6 +260,28 @@ pub trait World {
     fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
         &[]
     }
+
+    /// Provide the output of executing a piece of code in the given language.
+    ///
+    /// This function is optional to implement. It is called for raw blocks
+    /// that request execution and lets embedders supply their results, for
+    /// instance from a cache of earlier runs. Typst itself never executes
+    /// code. If this function returns `None`, the language is considered
+    /// unsupported and the raw block produces an error.
+    fn execute(&self, lang: &str, code: &str) -> Option<StrResult<EcoString>> {
+        let _ = (lang, code);
+        None
+    }
+
+    /// The maximum number of bytes a compilation may keep allocated.
+    ///
+    /// This function is optional to implement. The budget only takes effect
+    /// if the [counting allocator](crate::util::memory::CountingAllocator) is the
+    /// global allocator. When the budget is exceeded, memoized results are
+    /// evicted and, if that doesn't help, compilation fails.
+    fn memory_budget(&self) -> Option<usize> {
+        None
+    }
 }
 
 /// Helper methods on [`World`] implementations.
@@ -0,0 +1,575 @@
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{func, scope, Array, Bytes, Str, Value};
+use crate::loading::{columns_into_rows, date, select_columns};
+use crate::syntax::Spanned;
+use crate::World;
+
+/// Reads a table from an Arrow IPC file.
+///
+/// Both the file format (also known as Feather) and the streaming format of
+/// Apache Arrow are supported. Just like with [`parquet`]($parquet), the table
+/// is returned as an array of dictionaries, one per row, and the columns to
+/// read and the number of rows can be restricted.
+///
+/// Booleans, integers, and floating point numbers are converted into the
+/// Typst equivalents, strings become [strings]($str), dates become
+/// [datetimes]($datetime), and other binary data becomes [bytes]($bytes).
+/// Missing values become `{none}`.
+///
+/// # Example
+/// ```typ
+/// #let runs = arrow("results.arrow", columns: ("name", "score"))
+///
+/// #table(
+///   columns: 2,
+///   [*Name*], [*Score*],
+///   ..runs.map(row => (row.name, str(row.score))).flatten(),
+/// )
+/// ```
+///
+/// Only flat tables without nested or dictionary-encoded columns are
+/// supported, and the record batches must not be compressed.
+#[func(scope, title = "Arrow IPC")]
+pub fn arrow(
+    /// The engine.
+    engine: &mut Engine,
+    /// Path to an Arrow IPC file.
+    path: Spanned<EcoString>,
+    /// The names of the columns to read. By default, all columns are read.
+    #[named]
+    columns: Option<Spanned<Vec<EcoString>>>,
+    /// The maximum number of rows to read. By default, all rows are read.
+    #[named]
+    limit: Option<usize>,
+) -> SourceResult<Array> {
+    let Spanned { v: path, span } = path;
+    let id = span.resolve_path(&path).at(span)?;
+    let data = engine.world.file(id).at(span)?;
+    arrow::decode(Spanned::new(data, span), columns, limit)
+}
+
+#[scope]
+impl arrow {
+    /// Reads a table from the bytes of an Arrow IPC file or stream.
+    #[func(title = "Decode Arrow IPC")]
+    pub fn decode(
+        /// The Arrow IPC file or stream.
+        data: Spanned<Bytes>,
+        /// The names of the columns to read. By default, all columns are
+        /// read.
+        #[named]
+        columns: Option<Spanned<Vec<EcoString>>>,
+        /// The maximum number of rows to read. By default, all rows are read.
+        #[named]
+        limit: Option<usize>,
+    ) -> SourceResult<Array> {
+        let Spanned { v: data, span } = data;
+        let ipc = Ipc::open(&data).map_err(format_arrow_error).at(span)?;
+        let names: Vec<_> = ipc.fields.iter().map(|field| field.name.clone()).collect();
+        let selected = select_columns(&names, columns)?;
+        let values = ipc.read(&selected, limit).map_err(format_arrow_error).at(span)?;
+        Ok(columns_into_rows(&names, &selected, values))
+    }
+}
+
+/// Format the user-facing Arrow error message.
+fn format_arrow_error(error: EcoString) -> EcoString {
+    eco_format!("failed to read Arrow file ({error})")
+}
+
+/// A column of the table.
+struct Field {
+    name: EcoString,
+    kind: Kind,
+}
+
+/// The type of a column.
+#[derive(Copy, Clone)]
+enum Kind {
+    Null,
+    Int { width: usize, signed: bool },
+    Float { width: usize },
+    Bool,
+    Binary { large: bool, text: bool },
+    Date { millis: bool },
+    FixedBinary { width: usize },
+}
+
+impl Kind {
+    /// The number of buffers that hold a column of this type.
+    fn buffers(self) -> usize {
+        match self {
+            Self::Null => 0,
+            Self::Binary { .. } => 3,
+            _ => 2,
+        }
+    }
+}
+
+/// An Arrow IPC file or stream.
+struct Ipc<'a> {
+    data: &'a [u8],
+    fields: Vec<Field>,
+    /// The positions of the record batch messages in a file, or `None` for a
+    /// stream, whose messages must be read one after another.
+    blocks: Option<Vec<usize>>,
+    /// The position of the first message after the schema in a stream.
+    start: usize,
+}
+
+impl<'a> Ipc<'a> {
+    /// Parse the schema of a file or stream.
+    fn open(data: &'a [u8]) -> StrResult<Self> {
+        // The file format wraps a stream in magic numbers and adds a footer
+        // with the schema and the positions of the record batches.
+        if data.len() >= 16 && data.starts_with(b"ARROW1") && data.ends_with(b"ARROW1") {
+            let end = data.len() - 10;
+            let len =
+                usize::try_from(read_i32(data, end)?).map_err(|_| "file is corrupted")?;
+            let start = end.checked_sub(len).ok_or("file is corrupted")?;
+            let footer = Table::root(slice(data, start, len)?)?;
+            let schema = footer.table(1)?.ok_or("file is corrupted")?;
+            let blocks = footer.blocks(3)?;
+            return Ok(Self {
+                data,
+                fields: fields(schema)?,
+                blocks: Some(blocks),
+                start: 0,
+            });
+        }
+
+        let (message, _, start) = message(data, 0)
+            .ok()
+            .flatten()
+            .filter(|(message, ..)| message.u8(1, 0).is_ok_and(|kind| kind == 1))
+            .ok_or("not an Arrow IPC file")?;
+        let schema = message.table(2)?.ok_or("file is corrupted")?;
+        Ok(Self { data, fields: fields(schema)?, blocks: None, start })
+    }
+
+    /// Decode the selected columns of all record batches, stopping after
+    /// `limit` rows.
+    fn read(
+        &self,
+        selected: &[usize],
+        limit: Option<usize>,
+    ) -> StrResult<Vec<Vec<Value>>> {
+        let mut remaining = limit.unwrap_or(usize::MAX);
+        let mut columns = vec![vec![]; selected.len()];
+        let mut pos = self.start;
+        let mut i = 0;
+        while remaining > 0 {
+            let next = match &self.blocks {
+                Some(blocks) => match blocks.get(i) {
+                    Some(&block) => message(self.data, block)?,
+                    None => None,
+                },
+                None => message(self.data, pos)?,
+            };
+            let Some((message, body, end)) = next else { break };
+            pos = end;
+            i += 1;
+
+            match message.u8(1, 0)? {
+                // Record batch.
+                3 => {
+                    let batch = message.table(2)?.ok_or("file is corrupted")?;
+                    let rows = batch_rows(&batch)?.min(remaining);
+                    read_batch(&batch, body, &self.fields, selected, rows, &mut columns)?;
+                    remaining -= rows;
+                }
+                // Dictionary batch.
+                2 => bail!("dictionary-encoded columns are not supported"),
+                _ => {}
+            }
+        }
+        Ok(columns)
+    }
+}
+
+/// Read the encapsulated message at the given position and return its
+/// metadata, its body, and the position after it.
+fn message(data: &[u8], mut pos: usize) -> StrResult<Option<(Table<'_>, &[u8], usize)>> {
+    if pos >= data.len() {
+        return Ok(None);
+    }
+
+    // Newer writers put a continuation marker before the length.
+    let mut len = read_i32(data, pos)?;
+    pos += 4;
+    if len == -1 {
+        len = read_i32(data, pos)?;
+        pos += 4;
+    }
+
+    // A length of zero marks the end of a stream.
+    if len == 0 {
+        return Ok(None);
+    }
+
+    let len = usize::try_from(len).map_err(|_| "file is corrupted")?;
+    let message = Table::root(slice(data, pos, len)?)?;
+    pos += len;
+
+    let body_len =
+        usize::try_from(message.i64(3, 0)?).map_err(|_| "file is corrupted")?;
+    let body = slice(data, pos, body_len)?;
+    pos += body_len;
+
+    Ok(Some((message, body, pos)))
+}
+
+/// Parse the fields of a schema.
+fn fields(schema: Table) -> StrResult<Vec<Field>> {
+    if schema.i16(0, 0)? != 0 {
+        bail!("big-endian files are not supported");
+    }
+
+    schema
+        .tables(1)?
+        .into_iter()
+        .map(|field| -> StrResult<Field> {
+            let name = field.string(0)?;
+            if field.table(4)?.is_some() {
+                bail!("dictionary-encoded columns are not supported");
+            }
+
+            let ty = || -> StrResult<Table> {
+                field.table(3)?.ok_or_else(|| "file is corrupted".into())
+            };
+            let kind = match field.u8(2, 0)? {
+                1 => Kind::Null,
+                2 => {
+                    let ty = ty()?;
+                    let width = match ty.i32(0, 0)? {
+                        bits @ (8 | 16 | 32 | 64) => bits as usize / 8,
+                        _ => bail!("file is corrupted"),
+                    };
+                    Kind::Int { width, signed: ty.u8(1, 0)? != 0 }
+                }
+                3 => {
+                    let width = match ty()?.i16(0, 0)? {
+                        0 => 2,
+                        1 => 4,
+                        2 => 8,
+                        _ => bail!("file is corrupted"),
+                    };
+                    Kind::Float { width }
+                }
+                4 => Kind::Binary { large: false, text: false },
+                5 => Kind::Binary { large: false, text: true },
+                6 => Kind::Bool,
+                8 => Kind::Date { millis: ty()?.i16(0, 1)? == 1 },
+                15 => {
+                    let width = usize::try_from(ty()?.i32(0, 0)?)
+                        .map_err(|_| "file is corrupted")?;
+                    Kind::FixedBinary { width }
+                }
+                19 => Kind::Binary { large: true, text: false },
+                20 => Kind::Binary { large: true, text: true },
+                _ => bail!("column `{name}` has an unsupported type"),
+            };
+
+            Ok(Field { name, kind })
+        })
+        .collect()
+}
+
+/// The number of rows in a record batch.
+fn batch_rows(batch: &Table) -> StrResult<usize> {
+    usize::try_from(batch.i64(0, 0)?).map_err(|_| "file is corrupted".into())
+}
+
+/// Decode the first `rows` rows of the selected columns of a record batch.
+fn read_batch(
+    batch: &Table,
+    body: &[u8],
+    fields: &[Field],
+    selected: &[usize],
+    rows: usize,
+    columns: &mut [Vec<Value>],
+) -> StrResult<()> {
+    if batch.table(3)?.is_some() {
+        bail!("compressed record batches are not supported");
+    }
+
+    // Each column has a node with its length and null count and a number of
+    // buffers depending on its type.
+    let nodes = batch.pairs(1)?;
+    let buffers = batch
+        .pairs(2)?
+        .into_iter()
+        .map(|(offset, len)| slice(body, offset, len))
+        .collect::<StrResult<Vec<_>>>()?;
+
+    let mut start = 0;
+    let mut ranges = vec![];
+    for field in fields {
+        let end = start + field.kind.buffers();
+        ranges.push(start..end);
+        start = end;
+    }
+
+    for (out, &i) in columns.iter_mut().zip(selected) {
+        let &(len, nulls) = nodes.get(i).ok_or("file is corrupted")?;
+        let buffers = buffers.get(ranges[i].clone()).ok_or("file is corrupted")?;
+        read_column(fields[i].kind, buffers, len.min(rows), nulls, out)?;
+    }
+
+    Ok(())
+}
+
+/// Decode the first `len` values of a column.
+fn read_column(
+    kind: Kind,
+    buffers: &[&[u8]],
+    len: usize,
+    nulls: usize,
+    out: &mut Vec<Value>,
+) -> StrResult<()> {
+    let bit = |bytes: &[u8], i: usize| {
+        bytes
+            .get(i / 8)
+            .map(|byte| (byte >> (i % 8)) & 1 == 1)
+            .ok_or_else(|| EcoString::from("file is corrupted"))
+    };
+
+    for i in 0..len {
+        // The validity bitmap may be omitted if there are no nulls.
+        let valid = match kind {
+            Kind::Null => false,
+            _ if nulls == 0 || buffers[0].is_empty() => true,
+            _ => bit(buffers[0], i)?,
+        };
+        if !valid {
+            out.push(Value::None);
+            continue;
+        }
+
+        let data = buffers[1];
+        let value = match kind {
+            Kind::Null => unreachable!(),
+            Kind::Int { width, signed } => int(slice(data, i * width, width)?, signed),
+            Kind::Float { width } => {
+                let bytes = slice(data, i * width, width)?;
+                Value::Float(match width {
+                    2 => half(from_le(bytes) as u16),
+                    4 => f32::from_le_bytes(bytes.try_into().unwrap()).into(),
+                    _ => f64::from_le_bytes(bytes.try_into().unwrap()),
+                })
+            }
+            Kind::Bool => Value::Bool(bit(data, i)?),
+            Kind::Binary { large, text } => {
+                let width = if large { 8 } else { 4 };
+                let start = from_le(slice(data, i * width, width)?) as usize;
+                let end = from_le(slice(data, (i + 1) * width, width)?) as usize;
+                let len = end.checked_sub(start).ok_or("file is corrupted")?;
+                let bytes = slice(buffers[2], start, len)?;
+                if text {
+                    let text = std::str::from_utf8(bytes)
+                        .map_err(|_| "file contains invalid text")?;
+                    Value::Str(Str::from(text))
+                } else {
+                    Value::Bytes(Bytes::from(bytes))
+                }
+            }
+            Kind::Date { millis: false } => date(
+                i32::from_le_bytes(slice(data, i * 4, 4)?.try_into().unwrap()).into(),
+            )?,
+            Kind::Date { millis: true } => {
+                let millis =
+                    i64::from_le_bytes(slice(data, i * 8, 8)?.try_into().unwrap());
+                date(millis.div_euclid(86_400_000))?
+            }
+            Kind::FixedBinary { width } => {
+                Value::Bytes(Bytes::from(slice(data, i * width, width)?))
+            }
+        };
+        out.push(value);
+    }
+
+    Ok(())
+}
+
+/// Convert a little-endian integer into an integer value.
+///
+/// Unsigned integers that don't fit into a Typst integer become floats.
+fn int(bytes: &[u8], signed: bool) -> Value {
+    let v = from_le(bytes);
+    if signed {
+        let shift = 64 - 8 * bytes.len() as u32;
+        Value::Int(((v << shift) as i64) >> shift)
+    } else {
+        i64::try_from(v).map_or(Value::Float(v as f64), Value::Int)
+    }
+}
+
+/// Convert a half-precision floating point number.
+fn half(bits: u16) -> f64 {
+    let sign = if bits >> 15 == 1 { -1.0 } else { 1.0 };
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let fraction = f64::from(bits & 0x3ff);
+    sign * match exponent {
+        0 => fraction * 2f64.powi(-24),
+        31 if fraction == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + fraction / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+/// A table in a FlatBuffers message.
+#[derive(Copy, Clone)]
+struct Table<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Table<'a> {
+    /// The root table of a message.
+    fn root(data: &'a [u8]) -> StrResult<Self> {
+        Ok(Self { data, pos: read_u32(data, 0)? as usize })
+    }
+
+    /// The position of a field's value, if it is present.
+    fn field(&self, slot: usize) -> StrResult<Option<usize>> {
+        let offset = i64::from(read_i32(self.data, self.pos)?);
+        let vtable =
+            usize::try_from(self.pos as i64 - offset).map_err(|_| "file is corrupted")?;
+        let entry = 4 + 2 * slot;
+        if entry + 2 > usize::from(read_u16(self.data, vtable)?) {
+            return Ok(None);
+        }
+        let offset = read_u16(self.data, vtable + entry)?;
+        Ok((offset != 0).then(|| self.pos + usize::from(offset)))
+    }
+
+    /// The bytes of a scalar field.
+    fn scalar<const N: usize>(&self, slot: usize) -> StrResult<Option<[u8; N]>> {
+        self.field(slot)?
+            .map(|pos| Ok(slice(self.data, pos, N)?.try_into().unwrap()))
+            .transpose()
+    }
+
+    /// An unsigned byte field.
+    fn u8(&self, slot: usize, default: u8) -> StrResult<u8> {
+        Ok(self.scalar(slot)?.map_or(default, u8::from_le_bytes))
+    }
+
+    /// A 16-bit integer field.
+    fn i16(&self, slot: usize, default: i16) -> StrResult<i16> {
+        Ok(self.scalar(slot)?.map_or(default, i16::from_le_bytes))
+    }
+
+    /// A 32-bit integer field.
+    fn i32(&self, slot: usize, default: i32) -> StrResult<i32> {
+        Ok(self.scalar(slot)?.map_or(default, i32::from_le_bytes))
+    }
+
+    /// A 64-bit integer field.
+    fn i64(&self, slot: usize, default: i64) -> StrResult<i64> {
+        Ok(self.scalar(slot)?.map_or(default, i64::from_le_bytes))
+    }
+
+    /// The target position of an offset field.
+    fn indirect(&self, slot: usize) -> StrResult<Option<usize>> {
+        self.field(slot)?
+            .map(|pos| Ok(pos + read_u32(self.data, pos)? as usize))
+            .transpose()
+    }
+
+    /// A nested table.
+    fn table(&self, slot: usize) -> StrResult<Option<Self>> {
+        Ok(self.indirect(slot)?.map(|pos| Self { data: self.data, pos }))
+    }
+
+    /// The start and length of a vector.
+    fn vector(&self, slot: usize) -> StrResult<(usize, usize)> {
+        match self.indirect(slot)? {
+            Some(pos) => Ok((pos + 4, read_u32(self.data, pos)? as usize)),
+            None => Ok((0, 0)),
+        }
+    }
+
+    /// A string field.
+    fn string(&self, slot: usize) -> StrResult<EcoString> {
+        let (start, len) = self.vector(slot)?;
+        std::str::from_utf8(slice(self.data, start, len)?)
+            .map(EcoString::from)
+            .map_err(|_| "file contains invalid text".into())
+    }
+
+    /// A vector of tables.
+    fn tables(&self, slot: usize) -> StrResult<Vec<Self>> {
+        let (start, len) = self.vector(slot)?;
+        (0..len)
+            .map(|i| {
+                let pos = start + 4 * i;
+                Ok(Self {
+                    data: self.data,
+                    pos: pos + read_u32(self.data, pos)? as usize,
+                })
+            })
+            .collect()
+    }
+
+    /// The offsets of a vector of blocks, which locate messages in a file.
+    fn blocks(&self, slot: usize) -> StrResult<Vec<usize>> {
+        let (start, len) = self.vector(slot)?;
+        let bytes = slice(self.data, start, len.saturating_mul(24))?;
+        bytes
+            .chunks_exact(24)
+            .map(|chunk| {
+                let offset = i64::from_le_bytes(chunk[..8].try_into().unwrap());
+                usize::try_from(offset).map_err(|_| "file is corrupted".into())
+            })
+            .collect()
+    }
+
+    /// A vector of structs consisting of two non-negative 64-bit integers.
+    fn pairs(&self, slot: usize) -> StrResult<Vec<(usize, usize)>> {
+        let (start, len) = self.vector(slot)?;
+        let bytes = slice(self.data, start, len.saturating_mul(16))?;
+        bytes
+            .chunks_exact(16)
+            .map(|chunk| {
+                let a = i64::from_le_bytes(chunk[..8].try_into().unwrap());
+                let b = i64::from_le_bytes(chunk[8..].try_into().unwrap());
+                match (usize::try_from(a), usize::try_from(b)) {
+                    (Ok(a), Ok(b)) => Ok((a, b)),
+                    _ => Err("file is corrupted".into()),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Get a subslice or fail because the file is corrupted.
+fn slice(data: &[u8], start: usize, len: usize) -> StrResult<&[u8]> {
+    start
+        .checked_add(len)
+        .and_then(|end| data.get(start..end))
+        .ok_or_else(|| "file is corrupted".into())
+}
+
+/// Read a little-endian 16-bit integer.
+fn read_u16(data: &[u8], pos: usize) -> StrResult<u16> {
+    Ok(u16::from_le_bytes(slice(data, pos, 2)?.try_into().unwrap()))
+}
+
+/// Read a little-endian 32-bit integer.
+fn read_u32(data: &[u8], pos: usize) -> StrResult<u32> {
+    Ok(u32::from_le_bytes(slice(data, pos, 4)?.try_into().unwrap()))
+}
+
+/// Read a little-endian signed 32-bit integer.
+fn read_i32(data: &[u8], pos: usize) -> StrResult<i32> {
+    Ok(i32::from_le_bytes(slice(data, pos, 4)?.try_into().unwrap()))
+}
+
+/// Combine up to eight little-endian bytes into an integer.
+fn from_le(bytes: &[u8]) -> u64 {
+    bytes.iter().rev().fold(0, |v, &b| (v << 8) | u64::from(b))
+}
@@ -0,0 +1,65 @@
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{func, scope, Array, IntoValue, Value};
+use crate::loading::Readable;
+use crate::syntax::Spanned;
+use crate::visualize::geo::decode_features;
+use crate::World;
+
+/// Reads geographic features from a GeoJSON file.
+///
+/// The file must contain a GeoJSON feature collection, a single feature, or a
+/// bare geometry. The result is an array of features, each of which is a
+/// dictionary with the keys `id`, `geometry`, and `properties`. A bare
+/// geometry becomes a feature without an `id` and with empty `properties`. A
+/// feature's `geometry` is a dictionary with its `type` and `coordinates`, as
+/// in the file, or `{none}` if the feature has no geometry.
+///
+/// The features can be drawn with [`geo.map`]($geo.map).
+///
+/// # Example
+/// ```typ
+/// #let regions = geojson("regions.geojson")
+///
+/// #geo.map(
+///   regions,
+///   projection: "equal-area",
+///   fill: region => if region.properties.visited { blue } else { silver },
+/// )
+/// ```
+#[func(scope, title = "GeoJSON")]
+pub fn geojson(
+    /// The engine.
+    engine: &mut Engine,
+    /// Path to a GeoJSON file.
+    path: Spanned<EcoString>,
+) -> SourceResult<Array> {
+    let Spanned { v: path, span } = path;
+    let id = span.resolve_path(&path).at(span)?;
+    let data = engine.world.file(id).at(span)?;
+    geojson::decode(Spanned::new(Readable::Bytes(data), span))
+}
+
+#[scope]
+impl geojson {
+    /// Reads geographic features from a GeoJSON string/bytes.
+    #[func(title = "Decode GeoJSON")]
+    pub fn decode(
+        /// GeoJSON data.
+        data: Spanned<Readable>,
+    ) -> SourceResult<Array> {
+        let Spanned { v: data, span } = data;
+        let value: Value = serde_json::from_slice(data.as_slice())
+            .map_err(|err| eco_format!("failed to parse GeoJSON ({err})"))
+            .at(span)?;
+        let features = decode_features(value)
+            .map_err(|err| eco_format!("failed to parse GeoJSON ({err})"))
+            .at(span)?;
+        Ok(features
+            .into_iter()
+            .map(|feature| feature.dict.into_value())
+            .collect())
+    }
+}
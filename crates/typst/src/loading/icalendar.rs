@@ -0,0 +1,786 @@
+use ecow::{eco_format, EcoString};
+use time::{Date, Month, PrimitiveDateTime, Weekday};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    func, scope, Array, Datetime, Dict, Duration, IntoValue, Str, Value,
+};
+use crate::loading::{
+    content_lines, parse_datetime, split_text, unescape_text, ContentLine, Readable,
+};
+use crate::syntax::Spanned;
+use crate::World;
+
+/// Reads events, to-dos, and journal entries from an iCalendar file.
+///
+/// The file must contain a `VCALENDAR` component, as exported by most
+/// calendar applications into `.ics` files. The result is a dictionary with
+/// the calendar's properties and the arrays `events`, `todos`, and `journals`.
+/// Each entry in these arrays is again a dictionary that maps the lowercased
+/// property names (such as `summary`, `dtstart`, or `location`) to their
+/// values. The alarms of an event or to-do are listed under its `alarms` key.
+/// Time zone definitions and other components are skipped.
+///
+/// Property values are converted as follows:
+/// - Dates and date-times, such as those of `dtstart` and `dtend`, become
+///   [datetimes]($datetime). They are taken as written in the file: the time
+///   zone given by a `TZID` parameter or a trailing `Z` is not applied.
+/// - `duration` and relative `trigger` values become [durations]($duration).
+/// - `rrule` becomes a dictionary with the lowercased rule parts, for example
+///   `{(freq: "weekly", byday: ("MO", "WE"), count: 10)}`.
+/// - `attendee` and `organizer` become dictionaries with the `name` given by
+///   the `CN` parameter (or `{none}`) and the `email` address.
+/// - `geo` becomes an array of latitude and longitude, and `priority`,
+///   `sequence`, `percent-complete`, and `repeat` become integers.
+/// - Properties that may occur multiple times (`attendee`, `attach`,
+///   `categories`, `comment`, `contact`, `exdate`, `rdate`, `related-to`, and
+///   `resources`) are always arrays.
+/// - All other properties become strings.
+///
+/// Recurring events can be expanded into their individual occurrences with
+/// [`icalendar.occurrences`]($icalendar.occurrences).
+///
+/// # Example
+/// ```typ
+/// #let cal = icalendar("program.ics")
+/// #let talks = cal.events.sorted(key: event => event.dtstart)
+///
+/// #for talk in talks [
+///   / #talk.dtstart.display("[hour]:[minute]"): #talk.summary
+/// ]
+/// ```
+#[func(scope, title = "iCalendar")]
+pub fn icalendar(
+    /// The engine.
+    engine: &mut Engine,
+    /// Path to an iCalendar file.
+    path: Spanned<EcoString>,
+) -> SourceResult<Dict> {
+    let Spanned { v: path, span } = path;
+    let id = span.resolve_path(&path).at(span)?;
+    let data = engine.world.file(id).at(span)?;
+    icalendar::decode(Spanned::new(Readable::Bytes(data), span))
+}
+
+#[scope]
+impl icalendar {
+    /// Reads events, to-dos, and journal entries from an iCalendar
+    /// string/bytes.
+    #[func(title = "Decode iCalendar")]
+    pub fn decode(
+        /// iCalendar data.
+        data: Spanned<Readable>,
+    ) -> SourceResult<Dict> {
+        let Spanned { v: data, span } = data;
+        let text = std::str::from_utf8(data.as_slice())
+            .map_err(|_| "file is not valid utf-8")
+            .at(span)?;
+        decode_calendar(text)
+            .map_err(|err| eco_format!("failed to parse iCalendar ({err})"))
+            .at(span)
+    }
+
+    /// Expands a recurring event or to-do into its individual occurrences.
+    ///
+    /// The occurrences are computed from the `dtstart`, `rrule`, `rdate`, and
+    /// `exdate` properties. Each occurrence is a copy of the event whose
+    /// `dtstart` is moved to the start of the occurrence. Its `dtend` or `due`
+    /// is moved along, and the recurrence properties are removed. An event
+    /// without a recurrence rule has just itself as its only occurrence.
+    ///
+    /// Rules with a `freq` of `daily`, `weekly`, `monthly`, or `yearly` are
+    /// supported, along with the `interval`, `count`, `until`, `wkst`,
+    /// `bymonth`, `bymonthday`, `byday`, and `bysetpos` parts. If the rule
+    /// itself has neither a `count` nor an `until` part, either `until` or
+    /// `limit` must be specified.
+    ///
+    /// ```example
+    /// #let cal = icalendar.decode("BEGIN:VCALENDAR
+    /// BEGIN:VEVENT
+    /// SUMMARY:Reading group
+    /// DTSTART:20240301T180000
+    /// RRULE:FREQ=WEEKLY;BYDAY=FR;COUNT=4
+    /// EXDATE:20240315T180000
+    /// END:VEVENT
+    /// END:VCALENDAR")
+    ///
+    /// #for event in icalendar.occurrences(cal.events.first()) [
+    ///   - #event.dtstart.display()
+    /// ]
+    /// ```
+    #[func]
+    pub fn occurrences(
+        /// The event or to-do to expand, as returned by
+        /// [`icalendar`]($icalendar).
+        event: Spanned<Dict>,
+        /// Only occurrences that start on or before this date are returned.
+        #[named]
+        until: Option<Datetime>,
+        /// The maximum number of occurrences to return.
+        #[named]
+        limit: Option<usize>,
+    ) -> SourceResult<Array> {
+        let Spanned { v: event, span } = event;
+        expand(&event, until, limit).at(span)
+    }
+}
+
+/// Decode the components of an iCalendar file.
+fn decode_calendar(text: &str) -> Result<Dict, EcoString> {
+    let mut calendar: Option<Dict> = None;
+    let mut stack: Vec<(EcoString, Dict)> = vec![];
+
+    for line in content_lines(text)? {
+        match line.name.as_str() {
+            "BEGIN" => {
+                let name: EcoString = line.value.trim().to_uppercase().into();
+                let mut dict = Dict::new();
+                if name == "VCALENDAR" {
+                    for key in ["events", "todos", "journals"] {
+                        dict.insert(key.into(), Array::new().into_value());
+                    }
+                } else if stack.is_empty() {
+                    return Err(eco_format!(
+                        "expected BEGIN:VCALENDAR at line {}",
+                        line.line
+                    ));
+                }
+                stack.push((name, dict));
+            }
+            "END" => {
+                let name = line.value.trim().to_uppercase();
+                let Some((open, dict)) = stack.pop() else {
+                    return Err(eco_format!("unexpected END at line {}", line.line));
+                };
+                if open != name {
+                    return Err(eco_format!(
+                        "expected END:{open}, found END:{name} at line {}",
+                        line.line
+                    ));
+                }
+
+                match stack.last_mut() {
+                    Some((parent, parent_dict)) => {
+                        if let Some(key) = child_key(parent, &open) {
+                            push(parent_dict, key, dict.into_value());
+                        }
+                    }
+                    None => match &mut calendar {
+                        Some(calendar) => {
+                            for key in ["events", "todos", "journals"] {
+                                let Ok(Value::Array(items)) = dict.get(key) else {
+                                    continue;
+                                };
+                                for item in items.iter() {
+                                    push(calendar, key, item.clone());
+                                }
+                            }
+                        }
+                        None => calendar = Some(dict),
+                    },
+                }
+            }
+            _ => {
+                let Some((_, dict)) = stack.last_mut() else {
+                    return Err(eco_format!(
+                        "expected BEGIN:VCALENDAR at line {}",
+                        line.line
+                    ));
+                };
+                insert_property(dict, &line)?;
+            }
+        }
+    }
+
+    if let Some((open, _)) = stack.last() {
+        return Err(eco_format!("missing END:{open}"));
+    }
+
+    calendar.ok_or_else(|| "file contains no calendar".into())
+}
+
+/// The key under which a component is stored in its parent, if it is kept.
+fn child_key(parent: &str, child: &str) -> Option<&'static str> {
+    Some(match (parent, child) {
+        ("VCALENDAR", "VEVENT") => "events",
+        ("VCALENDAR", "VTODO") => "todos",
+        ("VCALENDAR", "VJOURNAL") => "journals",
+        ("VEVENT" | "VTODO", "VALARM") => "alarms",
+        _ => return None,
+    })
+}
+
+/// Append a value to the array stored under the given key.
+fn push(dict: &mut Dict, key: &str, value: Value) {
+    match dict.at_mut(key) {
+        Ok(Value::Array(items)) => items.push(value),
+        _ => dict.insert(key.into(), Array::from_iter([value]).into_value()),
+    }
+}
+
+/// Insert a property into the dictionary of its component.
+fn insert_property(dict: &mut Dict, line: &ContentLine) -> Result<(), EcoString> {
+    let key: Str = line.name.to_lowercase().into();
+    let value = property_value(line).ok_or_else(|| {
+        eco_format!("invalid {} value at line {}", line.name, line.line)
+    })?;
+
+    match line.name.as_str() {
+        "ATTENDEE" | "ATTACH" | "COMMENT" | "CONTACT" | "RELATED-TO" => {
+            push(dict, &key, value);
+        }
+        "CATEGORIES" | "EXDATE" | "RDATE" | "RESOURCES" => {
+            let Value::Array(values) = value else { unreachable!() };
+            for value in values {
+                push(dict, &key, value);
+            }
+        }
+        _ => {
+            if !dict.contains(&key) {
+                dict.insert(key, value);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert the value of a property into a Typst value.
+fn property_value(line: &ContentLine) -> Option<Value> {
+    let value = line.value.as_str();
+    Some(match line.name.as_str() {
+        "DTSTART" | "DTEND" | "DUE" | "DTSTAMP" | "CREATED" | "LAST-MODIFIED"
+        | "RECURRENCE-ID" | "COMPLETED" => parse_datetime(value)?.into_value(),
+        "EXDATE" | "RDATE" => value
+            .split(',')
+            // Periods are reduced to their start.
+            .map(|item| {
+                parse_datetime(item.split('/').next()?).map(IntoValue::into_value)
+            })
+            .collect::<Option<Array>>()?
+            .into_value(),
+        "DURATION" => parse_duration(value)?.into_value(),
+        "TRIGGER" => match line.param("VALUE") {
+            Some(kind) if kind.eq_ignore_ascii_case("DATE-TIME") => {
+                parse_datetime(value)?.into_value()
+            }
+            _ => parse_duration(value)?.into_value(),
+        },
+        "RRULE" => parse_rule(value)?.into_value(),
+        "CATEGORIES" | "RESOURCES" => split_text(value, ',')
+            .into_iter()
+            .map(IntoValue::into_value)
+            .collect::<Array>()
+            .into_value(),
+        "ATTENDEE" | "ORGANIZER" => {
+            let mut person = Dict::new();
+            let name = line.param("CN").map(unescape_text);
+            let email = value
+                .get(..7)
+                .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+                .map_or(value, |_| &value[7..]);
+            person.insert("name".into(), name.into_value());
+            person.insert("email".into(), email.into_value());
+            person.into_value()
+        }
+        "GEO" => {
+            let (lat, lon) = value.split_once(';')?;
+            let lat: f64 = lat.trim().parse().ok()?;
+            let lon: f64 = lon.trim().parse().ok()?;
+            Array::from_iter([lat.into_value(), lon.into_value()]).into_value()
+        }
+        "PRIORITY" | "SEQUENCE" | "PERCENT-COMPLETE" | "REPEAT" => {
+            value.trim().parse::<i64>().ok()?.into_value()
+        }
+        _ => unescape_text(value).into_value(),
+    })
+}
+
+/// Parse an iCalendar duration, such as `-P1DT2H30M`.
+fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let (negative, rest) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+
+    let mut rest = rest.strip_prefix(['P', 'p'])?;
+    let mut seconds: i64 = 0;
+    let mut in_time = false;
+    while !rest.is_empty() {
+        if let Some(tail) = rest.strip_prefix(['T', 't']) {
+            in_time = true;
+            rest = tail;
+            continue;
+        }
+
+        let end = rest.find(|c: char| !c.is_ascii_digit())?;
+        let n: i64 = rest[..end].parse().ok()?;
+        let unit = match (rest.as_bytes()[end].to_ascii_uppercase(), in_time) {
+            (b'W', false) => 7 * 86400,
+            (b'D', false) => 86400,
+            (b'H', true) => 3600,
+            (b'M', true) => 60,
+            (b'S', true) => 1,
+            _ => return None,
+        };
+        seconds = seconds.checked_add(n.checked_mul(unit)?)?;
+        rest = &rest[end + 1..];
+    }
+
+    Some(time::Duration::seconds(if negative { -seconds } else { seconds }).into())
+}
+
+/// Parse a recurrence rule, such as `FREQ=WEEKLY;BYDAY=MO,WE;COUNT=10`, into a
+/// dictionary.
+fn parse_rule(text: &str) -> Option<Dict> {
+    let mut dict = Dict::new();
+    for part in text.split(';').filter(|part| !part.trim().is_empty()) {
+        let (key, value) = part.split_once('=')?;
+        let key = key.trim().to_lowercase();
+        let value = value.trim();
+        let value = match key.as_str() {
+            "freq" => value.to_lowercase().into_value(),
+            "wkst" => value.to_uppercase().into_value(),
+            "interval" | "count" => value.parse::<i64>().ok()?.into_value(),
+            "until" => parse_datetime(value)?.into_value(),
+            "byday" => value
+                .split(',')
+                .map(|day| day.trim().to_uppercase().into_value())
+                .collect::<Array>()
+                .into_value(),
+            "bysecond" | "byminute" | "byhour" | "bymonthday" | "byyearday"
+            | "byweekno" | "bymonth" | "bysetpos" => value
+                .split(',')
+                .map(|n| n.trim().parse::<i64>().ok().map(IntoValue::into_value))
+                .collect::<Option<Array>>()?
+                .into_value(),
+            _ => value.into_value(),
+        };
+        dict.insert(key.into(), value);
+    }
+    Some(dict)
+}
+
+/// Expand an event into its occurrences.
+fn expand(
+    event: &Dict,
+    until: Option<Datetime>,
+    limit: Option<usize>,
+) -> StrResult<Array> {
+    let Ok(start) = event.get("dtstart") else {
+        bail!("event has no `dtstart`");
+    };
+    let start = start.clone().cast::<Datetime>()?;
+    let first = to_primitive(start)?;
+    let until = until.map(to_primitive).transpose()?;
+
+    let times = |key: &str| -> StrResult<Vec<PrimitiveDateTime>> {
+        match event.get(key) {
+            Ok(value) => value
+                .clone()
+                .cast::<Vec<Datetime>>()?
+                .into_iter()
+                .map(to_primitive)
+                .collect(),
+            Err(_) => Ok(vec![]),
+        }
+    };
+    let excluded = times("exdate")?;
+
+    let mut starts = match event.get("rrule") {
+        Ok(rule) => {
+            let rule = Rule::from_dict(&rule.clone().cast::<Dict>()?)?;
+            if rule.count.is_none()
+                && rule.until.is_none()
+                && until.is_none()
+                && limit.is_none()
+            {
+                bail!("recurrence is unbounded (specify `until` or `limit`)");
+            }
+            rule.expand(first, until, limit, &excluded)
+        }
+        Err(_) => vec![first],
+    };
+
+    starts.extend(times("rdate")?);
+    starts.retain(|start| !excluded.contains(start));
+    if let Some(until) = until {
+        starts.retain(|&start| start <= until);
+    }
+    starts.sort();
+    starts.dedup();
+    if let Some(limit) = limit {
+        starts.truncate(limit);
+    }
+
+    let mut occurrence = event.clone();
+    for key in ["rrule", "rdate", "exdate"] {
+        occurrence.take(key).ok();
+    }
+
+    starts
+        .into_iter()
+        .map(|moved| {
+            let shift: Duration = (moved - first).into();
+            let mut occurrence = occurrence.clone();
+            for key in ["dtstart", "dtend", "due"] {
+                if let Ok(value) = occurrence.get(key) {
+                    let datetime = value.clone().cast::<Datetime>()?;
+                    occurrence.insert(key.into(), (datetime + shift).into_value());
+                }
+            }
+            Ok(occurrence.into_value())
+        })
+        .collect()
+}
+
+/// Convert a date or date-time into a date-time for comparisons.
+fn to_primitive(datetime: Datetime) -> StrResult<PrimitiveDateTime> {
+    match datetime {
+        Datetime::Date(date) => Ok(date.midnight()),
+        Datetime::Datetime(datetime) => Ok(datetime),
+        Datetime::Time(_) => bail!("expected a date or date-time, found a time"),
+    }
+}
+
+/// How often a recurrence rule repeats.
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A recurrence rule.
+struct Rule {
+    frequency: Frequency,
+    interval: i64,
+    count: Option<usize>,
+    until: Option<PrimitiveDateTime>,
+    week_start: Weekday,
+    by_month: Vec<u8>,
+    by_month_day: Vec<i64>,
+    by_day: Vec<(Option<i64>, Weekday)>,
+    by_set_pos: Vec<i64>,
+}
+
+impl Rule {
+    /// Read a rule from its dictionary form.
+    fn from_dict(dict: &Dict) -> StrResult<Self> {
+        let get = |key: &str| dict.get(key).ok().cloned();
+        let Some(frequency) = get("freq") else {
+            bail!("recurrence rule has no `freq`");
+        };
+        let frequency = match frequency.cast::<EcoString>()?.as_str() {
+            "daily" => Frequency::Daily,
+            "weekly" => Frequency::Weekly,
+            "monthly" => Frequency::Monthly,
+            "yearly" => Frequency::Yearly,
+            other => bail!("unsupported recurrence frequency `{other}`"),
+        };
+
+        for key in ["bysecond", "byminute", "byhour", "byyearday", "byweekno"] {
+            if dict.contains(key) {
+                bail!("unsupported recurrence rule part `{key}`");
+            }
+        }
+
+        let ints = |key: &str| -> StrResult<Vec<i64>> {
+            get(key).map_or(Ok(vec![]), Value::cast)
+        };
+
+        let interval = get("interval").map_or(Ok(1), Value::cast::<i64>)?;
+        if interval < 1 {
+            bail!("recurrence interval must be positive");
+        }
+
+        let by_month = ints("bymonth")?
+            .into_iter()
+            .map(|month| {
+                u8::try_from(month)
+                    .ok()
+                    .filter(|month| (1..=12).contains(month))
+                    .ok_or_else(|| eco_format!("invalid month {month}"))
+            })
+            .collect::<StrResult<_>>()?;
+
+        let by_day = get("byday")
+            .map_or(Ok(vec![]), Value::cast::<Vec<EcoString>>)?
+            .iter()
+            .map(|day| {
+                parse_weekday_num(day)
+                    .ok_or_else(|| eco_format!("invalid weekday `{day}`"))
+            })
+            .collect::<StrResult<_>>()?;
+
+        let week_start = match get("wkst") {
+            Some(day) => {
+                let day = day.cast::<EcoString>()?;
+                parse_weekday(&day)
+                    .ok_or_else(|| eco_format!("invalid weekday `{day}`"))?
+            }
+            None => Weekday::Monday,
+        };
+
+        Ok(Self {
+            frequency,
+            interval,
+            count: get("count").map(Value::cast::<usize>).transpose()?,
+            until: get("until")
+                .map(|until| until.cast::<Datetime>().and_then(to_primitive))
+                .transpose()?,
+            week_start,
+            by_month,
+            by_month_day: ints("bymonthday")?,
+            by_day,
+            by_set_pos: ints("bysetpos")?,
+        })
+    }
+
+    /// Compute the occurrences of the rule, starting with `first`.
+    ///
+    /// Expansion stops once the rule's count or end, the given end, or the
+    /// given number of non-excluded occurrences is reached.
+    fn expand(
+        &self,
+        first: PrimitiveDateTime,
+        until: Option<PrimitiveDateTime>,
+        limit: Option<usize>,
+        excluded: &[PrimitiveDateTime],
+    ) -> Vec<PrimitiveDateTime> {
+        let end = match (self.until, until) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        let mut starts = vec![first];
+        let mut kept = usize::from(!excluded.contains(&first));
+        let done = |starts: &Vec<PrimitiveDateTime>, kept: usize| {
+            self.count.is_some_and(|count| starts.len() >= count)
+                || limit.is_some_and(|limit| kept >= limit)
+        };
+
+        if end.is_some_and(|end| first > end) || done(&starts, kept) {
+            return starts;
+        }
+
+        for period in 0.. {
+            let Some(mut dates) = self.period(first.date(), period) else { break };
+            dates.sort();
+            dates.dedup();
+            if !self.by_set_pos.is_empty() {
+                dates = select_positions(&dates, &self.by_set_pos);
+            }
+
+            for date in dates {
+                let start = date.with_time(first.time());
+                if start <= first {
+                    continue;
+                }
+                if end.is_some_and(|end| start > end) {
+                    return starts;
+                }
+                starts.push(start);
+                kept += usize::from(!excluded.contains(&start));
+                if done(&starts, kept) {
+                    return starts;
+                }
+            }
+        }
+
+        starts
+    }
+
+    /// The candidate dates in the given period after the one of the first
+    /// occurrence, or `None` once the dates are out of range.
+    fn period(&self, first: Date, period: i64) -> Option<Vec<Date>> {
+        let step = period.checked_mul(self.interval)?;
+        Some(match self.frequency {
+            Frequency::Daily => {
+                let date = add_days(first, step)?;
+                let matches = (self.by_month.is_empty()
+                    || self.by_month.contains(&u8::from(date.month())))
+                    && (self.by_month_day.is_empty()
+                        || month_days(date.year(), date.month(), &self.by_month_day)
+                            .contains(&date))
+                    && (self.by_day.is_empty()
+                        || self.by_day.iter().any(|&(_, day)| day == date.weekday()));
+                if matches {
+                    vec![date]
+                } else {
+                    vec![]
+                }
+            }
+            Frequency::Weekly => {
+                let offset = |day: Weekday| {
+                    i64::from(
+                        (day.number_days_from_monday() + 7
+                            - self.week_start.number_days_from_monday())
+                            % 7,
+                    )
+                };
+                let week =
+                    add_days(first, step.checked_mul(7)? - offset(first.weekday()))?;
+                let days = if self.by_day.is_empty() {
+                    vec![first.weekday()]
+                } else {
+                    self.by_day.iter().map(|&(_, day)| day).collect()
+                };
+                days.into_iter()
+                    .filter_map(|day| add_days(week, offset(day)))
+                    .filter(|date| {
+                        self.by_month.is_empty()
+                            || self.by_month.contains(&u8::from(date.month()))
+                    })
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let months = i64::from(u8::from(first.month())) - 1 + step;
+                let year = i32::try_from(i64::from(first.year()) + months.div_euclid(12))
+                    .ok()
+                    .filter(|&year| year <= 9999)?;
+                let month = Month::try_from(months.rem_euclid(12) as u8 + 1).ok()?;
+                if !self.by_month.is_empty() && !self.by_month.contains(&u8::from(month))
+                {
+                    return Some(vec![]);
+                }
+                self.days_in_month(year, month, first.day())
+            }
+            Frequency::Yearly => {
+                let year = i32::try_from(i64::from(first.year()) + step)
+                    .ok()
+                    .filter(|&year| year <= 9999)?;
+                if !self.by_month.is_empty() {
+                    self.by_month
+                        .iter()
+                        .filter_map(|&month| Month::try_from(month).ok())
+                        .flat_map(|month| self.days_in_month(year, month, first.day()))
+                        .collect()
+                } else if !self.by_day.is_empty() && self.by_month_day.is_empty() {
+                    let start = Date::from_calendar_date(year, Month::January, 1).ok()?;
+                    let end = Date::from_calendar_date(year, Month::December, 31).ok()?;
+                    weekdays(start, end, &self.by_day)
+                } else {
+                    self.days_in_month(year, first.month(), first.day())
+                }
+            }
+        })
+    }
+
+    /// The dates within a month that match the day-based parts of the rule.
+    fn days_in_month(&self, year: i32, month: Month, day: u8) -> Vec<Date> {
+        if !self.by_month_day.is_empty() {
+            month_days(year, month, &self.by_month_day)
+                .into_iter()
+                .filter(|date| {
+                    self.by_day.is_empty()
+                        || self.by_day.iter().any(|&(_, day)| day == date.weekday())
+                })
+                .collect()
+        } else if !self.by_day.is_empty() {
+            let Ok(start) = Date::from_calendar_date(year, month, 1) else {
+                return vec![];
+            };
+            let last = month_length(year, month);
+            let Ok(end) = Date::from_calendar_date(year, month, last) else {
+                return vec![];
+            };
+            weekdays(start, end, &self.by_day)
+        } else {
+            Date::from_calendar_date(year, month, day).into_iter().collect()
+        }
+    }
+}
+
+/// Add a number of days to a date, if the result is in range.
+fn add_days(date: Date, days: i64) -> Option<Date> {
+    date.checked_add(time::Duration::days(days))
+        .filter(|date| date.year() <= 9999)
+}
+
+/// The number of days in a month.
+fn month_length(year: i32, month: Month) -> u8 {
+    (28..31)
+        .rev()
+        .find(|&day| Date::from_calendar_date(year, month, day + 1).is_ok())
+        .map_or(28, |day| day + 1)
+}
+
+/// The dates of a month with the given day numbers. Negative numbers count
+/// from the end of the month.
+fn month_days(year: i32, month: Month, days: &[i64]) -> Vec<Date> {
+    let len = i64::from(month_length(year, month));
+    days.iter()
+        .filter_map(|&day| {
+            let day = if day < 0 { len + 1 + day } else { day };
+            let day = u8::try_from(day).ok().filter(|&day| day >= 1)?;
+            Date::from_calendar_date(year, month, day).ok()
+        })
+        .collect()
+}
+
+/// The dates between `start` and `end` (inclusive) on the given weekdays. An
+/// ordinal selects only the n-th such weekday, counting from the end if it is
+/// negative.
+fn weekdays(start: Date, end: Date, days: &[(Option<i64>, Weekday)]) -> Vec<Date> {
+    let mut dates = vec![];
+    for &(ordinal, weekday) in days {
+        let mut matching = vec![];
+        let mut date = start;
+        while date <= end {
+            if date.weekday() == weekday {
+                matching.push(date);
+            }
+            let Some(next) = date.next_day() else { break };
+            date = next;
+        }
+        match ordinal {
+            Some(n) => dates.extend(select_positions(&matching, &[n])),
+            None => dates.extend(matching),
+        }
+    }
+    dates
+}
+
+/// Select the items at the given 1-based positions. Negative positions count
+/// from the end.
+fn select_positions<T: Copy>(items: &[T], positions: &[i64]) -> Vec<T> {
+    let len = items.len() as i64;
+    let mut indices: Vec<i64> = positions
+        .iter()
+        .map(|&pos| if pos < 0 { len + pos } else { pos - 1 })
+        .filter(|index| (0..len).contains(index))
+        .collect();
+    indices.sort();
+    indices.dedup();
+    indices.into_iter().map(|index| items[index as usize]).collect()
+}
+
+/// Parse a weekday with an optional ordinal, such as `MO` or `-1FR`.
+fn parse_weekday_num(text: &str) -> Option<(Option<i64>, Weekday)> {
+    let text = text.trim();
+    let split = text.len().checked_sub(2)?;
+    let day = parse_weekday(text.get(split..)?)?;
+    let ordinal = match text.get(..split)? {
+        "" => None,
+        n => Some(n.parse::<i64>().ok().filter(|&n| n != 0)?),
+    };
+    Some((ordinal, day))
+}
+
+/// Parse a two-letter weekday, such as `MO`.
+fn parse_weekday(text: &str) -> Option<Weekday> {
+    Some(match text.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Monday,
+        "TU" => Weekday::Tuesday,
+        "WE" => Weekday::Wednesday,
+        "TH" => Weekday::Thursday,
+        "FR" => Weekday::Friday,
+        "SA" => Weekday::Saturday,
+        "SU" => Weekday::Sunday,
+        _ => return None,
+    })
+}
@@ -1,29 +1,56 @@
 //! Data loading.
 
+#[path = "arrow.rs"]
+mod arrow_;
 #[path = "cbor.rs"]
 mod cbor_;
 #[path = "csv.rs"]
 mod csv_;
+#[path = "geojson.rs"]
+mod geojson_;
+#[path = "icalendar.rs"]
+mod icalendar_;
 #[path = "json.rs"]
 mod json_;
+#[path = "parquet.rs"]
+mod parquet_;
 #[path = "read.rs"]
 mod read_;
+#[path = "sqlite.rs"]
+mod sqlite_;
 #[path = "toml.rs"]
 mod toml_;
+#[path = "vcard.rs"]
+mod vcard_;
+#[path = "xlsx.rs"]
+mod xlsx_;
 #[path = "xml.rs"]
 mod xml_;
 #[path = "yaml.rs"]
 mod yaml_;
 
+pub use self::arrow_::*;
 pub use self::cbor_::*;
 pub use self::csv_::*;
+pub use self::geojson_::*;
+pub use self::icalendar_::*;
 pub use self::json_::*;
+pub use self::parquet_::*;
 pub use self::read_::*;
+pub use self::sqlite_::*;
 pub use self::toml_::*;
+pub use self::vcard_::*;
+pub use self::xlsx_::*;
 pub use self::xml_::*;
 pub use self::yaml_::*;
 
-use crate::foundations::{cast, category, Bytes, Category, Scope, Str};
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{At, SourceResult, StrResult};
+use crate::foundations::{
+    cast, category, Array, Bytes, Category, Datetime, Dict, IntoValue, Scope, Str, Value,
+};
+use crate::syntax::Spanned;
 
 /// Data loading from external files.
 ///
@@ -42,6 +69,13 @@ pub(super) fn define(global: &mut Scope) {
     global.define_func::<yaml>();
     global.define_func::<cbor>();
     global.define_func::<xml>();
+    global.define_func::<sqlite>();
+    global.define_func::<parquet>();
+    global.define_func::<arrow>();
+    global.define_func::<icalendar>();
+    global.define_func::<vcard>();
+    global.define_func::<xlsx>();
+    global.define_func::<geojson>();
 }
 
 /// A value that can be read from a file.
@@ -80,3 +114,220 @@ impl From<Readable> for Bytes {
         }
     }
 }
+
+/// Resolve the columns selected from a columnar file to their indices.
+///
+/// Without an explicit selection, all columns are selected.
+fn select_columns(
+    names: &[EcoString],
+    columns: Option<Spanned<Vec<EcoString>>>,
+) -> SourceResult<Vec<usize>> {
+    let Some(Spanned { v: columns, span }) = columns else {
+        return Ok((0..names.len()).collect());
+    };
+
+    columns
+        .iter()
+        .map(|column| {
+            names
+                .iter()
+                .position(|name| name == column)
+                .ok_or_else(|| eco_format!("unknown column `{column}`"))
+                .at(span)
+        })
+        .collect()
+}
+
+/// Turn the decoded columns of a columnar file into one dictionary per row.
+fn columns_into_rows(
+    names: &[EcoString],
+    selected: &[usize],
+    columns: Vec<Vec<Value>>,
+) -> Array {
+    let len = columns.iter().map(Vec::len).min().unwrap_or(0);
+    let mut columns: Vec<_> = columns.into_iter().map(Vec::into_iter).collect();
+    (0..len)
+        .map(|_| {
+            let mut dict = Dict::new();
+            for (column, &i) in columns.iter_mut().zip(selected) {
+                dict.insert(names[i].as_str().into(), column.next().unwrap());
+            }
+            dict.into_value()
+        })
+        .collect()
+}
+
+/// Convert a number of days since the Unix epoch into a date.
+fn date(days: i64) -> StrResult<Value> {
+    // The Julian day of 1970-01-01.
+    const EPOCH: i64 = 2440588;
+    days.checked_add(EPOCH)
+        .and_then(|day| i32::try_from(day).ok())
+        .and_then(|day| time::Date::from_julian_day(day).ok())
+        .map(|date| Value::Datetime(Datetime::Date(date)))
+        .ok_or_else(|| "date is out of range".into())
+}
+
+/// A content line of an iCalendar or vCard file, such as
+/// `DTSTART;TZID=Europe/Berlin:20240301T090000`.
+struct ContentLine {
+    /// The uppercased property name, without a vCard group prefix.
+    name: EcoString,
+    /// The parameters with uppercased names and their raw values.
+    params: Vec<(EcoString, Vec<EcoString>)>,
+    /// The raw, still escaped value.
+    value: EcoString,
+    /// The line number at which the content line starts.
+    line: usize,
+}
+
+impl ContentLine {
+    /// The first value of the parameter with the given name.
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key == name)
+            .and_then(|(_, values)| values.first())
+            .map(EcoString::as_str)
+    }
+}
+
+/// Unfold and split iCalendar or vCard text into its content lines.
+fn content_lines(text: &str) -> Result<Vec<ContentLine>, EcoString> {
+    let mut unfolded: Vec<(usize, String)> = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix([' ', '\t']), unfolded.last_mut()) {
+            (Some(rest), Some((_, prev))) => prev.push_str(rest),
+            _ if line.trim().is_empty() => {}
+            _ => unfolded.push((i + 1, line.into())),
+        }
+    }
+
+    unfolded
+        .into_iter()
+        .map(|(line, text)| {
+            parse_content_line(&text, line)
+                .ok_or_else(|| eco_format!("malformed content line at line {line}"))
+        })
+        .collect()
+}
+
+/// Parse a single unfolded content line.
+fn parse_content_line(text: &str, line: usize) -> Option<ContentLine> {
+    let end = text.find([';', ':'])?;
+    let name = text[..end].rsplit('.').next()?.trim().to_uppercase();
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut params = vec![];
+    let mut rest = &text[end..];
+    while let Some(tail) = rest.strip_prefix(';') {
+        let end = tail.find(['=', ';', ':'])?;
+        let key = tail[..end].trim().to_uppercase();
+        let mut values = vec![];
+        rest = &tail[end..];
+        if let Some(mut tail) = rest.strip_prefix('=') {
+            loop {
+                let value;
+                if let Some(quoted) = tail.strip_prefix('"') {
+                    let end = quoted.find('"')?;
+                    value = &quoted[..end];
+                    tail = &quoted[end + 1..];
+                } else {
+                    let end = tail.find([',', ';', ':'])?;
+                    value = &tail[..end];
+                    tail = &tail[end..];
+                }
+                values.push(value.into());
+                match tail.strip_prefix(',') {
+                    Some(next) => tail = next,
+                    None => break,
+                }
+            }
+            rest = tail;
+        }
+        params.push((key.into(), values));
+    }
+
+    let value = rest.strip_prefix(':')?;
+    Some(ContentLine {
+        name: name.into(),
+        params,
+        value: value.into(),
+        line,
+    })
+}
+
+/// Split an escaped iCalendar or vCard text value at unescaped occurrences of
+/// the separator and unescape the parts.
+fn split_text(value: &str, separator: char) -> Vec<EcoString> {
+    let mut parts = vec![];
+    let mut part = EcoString::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n' | 'N') => part.push('\n'),
+                Some(c) => part.push(c),
+                None => part.push('\\'),
+            },
+            c if c == separator => parts.push(std::mem::take(&mut part)),
+            c => part.push(c),
+        }
+    }
+    parts.push(part);
+    parts
+}
+
+/// Unescape an iCalendar or vCard text value.
+fn unescape_text(value: &str) -> EcoString {
+    let mut chars = value.chars();
+    let mut text = EcoString::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n' | 'N') => text.push('\n'),
+                Some(c) => text.push(c),
+                None => text.push('\\'),
+            },
+            c => text.push(c),
+        }
+    }
+    text
+}
+
+/// Parse an iCalendar or vCard date (`19960415` or `1996-04-15`) or date-time
+/// (`19960415T133000Z`). The time zone is ignored.
+fn parse_datetime(text: &str) -> Option<Datetime> {
+    let (date, time) = match text.split_once(['T', 't']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (text, None),
+    };
+
+    let digits: String = date.chars().filter(|&c| c != '-').collect();
+    if digits.len() != 8 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let year = digits[..4].parse().ok()?;
+    let month = digits[4..6].parse().ok()?;
+    let day = digits[6..].parse().ok()?;
+
+    let Some(time) = time else {
+        return Datetime::from_ymd(year, month, day);
+    };
+
+    let time = time.trim_end_matches(['Z', 'z']);
+    let time = time.split(['+', '-']).next()?;
+    let digits: String = time.chars().filter(|&c| c != ':').collect();
+    let digits = digits.split('.').next()?;
+    if digits.len() != 6 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hour = digits[..2].parse().ok()?;
+    let minute = digits[2..4].parse().ok()?;
+    // Leap seconds are allowed in iCalendar, but not by Typst.
+    let second = digits[4..].parse::<u8>().ok()?.min(59);
+    Datetime::from_ymd_hms(year, month, day, hour, minute, second)
+}
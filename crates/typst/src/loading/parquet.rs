@@ -0,0 +1,995 @@
+use std::borrow::Cow;
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{func, scope, Array, Bytes, Str, Value};
+use crate::loading::{columns_into_rows, date, select_columns};
+use crate::syntax::Spanned;
+use crate::World;
+
+/// Reads a table from a Parquet file.
+///
+/// The table is returned as an array of dictionaries, one per row, that map
+/// from the column names to the values. Since Parquet files tend to be large,
+/// the columns to read and the number of rows can be restricted, which skips
+/// decoding the rest of the file.
+///
+/// Booleans, integers, and floating point numbers are converted into the
+/// Typst equivalents, strings become [strings]($str), dates become
+/// [datetimes]($datetime), and other binary data becomes [bytes]($bytes).
+/// Missing values become `{none}`.
+///
+/// # Example
+/// ```typ
+/// #let runs = parquet(
+///   "results.parquet",
+///   columns: ("name", "score"),
+///   limit: 10,
+/// )
+///
+/// #table(
+///   columns: 2,
+///   [*Name*], [*Score*],
+///   ..runs.map(row => (row.name, str(row.score))).flatten(),
+/// )
+/// ```
+///
+/// Only flat tables without nested or repeated columns are supported. The
+/// file may be uncompressed or compressed with Snappy, Gzip, or LZ4.
+#[func(scope)]
+pub fn parquet(
+    /// The engine.
+    engine: &mut Engine,
+    /// Path to a Parquet file.
+    path: Spanned<EcoString>,
+    /// The names of the columns to read. By default, all columns are read.
+    #[named]
+    columns: Option<Spanned<Vec<EcoString>>>,
+    /// The maximum number of rows to read. By default, all rows are read.
+    #[named]
+    limit: Option<usize>,
+) -> SourceResult<Array> {
+    let Spanned { v: path, span } = path;
+    let id = span.resolve_path(&path).at(span)?;
+    let data = engine.world.file(id).at(span)?;
+    parquet::decode(Spanned::new(data, span), columns, limit)
+}
+
+#[scope]
+impl parquet {
+    /// Reads a table from the bytes of a Parquet file.
+    #[func(title = "Decode Parquet")]
+    pub fn decode(
+        /// The Parquet file.
+        data: Spanned<Bytes>,
+        /// The names of the columns to read. By default, all columns are
+        /// read.
+        #[named]
+        columns: Option<Spanned<Vec<EcoString>>>,
+        /// The maximum number of rows to read. By default, all rows are read.
+        #[named]
+        limit: Option<usize>,
+    ) -> SourceResult<Array> {
+        let Spanned { v: data, span } = data;
+        let file = File::open(&data).map_err(format_parquet_error).at(span)?;
+        let names: Vec<_> = file.columns.iter().map(|c| c.name.clone()).collect();
+        let selected = select_columns(&names, columns)?;
+        let values =
+            file.read(&selected, limit).map_err(format_parquet_error).at(span)?;
+        Ok(columns_into_rows(&names, &selected, values))
+    }
+}
+
+/// Format the user-facing Parquet error message.
+fn format_parquet_error(error: EcoString) -> EcoString {
+    eco_format!("failed to read Parquet file ({error})")
+}
+
+/// The physical types of Parquet values.
+const BOOLEAN: i32 = 0;
+const INT32: i32 = 1;
+const INT64: i32 = 2;
+const INT96: i32 = 3;
+const FLOAT: i32 = 4;
+const DOUBLE: i32 = 5;
+const BYTE_ARRAY: i32 = 6;
+const FIXED_LEN_BYTE_ARRAY: i32 = 7;
+
+/// A parsed Parquet file.
+struct File<'a> {
+    data: &'a [u8],
+    columns: Vec<Column>,
+    row_groups: Vec<RowGroup>,
+}
+
+/// A column of the table.
+struct Column {
+    name: EcoString,
+    /// The physical type.
+    kind: i32,
+    /// The length of fixed-length byte arrays.
+    length: usize,
+    /// Whether values may be missing.
+    optional: bool,
+    /// How the physical values are interpreted.
+    logical: Logical,
+}
+
+/// How a column's physical values are interpreted.
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Logical {
+    Plain,
+    String,
+    Date,
+}
+
+/// A horizontal slice of the table.
+struct RowGroup {
+    rows: usize,
+    chunks: Vec<Chunk>,
+}
+
+/// The data of one column in a row group.
+struct Chunk {
+    /// The compression codec.
+    codec: i32,
+    /// The number of values, including missing ones.
+    values: usize,
+    /// The offset of the first page.
+    start: usize,
+}
+
+/// An element of the flattened schema tree.
+#[derive(Default)]
+struct SchemaElement {
+    kind: i32,
+    length: i32,
+    repetition: i32,
+    name: EcoString,
+    children: i32,
+    converted: Option<i32>,
+    logical: Option<i16>,
+}
+
+/// The header of a page in a column chunk.
+#[derive(Default)]
+struct PageHeader {
+    kind: i32,
+    uncompressed_size: usize,
+    compressed_size: usize,
+    values: usize,
+    encoding: i32,
+    /// The byte lengths of the repetition and definition levels in version 2
+    /// data pages.
+    levels: (usize, usize),
+    /// Whether a version 2 data page is compressed.
+    compressed: bool,
+}
+
+impl<'a> File<'a> {
+    /// Parse the file's metadata.
+    fn open(data: &'a [u8]) -> StrResult<Self> {
+        if data.len() < 12 || !data.starts_with(b"PAR1") || !data.ends_with(b"PAR1") {
+            bail!("not a Parquet file");
+        }
+
+        let end = data.len() - 8;
+        let len = u32::from_le_bytes(data[end..end + 4].try_into().unwrap());
+        let start = end
+            .checked_sub(len as usize)
+            .filter(|&start| start >= 4)
+            .ok_or("file is corrupted")?;
+
+        let mut schema = vec![];
+        let mut row_groups = vec![];
+        Thrift::new(&data[start..end]).fields(|p, id, kind| {
+            match id {
+                2 => schema = p.list(Thrift::schema_element)?,
+                4 => row_groups = p.list(|p| p.row_group(data.len()))?,
+                _ => p.skip(kind)?,
+            }
+            Ok(())
+        })?;
+
+        let (root, elements) = schema.split_first().ok_or("file is corrupted")?;
+        if usize::try_from(root.children).ok() != Some(elements.len())
+            || elements.iter().any(|e| e.children > 0 || e.repetition == 2)
+        {
+            bail!("nested columns are not supported");
+        }
+
+        let columns: Vec<_> = elements
+            .iter()
+            .map(|element| Column {
+                name: element.name.clone(),
+                kind: element.kind,
+                length: element.length.max(0) as usize,
+                optional: element.repetition == 1,
+                logical: match (element.logical, element.converted) {
+                    (Some(1 | 4 | 12), _) | (_, Some(0 | 4 | 19)) => Logical::String,
+                    (Some(6), _) | (_, Some(6)) => Logical::Date,
+                    _ => Logical::Plain,
+                },
+            })
+            .collect();
+
+        if row_groups.iter().any(|group| group.chunks.len() != columns.len()) {
+            bail!("file is corrupted");
+        }
+
+        Ok(Self { data, columns, row_groups })
+    }
+
+    /// Decode the selected columns, stopping after `limit` rows.
+    fn read(
+        &self,
+        selected: &[usize],
+        limit: Option<usize>,
+    ) -> StrResult<Vec<Vec<Value>>> {
+        let limit = limit.unwrap_or(usize::MAX);
+        let mut columns = vec![vec![]; selected.len()];
+        let mut rows = 0;
+        for group in &self.row_groups {
+            if rows >= limit {
+                break;
+            }
+            for (values, &i) in columns.iter_mut().zip(selected) {
+                self.read_chunk(&self.columns[i], &group.chunks[i], values)?;
+                values.truncate(limit);
+            }
+            rows += group.rows;
+        }
+        Ok(columns)
+    }
+
+    /// Decode the pages of a column chunk.
+    fn read_chunk(
+        &self,
+        column: &Column,
+        chunk: &Chunk,
+        out: &mut Vec<Value>,
+    ) -> StrResult<()> {
+        let mut pos = chunk.start;
+        let mut dict = vec![];
+        let mut read = 0;
+        while read < chunk.values {
+            let mut p = Thrift::new(self.data.get(pos..).ok_or("file is corrupted")?);
+            let header = p.page_header()?;
+            pos += p.pos;
+            let body = slice(self.data, pos, header.compressed_size)?;
+            pos += header.compressed_size;
+
+            let (levels, data) = match header.kind {
+                // Data page (version 1): The levels are compressed along with
+                // the values and prefixed with their length.
+                0 => {
+                    let data = decompress(chunk.codec, body, header.uncompressed_size)?;
+                    if !column.optional {
+                        (None, data)
+                    } else {
+                        let len = read_u32(&data, 0)? as usize;
+                        let levels = hybrid(slice(&data, 4, len)?, 1, header.values)?;
+                        let rest = data[4 + len..].to_vec();
+                        (Some(levels), Cow::Owned(rest))
+                    }
+                }
+                // Dictionary page: Holds the values that data pages refer to.
+                2 => {
+                    let data = decompress(chunk.codec, body, header.uncompressed_size)?;
+                    dict = plain(column, &data, header.values)?;
+                    continue;
+                }
+                // Data page (version 2): The levels are stored uncompressed
+                // in front of the values.
+                3 => {
+                    let (repetition, definition) = header.levels;
+                    let levels = column
+                        .optional
+                        .then(|| {
+                            hybrid(slice(body, repetition, definition)?, 1, header.values)
+                        })
+                        .transpose()?;
+                    let rest =
+                        body.get(repetition + definition..).ok_or("file is corrupted")?;
+                    let data = if header.compressed {
+                        let size = header
+                            .uncompressed_size
+                            .saturating_sub(repetition + definition);
+                        decompress(chunk.codec, rest, size)?
+                    } else {
+                        Cow::Borrowed(rest)
+                    };
+                    (levels, data)
+                }
+                // Index pages are not needed.
+                _ => continue,
+            };
+
+            let present = levels.as_ref().map_or(header.values, |levels| {
+                levels.iter().filter(|&&level| level == 1).count()
+            });
+            let mut values =
+                decode(column, header.encoding, &data, present, &dict)?.into_iter();
+            match levels {
+                Some(levels) => out.extend(levels.into_iter().map(|level| {
+                    if level == 1 {
+                        values.next().unwrap_or(Value::None)
+                    } else {
+                        Value::None
+                    }
+                })),
+                None => out.extend(values),
+            }
+
+            read += header.values;
+        }
+
+        Ok(())
+    }
+}
+
+/// Decode `n` values in the given encoding.
+fn decode(
+    column: &Column,
+    encoding: i32,
+    data: &[u8],
+    n: usize,
+    dict: &[Value],
+) -> StrResult<Vec<Value>> {
+    match encoding {
+        // Plain.
+        0 => plain(column, data, n),
+        // Dictionary indices.
+        2 | 8 => {
+            let width = *data.first().ok_or("file is corrupted")?;
+            hybrid(&data[1..], width, n)?
+                .into_iter()
+                .map(|i| dict.get(i).cloned().ok_or_else(|| "file is corrupted".into()))
+                .collect()
+        }
+        // Run-length encoded booleans.
+        3 if column.kind == BOOLEAN => {
+            let len = read_u32(data, 0)? as usize;
+            Ok(hybrid(slice(data, 4, len)?, 1, n)?
+                .into_iter()
+                .map(|v| Value::Bool(v == 1))
+                .collect())
+        }
+        // Deltas between integers.
+        5 => delta(data, &mut 0)?
+            .into_iter()
+            .take(n)
+            .map(|v| integer(column, v))
+            .collect(),
+        // Delta-encoded lengths followed by the byte arrays.
+        6 => {
+            let mut pos = 0;
+            let lengths = delta(data, &mut pos)?;
+            lengths
+                .into_iter()
+                .take(n)
+                .map(|len| {
+                    let len = usize::try_from(len).map_err(|_| "file is corrupted")?;
+                    let bytes = slice(data, pos, len)?;
+                    pos += len;
+                    binary(column, bytes)
+                })
+                .collect()
+        }
+        // Byte arrays sharing a prefix with their predecessor.
+        7 => {
+            let mut pos = 0;
+            let prefixes = delta(data, &mut pos)?;
+            let lengths = delta(data, &mut pos)?;
+            let mut last = vec![];
+            prefixes
+                .into_iter()
+                .zip(lengths)
+                .take(n)
+                .map(|(prefix, len)| {
+                    let prefix =
+                        usize::try_from(prefix).map_err(|_| "file is corrupted")?;
+                    let len = usize::try_from(len).map_err(|_| "file is corrupted")?;
+                    if prefix > last.len() {
+                        bail!("file is corrupted");
+                    }
+                    last.truncate(prefix);
+                    last.extend_from_slice(slice(data, pos, len)?);
+                    pos += len;
+                    binary(column, &last)
+                })
+                .collect()
+        }
+        // The bytes of fixed-width values, split into one stream per byte.
+        9 => {
+            let width = match column.kind {
+                INT32 | FLOAT => 4,
+                INT64 | DOUBLE => 8,
+                FIXED_LEN_BYTE_ARRAY => column.length,
+                _ => bail!("file is corrupted"),
+            };
+            let streams = slice(data, 0, n.saturating_mul(width))?;
+            let mut joined = vec![0; streams.len()];
+            for (i, &byte) in streams.iter().enumerate() {
+                joined[(i % n) * width + i / n] = byte;
+            }
+            plain(column, &joined, n)
+        }
+        _ => bail!("unsupported encoding"),
+    }
+}
+
+/// Decode `n` plainly encoded values.
+fn plain(column: &Column, data: &[u8], n: usize) -> StrResult<Vec<Value>> {
+    let mut values = Vec::with_capacity(n.min(data.len()));
+    let mut pos = 0;
+    let mut take = |len: usize| {
+        let bytes = slice(data, pos, len)?;
+        pos += len;
+        Ok::<_, EcoString>(bytes)
+    };
+
+    for i in 0..n {
+        let value = match column.kind {
+            BOOLEAN => {
+                let byte = data.get(i / 8).ok_or("file is corrupted")?;
+                Value::Bool((byte >> (i % 8)) & 1 == 1)
+            }
+            INT32 => {
+                integer(column, i32::from_le_bytes(take(4)?.try_into().unwrap()).into())?
+            }
+            INT64 => integer(column, i64::from_le_bytes(take(8)?.try_into().unwrap()))?,
+            INT96 => Value::Bytes(take(12)?.into()),
+            FLOAT => {
+                Value::Float(f32::from_le_bytes(take(4)?.try_into().unwrap()).into())
+            }
+            DOUBLE => Value::Float(f64::from_le_bytes(take(8)?.try_into().unwrap())),
+            BYTE_ARRAY => {
+                let len = u32::from_le_bytes(take(4)?.try_into().unwrap());
+                binary(column, take(len as usize)?)?
+            }
+            FIXED_LEN_BYTE_ARRAY => binary(column, take(column.length)?)?,
+            _ => bail!("unsupported column type"),
+        };
+        values.push(value);
+    }
+
+    Ok(values)
+}
+
+/// Convert an integer into an integer or date value.
+fn integer(column: &Column, v: i64) -> StrResult<Value> {
+    match (column.kind, column.logical) {
+        (INT32, Logical::Date) => date(v),
+        // Deltas between 32-bit integers may overflow.
+        (INT32, _) => Ok(Value::Int((v as i32).into())),
+        (INT64, _) => Ok(Value::Int(v)),
+        _ => bail!("file is corrupted"),
+    }
+}
+
+/// Convert a byte array into a string or bytes.
+fn binary(column: &Column, bytes: &[u8]) -> StrResult<Value> {
+    Ok(if column.logical == Logical::String {
+        let text =
+            std::str::from_utf8(bytes).map_err(|_| "file contains invalid text")?;
+        Value::Str(Str::from(text))
+    } else {
+        Value::Bytes(Bytes::from(bytes))
+    })
+}
+
+/// Decode `count` integers in the hybrid of run-length encoding and bit
+/// packing that Parquet uses for levels and dictionary indices.
+fn hybrid(data: &[u8], width: u8, count: usize) -> StrResult<Vec<usize>> {
+    if width > 32 {
+        bail!("file is corrupted");
+    }
+
+    let width = usize::from(width);
+    let mut out = Vec::with_capacity(count.min(data.len() * 8));
+    let mut pos = 0;
+    while out.len() < count {
+        let header = read_varint(data, &mut pos)?;
+        let remaining = count - out.len();
+        if header & 1 == 0 {
+            // A run of a repeated value.
+            let len = usize::try_from(header >> 1).unwrap_or(usize::MAX);
+            let bytes = slice(data, pos, width.div_ceil(8))?;
+            pos += bytes.len();
+            let value = from_le(bytes);
+            out.extend(std::iter::repeat(value).take(len.min(remaining)));
+        } else {
+            // Groups of eight bit-packed values.
+            let n = usize::try_from(header >> 1).unwrap_or(usize::MAX).saturating_mul(8);
+            let packed = slice(data, pos, n.saturating_mul(width) / 8)?;
+            pos += packed.len();
+            out.extend((0..n.min(remaining)).map(|i| unpack(packed, width, i) as usize));
+        }
+    }
+
+    Ok(out)
+}
+
+/// Decode integers in the delta binary packed encoding, which stores the
+/// bit-packed differences between consecutive values in blocks.
+fn delta(data: &[u8], pos: &mut usize) -> StrResult<Vec<i64>> {
+    let block = usize::try_from(read_varint(data, pos)?).unwrap_or(usize::MAX);
+    let miniblocks = usize::try_from(read_varint(data, pos)?).unwrap_or(usize::MAX);
+    let count = usize::try_from(read_varint(data, pos)?).unwrap_or(usize::MAX);
+    let mut value = zigzag(read_varint(data, pos)?);
+    if miniblocks == 0 || block % miniblocks != 0 || (block / miniblocks) % 8 != 0 {
+        bail!("file is corrupted");
+    }
+
+    let per_miniblock = block / miniblocks;
+    let mut out = Vec::with_capacity(count.min(data.len() * 8));
+    if count > 0 {
+        out.push(value);
+    }
+
+    while out.len() < count {
+        let min = zigzag(read_varint(data, pos)?);
+        let widths = slice(data, *pos, miniblocks)?;
+        *pos += miniblocks;
+
+        // The bodies of miniblocks after the last value are omitted.
+        for &width in widths {
+            if out.len() >= count {
+                break;
+            }
+
+            let width = usize::from(width);
+            if width > 64 {
+                bail!("file is corrupted");
+            }
+
+            let packed = slice(data, *pos, per_miniblock.saturating_mul(width) / 8)?;
+            *pos += packed.len();
+            for i in 0..per_miniblock.min(count - out.len()) {
+                let delta = unpack(packed, width, i) as i64;
+                value = value.wrapping_add(min).wrapping_add(delta);
+                out.push(value);
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Extract the `i`-th of several `width`-bit integers that are packed
+/// starting from the least significant bit.
+fn unpack(packed: &[u8], width: usize, i: usize) -> u64 {
+    (0..width)
+        .filter(|&j| (packed[(i * width + j) / 8] >> ((i * width + j) % 8)) & 1 == 1)
+        .fold(0, |v, j| v | (1 << j))
+}
+
+/// Decode a zigzag-encoded signed integer.
+fn zigzag(v: u64) -> i64 {
+    (v >> 1) as i64 ^ -((v & 1) as i64)
+}
+
+/// Decompress the data of a page.
+fn decompress(codec: i32, data: &[u8], size: usize) -> StrResult<Cow<'_, [u8]>> {
+    let out = match codec {
+        0 => return Ok(Cow::Borrowed(data)),
+        1 => snappy(data)?,
+        2 => gzip(data)?,
+        7 => lz4(data, size)?,
+        _ => {
+            let name = match codec {
+                3 => "LZO",
+                4 => "Brotli",
+                5 => "Hadoop LZ4",
+                6 => "Zstandard",
+                _ => "unknown",
+            };
+            bail!("{name} compression is not supported");
+        }
+    };
+    Ok(Cow::Owned(out))
+}
+
+/// Decompress raw Snappy data.
+fn snappy(data: &[u8]) -> StrResult<Vec<u8>> {
+    let mut pos = 0;
+    let size = usize::try_from(read_varint(data, &mut pos)?).unwrap_or(usize::MAX);
+    let mut out = Vec::with_capacity(size.min(data.len().saturating_mul(32)));
+    while let Some(&tag) = data.get(pos) {
+        pos += 1;
+        let (len, offset) = match tag & 3 {
+            // Literal bytes, whose length may follow the tag.
+            0 => {
+                let mut len = usize::from(tag >> 2);
+                if len >= 60 {
+                    let bytes = slice(data, pos, len - 59)?;
+                    pos += bytes.len();
+                    len = from_le(bytes);
+                }
+                out.extend_from_slice(slice(data, pos, len + 1)?);
+                pos += len + 1;
+                continue;
+            }
+            // Copies of earlier output with differently sized offsets.
+            1 => {
+                let low = *data.get(pos).ok_or("file is corrupted")?;
+                pos += 1;
+                (
+                    usize::from((tag >> 2) & 7) + 4,
+                    (usize::from(tag >> 5) << 8) | usize::from(low),
+                )
+            }
+            n => {
+                let bytes = slice(data, pos, if n == 2 { 2 } else { 4 })?;
+                pos += bytes.len();
+                (usize::from(tag >> 2) + 1, from_le(bytes))
+            }
+        };
+        copy_back(&mut out, offset, len)?;
+    }
+
+    if out.len() != size {
+        bail!("file is corrupted");
+    }
+
+    Ok(out)
+}
+
+/// Decompress Gzip data.
+fn gzip(data: &[u8]) -> StrResult<Vec<u8>> {
+    if !data.starts_with(&[0x1f, 0x8b, 8]) || data.len() < 10 {
+        bail!("file is corrupted");
+    }
+
+    // Skip the optional header fields.
+    let flags = data[3];
+    let mut pos = 10;
+    if flags & 4 != 0 {
+        pos += 2 + from_le(slice(data, pos, 2)?);
+    }
+    for flag in [8, 16] {
+        if flags & flag != 0 {
+            let len = data.get(pos..).and_then(|rest| rest.iter().position(|&b| b == 0));
+            pos += len.ok_or("file is corrupted")? + 1;
+        }
+    }
+    if flags & 2 != 0 {
+        pos += 2;
+    }
+
+    let deflated = data.get(pos..).ok_or("file is corrupted")?;
+    miniz_oxide::inflate::decompress_to_vec(deflated)
+        .map_err(|_| "file is corrupted".into())
+}
+
+/// Decompress a raw LZ4 block.
+fn lz4(data: &[u8], size: usize) -> StrResult<Vec<u8>> {
+    let length = |pos: &mut usize| -> StrResult<usize> {
+        let mut len = 0;
+        loop {
+            let byte = *data.get(*pos).ok_or("file is corrupted")?;
+            *pos += 1;
+            len += usize::from(byte);
+            if byte != 255 {
+                return Ok(len);
+            }
+        }
+    };
+
+    let mut out = Vec::with_capacity(size.min(data.len().saturating_mul(255)));
+    let mut pos = 0;
+    loop {
+        let token = *data.get(pos).ok_or("file is corrupted")?;
+        pos += 1;
+
+        let mut literals = usize::from(token >> 4);
+        if literals == 15 {
+            literals += length(&mut pos)?;
+        }
+        out.extend_from_slice(slice(data, pos, literals)?);
+        pos += literals;
+
+        // The last sequence only consists of literals.
+        if pos == data.len() {
+            break;
+        }
+
+        let offset = from_le(slice(data, pos, 2)?);
+        pos += 2;
+        let mut len = usize::from(token & 15);
+        if len == 15 {
+            len += length(&mut pos)?;
+        }
+        copy_back(&mut out, offset, len + 4)?;
+    }
+
+    Ok(out)
+}
+
+/// Append a copy of earlier output, which may overlap with itself.
+fn copy_back(out: &mut Vec<u8>, offset: usize, len: usize) -> StrResult<()> {
+    if offset == 0 || offset > out.len() {
+        bail!("file is corrupted");
+    }
+    let start = out.len() - offset;
+    for i in start..start + len {
+        out.push(out[i]);
+    }
+    Ok(())
+}
+
+/// Get a subslice or fail because the file is corrupted.
+fn slice(data: &[u8], start: usize, len: usize) -> StrResult<&[u8]> {
+    start
+        .checked_add(len)
+        .and_then(|end| data.get(start..end))
+        .ok_or_else(|| "file is corrupted".into())
+}
+
+/// Read a little-endian 32-bit integer.
+fn read_u32(data: &[u8], pos: usize) -> StrResult<u32> {
+    Ok(u32::from_le_bytes(slice(data, pos, 4)?.try_into().unwrap()))
+}
+
+/// Read an unsigned LEB128 variable-length integer.
+fn read_varint(data: &[u8], pos: &mut usize) -> StrResult<u64> {
+    let mut v = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*pos).ok_or("file is corrupted")?;
+        *pos += 1;
+        v |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(v);
+        }
+    }
+    bail!("file is corrupted")
+}
+
+/// Combine up to eight little-endian bytes into an integer.
+fn from_le(bytes: &[u8]) -> usize {
+    bytes.iter().rev().fold(0, |v, &b| (v << 8) | usize::from(b))
+}
+
+/// A reader for the Thrift compact protocol, in which Parquet's metadata is
+/// encoded.
+struct Thrift<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Thrift<'a> {
+    /// Create a reader at the start of the data.
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Read a single byte.
+    fn byte(&mut self) -> StrResult<u8> {
+        let byte = *self.data.get(self.pos).ok_or("file is corrupted")?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    /// Read a zigzag-encoded integer.
+    fn int(&mut self) -> StrResult<i64> {
+        read_varint(self.data, &mut self.pos).map(zigzag)
+    }
+
+    /// Read a 32-bit integer.
+    fn i32(&mut self) -> StrResult<i32> {
+        i32::try_from(self.int()?).map_err(|_| "file is corrupted".into())
+    }
+
+    /// Read a non-negative integer.
+    fn size(&mut self) -> StrResult<usize> {
+        usize::try_from(self.int()?).map_err(|_| "file is corrupted".into())
+    }
+
+    /// Read a length-prefixed string.
+    fn string(&mut self) -> StrResult<EcoString> {
+        let len = read_varint(self.data, &mut self.pos)?;
+        let bytes =
+            slice(self.data, self.pos, usize::try_from(len).unwrap_or(usize::MAX))?;
+        self.pos += bytes.len();
+        std::str::from_utf8(bytes)
+            .map(EcoString::from)
+            .map_err(|_| "file contains invalid text".into())
+    }
+
+    /// Read the fields of a struct, calling `f` with each field's ID and
+    /// type.
+    fn fields(
+        &mut self,
+        mut f: impl FnMut(&mut Self, i16, u8) -> StrResult<()>,
+    ) -> StrResult<()> {
+        let mut last = 0;
+        loop {
+            let header = self.byte()?;
+            if header == 0 {
+                return Ok(());
+            }
+
+            let delta = i16::from(header >> 4);
+            let id = if delta == 0 {
+                i16::try_from(self.int()?).map_err(|_| "file is corrupted")?
+            } else {
+                last + delta
+            };
+            last = id;
+            f(self, id, header & 0x0f)?;
+        }
+    }
+
+    /// Read the header of a list and return the element type and length.
+    fn list_header(&mut self) -> StrResult<(u8, usize)> {
+        let header = self.byte()?;
+        let len = match header >> 4 {
+            15 => usize::try_from(read_varint(self.data, &mut self.pos)?)
+                .map_err(|_| "file is corrupted")?,
+            n => usize::from(n),
+        };
+        Ok((header & 0x0f, len))
+    }
+
+    /// Read a list, calling `f` for each element.
+    fn list<T>(&mut self, f: impl Fn(&mut Self) -> StrResult<T>) -> StrResult<Vec<T>> {
+        let (_, len) = self.list_header()?;
+        (0..len).map(|_| f(self)).collect()
+    }
+
+    /// Skip a value of the given type.
+    fn skip(&mut self, kind: u8) -> StrResult<()> {
+        match kind {
+            1 | 2 => {}
+            3 => self.pos += 1,
+            4..=6 => {
+                read_varint(self.data, &mut self.pos)?;
+            }
+            7 => self.pos += 8,
+            8 => {
+                let len = read_varint(self.data, &mut self.pos)?;
+                self.pos =
+                    self.pos.saturating_add(usize::try_from(len).unwrap_or(usize::MAX));
+            }
+            9 | 10 => {
+                let (element, len) = self.list_header()?;
+                for _ in 0..len {
+                    // Booleans in lists take up a full byte.
+                    self.skip(if matches!(element, 1 | 2) { 3 } else { element })?;
+                }
+            }
+            11 => {
+                let len = read_varint(self.data, &mut self.pos)?;
+                if len > 0 {
+                    let types = self.byte()?;
+                    for _ in 0..len {
+                        self.skip(types >> 4)?;
+                        self.skip(types & 0x0f)?;
+                    }
+                }
+            }
+            12 => self.fields(|p, _, kind| p.skip(kind))?,
+            _ => bail!("file is corrupted"),
+        }
+
+        if self.pos > self.data.len() {
+            bail!("file is corrupted");
+        }
+
+        Ok(())
+    }
+
+    /// Read an element of the schema.
+    fn schema_element(&mut self) -> StrResult<SchemaElement> {
+        let mut element = SchemaElement::default();
+        self.fields(|p, id, kind| {
+            match id {
+                1 => element.kind = p.i32()?,
+                2 => element.length = p.i32()?,
+                3 => element.repetition = p.i32()?,
+                4 => element.name = p.string()?,
+                5 => element.children = p.i32()?,
+                6 => element.converted = Some(p.i32()?),
+                // The logical type is a union, whose set field determines the
+                // type.
+                10 => p.fields(|p, id, kind| {
+                    element.logical = Some(id);
+                    p.skip(kind)
+                })?,
+                _ => p.skip(kind)?,
+            }
+            Ok(())
+        })?;
+        Ok(element)
+    }
+
+    /// Read the metadata of a row group.
+    fn row_group(&mut self, file_len: usize) -> StrResult<RowGroup> {
+        let mut rows = 0;
+        let mut chunks = vec![];
+        self.fields(|p, id, kind| {
+            match id {
+                1 => chunks = p.list(|p| p.chunk(file_len))?,
+                3 => rows = p.size()?,
+                _ => p.skip(kind)?,
+            }
+            Ok(())
+        })?;
+        Ok(RowGroup { rows, chunks })
+    }
+
+    /// Read the metadata of a column chunk.
+    fn chunk(&mut self, file_len: usize) -> StrResult<Chunk> {
+        let mut external = false;
+        let (mut codec, mut values) = (0, 0);
+        let (mut data_offset, mut dict_offset) = (0, None);
+        self.fields(|p, id, kind| {
+            match id {
+                1 => external = !p.string()?.is_empty(),
+                3 => p.fields(|p, id, kind| {
+                    match id {
+                        4 => codec = p.i32()?,
+                        5 => values = p.size()?,
+                        9 => data_offset = p.size()?,
+                        11 => dict_offset = Some(p.size()?),
+                        _ => p.skip(kind)?,
+                    }
+                    Ok(())
+                })?,
+                _ => p.skip(kind)?,
+            }
+            Ok(())
+        })?;
+
+        if external {
+            bail!("columns in external files are not supported");
+        }
+
+        // Some writers set the dictionary offset to zero when there is none.
+        let start = dict_offset.filter(|&offset| offset > 0).unwrap_or(data_offset);
+        if start >= file_len {
+            bail!("file is corrupted");
+        }
+
+        Ok(Chunk { codec, values, start })
+    }
+
+    /// Read the header of a page.
+    fn page_header(&mut self) -> StrResult<PageHeader> {
+        let mut header = PageHeader { compressed: true, ..Default::default() };
+        self.fields(|p, id, kind| {
+            match id {
+                1 => header.kind = p.i32()?,
+                2 => header.uncompressed_size = p.size()?,
+                3 => header.compressed_size = p.size()?,
+                // The headers of version 1 data pages and dictionary pages.
+                5 | 7 => p.fields(|p, id, kind| {
+                    match id {
+                        1 => header.values = p.size()?,
+                        2 => header.encoding = p.i32()?,
+                        _ => p.skip(kind)?,
+                    }
+                    Ok(())
+                })?,
+                // The header of version 2 data pages.
+                8 => p.fields(|p, id, kind| {
+                    match id {
+                        1 => header.values = p.size()?,
+                        4 => header.encoding = p.i32()?,
+                        5 => header.levels.1 = p.size()?,
+                        6 => header.levels.0 = p.size()?,
+                        7 => header.compressed = kind == 1,
+                        _ => p.skip(kind)?,
+                    }
+                    Ok(())
+                })?,
+                _ => p.skip(kind)?,
+            }
+            Ok(())
+        })?;
+        Ok(header)
+    }
+}
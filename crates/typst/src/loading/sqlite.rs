@@ -0,0 +1,576 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{func, scope, Array, Bytes, Dict, IntoValue, Str, Value};
+use crate::syntax::Spanned;
+use crate::World;
+
+/// Reads a table from an SQLite database.
+///
+/// The rows of the table are returned as an array of dictionaries, one per
+/// row, that map from the column names to the values. The database is only
+/// ever read, never modified.
+///
+/// SQLite values are converted as follows: `NULL` becomes `{none}`, integers
+/// and reals become [integers]($int) and [floats]($float), text becomes a
+/// [string]($str), and blobs become [bytes]($bytes).
+///
+/// SQL queries are not supported yet, so only whole tables can be read.
+/// Instead, you can filter, sort, and transform the rows with the methods of
+/// [arrays]($array). Tables without row IDs are not supported.
+///
+/// # Example
+/// ```typ
+/// #let runs = sqlite("results.db", table: "runs")
+/// #let best = runs
+///   .filter(run => run.score > 0.5)
+///   .sorted(key: run => -run.score)
+///
+/// #table(
+///   columns: 2,
+///   [*Name*], [*Score*],
+///   ..best.map(run => (run.name, str(run.score))).flatten(),
+/// )
+/// ```
+#[func(scope, title = "SQLite")]
+pub fn sqlite(
+    /// The engine.
+    engine: &mut Engine,
+    /// Path to an SQLite database file.
+    path: Spanned<EcoString>,
+    /// The name of the table to read.
+    ///
+    /// Can be omitted if the database contains only one table. The special
+    /// `{"sqlite_schema"}` table lists the tables of the database.
+    #[named]
+    table: Option<Spanned<EcoString>>,
+) -> SourceResult<Array> {
+    let Spanned { v: path, span } = path;
+    let id = span.resolve_path(&path).at(span)?;
+    let data = engine.world.file(id).at(span)?;
+    sqlite::decode(Spanned::new(data, span), table)
+}
+
+#[scope]
+impl sqlite {
+    /// Reads a table from the bytes of an SQLite database.
+    #[func(title = "Decode SQLite")]
+    pub fn decode(
+        /// The database.
+        data: Spanned<Bytes>,
+        /// The name of the table to read.
+        ///
+        /// Can be omitted if the database contains only one table.
+        #[named]
+        table: Option<Spanned<EcoString>>,
+    ) -> SourceResult<Array> {
+        let Spanned { v: data, span } = data;
+        let db = Database::open(&data).map_err(format_db_error).at(span)?;
+        let table = match table {
+            Some(table) => table,
+            None => {
+                let mut names = db.tables().map_err(format_db_error).at(span)?;
+                match names.len() {
+                    0 => bail!(span, "database contains no tables"),
+                    1 => Spanned::new(names.remove(0), span),
+                    n => bail!(
+                        span, "database contains {n} tables";
+                        hint: "select one of them with the `table` argument"
+                    ),
+                }
+            }
+        };
+
+        let Some(found) = db.table(&table.v).map_err(format_db_error).at(span)? else {
+            bail!(table.span, "unknown table `{}`", table.v);
+        };
+
+        let rows = db.rows(found.root).map_err(format_db_error).at(span)?;
+        Ok(rows.into_iter().map(|row| found.dict(row).into_value()).collect())
+    }
+}
+
+/// Format the user-facing database error message.
+fn format_db_error(error: EcoString) -> EcoString {
+    eco_format!("failed to read SQLite database ({error})")
+}
+
+/// A value stored in an SQLite database.
+#[derive(Debug, Clone, PartialEq)]
+enum SqlValue {
+    Null,
+    Int(i64),
+    Float(f64),
+    Text(EcoString),
+    Blob(Vec<u8>),
+}
+
+impl IntoValue for SqlValue {
+    fn into_value(self) -> Value {
+        match self {
+            Self::Null => Value::None,
+            Self::Int(v) => Value::Int(v),
+            Self::Float(v) => Value::Float(v),
+            Self::Text(v) => Value::Str(Str::from(v)),
+            Self::Blob(v) => Value::Bytes(Bytes::from(v)),
+        }
+    }
+}
+
+/// A table in the database.
+struct Table {
+    /// The root page of the table's b-tree.
+    root: u32,
+    /// The names of the columns.
+    columns: Vec<EcoString>,
+    /// The column that is an alias for the row ID, if any.
+    rowid_alias: Option<usize>,
+}
+
+impl Table {
+    /// The schema table, which lists all tables of the database.
+    fn schema() -> Self {
+        Self {
+            root: 1,
+            columns: ["type", "name", "tbl_name", "rootpage", "sql"]
+                .into_iter()
+                .map(EcoString::from)
+                .collect(),
+            rowid_alias: None,
+        }
+    }
+
+    /// Map a row's values to the column names.
+    fn dict(&self, row: Row) -> Dict {
+        let mut values = row.values.into_iter();
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                // Values missing at the end of a record are `NULL`, and the
+                // row ID alias is always stored as `NULL`.
+                let value = values.next().unwrap_or(SqlValue::Null);
+                let value = match self.rowid_alias {
+                    Some(alias) if alias == i => SqlValue::Int(row.rowid),
+                    _ => value,
+                };
+                (name.as_str().into(), value.into_value())
+            })
+            .collect()
+    }
+}
+
+/// A row of a table.
+struct Row {
+    rowid: i64,
+    values: Vec<SqlValue>,
+}
+
+/// The text encoding of a database.
+#[derive(Copy, Clone)]
+enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+/// A read-only view of an SQLite database file.
+struct Database<'a> {
+    data: &'a [u8],
+    page_size: usize,
+    usable_size: usize,
+    encoding: Encoding,
+}
+
+impl<'a> Database<'a> {
+    /// Check the database header.
+    fn open(data: &'a [u8]) -> StrResult<Self> {
+        if data.len() < 100 || !data.starts_with(b"SQLite format 3\0") {
+            bail!("not an SQLite database");
+        }
+
+        let page_size = match u16::from_be_bytes([data[16], data[17]]) {
+            1 => 65536,
+            n => usize::from(n),
+        };
+        if page_size < 512 || !page_size.is_power_of_two() {
+            bail!("invalid page size");
+        }
+
+        let usable_size = page_size - usize::from(data[20]);
+        if usable_size < 480 {
+            bail!("invalid page size");
+        }
+
+        let encoding = match read_u32(data, 56)? {
+            0 | 1 => Encoding::Utf8,
+            2 => Encoding::Utf16Le,
+            3 => Encoding::Utf16Be,
+            _ => bail!("unknown text encoding"),
+        };
+
+        Ok(Self { data, page_size, usable_size, encoding })
+    }
+
+    /// The names of the database's tables, except for internal ones.
+    fn tables(&self) -> StrResult<Vec<EcoString>> {
+        let mut names = vec![];
+        for row in self.rows(1)? {
+            if let [SqlValue::Text(kind), SqlValue::Text(name), ..] =
+                row.values.as_slice()
+            {
+                if kind == "table" && !name.starts_with("sqlite_") {
+                    names.push(name.clone());
+                }
+            }
+        }
+        Ok(names)
+    }
+
+    /// Find a table by its name.
+    fn table(&self, name: &str) -> StrResult<Option<Table>> {
+        if ["sqlite_schema", "sqlite_master"]
+            .iter()
+            .any(|n| n.eq_ignore_ascii_case(name))
+        {
+            return Ok(Some(Table::schema()));
+        }
+
+        for row in self.rows(1)? {
+            let [SqlValue::Text(kind), SqlValue::Text(table), _, SqlValue::Int(root), SqlValue::Text(sql), ..] =
+                row.values.as_slice()
+            else {
+                continue;
+            };
+
+            if kind == "table" && table.eq_ignore_ascii_case(name) {
+                let root = u32::try_from(*root).map_err(|_| "database is corrupted")?;
+                let (columns, rowid_alias) = parse_create_table(sql)?;
+                return Ok(Some(Table { root, columns, rowid_alias }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Read all rows of the table b-tree with the given root page.
+    fn rows(&self, root: u32) -> StrResult<Vec<Row>> {
+        let mut rows = vec![];
+        self.visit(root, 0, &mut HashSet::new(), &mut rows)?;
+        Ok(rows)
+    }
+
+    /// Collect the rows in a page of a table b-tree and its children.
+    fn visit(
+        &self,
+        number: u32,
+        depth: usize,
+        visited: &mut HashSet<u32>,
+        rows: &mut Vec<Row>,
+    ) -> StrResult<()> {
+        // Each page may only be part of the tree once, which prevents cycles
+        // and pages shared between subtrees. Real databases are also never
+        // this deep.
+        if depth > 64 || !visited.insert(number) {
+            bail!("database is corrupted");
+        }
+
+        let page = self.page(number)?;
+        let header = if number == 1 { 100 } else { 0 };
+        let kind = *page.get(header).ok_or("database is corrupted")?;
+        let cells = usize::from(read_u16(page, header + 3)?);
+
+        match kind {
+            // Interior page: Each cell points to a child, followed by the
+            // right-most child in the header.
+            0x05 => {
+                for i in 0..cells {
+                    let cell = usize::from(read_u16(page, header + 12 + 2 * i)?);
+                    self.visit(read_u32(page, cell)?, depth + 1, visited, rows)?;
+                }
+                self.visit(read_u32(page, header + 8)?, depth + 1, visited, rows)?;
+            }
+            // Leaf page: Each cell holds a row.
+            0x0d => {
+                for i in 0..cells {
+                    let cell = usize::from(read_u16(page, header + 8 + 2 * i)?);
+                    let (size, n) = read_varint(page, cell)?;
+                    let (rowid, m) = read_varint(page, cell + n)?;
+                    let size =
+                        usize::try_from(size).map_err(|_| "database is corrupted")?;
+                    let payload = self.payload(page, cell + n + m, size)?;
+                    rows.push(Row {
+                        rowid: rowid as i64,
+                        values: self.record(&payload)?,
+                    });
+                }
+            }
+            0x02 | 0x0a => bail!("tables without row IDs are not supported"),
+            _ => bail!("database is corrupted"),
+        }
+
+        Ok(())
+    }
+
+    /// A page by its one-based number.
+    fn page(&self, number: u32) -> StrResult<&'a [u8]> {
+        let index = (number as usize).checked_sub(1).ok_or("database is corrupted")?;
+        let start = index * self.page_size;
+        self.data
+            .get(start..start + self.page_size)
+            .ok_or_else(|| "database is truncated".into())
+    }
+
+    /// Read the payload of a cell, following overflow pages if necessary.
+    fn payload(
+        &self,
+        page: &'a [u8],
+        start: usize,
+        size: usize,
+    ) -> StrResult<Cow<'a, [u8]>> {
+        let usable = self.usable_size;
+        let max_local = usable - 35;
+        if size <= max_local {
+            return slice(page, start, size).map(Cow::Borrowed);
+        }
+
+        // The amount of the payload that is stored on the page itself.
+        let min_local = (usable - 12) * 32 / 255 - 23;
+        let local = min_local + (size - min_local) % (usable - 4);
+        let local = if local <= max_local { local } else { min_local };
+
+        let mut out = Vec::with_capacity(size.min(self.data.len()));
+        out.extend_from_slice(slice(page, start, local)?);
+
+        let mut next = read_u32(page, start + local)?;
+        let mut remaining = self.data.len() / self.page_size;
+        while out.len() < size {
+            if next == 0 || remaining == 0 {
+                bail!("database is corrupted");
+            }
+            let overflow = self.page(next)?;
+            let len = (size - out.len()).min(usable - 4);
+            out.extend_from_slice(slice(overflow, 4, len)?);
+            next = read_u32(overflow, 0)?;
+            remaining -= 1;
+        }
+
+        Ok(Cow::Owned(out))
+    }
+
+    /// Decode the values of a record.
+    fn record(&self, payload: &[u8]) -> StrResult<Vec<SqlValue>> {
+        let (header_size, mut pos) = read_varint(payload, 0)?;
+        let header_size =
+            usize::try_from(header_size).map_err(|_| "database is corrupted")?;
+
+        let mut body = header_size;
+        let mut values = vec![];
+        while pos < header_size {
+            let (kind, n) = read_varint(payload, pos)?;
+            pos += n;
+
+            let len = match kind {
+                0 | 8 | 9 => 0,
+                1..=4 => kind as usize,
+                5 => 6,
+                6 | 7 => 8,
+                10 | 11 => bail!("database is corrupted"),
+                _ => ((kind - 12) / 2) as usize,
+            };
+
+            let bytes = slice(payload, body, len)?;
+            body += len;
+
+            values.push(match kind {
+                0 => SqlValue::Null,
+                1..=6 => {
+                    // Sign-extend from the first byte.
+                    let mut v: i64 = if bytes[0] & 0x80 != 0 { -1 } else { 0 };
+                    for &b in bytes {
+                        v = (v << 8) | i64::from(b);
+                    }
+                    SqlValue::Int(v)
+                }
+                7 => SqlValue::Float(f64::from_be_bytes(bytes.try_into().unwrap())),
+                8 => SqlValue::Int(0),
+                9 => SqlValue::Int(1),
+                _ if kind % 2 == 0 => SqlValue::Blob(bytes.to_vec()),
+                _ => SqlValue::Text(self.text(bytes)?),
+            });
+        }
+
+        Ok(values)
+    }
+
+    /// Decode text in the database's encoding.
+    fn text(&self, bytes: &[u8]) -> StrResult<EcoString> {
+        let units = |f: fn([u8; 2]) -> u16| {
+            bytes.chunks_exact(2).map(|c| f([c[0], c[1]])).collect::<Vec<_>>()
+        };
+
+        let text = match self.encoding {
+            Encoding::Utf8 => std::str::from_utf8(bytes).ok().map(EcoString::from),
+            Encoding::Utf16Le => {
+                String::from_utf16(&units(u16::from_le_bytes)).ok().map(Into::into)
+            }
+            Encoding::Utf16Be => {
+                String::from_utf16(&units(u16::from_be_bytes)).ok().map(Into::into)
+            }
+        };
+
+        text.ok_or_else(|| "database contains invalid text".into())
+    }
+}
+
+/// Get a subslice or fail because the database is corrupted.
+fn slice(data: &[u8], start: usize, len: usize) -> StrResult<&[u8]> {
+    data.get(start..start + len)
+        .ok_or_else(|| "database is corrupted".into())
+}
+
+/// Read a big-endian 16-bit integer.
+fn read_u16(data: &[u8], pos: usize) -> StrResult<u16> {
+    Ok(u16::from_be_bytes(slice(data, pos, 2)?.try_into().unwrap()))
+}
+
+/// Read a big-endian 32-bit integer.
+fn read_u32(data: &[u8], pos: usize) -> StrResult<u32> {
+    Ok(u32::from_be_bytes(slice(data, pos, 4)?.try_into().unwrap()))
+}
+
+/// Read a variable-length integer and return it along with its length.
+fn read_varint(data: &[u8], pos: usize) -> StrResult<(u64, usize)> {
+    let mut v = 0u64;
+    for i in 0..9 {
+        let b = *data.get(pos + i).ok_or("database is corrupted")?;
+        if i == 8 {
+            return Ok(((v << 8) | u64::from(b), 9));
+        }
+        v = (v << 7) | u64::from(b & 0x7f);
+        if b & 0x80 == 0 {
+            return Ok((v, i + 1));
+        }
+    }
+    unreachable!()
+}
+
+/// Determine the column names and the row ID alias from a table's
+/// `CREATE TABLE` statement.
+fn parse_create_table(sql: &str) -> StrResult<(Vec<EcoString>, Option<usize>)> {
+    let tokens = lex(sql).map_err(|_| "database is corrupted")?;
+    let start = tokens
+        .iter()
+        .position(|t| *t == Token::Open)
+        .ok_or("database is corrupted")?;
+
+    // Split the definitions at top-level commas.
+    let mut defs = vec![vec![]];
+    let mut depth = 0;
+    for token in &tokens[start + 1..] {
+        match token {
+            Token::Open => depth += 1,
+            Token::Close if depth == 0 => break,
+            Token::Close => depth -= 1,
+            Token::Comma if depth == 0 => {
+                defs.push(vec![]);
+                continue;
+            }
+            _ => {}
+        }
+        defs.last_mut().unwrap().push(token);
+    }
+
+    let mut columns = vec![];
+    let mut rowid_alias = None;
+    for def in defs {
+        let Some(Token::Ident(name, quoted)) = def.first() else { continue };
+        let is = |token: &Token, kw: &str| matches!(token, Token::Ident(v, false) if v.eq_ignore_ascii_case(kw));
+
+        // Skip table constraints.
+        if !quoted
+            && ["constraint", "primary", "unique", "check", "foreign"]
+                .iter()
+                .any(|kw| name.eq_ignore_ascii_case(kw))
+        {
+            continue;
+        }
+
+        // A column declared as `INTEGER PRIMARY KEY` stores the row ID.
+        let integer = def.get(1).is_some_and(|t| is(t, "integer"));
+        let primary = def.windows(2).any(|w| is(w[0], "primary") && is(w[1], "key"));
+        if integer && primary {
+            rowid_alias = Some(columns.len());
+        }
+
+        columns.push(name.clone());
+    }
+
+    Ok((columns, rowid_alias))
+}
+
+/// A token of a `CREATE TABLE` statement.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// An identifier or keyword and whether it was quoted.
+    Ident(EcoString, bool),
+    Open,
+    Close,
+    Comma,
+    /// A literal or operator, which doesn't matter for the column names.
+    Other,
+}
+
+/// Split an SQL statement into tokens.
+fn lex(text: &str) -> StrResult<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut s = unscanny::Scanner::new(text);
+    loop {
+        s.eat_whitespace();
+        if s.eat_if("--") {
+            s.eat_until('\n');
+            continue;
+        }
+
+        let Some(c) = s.eat() else { break };
+        let token = match c {
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            '\'' | '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                let mut value = EcoString::new();
+                loop {
+                    match s.eat() {
+                        // A doubled quote stands for the quote itself.
+                        Some(q) if q == close && close != ']' && s.eat_if(close) => {
+                            value.push(q)
+                        }
+                        Some(q) if q == close => break,
+                        Some(q) => value.push(q),
+                        None => bail!("unterminated string"),
+                    }
+                }
+                if c == '\'' {
+                    Token::Other
+                } else {
+                    Token::Ident(value, true)
+                }
+            }
+            _ if c.is_alphanumeric() || c == '_' => {
+                s.uneat();
+                let ident = s.eat_while(|c: char| c.is_alphanumeric() || c == '_');
+                Token::Ident(ident.into(), false)
+            }
+            _ => Token::Other,
+        };
+
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
@@ -0,0 +1,233 @@
+use base64::Engine as _;
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{func, scope, Array, Bytes, Dict, IntoValue, Str, Value};
+use crate::loading::{
+    content_lines, parse_datetime, split_text, unescape_text, ContentLine, Readable,
+};
+use crate::syntax::Spanned;
+use crate::World;
+
+/// Reads contacts from a vCard file.
+///
+/// The file may contain any number of `VCARD` components, as exported by
+/// address books into `.vcf` files. The result is an array with one dictionary
+/// per contact that maps the lowercased property names (such as `fn`, `email`,
+/// or `org`) to their values.
+///
+/// Property values are converted as follows:
+/// - `n` becomes a dictionary with the `family`, `given`, `additional`,
+///   `prefixes`, and `suffixes` components of the name.
+/// - `adr` entries become dictionaries with the `pobox`, `extended`, `street`,
+///   `locality`, `region`, `code`, and `country` components of the address
+///   and its `types` (such as `{("home",)}`).
+/// - `email`, `tel`, `url`, and `impp` entries become dictionaries with the
+///   `value` and its `types` (such as `{("work", "voice")}`).
+/// - As a contact may have several of them, `adr`, `email`, `tel`, `url`, and
+///   `impp` are always arrays.
+/// - `org` becomes an array of the organization's name and units, while
+///   `nickname` and `categories` become arrays of strings.
+/// - `bday`, `anniversary`, and `rev` become [datetimes]($datetime) if they
+///   specify a full date.
+/// - Inline base64 data of `photo`, `logo`, and `sound` becomes
+///   [bytes]($bytes), which can, for instance, be passed to
+///   [`image.decode`]($image.decode). Links remain strings.
+/// - All other properties become strings.
+///
+/// # Example
+/// ```typ
+/// #let contacts = vcard("team.vcf")
+///
+/// #table(
+///   columns: 3,
+///   [*Name*], [*Email*], [*Phone*],
+///   ..contacts.map(contact => (
+///     contact.fn,
+///     contact.email.map(email => email.value).join(", "),
+///     contact.tel.map(tel => tel.value).join(", "),
+///   )).flatten(),
+/// )
+/// ```
+#[func(scope, title = "vCard")]
+pub fn vcard(
+    /// The engine.
+    engine: &mut Engine,
+    /// Path to a vCard file.
+    path: Spanned<EcoString>,
+) -> SourceResult<Array> {
+    let Spanned { v: path, span } = path;
+    let id = span.resolve_path(&path).at(span)?;
+    let data = engine.world.file(id).at(span)?;
+    vcard::decode(Spanned::new(Readable::Bytes(data), span))
+}
+
+#[scope]
+impl vcard {
+    /// Reads contacts from a vCard string/bytes.
+    #[func(title = "Decode vCard")]
+    pub fn decode(
+        /// vCard data.
+        data: Spanned<Readable>,
+    ) -> SourceResult<Array> {
+        let Spanned { v: data, span } = data;
+        let text = std::str::from_utf8(data.as_slice())
+            .map_err(|_| "file is not valid utf-8")
+            .at(span)?;
+        decode_contacts(text)
+            .map_err(|err| eco_format!("failed to parse vCard ({err})"))
+            .at(span)
+    }
+}
+
+/// Decode the contacts of a vCard file.
+fn decode_contacts(text: &str) -> Result<Array, EcoString> {
+    let mut contacts = Array::new();
+    let mut contact: Option<Dict> = None;
+
+    for line in content_lines(text)? {
+        match (line.name.as_str(), &mut contact) {
+            ("BEGIN", None) if line.value.trim().eq_ignore_ascii_case("VCARD") => {
+                contact = Some(Dict::new());
+            }
+            ("END", Some(_)) if line.value.trim().eq_ignore_ascii_case("VCARD") => {
+                contacts.push(contact.take().unwrap().into_value());
+            }
+            ("BEGIN" | "END", _) => {
+                return Err(eco_format!(
+                    "unexpected {} at line {}",
+                    line.name,
+                    line.line
+                ));
+            }
+            (_, Some(dict)) => insert_property(dict, &line),
+            (_, None) => {
+                return Err(eco_format!("expected BEGIN:VCARD at line {}", line.line));
+            }
+        }
+    }
+
+    if contact.is_some() {
+        return Err("missing END:VCARD".into());
+    }
+
+    Ok(contacts)
+}
+
+/// Insert a property into the dictionary of its contact.
+fn insert_property(dict: &mut Dict, line: &ContentLine) {
+    let key: Str = line.name.to_lowercase().into();
+    let value = line.value.as_str();
+    match line.name.as_str() {
+        "EMAIL" | "TEL" | "URL" | "IMPP" => {
+            let mut entry = Dict::new();
+            entry.insert("value".into(), unescape_text(value).into_value());
+            entry.insert("types".into(), types(line).into_value());
+            push(dict, key, entry.into_value());
+        }
+        "ADR" => {
+            let mut entry = components(
+                value,
+                &["pobox", "extended", "street", "locality", "region", "code", "country"],
+            );
+            entry.insert("types".into(), types(line).into_value());
+            push(dict, key, entry.into_value());
+        }
+        "NICKNAME" | "CATEGORIES" => {
+            for part in split_text(value, ',') {
+                push(dict, key.clone(), part.into_value());
+            }
+        }
+        _ if dict.contains(&key) => {}
+        "N" => {
+            let name = components(
+                value,
+                &["family", "given", "additional", "prefixes", "suffixes"],
+            );
+            dict.insert(key, name.into_value());
+        }
+        "ORG" => {
+            let units: Array = split_text(value, ';')
+                .into_iter()
+                .map(IntoValue::into_value)
+                .collect();
+            dict.insert(key, units.into_value());
+        }
+        "BDAY" | "ANNIVERSARY" | "REV" => {
+            let value = match parse_datetime(value.trim()) {
+                Some(datetime) => datetime.into_value(),
+                None => unescape_text(value).into_value(),
+            };
+            dict.insert(key, value);
+        }
+        "PHOTO" | "LOGO" | "SOUND" => {
+            let value = match inline_data(line) {
+                Some(data) => Bytes::from(data).into_value(),
+                None => unescape_text(value).into_value(),
+            };
+            dict.insert(key, value);
+        }
+        _ => dict.insert(key, unescape_text(value).into_value()),
+    }
+}
+
+/// Append a value to the array stored under the given key.
+fn push(dict: &mut Dict, key: Str, value: Value) {
+    match dict.at_mut(&key) {
+        Ok(Value::Array(items)) => items.push(value),
+        _ => dict.insert(key, Array::from_iter([value]).into_value()),
+    }
+}
+
+/// Split a structured value into named components.
+fn components(value: &str, names: &[&str]) -> Dict {
+    let mut parts = split_text(value, ';').into_iter();
+    names
+        .iter()
+        .map(|&name| (name.into(), parts.next().unwrap_or_default().into_value()))
+        .collect()
+}
+
+/// The lowercased types of a property, given by `TYPE` parameters or, in
+/// vCard 2.1, by parameters without a value.
+fn types(line: &ContentLine) -> Array {
+    line.params
+        .iter()
+        .flat_map(|(key, values)| match (key.as_str(), values.as_slice()) {
+            ("TYPE", values) => values.to_vec(),
+            (_, []) => vec![key.clone()],
+            _ => vec![],
+        })
+        .flat_map(|value| {
+            value
+                .split(',')
+                .map(|kind| kind.trim().to_lowercase().into_value())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Decode the data of a property if it is inline base64, either marked by
+/// an `ENCODING` parameter or given as a `data:` URI.
+fn inline_data(line: &ContentLine) -> Option<Vec<u8>> {
+    let value = line.value.as_str();
+    let encoded = match line.param("ENCODING") {
+        Some(encoding)
+            if encoding.eq_ignore_ascii_case("b")
+                || encoding.eq_ignore_ascii_case("base64") =>
+        {
+            value
+        }
+        _ => {
+            let (header, data) = value.strip_prefix("data:")?.split_once(',')?;
+            if !header.ends_with(";base64") {
+                return None;
+            }
+            data
+        }
+    };
+
+    let encoded: String = encoded.chars().filter(|c| !c.is_whitespace()).collect();
+    base64::engine::general_purpose::STANDARD.decode(encoded).ok()
+}
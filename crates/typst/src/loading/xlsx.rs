@@ -0,0 +1,893 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{cast, func, scope, Array, Bytes, Datetime, IntoValue, Value};
+use crate::syntax::{Span, Spanned};
+use crate::World;
+
+/// Reads cells from an XLSX or ODS spreadsheet.
+///
+/// The cells of one sheet are returned as a 2-dimensional array: Each row of
+/// the selected range is represented as an array of cell values, and all rows
+/// are collected into a single array. Header rows are not stripped. Both the
+/// Office Open XML format of Microsoft Excel (`.xlsx`) and the OpenDocument
+/// format of LibreOffice Calc (`.ods`) are supported, which one is used is
+/// detected from the file's contents.
+///
+/// Cell values are converted as follows:
+/// - Numbers become [integers]($int) if they are whole and
+///   [floats]($float) otherwise.
+/// - Cells formatted as dates or times become [datetimes]($datetime).
+/// - Booleans become [booleans]($bool).
+/// - Text becomes a [string]($str), as do error values such as `#DIV/0!`.
+/// - Empty cells become `{none}`.
+///
+/// For cells with formulas, the value last computed by the spreadsheet
+/// application is read. Formulas are not evaluated.
+///
+/// # Example
+/// ```typ
+/// #let rows = xlsx("book.xlsx", sheet: "Data", range: "A1:C20")
+///
+/// #table(
+///   columns: 3,
+///   ..rows.flatten().map(cell => [#cell]),
+/// )
+/// ```
+#[func(scope, title = "XLSX")]
+pub fn xlsx(
+    /// The engine.
+    engine: &mut Engine,
+    /// Path to an XLSX or ODS file.
+    path: Spanned<EcoString>,
+    /// The sheet to read, given by its name or by its zero-based index.
+    /// Defaults to the first sheet.
+    #[named]
+    sheet: Option<Spanned<Sheet>>,
+    /// The range of cells to read, such as `{"A1:F100"}`, a single cell such
+    /// as `{"B2"}`, or whole columns such as `{"A:C"}`. Cells outside of the
+    /// sheet's data in the range become `{none}`.
+    ///
+    /// By default, the smallest range that contains all non-empty cells is
+    /// read.
+    #[named]
+    range: Option<CellRange>,
+) -> SourceResult<Array> {
+    let Spanned { v: path, span } = path;
+    let id = span.resolve_path(&path).at(span)?;
+    let data = engine.world.file(id).at(span)?;
+    xlsx::decode(Spanned::new(data, span), sheet, range)
+}
+
+#[scope]
+impl xlsx {
+    /// Reads cells from the bytes of an XLSX or ODS spreadsheet.
+    #[func(title = "Decode XLSX")]
+    pub fn decode(
+        /// The spreadsheet file.
+        data: Spanned<Bytes>,
+        /// The sheet to read, given by its name or by its zero-based index.
+        /// Defaults to the first sheet.
+        #[named]
+        sheet: Option<Spanned<Sheet>>,
+        /// The range of cells to read, such as `{"A1:F100"}`, a single cell
+        /// such as `{"B2"}`, or whole columns such as `{"A:C"}`. Cells outside
+        /// of the sheet's data in the range become `{none}`.
+        ///
+        /// By default, the smallest range that contains all non-empty cells
+        /// is read.
+        #[named]
+        range: Option<CellRange>,
+    ) -> SourceResult<Array> {
+        let Spanned { v: data, span } = data;
+        let archive = Archive::open(&data).map_err(format_spreadsheet_error).at(span)?;
+        let cells = if archive.entry("content.xml").is_some() {
+            read_ods(&archive, sheet, span)?
+        } else {
+            read_xlsx(&archive, sheet, span)?
+        };
+        Ok(into_rows(cells, range))
+    }
+}
+
+/// Format the user-facing spreadsheet error message.
+fn format_spreadsheet_error(error: EcoString) -> EcoString {
+    eco_format!("failed to read spreadsheet ({error})")
+}
+
+/// A sheet of a spreadsheet, selected by name or index.
+pub enum Sheet {
+    Name(EcoString),
+    Index(usize),
+}
+
+cast! {
+    Sheet,
+    self => match self {
+        Self::Name(name) => name.into_value(),
+        Self::Index(index) => index.into_value(),
+    },
+    v: EcoString => Self::Name(v),
+    v: usize => Self::Index(v),
+}
+
+/// A rectangular range of cells, with zero-based rows and columns.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct CellRange {
+    rows: Option<(usize, usize)>,
+    columns: (usize, usize),
+}
+
+cast! {
+    CellRange,
+    self => {
+        let start = reference(self.rows.map(|(first, _)| first), self.columns.0);
+        let end = reference(self.rows.map(|(_, last)| last), self.columns.1);
+        if start == end { start } else { eco_format!("{start}:{end}") }
+    }.into_value(),
+    v: EcoString => {
+        let (start, end) = v.split_once(':').unwrap_or((&v, &v));
+        let (Some(start), Some(end)) = (parse_reference(start), parse_reference(end))
+        else {
+            bail!("expected a cell range like \"A1:F100\"");
+        };
+        let rows = match (start.0, end.0) {
+            (Some(first), Some(last)) => Some((first.min(last), first.max(last))),
+            (None, None) => None,
+            _ => bail!("cell range must either specify both rows or none"),
+        };
+        let columns = (start.1.min(end.1), start.1.max(end.1));
+        Self { rows, columns }
+    },
+}
+
+/// Parse a cell reference like `B2`, `$B$2`, or `B` into its optional row and
+/// its column.
+fn parse_reference(text: &str) -> Option<(Option<usize>, usize)> {
+    let text = text.trim().replace('$', "");
+    let split = text.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(text.len());
+    let (letters, digits) = text.split_at(split);
+    if letters.is_empty() || letters.len() > 3 {
+        return None;
+    }
+
+    let column = letters
+        .bytes()
+        .fold(0, |acc, b| acc * 26 + usize::from(b.to_ascii_uppercase() - b'A') + 1);
+    let row = match digits {
+        "" => None,
+        _ => Some(digits.parse::<usize>().ok()?.checked_sub(1)?),
+    };
+
+    Some((row, column - 1))
+}
+
+/// Format a zero-based row and column as a cell reference like `B2`.
+fn reference(row: Option<usize>, column: usize) -> EcoString {
+    let mut letters = vec![];
+    let mut rest = column + 1;
+    while rest > 0 {
+        letters.push(b'A' + ((rest - 1) % 26) as u8);
+        rest = (rest - 1) / 26;
+    }
+
+    let mut text: EcoString = letters.iter().rev().map(|&b| b as char).collect();
+    if let Some(row) = row {
+        text.push_str(&eco_format!("{}", row + 1));
+    }
+    text
+}
+
+/// The non-empty cells of a sheet, keyed by their zero-based row and column.
+type Cells = BTreeMap<(usize, usize), Value>;
+
+/// Arrange the cells in the given range into rows.
+fn into_rows(mut cells: Cells, range: Option<CellRange>) -> Array {
+    let in_columns = |column: usize| {
+        range.map_or(true, |range| (range.columns.0..=range.columns.1).contains(&column))
+    };
+    let used = cells.keys().filter(|(_, column)| in_columns(*column));
+    let rows = match range.and_then(|range| range.rows) {
+        Some(rows) => rows,
+        None => match (
+            used.clone().map(|&(row, _)| row).min(),
+            used.map(|&(row, _)| row).max(),
+        ) {
+            (Some(first), Some(last)) => (if range.is_some() { 0 } else { first }, last),
+            _ => return Array::new(),
+        },
+    };
+    let columns = match range {
+        Some(range) => range.columns,
+        None => {
+            let first = cells.keys().map(|&(_, column)| column).min().unwrap_or(0);
+            let last = cells.keys().map(|&(_, column)| column).max().unwrap_or(0);
+            (first, last)
+        }
+    };
+
+    (rows.0..=rows.1)
+        .map(|row| {
+            (columns.0..=columns.1)
+                .map(|column| cells.remove(&(row, column)).unwrap_or(Value::None))
+                .collect::<Array>()
+                .into_value()
+        })
+        .collect()
+}
+
+/// Resolve the selected sheet to its index.
+fn select_sheet(
+    names: &[EcoString],
+    sheet: Option<Spanned<Sheet>>,
+    span: Span,
+) -> SourceResult<usize> {
+    match sheet {
+        None if names.is_empty() => bail!(span, "spreadsheet contains no sheets"),
+        None => Ok(0),
+        Some(Spanned { v: Sheet::Name(name), span }) => names
+            .iter()
+            .position(|other| *other == name)
+            .ok_or_else(|| eco_format!("unknown sheet `{name}`"))
+            .at(span),
+        Some(Spanned { v: Sheet::Index(index), span }) => {
+            if index >= names.len() {
+                bail!(
+                    span,
+                    "sheet index out of bounds (index: {index}, len: {})",
+                    names.len()
+                );
+            }
+            Ok(index)
+        }
+    }
+}
+
+/// Read the selected sheet of an XLSX file.
+fn read_xlsx(
+    archive: &Archive,
+    sheet: Option<Spanned<Sheet>>,
+    span: Span,
+) -> SourceResult<Cells> {
+    let book = Workbook::open(archive).map_err(format_spreadsheet_error).at(span)?;
+    let names: Vec<_> = book.sheets.iter().map(|(name, _)| name.clone()).collect();
+    let index = select_sheet(&names, sheet, span)?;
+    book.cells(archive, &book.sheets[index].1)
+        .map_err(format_spreadsheet_error)
+        .at(span)
+}
+
+/// The metadata of an XLSX workbook.
+struct Workbook {
+    /// The names of the sheets and the paths of their parts.
+    sheets: Vec<(EcoString, EcoString)>,
+    /// The shared strings that cells can refer to.
+    strings: Vec<EcoString>,
+    /// For each cell style, how numbers in cells of that style are shown.
+    formats: Vec<Format>,
+    /// Whether date serial numbers count from 1904 instead of 1900.
+    date1904: bool,
+}
+
+impl Workbook {
+    /// Read the workbook's metadata.
+    fn open(archive: &Archive) -> StrResult<Self> {
+        let root = relationships(archive, "_rels/.rels", "")?;
+        let path = find_target(&root, "officeDocument")
+            .unwrap_or_else(|| "xl/workbook.xml".into());
+        let (dir, file) = path.rsplit_once('/').unwrap_or(("", &path));
+        let rels = relationships(archive, &eco_format!("{dir}/_rels/{file}.rels"), dir)?;
+
+        let text = archive.text(&path)?.ok_or("file is not a spreadsheet")?;
+        let workbook = parse_xml(&text)?;
+        let date1904 = workbook
+            .descendants()
+            .find(|node| is(*node, "workbookPr"))
+            .and_then(|node| attr(node, "date1904"))
+            .is_some_and(|v| v == "1" || v == "true");
+
+        let mut sheets = vec![];
+        for node in workbook.descendants().filter(|node| is(*node, "sheet")) {
+            let name = attr(node, "name").unwrap_or_default();
+            let id = attr(node, "id").unwrap_or_default();
+            let Some((_, _, target)) = rels.iter().find(|(other, _, _)| other == id)
+            else {
+                bail!("missing part of sheet `{name}`");
+            };
+            sheets.push((name.into(), target.clone()));
+        }
+
+        let strings = match find_target(&rels, "sharedStrings") {
+            Some(path) => shared_strings(archive, &path)?,
+            None => vec![],
+        };
+
+        let formats = match find_target(&rels, "styles") {
+            Some(path) => formats(archive, &path)?,
+            None => vec![],
+        };
+
+        Ok(Self { sheets, strings, formats, date1904 })
+    }
+
+    /// Read the non-empty cells of the sheet at the given path.
+    fn cells(&self, archive: &Archive, path: &str) -> StrResult<Cells> {
+        let text = archive.text(path)?.ok_or_else(|| eco_format!("missing {path}"))?;
+        let document = parse_xml(&text)?;
+
+        let mut cells = Cells::new();
+        let mut row = 0;
+        let rows = document.descendants().filter(|node| is(*node, "row"));
+        for (i, node) in rows.enumerate() {
+            row = match attr(node, "r") {
+                Some(r) => r.parse::<usize>().ok().and_then(|r| r.checked_sub(1)),
+                None if i == 0 => Some(0),
+                None => Some(row + 1),
+            }
+            .ok_or("invalid row number")?;
+
+            let mut column = 0;
+            let items = node.children().filter(|node| is(*node, "c"));
+            for (j, cell) in items.enumerate() {
+                column = match attr(cell, "r") {
+                    Some(r) => parse_reference(r).map(|(_, column)| column),
+                    None if j == 0 => Some(0),
+                    None => Some(column + 1),
+                }
+                .ok_or("invalid cell reference")?;
+
+                if let Some(value) = self.value(cell).map_err(|err| {
+                    eco_format!("{err} in cell {}", reference(Some(row), column))
+                })? {
+                    cells.insert((row, column), value);
+                }
+            }
+        }
+
+        Ok(cells)
+    }
+
+    /// Convert the value of a cell.
+    fn value(&self, cell: roxmltree::Node) -> StrResult<Option<Value>> {
+        let raw = cell.children().find(|node| is(*node, "v")).and_then(|v| v.text());
+        let value = match (attr(cell, "t").unwrap_or("n"), raw) {
+            ("inlineStr", _) => match cell.children().find(|node| is(*node, "is")) {
+                Some(node) => rich_text(node).into_value(),
+                None => return Ok(None),
+            },
+            (_, None) => return Ok(None),
+            ("s", Some(raw)) => {
+                let index: usize =
+                    raw.trim().parse().map_err(|_| "invalid string index")?;
+                let string = self.strings.get(index).ok_or("invalid string index")?;
+                string.clone().into_value()
+            }
+            ("b", Some(raw)) => (raw.trim() == "1").into_value(),
+            ("d", Some(raw)) => match parse_iso(raw.trim()) {
+                Some(datetime) => datetime.into_value(),
+                None => raw.into_value(),
+            },
+            ("str" | "e", Some(raw)) => raw.into_value(),
+            (_, Some(raw)) => {
+                let number: f64 = raw.trim().parse().map_err(|_| "invalid number")?;
+                let format = attr(cell, "s")
+                    .and_then(|s| s.parse::<usize>().ok())
+                    .and_then(|s| self.formats.get(s))
+                    .copied()
+                    .unwrap_or_default();
+                match format.serial(number, self.date1904) {
+                    Some(datetime) => datetime.into_value(),
+                    None => number_value(number),
+                }
+            }
+        };
+        Ok(Some(value))
+    }
+}
+
+/// Read the relationships of a part, resolving their targets relative to the
+/// given directory. Returns pairs of IDs, types, and targets.
+fn relationships(
+    archive: &Archive,
+    path: &str,
+    dir: &str,
+) -> StrResult<Vec<(EcoString, EcoString, EcoString)>> {
+    let Some(text) = archive.text(path)? else { return Ok(vec![]) };
+    let document = parse_xml(&text)?;
+    Ok(document
+        .descendants()
+        .filter(|node| is(*node, "Relationship"))
+        .map(|node| {
+            let id = attr(node, "Id").unwrap_or_default();
+            let kind = attr(node, "Type").unwrap_or_default();
+            let target = attr(node, "Target").unwrap_or_default();
+            (id.into(), kind.into(), resolve_target(dir, target))
+        })
+        .collect())
+}
+
+/// Find the target of the first relationship of the given kind.
+fn find_target(
+    rels: &[(EcoString, EcoString, EcoString)],
+    kind: &str,
+) -> Option<EcoString> {
+    rels.iter()
+        .find(|(_, other, _)| other.rsplit('/').next() == Some(kind))
+        .map(|(_, _, target)| target.clone())
+}
+
+/// Resolve a relationship target relative to a directory in the archive.
+fn resolve_target(dir: &str, target: &str) -> EcoString {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.into();
+    }
+
+    let mut parts: Vec<&str> = dir.split('/').filter(|part| !part.is_empty()).collect();
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/").into()
+}
+
+/// Read the shared strings of a workbook.
+fn shared_strings(archive: &Archive, path: &str) -> StrResult<Vec<EcoString>> {
+    let Some(text) = archive.text(path)? else { return Ok(vec![]) };
+    let document = parse_xml(&text)?;
+    Ok(document
+        .root_element()
+        .children()
+        .filter(|node| is(*node, "si"))
+        .map(rich_text)
+        .collect())
+}
+
+/// Concatenate the text runs of a possibly rich text element, skipping
+/// phonetic hints.
+fn rich_text(node: roxmltree::Node) -> EcoString {
+    node.descendants()
+        .filter(|node| is(*node, "t"))
+        .filter(|node| !node.ancestors().any(|parent| is(parent, "rPh")))
+        .filter_map(|node| node.text())
+        .collect::<String>()
+        .into()
+}
+
+/// Read how numbers are shown in cells of each style.
+fn formats(archive: &Archive, path: &str) -> StrResult<Vec<Format>> {
+    let Some(text) = archive.text(path)? else { return Ok(vec![]) };
+    let document = parse_xml(&text)?;
+
+    let custom: Vec<(&str, &str)> = document
+        .descendants()
+        .filter(|node| is(*node, "numFmt"))
+        .filter_map(|node| Some((attr(node, "numFmtId")?, attr(node, "formatCode")?)))
+        .collect();
+
+    let Some(xfs) = document.descendants().find(|node| is(*node, "cellXfs")) else {
+        return Ok(vec![]);
+    };
+
+    Ok(xfs
+        .children()
+        .filter(|node| is(*node, "xf"))
+        .map(|node| {
+            let id = attr(node, "numFmtId").unwrap_or("0");
+            match custom.iter().find(|(other, _)| *other == id) {
+                Some((_, code)) => Format::from_code(code),
+                None => Format::builtin(id.parse().unwrap_or(0)),
+            }
+        })
+        .collect())
+}
+
+/// Whether a number format shows a date and/or a time.
+#[derive(Debug, Default, Copy, Clone)]
+struct Format {
+    date: bool,
+    time: bool,
+}
+
+impl Format {
+    /// The kind of a built-in number format.
+    fn builtin(id: u32) -> Self {
+        match id {
+            14..=17 | 27..=36 | 50..=58 => Self { date: true, time: false },
+            18..=21 | 45..=47 => Self { date: false, time: true },
+            22 => Self { date: true, time: true },
+            _ => Self::default(),
+        }
+    }
+
+    /// The kind of a custom number format code like `yyyy-mm-dd`.
+    fn from_code(code: &str) -> Self {
+        // Only the first section, which applies to positive numbers, matters.
+        // Quoted literals, escaped characters, and bracketed colors, locales,
+        // and conditions don't contribute to the kind.
+        let mut letters = String::new();
+        let mut chars = code.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                ';' => break,
+                '"' => chars.by_ref().take_while(|&c| c != '"').for_each(drop),
+                '[' => chars.by_ref().take_while(|&c| c != ']').for_each(drop),
+                '\\' | '_' | '*' => {
+                    chars.next();
+                }
+                c => letters.push(c.to_ascii_lowercase()),
+            }
+        }
+
+        let has = |c| letters.contains(c);
+        let time = has('h') || has('s');
+        let date = has('y') || has('d') || (has('m') && !time);
+        Self { date, time }
+    }
+
+    /// Convert a serial date number if cells with this format show dates or
+    /// times.
+    fn serial(self, number: f64, date1904: bool) -> Option<Datetime> {
+        if !self.date && !self.time {
+            return None;
+        }
+
+        let seconds = (number * 86400.0).round();
+        if !seconds.is_finite() || seconds.abs() > 1e15 {
+            return None;
+        }
+
+        let seconds = seconds as i64;
+        let days = seconds.div_euclid(86400);
+        let rest = seconds.rem_euclid(86400);
+        let time = time::Time::from_hms(
+            (rest / 3600) as u8,
+            (rest / 60 % 60) as u8,
+            (rest % 60) as u8,
+        )
+        .ok()?;
+
+        if !self.date && days == 0 {
+            return Some(Datetime::Time(time));
+        }
+
+        // Days since the Unix epoch. The 1900 date system counts from
+        // 1899-12-31, but wrongly considers 1900 a leap year.
+        let days = if date1904 {
+            days - 24107
+        } else if days < 60 {
+            days - 25568
+        } else {
+            days - 25569
+        };
+
+        let date = days
+            .checked_add(2440588)
+            .and_then(|day| i32::try_from(day).ok())
+            .and_then(|day| time::Date::from_julian_day(day).ok())?;
+
+        Some(if self.time {
+            Datetime::Datetime(time::PrimitiveDateTime::new(date, time))
+        } else {
+            Datetime::Date(date)
+        })
+    }
+}
+
+/// Read the selected sheet of an ODS file.
+fn read_ods(
+    archive: &Archive,
+    sheet: Option<Spanned<Sheet>>,
+    span: Span,
+) -> SourceResult<Cells> {
+    let text = archive
+        .text("content.xml")
+        .and_then(|text| text.ok_or_else(|| "missing content.xml".into()))
+        .map_err(format_spreadsheet_error)
+        .at(span)?;
+    let document = parse_xml(&text).map_err(format_spreadsheet_error).at(span)?;
+
+    let tables: Vec<_> = document
+        .descendants()
+        .find(|node| is(*node, "spreadsheet"))
+        .into_iter()
+        .flat_map(|node| node.children())
+        .filter(|node| is(*node, "table"))
+        .collect();
+
+    let names: Vec<EcoString> = tables
+        .iter()
+        .map(|node| attr(*node, "name").unwrap_or_default().into())
+        .collect();
+    let index = select_sheet(&names, sheet, span)?;
+
+    let mut cells = Cells::new();
+    let mut row = 0;
+    ods_rows(tables[index], &mut cells, &mut row)
+        .map_err(format_spreadsheet_error)
+        .at(span)?;
+    Ok(cells)
+}
+
+/// Read the rows of an ODS table or row group.
+fn ods_rows(node: roxmltree::Node, cells: &mut Cells, row: &mut usize) -> StrResult<()> {
+    for child in node.children() {
+        match child.tag_name().name() {
+            "table-header-rows" | "table-rows" | "table-row-group" => {
+                ods_rows(child, cells, row)?;
+            }
+            "table-row" => {
+                let mut values = vec![];
+                let mut column = 0;
+                for cell in child.children() {
+                    if !is(cell, "table-cell") && !is(cell, "covered-table-cell") {
+                        continue;
+                    }
+                    let repeat = repeated(cell, "number-columns-repeated");
+                    if let Some(value) = ods_value(cell).map_err(|err| {
+                        eco_format!("{err} in cell {}", reference(Some(*row), column))
+                    })? {
+                        for i in 0..repeat {
+                            values.push((column + i, value.clone()));
+                        }
+                    }
+                    column += repeat;
+                }
+
+                let repeat = repeated(child, "number-rows-repeated");
+                if !values.is_empty() {
+                    for i in 0..repeat {
+                        for (column, value) in &values {
+                            cells.insert((*row + i, *column), value.clone());
+                        }
+                    }
+                }
+                *row += repeat;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// How often a row or cell is repeated.
+fn repeated(node: roxmltree::Node, name: &str) -> usize {
+    attr(node, name).and_then(|v| v.parse().ok()).unwrap_or(1).max(1)
+}
+
+/// Convert the value of an ODS cell.
+fn ods_value(cell: roxmltree::Node) -> StrResult<Option<Value>> {
+    let Some(kind) = attr(cell, "value-type") else { return Ok(None) };
+    let value = match kind {
+        "float" | "percentage" | "currency" => {
+            let raw = attr(cell, "value").unwrap_or_default();
+            number_value(raw.trim().parse().map_err(|_| "invalid number")?)
+        }
+        "boolean" => (attr(cell, "boolean-value") == Some("true")).into_value(),
+        "date" => {
+            let raw = attr(cell, "date-value").unwrap_or_default();
+            parse_iso(raw).ok_or("invalid date")?.into_value()
+        }
+        "time" => {
+            let raw = attr(cell, "time-value").unwrap_or_default();
+            match parse_duration(raw) {
+                Some(time) => Datetime::Time(time).into_value(),
+                None => ods_text(cell).into_value(),
+            }
+        }
+        _ => match attr(cell, "string-value") {
+            Some(text) => text.into_value(),
+            None => ods_text(cell).into_value(),
+        },
+    };
+    Ok(Some(value))
+}
+
+/// The text of an ODS cell, with one line per paragraph.
+fn ods_text(cell: roxmltree::Node) -> EcoString {
+    fn walk(node: roxmltree::Node, text: &mut EcoString) {
+        for child in node.children() {
+            if child.is_text() {
+                text.push_str(child.text().unwrap_or_default());
+                continue;
+            }
+            match child.tag_name().name() {
+                "s" => {
+                    for _ in 0..repeated(child, "c") {
+                        text.push(' ');
+                    }
+                }
+                "tab" => text.push('\t'),
+                "line-break" => text.push('\n'),
+                "annotation" => {}
+                _ => walk(child, text),
+            }
+        }
+    }
+
+    let mut text = EcoString::new();
+    for (i, paragraph) in cell.children().filter(|node| is(*node, "p")).enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        walk(paragraph, &mut text);
+    }
+    text
+}
+
+/// Parse an ISO 8601 date (`2024-03-01`) or date-time
+/// (`2024-03-01T09:30:00`), ignoring fractional seconds and time zones.
+fn parse_iso(text: &str) -> Option<Datetime> {
+    let (date, time) = match text.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (text, None),
+    };
+
+    let mut parts = date.splitn(3, '-').map(|part| part.parse::<i32>().ok());
+    let year = parts.next()??;
+    let month = parts.next()??.try_into().ok()?;
+    let day = parts.next()??.try_into().ok()?;
+
+    let Some(time) = time else {
+        return Datetime::from_ymd(year, month, day);
+    };
+
+    let time = time.trim_end_matches('Z');
+    let time = time.split(['+', '-']).next()?;
+    let mut parts = time.splitn(3, ':');
+    let hour = parts.next()?.parse().ok()?;
+    let minute = parts.next()?.parse().ok()?;
+    let second = parts.next().unwrap_or("0").split('.').next()?.parse().ok()?;
+    Datetime::from_ymd_hms(year, month, day, hour, minute, second)
+}
+
+/// Parse an ISO 8601 duration like `PT09H30M00S` into a time of day.
+fn parse_duration(text: &str) -> Option<time::Time> {
+    let text = text.strip_prefix("PT")?;
+    let (hours, text) = text.split_once('H')?;
+    let (minutes, text) = text.split_once('M')?;
+    let seconds = text.strip_suffix('S')?;
+    let seconds = seconds.split(['.', ',']).next()?;
+    time::Time::from_hms(
+        hours.parse().ok()?,
+        minutes.parse().ok()?,
+        seconds.parse().ok()?,
+    )
+    .ok()
+}
+
+/// Convert a number into an integer if it is whole and a float otherwise.
+fn number_value(number: f64) -> Value {
+    if number.fract() == 0.0 && number.abs() < (1u64 << 53) as f64 {
+        (number as i64).into_value()
+    } else {
+        number.into_value()
+    }
+}
+
+/// Parse the XML of a part of the spreadsheet.
+fn parse_xml(text: &str) -> StrResult<roxmltree::Document<'_>> {
+    roxmltree::Document::parse(text).map_err(|err| eco_format!("malformed XML ({err})"))
+}
+
+/// Whether a node is an element with the given local name.
+fn is(node: roxmltree::Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+/// The value of an attribute with the given local name, regardless of its
+/// namespace.
+fn attr<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attributes()
+        .find(|attr| attr.name() == name)
+        .map(|attr| attr.value())
+}
+
+/// A ZIP archive, the container format of XLSX and ODS files.
+struct Archive<'a> {
+    data: &'a [u8],
+    entries: Vec<Entry>,
+}
+
+/// A file in a ZIP archive.
+struct Entry {
+    name: EcoString,
+    method: usize,
+    offset: usize,
+    compressed: usize,
+    size: usize,
+}
+
+impl<'a> Archive<'a> {
+    /// Read the central directory of an archive.
+    fn open(data: &'a [u8]) -> StrResult<Self> {
+        // The end of central directory record is followed by a comment of at
+        // most 64 KiB.
+        let last = data.len().checked_sub(22).ok_or("file is not a spreadsheet")?;
+        let end = (last.saturating_sub(usize::from(u16::MAX))..=last)
+            .rev()
+            .find(|&pos| data[pos..].starts_with(b"PK\x05\x06"))
+            .ok_or("file is not a spreadsheet")?;
+
+        let count = le(data, end + 10, 2)?;
+        let mut pos = le(data, end + 16, 4)?;
+        if count == 0xFFFF || pos == 0xFFFF_FFFF {
+            bail!("ZIP64 archives are not supported");
+        }
+
+        let mut entries = Vec::with_capacity(count);
+        for _ in 0..count {
+            if !data.get(pos..).is_some_and(|rest| rest.starts_with(b"PK\x01\x02")) {
+                bail!("file is corrupted");
+            }
+            let name_len = le(data, pos + 28, 2)?;
+            let name = slice(data, pos + 46, name_len)?;
+            entries.push(Entry {
+                name: String::from_utf8_lossy(name).into(),
+                method: le(data, pos + 10, 2)?,
+                compressed: le(data, pos + 20, 4)?,
+                size: le(data, pos + 24, 4)?,
+                offset: le(data, pos + 42, 4)?,
+            });
+            pos += 46 + name_len + le(data, pos + 30, 2)? + le(data, pos + 32, 2)?;
+        }
+
+        Ok(Self { data, entries })
+    }
+
+    /// Find the entry with the given path.
+    fn entry(&self, name: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Read and decompress the file at the given path, if it exists.
+    fn read(&self, name: &str) -> StrResult<Option<Cow<'a, [u8]>>> {
+        let Some(entry) = self.entry(name) else { return Ok(None) };
+        let header = slice(self.data, entry.offset, 30)?;
+        if !header.starts_with(b"PK\x03\x04") {
+            bail!("file is corrupted");
+        }
+
+        let start = entry.offset + 30 + le(header, 26, 2)? + le(header, 28, 2)?;
+        let data = slice(self.data, start, entry.compressed)?;
+        Ok(Some(match entry.method {
+            0 => Cow::Borrowed(data),
+            8 => Cow::Owned(
+                miniz_oxide::inflate::decompress_to_vec_with_limit(data, entry.size)
+                    .map_err(|_| "file is corrupted")?,
+            ),
+            method => bail!("unsupported compression method {method}"),
+        }))
+    }
+
+    /// Read the file at the given path as UTF-8 text, if it exists.
+    fn text(&self, name: &str) -> StrResult<Option<String>> {
+        let Some(data) = self.read(name)? else { return Ok(None) };
+        let text = String::from_utf8(data.into_owned())
+            .map_err(|_| eco_format!("{name} is not valid utf-8"))?;
+        Ok(Some(text))
+    }
+}
+
+/// Get `len` bytes at the given position.
+fn slice(data: &[u8], pos: usize, len: usize) -> StrResult<&[u8]> {
+    pos.checked_add(len)
+        .and_then(|end| data.get(pos..end))
+        .ok_or_else(|| "file is corrupted".into())
+}
+
+/// Read a little-endian integer of `len` bytes at the given position.
+fn le(data: &[u8], pos: usize, len: usize) -> StrResult<usize> {
+    Ok(slice(data, pos, len)?
+        .iter()
+        .rev()
+        .fold(0, |acc, &byte| acc << 8 | usize::from(byte)))
+}
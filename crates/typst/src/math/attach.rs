@@ -2,10 +2,10 @@ use unicode_math_class::MathClass;
 
 use crate::diag::SourceResult;
 use crate::foundations::{elem, Content, Packed, StyleChain};
-use crate::layout::{Abs, Frame, Point, Size};
+use crate::layout::{Abs, Corner, Frame, Point, Size};
 use crate::math::{
-    style_for_subscript, style_for_superscript, EquationElem, FrameFragment, LayoutMath,
-    MathContext, MathFragment, MathSize, Scaled,
+    kern_at_height, style_for_subscript, style_for_superscript, EquationElem,
+    FrameFragment, LayoutMath, MathContext, MathFragment, MathSize, Scaled,
 };
 use crate::text::TextElem;
 
@@ -248,8 +248,25 @@ fn layout_attachments(
     let (shift_up, shift_down) =
         compute_shifts_up_and_down(ctx, styles, &base, [&tl, &tr, &bl, &br]);
 
-    let sup_delta = Abs::zero();
-    let sub_delta = -base.italics_correction();
+    let mut sup_delta = Abs::zero();
+    let mut sub_delta = -base.italics_correction();
+
+    // Apply the cut-ins of the base's right corners at the heights where the
+    // scripts come closest to it.
+    if let MathFragment::Glyph(glyph) = &base {
+        if EquationElem::cut_ins_in(styles) {
+            let kern = |corner, height| {
+                kern_at_height(ctx, glyph.font_size, glyph.id, corner, height)
+                    .unwrap_or_default()
+            };
+            if let Some(tr) = &tr {
+                sup_delta += kern(Corner::TopRight, shift_up - tr.descent());
+            }
+            if let Some(br) = &br {
+                sub_delta += kern(Corner::BottomRight, br.ascent() - shift_down);
+            }
+        }
+    }
     let (base_width, base_ascent, base_descent) =
         (base.width(), base.ascent(), base.descent());
     let base_class = base.class();
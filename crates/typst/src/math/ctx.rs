@@ -206,14 +206,16 @@ impl<'a, 'b, 'v> MathContext<'a, 'b, 'v> {
             .and_then(|c| GlyphFragment::try_new(self, styles, c, span))
         {
             // A single letter that is available in the math font.
-            match math_size {
-                MathSize::Script => {
-                    glyph.make_scriptsize(self);
-                }
-                MathSize::ScriptScript => {
-                    glyph.make_scriptscriptsize(self);
+            if EquationElem::script_alternates_in(styles) {
+                match math_size {
+                    MathSize::Script => {
+                        glyph.make_scriptsize(self);
+                    }
+                    MathSize::ScriptScript => {
+                        glyph.make_scriptscriptsize(self);
+                    }
+                    _ => (),
                 }
-                _ => (),
             }
 
             if glyph.class == MathClass::Large {
@@ -5,13 +5,14 @@ use unicode_math_class::MathClass;
 use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, Content, NativeElement, Packed, Resolve, ShowSet, Smart, StyleChain, Styles,
-    Synthesize,
+    elem, Cast, Content, NativeElement, Packed, Resolve, ShowSet, Smart, StyleChain,
+    Styles, Synthesize,
 };
 use crate::introspection::{Count, Counter, CounterUpdate, Locatable};
 use crate::layout::{
-    Abs, AlignElem, Alignment, Axes, Em, FixedAlignment, Frame, LayoutMultiple,
-    LayoutSingle, OuterHAlignment, Point, Regions, Size, SpecificAlignment, VAlignment,
+    Abs, AlignElem, Alignment, Axes, Binding, Dir, Em, FixedAlignment, Frame, HAlignment,
+    LayoutMultiple, LayoutSingle, Length, OuterHAlignment, PageElem, Point, Ratio,
+    Regions, Size, SpecificAlignment, Transform, VAlignment,
 };
 use crate::math::{
     scaled_font_size, LayoutMath, MathContext, MathRunFrameBuilder, MathSize, MathVariant,
@@ -92,9 +93,33 @@ pub struct EquationElem {
     /// $ E &= sqrt(m_0^2 + p^2) \
     ///     &approx 125 "GeV" $
     /// ```
+    ///
+    /// With `{top}` and `{bottom}`, the number is vertically centered on the
+    /// first or last line of a multi-line equation, respectively.
     #[default(SpecificAlignment::Both(OuterHAlignment::End, VAlignment::Horizon))]
     pub number_align: SpecificAlignment<OuterHAlignment, VAlignment>,
 
+    /// Where to place the equation number horizontally.
+    ///
+    /// By default, the number is placed within the text area, on the side
+    /// given by [`number-align`]($math.equation.number-align). Alternatively,
+    /// it can be moved into the page margin next to the text area. On
+    /// two-sided pages, `{"inside"}` and `{"outside"}` keep the number on the
+    /// side facing towards or away from the [binding]($page.binding),
+    /// respectively, regardless of `number-align`.
+    ///
+    /// ```example
+    /// #set page(margin: (x: 40pt))
+    /// #set math.equation(
+    ///   numbering: "(1)",
+    ///   number-position: "margin",
+    /// )
+    ///
+    /// $ a^2 + b^2 = c^2 $
+    /// ```
+    #[default(NumberPosition::Text)]
+    pub number_position: NumberPosition,
+
     /// A supplement for the equation.
     ///
     /// For references to equations, this is added before the referenced number.
@@ -113,6 +138,98 @@ pub struct EquationElem {
     /// ```
     pub supplement: Smart<Option<Supplement>>,
 
+    /// The size of first-level sub- and superscripts, relative to the size of
+    /// their base.
+    ///
+    /// When `{auto}`, the value of the math font's `ScriptPercentScaleDown`
+    /// constant is used. Some journal styles call for a different size than
+    /// the one the font prescribes.
+    ///
+    /// ```example
+    /// $ x^2 + y_i $
+    /// #set math.equation(script-size: 80%)
+    /// $ x^2 + y_i $
+    /// ```
+    pub script_size: Smart<Ratio>,
+
+    /// The size of second-level sub- and superscripts (scripts of scripts),
+    /// relative to the size of their base.
+    ///
+    /// When `{auto}`, the value of the math font's
+    /// `ScriptScriptPercentScaleDown` constant is used.
+    pub sscript_size: Smart<Ratio>,
+
+    /// Whether to use the math font's script-style glyph variants in sub- and
+    /// superscripts.
+    ///
+    /// Many math fonts provide glyphs that are optimized for smaller sizes
+    /// through the `ssty` feature. Turning this off uses the regular glyphs,
+    /// just scaled down.
+    #[default(true)]
+    pub script_alternates: bool,
+
+    /// Whether the equation is set in cramped style.
+    ///
+    /// In cramped style, superscripts are raised less. Regardless of this
+    /// setting, subscripts, denominators, radicands and accented bases are
+    /// always cramped. To change the style of only parts of an equation, use
+    /// the `cramped` argument of the [sizing functions]($category/math/sizes).
+    ///
+    /// ```example
+    /// $ x^2 $
+    /// #set math.equation(cramped: true)
+    /// $ x^2 $
+    /// ```
+    #[default(false)]
+    pub cramped: bool,
+
+    /// Whether to move sub- and superscripts after a glyph closer to it using
+    /// the math font's kerning information (cut-ins).
+    ///
+    /// Cut-ins let a subscript tuck under the arm of a letter like _P_ or a
+    /// superscript reach over the slanted side of a letter like _A_. Not all
+    /// math fonts provide this data.
+    #[default(false)]
+    pub cut_ins: bool,
+
+    /// How inline equations that are taller than the surrounding text fit
+    /// into their line.
+    ///
+    /// By default, tall inline equations like fractions or large operators
+    /// with limits increase the height of their line. In documents where an
+    /// even line spacing matters more, this can be avoided at the cost of
+    /// smaller or overlapping math.
+    ///
+    /// ```example
+    /// #let body = [
+    ///   The sum $sum_(k=0)^n k^2$
+    ///   and the fraction $1/(x^2 + 1)$
+    ///   appear in this line.
+    /// ]
+    ///
+    /// #body
+    ///
+    /// #set math.equation(inline-fit: "scale")
+    /// #body
+    /// ```
+    #[default(InlineFit::Grow)]
+    pub inline_fit: InlineFit,
+
+    /// An amount to shift the baseline of inline equations by.
+    ///
+    /// Positive values lower the equation, negative values raise it. This can
+    /// be used to align the math axis with the surrounding text when the text
+    /// font and the math font don't match well.
+    ///
+    /// ```example
+    /// #set text(font: "Linux Libertine")
+    /// Let $x - y$ be positive. \
+    /// #set math.equation(inline-baseline: -0.5pt)
+    /// Let $x - y$ be positive.
+    /// ```
+    #[resolve]
+    pub inline_baseline: Length,
+
     /// The contents of the equation.
     #[required]
     pub body: Content,
@@ -128,12 +245,6 @@ pub struct EquationElem {
     #[ghost]
     pub variant: MathVariant,
 
-    /// Affects the height of exponents.
-    #[internal]
-    #[default(false)]
-    #[ghost]
-    pub cramped: bool,
-
     /// Whether to use bold glyphs.
     #[internal]
     #[default(false)]
@@ -173,12 +284,21 @@ impl Synthesize for Packed<EquationElem> {
 impl ShowSet for Packed<EquationElem> {
     fn show_set(&self, styles: StyleChain) -> Styles {
         let mut out = Styles::new();
-        if self.block(styles) {
+        let block = self.block(styles);
+        if block {
             out.set(AlignElem::set_alignment(Alignment::CENTER));
             out.set(EquationElem::set_size(MathSize::Display));
         } else {
             out.set(EquationElem::set_size(MathSize::Text));
         }
+        out.set(EquationElem::set_script_size(self.script_size(styles)));
+        out.set(EquationElem::set_sscript_size(self.sscript_size(styles)));
+        out.set(EquationElem::set_script_alternates(self.script_alternates(styles)));
+        out.set(EquationElem::set_cramped(
+            self.cramped(styles)
+                || (!block && self.inline_fit(styles) == InlineFit::Cramp),
+        ));
+        out.set(EquationElem::set_cut_ins(self.cut_ins(styles)));
         out.set(TextElem::set_weight(FontWeight::from_number(450)));
         out.set(TextElem::set_font(FontList(vec![FontFamily::new(
             "New Computer Modern Math",
@@ -224,17 +344,58 @@ impl Packed<EquationElem> {
             vec![MathParItem::Frame(run.into_fragment(&ctx, styles).into_frame())]
         };
 
+        let font_size = scaled_font_size(&ctx, styles);
+        let slack = ParElem::leading_in(styles) * 0.7;
+        let top_edge = TextElem::top_edge_in(styles).resolve(font_size, &font, None);
+        let bottom_edge =
+            -TextElem::bottom_edge_in(styles).resolve(font_size, &font, None);
+
+        let shift = self.inline_baseline(styles);
+        let fit = self.inline_fit(styles);
+
         for item in &mut items {
             let MathParItem::Frame(frame) = item else { continue };
+            if !shift.is_zero() {
+                frame.set_baseline(frame.baseline() - shift);
+            }
+        }
+
+        if fit == InlineFit::Scale {
+            // Shrink all items by the same factor so that the equation stays
+            // consistent across line breaks.
+            let (mut ascent, mut descent) = (Abs::zero(), Abs::zero());
+            for item in &items {
+                let MathParItem::Frame(frame) = item else { continue };
+                ascent.set_max(frame.ascent());
+                descent.set_max(frame.descent());
+            }
 
-            let font_size = scaled_font_size(&ctx, styles);
-            let slack = ParElem::leading_in(styles) * 0.7;
-            let top_edge = TextElem::top_edge_in(styles).resolve(font_size, &font, None);
-            let bottom_edge =
-                -TextElem::bottom_edge_in(styles).resolve(font_size, &font, None);
+            let mut factor = 1.0f64;
+            if ascent > top_edge + slack {
+                factor = factor.min((top_edge + slack) / ascent);
+            }
+            if descent > bottom_edge + slack {
+                factor = factor.min((bottom_edge + slack) / descent);
+            }
+
+            if factor < 1.0 {
+                for item in &mut items {
+                    scale_par_item(item, factor);
+                }
+            }
+        }
 
-            let ascent = top_edge.max(frame.ascent() - slack);
-            let descent = bottom_edge.max(frame.descent() - slack);
+        for item in &mut items {
+            let MathParItem::Frame(frame) = item else { continue };
+
+            let (ascent, descent) = if fit == InlineFit::Overlap {
+                (top_edge, bottom_edge)
+            } else {
+                (
+                    top_edge.max(frame.ascent() - slack),
+                    bottom_edge.max(frame.descent() - slack),
+                )
+            };
             frame.translate(Point::with_y(ascent - frame.baseline()));
             frame.size_mut().y = ascent + descent;
         }
@@ -281,13 +442,23 @@ impl LayoutSingle for Packed<EquationElem> {
             SpecificAlignment::Both(h, v) => SpecificAlignment::Both(h, v),
         };
 
+        let mut number_align = number_align.resolve(styles);
+        let position = self.number_position(styles);
+        if let NumberPosition::Inside | NumberPosition::Outside = position {
+            let page = self.location().unwrap().page(engine);
+            number_align.x =
+                facing_side(page, position == NumberPosition::Inside, styles)
+                    .resolve(styles);
+        }
+
         let frame = add_equation_number(
             equation_builder,
             number,
-            number_align.resolve(styles),
+            number_align,
             AlignElem::alignment_in(styles).resolve(styles).x,
             regions.size.x,
             full_number_width,
+            position != NumberPosition::Text,
         );
 
         Ok(frame)
@@ -423,6 +594,7 @@ fn add_equation_number(
     equation_align: FixedAlignment,
     region_size_x: Abs,
     full_number_width: Abs,
+    in_margin: bool,
 ) -> Frame {
     let first = equation_builder
         .frames
@@ -461,15 +633,22 @@ fn add_equation_number(
         Size::new(width, height),
         Axes::<FixedAlignment>::new(equation_align, number_align.y.inv()),
     );
-    equation.translate(Point::with_x(match (equation_align, number_align.x) {
-        (FixedAlignment::Start, FixedAlignment::Start) => full_number_width,
-        (FixedAlignment::End, FixedAlignment::End) => -full_number_width,
-        _ => Abs::zero(),
-    }));
-
-    let x = match number_align.x {
-        FixedAlignment::Start => Abs::zero(),
-        FixedAlignment::End => equation.width() - number.width(),
+    // A number in the margin doesn't take up space in the text area.
+    if !in_margin {
+        equation.translate(Point::with_x(match (equation_align, number_align.x) {
+            (FixedAlignment::Start, FixedAlignment::Start) => full_number_width,
+            (FixedAlignment::End, FixedAlignment::End) => -full_number_width,
+            _ => Abs::zero(),
+        }));
+    }
+
+    let x = match (number_align.x, in_margin) {
+        (FixedAlignment::Start, false) => Abs::zero(),
+        (FixedAlignment::End, false) => equation.width() - number.width(),
+        (FixedAlignment::Start, true) => -full_number_width,
+        (FixedAlignment::End, true) => {
+            equation.width() + full_number_width - number.width()
+        }
         _ => unreachable!(),
     };
     let dh = |h1: Abs, h2: Abs| (h1 - h2) / 2.0;
@@ -488,3 +667,68 @@ fn add_equation_number(
     equation.push_frame(Point::new(x, y), number);
     equation
 }
+
+/// How inline equations fit into their line.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum InlineFit {
+    /// Increase the height of the line as needed.
+    Grow,
+    /// Set the equation in cramped style to lower its superscripts and grow
+    /// the line only if that isn't enough.
+    Cramp,
+    /// Scale the equation down until it fits into the line.
+    Scale,
+    /// Keep the height of the line and let the equation overlap with the
+    /// adjacent lines.
+    Overlap,
+}
+
+/// Scales a paragraph item of an inline equation about its origin.
+fn scale_par_item(item: &mut MathParItem, factor: f64) {
+    match item {
+        MathParItem::Space(width) => *width *= factor,
+        MathParItem::Frame(frame) => {
+            let baseline = frame.baseline();
+            let ratio = Ratio::new(factor);
+            frame.transform(Transform::scale(ratio, ratio));
+            frame.set_size(frame.size() * factor);
+            frame.set_baseline(baseline * factor);
+        }
+    }
+}
+
+/// Where to place the number of a block-level equation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum NumberPosition {
+    /// Within the text area.
+    Text,
+    /// In the page margin, on the side given by `number-align`.
+    Margin,
+    /// In the margin facing towards the binding.
+    Inside,
+    /// In the margin facing away from the binding.
+    Outside,
+}
+
+/// Determines the physical side of the inside or outside margin on the given
+/// page.
+fn facing_side(page: NonZeroUsize, inside: bool, styles: StyleChain) -> HAlignment {
+    let binding =
+        PageElem::binding_in(styles).unwrap_or_else(|| match TextElem::dir_in(styles) {
+            Dir::LTR => Binding::Left,
+            _ => Binding::Right,
+        });
+
+    // The inside is the bound side, which alternates on two-sided pages.
+    let two_sided = PageElem::margin_in(styles).two_sided.unwrap_or(false);
+    let mut left = binding == Binding::Left;
+    if two_sided && binding.swap(page) {
+        left = !left;
+    }
+
+    if left == inside {
+        HAlignment::Left
+    } else {
+        HAlignment::Right
+    }
+}
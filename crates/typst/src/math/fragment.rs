@@ -525,11 +525,7 @@ fn is_extended_shape(ctx: &MathContext, id: GlyphId) -> bool {
 }
 
 /// Look up a kerning value at a specific corner and height.
-///
-/// This can be integrated once we've found a font that actually provides this
-/// data.
-#[allow(unused)]
-fn kern_at_height(
+pub fn kern_at_height(
     ctx: &MathContext,
     font_size: Abs,
     id: GlyphId,
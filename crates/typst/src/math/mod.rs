@@ -14,6 +14,7 @@ mod fragment;
 mod lr;
 mod matrix;
 mod op;
+mod physics;
 mod root;
 mod row;
 mod spacing;
@@ -31,6 +32,7 @@ pub use self::frac::*;
 pub use self::lr::*;
 pub use self::matrix::*;
 pub use self::op::*;
+pub use self::physics::*;
 pub use self::root::*;
 pub use self::style::*;
 pub use self::underover::*;
@@ -202,6 +204,17 @@ pub fn module() -> Module {
     math.define_func::<inline>();
     math.define_func::<script>();
     math.define_func::<sscript>();
+    math.define_func::<vb>();
+    math.define_func::<va>();
+    math.define_func::<vu>();
+    math.define_func::<dd>();
+    math.define_func::<dv>();
+    math.define_func::<pdv>();
+    math.define_func::<bra>();
+    math.define_func::<ket>();
+    math.define_func::<braket>();
+    math.define_func::<ketbra>();
+    math.define_func::<tensor>();
 
     // Text operators, spacings, and symbols.
     op::define(&mut math);
@@ -0,0 +1,332 @@
+use ecow::eco_format;
+
+use crate::diag::{bail, At, SourceResult};
+use crate::foundations::{func, Content, NativeElement, SequenceElem, Smart};
+use crate::layout::{HElem, HideElem};
+use crate::math::{
+    Accent, AccentElem, AttachElem, EquationElem, FracElem, LrElem, MidElem, THIN,
+};
+use crate::syntax::{Span, Spanned};
+use crate::text::TextElem;
+
+/// A vector in bold face.
+///
+/// ```example
+/// $ vb(F) = m vb(a) $
+/// ```
+#[func(title = "Bold Vector")]
+pub fn vb(
+    /// The vector.
+    body: Content,
+) -> Content {
+    body.styled(EquationElem::set_bold(true))
+}
+
+/// A vector with an arrow above.
+///
+/// ```example
+/// $ va(F) = m va(a) $
+/// ```
+#[func(title = "Arrow Vector")]
+pub fn va(
+    /// The vector.
+    body: Content,
+) -> Content {
+    let span = body.span();
+    AccentElem::new(body, Accent::new('→')).pack().spanned(span)
+}
+
+/// A unit vector, in bold face with a hat.
+///
+/// ```example
+/// $ vb(r) = r vu(r) $
+/// ```
+#[func(title = "Unit Vector")]
+pub fn vu(
+    /// The vector.
+    body: Content,
+) -> Content {
+    let span = body.span();
+    AccentElem::new(body.styled(EquationElem::set_bold(true)), Accent::new('^'))
+        .pack()
+        .spanned(span)
+}
+
+/// A differential, like the _dx_ in an integral.
+///
+/// The upright "d" is preceded by a thin space. With multiple variables, their
+/// differentials are separated by thin spaces.
+///
+/// ```example
+/// $ integral f(x) dd(x) $
+/// $ integral.double f dd(x, y) $
+/// $ dd(x, order: 3) $
+/// ```
+#[func(title = "Differential")]
+pub fn dd(
+    /// The variables.
+    #[variadic]
+    vars: Vec<Content>,
+    /// The order of the differential.
+    #[named]
+    order: Option<Content>,
+) -> Content {
+    let mut seq = vec![];
+    for var in vars {
+        seq.push(HElem::new(THIN.into()).pack());
+        seq.push(differential('d', order.clone()));
+        seq.push(var);
+    }
+    Content::sequence(seq)
+}
+
+/// A derivative, written as a fraction of differentials.
+///
+/// The first argument is the function that is differentiated and the second
+/// one is the variable. To only display the operator, pass empty content
+/// (`[]`) as the function.
+///
+/// ```example
+/// $ dv(f, x) = dv(, x) f $
+/// $ dv(f, t, order: 2) $
+/// ```
+#[func(title = "Derivative")]
+pub fn dv(
+    /// The call site span.
+    span: Span,
+    /// The function to differentiate.
+    f: Content,
+    /// The variable to differentiate with respect to.
+    x: Content,
+    /// The order of the derivative.
+    #[named]
+    order: Option<Content>,
+) -> Content {
+    derivative(span, 'd', f, vec![x], order)
+}
+
+/// A partial derivative, written as a fraction of partial differentials.
+///
+/// The first argument is the function that is differentiated and the
+/// remaining ones are the variables. With multiple variables, a mixed partial
+/// derivative is produced.
+///
+/// ```example
+/// $ pdv(f, x) quad pdv(f, x, order: 2) quad pdv(f, x, y) $
+/// $ pdv(, t) psi = -i H psi $
+/// ```
+#[func(title = "Partial Derivative")]
+pub fn pdv(
+    /// The call site span.
+    span: Span,
+    /// The function to differentiate.
+    f: Content,
+    /// The variables to differentiate with respect to.
+    #[variadic]
+    vars: Vec<Content>,
+    /// The order of the derivative with respect to each variable.
+    #[named]
+    order: Option<Content>,
+) -> SourceResult<Content> {
+    if vars.is_empty() {
+        bail!(span, "missing variable to differentiate with respect to");
+    }
+    Ok(derivative(span, '∂', f, vars, order))
+}
+
+/// A bra in Dirac notation.
+///
+/// ```example
+/// $ bra(psi) $
+/// ```
+#[func]
+pub fn bra(
+    /// The state.
+    body: Content,
+) -> Content {
+    dirac('⟨', [body], '|')
+}
+
+/// A ket in Dirac notation.
+///
+/// ```example
+/// $ ket(psi) = sum_n c_n ket(n) $
+/// ```
+#[func]
+pub fn ket(
+    /// The state.
+    body: Content,
+) -> Content {
+    dirac('|', [body], '⟩')
+}
+
+/// An inner product or matrix element in Dirac notation.
+///
+/// With one argument, this is the inner product of the state with itself.
+/// With three arguments, the middle one is the operator of a matrix element.
+/// The inner bars scale together with the outer brackets.
+///
+/// ```example
+/// $ braket(phi) quad braket(phi, psi) quad braket(m, hat(H), n) $
+/// ```
+#[func(title = "Bra-Ket")]
+pub fn braket(
+    /// The call site span.
+    span: Span,
+    /// The states and optionally the operator.
+    #[variadic]
+    parts: Vec<Content>,
+) -> SourceResult<Content> {
+    Ok(match parts.len() {
+        1 => dirac('⟨', [parts[0].clone(), parts[0].clone()], '⟩'),
+        2 | 3 => dirac('⟨', parts, '⟩'),
+        _ => bail!(span, "expected one to three arguments"),
+    })
+}
+
+/// An outer product in Dirac notation.
+///
+/// ```example
+/// $ ketbra(psi) quad ketbra(n, m) $
+/// ```
+#[func(title = "Ket-Bra")]
+pub fn ketbra(
+    /// The first state.
+    a: Content,
+    /// The second state. Defaults to the first one.
+    #[default]
+    b: Option<Content>,
+) -> Content {
+    let b = b.unwrap_or_else(|| a.clone());
+    Content::sequence([dirac('|', [a], '⟩'), dirac('⟨', [b], '|')])
+}
+
+/// A tensor with aligned upper and lower indices.
+///
+/// Each index starts with `+` if it is an upper (contravariant) index or with
+/// `-` if it is a lower (covariant) index. The indices are set in the order
+/// they are given, so that no upper index sits above a lower one.
+///
+/// ```example
+/// $ tensor(T, +mu, -nu) = g^(mu alpha) tensor(T, -alpha, -nu) $
+/// $ tensor(R, +rho, -sigma, -mu, -nu) $
+/// ```
+#[func]
+pub fn tensor(
+    /// The base of the tensor.
+    base: Content,
+    /// The indices, each starting with `+` or `-`.
+    #[variadic]
+    indices: Vec<Spanned<Content>>,
+) -> SourceResult<Content> {
+    // Each index is mirrored by an invisible copy on the other side so that
+    // the indices line up in columns.
+    let (mut upper, mut lower) = (vec![], vec![]);
+    let (mut has_upper, mut has_lower) = (false, false);
+    for Spanned { v: index, span } in indices {
+        let (up, index) = split_index(&index).at(span)?;
+        let hidden = HideElem::new(index.clone()).pack();
+        if up {
+            has_upper = true;
+            upper.push(index);
+            lower.push(hidden);
+        } else {
+            has_lower = true;
+            lower.push(index);
+            upper.push(hidden);
+        }
+    }
+
+    let span = base.span();
+    let mut elem = AttachElem::new(base);
+    if has_upper {
+        elem.push_tr(Some(Content::sequence(upper)));
+    }
+    if has_lower {
+        elem.push_br(Some(Content::sequence(lower)));
+    }
+    Ok(elem.pack().spanned(span))
+}
+
+/// An upright differential operator, optionally raised to a power.
+fn differential(d: char, order: Option<Content>) -> Content {
+    let d = TextElem::packed(d).styled(EquationElem::set_italic(Smart::Custom(false)));
+    match order {
+        Some(order) => AttachElem::new(d).with_tr(Some(order)).pack(),
+        None => d,
+    }
+}
+
+/// Builds a derivative fraction.
+fn derivative(
+    span: Span,
+    d: char,
+    f: Content,
+    vars: Vec<Content>,
+    order: Option<Content>,
+) -> Content {
+    // The order of the numerator's operator is the total order of the
+    // derivative.
+    let total = match (&order, vars.len()) {
+        (Some(order), 1) => Some(order.clone()),
+        (None, 1) => None,
+        (None, n) => Some(TextElem::packed(eco_format!("{n}"))),
+        (Some(order), n) => Some(
+            match order
+                .to_packed::<TextElem>()
+                .and_then(|t| t.text().parse::<u64>().ok())
+            {
+                Some(k) => TextElem::packed(eco_format!("{}", n as u64 * k)),
+                None => Content::sequence([
+                    TextElem::packed(eco_format!("{n}")),
+                    order.clone(),
+                ]),
+            },
+        ),
+    };
+
+    let num = Content::sequence([differential(d, total), f]);
+    let mut den = vec![];
+    for (i, var) in vars.into_iter().enumerate() {
+        if i > 0 {
+            den.push(HElem::new(THIN.into()).pack());
+        }
+        den.push(differential(d, None));
+        den.push(match &order {
+            Some(order) => AttachElem::new(var).with_tr(Some(order.clone())).pack(),
+            None => var,
+        });
+    }
+
+    FracElem::new(num, Content::sequence(den)).pack().spanned(span)
+}
+
+/// Builds scaling Dirac brackets with bars between the parts.
+fn dirac(left: char, parts: impl IntoIterator<Item = Content>, right: char) -> Content {
+    let mut seq = vec![TextElem::packed(left)];
+    for (i, part) in parts.into_iter().enumerate() {
+        if i > 0 {
+            seq.push(MidElem::new(TextElem::packed('|')).pack());
+        }
+        seq.push(part);
+    }
+    seq.push(TextElem::packed(right));
+    LrElem::new(Content::sequence(seq)).pack()
+}
+
+/// Splits a tensor index into its position and the index itself.
+fn split_index(index: &Content) -> Result<(bool, Content), &'static str> {
+    let children = match index.to_packed::<SequenceElem>() {
+        Some(seq) => seq.children.clone(),
+        None => vec![index.clone()],
+    };
+
+    let mut iter = children.into_iter().filter(|child| !child.is_empty());
+    let up = match iter.next().as_ref().and_then(|c| c.to_packed::<TextElem>()) {
+        Some(text) if text.text() == "+" => true,
+        Some(text) if text.text() == "-" || text.text() == "−" => false,
+        _ => return Err("tensor index must start with `+` or `-`"),
+    };
+
+    Ok((up, Content::sequence(iter)))
+}
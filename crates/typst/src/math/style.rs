@@ -1,5 +1,5 @@
 use crate::foundations::{func, Cast, Content, Smart, Style, StyleChain};
-use crate::layout::Abs;
+use crate::layout::{Abs, Ratio};
 use crate::math::{EquationElem, MathContext};
 use crate::text::TextElem;
 use crate::util::LazyHash;
@@ -225,11 +225,13 @@ pub enum MathSize {
 
 impl MathSize {
     /// The scaling factor.
-    pub fn factor(self, ctx: &MathContext) -> f64 {
+    pub fn factor(self, ctx: &MathContext, styles: StyleChain) -> f64 {
         match self {
             Self::Display | Self::Text => 1.0,
-            Self::Script => percent!(ctx, script_percent_scale_down),
-            Self::ScriptScript => percent!(ctx, script_script_percent_scale_down),
+            Self::Script => EquationElem::script_size_in(styles)
+                .map_or(percent!(ctx, script_percent_scale_down), Ratio::get),
+            Self::ScriptScript => EquationElem::sscript_size_in(styles)
+                .map_or(percent!(ctx, script_script_percent_scale_down), Ratio::get),
         }
     }
 }
@@ -248,7 +250,7 @@ pub enum MathVariant {
 
 /// Get the font size scaled with the `MathSize`.
 pub fn scaled_font_size(ctx: &MathContext, styles: StyleChain) -> Abs {
-    EquationElem::size_in(styles).factor(ctx) * TextElem::size_in(styles)
+    EquationElem::size_in(styles).factor(ctx, styles) * TextElem::size_in(styles)
 }
 
 /// Styles something as cramped.
@@ -0,0 +1,838 @@
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use ecow::{eco_format, EcoString};
+use smallvec::SmallVec;
+
+use crate::diag::{bail, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, dict, elem, func, scope, Array, Cast, Content, Datetime, Dict, Duration,
+    IntoValue, NativeElement, Packed, Show, Smart, StyleChain, Value,
+};
+use crate::layout::{
+    BlockElem, Celled, Corners, Dir, Em, Fr, GridCell, GridChild, GridElem, GridItem,
+    HAlignment, Length, Rel, Sides, Sizing, Spacing, StackChild, StackElem, TrackSizings,
+};
+use crate::text::{Lang, Region, TextElem, TextSize};
+use crate::util::option_eq;
+use crate::visualize::{Color, Luma, Paint, Stroke};
+
+/// A calendar for a month or a week.
+///
+/// The calendar is laid out as a grid with one column per weekday and shows
+/// the given events as blocks in the cells of their days. The names of the
+/// month and the weekdays follow the [text language]($text.lang), and the
+/// first day of the week follows the [region]($text.region).
+///
+/// # Example
+/// ```example
+/// #set page(width: 12cm, height: auto)
+/// #calendar(
+///   datetime(year: 2024, month: 5, day: 1),
+///   events: (
+///     (datetime(year: 2024, month: 5, day: 8), [Launch]),
+///     (
+///       date: datetime(year: 2024, month: 5, day: 20),
+///       end: datetime(year: 2024, month: 5, day: 22),
+///       body: [Conference],
+///       fill: red.lighten(70%),
+///     ),
+///   ),
+/// )
+/// ```
+#[elem(scope, Show)]
+pub struct CalendarElem {
+    /// A date within the month or week to show.
+    #[required]
+    pub date: Datetime,
+
+    /// The events to show in the calendar.
+    ///
+    /// Each event is either an array of a date and a body or a dictionary
+    /// with the following keys:
+    /// - `date`: The date of the event.
+    /// - `end`: The last day of the event, for events that span several
+    ///   days. Optional.
+    /// - `body`: The content to show in the event's block.
+    /// - `fill`: How to fill the event's block. Defaults to the calendar's
+    ///   [`event-fill`]($calendar.event-fill).
+    ///
+    /// Events outside of the shown days are ignored.
+    pub events: Vec<CalendarEvent>,
+
+    /// Whether to show the whole month or only the week of the date.
+    ///
+    /// ```example
+    /// #calendar(
+    ///   datetime(year: 2024, month: 3, day: 13),
+    ///   view: "week",
+    ///   events: ((datetime(year: 2024, month: 3, day: 14), [Pi day]),),
+    /// )
+    /// ```
+    #[default(CalendarView::Month)]
+    pub view: CalendarView,
+
+    /// The day on which weeks start.
+    ///
+    /// When set to `{auto}`, weeks start on Sunday in regions where that is
+    /// customary (such as the United States) and on Monday otherwise.
+    ///
+    /// ```example
+    /// #set text(region: "us")
+    /// #calendar(datetime(year: 2024, month: 2, day: 1))
+    /// ```
+    pub first_day: Smart<Weekday>,
+
+    /// Whether to show the ISO week numbers in front of each week.
+    #[default(false)]
+    pub week_numbers: bool,
+
+    /// The minimum height of a day's cell.
+    #[default(Em::new(3.0).into())]
+    pub day_height: Rel<Length>,
+
+    /// How to fill the blocks of events that don't specify their own fill.
+    #[default(Color::Luma(Luma::new(0.88, 1.0)).into())]
+    pub event_fill: Paint,
+
+    /// How to stroke the grid of the calendar.
+    #[default(Some(Stroke {
+        paint: Smart::Custom(Color::GRAY.into()),
+        thickness: Smart::Custom(Length::from(crate::layout::Abs::pt(0.5))),
+        ..Default::default()
+    }))]
+    pub stroke: Option<Stroke>,
+}
+
+#[scope]
+impl CalendarElem {
+    /// Creates an array of recurring dates.
+    ///
+    /// Either `until` or `count` must be given. When stepping by months or
+    /// years, occurrences that fall on a day that doesn't exist (such as
+    /// February 30th) are skipped.
+    ///
+    /// ```example
+    /// #let start = datetime(year: 2024, month: 1, day: 31)
+    /// #calendar.recurring(start, "month", count: 4)
+    ///   .map(d => d.display())
+    ///   .join(", ")
+    /// ```
+    #[func]
+    pub fn recurring(
+        /// The first occurrence.
+        start: Datetime,
+        /// The interval between occurrences. Either a duration or one of
+        /// `{"day"}`, `{"week"}`, `{"month"}`, and `{"year"}`.
+        every: Recurrence,
+        /// The latest date (inclusive) on which an occurrence may fall.
+        #[named]
+        until: Option<Datetime>,
+        /// The maximum number of occurrences.
+        #[named]
+        count: Option<usize>,
+    ) -> StrResult<Array> {
+        if until.is_none() && count.is_none() {
+            bail!("must specify either `until` or `count`");
+        }
+        if let Recurrence::Duration(duration) = every {
+            if time::Duration::from(duration) <= time::Duration::ZERO {
+                bail!("recurrence interval must be positive");
+            }
+        } else if start.year().is_none() {
+            bail!("cannot step a time by {}", every.name());
+        }
+        if let Some(until) = until {
+            if start.partial_cmp(&until).is_none() {
+                bail!("cannot compare {} with {}", start.kind(), until.kind());
+            }
+        }
+
+        let mut dates = Array::new();
+        let mut step = 0;
+        while count.map_or(true, |count| dates.len() < count) {
+            // As the interval is positive, occurrences beyond the supported
+            // range are also after `until`.
+            let Some(occurrence) = every.nth(start, step) else {
+                if until.is_some() {
+                    break;
+                }
+                bail!("recurrence exceeds the supported range of dates");
+            };
+            if let Some(date) = occurrence {
+                if until.is_some_and(|until| date > until) {
+                    break;
+                }
+                dates.push(date.into_value());
+            }
+            step = step
+                .checked_add(1)
+                .ok_or("recurrence exceeds the supported range of dates")?;
+        }
+
+        Ok(dates)
+    }
+}
+
+impl Show for Packed<CalendarElem> {
+    #[typst_macros::time(name = "calendar", span = self.span())]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let span = self.span();
+        let lang = TextElem::lang_in(styles);
+        let region = TextElem::region_in(styles);
+        let names = CalendarNames::new(lang, region);
+        let Some(date) = date_of(*self.date()) else {
+            bail!(span, "calendar date must contain a date");
+        };
+
+        let first_day = self
+            .first_day(styles)
+            .unwrap_or_else(|| Weekday::first_in(lang, region));
+        let view = self.view(styles);
+        let offset = |day: time::Date| {
+            (day.weekday().number_days_from_monday() + 7 - first_day as u8) % 7
+        };
+
+        // Determine the weeks to show. Days of adjacent months are only shown
+        // in the week view.
+        let (first, last) = match view {
+            CalendarView::Month => {
+                let len = time::util::days_in_year_month(date.year(), date.month());
+                (date.replace_day(1).unwrap(), date.replace_day(len).unwrap())
+            }
+            CalendarView::Week => (date, date),
+        };
+        let start = first - time::Duration::days(offset(first).into());
+        let weeks = (last - start).whole_weeks() + 1;
+        let shown =
+            |day: time::Date| view == CalendarView::Week || day.month() == date.month();
+
+        let week_numbers = self.week_numbers(styles);
+        let columns = 7 + usize::from(week_numbers);
+        let small = |body: Content| {
+            body.styled(TextElem::set_size(TextSize(Em::new(0.8).into())))
+        };
+        let cell =
+            |body: Content| GridChild::Item(GridItem::Cell(Packed::new(body.into())));
+
+        let mut cells = vec![];
+        let title = names.title(date.year(), date.month());
+        cells.push(GridChild::Item(GridItem::Cell(Packed::new(
+            GridCell::new(TextElem::packed(title).strong())
+                .with_colspan(NonZeroUsize::new(columns).unwrap())
+                .with_align(Smart::Custom(HAlignment::Center.into())),
+        ))));
+
+        if week_numbers {
+            cells.push(cell(Content::empty()));
+        }
+        for i in 0..7 {
+            let name = names.weekdays[(first_day as usize + i) % 7];
+            let body = TextElem::packed(capitalize(name)).strong();
+            cells.push(cell(small(body).aligned(HAlignment::Center.into())));
+        }
+
+        let day_height = self.day_height(styles);
+        let event_fill = self.event_fill(styles);
+        let events = self.events(styles);
+        for week in 0..weeks {
+            let week_start = start + time::Duration::weeks(week);
+            if week_numbers {
+                // Use the middle of the row, which works for any first day.
+                let middle = week_start + time::Duration::days(3);
+                let number = TextElem::packed(eco_format!("{}", middle.iso_week()));
+                let body = small(number).styled(TextElem::set_fill(Color::GRAY.into()));
+                cells.push(cell(body.aligned(HAlignment::Center.into())));
+            }
+
+            for weekday in 0..7 {
+                let day = week_start + time::Duration::days(weekday);
+                if !shown(day) {
+                    cells.push(cell(Content::empty()));
+                    continue;
+                }
+
+                let number = TextElem::packed(eco_format!("{}", day.day()));
+                let mut children = vec![StackChild::Block(
+                    small(number).aligned(HAlignment::End.into()),
+                )];
+                for event in events.iter().filter(|event| event.contains(day)) {
+                    let fill = event.fill.clone().unwrap_or_else(|| event_fill.clone());
+                    let block = BlockElem::new()
+                        .with_width(Smart::Custom(Rel::one()))
+                        .with_inset(Sides::splat(Some(Em::new(0.2).into())))
+                        .with_radius(Corners::splat(Some(Em::new(0.2).into())))
+                        .with_fill(Some(fill))
+                        .with_body(Some(small(event.body.clone())));
+                    children.push(StackChild::Block(block.pack().spanned(span)));
+                }
+
+                // A zero-width strut next to the contents of the day ensures
+                // the minimum height of the cell.
+                let strut = BlockElem::new()
+                    .with_width(Smart::Custom(Rel::zero()))
+                    .with_height(Smart::Custom(day_height));
+                let body = StackElem::new(vec![
+                    StackChild::Block(strut.pack()),
+                    StackChild::Block(
+                        BlockElem::new()
+                            .with_width(Smart::Custom(Rel::one()))
+                            .with_body(Some(
+                                StackElem::new(children)
+                                    .with_spacing(Some(Spacing::Rel(Em::new(0.2).into())))
+                                    .pack(),
+                            ))
+                            .pack(),
+                    ),
+                ])
+                .with_dir(Dir::LTR);
+                cells.push(cell(body.pack()));
+            }
+        }
+
+        let mut tracks: SmallVec<[Sizing; 4]> =
+            std::iter::repeat(Sizing::Fr(Fr::one())).take(7).collect();
+        if week_numbers {
+            tracks.insert(0, Sizing::Auto);
+        }
+
+        let stroke = self.stroke(styles).map(Arc::new);
+        let grid = GridElem::new(cells)
+            .with_columns(TrackSizings(tracks))
+            .with_inset(Celled::Value(Sides::splat(Some(Em::new(0.3).into()))))
+            .with_stroke(Celled::Value(Sides::splat(Some(stroke))))
+            .pack()
+            .spanned(span);
+
+        Ok(BlockElem::new().with_body(Some(grid)).pack().spanned(span))
+    }
+}
+
+/// An event in a calendar.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct CalendarEvent {
+    /// The first day of the event.
+    date: time::Date,
+    /// The last day of the event.
+    end: Option<time::Date>,
+    /// The body of the event.
+    body: Content,
+    /// How to fill the event's block.
+    fill: Smart<Paint>,
+}
+
+impl CalendarEvent {
+    /// Whether the event takes place on the given day.
+    fn contains(&self, day: time::Date) -> bool {
+        match self.end {
+            Some(end) => (self.date..=end).contains(&day),
+            None => self.date == day,
+        }
+    }
+}
+
+cast! {
+    CalendarEvent,
+    self => dict! {
+        "date" => Datetime::Date(self.date),
+        "end" => self.end.map(Datetime::Date),
+        "body" => self.body,
+        "fill" => self.fill,
+    }.into_value(),
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(date), Some(body), None) => Self {
+                date: event_date(date)?,
+                end: None,
+                body: body.display(),
+                fill: Smart::Auto,
+            },
+            _ => bail!("event array must contain exactly two entries"),
+        }
+    },
+    mut dict: Dict => {
+        let date = event_date(dict.take("date")?)?;
+        let end = dict.take("end").ok().map(event_date).transpose()?;
+        let body = dict.take("body")?.display();
+        let fill = dict.take("fill").ok().map(Value::cast).transpose()?;
+        dict.finish(&["date", "end", "body", "fill"])?;
+        Self { date, end, body, fill: fill.unwrap_or(Smart::Auto) }
+    },
+}
+
+/// Extract the date of an event from a value.
+fn event_date(value: Value) -> StrResult<time::Date> {
+    let datetime: Datetime = value.cast()?;
+    date_of(datetime).ok_or_else(|| "event date must contain a date".into())
+}
+
+/// The date part of a datetime, if any.
+fn date_of(datetime: Datetime) -> Option<time::Date> {
+    match datetime {
+        Datetime::Date(date) => Some(date),
+        Datetime::Datetime(datetime) => Some(datetime.date()),
+        Datetime::Time(_) => None,
+    }
+}
+
+/// The range of days shown by a calendar.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum CalendarView {
+    /// The whole month.
+    Month,
+    /// A single week.
+    Week,
+}
+
+/// A day of the week.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Weekday {
+    /// Monday.
+    Monday,
+    /// Tuesday.
+    Tuesday,
+    /// Wednesday.
+    Wednesday,
+    /// Thursday.
+    Thursday,
+    /// Friday.
+    Friday,
+    /// Saturday.
+    Saturday,
+    /// Sunday.
+    Sunday,
+}
+
+impl Weekday {
+    /// The customary first day of the week for a language and region.
+    fn first_in(lang: Lang, region: Option<Region>) -> Self {
+        let sunday =
+            ["US", "CA", "MX", "BR", "JP", "IL", "PH", "KR", "TW", "HK", "IN", "ZA"];
+        if sunday.iter().any(|code| option_eq(region, *code))
+            || (region.is_none() && lang == Lang::JAPANESE)
+        {
+            Self::Sunday
+        } else {
+            Self::Monday
+        }
+    }
+}
+
+/// The interval between recurring dates.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Recurrence {
+    /// A fixed duration.
+    Duration(Duration),
+    /// Calendar days.
+    Day,
+    /// Calendar weeks.
+    Week,
+    /// Calendar months.
+    Month,
+    /// Calendar years.
+    Year,
+}
+
+impl Recurrence {
+    /// The name of the interval.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Duration(_) => "duration",
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Year => "year",
+        }
+    }
+
+    /// The `n`-th occurrence after the start. Returns `Some(None)` if that
+    /// day doesn't exist and `None` if it is out of the supported range.
+    fn nth(self, start: Datetime, n: i32) -> Option<Option<Datetime>> {
+        let duration = |duration: time::Duration| {
+            add_duration(start, duration.checked_mul(n)?).map(Some)
+        };
+        let months = |months: i32| add_months(start, months.checked_mul(n)?);
+        match self {
+            Self::Duration(d) => duration(d.into()),
+            Self::Day => duration(time::Duration::DAY),
+            Self::Week => duration(time::Duration::WEEK),
+            Self::Month => months(1),
+            Self::Year => months(12),
+        }
+    }
+}
+
+cast! {
+    Recurrence,
+    self => match self {
+        Self::Duration(duration) => duration.into_value(),
+        other => other.name().into_value(),
+    },
+    duration: Duration => Self::Duration(duration),
+    /// Every day.
+    "day" => Self::Day,
+    /// Every week.
+    "week" => Self::Week,
+    /// Every month.
+    "month" => Self::Month,
+    /// Every year.
+    "year" => Self::Year,
+}
+
+/// Add a duration to a datetime. Returns `None` if the result is out of the
+/// supported range, which for a time is the same day.
+fn add_duration(datetime: Datetime, duration: time::Duration) -> Option<Datetime> {
+    Some(match datetime {
+        Datetime::Date(date) => Datetime::Date(date.checked_add(duration)?),
+        Datetime::Datetime(datetime) => {
+            Datetime::Datetime(datetime.checked_add(duration)?)
+        }
+        Datetime::Time(time) => {
+            let offset = (time - time::Time::MIDNIGHT).checked_add(duration)?;
+            if offset.is_negative() || offset >= time::Duration::DAY {
+                return None;
+            }
+            Datetime::Time(time::Time::MIDNIGHT + offset)
+        }
+    })
+}
+
+/// Add a number of calendar months to a datetime. Returns `Some(None)` if the
+/// day doesn't exist in the resulting month and `None` if the month is out of
+/// the supported range.
+fn add_months(datetime: Datetime, months: i32) -> Option<Option<Datetime>> {
+    let shift = |date: time::Date| {
+        let total = (date.year() * 12 + i32::from(u8::from(date.month())) - 1)
+            .checked_add(months)?;
+        let year = total.div_euclid(12);
+        if year < time::Date::MIN.year() || year > time::Date::MAX.year() {
+            return None;
+        }
+        let month = time::Month::try_from((total.rem_euclid(12) + 1) as u8).ok()?;
+        Some(time::Date::from_calendar_date(year, month, date.day()).ok())
+    };
+    match datetime {
+        Datetime::Date(date) => Some(shift(date)?.map(Datetime::Date)),
+        Datetime::Datetime(datetime) => Some(
+            shift(datetime.date())?
+                .map(|date| Datetime::Datetime(datetime.replace_date(date))),
+        ),
+        Datetime::Time(_) => None,
+    }
+}
+
+/// Capitalize the first letter of a name.
+fn capitalize(name: &str) -> EcoString {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => EcoString::new(),
+    }
+}
+
+/// The localized names of the months and weekdays.
+struct CalendarNames {
+    /// The names of the months, starting with January.
+    months: [&'static str; 12],
+    /// The abbreviated names of the weekdays, starting with Monday.
+    weekdays: [&'static str; 7],
+    /// Whether the title is written as "{year}年{month}月".
+    cjk: bool,
+}
+
+impl CalendarNames {
+    /// The names for a language and region.
+    fn new(lang: Lang, _: Option<Region>) -> Self {
+        let (months, weekdays) = match lang {
+            Lang::GERMAN => (
+                [
+                    "Januar",
+                    "Februar",
+                    "März",
+                    "April",
+                    "Mai",
+                    "Juni",
+                    "Juli",
+                    "August",
+                    "September",
+                    "Oktober",
+                    "November",
+                    "Dezember",
+                ],
+                ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+            ),
+            Lang::FRENCH => (
+                [
+                    "janvier",
+                    "février",
+                    "mars",
+                    "avril",
+                    "mai",
+                    "juin",
+                    "juillet",
+                    "août",
+                    "septembre",
+                    "octobre",
+                    "novembre",
+                    "décembre",
+                ],
+                ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+            ),
+            Lang::SPANISH => (
+                [
+                    "enero",
+                    "febrero",
+                    "marzo",
+                    "abril",
+                    "mayo",
+                    "junio",
+                    "julio",
+                    "agosto",
+                    "septiembre",
+                    "octubre",
+                    "noviembre",
+                    "diciembre",
+                ],
+                ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+            ),
+            Lang::ITALIAN => (
+                [
+                    "gennaio",
+                    "febbraio",
+                    "marzo",
+                    "aprile",
+                    "maggio",
+                    "giugno",
+                    "luglio",
+                    "agosto",
+                    "settembre",
+                    "ottobre",
+                    "novembre",
+                    "dicembre",
+                ],
+                ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+            ),
+            Lang::PORTUGUESE => (
+                [
+                    "janeiro",
+                    "fevereiro",
+                    "março",
+                    "abril",
+                    "maio",
+                    "junho",
+                    "julho",
+                    "agosto",
+                    "setembro",
+                    "outubro",
+                    "novembro",
+                    "dezembro",
+                ],
+                ["seg", "ter", "qua", "qui", "sex", "sáb", "dom"],
+            ),
+            Lang::DUTCH => (
+                [
+                    "januari",
+                    "februari",
+                    "maart",
+                    "april",
+                    "mei",
+                    "juni",
+                    "juli",
+                    "augustus",
+                    "september",
+                    "oktober",
+                    "november",
+                    "december",
+                ],
+                ["ma", "di", "wo", "do", "vr", "za", "zo"],
+            ),
+            Lang::SWEDISH => (
+                [
+                    "januari",
+                    "februari",
+                    "mars",
+                    "april",
+                    "maj",
+                    "juni",
+                    "juli",
+                    "augusti",
+                    "september",
+                    "oktober",
+                    "november",
+                    "december",
+                ],
+                ["mån", "tis", "ons", "tor", "fre", "lör", "sön"],
+            ),
+            Lang::DANISH => (
+                [
+                    "januar",
+                    "februar",
+                    "marts",
+                    "april",
+                    "maj",
+                    "juni",
+                    "juli",
+                    "august",
+                    "september",
+                    "oktober",
+                    "november",
+                    "december",
+                ],
+                ["man", "tir", "ons", "tor", "fre", "lør", "søn"],
+            ),
+            Lang::BOKMÅL | Lang::NYNORSK => (
+                [
+                    "januar",
+                    "februar",
+                    "mars",
+                    "april",
+                    "mai",
+                    "juni",
+                    "juli",
+                    "august",
+                    "september",
+                    "oktober",
+                    "november",
+                    "desember",
+                ],
+                ["man", "tir", "ons", "tor", "fre", "lør", "søn"],
+            ),
+            Lang::FINNISH => (
+                [
+                    "tammikuu",
+                    "helmikuu",
+                    "maaliskuu",
+                    "huhtikuu",
+                    "toukokuu",
+                    "kesäkuu",
+                    "heinäkuu",
+                    "elokuu",
+                    "syyskuu",
+                    "lokakuu",
+                    "marraskuu",
+                    "joulukuu",
+                ],
+                ["ma", "ti", "ke", "to", "pe", "la", "su"],
+            ),
+            Lang::POLISH => (
+                [
+                    "styczeń",
+                    "luty",
+                    "marzec",
+                    "kwiecień",
+                    "maj",
+                    "czerwiec",
+                    "lipiec",
+                    "sierpień",
+                    "wrzesień",
+                    "październik",
+                    "listopad",
+                    "grudzień",
+                ],
+                ["pon", "wt", "śr", "czw", "pt", "sob", "nd"],
+            ),
+            Lang::CZECH => (
+                [
+                    "leden",
+                    "únor",
+                    "březen",
+                    "duben",
+                    "květen",
+                    "červen",
+                    "červenec",
+                    "srpen",
+                    "září",
+                    "říjen",
+                    "listopad",
+                    "prosinec",
+                ],
+                ["po", "út", "st", "čt", "pá", "so", "ne"],
+            ),
+            Lang::RUSSIAN => (
+                [
+                    "январь",
+                    "февраль",
+                    "март",
+                    "апрель",
+                    "май",
+                    "июнь",
+                    "июль",
+                    "август",
+                    "сентябрь",
+                    "октябрь",
+                    "ноябрь",
+                    "декабрь",
+                ],
+                ["пн", "вт", "ср", "чт", "пт", "сб", "вс"],
+            ),
+            Lang::UKRAINIAN => (
+                [
+                    "січень",
+                    "лютий",
+                    "березень",
+                    "квітень",
+                    "травень",
+                    "червень",
+                    "липень",
+                    "серпень",
+                    "вересень",
+                    "жовтень",
+                    "листопад",
+                    "грудень",
+                ],
+                ["пн", "вт", "ср", "чт", "пт", "сб", "нд"],
+            ),
+            Lang::TURKISH => (
+                [
+                    "Ocak", "Şubat", "Mart", "Nisan", "Mayıs", "Haziran", "Temmuz",
+                    "Ağustos", "Eylül", "Ekim", "Kasım", "Aralık",
+                ],
+                ["Pzt", "Sal", "Çar", "Per", "Cum", "Cmt", "Paz"],
+            ),
+            Lang::CHINESE => {
+                return Self {
+                    months: [""; 12],
+                    weekdays: ["一", "二", "三", "四", "五", "六", "日"],
+                    cjk: true,
+                }
+            }
+            Lang::JAPANESE => {
+                return Self {
+                    months: [""; 12],
+                    weekdays: ["月", "火", "水", "木", "金", "土", "日"],
+                    cjk: true,
+                }
+            }
+            Lang::ENGLISH | _ => (
+                [
+                    "January",
+                    "February",
+                    "March",
+                    "April",
+                    "May",
+                    "June",
+                    "July",
+                    "August",
+                    "September",
+                    "October",
+                    "November",
+                    "December",
+                ],
+                ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+            ),
+        };
+        Self { months, weekdays, cjk: false }
+    }
+
+    /// The title of a month.
+    fn title(&self, year: i32, month: time::Month) -> EcoString {
+        if self.cjk {
+            eco_format!("{year}年{}月", u8::from(month))
+        } else {
+            let name = self.months[usize::from(u8::from(month)) - 1];
+            eco_format!("{} {year}", capitalize(name))
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use ecow::EcoString;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, Cast, Content, Datetime, NativeElement, Packed, Resolve, Show, Smart,
+    StyleChain, Synthesize,
+};
+use crate::introspection::{Locatable, Meta};
+use crate::layout::{Abs, Frame, FrameItem, Length, Point, Transform};
+use crate::model::DocumentElem;
+use crate::syntax::Span;
+use crate::text::{StrikeElem, TextElem, UnderlineElem};
+use crate::util::hash128;
+use crate::visualize::{Color, FixedStroke, Geometry, Paint, Stroke};
+
+/// Marks content as inserted by a tracked change.
+///
+/// Together with [deletions]($deletion), insertions record the changes made
+/// to a text, for example while negotiating a contract or editing a standard.
+/// By default, an insertion is underlined and colored in a color that depends
+/// on its author. With the document's [`changes`]($document.changes) setting,
+/// you can instead show the final or the original text.
+///
+/// # Example
+/// ```example
+/// The contract ends on
+/// #deletion(author: "Alice")[May 1]
+/// #insertion(author: "Alice")[June 1]
+/// and may be extended
+/// #insertion(author: "Bob")[twice].
+/// ```
+#[elem(Locatable, Synthesize, Show)]
+pub struct InsertionElem {
+    /// The author of the change.
+    pub author: Option<EcoString>,
+
+    /// When the change was made.
+    pub date: Option<Datetime>,
+
+    /// How to color the inserted content.
+    ///
+    /// If set to `{auto}`, the color is picked based on the author, so that
+    /// the changes of different authors can be told apart.
+    ///
+    /// ```example
+    /// #set insertion(fill: blue)
+    /// A #insertion[blue] word.
+    /// ```
+    pub fill: Smart<Paint>,
+
+    /// How to stroke the change bar next to the changed lines.
+    ///
+    /// Change bars are drawn in the left margin of the page. If the stroke
+    /// has no paint, the change's [fill]($insertion.fill) is used.
+    ///
+    /// ```example
+    /// #set insertion(bar: 1pt)
+    /// #set page(margin: (left: 32pt))
+    /// This line was
+    /// #insertion[changed].
+    /// ```
+    pub bar: Option<Stroke>,
+
+    /// The inserted content.
+    #[required]
+    pub body: Content,
+}
+
+impl Synthesize for Packed<InsertionElem> {
+    fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
+        let fill = resolve_fill(self.author(styles), self.fill(styles));
+        let bar = resolve_bar(self.bar(styles), &fill, styles);
+        let elem = self.as_mut();
+        elem.push_fill(Smart::Custom(fill));
+        elem.push_bar(bar);
+        Ok(())
+    }
+}
+
+impl Show for Packed<InsertionElem> {
+    #[typst_macros::time(name = "insertion", span = self.span())]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let body = self.body().clone();
+        Ok(match DocumentElem::changes_in(styles) {
+            ChangeView::Markup => UnderlineElem::new(body)
+                .pack()
+                .spanned(self.span())
+                .styled(TextElem::set_fill(resolve_fill(
+                    self.author(styles),
+                    self.fill(styles),
+                ))),
+            ChangeView::Final => body,
+            ChangeView::Original => Content::empty(),
+        })
+    }
+}
+
+/// Marks content as deleted by a tracked change.
+///
+/// This is the counterpart to [insertions]($insertion). By default, a
+/// deletion is struck through and colored in a color that depends on its
+/// author.
+///
+/// # Example
+/// ```example
+/// Payment is due within
+/// #deletion(author: "Alice")[30]
+/// #insertion(author: "Alice")[60]
+/// days.
+/// ```
+#[elem(Locatable, Synthesize, Show)]
+pub struct DeletionElem {
+    /// The author of the change.
+    pub author: Option<EcoString>,
+
+    /// When the change was made.
+    pub date: Option<Datetime>,
+
+    /// How to color the deleted content.
+    ///
+    /// If set to `{auto}`, the color is picked based on the author, so that
+    /// the changes of different authors can be told apart.
+    pub fill: Smart<Paint>,
+
+    /// How to stroke the change bar next to the changed lines.
+    ///
+    /// Change bars are drawn in the left margin of the page. If the stroke
+    /// has no paint, the change's [fill]($deletion.fill) is used.
+    pub bar: Option<Stroke>,
+
+    /// The deleted content.
+    #[required]
+    pub body: Content,
+}
+
+impl Synthesize for Packed<DeletionElem> {
+    fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
+        let fill = resolve_fill(self.author(styles), self.fill(styles));
+        let bar = resolve_bar(self.bar(styles), &fill, styles);
+        let elem = self.as_mut();
+        elem.push_fill(Smart::Custom(fill));
+        elem.push_bar(bar);
+        Ok(())
+    }
+}
+
+impl Show for Packed<DeletionElem> {
+    #[typst_macros::time(name = "deletion", span = self.span())]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let body = self.body().clone();
+        Ok(match DocumentElem::changes_in(styles) {
+            ChangeView::Markup => StrikeElem::new(body)
+                .pack()
+                .spanned(self.span())
+                .styled(TextElem::set_fill(resolve_fill(
+                    self.author(styles),
+                    self.fill(styles),
+                ))),
+            ChangeView::Final => Content::empty(),
+            ChangeView::Original => body,
+        })
+    }
+}
+
+/// Which version of a text with tracked changes to show.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum ChangeView {
+    /// Show insertions and deletions with their markup.
+    #[default]
+    Markup,
+    /// Show the text with all changes applied, without markup.
+    Final,
+    /// Show the text before any changes, without markup.
+    Original,
+}
+
+/// The colors in which the changes of different authors are shown.
+const AUTHOR_COLORS: [Color; 6] =
+    [Color::RED, Color::BLUE, Color::GREEN, Color::PURPLE, Color::ORANGE, Color::EASTERN];
+
+/// Determine the paint of a change.
+fn resolve_fill(author: Option<EcoString>, fill: Smart<Paint>) -> Paint {
+    fill.unwrap_or_else(|| {
+        let index = author.map_or(0, |author| {
+            (hash128(&author) % AUTHOR_COLORS.len() as u128) as usize
+        });
+        AUTHOR_COLORS[index].into()
+    })
+}
+
+/// Determine the stroke of a change's bar, using the change's paint if the
+/// stroke has none.
+fn resolve_bar(bar: Option<Stroke>, fill: &Paint, styles: StyleChain) -> Option<Stroke> {
+    if DocumentElem::changes_in(styles) != ChangeView::Markup {
+        return None;
+    }
+
+    let mut bar = bar?.resolve(styles).map(Length::from);
+    if bar.paint.is_auto() {
+        bar.paint = Smart::Custom(fill.clone());
+    }
+    Some(bar)
+}
+
+/// Draw change bars at the given horizontal position next to all changes in
+/// the frame that have one.
+pub(crate) fn draw_change_bars(frame: &mut Frame, x: Abs) {
+    let mut bars = vec![];
+    collect_change_bars(frame, Transform::identity(), &mut bars);
+    if bars.is_empty() {
+        return;
+    }
+
+    // Merge the bars of adjacent changes with the same stroke, so that
+    // dashed bars continue across multiple changes.
+    bars.sort_by_key(|bar| bar.0);
+    let mut merged: Vec<(Abs, Abs, FixedStroke)> = vec![];
+    for (top, bottom, stroke) in bars {
+        if let Some(last) = merged.last_mut() {
+            if last.2 == stroke && top <= last.1 {
+                last.1.set_max(bottom);
+                continue;
+            }
+        }
+        merged.push((top, bottom, stroke));
+    }
+
+    for (top, bottom, stroke) in merged {
+        let line = Geometry::Line(Point::with_y(bottom - top)).stroked(stroke);
+        frame.push(Point::new(x, top), FrameItem::Shape(line, Span::detached()));
+    }
+}
+
+/// Collect the vertical extents and strokes of all change bars in the frame.
+fn collect_change_bars(
+    frame: &Frame,
+    ts: Transform,
+    bars: &mut Vec<(Abs, Abs, FixedStroke)>,
+) {
+    for (pos, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => {
+                let ts = ts
+                    .pre_concat(Transform::translate(pos.x, pos.y))
+                    .pre_concat(group.transform);
+                collect_change_bars(&group.frame, ts, bars);
+            }
+            FrameItem::Meta(Meta::Elem(content), size) => {
+                let bar = if let Some(insertion) = content.to_packed::<InsertionElem>() {
+                    insertion.bar(StyleChain::default())
+                } else if let Some(deletion) = content.to_packed::<DeletionElem>() {
+                    deletion.bar(StyleChain::default())
+                } else {
+                    None
+                };
+
+                if let Some(bar) = bar {
+                    let top = pos.transform(ts).y;
+                    let bottom = Point::new(pos.x, pos.y + size.y).transform(ts).y;
+                    let stroke = bar.resolve(StyleChain::default()).unwrap_or_default();
+                    bars.push((top.min(bottom), top.max(bottom), stroke));
+                }
+            }
+            _ => {}
+        }
+    }
+}
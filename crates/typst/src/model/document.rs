@@ -1,13 +1,23 @@
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+
+use comemo::{Track, TrackedMut};
 use ecow::EcoString;
+use rayon::prelude::*;
 
-use crate::diag::{bail, SourceResult, StrResult};
-use crate::engine::Engine;
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::{Engine, Route};
+use crate::eval::Tracer;
 use crate::foundations::{
-    cast, elem, Args, Array, Construct, Content, Datetime, Packed, Smart, StyleChain,
-    StyledElem, Value,
+    cast, dict, elem, Args, Array, Construct, Content, Context, Datetime, Func, Label,
+    NativeElement, Packed, Repr, Smart, StyleChain, StyledElem, Value,
 };
-use crate::introspection::{Introspector, ManualPageCounter};
-use crate::layout::{LayoutRoot, Page, PageElem};
+use crate::introspection::{Introspector, Location, Locator, ManualPageCounter, Meta};
+use crate::layout::{Frame, FrameItem, LayoutRoot, Page, PageElem, Parity, Position};
+use crate::model::{ChangeView, Destination, HeadingElem};
+use crate::util::hash128;
+use crate::util::memory::Scope;
+use crate::visualize::IccProfile;
 
 /// The root element of a document and its metadata.
 ///
@@ -57,6 +67,58 @@ pub struct DocumentElem {
     #[ghost]
     pub date: Smart<Option<Datetime>>,
 
+    /// How to derive the anchors of labelled elements.
+    ///
+    /// Exporters give labelled headings and labelled elements that are linked
+    /// to within the document an anchor through which they can be linked to
+    /// from other documents, for example as a named destination in a PDF
+    /// (`file.pdf#intro`). Links to labels within the document lead to the
+    /// same anchors. By default, an anchor is just the label's name. If you
+    /// set this to a function, it receives each label and must return the
+    /// anchor as a string. No two labels may share an anchor.
+    ///
+    /// ```example
+    /// #set document(anchors: it => "sec-" + str(it))
+    ///
+    /// = Introduction <intro>
+    /// This heading can be linked to
+    /// as `#sec-intro` in the PDF.
+    /// ```
+    #[ghost]
+    pub anchors: Smart<Func>,
+
+    /// Which version of the text to show for tracked changes.
+    ///
+    /// By default, [insertions]($insertion) and [deletions]($deletion) are
+    /// shown with their markup. Set this to `{"final"}` to show the text with
+    /// all changes applied or to `{"original"}` to show the text before the
+    /// changes. This is useful to export a clean copy of a document from the
+    /// same source, for example with
+    /// `{set document(changes: sys.inputs.at("changes", default: "markup"))}`.
+    ///
+    /// ```example
+    /// #set document(changes: "final")
+    ///
+    /// Hello #deletion[World]
+    /// #insertion[Typst]!
+    /// ```
+    #[ghost]
+    pub changes: ChangeView,
+
+    /// An ICC profile that describes the intended output condition, like the
+    /// printing press and paper.
+    ///
+    /// When exporting to PDF, the profile is embedded as the document's output
+    /// intent. PDF viewers then use it to preview [CMYK]($color.cmyk) colors,
+    /// so that proofs match the actual press condition instead of a generic
+    /// one.
+    ///
+    /// ```typ
+    /// #set document(output-intent: read("fogra39.icc", encoding: none))
+    /// ```
+    #[ghost]
+    pub output_intent: Option<IccProfile>,
+
     /// The page runs.
     #[internal]
     #[variadic]
@@ -76,19 +138,16 @@ impl LayoutRoot for Packed<DocumentElem> {
         engine: &mut Engine,
         styles: StyleChain,
     ) -> SourceResult<Document> {
-        let mut pages = Vec::with_capacity(self.children().len());
-        let mut page_counter = ManualPageCounter::new();
-
+        // Collect the page runs along with their styles and the parity to
+        // which they must be extended.
+        let mut runs = Vec::with_capacity(self.children().len());
         let children = self.children();
         let mut iter = children.iter().peekable();
-
-        while let Some(mut child) = iter.next() {
-            let outer = styles;
-            let mut styles = styles;
-            if let Some(styled) = child.to_packed::<StyledElem>() {
-                child = &styled.child;
-                styles = outer.chain(&styled.styles);
-            }
+        while let Some(child) = iter.next() {
+            let (child, styles) = match child.to_packed::<StyledElem>() {
+                Some(styled) => (&styled.child, styles.chain(&styled.styles)),
+                None => (child, styles),
+            };
 
             if let Some(page) = child.to_packed::<PageElem>() {
                 let extend_to = iter.peek().and_then(|&next| {
@@ -98,24 +157,138 @@ impl LayoutRoot for Packed<DocumentElem> {
                         .to_packed::<PageElem>()?
                         .clear_to()?
                 });
-                let run = page.layout(engine, styles, &mut page_counter, extend_to)?;
-                pages.extend(run);
+                runs.push((page, styles, extend_to));
             } else {
                 bail!(child.span(), "unexpected document child");
             }
         }
 
+        // The bodies of the page runs don't depend on each other, so they can
+        // be laid out in parallel. Only the page numbers and marginals depend
+        // on the preceding runs. They are added afterwards, in order.
+        let bodies = if runs.len() > 1 { layout_bodies(engine, &runs) } else { vec![] };
+        let mut bodies = bodies.into_iter();
+
+        let mut pages = Vec::with_capacity(runs.len());
+        let mut page_counter = ManualPageCounter::new();
+        for (page, styles, extend_to) in runs {
+            let frames = match bodies.next() {
+                Some((frames, tracer)) => {
+                    // Forward what the run traced before finishing it, so that
+                    // the diagnostics are in the same order as when laying out
+                    // the runs one after another.
+                    tracer.merge_into(TrackedMut::reborrow_mut(&mut engine.tracer));
+                    frames?
+                }
+                None => page.layout_body(engine, styles)?,
+            };
+            let run =
+                page.finalize(engine, styles, frames, &mut page_counter, extend_to)?;
+
+            // Pages with content that is revealed in steps are laid out once
+            // and then repeated for each step.
+            for page in run {
+                let steps = page.frame.steps();
+                for step in 1..steps {
+                    let mut frame = page.frame.clone();
+                    frame.reveal(step);
+                    pages.push(Page { frame, repeated: step > 1, ..page.clone() });
+                }
+                pages.push(Page { repeated: steps > 1, ..page });
+            }
+        }
+
+        let anchors = match DocumentElem::anchors_in(styles) {
+            Smart::Auto => HashMap::new(),
+            Smart::Custom(func) => resolve_anchors(engine, &func)?,
+        };
+
         Ok(Document {
             pages,
+            anchors,
             title: DocumentElem::title_in(styles).map(|content| content.plain_text()),
             author: DocumentElem::author_in(styles).0,
             keywords: DocumentElem::keywords_in(styles).0,
             date: DocumentElem::date_in(styles),
+            output_intent: DocumentElem::output_intent_in(styles),
             introspector: Introspector::default(),
         })
     }
 }
 
+/// Compute the custom anchors of all labels in the document.
+fn resolve_anchors(
+    engine: &mut Engine,
+    func: &Func,
+) -> SourceResult<HashMap<Label, EcoString>> {
+    let span = func.span();
+    let mut anchors = HashMap::new();
+    let mut seen = HashMap::new();
+    for label in engine.introspector.labels() {
+        let anchor: EcoString =
+            func.call(engine, Context::none().track(), [label])?.cast().at(span)?;
+
+        if anchor.is_empty() {
+            bail!(span, "anchor for label `{}` is empty", label.repr());
+        }
+
+        if let Some(other) = seen.insert(anchor.clone(), label) {
+            bail!(
+                span,
+                "labels `{}` and `{}` have the same anchor",
+                other.repr(),
+                label.repr();
+                hint: "each label must map to a distinct anchor"
+            );
+        }
+
+        anchors.insert(label, anchor);
+    }
+    Ok(anchors)
+}
+
+/// Lay out the bodies of multiple page runs in parallel.
+///
+/// Returns the frames of each run together with what it traced.
+fn layout_bodies(
+    engine: &mut Engine,
+    runs: &[(&Packed<PageElem>, StyleChain, Option<Parity>)],
+) -> Vec<(SourceResult<Vec<Frame>>, Tracer)> {
+    // Each run gets its own locator and tracer since these can't be shared
+    // across threads. The locators are salted with a location from the parent
+    // locator to keep the locations of the runs distinct and deterministic.
+    let salts: Vec<_> = (0..runs.len())
+        .map(|i| hash128(&engine.locator.locate(i as u128)))
+        .collect();
+
+    // The runs' allocations count towards the budget of this compilation.
+    let scope = Scope::current();
+    let world = engine.world;
+    let introspector = engine.introspector;
+    let route = engine.route.track();
+    let inspected = engine.tracer.inspected_any();
+    runs.par_iter()
+        .zip(salts)
+        .map(|((page, styles, _), salt)| {
+            let _scope = scope.enter();
+            let mut locator = Locator::salted(salt);
+            let mut tracer = Tracer::new();
+            if let Some(span) = inspected {
+                tracer.inspect(span);
+            }
+            let mut engine = Engine {
+                world,
+                introspector,
+                route: Route::extend(route),
+                locator: &mut locator,
+                tracer: tracer.track_mut(),
+            };
+            let frames = page.layout_body(&mut engine, *styles);
+            (frames, tracer)
+        })
+        .collect()
+}
+
 /// A list of authors.
 #[derive(Debug, Default, Clone, PartialEq, Hash)]
 pub struct Author(Vec<EcoString>);
@@ -151,10 +324,204 @@ pub struct Document {
     pub keywords: Vec<EcoString>,
     /// The document's creation date.
     pub date: Smart<Option<Datetime>>,
+    /// Custom anchors for labels. Labels without an entry use their name.
+    pub anchors: HashMap<Label, EcoString>,
+    /// The ICC profile of the intended output condition.
+    pub output_intent: Option<IccProfile>,
     /// Provides the ability to execute queries on the document.
     pub introspector: Introspector,
 }
 
+impl Document {
+    /// The anchor through which the element with the given label can be
+    /// linked to.
+    pub fn anchor(&self, label: Label) -> EcoString {
+        self.anchors
+            .get(&label)
+            .cloned()
+            .unwrap_or_else(|| label.as_str().into())
+    }
+
+    /// The elements that have an anchor along with their labels, in document
+    /// order.
+    ///
+    /// These are the labelled headings and the labelled elements that a link
+    /// in the document leads to. If multiple elements share a label, only the
+    /// first one is considered.
+    pub fn anchored(&self) -> Vec<(Location, Label)> {
+        let mut linked = HashSet::new();
+        for page in &self.pages {
+            collect_link_targets(&page.frame, &mut linked);
+        }
+
+        let mut seen = HashSet::new();
+        self.introspector
+            .all()
+            .filter(|elem| elem.label().is_some_and(|label| seen.insert(label)))
+            .filter(|elem| {
+                elem.is::<HeadingElem>()
+                    || elem.location().is_some_and(|loc| linked.contains(&loc))
+            })
+            .filter_map(|elem| elem.location().zip(elem.label()))
+            .collect()
+    }
+
+    /// Resolve the document's outline.
+    ///
+    /// This is the tree of bookmarked headings that is also written into the
+    /// outline of exported PDFs.
+    pub fn outline(&self) -> Vec<OutlineNode> {
+        let mut tree: Vec<OutlineNode> = vec![];
+
+        // Stores the level of the topmost skipped ancestor of the next
+        // bookmarked heading. A skipped heading is a heading with
+        // 'bookmarked: false', that is, it is not added to the outline, and so
+        // is not in the tree. Therefore, its next descendant must be added at
+        // its level, which is enforced in the manner shown below.
+        let mut last_skipped_level = None;
+        let elements = self.introspector.query(&HeadingElem::elem().select());
+        for elem in elements.iter() {
+            let heading = elem.to_packed::<HeadingElem>().unwrap();
+            let level = heading.resolve_level(StyleChain::default());
+
+            // 'bookmarked' set to 'auto' falls back to the value of 'outlined'.
+            let bookmarked = heading
+                .bookmarked(StyleChain::default())
+                .unwrap_or_else(|| heading.outlined(StyleChain::default()));
+
+            if bookmarked {
+                let mut children = &mut tree;
+
+                // Descend the tree through the latest bookmarked heading of
+                // each level until either:
+                // - you reach a node whose children would be brothers of this
+                // heading (=> add the current heading as a child of this node);
+                // - you reach a node with no children (=> this heading probably
+                // skipped a few nesting levels in Typst, or one or more
+                // ancestors of this heading weren't bookmarked, so add it as a
+                // child of this node, which is its deepest bookmarked
+                // ancestor);
+                // - or, if the latest heading(s) was(/were) skipped
+                // ('bookmarked: false'), then stop if you reach a node whose
+                // children would be brothers of the latest skipped heading
+                // of lowest level (=> those skipped headings would be
+                // ancestors of the current heading, so add it as a 'brother'
+                // of the least deep skipped ancestor among them, as those
+                // ancestors weren't added to the bookmark tree, and the
+                // current heading should not be mistakenly added as a
+                // descendant of a brother of that ancestor.)
+                //
+                // That is, if you had a bookmarked heading of level N, a
+                // skipped heading of level N, a skipped heading of level N + 1,
+                // and then a bookmarked heading of level N + 2, that last one
+                // is bookmarked as a level N heading (taking the place of its
+                // topmost skipped ancestor), so that it is not mistakenly
+                // added as a descendant of the previous level N heading.
+                //
+                // In other words, a heading can be added to the bookmark tree
+                // at most as deep as its topmost skipped direct ancestor (if it
+                // exists), or at most as deep as its actual nesting level in
+                // Typst (not exceeding whichever is the most restrictive depth
+                // limit of those two).
+                while children.last().is_some_and(|last| {
+                    last_skipped_level.map_or(true, |l| last.level < l)
+                        && last.level < level
+                }) {
+                    children = &mut children.last_mut().unwrap().children;
+                }
+
+                // Since this heading was bookmarked, the next heading, if it
+                // is a child of this one, won't have a skipped direct ancestor
+                // (indeed, this heading would be its most direct ancestor, and
+                // wasn't skipped). Therefore, it can be added as a child of
+                // this one, if needed, following the usual rules listed above.
+                last_skipped_level = None;
+                children.push(OutlineNode::leaf(self, heading.clone(), level));
+            } else if last_skipped_level.map_or(true, |l| level < l) {
+                // Only the topmost / lowest-level skipped heading matters when
+                // you have consecutive skipped headings (since none of them
+                // are being added to the bookmark tree), hence the condition
+                // above. This ensures the next bookmarked heading will be
+                // placed at most as deep as its topmost skipped ancestors.
+                // Deeper ancestors do not matter as the nesting structure they
+                // create won't be visible in the outline.
+                last_skipped_level = Some(level);
+            }
+        }
+
+        tree
+    }
+}
+
+/// A heading in the resolved outline of a document.
+#[derive(Debug, Clone, Hash)]
+pub struct OutlineNode {
+    /// The heading element.
+    pub heading: Packed<HeadingElem>,
+    /// The heading's title as plain text.
+    pub title: EcoString,
+    /// The heading's displayed number as plain text, if it is numbered.
+    pub numbers: Option<EcoString>,
+    /// The heading's level.
+    pub level: NonZeroUsize,
+    /// Where the heading is located in the document.
+    pub position: Position,
+    /// The headings nested below this one.
+    pub children: Vec<OutlineNode>,
+}
+
+impl OutlineNode {
+    fn leaf(
+        document: &Document,
+        heading: Packed<HeadingElem>,
+        level: NonZeroUsize,
+    ) -> Self {
+        let position = document.introspector.position(heading.location().unwrap());
+        Self {
+            title: heading.body().plain_text().trim().into(),
+            numbers: heading
+                .numbers()
+                .cloned()
+                .flatten()
+                .map(|numbers| numbers.plain_text().trim().into()),
+            level,
+            position,
+            heading,
+            children: vec![],
+        }
+    }
+
+    /// The number of headings in this subtree, including this one.
+    pub fn count(&self) -> usize {
+        1 + self.children.iter().map(Self::count).sum::<usize>()
+    }
+}
+
+/// Collect the locations that links in the frame lead to.
+fn collect_link_targets(frame: &Frame, targets: &mut HashSet<Location>) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => collect_link_targets(&group.frame, targets),
+            FrameItem::Meta(Meta::Link(Destination::Location(loc)), _) => {
+                targets.insert(*loc);
+            }
+            _ => {}
+        }
+    }
+}
+
+cast! {
+    OutlineNode,
+    self => dict! {
+        "title" => self.title,
+        "numbers" => self.numbers,
+        "level" => self.level,
+        "label" => self.heading.label(),
+        "position" => self.position,
+        "children" => self.children,
+    }.into_value(),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
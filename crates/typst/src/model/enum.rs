@@ -12,7 +12,7 @@ use crate::layout::{
     Alignment, Axes, BlockElem, Cell, CellGrid, Em, Fragment, GridLayouter, HAlignment,
     LayoutMultiple, Length, Regions, Sizing, Spacing, VAlignment,
 };
-use crate::model::{Numbering, NumberingPattern, ParElem};
+use crate::model::{reveal_with, Numbering, NumberingPattern, ParElem};
 use crate::text::TextElem;
 
 /// A numbered list.
@@ -267,7 +267,7 @@ impl LayoutMultiple for Packed<EnumElem> {
                 resolved.aligned(number_align).styled(TextElem::set_overhang(false));
 
             cells.push(Cell::from(Content::empty()));
-            cells.push(Cell::from(resolved));
+            cells.push(Cell::from(reveal_with(resolved, item.body())));
             cells.push(Cell::from(Content::empty()));
             cells.push(Cell::from(
                 item.body().clone().styled(EnumElem::set_parents(smallvec![number])),
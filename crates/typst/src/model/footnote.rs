@@ -5,7 +5,7 @@ use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, elem, scope, Content, Label, NativeElement, Packed, Show, ShowSet, Smart,
-    StyleChain, Styles,
+    StyleChain, Styles, Synthesize,
 };
 use crate::introspection::{Count, Counter, CounterUpdate, Locatable, Location};
 use crate::layout::{Abs, Em, HElem, Length, Ratio};
@@ -50,7 +50,7 @@ use crate::visualize::{LineElem, Stroke};
 /// apply to the footnote's content. See [here][issue] for more information.
 ///
 /// [issue]: https://github.com/typst/typst/issues/1467#issuecomment-1588799440
-#[elem(scope, Locatable, Show, Count)]
+#[elem(scope, Locatable, Synthesize, Show, Count)]
 pub struct FootnoteElem {
     /// How to number footnotes.
     ///
@@ -74,6 +74,13 @@ pub struct FootnoteElem {
     /// footnote this one should point to.
     #[required]
     pub body: FootnoteBody,
+
+    /// Whether the footnote's entry is listed below the surrounding table
+    /// instead of at the bottom of the page.
+    #[internal]
+    #[parse(None)]
+    #[default(false)]
+    pub table_note: bool,
 }
 
 #[scope]
@@ -123,6 +130,14 @@ impl Packed<FootnoteElem> {
     }
 }
 
+impl Synthesize for Packed<FootnoteElem> {
+    fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
+        let table_note = self.table_note(styles);
+        self.push_table_note(table_note);
+        Ok(())
+    }
+}
+
 impl Show for Packed<FootnoteElem> {
     #[typst_macros::time(name = "footnote", span = self.span())]
     fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
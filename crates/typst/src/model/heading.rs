@@ -1,14 +1,18 @@
 use std::num::NonZeroUsize;
 
-use crate::diag::SourceResult;
+use comemo::Tracked;
+use smallvec::smallvec;
+
+use crate::diag::{At, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, Content, NativeElement, Packed, Show, ShowSet, Smart, StyleChain, Styles,
-    Synthesize,
+    elem, func, scope, Content, Context, IntoValue, NativeElement, Packed, Show, ShowSet,
+    Smart, StyleChain, Styles, Synthesize,
 };
-use crate::introspection::{Count, Counter, CounterUpdate, Locatable};
+use crate::introspection::{Count, Counter, CounterUpdate, Locatable, PageMarks};
 use crate::layout::{BlockElem, Em, HElem, VElem};
 use crate::model::{Numbering, Outlinable, Refable, Supplement};
+use crate::syntax::Span;
 use crate::text::{FontWeight, Lang, LocalName, Region, SpaceElem, TextElem, TextSize};
 use crate::util::{option_eq, NonZeroExt};
 
@@ -44,7 +48,9 @@ use crate::util::{option_eq, NonZeroExt};
 /// one or multiple equals signs, followed by a space. The number of equals
 /// signs determines the heading's logical nesting depth. The `{offset}` field
 /// can be set to configure the starting depth.
-#[elem(Locatable, Synthesize, Count, Show, ShowSet, LocalName, Refable, Outlinable)]
+#[elem(
+    scope, Locatable, Synthesize, Count, Show, ShowSet, LocalName, Refable, Outlinable
+)]
 pub struct HeadingElem {
     /// The absolute nesting depth of the heading, starting from one. If set
     /// to `{auto}`, it is computed from `{offset + depth}`.
@@ -166,6 +172,69 @@ pub struct HeadingElem {
     /// The heading's title.
     #[required]
     pub body: Content,
+
+    /// The heading's displayed number, if it is numbered.
+    #[internal]
+    #[synthesized]
+    pub numbers: Option<Content>,
+}
+
+#[scope]
+impl HeadingElem {
+    /// Retrieves the heading that is in effect on the current page.
+    ///
+    /// This is the first heading on the current page or, if the page has no
+    /// headings, the last heading on any of the preceding pages. This makes the
+    /// function suitable for running headers: A page on which a new chapter
+    /// starts shows that chapter in its header. It is a shorthand for the
+    /// `first` or `previous` heading found by [`page-marks`]($page-marks).
+    ///
+    /// Returns `{none}` if there is no such heading.
+    ///
+    /// ```example
+    /// #set page(
+    ///   height: 120pt,
+    ///   header: context {
+    ///     let chapter = heading.current(level: 1)
+    ///     if chapter != none [
+    ///       _#chapter.body;_
+    ///       #h(1fr)
+    ///       #counter(page).display("1 of 1", both: true)
+    ///     ]
+    ///   },
+    /// )
+    ///
+    /// = Introduction
+    /// #lorem(15)
+    ///
+    /// = Background
+    /// #lorem(15)
+    /// ```
+    #[func(contextual)]
+    pub fn current(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// If given, only headings of this level are considered.
+        #[named]
+        #[default]
+        level: Option<NonZeroUsize>,
+    ) -> SourceResult<Option<Content>> {
+        let page = engine.introspector.page(context.location().at(span)?);
+        let elem = HeadingElem::elem();
+        let selector = match level {
+            Some(level) => {
+                let id = elem.field_id("level").unwrap();
+                elem.where_(smallvec![(id, level.into_value())])
+            }
+            None => elem.select(),
+        };
+        let marks = PageMarks::new(engine.introspector, page, &selector);
+        Ok(marks.first.or(marks.previous))
+    }
 }
 
 impl HeadingElem {
@@ -191,9 +260,20 @@ impl Synthesize for Packed<HeadingElem> {
             }
         };
 
+        // The location is missing during pre-synthesis, which happens before
+        // the element is located.
+        let numbers = match ((**self).numbering(styles).as_ref(), self.location()) {
+            (Some(numbering), Some(loc)) => Some(
+                Counter::of(HeadingElem::elem())
+                    .display_at_loc(engine, loc, styles, numbering)?,
+            ),
+            _ => None,
+        };
+
         let elem = self.as_mut();
         elem.push_level(Smart::Custom(elem.resolve_level(styles)));
         elem.push_supplement(Smart::Custom(Some(Supplement::Content(supplement))));
+        elem.push_numbers(numbers);
         Ok(())
     }
 }
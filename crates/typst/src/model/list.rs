@@ -10,7 +10,7 @@ use crate::layout::{
     Axes, BlockElem, Cell, CellGrid, Em, Fragment, GridLayouter, HAlignment,
     LayoutMultiple, Length, Regions, Sizing, Spacing, VAlignment,
 };
-use crate::model::ParElem;
+use crate::model::{reveal_with, ParElem};
 use crate::text::TextElem;
 
 /// A bullet list.
@@ -164,7 +164,7 @@ impl LayoutMultiple for Packed<ListElem> {
         let mut cells = vec![];
         for item in self.children() {
             cells.push(Cell::from(Content::empty()));
-            cells.push(Cell::from(marker.clone()));
+            cells.push(Cell::from(reveal_with(marker.clone(), item.body())));
             cells.push(Cell::from(Content::empty()));
             cells.push(Cell::from(
                 item.body().clone().styled(ListElem::set_depth(Depth(1))),
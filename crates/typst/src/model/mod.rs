@@ -1,6 +1,8 @@
 //! Structuring elements that define the document model.
 
 mod bibliography;
+mod calendar;
+mod change;
 mod cite;
 mod document;
 mod emph;
@@ -17,11 +19,14 @@ mod outline;
 mod par;
 mod quote;
 mod reference;
+mod slide;
 mod strong;
 mod table;
 mod terms;
 
 pub use self::bibliography::*;
+pub use self::calendar::*;
+pub use self::change::*;
 pub use self::cite::*;
 pub use self::document::*;
 pub use self::emph::*;
@@ -36,6 +41,7 @@ pub use self::outline::*;
 pub use self::par::*;
 pub use self::quote::*;
 pub use self::reference::*;
+pub use self::slide::*;
 pub use self::strong::*;
 pub use self::table::*;
 pub use self::terms::*;
@@ -63,6 +69,7 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<QuoteElem>();
     global.define_elem::<CiteElem>();
     global.define_elem::<BibliographyElem>();
+    global.define_elem::<CalendarElem>();
     global.define_elem::<EnumElem>();
     global.define_elem::<ListElem>();
     global.define_elem::<ParbreakElem>();
@@ -71,5 +78,9 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<TermsElem>();
     global.define_elem::<EmphElem>();
     global.define_elem::<StrongElem>();
+    global.define_elem::<InsertionElem>();
+    global.define_elem::<DeletionElem>();
+    global.define_elem::<SlideElem>();
+    global.define_elem::<PauseElem>();
     global.define_func::<numbering>();
 }
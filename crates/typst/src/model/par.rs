@@ -96,6 +96,29 @@ pub struct ParElem {
     #[resolve]
     pub hanging_indent: Length,
 
+    /// Lays out Chinese and Japanese text on a fixed character grid, like on
+    /// manuscript paper (genkō yōshi).
+    ///
+    /// When set to a length, every CJK character occupies exactly one cell of
+    /// that width and each line holds a whole number of cells. Runs of other
+    /// text, like Latin words or numbers, are centered in as many cells as
+    /// they need. Punctuation follows the basic rules of JIS X 4051:
+    /// consecutive punctuation marks are compressed to half a cell and a
+    /// comma or full stop at the end of a line hangs into the margin instead
+    /// of being pushed onto the next line. Justification and the automatic
+    /// [spacing between CJK and Latin text]($text.cjk-latin-spacing) are
+    /// disabled on the grid.
+    ///
+    /// ```example
+    /// #set page(width: 120pt)
+    /// #set text(lang: "ja", font: "Noto Serif CJK SC")
+    /// #set par(char-grid: 1.2em)
+    /// 吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。
+    /// ```
+    #[ghost]
+    #[resolve]
+    pub char_grid: Option<Length>,
+
     /// The contents of the paragraph.
     #[external]
     #[required]
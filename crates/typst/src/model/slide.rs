@@ -0,0 +1,161 @@
+use std::num::NonZeroUsize;
+
+use smallvec::{smallvec, SmallVec};
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, Content, NativeElement, Packed, SequenceElem, Show, StyleChain, StyledElem,
+};
+use crate::introspection::{Meta, MetaElem};
+use crate::layout::PagebreakElem;
+use crate::model::{EnumItem, ListItem};
+
+/// A slide of a presentation.
+///
+/// A slide is laid out on its own page. Its content can be revealed in
+/// multiple steps by inserting [pauses]($pause) into its body: The slide's
+/// page is then repeated once per step, with all content that comes after a
+/// pause hidden until its step is reached.
+///
+/// Since hidden content still takes up space, the slide's layout is the same
+/// across all of its steps. This allows the document to be laid out just once
+/// instead of once per step. As a consequence, counters, states, and queries
+/// behave as if each slide was laid out a single time: A heading on a slide
+/// is numbered once, [`counter(page)`]($counter) stays the same for all steps
+/// of a slide, and [`locate`]($locate) resolves to the first page of the
+/// slide.
+///
+/// # Example
+/// ```typ
+/// #set page(paper: "presentation-16-9")
+///
+/// #slide[
+///   = Agenda
+///   Things we will talk about.
+///   #pause()
+///   - The problem
+///   - Our solution
+/// ]
+///
+/// #slide(incremental: true)[
+///   = The problem
+///   - Compiling takes long
+///   - Overlays are slow
+/// ]
+/// ```
+#[elem(Show)]
+pub struct SlideElem {
+    /// Whether to reveal each top-level list and enumeration item in its own
+    /// step.
+    ///
+    /// ```typ
+    /// #set slide(incremental: true)
+    /// ```
+    #[default(false)]
+    pub incremental: bool,
+
+    /// The content of the slide.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<SlideElem> {
+    #[typst_macros::time(name = "slide", span = self.span())]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let mut steps = Steps {
+            step: 1,
+            incremental: self.incremental(styles),
+            items: false,
+        };
+        let body = steps.assign(self.body());
+        let pagebreak = PagebreakElem::new().with_weak(true).pack();
+        Ok(pagebreak.clone() + body + pagebreak)
+    }
+}
+
+/// Delays the remaining content of a slide to the next step.
+///
+/// Pauses only take effect at the top level of a [slide's]($slide) body and
+/// within content that is styled there. Outside of slides, they have no
+/// effect.
+///
+/// # Example
+/// ```typ
+/// #slide[
+///   First, we see this.
+///   #pause()
+///   And then this.
+/// ]
+/// ```
+#[elem(Show)]
+pub struct PauseElem {}
+
+impl Show for Packed<PauseElem> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// Assigns the steps at which the parts of a slide's body are revealed.
+struct Steps {
+    /// The current step, starting at one.
+    step: usize,
+    /// Whether list and enumeration items are revealed one by one.
+    incremental: bool,
+    /// Whether an item was already encountered.
+    items: bool,
+}
+
+impl Steps {
+    /// Remove the pauses from the content and attach the steps at which its
+    /// parts are revealed.
+    fn assign(&mut self, content: &Content) -> Content {
+        if content.is::<PauseElem>() {
+            self.step += 1;
+            return Content::empty();
+        }
+
+        if let Some(sequence) = content.to_packed::<SequenceElem>() {
+            return Content::sequence(
+                sequence.children.iter().map(|child| self.assign(child)),
+            )
+            .spanned(content.span());
+        }
+
+        if let Some(styled) = content.to_packed::<StyledElem>() {
+            return self.assign(&styled.child).styled_with_map(styled.styles.clone());
+        }
+
+        if self.incremental && (content.is::<ListItem>() || content.is::<EnumItem>()) {
+            if self.items {
+                self.step += 1;
+            }
+            self.items = true;
+        }
+
+        match NonZeroUsize::new(self.step) {
+            Some(step) if step.get() > 1 => content
+                .clone()
+                .styled(MetaElem::set_data(smallvec![Meta::Reveal(step)])),
+            _ => content.clone(),
+        }
+    }
+}
+
+/// Reveals a list or enumeration marker in the same step as its item's body.
+///
+/// The steps of incrementally revealed items are only part of the items'
+/// local styles, which don't apply to the markers.
+pub(crate) fn reveal_with(marker: Content, body: &Content) -> Content {
+    let Some(styled) = body.to_packed::<StyledElem>() else { return marker };
+    let reveals: SmallVec<[Meta; 1]> = MetaElem::data_in(StyleChain::new(&styled.styles))
+        .into_iter()
+        .filter(|meta| matches!(meta, Meta::Reveal(_)))
+        .collect();
+    if reveals.is_empty() {
+        marker
+    } else {
+        marker.styled(MetaElem::set_data(reveals))
+    }
+}
@@ -3,23 +3,37 @@ use std::sync::Arc;
 
 use ecow::{eco_format, EcoString};
 
-use crate::diag::{bail, SourceResult, StrResult, Trace, Tracepoint};
+use comemo::Tracked;
+
+use crate::diag::{bail, At, SourceResult, StrResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Content, Fold, Packed, Show, Smart, StyleChain,
+    cast, dict, elem, func, repr, scope, Arg, Args, Array, Cast, Construct, Content,
+    Context, Dict, Fold, Func, IntoValue, NativeElement, Packed, Repr, Show, Smart, Str,
+    StyleChain, Value,
 };
 use crate::layout::{
-    show_grid_cell, Abs, Alignment, Axes, Cell, CellGrid, Celled, Dir, Fragment,
-    GridCell, GridFooter, GridHLine, GridHeader, GridLayouter, GridVLine, LayoutMultiple,
-    Length, LinePosition, OuterHAlignment, OuterVAlignment, Regions, Rel, ResolvableCell,
-    ResolvableGridChild, ResolvableGridItem, Sides, TrackSizings,
+    find_footnotes, show_grid_cell, Abs, Alignment, Angle, Axes, Cell, CellGrid, Celled,
+    Dir, Fit, Fragment, Frame, GridCell, GridFooter, GridHLine, GridHeader, GridLayouter,
+    GridVLine, LayoutMultiple, Length, LinePosition, OuterHAlignment, OuterVAlignment,
+    Point, Regions, Rel, ResolvableCell, ResolvableGridChild, ResolvableGridItem, Sides,
+    Size, Sizing, TrackSizings,
 };
-use crate::model::Figurable;
-use crate::syntax::Span;
+use crate::model::{Figurable, FootnoteElem, FootnoteEntry};
+use crate::syntax::{Span, Spanned};
 use crate::text::{Lang, LocalName, Region, TextElem};
 use crate::util::NonZeroExt;
 use crate::visualize::{Paint, Stroke};
 
+/// Where the entries of footnotes in a table are listed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum TableFootnotes {
+    /// At the bottom of the page, like other footnotes.
+    Page,
+    /// Right below the table.
+    Table,
+}
+
 /// A table of items.
 ///
 /// Tables are used to arrange content in cells. Cells can contain arbitrary
@@ -235,6 +249,24 @@ pub struct TableElem {
     #[default(Celled::Value(Sides::splat(Some(Abs::pt(5.0).into()))))]
     pub inset: Celled<Sides<Option<Rel<Length>>>>,
 
+    /// Where to list the entries of footnotes within the table.
+    ///
+    /// By default, they are listed at the bottom of the page like any other
+    /// footnote. With `{"table"}`, they are instead listed as table notes right
+    /// below the table. If the table breaks across pages, all of its notes
+    /// follow its last part.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 2,
+    ///   footnotes: "table",
+    ///   [Speed], [12 km/h #footnote[Measured on flat ground.]],
+    ///   [Range], [40 km #footnote[With a full battery.]],
+    /// )
+    /// ```
+    #[default(TableFootnotes::Page)]
+    pub footnotes: TableFootnotes,
+
     /// The contents of the table cells, plus any extra table lines specified
     /// with the [`table.hline`]($table.hline) and
     /// [`table.vline`]($table.vline) elements.
@@ -258,6 +290,145 @@ impl TableElem {
 
     #[elem]
     type TableFooter;
+
+    /// Creates a table from structured data, such as the result of decoding a
+    /// [JSON]($json) or [CSV]($csv) file.
+    ///
+    /// Each row becomes one row of the table and each column specification
+    /// determines which value of a row is shown in a column and how. All
+    /// further arguments are passed on to the [`table`]($table) function.
+    ///
+    /// ```example
+    /// #let data = (
+    ///   (name: "Apples", price: 1.5, stock: 120),
+    ///   (name: "Pears", price: 2.25, stock: 80),
+    ///   (name: "Plums", price: 3, stock: 0),
+    /// )
+    ///
+    /// #table.from-data(
+    ///   data,
+    ///   columns: (
+    ///     (key: "name", title: [*Fruit*]),
+    ///     (key: "price", title: [*Price*], align: right, format: "{:.2} €"),
+    ///     (key: "stock", title: [*In stock*], align: center,
+    ///      format: n => if n == 0 [--] else [#n]),
+    ///   ),
+    ///   stroke: none,
+    /// )
+    /// ```
+    #[func]
+    pub fn from_data(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The remaining arguments, which are passed on to the table.
+        args: &mut Args,
+        /// The rows of the table.
+        ///
+        /// Each row is either a dictionary, whose values are selected with
+        /// string keys, or an array, whose values are selected with integer
+        /// keys.
+        rows: Spanned<Array>,
+        /// The columns of the table.
+        ///
+        /// Each column is either just a key or a dictionary with the
+        /// following entries:
+        /// - `key`: The key of the column's values in each row.
+        /// - `title`: The title of the column in the header. Defaults to the
+        ///   key.
+        /// - `align`: How to [align]($table.cell.align) the column's cells.
+        /// - `format`: How to format the column's values. Either a function
+        ///   that receives the value and returns content or a string template
+        ///   in which `{}` is replaced with the value. Numbers can be rounded
+        ///   to a fixed number of decimal places with `{:.2}`. By default,
+        ///   values are displayed as usual.
+        /// - `width`: The [size]($table.columns) of the column. Defaults to
+        ///   `{auto}`.
+        ///
+        /// When set to `{auto}` and the rows are dictionaries, one column is
+        /// created for each key of the first row. When set to `{auto}` and the
+        /// rows are arrays, the first row is used as the titles of the columns
+        /// if there is a header.
+        #[named]
+        #[default]
+        columns: Smart<Vec<TableColumn>>,
+        /// Whether to generate a header with the titles of the columns.
+        #[named]
+        #[default(true)]
+        header: bool,
+    ) -> SourceResult<Content> {
+        let span = rows.span;
+        let mut rows = rows.v.into_iter().peekable();
+        let columns = match columns {
+            Smart::Custom(columns) => columns,
+            Smart::Auto => match rows.peek() {
+                None => vec![],
+                Some(Value::Dict(dict)) => {
+                    dict.iter().map(|(key, _)| TableColumn::new(key.clone())).collect()
+                }
+                Some(Value::Array(array)) if header => {
+                    let array = array.clone();
+                    rows.next();
+                    array
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, title)| TableColumn {
+                            title: title.display(),
+                            ..TableColumn::new(i as i64)
+                        })
+                        .collect()
+                }
+                Some(Value::Array(array)) => {
+                    (0..array.len()).map(|i| TableColumn::new(i as i64)).collect()
+                }
+                Some(other) => {
+                    bail!(span, "expected array or dictionary, found {}", other.ty())
+                }
+            },
+        };
+
+        let cell = |body: Content, column: &TableColumn| {
+            let cell = TableCell::new(body).with_align(column.align);
+            TableItem::Cell(Packed::new(cell).spanned(span))
+        };
+
+        let mut children = vec![];
+        if header {
+            let titles = columns.iter().map(|column| cell(column.title.clone(), column));
+            let header = TableHeader::new(titles.collect()).pack().spanned(span);
+            children.push(header.into_value());
+        }
+
+        for row in rows {
+            for column in &columns {
+                let value = column.key.select(&row).at(span)?;
+                let body = match (&column.format, value) {
+                    (_, None) => Content::empty(),
+                    (None, Some(value)) => value.display(),
+                    (Some(TableFormat::Func(func)), Some(value)) => {
+                        func.call(engine, context, [value])?.display()
+                    }
+                    (Some(TableFormat::Template(template)), Some(value)) => {
+                        TextElem::packed(format_template(template, &value).at(span)?)
+                    }
+                };
+                children.push(cell(body, column).into_value());
+            }
+        }
+
+        let widths = columns.iter().map(|column| column.width).collect();
+        args.items.push(Arg {
+            span,
+            name: Some("columns".into()),
+            value: Spanned::new(TrackSizings(widths).into_value(), span),
+        });
+        for child in children {
+            args.push(span, child);
+        }
+
+        <TableElem as Construct>::construct(engine, args)
+    }
 }
 
 impl LayoutMultiple for Packed<TableElem> {
@@ -276,6 +447,13 @@ impl LayoutMultiple for Packed<TableElem> {
         let row_gutter = self.row_gutter(styles);
         let fill = self.fill(styles);
         let stroke = self.stroke(styles);
+        let footnotes = self.footnotes(styles);
+
+        // Mark footnotes within the table as table notes, so that the flow
+        // leaves them to us.
+        let local =
+            FootnoteElem::set_table_note(footnotes == TableFootnotes::Table).wrap();
+        let styles = styles.chain(&local);
 
         let tracks = Axes::new(columns.0.as_slice(), rows.0.as_slice());
         let gutter = Axes::new(column_gutter.0.as_slice(), row_gutter.0.as_slice());
@@ -312,8 +490,78 @@ impl LayoutMultiple for Packed<TableElem> {
         .trace(engine.world, tracepoint, self.span())?;
 
         let layouter = GridLayouter::new(&grid, regions, styles, self.span());
-        layouter.layout(engine)
+        let fragment = layouter.layout(engine)?;
+        if footnotes == TableFootnotes::Table {
+            return layout_table_notes(engine, styles, regions, fragment);
+        }
+
+        Ok(fragment)
+    }
+}
+
+/// Lists the entries of all table notes in the fragment below its last frame.
+///
+/// Entries that don't fit into the region of the last frame move on to frames
+/// in the following regions.
+fn layout_table_notes(
+    engine: &mut Engine,
+    styles: StyleChain,
+    regions: Regions,
+    fragment: Fragment,
+) -> SourceResult<Fragment> {
+    let mut notes = Vec::new();
+    for frame in fragment.iter() {
+        find_footnotes(&mut notes, frame, true);
+    }
+
+    if notes.is_empty() {
+        return Ok(fragment);
     }
+
+    let gap = FootnoteEntry::gap_in(styles);
+    let pod = Regions::one(Size::new(regions.size.x, Abs::inf()), Axes::splat(false));
+
+    // Nested notes are found while laying out the entries and processed in
+    // the same loop.
+    let mut entries = vec![];
+    let mut k = 0;
+    while k < notes.len() {
+        if !notes[k].is_ref() {
+            let frame = FootnoteEntry::new(notes[k].clone())
+                .pack()
+                .layout(engine, styles, pod)?
+                .into_frame();
+            find_footnotes(&mut notes, &frame, true);
+            entries.push(frame);
+        }
+        k += 1;
+    }
+
+    // The heights of the region of the last frame and the ones after it.
+    let mut heights = regions.iter().skip(fragment.len() - 1).map(|size| size.y);
+    let mut height = heights.next();
+    let mut frames = fragment.into_frames();
+    let mut last = frames.pop().unwrap();
+
+    for entry in entries {
+        let mut y = last.height() + gap;
+        if !last.is_empty() && !height.map_or(true, |h| h.fits(y + entry.height())) {
+            // If there is no further region, the entries overflow.
+            if let Some(next) = heights.next() {
+                let width = last.width();
+                frames
+                    .push(std::mem::replace(&mut last, Frame::soft(Size::with_x(width))));
+                height = Some(next);
+                y = Abs::zero();
+            }
+        }
+
+        last.size_mut().y = y + entry.height();
+        last.push_frame(Point::with_y(y), entry);
+    }
+
+    frames.push(last);
+    Ok(Fragment::frames(frames))
 }
 
 impl LocalName for Packed<TableElem> {
@@ -805,6 +1053,41 @@ pub struct TableCell {
     /// unbreakable, while a cell spanning at least one `{auto}`-sized row is
     /// breakable.
     pub breakable: Smart<bool>,
+
+    /// How much to rotate the cell's content.
+    ///
+    /// The cell is sized to fit the rotated content, so that vertical header
+    /// cells in wide tables take up just as much space as they need. The
+    /// rotated content may still span multiple lines.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 4,
+    ///   align: bottom,
+    ///   [],
+    ///   ..([Red], [Green], [Blue]).map(table.cell.with(rotate: -90deg)),
+    ///   [Apple], [Yes], [Yes], [No],
+    /// )
+    /// ```
+    #[default(Angle::zero())]
+    pub rotate: Angle,
+
+    /// What to do if the cell's content doesn't fit into it.
+    ///
+    /// With `{"shrink"}`, the cell's content is scaled down until it fits
+    /// into the cell, which is most useful for rows with a fixed height. See
+    /// the [block's `fit`]($block.fit) for more details.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: (60pt, 60pt),
+    ///   rows: 20pt,
+    ///   [Overflowing content],
+    ///   table.cell(fit: "shrink")[Shrunk content],
+    /// )
+    /// ```
+    #[default(Fit::Overflow)]
+    pub fit: Fit,
 }
 
 cast! {
@@ -913,7 +1196,13 @@ impl ResolvableCell for Packed<TableCell> {
 
 impl Show for Packed<TableCell> {
     fn show(&self, _engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
-        show_grid_cell(self.body().clone(), self.inset(styles), self.align(styles))
+        show_grid_cell(
+            self.body().clone(),
+            self.inset(styles),
+            self.align(styles),
+            self.rotate(styles),
+            self.fit(styles),
+        )
     }
 }
 
@@ -923,3 +1212,169 @@ impl From<Content> for TableCell {
         value.unpack::<Self>().unwrap_or_else(Self::new)
     }
 }
+
+/// A column of a table created with [`table.from-data`]($table.from-data).
+pub struct TableColumn {
+    /// Selects the column's value in each row.
+    key: TableKey,
+    /// The title of the column in the header.
+    title: Content,
+    /// How to align the column's cells.
+    align: Smart<Alignment>,
+    /// How to format the column's values.
+    format: Option<TableFormat>,
+    /// The size of the column.
+    width: Sizing,
+}
+
+impl TableColumn {
+    /// Create a column with the given key, which also serves as its title.
+    fn new(key: impl Into<TableKey>) -> Self {
+        let key = key.into();
+        let title = match &key {
+            TableKey::Name(name) => TextElem::packed(name.clone()),
+            TableKey::Index(index) => TextElem::packed(eco_format!("{index}")),
+        };
+        Self {
+            key,
+            title,
+            align: Smart::Auto,
+            format: None,
+            width: Sizing::Auto,
+        }
+    }
+}
+
+cast! {
+    TableColumn,
+    self => dict! {
+        "key" => self.key,
+        "title" => self.title,
+        "align" => self.align,
+        "format" => self.format,
+        "width" => self.width,
+    }.into_value(),
+    key: TableKey => Self::new(key),
+    mut dict: Dict => {
+        let key: TableKey = dict.take("key")?.cast()?;
+        let mut column = Self::new(key);
+        if let Ok(title) = dict.take("title") {
+            column.title = title.display();
+        }
+        if let Ok(align) = dict.take("align") {
+            column.align = align.cast()?;
+        }
+        if let Ok(format) = dict.take("format") {
+            column.format = format.cast()?;
+        }
+        if let Ok(width) = dict.take("width") {
+            column.width = width.cast()?;
+        }
+        dict.finish(&["key", "title", "align", "format", "width"])?;
+        column
+    },
+}
+
+/// Selects a value in a row of data.
+pub enum TableKey {
+    /// The key of a value in a dictionary.
+    Name(Str),
+    /// The index of a value in an array.
+    Index(i64),
+}
+
+impl TableKey {
+    /// Select the value from a row. Missing values are `None`.
+    fn select(&self, row: &Value) -> StrResult<Option<Value>> {
+        Ok(match (self, row) {
+            (Self::Name(name), Value::Dict(dict)) => dict.get(name).ok().cloned(),
+            (Self::Index(index), Value::Array(array)) => array.at(*index, None).ok(),
+            (Self::Name(_), Value::Array(_)) => {
+                bail!("cannot select a value from an array row with a string key")
+            }
+            (Self::Index(_), Value::Dict(_)) => {
+                bail!("cannot select a value from a dictionary row with an integer key")
+            }
+            (_, other) => bail!("expected array or dictionary, found {}", other.ty()),
+        })
+    }
+}
+
+impl From<Str> for TableKey {
+    fn from(name: Str) -> Self {
+        Self::Name(name)
+    }
+}
+
+impl From<i64> for TableKey {
+    fn from(index: i64) -> Self {
+        Self::Index(index)
+    }
+}
+
+cast! {
+    TableKey,
+    self => match self {
+        Self::Name(name) => name.into_value(),
+        Self::Index(index) => index.into_value(),
+    },
+    name: Str => Self::Name(name),
+    index: i64 => Self::Index(index),
+}
+
+/// How to format the values of a column.
+pub enum TableFormat {
+    /// A function that turns a value into content.
+    Func(Func),
+    /// A template in which the value is inserted.
+    Template(Str),
+}
+
+cast! {
+    TableFormat,
+    self => match self {
+        Self::Func(func) => func.into_value(),
+        Self::Template(template) => template.into_value(),
+    },
+    func: Func => Self::Func(func),
+    template: Str => Self::Template(template),
+}
+
+/// Insert a value into a format template.
+fn format_template(template: &str, value: &Value) -> StrResult<EcoString> {
+    let mut out = EcoString::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            bail!("unclosed placeholder in format template");
+        };
+        let spec = &rest[start + 1..start + end];
+        let text = match (spec.strip_prefix(":."), value) {
+            (None, _) if !spec.is_empty() => {
+                bail!("invalid placeholder in format template: {}", spec.repr())
+            }
+            (None, Value::Str(s)) => s.as_str().into(),
+            (None, Value::Int(i)) => repr::format_int_with_base(*i, 10),
+            (None, Value::Float(f)) => repr::display_float(*f),
+            (Some(digits), Value::Int(_) | Value::Float(_)) => {
+                let Ok(digits) = digits.parse::<usize>() else {
+                    bail!("invalid placeholder in format template: {}", spec.repr())
+                };
+                let number = value.clone().cast::<f64>()?;
+                let text = eco_format!("{:.*}", digits, number.abs());
+                if number < 0.0 && text.chars().any(|c| matches!(c, '1'..='9')) {
+                    eco_format!("{}{}", repr::MINUS_SIGN, text)
+                } else {
+                    text
+                }
+            }
+            (None, Value::None) => EcoString::new(),
+            _ => bail!("cannot format {} with a template", value.ty()),
+        };
+        out.push_str(&text);
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
@@ -290,14 +290,20 @@ fn show_regex(
 
     let text = target.text();
 
-    for m in regex.find_iter(target.text()) {
+    for caps in regex.captures_iter(target.text()) {
+        let m = caps.get(0).unwrap();
         let start = m.start();
         if cursor < start {
             result.push(make(&text[cursor..start]));
         }
 
-        let piece = make(m.as_str());
-        let transformed = recipe.apply(engine, context, piece)?;
+        let mut piece = target.clone();
+        piece.push_text(m.as_str().into());
+        piece.push_captures(
+            caps.iter().skip(1).map(|c| c.map(|c| c.as_str().into())).collect(),
+        );
+
+        let transformed = recipe.apply(engine, context, piece.pack())?;
         result.push(transformed);
         cursor = m.end();
     }
@@ -39,13 +39,14 @@ use crate::diag::{bail, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::Packed;
 use crate::foundations::{
-    cast, category, elem, Args, Array, Cast, Category, Construct, Content, Dict, Fold,
-    NativeElement, Never, PlainText, Repr, Resolve, Scope, Set, Smart, StyleChain,
+    cast, category, dict, elem, Args, Array, Cast, Category, Construct, Content, Dict,
+    Fold, NativeElement, PlainText, Repr, Resolve, Scope, Set, Smart, Str, StyleChain,
 };
 use crate::layout::Em;
-use crate::layout::{Abs, Axis, Dir, Length, Rel};
+use crate::layout::{Abs, Axis, Dir, Length, Ratio, Rel};
 use crate::model::ParElem;
 use crate::syntax::Spanned;
+use crate::util::Numeric;
 use crate::visualize::{Color, Paint, RelativeTo, Stroke};
 
 /// Text styling.
@@ -120,6 +121,31 @@ pub struct TextElem {
     /// This is Latin. \
     /// هذا عربي.
     /// ```
+    ///
+    /// Fonts for different scripts often don't fit together well out of the
+    /// box: Their x-heights and baselines differ. To tune a family that is
+    /// used for fallback, you can give it as a dictionary with the following
+    /// keys instead of just its name:
+    ///
+    /// - `name`: The name of the font family.
+    /// - `size-adjust`: A [ratio] by which to scale text set in this family.
+    ///   Defaults to `{100%}`.
+    /// - `baseline-shift`: A [length] by which to shift the baseline of text
+    ///   set in this family. Like the [`baseline`]($text.baseline) property,
+    ///   positive values shift downwards. Defaults to `{0pt}`.
+    ///
+    /// ```example
+    /// #set text(font: (
+    ///   "Linux Libertine",
+    ///   (
+    ///     name: "Noto Serif CJK SC",
+    ///     size-adjust: 90%,
+    ///     baseline-shift: 0.05em,
+    ///   ),
+    /// ))
+    ///
+    /// Latin and 中文 text.
+    /// ```
     #[default(FontList(vec![FontFamily::new("Linux Libertine")]))]
     #[borrowed]
     #[ghost]
@@ -254,13 +280,41 @@ pub struct TextElem {
 
     /// The amount of space that should be added between characters.
     ///
+    /// Instead of a fixed length, you can also pass the preset `{"optical"}`.
+    /// It picks the tracking based on the font size, following the dynamic
+    /// metrics of the Inter typeface: Small text is tracked slightly looser
+    /// and large display text tighter.
+    ///
     /// ```example
     /// #set text(tracking: 1.5pt)
     /// Distant text.
     /// ```
+    ///
+    /// ```example
+    /// #set text(tracking: "optical")
+    /// #text(8pt)[Small text] \
+    /// #text(24pt)[Display text]
+    /// ```
     #[resolve]
     #[ghost]
-    pub tracking: Length,
+    pub tracking: Tracking,
+
+    /// The amount of space that is added between characters in addition to
+    /// the [`tracking`]($text.tracking) when they are set in capitals.
+    ///
+    /// Text in capitals is easier to read when it is spaced a bit more
+    /// loosely than lowercase text. This applies to [small caps]($smallcaps),
+    /// [uppercased text]($upper), and runs of consecutive uppercase letters
+    /// like acronyms. Typically, an amount of `{0.05em}` to `{0.1em}` works
+    /// well.
+    ///
+    /// ```example
+    /// #set text(caps-tracking: 0.08em)
+    /// The #smallcaps[Small Caps] of the UNESCO.
+    /// ```
+    #[resolve]
+    #[ghost]
+    pub caps_tracking: Length,
 
     /// The amount of space between words.
     ///
@@ -279,7 +333,17 @@ pub struct TextElem {
     #[ghost]
     pub spacing: Rel<Length>,
 
-    /// Whether to automatically insert spacing between CJK and Latin characters.
+    /// Whether and how to automatically insert spacing between CJK and Latin
+    /// characters.
+    ///
+    /// - `{auto}` inserts a quarter em of spacing, which may shrink to an
+    ///   eighth em for justification.
+    /// - `{none}` disables the automatic spacing.
+    /// - A length inserts spacing of that width instead.
+    /// - A dictionary configures the spacing in more detail. It may contain
+    ///   the `width` of the spacing and an `except` string. No spacing is
+    ///   inserted next to the characters in `except`, which is useful for
+    ///   units and symbols like `%` that should stay attached to CJK text.
     ///
     /// ```example
     /// #set text(cjk-latin-spacing: auto)
@@ -287,9 +351,15 @@ pub struct TextElem {
     ///
     /// #set text(cjk-latin-spacing: none)
     /// 第4章介绍了基本的API。
+    ///
+    /// #set text(cjk-latin-spacing: 0.5em)
+    /// 第4章介绍了基本的API。
+    ///
+    /// #set text(cjk-latin-spacing: (except: "4"))
+    /// 第4章介绍了基本的API。
     /// ```
     #[ghost]
-    pub cjk_latin_spacing: Smart<Option<Never>>,
+    pub cjk_latin_spacing: Smart<Option<CjkLatinSpacing>>,
 
     /// An amount to shift the text baseline by.
     ///
@@ -621,6 +691,22 @@ pub struct TextElem {
     #[required]
     pub text: EcoString,
 
+    /// The capture groups of the regular expression if this text was matched
+    /// by a [regex show rule]($reference/styling/#show-rules). Groups that
+    /// didn't participate in the match are `{none}`.
+    ///
+    /// ```example
+    /// #show regex("(\d+)\.(\d+)\.(\d+)"): it => {
+    ///   let (major, minor, patch) = it.captures
+    ///   [v#(major).#(minor)]
+    ///   if patch != "0" [.#(patch)]
+    /// }
+    ///
+    /// Released 1.4.0, patched in 1.4.2.
+    /// ```
+    #[synthesized]
+    pub captures: Vec<Option<Str>>,
+
     /// The offset of the text in the text syntax node referenced by this
     /// element's span.
     #[internal]
@@ -694,32 +780,92 @@ impl PlainText for Packed<TextElem> {
     }
 }
 
-/// A lowercased font family like "arial".
+/// A lowercased font family like "arial", along with adjustments for the
+/// family's fonts.
 #[derive(Clone, Eq, PartialEq, Hash)]
-pub struct FontFamily(EcoString);
+pub struct FontFamily {
+    /// The lowercased family name.
+    name: EcoString,
+    /// How much to scale text set in this family.
+    size_adjust: Ratio,
+    /// How much to shift the baseline of text set in this family.
+    baseline_shift: Length,
+}
 
 impl FontFamily {
     /// Create a named font family variant.
     pub fn new(string: &str) -> Self {
-        Self(string.to_lowercase().into())
+        Self {
+            name: string.to_lowercase().into(),
+            size_adjust: Ratio::one(),
+            baseline_shift: Length::zero(),
+        }
     }
 
     /// The lowercased family name.
     pub fn as_str(&self) -> &str {
-        &self.0
+        &self.name
+    }
+
+    /// How much to scale text set in this family.
+    pub fn size_adjust(&self) -> Ratio {
+        self.size_adjust
+    }
+
+    /// How much to shift the baseline of text set in this family. Positive
+    /// values shift downwards.
+    pub fn baseline_shift(&self) -> Length {
+        self.baseline_shift
     }
 }
 
 impl Debug for FontFamily {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        self.0.fmt(f)
+        self.name.fmt(f)
     }
 }
 
 cast! {
     FontFamily,
-    self => self.0.into_value(),
+    self => if self.size_adjust.is_one() && self.baseline_shift.is_zero() {
+        self.name.into_value()
+    } else {
+        dict! {
+            "name" => self.name,
+            "size-adjust" => self.size_adjust,
+            "baseline-shift" => self.baseline_shift,
+        }.into_value()
+    },
     string: EcoString => Self::new(&string),
+    mut dict: Dict => {
+        let name: EcoString = dict.take("name")?.cast()?;
+        let mut family = Self::new(&name);
+        if let Ok(size_adjust) = dict.take("size-adjust") {
+            family.size_adjust = size_adjust.cast()?;
+            if family.size_adjust.get() <= 0.0 {
+                bail!("size adjustment must be positive");
+            }
+        }
+        if let Ok(baseline_shift) = dict.take("baseline-shift") {
+            family.baseline_shift = baseline_shift.cast()?;
+        }
+        dict.finish(&["name", "size-adjust", "baseline-shift"])?;
+        family
+    },
+}
+
+/// The size scale and baseline shift (relative to the text size, positive
+/// upwards) of a font that was selected from the font list.
+pub(crate) fn font_adjustment(styles: StyleChain, font: &Font, size: Abs) -> (f64, Em) {
+    let family = font.info().family.to_lowercase();
+    TextElem::font_in(styles)
+        .into_iter()
+        .find(|f| f.as_str() == family)
+        .map_or((1.0, Em::zero()), |f| {
+            let scale = f.size_adjust().get();
+            let shift = -f.baseline_shift().at(size);
+            (scale, Em::from_length(shift, size))
+        })
 }
 
 /// Font family fallback list.
@@ -738,7 +884,7 @@ impl<'a> IntoIterator for &'a FontList {
 cast! {
     FontList,
     self => if self.0.len() == 1 {
-        self.0.into_iter().next().unwrap().0.into_value()
+        self.0.into_iter().next().unwrap().into_value()
     } else {
         self.0.into_value()
     },
@@ -815,6 +961,91 @@ cast! {
     v: Length => Self(v),
 }
 
+/// The amount of space between characters.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Tracking {
+    /// A fixed amount of space.
+    Length(Length),
+    /// Tracking that depends on the font size.
+    Optical,
+}
+
+impl Default for Tracking {
+    fn default() -> Self {
+        Self::Length(Length::zero())
+    }
+}
+
+impl Resolve for Tracking {
+    type Output = Abs;
+
+    fn resolve(self, styles: StyleChain) -> Self::Output {
+        match self {
+            Self::Length(length) => length.resolve(styles),
+            Self::Optical => {
+                // The dynamic metrics of the Inter typeface, which are
+                // specified for sizes in CSS pixels.
+                let size = TextElem::size_in(styles);
+                let px = size.to_pt() * 4.0 / 3.0;
+                Em::new(-0.0223 + 0.185 * (-0.1745 * px).exp()).at(size)
+            }
+        }
+    }
+}
+
+cast! {
+    Tracking,
+    self => match self {
+        Self::Length(length) => length.into_value(),
+        Self::Optical => "optical".into_value(),
+    },
+    v: Length => Self::Length(v),
+    /// Tracking based on the font size.
+    "optical" => Self::Optical,
+}
+
+/// Configures the spacing between CJK and Latin characters.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CjkLatinSpacing {
+    /// The width of the inserted spacing.
+    pub width: Length,
+    /// Characters next to which no spacing is inserted.
+    pub except: EcoString,
+}
+
+impl Default for CjkLatinSpacing {
+    fn default() -> Self {
+        Self {
+            width: Em::new(0.25).into(),
+            except: EcoString::new(),
+        }
+    }
+}
+
+cast! {
+    CjkLatinSpacing,
+    self => if self.except.is_empty() {
+        self.width.into_value()
+    } else {
+        dict! {
+            "width" => self.width,
+            "except" => self.except,
+        }.into_value()
+    },
+    width: Length => Self { width, ..Self::default() },
+    mut dict: Dict => {
+        let mut spacing = Self::default();
+        if let Ok(width) = dict.take("width") {
+            spacing.width = width.cast()?;
+        }
+        if let Ok(except) = dict.take("except") {
+            spacing.except = except.cast()?;
+        }
+        dict.finish(&["width", "except"])?;
+        spacing
+    },
+}
+
 /// Specifies the top edge of text.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum TopEdge {
@@ -5,25 +5,34 @@ use std::sync::Arc;
 use ecow::{eco_format, EcoString, EcoVec};
 use once_cell::sync::Lazy;
 use once_cell::unsync::Lazy as UnsyncLazy;
+use smallvec::smallvec;
 use syntect::highlighting as synt;
-use syntect::parsing::{SyntaxDefinition, SyntaxSet, SyntaxSetBuilder};
+use syntect::highlighting::ScopeSelectors;
+use syntect::parsing::{
+    ParseState, ScopeStack, SyntaxDefinition, SyntaxReference, SyntaxSet,
+    SyntaxSetBuilder,
+};
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::diag::{At, FileError, SourceResult, StrResult};
+use crate::diag::{bail, At, FileError, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Args, Array, Bytes, Content, Fold, NativeElement, Packed,
-    PlainText, Show, ShowSet, Smart, StyleChain, Styles, Synthesize, Value,
+    array, cast, elem, scope, Args, Array, Bytes, Content, Dict, Fold, NativeElement,
+    Packed, PlainText, Repr, Show, ShowSet, Smart, StyleChain, Styles, Synthesize, Value,
+};
+use crate::layout::{
+    Alignment, BlockElem, Celled, Em, Fr, GridCell, GridChild, GridElem, GridItem,
+    HAlignment, Ratio, Sides, Sizing, TrackSizings,
 };
-use crate::layout::{BlockElem, Em, HAlignment};
-use crate::model::Figurable;
+use crate::loading::Readable;
+use crate::model::{Figurable, ParElem};
 use crate::syntax::{split_newlines, LinkedNode, Span, Spanned};
 use crate::text::{
-    FontFamily, FontList, Hyphenate, Lang, LinebreakElem, LocalName, Region,
-    SmartQuoteElem, TextElem, TextSize,
+    FontFamily, FontList, HighlightElem, Hyphenate, Lang, LinebreakElem, LocalName,
+    Region, SmartQuoteElem, StrikeElem, TextElem, TextSize,
 };
 use crate::util::option_eq;
-use crate::visualize::Color;
+use crate::visualize::{Color, Paint};
 use crate::{syntax, World};
 
 // Shorthand for highlighter closures.
@@ -153,6 +162,21 @@ pub struct RawElem {
     ///
     /// This is ```typ also *Typst*```, but inline!
     /// ````
+    ///
+    /// With the `{"ansi"}` tag, the raw text is interpreted as terminal
+    /// output: [ANSI escape sequences](https://en.wikipedia.org/wiki/ANSI_escape_code#SGR)
+    /// for colors and text styles are applied and all other escape sequences
+    /// are removed. This is useful for displaying captured output of command
+    /// line tools.
+    ///
+    /// ```example
+    /// #raw(
+    ///   lang: "ansi",
+    ///   block: true,
+    ///   "\u{1b}[1;32m   Compiling\u{1b}[0m typst v0.11.0\n" +
+    ///   "\u{1b}[1;31merror\u{1b}[0m: could not compile `typst`",
+    /// )
+    /// ```
     #[borrowed]
     pub lang: Option<EcoString>,
 
@@ -205,6 +229,54 @@ pub struct RawElem {
     #[fold]
     pub syntaxes_data: Vec<Bytes>,
 
+    /// One or multiple additional syntax definitions given as source code,
+    /// for instance loaded with [`read`]. Like with
+    /// [`syntaxes`]($raw.syntaxes), the definitions should be in the
+    /// [`sublime-syntax` file format](https://www.sublimetext.com/docs/syntax.html).
+    ///
+    /// With a set rule, the grammars are registered for the rest of the
+    /// document. Grammars given this way take precedence over the bundled
+    /// ones, so they can also be used to replace the highlighting for a
+    /// language.
+    ///
+    /// ````example
+    /// #set raw(grammar: read("SExpressions.sublime-syntax"))
+    ///
+    /// ```sexp
+    /// (defun square (x) (* x x))
+    /// ```
+    /// ````
+    #[parse(
+        let grammar = args.named::<Spanned<Grammars>>("grammar")?;
+        if let Some(Spanned { v, span }) = &grammar {
+            load_grammars(v).at(*span)?;
+        }
+        grammar.map(|grammar| grammar.v)
+    )]
+    #[fold]
+    pub grammar: Grammars,
+
+    /// Languages to highlight embedded code with.
+    ///
+    /// Maps [scope selectors](https://www.sublimetext.com/docs/selectors.html)
+    /// to language tags. Parts of the raw text whose scopes match a selector
+    /// are highlighted in the given language instead, for instance SQL
+    /// queries in the strings of a Python program. To keep the delimiters of
+    /// a string highlighted as such, subtract them from the selector with
+    /// `-`. If multiple selectors match, the first one wins.
+    ///
+    /// ````example
+    /// #set raw(injections: (
+    ///   "string.quoted - punctuation": "sql",
+    /// ))
+    ///
+    /// ```js
+    /// db.query("SELECT name FROM users WHERE id = 1");
+    /// ```
+    /// ````
+    #[fold]
+    pub injections: Injections,
+
     /// The theme to use for syntax highlighting. Theme files should be in the
     /// in the [`tmTheme` file format](https://www.sublimetext.com/docs/color_schemes_tmtheme.html).
     ///
@@ -257,6 +329,110 @@ pub struct RawElem {
     #[default(2)]
     pub tab_size: usize,
 
+    /// Whether to show line numbers in the gutter of a raw block.
+    ///
+    /// Like the options below, this is ignored if this is not a raw block.
+    ///
+    /// ````example
+    /// #set raw(numbers: true)
+    /// ```rust
+    /// fn main() {
+    ///     println!("Hello World!");
+    /// }
+    /// ```
+    /// ````
+    #[default(false)]
+    pub numbers: bool,
+
+    /// Lines of a raw block to highlight.
+    ///
+    /// Each entry is either a single line number or an array of the first and
+    /// last line number of a range, both inclusive. Line numbers start at 1.
+    ///
+    /// ````example
+    /// #set raw(highlight: (1, (3, 4)))
+    /// ```py
+    /// import math
+    ///
+    /// def area(r):
+    ///     return math.pi * r ** 2
+    /// ```
+    /// ````
+    pub highlight: Vec<RawLineRange>,
+
+    /// How to fill [highlighted]($raw.highlight) lines.
+    #[default(Color::from_u8(0xff, 0xf5, 0xb1, 0xff).into())]
+    pub highlight_fill: Paint,
+
+    /// Whether to interpret the first character of each line in a raw block
+    /// as a diff marker.
+    ///
+    /// Lines starting with `+` are shown as added and lines starting with `-`
+    /// as removed. The marker is moved into the gutter and does not take part
+    /// in syntax highlighting. For lines starting with a space, the space is
+    /// removed.
+    ///
+    /// ````example
+    /// #set raw(diff: true)
+    /// ```rust
+    ///  fn main() {
+    /// -    println!("Hello World!");
+    /// +    println!("Hello Typst!");
+    ///  }
+    /// ```
+    /// ````
+    #[default(false)]
+    pub diff: bool,
+
+    /// Annotations to show next to lines of a raw block.
+    ///
+    /// Each annotation is an array of a line number and the content to show
+    /// in the gutter after that line.
+    ///
+    /// ````example
+    /// #set raw(annotations: (
+    ///   (1, [Entry point]),
+    ///   (2, [Prints a greeting]),
+    /// ))
+    /// ```rust
+    /// fn main() {
+    ///     println!("Hello World!");
+    /// }
+    /// ```
+    /// ````
+    pub annotations: Vec<RawAnnotation>,
+
+    /// Whether to execute the code and show its output after it.
+    ///
+    /// Typst never executes code itself. Instead, the output is provided by
+    /// the environment Typst is embedded in, for instance from a cache of
+    /// earlier runs. This enables literate programming and notebook-like
+    /// workflows. If the environment cannot execute code in the raw text's
+    /// [language]($raw.lang), an error is raised. Raw text without a language
+    /// is never executed.
+    ///
+    /// The output is shown after the code. Like for the `{"ansi"}`
+    /// [language tag]($raw.lang), escape sequences for colors and text styles
+    /// in it are interpreted. To show it differently, you can use a show rule
+    /// with the [`output`]($raw.output) field.
+    ///
+    /// ````example
+    /// #show raw.where(execute: true): it => {
+    ///   it.text
+    ///   [ evaluates to #it.output]
+    /// }
+    /// ```py
+    /// 1 + 2
+    /// ```
+    /// ````
+    #[default(false)]
+    pub execute: bool,
+
+    /// The output of executing the code, if [`execute`]($raw.execute) is
+    /// enabled.
+    #[synthesized]
+    pub output: Option<EcoString>,
+
     /// The stylized lines of raw text.
     ///
     /// Made accessible for the [`raw.line` element]($raw.line).
@@ -283,22 +459,51 @@ impl RawElem {
                     syntax.file_extensions.iter().map(|s| s.as_str()).collect(),
                 )
             })
-            .chain([("Typst", vec!["typ"]), ("Typst (code)", vec!["typc"])])
+            .chain([
+                ("Typst", vec!["typ"]),
+                ("Typst (code)", vec!["typc"]),
+                ("ANSI Escape Codes", vec!["ansi"]),
+            ])
             .collect()
     }
 }
 
 impl Synthesize for Packed<RawElem> {
-    fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
-        let seq = self.highlight(styles);
+    fn synthesize(
+        &mut self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        let seq = self.highlight_lines(styles);
         self.push_lines(seq);
+
+        let output = match self.lang(styles).as_ref() {
+            Some(lang) if self.execute(styles) => Some(self.run(engine, lang)?),
+            _ => None,
+        };
+        self.push_output(output);
+
         Ok(())
     }
 }
 
 impl Packed<RawElem> {
+    /// Let the environment execute the code.
+    fn run(&self, engine: &mut Engine, lang: &str) -> SourceResult<EcoString> {
+        let span = self.span();
+        match engine.world.execute(lang, &self.text().get()) {
+            Some(output) => output
+                .map_err(|err| eco_format!("failed to execute code ({err})"))
+                .at(span),
+            None => bail!(
+                span, "cannot execute code in language {}", lang.repr();
+                hint: "code execution must be supported by the environment"
+            ),
+        }
+    }
+
     #[comemo::memoize]
-    fn highlight(&self, styles: StyleChain) -> Vec<Packed<RawLine>> {
+    fn highlight_lines(&self, styles: StyleChain) -> Vec<Packed<RawLine>> {
         let elem = self.as_ref();
 
         let text = elem.text();
@@ -317,6 +522,16 @@ impl Packed<RawElem> {
             }
         };
 
+        let diff = RawElem::diff_in(styles);
+        let lines: EcoVec<_> = if diff {
+            lines
+                .into_iter()
+                .map(|(line, span)| (split_diff_marker(&line).1.into(), span))
+                .collect()
+        } else {
+            lines
+        };
+
         let count = lines.len() as i64;
         let lang = elem
             .lang(styles)
@@ -329,6 +544,24 @@ impl Packed<RawElem> {
             load_syntaxes(&elem.syntaxes(styles), &elem.syntaxes_data(styles)).unwrap()
         });
 
+        let grammars = UnsyncLazy::new(|| load_grammars(&elem.grammar(styles)).unwrap());
+
+        let find_syntax = |token: &str| {
+            grammars
+                .find_syntax_by_token(token)
+                .map(|syntax| (&**grammars, syntax))
+                .or_else(|| {
+                    RAW_SYNTAXES
+                        .find_syntax_by_token(token)
+                        .map(|syntax| (&*RAW_SYNTAXES, syntax))
+                })
+                .or_else(|| {
+                    extra_syntaxes
+                        .find_syntax_by_token(token)
+                        .map(|syntax| (&**extra_syntaxes, syntax))
+                })
+        };
+
         let theme = elem.theme(styles).as_ref().as_ref().map(|theme_path| {
             load_theme(theme_path, elem.theme_data(styles).as_ref().as_ref().unwrap())
                 .unwrap()
@@ -338,8 +571,24 @@ impl Packed<RawElem> {
         let foreground = theme.settings.foreground.unwrap_or(synt::Color::BLACK);
 
         let mut seq = vec![];
-        if matches!(lang.as_deref(), Some("typ" | "typst" | "typc")) {
-            let text = text.get();
+        if lang.as_deref() == Some("ansi") {
+            // The style carries over from one line to the next, like in a
+            // terminal.
+            let mut style = AnsiStyle::default();
+            for (i, (line, line_span)) in lines.into_iter().enumerate() {
+                let (text, body) = highlight_ansi(&line, &mut style, line_span);
+                seq.push(
+                    Packed::new(RawLine::new(i as i64 + 1, count, text, body))
+                        .spanned(line_span),
+                );
+            }
+        } else if matches!(lang.as_deref(), Some("typ" | "typst" | "typc")) {
+            let text = if diff {
+                let lines: Vec<_> = lines.iter().map(|(line, _)| line.as_str()).collect();
+                lines.join("\n").into()
+            } else {
+                text.get()
+            };
             let root = match lang.as_deref() {
                 Some("typc") => syntax::parse_code(&text),
                 _ => syntax::parse(&text),
@@ -372,25 +621,34 @@ impl Packed<RawElem> {
                 },
             )
             .highlight();
-        } else if let Some((syntax_set, syntax)) = lang.and_then(|token| {
-            RAW_SYNTAXES
-                .find_syntax_by_token(&token)
-                .map(|syntax| (&*RAW_SYNTAXES, syntax))
-                .or_else(|| {
-                    extra_syntaxes
-                        .find_syntax_by_token(&token)
-                        .map(|syntax| (&**extra_syntaxes, syntax))
+        } else if let Some((syntax_set, syntax)) =
+            lang.and_then(|token| find_syntax(&token))
+        {
+            let injections: Vec<_> = elem
+                .injections(styles)
+                .0
+                .iter()
+                .filter_map(|(selector, token)| {
+                    Some((selector.parse().ok()?, find_syntax(&token.to_lowercase())?))
                 })
-        }) {
+                .collect();
+
             let mut highlighter = syntect::easy::HighlightLines::new(syntax, theme);
+            let mut injecting =
+                InjectingHighlighter::new(syntax, syntax_set, theme, &injections);
+
             for (i, (line, line_span)) in lines.into_iter().enumerate() {
+                let pieces = if injections.is_empty() {
+                    highlighter
+                        .highlight_line(line.as_str(), syntax_set)
+                        .unwrap_or_default()
+                } else {
+                    injecting.highlight_line(line.as_str())
+                };
+
                 let mut line_content = vec![];
                 let mut span_offset = 0;
-                for (style, piece) in highlighter
-                    .highlight_line(line.as_str(), syntax_set)
-                    .into_iter()
-                    .flatten()
-                {
+                for (style, piece) in pieces {
                     line_content.push(styled(
                         piece,
                         foreground,
@@ -432,24 +690,162 @@ impl Show for Packed<RawElem> {
     fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
         let lines = self.lines().map(|v| v.as_slice()).unwrap_or_default();
 
-        let mut seq = EcoVec::with_capacity((2 * lines.len()).saturating_sub(1));
+        let mut realized = if self.block(styles)
+            && (self.numbers(styles)
+                || self.diff(styles)
+                || !self.highlight(styles).is_empty()
+                || !self.annotations(styles).is_empty())
+        {
+            self.decorated(lines, styles)
+        } else {
+            let mut seq = EcoVec::with_capacity((2 * lines.len()).saturating_sub(1));
+            for (i, line) in lines.iter().enumerate() {
+                if i != 0 {
+                    seq.push(LinebreakElem::new().pack());
+                }
+
+                seq.push(line.clone().pack());
+            }
+
+            let mut realized = Content::sequence(seq);
+            if self.block(styles) {
+                // Align the text before inserting it into the block.
+                realized = realized.aligned(self.align(styles).into());
+                realized = BlockElem::new()
+                    .with_body(Some(realized))
+                    .pack()
+                    .spanned(self.span());
+            }
+            realized
+        };
+
+        if let Some(Some(output)) = self.output() {
+            // Terminal output often contains escape sequences, so we interpret
+            // them like for raw text with the `ansi` tag.
+            let mut style = AnsiStyle::default();
+            let mut seq = vec![];
+            for (i, line) in split_newlines(output).into_iter().enumerate() {
+                if i != 0 {
+                    seq.push(LinebreakElem::new().pack());
+                }
+                seq.push(highlight_ansi(line, &mut style, self.span()).1);
+            }
+
+            let mut output = Content::sequence(seq);
+            if self.block(styles) {
+                output = output.aligned(self.align(styles).into());
+                output =
+                    BlockElem::new().with_body(Some(output)).pack().spanned(self.span());
+            } else {
+                output = TextElem::packed(' ') + output;
+            }
+            realized += output;
+        }
+
+        Ok(realized)
+    }
+}
+
+impl Packed<RawElem> {
+    /// Lay the lines of a raw block out in a grid, with line numbers and diff
+    /// markers in the gutter before and annotations in the gutter after them.
+    ///
+    /// Since each line is a row of the grid, the gutters stay aligned with the
+    /// lines and the block can break between any two lines.
+    fn decorated(&self, lines: &[Packed<RawLine>], styles: StyleChain) -> Content {
+        let span = self.span();
+        let numbers = self.numbers(styles);
+        let diff = self.diff(styles);
+        let highlight = self.highlight(styles);
+        let highlight_fill = self.highlight_fill(styles);
+        let annotations = self.annotations(styles);
+        let align = Alignment::from(self.align(styles));
+
+        let markers: Vec<_> = if diff {
+            split_newlines(&self.text().get())
+                .into_iter()
+                .map(|line| split_diff_marker(line).0)
+                .collect()
+        } else {
+            vec![]
+        };
+
+        let cell = |body: Content, fill: &Option<Paint>| {
+            GridChild::Item(GridItem::Cell(
+                Packed::new(GridCell::new(body).with_fill(Smart::Custom(fill.clone())))
+                    .spanned(span),
+            ))
+        };
+
+        let mut cells = vec![];
         for (i, line) in lines.iter().enumerate() {
-            if i != 0 {
-                seq.push(LinebreakElem::new().pack());
+            let number = *line.number();
+            let marker = markers.get(i).copied().flatten();
+            let fill = if highlight.iter().any(|range| range.contains(number)) {
+                Some(highlight_fill.clone())
+            } else {
+                match marker {
+                    Some('+') => Some(Color::from_u8(0xe6, 0xff, 0xec, 0xff).into()),
+                    Some('-') => Some(Color::from_u8(0xff, 0xeb, 0xe9, 0xff).into()),
+                    _ => None,
+                }
+            };
+
+            if numbers || diff {
+                let mut gutter = EcoString::new();
+                if numbers {
+                    gutter.push_str(&eco_format!("{number}"));
+                }
+                if diff {
+                    if numbers {
+                        gutter.push(' ');
+                    }
+                    gutter.push(marker.unwrap_or(' '));
+                }
+                let body = TextElem::packed(gutter)
+                    .styled(TextElem::set_fill(Color::GRAY.into()))
+                    .aligned(HAlignment::End.into());
+                cells.push(cell(body, &fill));
             }
 
-            seq.push(line.clone().pack());
+            cells.push(cell(line.clone().pack().aligned(align), &fill));
+
+            if !annotations.is_empty() {
+                let body = Content::sequence(
+                    annotations
+                        .iter()
+                        .filter(|annotation| annotation.line == number)
+                        .map(|annotation| annotation.body.clone()),
+                );
+                cells.push(cell(body, &None));
+            }
         }
 
-        let mut realized = Content::sequence(seq);
-        if self.block(styles) {
-            // Align the text before inserting it into the block.
-            realized = realized.aligned(self.align(styles).into());
-            realized =
-                BlockElem::new().with_body(Some(realized)).pack().spanned(self.span());
+        let mut columns = smallvec![Sizing::Fr(Fr::one())];
+        if numbers || diff {
+            columns.insert(0, Sizing::Auto);
+        }
+        if !annotations.is_empty() {
+            columns.push(Sizing::Auto);
         }
 
-        Ok(realized)
+        // Split the leading between the rows such that the fills of adjacent
+        // lines touch.
+        let leading = ParElem::leading_in(styles);
+        let inset = Sides::new(
+            Some(Em::new(0.3).into()),
+            Some((leading / 2.0).into()),
+            Some(Em::new(0.3).into()),
+            Some((leading / 2.0).into()),
+        );
+
+        let grid = GridElem::new(cells)
+            .with_columns(TrackSizings(columns))
+            .with_inset(Celled::Value(inset))
+            .pack()
+            .spanned(span);
+
+        BlockElem::new().with_body(Some(grid)).pack().spanned(span)
     }
 }
 
@@ -541,6 +937,69 @@ cast! {
     v: EcoString => Self::Text(v),
 }
 
+/// A range of lines in a raw block, with both ends inclusive.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct RawLineRange {
+    /// The first line of the range.
+    pub start: i64,
+    /// The last line of the range.
+    pub end: i64,
+}
+
+impl RawLineRange {
+    /// Whether the range contains the given line number.
+    pub fn contains(&self, line: i64) -> bool {
+        (self.start..=self.end).contains(&line)
+    }
+}
+
+cast! {
+    RawLineRange,
+    self => if self.start == self.end {
+        self.start.into_value()
+    } else {
+        array![self.start, self.end].into_value()
+    },
+    line: i64 => Self { start: line, end: line },
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => Self { start: a.cast()?, end: b.cast()? },
+            _ => bail!("line range must contain exactly two line numbers"),
+        }
+    },
+}
+
+/// An annotation for a line in a raw block.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct RawAnnotation {
+    /// The line to annotate.
+    pub line: i64,
+    /// The content of the annotation.
+    pub body: Content,
+}
+
+cast! {
+    RawAnnotation,
+    self => array![self.line, self.body].into_value(),
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => Self { line: a.cast()?, body: b.cast()? },
+            _ => bail!("annotation must contain a line number and content"),
+        }
+    },
+}
+
+/// Split a line of a diff into its marker and the rest of the line.
+fn split_diff_marker(line: &str) -> (Option<char>, &str) {
+    match line.chars().next() {
+        Some(c @ ('+' | '-')) => (Some(c), &line[1..]),
+        Some(' ') => (None, &line[1..]),
+        _ => (None, line),
+    }
+}
+
 /// A highlighted line of raw text.
 ///
 /// This is a helper element that is synthesized by [`raw`] elements.
@@ -580,6 +1039,115 @@ impl PlainText for Packed<RawLine> {
     }
 }
 
+/// A language to highlight code matching a scope selector in.
+type Injection<'a> = (ScopeSelectors, (&'a SyntaxSet, &'a SyntaxReference));
+
+/// Highlights code line by line, but highlights the parts of it matching an
+/// injection's selector in the injection's language.
+struct InjectingHighlighter<'a> {
+    /// The syntax set containing the main syntax.
+    syntax_set: &'a SyntaxSet,
+    /// The parser for the main syntax.
+    parser: ParseState,
+    /// The scopes at the current position.
+    stack: ScopeStack,
+    /// The highlighter for the main syntax.
+    highlighter: synt::Highlighter<'a>,
+    /// The theme to highlight with.
+    theme: &'a synt::Theme,
+    /// The injections to apply.
+    injections: &'a [Injection<'a>],
+    /// The injection that extended until the end of the previous line, along
+    /// with its highlighter. This way, embedded code spanning multiple lines
+    /// is highlighted as a whole.
+    active: Option<(usize, syntect::easy::HighlightLines<'a>)>,
+}
+
+impl<'a> InjectingHighlighter<'a> {
+    fn new(
+        syntax: &'a SyntaxReference,
+        syntax_set: &'a SyntaxSet,
+        theme: &'a synt::Theme,
+        injections: &'a [Injection<'a>],
+    ) -> Self {
+        Self {
+            syntax_set,
+            parser: ParseState::new(syntax),
+            stack: ScopeStack::new(),
+            highlighter: synt::Highlighter::new(theme),
+            theme,
+            injections,
+            active: None,
+        }
+    }
+
+    /// Highlight the next line.
+    fn highlight_line<'l>(&mut self, line: &'l str) -> Vec<(synt::Style, &'l str)> {
+        let ops = self.parser.parse_line(line, self.syntax_set).unwrap_or_default();
+
+        // Split the line into pieces with the same scopes.
+        let mut pieces = vec![];
+        let mut last = 0;
+        for (i, op) in ops {
+            if i > last {
+                pieces.push((last..i, self.stack.clone()));
+                last = i;
+            }
+            let _ = self.stack.apply(&op);
+        }
+        if last < line.len() {
+            pieces.push((last..line.len(), self.stack.clone()));
+        }
+
+        let injection = |stack: &ScopeStack| {
+            self.injections
+                .iter()
+                .position(|(selector, _)| selector.does_match(stack.as_slice()).is_some())
+        };
+
+        let mut out = vec![];
+        let mut k = 0;
+        while k < pieces.len() {
+            let (range, stack) = &pieces[k];
+            let Some(index) = injection(stack) else {
+                let style = self.highlighter.style_for_stack(stack.as_slice());
+                out.push((style, &line[range.clone()]));
+                self.active = None;
+                k += 1;
+                continue;
+            };
+
+            // Merge adjacent pieces with the same injection.
+            let start = range.start;
+            let mut end = range.end;
+            k += 1;
+            while k < pieces.len() && injection(&pieces[k].1) == Some(index) {
+                end = pieces[k].0.end;
+                k += 1;
+            }
+
+            let (_, (syntax_set, syntax)) = self.injections[index];
+            if start != 0 || self.active.as_ref().map(|(i, _)| *i) != Some(index) {
+                let highlighter = syntect::easy::HighlightLines::new(syntax, self.theme);
+                self.active = Some((index, highlighter));
+            }
+
+            let (_, highlighter) = self.active.as_mut().unwrap();
+            out.extend(
+                highlighter
+                    .highlight_line(&line[start..end], syntax_set)
+                    .unwrap_or_default(),
+            );
+
+            if end != line.len() {
+                self.active = None;
+            }
+        }
+
+        out
+    }
+}
+
 /// Wrapper struct for the state required to highlight typst code.
 struct ThemedHighlighter<'a> {
     /// The code being highlighted.
@@ -716,6 +1284,208 @@ fn styled(
     body
 }
 
+/// The text style selected by ANSI escape sequences.
+#[derive(Debug, Default, Copy, Clone)]
+struct AnsiStyle {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    strike: bool,
+    inverse: bool,
+}
+
+impl AnsiStyle {
+    /// Apply the parameters of a Select Graphic Rendition (SGR) sequence.
+    fn apply(&mut self, params: &str) {
+        let mut params = params.split([';', ':']).map(|p| p.parse::<u8>().unwrap_or(0));
+        while let Some(param) = params.next() {
+            match param {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                7 => self.inverse = true,
+                9 => self.strike = true,
+                21 | 22 => (self.bold, self.dim) = (false, false),
+                23 => self.italic = false,
+                24 => self.underline = false,
+                27 => self.inverse = false,
+                29 => self.strike = false,
+                30..=37 => self.fg = Some(ansi_color(param - 30)),
+                38 => self.fg = ansi_extended_color(&mut params),
+                39 => self.fg = None,
+                40..=47 => self.bg = Some(ansi_color(param - 40)),
+                48 => self.bg = ansi_extended_color(&mut params),
+                49 => self.bg = None,
+                90..=97 => self.fg = Some(ansi_color(param - 90 + 8)),
+                100..=107 => self.bg = Some(ansi_color(param - 100 + 8)),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Parse the color of an extended color sequence, that is the parameters
+/// following a 38 or 48.
+fn ansi_extended_color(params: &mut impl Iterator<Item = u8>) -> Option<Color> {
+    match params.next()? {
+        5 => params.next().map(ansi_color),
+        2 => Some(Color::from_u8(params.next()?, params.next()?, params.next()?, 255)),
+        _ => None,
+    }
+}
+
+/// Look up a color in the 256-color palette of terminals.
+///
+/// The first 16 colors are tuned for legibility on a light background.
+fn ansi_color(index: u8) -> Color {
+    const BASIC: [[u8; 3]; 16] = [
+        [0x00, 0x00, 0x00],
+        [0xcd, 0x31, 0x31],
+        [0x00, 0xbc, 0x00],
+        [0x94, 0x98, 0x00],
+        [0x04, 0x51, 0xa5],
+        [0xbc, 0x05, 0xbc],
+        [0x05, 0x98, 0xbc],
+        [0x55, 0x55, 0x55],
+        [0x66, 0x66, 0x66],
+        [0xcd, 0x31, 0x31],
+        [0x14, 0xce, 0x14],
+        [0xb5, 0xba, 0x00],
+        [0x04, 0x51, 0xa5],
+        [0xbc, 0x05, 0xbc],
+        [0x05, 0x98, 0xbc],
+        [0xa5, 0xa5, 0xa5],
+    ];
+
+    let [r, g, b] = match index {
+        0..=15 => BASIC[usize::from(index)],
+        16..=231 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + 40 * v };
+            let i = index - 16;
+            [level(i / 36), level(i / 6 % 6), level(i % 6)]
+        }
+        232..=255 => [8 + 10 * (index - 232); 3],
+    };
+
+    Color::from_u8(r, g, b, 255)
+}
+
+/// Strip the escape sequences from a line of terminal output and style its
+/// text accordingly.
+///
+/// Returns the plain text of the line and its styled content.
+fn highlight_ansi(line: &str, style: &mut AnsiStyle, span: Span) -> (EcoString, Content) {
+    let mut text = EcoString::new();
+    let mut seq = vec![];
+    let mut start = 0;
+    let mut cursor = 0;
+
+    let bytes = line.as_bytes();
+    while cursor < bytes.len() {
+        if bytes[cursor] != 0x1b {
+            cursor += 1;
+            continue;
+        }
+
+        if start < cursor {
+            let piece = &line[start..cursor];
+            text.push_str(piece);
+            seq.push(ansi_styled(piece, *style, span, start));
+        }
+
+        // Skip the escape sequence, applying it if it selects a style.
+        cursor += 1;
+        match bytes.get(cursor) {
+            // Control Sequence Introducer: Parameters followed by a final byte.
+            Some(b'[') => {
+                let params = cursor + 1;
+                cursor = params;
+                while cursor < bytes.len() && !(0x40..=0x7e).contains(&bytes[cursor]) {
+                    cursor += 1;
+                }
+                if bytes.get(cursor) == Some(&b'm') {
+                    style.apply(&line[params..cursor]);
+                }
+                cursor += 1;
+            }
+            // Operating System Command: Terminated by BEL or ESC and a backslash.
+            Some(b']') => {
+                while cursor < bytes.len() && bytes[cursor] != 0x07 {
+                    if bytes[cursor] == 0x1b && bytes.get(cursor + 1) == Some(&b'\\') {
+                        cursor += 1;
+                        break;
+                    }
+                    cursor += 1;
+                }
+                cursor += 1;
+            }
+            Some(_) => cursor += 1,
+            None => {}
+        }
+
+        cursor = cursor.min(bytes.len());
+        start = cursor;
+    }
+
+    if start < bytes.len() {
+        let piece = &line[start..];
+        text.push_str(piece);
+        seq.push(ansi_styled(piece, *style, span, start));
+    }
+
+    (text, Content::sequence(seq))
+}
+
+/// Style a piece of terminal output.
+fn ansi_styled(piece: &str, style: AnsiStyle, span: Span, span_offset: usize) -> Content {
+    let mut body = TextElem::packed(piece).spanned(span);
+
+    if span_offset > 0 {
+        body = body.styled(TextElem::set_span_offset(span_offset));
+    }
+
+    let (mut fg, bg) = if style.inverse {
+        (Some(style.bg.unwrap_or(Color::WHITE)), Some(style.fg.unwrap_or(Color::BLACK)))
+    } else {
+        (style.fg, style.bg)
+    };
+
+    if style.dim {
+        fg = Some(fg.unwrap_or(Color::BLACK).lighten(Ratio::new(0.5)));
+    }
+
+    if let Some(fg) = fg {
+        body = body.styled(TextElem::set_fill(fg.into()));
+    }
+
+    if style.bold {
+        body = body.strong().spanned(span);
+    }
+
+    if style.italic {
+        body = body.emph().spanned(span);
+    }
+
+    if style.underline {
+        body = body.underlined().spanned(span);
+    }
+
+    if style.strike {
+        body = StrikeElem::new(body).pack().spanned(span);
+    }
+
+    if let Some(bg) = bg {
+        body = HighlightElem::new(body).with_fill(bg.into()).pack().spanned(span);
+    }
+
+    body
+}
+
 fn to_typst(synt::Color { r, g, b, a }: synt::Color) -> Color {
     Color::from_u8(r, g, b, a)
 }
@@ -759,6 +1529,73 @@ fn load_syntaxes(paths: &SyntaxPaths, bytes: &[Bytes]) -> StrResult<Arc<SyntaxSe
     Ok(Arc::new(out.build()))
 }
 
+/// Syntax definitions given as source code.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct Grammars(Vec<Bytes>);
+
+cast! {
+    Grammars,
+    self => self.0.into_value(),
+    v: Readable => Self(vec![v.into()]),
+    v: Array => Self(
+        v.into_iter()
+            .map(|v| v.cast::<Readable>().map(Into::into))
+            .collect::<StrResult<_>>()?
+    ),
+}
+
+impl Fold for Grammars {
+    fn fold(self, outer: Self) -> Self {
+        Self(self.0.fold(outer.0))
+    }
+}
+
+/// Languages to highlight parts of raw text in, keyed by scope selector.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct Injections(Vec<(EcoString, EcoString)>);
+
+cast! {
+    Injections,
+    self => self.0
+        .into_iter()
+        .map(|(selector, lang)| (selector.into(), lang.into_value()))
+        .collect::<Dict>()
+        .into_value(),
+    v: Dict => Self(
+        v.into_iter()
+            .map(|(selector, lang)| {
+                if selector.parse::<ScopeSelectors>().is_err() {
+                    bail!("invalid scope selector: {}", selector.repr());
+                }
+                Ok((selector.into(), lang.cast()?))
+            })
+            .collect::<StrResult<_>>()?
+    ),
+}
+
+impl Fold for Injections {
+    fn fold(self, outer: Self) -> Self {
+        // Inner injections come first so that they take precedence.
+        Self(self.0.into_iter().chain(outer.0).collect())
+    }
+}
+
+/// Load a syntax set from syntax definitions given as source code.
+#[comemo::memoize]
+#[typst_macros::time(name = "load grammars")]
+fn load_grammars(grammars: &Grammars) -> StrResult<Arc<SyntaxSet>> {
+    let mut out = SyntaxSetBuilder::new();
+    for bytes in &grammars.0 {
+        let src = std::str::from_utf8(bytes).map_err(FileError::from)?;
+        out.add(
+            SyntaxDefinition::load_from_str(src, false, None)
+                .map_err(|err| eco_format!("failed to parse grammar ({err})"))?,
+        );
+    }
+
+    Ok(Arc::new(out.build()))
+}
+
 /// Function to parse the syntaxes argument.
 /// Much nicer than having it be part of the `element` macro.
 fn parse_syntaxes(
@@ -0,0 +1,256 @@
+//! Bounding the memory used for compilation.
+//!
+//! To enforce a budget, an embedder installs the [`CountingAllocator`] as the
+//! global allocator and returns the budget from
+//! [`World::memory_budget`](crate::World::memory_budget). Each compilation
+//! with a budget then counts the bytes allocated by the threads that work for
+//! it. After each layout iteration, the compiler checks that count: When it
+//! exceeds the budget, all memoized results are evicted. If that doesn't free
+//! enough memory, compilation fails with an error.
+//!
+//! Without the counting allocator, no allocations are observed and the budget
+//! is never exceeded.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, SourceResult, StrResult};
+use crate::syntax::Span;
+
+/// How many compilations with a budget can be in progress at the same time.
+const METERS: usize = 64;
+
+/// The allocation counters of the compilations with a budget.
+static METER: [Meter; METERS] = [Meter::UNUSED; METERS];
+
+thread_local! {
+    /// The index of the meter that counts the current thread's allocations.
+    static CURRENT: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Counts the bytes allocated for one compilation.
+struct Meter {
+    /// Whether a compilation uses this meter.
+    in_use: AtomicBool,
+    /// The number of bytes allocated for the compilation.
+    ///
+    /// This is signed because memory allocated before the compilation, for
+    /// instance by memoized results of an earlier one, can be freed during it.
+    allocated: AtomicIsize,
+}
+
+impl Meter {
+    /// A meter that is not in use.
+    #[allow(clippy::declare_interior_mutable_const)]
+    const UNUSED: Self = Self {
+        in_use: AtomicBool::new(false),
+        allocated: AtomicIsize::new(0),
+    };
+}
+
+/// Count a change in the allocated bytes towards the current thread's meter.
+fn count(delta: isize) {
+    // The thread-local may already be destroyed when a thread exits.
+    if let Ok(Some(meter)) = CURRENT.try_with(Cell::get) {
+        METER[meter].allocated.fetch_add(delta, Ordering::Relaxed);
+    }
+}
+
+/// The system allocator, plus counting of the allocated bytes.
+///
+/// Install it with `#[global_allocator]` to make the budgets of compilations
+/// effective.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            count(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            count(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        count(-(layout.size() as isize));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            count(new_size as isize - layout.size() as isize);
+        }
+        new
+    }
+}
+
+/// The memory budget of one compilation.
+pub(crate) struct Budget {
+    /// The maximum number of bytes the compilation may have allocated.
+    limit: usize,
+    /// The index of the meter that counts the compilation's allocations.
+    meter: usize,
+}
+
+impl Budget {
+    /// Start counting allocations for a compilation with the given budget.
+    pub fn new(limit: usize) -> StrResult<Self> {
+        let meter = METER
+            .iter()
+            .position(|meter| {
+                meter
+                    .in_use
+                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            })
+            .ok_or("too many compilations with a memory budget are in progress")?;
+        METER[meter].allocated.store(0, Ordering::Relaxed);
+        Ok(Self { limit, meter })
+    }
+
+    /// The scope in which allocations are counted towards this budget.
+    pub fn scope(&self) -> Scope {
+        Scope(Some(self.meter))
+    }
+
+    /// The number of bytes the compilation has allocated so far.
+    fn allocated(&self) -> usize {
+        METER[self.meter].allocated.load(Ordering::Relaxed).max(0) as usize
+    }
+
+    /// Check that the allocated memory is within the budget.
+    ///
+    /// If it isn't, all memoized results are evicted first. The error is only
+    /// returned when that doesn't bring the memory back within the budget.
+    ///
+    /// Must only be called outside of memoized functions, as it evicts their
+    /// results.
+    pub fn check(&self, span: Span) -> SourceResult<()> {
+        if self.allocated() <= self.limit {
+            return Ok(());
+        }
+
+        comemo::evict(0);
+        let allocated = self.allocated();
+        if allocated <= self.limit {
+            return Ok(());
+        }
+
+        bail!(
+            span, "compilation exceeded the memory budget of {}", format_size(self.limit);
+            hint: "{} were in use after evicting memoized results", format_size(allocated);
+            hint: "try to increase the memory budget",
+        )
+    }
+}
+
+impl Drop for Budget {
+    fn drop(&mut self) {
+        METER[self.meter].in_use.store(false, Ordering::Release);
+    }
+}
+
+/// Determines which budget, if any, the allocations of a thread count towards.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Scope(Option<usize>);
+
+impl Scope {
+    /// The scope of the current thread.
+    pub fn current() -> Self {
+        Self(CURRENT.with(Cell::get))
+    }
+
+    /// Count the current thread's allocations in this scope until the
+    /// returned guard is dropped.
+    pub fn enter(self) -> ScopeGuard {
+        ScopeGuard {
+            prev: CURRENT.with(|current| current.replace(self.0)),
+            _local: PhantomData,
+        }
+    }
+}
+
+/// Restores the previous scope of the current thread when dropped.
+pub(crate) struct ScopeGuard {
+    prev: Option<usize>,
+    /// Keeps the guard on the thread whose scope it restores.
+    _local: PhantomData<*const ()>,
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.set(self.prev));
+    }
+}
+
+/// Format a number of bytes with a binary unit.
+fn format_size(bytes: usize) -> EcoString {
+    let mut size = bytes as f64;
+    for unit in ["B", "KiB", "MiB"] {
+        if size < 1024.0 {
+            return eco_format!("{size:.1} {unit}");
+        }
+        size /= 1024.0;
+    }
+    eco_format!("{size:.1} GiB")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_budget() {
+        let budget = Budget::new(1 << 20).unwrap();
+        let meter = &METER[budget.meter].allocated;
+
+        meter.store(1 << 19, Ordering::Relaxed);
+        assert!(budget.check(Span::detached()).is_ok());
+
+        meter.store(3 << 20, Ordering::Relaxed);
+        let errors = budget.check(Span::detached()).unwrap_err();
+        assert_eq!(
+            errors[0].message,
+            "compilation exceeded the memory budget of 1.0 MiB"
+        );
+        assert_eq!(
+            errors[0].hints[0],
+            "3.0 MiB were in use after evicting memoized results"
+        );
+    }
+
+    #[test]
+    fn test_budget_scopes() {
+        let first = Budget::new(1 << 20).unwrap();
+        let second = Budget::new(1 << 20).unwrap();
+        assert_ne!(first.meter, second.meter);
+
+        let outer = first.scope().enter();
+        let inner = second.scope().enter();
+        assert_eq!(Scope::current().0, Some(second.meter));
+        drop(inner);
+        assert_eq!(Scope::current().0, Some(first.meter));
+        drop(outer);
+        assert_eq!(Scope::current().0, None);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512.0 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 << 30), "5.0 GiB");
+    }
+}
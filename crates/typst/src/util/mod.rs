@@ -1,6 +1,7 @@
 //! Utilities.
 
 pub mod fat;
+pub mod memory;
 
 #[macro_use]
 mod macros;
@@ -6,6 +6,7 @@ use std::ops::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
 };
 
+use crate::foundations::cast;
 use crate::util::Numeric;
 
 /// A 64-bit float that implements `Eq`, `Ord` and `Hash`.
@@ -48,6 +49,12 @@ const fn is_nan(x: f64) -> bool {
     (x_bits << 1 >> (64 - 12 + 1)) == 0b0_111_1111_1111 && (x_bits << 12) != 0
 }
 
+cast! {
+    Scalar,
+    self => self.get().into_value(),
+    v: f64 => Self::new(v),
+}
+
 impl Numeric for Scalar {
     fn zero() -> Self {
         Self(0.0)
@@ -0,0 +1,328 @@
+use std::f64::consts::FRAC_PI_2;
+
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{elem, Packed, Smart, StyleChain};
+use crate::layout::{
+    Abs, Angle, Frame, FrameItem, LayoutSingle, Length, Point, Regions, Size,
+};
+use crate::syntax::Span;
+use crate::util::Numeric;
+use crate::visualize::{FixedStroke, Geometry, Paint, Path, Shape, Stroke};
+
+/// A circular arc.
+///
+/// Angles are measured clockwise, starting from the positive x-axis. The
+/// resulting frame always has the size of the full circle, so that multiple
+/// arcs, pies and rings with the same radius line up when they are
+/// [placed]($place) on top of each other.
+///
+/// # Example
+/// ```example
+/// #arc(radius: 20pt, start: 0deg, end: 135deg)
+/// #arc(
+///   radius: 20pt,
+///   start: -90deg,
+///   end: 90deg,
+///   stroke: 3pt + blue,
+/// )
+/// ```
+#[elem(LayoutSingle)]
+pub struct ArcElem {
+    /// The radius of the circle the arc lies on.
+    #[resolve]
+    #[default(Abs::pt(15.0).into())]
+    pub radius: Length,
+
+    /// The angle at which the arc starts.
+    pub start: Angle,
+
+    /// The angle at which the arc ends.
+    ///
+    /// If the arc spans more than a full turn, it is drawn as a full circle.
+    #[default(Angle::deg(90.0))]
+    pub end: Angle,
+
+    /// How to fill the arc. The fill area is closed with a straight line from
+    /// the end to the start of the arc.
+    ///
+    /// When setting a fill, the default stroke disappears. To create an arc
+    /// with both fill and stroke, you have to configure both.
+    pub fill: Option<Paint>,
+
+    /// How to [stroke] the arc.
+    ///
+    /// Can be set to `{none}` to disable the stroke or to `{auto}` for a
+    /// stroke of `{1pt}` black if and if only if no fill is given.
+    #[resolve]
+    #[fold]
+    pub stroke: Smart<Option<Stroke>>,
+}
+
+impl LayoutSingle for Packed<ArcElem> {
+    #[typst_macros::time(name = "arc", span = self.span())]
+    fn layout(
+        &self,
+        _: &mut Engine,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Frame> {
+        let radius = self.radius(styles);
+        let (start, end) = (self.start(styles), self.end(styles));
+
+        let center = Point::splat(radius);
+        let mut path = Path::new();
+        path.move_to(arc_point(center, radius, start));
+        arc_to(&mut path, center, radius, start, end);
+
+        layout_round(
+            path,
+            radius,
+            self.fill(styles),
+            self.stroke(styles),
+            "arc",
+            self.span(),
+        )
+    }
+}
+
+/// A circular sector, as used in pie charts.
+///
+/// Angles are measured clockwise, starting from the positive x-axis. The
+/// resulting frame always has the size of the full circle, so that the slices
+/// of a pie chart can simply be [placed]($place) on top of each other.
+///
+/// # Example
+/// ```example
+/// #let data = ((35%, red), (25%, blue), (40%, green))
+/// #box({
+///   let start = -90deg
+///   for (share, fill) in data {
+///     let end = start + share * 360deg
+///     place(pie(radius: 25pt, start: start, end: end, fill: fill))
+///     start = end
+///   }
+///   h(50pt)
+///   v(50pt)
+/// })
+/// ```
+#[elem(LayoutSingle)]
+pub struct PieElem {
+    /// The radius of the pie.
+    #[resolve]
+    #[default(Abs::pt(15.0).into())]
+    pub radius: Length,
+
+    /// The angle at which the slice starts.
+    pub start: Angle,
+
+    /// The angle at which the slice ends.
+    ///
+    /// If the slice spans more than a full turn, a full disk is drawn.
+    #[default(Angle::deg(90.0))]
+    pub end: Angle,
+
+    /// How to fill the pie slice. See the
+    /// [rectangle's documentation]($rect.fill) for more details.
+    pub fill: Option<Paint>,
+
+    /// How to stroke the pie slice. See the
+    /// [arc's documentation]($arc.stroke) for more details.
+    #[resolve]
+    #[fold]
+    pub stroke: Smart<Option<Stroke>>,
+}
+
+impl LayoutSingle for Packed<PieElem> {
+    #[typst_macros::time(name = "pie", span = self.span())]
+    fn layout(
+        &self,
+        _: &mut Engine,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Frame> {
+        let radius = self.radius(styles);
+        let (start, end) = (self.start(styles), self.end(styles));
+
+        let center = Point::splat(radius);
+        let mut path = Path::new();
+        if is_full_turn(start, end) {
+            path.move_to(arc_point(center, radius, start));
+            arc_to(&mut path, center, radius, start, end);
+        } else {
+            path.move_to(center);
+            path.line_to(arc_point(center, radius, start));
+            arc_to(&mut path, center, radius, start, end);
+        }
+        path.close_path();
+
+        layout_round(
+            path,
+            radius,
+            self.fill(styles),
+            self.stroke(styles),
+            "pie",
+            self.span(),
+        )
+    }
+}
+
+/// A circular ring or a segment of it.
+///
+/// Angles are measured clockwise, starting from the positive x-axis. The
+/// resulting frame always has the size of the outer circle.
+///
+/// # Example
+/// ```example
+/// #ring(inner: 10pt, outer: 20pt, fill: blue)
+/// #ring(
+///   inner: 15pt,
+///   outer: 20pt,
+///   start: -90deg,
+///   end: 150deg,
+///   fill: red,
+/// )
+/// ```
+#[elem(LayoutSingle)]
+pub struct RingElem {
+    /// The radius of the ring's inner boundary.
+    #[resolve]
+    #[default(Abs::pt(7.5).into())]
+    pub inner: Length,
+
+    /// The radius of the ring's outer boundary.
+    #[resolve]
+    #[default(Abs::pt(15.0).into())]
+    pub outer: Length,
+
+    /// The angle at which the ring segment starts.
+    pub start: Angle,
+
+    /// The angle at which the ring segment ends. By default, the ring is
+    /// closed.
+    #[default(Angle::deg(360.0))]
+    pub end: Angle,
+
+    /// How to fill the ring. See the
+    /// [rectangle's documentation]($rect.fill) for more details.
+    pub fill: Option<Paint>,
+
+    /// How to stroke the ring. See the
+    /// [arc's documentation]($arc.stroke) for more details.
+    #[resolve]
+    #[fold]
+    pub stroke: Smart<Option<Stroke>>,
+}
+
+impl LayoutSingle for Packed<RingElem> {
+    #[typst_macros::time(name = "ring", span = self.span())]
+    fn layout(
+        &self,
+        _: &mut Engine,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Frame> {
+        let inner = self.inner(styles);
+        let outer = self.outer(styles);
+        let (start, end) = (self.start(styles), self.end(styles));
+        if inner > outer {
+            bail!(self.span(), "inner radius of ring must not exceed outer radius");
+        }
+
+        // The inner boundary is traversed in the opposite direction, so that
+        // the non-zero winding rule leaves the hole unfilled.
+        let center = Point::splat(outer);
+        let mut path = Path::new();
+        path.move_to(arc_point(center, outer, start));
+        arc_to(&mut path, center, outer, start, end);
+        if is_full_turn(start, end) {
+            path.close_path();
+            path.move_to(arc_point(center, inner, end));
+        } else {
+            path.line_to(arc_point(center, inner, end));
+        }
+        arc_to(&mut path, center, inner, end, start);
+        path.close_path();
+
+        layout_round(
+            path,
+            outer,
+            self.fill(styles),
+            self.stroke(styles),
+            "ring",
+            self.span(),
+        )
+    }
+}
+
+/// Lay out a path inscribed into a circle of the given radius.
+fn layout_round(
+    path: Path,
+    radius: Abs,
+    fill: Option<Paint>,
+    stroke: Smart<Option<Stroke<Abs>>>,
+    name: &str,
+    span: Span,
+) -> SourceResult<Frame> {
+    let size = Size::splat(2.0 * radius);
+    if !size.is_finite() {
+        bail!(span, "cannot create {name} with infinite radius");
+    }
+
+    let stroke = match stroke {
+        Smart::Auto if fill.is_none() => Some(FixedStroke::default()),
+        Smart::Auto => None,
+        Smart::Custom(stroke) => stroke.map(Stroke::unwrap_or_default),
+    };
+
+    let mut frame = Frame::soft(size);
+    if radius > Abs::zero() {
+        let shape = Shape { geometry: Geometry::Path(path), stroke, fill };
+        frame.push(Point::zero(), FrameItem::Shape(shape, span));
+    }
+
+    Ok(frame)
+}
+
+/// Whether the arc from `start` to `end` covers the full circle.
+fn is_full_turn(start: Angle, end: Angle) -> bool {
+    (end - start).abs() >= Angle::deg(360.0)
+}
+
+/// The point at the given angle on the circle around `center`.
+pub(crate) fn arc_point(center: Point, radius: Abs, angle: Angle) -> Point {
+    center + Point::new(radius * angle.cos(), radius * angle.sin())
+}
+
+/// Extend the path with a circular arc around `center`, assuming that the path
+/// currently ends at the arc's start point.
+///
+/// The arc is approximated with one cubic bezier curve per quarter turn.
+pub(crate) fn arc_to(
+    path: &mut Path,
+    center: Point,
+    radius: Abs,
+    start: Angle,
+    end: Angle,
+) {
+    let sweep = (end - start).to_rad().clamp(-4.0 * FRAC_PI_2, 4.0 * FRAC_PI_2);
+    if sweep == 0.0 {
+        return;
+    }
+
+    let count = (sweep.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = sweep / count as f64;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let tangent = |angle: f64| Point::new(radius * -angle.sin(), radius * angle.cos());
+
+    let mut a0 = start.to_rad();
+    for _ in 0..count {
+        let a1 = a0 + step;
+        let p0 = arc_point(center, radius, Angle::rad(a0));
+        let p3 = arc_point(center, radius, Angle::rad(a1));
+        let c1 = p0 + tangent(a0) * k;
+        let c2 = p3 - tangent(a1) * k;
+        path.cubic_to(c1, c2, p3);
+        a0 = a1;
+    }
+}
@@ -0,0 +1,300 @@
+use ecow::EcoString;
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{elem, Cast, Packed, Repr, Smart, Str, StyleChain};
+use crate::layout::{
+    Abs, Axes, Frame, FrameItem, LayoutMultiple, LayoutSingle, Length, Point, Regions,
+    Rel, Size,
+};
+use crate::text::TextElem;
+use crate::util::Numeric;
+use crate::visualize::{Color, Geometry, Paint, Path};
+
+/// A one-dimensional barcode.
+///
+/// The bars are drawn with crisp vector shapes, so the code stays sharp in
+/// print at any size. A quiet zone of ten modules is kept free on both sides.
+///
+/// # Example
+/// ```example
+/// #barcode("Typst 0.11")
+/// #barcode(kind: "ean13", "400638133393")
+/// ```
+#[elem(LayoutSingle)]
+pub struct BarcodeElem {
+    /// The symbology to use.
+    ///
+    /// - Code 128 can encode any printable ASCII text.
+    /// - EAN-13 encodes exactly twelve digits plus a check digit. If only
+    ///   twelve digits are given, the check digit is computed automatically.
+    ///   Otherwise, it is validated.
+    #[default(BarcodeKind::Code128)]
+    pub kind: BarcodeKind,
+
+    /// The data to encode.
+    #[required]
+    pub data: Str,
+
+    /// The width of the barcode, including the quiet zones.
+    ///
+    /// When set to `{auto}`, each module (the narrowest bar) is 0.33mm wide.
+    #[resolve]
+    pub width: Smart<Rel<Length>>,
+
+    /// The height of the bars.
+    #[resolve]
+    #[default(Abs::cm(1.5).into())]
+    pub height: Rel<Length>,
+
+    /// Whether to display the encoded data in human-readable form below the
+    /// bars.
+    ///
+    /// ```example
+    /// #barcode("HELLO", text: true)
+    /// ```
+    #[default(false)]
+    pub text: bool,
+
+    /// How to fill the bars.
+    #[default(Color::BLACK.into())]
+    pub fill: Paint,
+}
+
+impl LayoutSingle for Packed<BarcodeElem> {
+    #[typst_macros::time(name = "barcode", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Frame> {
+        let kind = self.kind(styles);
+        let (modules, readable) = kind.encode(self.data()).at(self.span())?;
+
+        let quiet = 10;
+        let count = modules.len() + 2 * quiet;
+        let width = match self.width(styles) {
+            Smart::Auto => Abs::mm(0.33) * count as f64,
+            Smart::Custom(width) => width.relative_to(regions.base().x),
+        };
+        let height = self.height(styles).relative_to(regions.base().y);
+        if !width.is_finite() || !height.is_finite() {
+            bail!(self.span(), "cannot create barcode with infinite size");
+        }
+
+        // Draw all bars as a single path, so that adjacent bars of the same
+        // color have no seams between them.
+        let module = width / count as f64;
+        let mut path = Path::new();
+        let mut x = 0;
+        while x < modules.len() {
+            if !modules[x] {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            while x < modules.len() && modules[x] {
+                x += 1;
+            }
+            let left = module * (start + quiet) as f64;
+            let right = module * (x + quiet) as f64;
+            path.move_to(Point::with_x(left));
+            path.line_to(Point::with_x(right));
+            path.line_to(Point::new(right, height));
+            path.line_to(Point::new(left, height));
+            path.close_path();
+        }
+
+        let mut frame = Frame::soft(Size::new(width, height));
+        let shape = Geometry::Path(path).filled(self.fill(styles));
+        frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+
+        if self.text(styles) {
+            let pod = Regions::one(regions.base(), Axes::splat(false));
+            let text = TextElem::packed(readable).spanned(self.span());
+            let label = text.layout(engine, styles, pod)?.into_frame();
+            let x = (width - label.width()) / 2.0;
+            frame.size_mut().y += label.height();
+            frame.push_frame(Point::new(x, height), label);
+        }
+
+        Ok(frame)
+    }
+}
+
+/// A barcode symbology.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum BarcodeKind {
+    /// Code 128, for arbitrary printable ASCII text.
+    #[string("code128")]
+    Code128,
+    /// EAN-13, the international article number.
+    #[string("ean13")]
+    Ean13,
+}
+
+impl BarcodeKind {
+    /// Encode the data into a sequence of modules (`true` for a bar) and the
+    /// human-readable text.
+    fn encode(self, data: &str) -> StrResult<(Vec<bool>, EcoString)> {
+        match self {
+            Self::Code128 => Ok((encode_code128(data)?, data.into())),
+            Self::Ean13 => encode_ean13(data),
+        }
+    }
+}
+
+/// The bar and space widths of the Code 128 symbols, indexed by value.
+const CODE128_PATTERNS: [&str; 103] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312",
+    "132212", "221213", "221312", "231212", "112232", "122132", "122231", "113222",
+    "123122", "123221", "223211", "221132", "221231", "213212", "223112", "312131",
+    "311222", "321122", "321221", "312212", "322112", "322211", "212123", "212321",
+    "232121", "111323", "131123", "131321", "112313", "132113", "132311", "211313",
+    "231113", "231311", "112133", "112331", "132131", "113123", "113321", "133121",
+    "313121", "211331", "231131", "213113", "213311", "213131", "311123", "311321",
+    "331121", "312113", "312311", "332111", "314111", "221411", "431111", "111224",
+    "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111",
+    "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112",
+    "421211", "212141", "214121", "412121", "111143", "111341", "131141", "114113",
+    "114311", "411113", "411311", "113141", "114131", "311141", "411131",
+];
+
+const CODE128_START_B: usize = 104;
+const CODE128_START_C: usize = 105;
+const CODE128_CODE_B: usize = 100;
+const CODE128_CODE_C: usize = 99;
+
+/// Encode printable ASCII text as Code 128, switching to the denser code set
+/// C for longer runs of digits.
+fn encode_code128(data: &str) -> StrResult<Vec<bool>> {
+    if data.is_empty() {
+        bail!("barcode data must not be empty");
+    }
+    if let Some(c) = data.chars().find(|c| !matches!(c, ' '..='~')) {
+        bail!(
+            "barcode data must be printable ASCII, found {}",
+            EcoString::from(c).repr()
+        );
+    }
+
+    let bytes = data.as_bytes();
+    let digits_at =
+        |i: usize| bytes[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+
+    let mut values = vec![];
+    let mut in_c = digits_at(0) >= 4;
+    values.push(if in_c { CODE128_START_C } else { CODE128_START_B });
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let digits = digits_at(i);
+        if in_c {
+            if digits >= 2 {
+                values.push(usize::from((bytes[i] - b'0') * 10 + (bytes[i + 1] - b'0')));
+                i += 2;
+                continue;
+            }
+            values.push(CODE128_CODE_B);
+            in_c = false;
+        } else if digits >= 6 || (digits >= 4 && i + digits == bytes.len()) {
+            // With an odd number of digits, the first one stays in code set B.
+            if digits % 2 == 1 {
+                values.push(usize::from(bytes[i] - b' '));
+                i += 1;
+            }
+            values.push(CODE128_CODE_C);
+            in_c = true;
+            continue;
+        }
+        values.push(usize::from(bytes[i] - b' '));
+        i += 1;
+    }
+
+    let checksum =
+        values.iter().enumerate().map(|(i, &v)| v * i.max(1)).sum::<usize>() % 103;
+    values.push(checksum);
+
+    let mut modules = vec![];
+    let mut push = |pattern: &str| {
+        for (i, width) in pattern.bytes().enumerate() {
+            let bar = i % 2 == 0;
+            modules.extend(std::iter::repeat(bar).take(usize::from(width - b'0')));
+        }
+    };
+    for v in values {
+        push(match v {
+            CODE128_START_B => "211214",
+            CODE128_START_C => "211232",
+            v => CODE128_PATTERNS[v],
+        });
+    }
+    push("2331112");
+    Ok(modules)
+}
+
+/// The left-hand odd parity (L) codes of the EAN-13 digits. The right-hand
+/// (R) codes are their complements and the even parity (G) codes are the
+/// reversed R codes.
+const EAN_L_CODES: [&str; 10] = [
+    "0001101", "0011001", "0010011", "0111101", "0100011", "0110001", "0101111",
+    "0111011", "0110111", "0001011",
+];
+
+/// Which of the left-hand digits use G codes, determined by the first digit.
+const EAN_PARITIES: [&str; 10] = [
+    "LLLLLL", "LLGLGG", "LLGGLG", "LLGGGL", "LGLLGG", "LGGLLG", "LGGGLL", "LGLGLG",
+    "LGLGGL", "LGGLGL",
+];
+
+/// Encode twelve or thirteen digits as EAN-13.
+fn encode_ean13(data: &str) -> StrResult<(Vec<bool>, EcoString)> {
+    if !data.bytes().all(|b| b.is_ascii_digit()) || !matches!(data.len(), 12 | 13) {
+        bail!("EAN-13 barcode data must consist of 12 or 13 digits");
+    }
+
+    let mut digits: Vec<usize> = data.bytes().map(|b| usize::from(b - b'0')).collect();
+    let sum: usize = digits[..12]
+        .iter()
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { *d } else { 3 * d })
+        .sum();
+    let check = (10 - sum % 10) % 10;
+    match digits.get(12) {
+        Some(&digit) if digit != check => {
+            bail!("EAN-13 check digit is {digit}, but should be {check}")
+        }
+        Some(_) => {}
+        None => digits.push(check),
+    }
+
+    let mut modules = vec![];
+    let mut push = |pattern: &str| modules.extend(pattern.bytes().map(|b| b == b'1'));
+    push("101");
+    for (i, &d) in digits[1..7].iter().enumerate() {
+        if EAN_PARITIES[digits[0]].as_bytes()[i] == b'L' {
+            push(EAN_L_CODES[d]);
+        } else {
+            let g: String = EAN_L_CODES[d]
+                .chars()
+                .rev()
+                .map(|c| if c == '0' { '1' } else { '0' })
+                .collect();
+            push(&g);
+        }
+    }
+    push("01010");
+    for &d in &digits[7..] {
+        let r: String = EAN_L_CODES[d]
+            .chars()
+            .map(|c| if c == '0' { '1' } else { '0' })
+            .collect();
+        push(&r);
+    }
+    push("101");
+
+    let text = digits.iter().map(|d| char::from(b'0' + *d as u8)).collect();
+    Ok((modules, text))
+}
@@ -0,0 +1,223 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Cast, Content, Packed, StyleChain};
+use crate::layout::{Axes, Frame, LayoutMultiple, LayoutSingle, Regions};
+
+/// Blends content with what is behind it.
+///
+/// The colors of the content are combined with the colors of everything below
+/// it according to a [blend mode]($blend.mode). This is useful for highlighter
+/// effects or for overlaying charts on top of each other.
+///
+/// # Example
+/// ```example
+/// #set page(height: 60pt)
+/// #place(rect(width: 60pt, height: 40pt, fill: aqua))
+/// #place(dx: 30pt, dy: 10pt, blend(
+///   rect(width: 60pt, height: 40pt, fill: yellow),
+/// ))
+/// ```
+#[elem(LayoutSingle)]
+pub struct BlendElem {
+    /// How to combine the colors of the content with what is behind it.
+    ///
+    /// ```example
+    /// #set page(width: 170pt)
+    /// #for mode in ("normal", "multiply", "screen", "difference") {
+    ///   box(inset: 2pt, {
+    ///     place(circle(radius: 12pt, fill: orange))
+    ///     place(dx: 12pt, blend(mode: mode, circle(radius: 12pt, fill: blue)))
+    ///     h(36pt)
+    ///     v(24pt)
+    ///   })
+    /// }
+    /// ```
+    #[default(BlendMode::Multiply)]
+    pub mode: BlendMode,
+
+    /// Whether to composite the content in isolation before blending it.
+    ///
+    /// If `{true}`, the content is first combined into a single layer, which
+    /// is then blended with what is behind it as a whole. Overlapping parts of
+    /// the content then don't blend with each other. If `{false}`, each part
+    /// of the content is blended individually with everything below it,
+    /// including earlier parts of the same content.
+    ///
+    /// ```example
+    /// #for isolate in (true, false) {
+    ///   box(blend(isolate: isolate, mode: "multiply", {
+    ///     place(circle(radius: 12pt, fill: aqua))
+    ///     place(dx: 12pt, circle(radius: 12pt, fill: yellow))
+    ///     h(36pt)
+    ///     v(24pt)
+    ///   }))
+    /// }
+    /// ```
+    #[default(true)]
+    pub isolate: bool,
+
+    /// The content to blend.
+    #[required]
+    pub body: Content,
+}
+
+impl LayoutSingle for Packed<BlendElem> {
+    #[typst_macros::time(name = "blend", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Frame> {
+        let pod = Regions::one(regions.base(), Axes::splat(false));
+        let mut frame = self.body().layout(engine, styles, pod)?.into_frame();
+        frame.blend(self.mode(styles), self.isolate(styles));
+        Ok(frame)
+    }
+}
+
+/// How the colors of content are combined with the colors behind it.
+///
+/// The separable modes operate on each color channel individually, while the
+/// last four modes operate on hue, saturation, and luminosity.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum BlendMode {
+    /// The content is simply drawn on top.
+    #[default]
+    Normal,
+    /// Multiplies the colors, which always results in a darker color. Useful
+    /// for highlighter effects.
+    Multiply,
+    /// Multiplies the complements of the colors, which always results in a
+    /// lighter color.
+    Screen,
+    /// Multiplies or screens the colors, depending on the backdrop color.
+    Overlay,
+    /// Selects the darker of the colors.
+    Darken,
+    /// Selects the lighter of the colors.
+    Lighten,
+    /// Brightens the backdrop to reflect the content's color.
+    ColorDodge,
+    /// Darkens the backdrop to reflect the content's color.
+    ColorBurn,
+    /// Multiplies or screens the colors, depending on the content's color.
+    HardLight,
+    /// Darkens or lightens the colors, depending on the content's color.
+    SoftLight,
+    /// Subtracts the darker from the lighter color.
+    Difference,
+    /// Like `difference`, but with lower contrast.
+    Exclusion,
+    /// Uses the hue of the content with the saturation and luminosity of the
+    /// backdrop.
+    Hue,
+    /// Uses the saturation of the content with the hue and luminosity of the
+    /// backdrop.
+    Saturation,
+    /// Uses the hue and saturation of the content with the luminosity of the
+    /// backdrop.
+    Color,
+    /// Uses the luminosity of the content with the hue and saturation of the
+    /// backdrop.
+    Luminosity,
+}
+
+impl BlendMode {
+    /// Blends the channels of a source color with those of a backdrop as
+    /// specified by the Compositing and Blending Level 1 specification. All
+    /// channels range from zero to one.
+    pub fn apply(self, backdrop: [f32; 3], source: [f32; 3]) -> [f32; 3] {
+        let (cb, cs) = (backdrop, source);
+        match self {
+            Self::Hue => set_lum(set_sat(cs, sat(cb)), lum(cb)),
+            Self::Saturation => set_lum(set_sat(cb, sat(cs)), lum(cb)),
+            Self::Color => set_lum(cs, lum(cb)),
+            Self::Luminosity => set_lum(cb, lum(cs)),
+            _ => [0, 1, 2].map(|i| self.apply_separable(cb[i], cs[i])),
+        }
+    }
+
+    /// Blends a single channel with a separable mode.
+    fn apply_separable(self, cb: f32, cs: f32) -> f32 {
+        let multiply = |cb: f32, cs: f32| cb * cs;
+        let screen = |cb: f32, cs: f32| cb + cs - cb * cs;
+        let hard_light = |cb: f32, cs: f32| {
+            if cs <= 0.5 {
+                multiply(cb, 2.0 * cs)
+            } else {
+                screen(cb, 2.0 * cs - 1.0)
+            }
+        };
+
+        match self {
+            Self::Multiply => multiply(cb, cs),
+            Self::Screen => screen(cb, cs),
+            Self::Overlay => hard_light(cs, cb),
+            Self::Darken => cb.min(cs),
+            Self::Lighten => cb.max(cs),
+            Self::ColorDodge if cb == 0.0 => 0.0,
+            Self::ColorDodge if cs >= 1.0 => 1.0,
+            Self::ColorDodge => (cb / (1.0 - cs)).min(1.0),
+            Self::ColorBurn if cb == 1.0 => 1.0,
+            Self::ColorBurn if cs <= 0.0 => 0.0,
+            Self::ColorBurn => 1.0 - ((1.0 - cb) / cs).min(1.0),
+            Self::HardLight => hard_light(cb, cs),
+            Self::SoftLight if cs <= 0.5 => cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb),
+            Self::SoftLight => {
+                let d = if cb <= 0.25 {
+                    ((16.0 * cb - 12.0) * cb + 4.0) * cb
+                } else {
+                    cb.sqrt()
+                };
+                cb + (2.0 * cs - 1.0) * (d - cb)
+            }
+            Self::Difference => (cb - cs).abs(),
+            Self::Exclusion => cb + cs - 2.0 * cb * cs,
+            _ => cs,
+        }
+    }
+}
+
+/// The luminosity of a color for the non-separable blend modes.
+fn lum([r, g, b]: [f32; 3]) -> f32 {
+    0.3 * r + 0.59 * g + 0.11 * b
+}
+
+/// Sets the luminosity of a color, clipping it back into the unit cube.
+fn set_lum(c: [f32; 3], l: f32) -> [f32; 3] {
+    let d = l - lum(c);
+    let c = c.map(|v| v + d);
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    c.map(|v| {
+        let mut v = v;
+        if n < 0.0 {
+            v = l + (v - l) * l / (l - n);
+        }
+        if x > 1.0 {
+            v = l + (v - l) * (1.0 - l) / (x - l);
+        }
+        v
+    })
+}
+
+/// The saturation of a color for the non-separable blend modes.
+fn sat([r, g, b]: [f32; 3]) -> f32 {
+    r.max(g).max(b) - r.min(g).min(b)
+}
+
+/// Sets the saturation of a color, keeping the order of its channels.
+fn set_sat(c: [f32; 3], s: f32) -> [f32; 3] {
+    let mut order = [0, 1, 2];
+    order.sort_by(|&a, &b| c[a].total_cmp(&c[b]));
+    let [min, mid, max] = order;
+
+    let mut out = [0.0; 3];
+    if c[max] > c[min] {
+        out[mid] = (c[mid] - c[min]) * s / (c[max] - c[min]);
+        out[max] = s;
+    }
+    out
+}
@@ -0,0 +1,54 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, Packed, StyleChain};
+use crate::layout::{Abs, Axes, Frame, LayoutMultiple, LayoutSingle, Length, Regions};
+
+/// Blurs content with a gaussian blur.
+///
+/// The blur does not affect layout: The content takes up the same space as if
+/// it was not blurred, but the blurred result may extend beyond it. This
+/// makes it possible to create frosted backgrounds and soft glows.
+///
+/// Since blurring is not natively supported by PDF, blurred content stays
+/// vector-based in PDF export and is faded out with a soft mask of its blurred
+/// shape instead. There, the blur thus doesn't extend beyond the content.
+///
+/// # Example
+/// ```example
+/// #box(inset: 8pt, {
+///   place(blur(4pt, circle(radius: 12pt, fill: blue)))
+///   circle(radius: 12pt, stroke: blue)
+/// })
+/// #box(inset: 8pt, {
+///   place(blur(2pt, text(fill: orange)[*Glow*]))
+///   text(fill: white)[*Glow*]
+/// })
+/// ```
+#[elem(LayoutSingle)]
+pub struct BlurElem {
+    /// The radius of the blur, that is, the standard deviation of the
+    /// gaussian distribution with which the content is blurred.
+    #[positional]
+    #[resolve]
+    #[default(Abs::pt(5.0).into())]
+    pub radius: Length,
+
+    /// The content to blur.
+    #[required]
+    pub body: Content,
+}
+
+impl LayoutSingle for Packed<BlurElem> {
+    #[typst_macros::time(name = "blur", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Frame> {
+        let pod = Regions::one(regions.base(), Axes::splat(false));
+        let mut frame = self.body().layout(engine, styles, pod)?.into_frame();
+        frame.blur(self.radius(styles));
+        Ok(frame)
+    }
+}
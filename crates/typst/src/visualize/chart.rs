@@ -0,0 +1,881 @@
+//! Charts for visualizing data.
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    array, cast, elem, repr, Array, Dict, IntoValue, Module, Packed, Resolve, Scope,
+    Smart, Str, StyleChain, Value,
+};
+use crate::layout::{
+    Abs, Angle, Axes, Frame, FrameItem, LayoutMultiple, LayoutSingle, Length, Point,
+    Ratio, Regions, Rel, Size,
+};
+use crate::syntax::Span;
+use crate::text::TextElem;
+use crate::util::{Numeric, Scalar};
+use crate::visualize::{
+    arc_point, arc_to, ellipse, Color, FixedStroke, Geometry, Gradient, Paint, Path,
+    RatioOrAngle, Stroke,
+};
+
+/// A module with chart definitions.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define_elem::<BarChartElem>();
+    scope.define_elem::<LineChartElem>();
+    scope.define_elem::<ScatterChartElem>();
+    scope.define_elem::<PieChartElem>();
+    Module::new("chart", scope)
+}
+
+/// A bar chart.
+///
+/// Each category of the data is displayed as a bar (or a group of bars, one
+/// per series) rising from the zero line.
+///
+/// # Example
+/// ```example
+/// #chart.bar(
+///   (apples: 12, pears: 7, plums: 9),
+///   height: 3cm,
+/// )
+///
+/// #chart.bar(
+///   (
+///     ("2022", (3, 5)),
+///     ("2023", (4, 6)),
+///     ("2024", (6, 5)),
+///   ),
+///   names: ("North", "South"),
+///   height: 3cm,
+/// )
+/// ```
+#[elem(name = "bar", title = "Bar Chart", LayoutSingle)]
+pub struct BarChartElem {
+    /// The data to display.
+    ///
+    /// Can be a dictionary mapping labels to values, an array of
+    /// `(label, value)` pairs, or an array of plain values, which are then
+    /// labelled with their position. Each value is either a single number or
+    /// an array of numbers, one for each series.
+    #[required]
+    pub data: ChartCategories,
+
+    /// The names of the series, shown in the legend.
+    pub names: Vec<Str>,
+
+    /// The width of the chart, including axes and legend.
+    #[resolve]
+    #[default(Rel::one())]
+    pub width: Rel<Length>,
+
+    /// The height of the chart, including axes and legend.
+    #[resolve]
+    #[default(Abs::cm(5.0).into())]
+    pub height: Rel<Length>,
+
+    /// The colors of the series. See the [line chart's
+    /// documentation]($chart.line.colors) for more details.
+    pub colors: ChartColors,
+
+    /// How to stroke the axes and ticks.
+    #[resolve]
+    #[fold]
+    pub stroke: Stroke,
+
+    /// The fraction of the space available per category that is filled with
+    /// bars.
+    #[default(Ratio::new(0.8))]
+    pub fill_ratio: Ratio,
+}
+
+impl LayoutSingle for Packed<BarChartElem> {
+    #[typst_macros::time(name = "chart.bar", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Frame> {
+        let span = self.span();
+        let size = chart_size(self.width(styles), self.height(styles), regions, span)?;
+        let data = self.data();
+        let series = data.0.iter().map(|(_, values)| values.len()).max().unwrap_or(0);
+
+        let values = data.0.iter().flat_map(|(_, values)| values).map(|v| v.get());
+        let (min, max) = bounds(values.chain([0.0]));
+        let y = Axis::new(min, max);
+
+        let colors = self.colors(styles);
+        let legend = legend(self.names(styles), &colors);
+        let x =
+            XAxis::Categories(data.0.iter().map(|(label, _)| label.clone()).collect());
+        let stroke = axis_stroke(self.stroke(styles));
+        let (mut frame, origin, plot) =
+            layout_axes(engine, styles, span, size, &stroke, &x, &y, &legend)?;
+
+        let slot = plot.x / data.0.len().max(1) as f64;
+        let group = slot * self.fill_ratio(styles).get().clamp(0.0, 1.0);
+        let width = group / series.max(1) as f64;
+        let baseline = y.position(0.0, plot.y);
+        for (i, (_, values)) in data.0.iter().enumerate() {
+            for (j, value) in values.iter().enumerate() {
+                let top = y.position(value.get(), plot.y);
+                let x = slot * i as f64 + (slot - group) / 2.0 + width * j as f64;
+                let pos = origin + Point::new(x, top.min(baseline));
+                let rect = Geometry::Rect(Size::new(width, (top - baseline).abs()));
+                let shape = rect.filled(colors.get(j, series));
+                frame.push(pos, FrameItem::Shape(shape, span));
+            }
+        }
+
+        Ok(frame)
+    }
+}
+
+/// A line chart.
+///
+/// Each series is drawn as a line through its points.
+///
+/// # Example
+/// ```example
+/// #chart.line(
+///   (1, 4, 2, 5, 3),
+///   ((0, 2), (2, 3), (4, 1)),
+///   names: ("Measured", "Predicted"),
+///   height: 3cm,
+/// )
+/// ```
+#[elem(name = "line", title = "Line Chart", LayoutSingle)]
+pub struct LineChartElem {
+    /// The series to display.
+    ///
+    /// Each series is an array of `(x, y)` pairs or an array of plain
+    /// `y` values, whose `x` values are then their indices.
+    #[variadic]
+    pub series: Vec<ChartSeries>,
+
+    /// The names of the series, shown in the legend.
+    pub names: Vec<Str>,
+
+    /// The width of the chart, including axes and legend.
+    #[resolve]
+    #[default(Rel::one())]
+    pub width: Rel<Length>,
+
+    /// The height of the chart, including axes and legend.
+    #[resolve]
+    #[default(Abs::cm(5.0).into())]
+    pub height: Rel<Length>,
+
+    /// The colors of the series.
+    ///
+    /// Can be an array of colors, which are used in turn, or a gradient,
+    /// which is sampled evenly. To color the series with one of the
+    /// predefined [color maps]($color.map), turn it into a gradient.
+    ///
+    /// ```example
+    /// #chart.line(
+    ///   ..range(6).map(i => range(8).map(x => x * i)),
+    ///   colors: gradient.linear(..color.map.viridis),
+    ///   height: 3cm,
+    /// )
+    /// ```
+    pub colors: ChartColors,
+
+    /// How to stroke the axes and ticks.
+    #[resolve]
+    #[fold]
+    pub stroke: Stroke,
+
+    /// The thickness of the lines.
+    #[resolve]
+    #[default(Abs::pt(1.5).into())]
+    pub thickness: Length,
+}
+
+impl LayoutSingle for Packed<LineChartElem> {
+    #[typst_macros::time(name = "chart.line", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Frame> {
+        let span = self.span();
+        let size = chart_size(self.width(styles), self.height(styles), regions, span)?;
+        let series = self.series();
+        let colors = self.colors(styles);
+        let legend = legend(self.names(styles), &colors);
+        let stroke = axis_stroke(self.stroke(styles));
+        let thickness = self.thickness(styles);
+
+        layout_xy_chart(
+            engine,
+            styles,
+            span,
+            size,
+            &stroke,
+            series,
+            0.0,
+            &legend,
+            |x, y| {
+                let mut frame = Frame::soft(Size::zero());
+                for (i, points) in series.iter().enumerate() {
+                    let mut path = Path::new();
+                    for (j, (px, py)) in points.0.iter().enumerate() {
+                        let point = Point::new(x(px.get()), y(py.get()));
+                        if j == 0 {
+                            path.move_to(point);
+                        } else {
+                            path.line_to(point);
+                        }
+                    }
+                    let stroke =
+                        FixedStroke::from_pair(colors.get(i, series.len()), thickness);
+                    let shape = Geometry::Path(path).stroked(stroke);
+                    frame.push(Point::zero(), FrameItem::Shape(shape, span));
+                }
+                frame
+            },
+        )
+    }
+}
+
+/// A scatter chart.
+///
+/// Each point of each series is drawn as a dot.
+///
+/// # Example
+/// ```example
+/// #chart.scatter(
+///   ((1, 2), (2, 3.5), (3, 3), (4, 5)),
+///   ((1, 4), (2, 1), (3.5, 2)),
+///   height: 3cm,
+/// )
+/// ```
+#[elem(name = "scatter", title = "Scatter Chart", LayoutSingle)]
+pub struct ScatterChartElem {
+    /// The series to display. See the [line chart's
+    /// documentation]($chart.line.series) for more details.
+    #[variadic]
+    pub series: Vec<ChartSeries>,
+
+    /// The names of the series, shown in the legend.
+    pub names: Vec<Str>,
+
+    /// The width of the chart, including axes and legend.
+    #[resolve]
+    #[default(Rel::one())]
+    pub width: Rel<Length>,
+
+    /// The height of the chart, including axes and legend.
+    #[resolve]
+    #[default(Abs::cm(5.0).into())]
+    pub height: Rel<Length>,
+
+    /// The colors of the series. See the [line chart's
+    /// documentation]($chart.line.colors) for more details.
+    pub colors: ChartColors,
+
+    /// How to stroke the axes and ticks.
+    #[resolve]
+    #[fold]
+    pub stroke: Stroke,
+
+    /// The radius of the dots.
+    #[resolve]
+    #[default(Abs::pt(2.0).into())]
+    pub radius: Length,
+}
+
+impl LayoutSingle for Packed<ScatterChartElem> {
+    #[typst_macros::time(name = "chart.scatter", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Frame> {
+        let span = self.span();
+        let size = chart_size(self.width(styles), self.height(styles), regions, span)?;
+        let series = self.series();
+        let colors = self.colors(styles);
+        let legend = legend(self.names(styles), &colors);
+        let stroke = axis_stroke(self.stroke(styles));
+        let radius = self.radius(styles);
+
+        layout_xy_chart(
+            engine,
+            styles,
+            span,
+            size,
+            &stroke,
+            series,
+            0.05,
+            &legend,
+            |x, y| {
+                let mut frame = Frame::soft(Size::zero());
+                for (i, points) in series.iter().enumerate() {
+                    let fill = colors.get(i, series.len());
+                    for (px, py) in &points.0 {
+                        let pos = Point::new(x(px.get()) - radius, y(py.get()) - radius);
+                        let shape =
+                            ellipse(Size::splat(2.0 * radius), Some(fill.clone()), None);
+                        frame.push(pos, FrameItem::Shape(shape, span));
+                    }
+                }
+                frame
+            },
+        )
+    }
+}
+
+/// A pie chart.
+///
+/// Each category of the data is displayed as a slice whose angle is
+/// proportional to its value. The slices start at the top and proceed
+/// clockwise.
+///
+/// # Example
+/// ```example
+/// #chart.pie(
+///   (rent: 900, food: 400, travel: 250),
+///   height: 3cm,
+/// )
+/// ```
+#[elem(name = "pie", title = "Pie Chart", LayoutSingle)]
+pub struct PieChartElem {
+    /// The data to display. See the [bar chart's
+    /// documentation]($chart.bar.data) for the supported formats. Each value
+    /// must be a single non-negative number.
+    #[required]
+    pub data: ChartCategories,
+
+    /// The width of the chart, including the legend.
+    #[resolve]
+    #[default(Rel::one())]
+    pub width: Rel<Length>,
+
+    /// The height of the chart.
+    #[resolve]
+    #[default(Abs::cm(5.0).into())]
+    pub height: Rel<Length>,
+
+    /// The colors of the slices. See the [line chart's
+    /// documentation]($chart.line.colors) for more details.
+    pub colors: ChartColors,
+
+    /// How to stroke the slices.
+    #[resolve]
+    #[fold]
+    pub stroke: Smart<Option<Stroke>>,
+
+    /// Whether to show the labels of the categories in a legend.
+    #[default(true)]
+    pub legend: bool,
+}
+
+impl LayoutSingle for Packed<PieChartElem> {
+    #[typst_macros::time(name = "chart.pie", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Frame> {
+        let span = self.span();
+        let size = chart_size(self.width(styles), self.height(styles), regions, span)?;
+        let data = &self.data().0;
+        let mut values = vec![];
+        for (_, value) in data {
+            match value.as_slice() {
+                [v] if v.get() >= 0.0 => values.push(v.get()),
+                _ => bail!(span, "pie chart values must be non-negative numbers"),
+            }
+        }
+
+        let colors = self.colors(styles);
+        let names = if self.legend(styles) {
+            data.iter().map(|(label, _)| label.clone().into()).collect()
+        } else {
+            vec![]
+        };
+
+        let mut frame = Frame::soft(size);
+        let legend = layout_legend(engine, styles, span, &legend(names, &colors))?;
+        let gap =
+            if legend.width().is_zero() { Abs::zero() } else { spacing(styles) * 2.0 };
+        let available = Size::new(size.x - legend.width() - gap, size.y);
+        let radius = available.x.min(available.y).max(Abs::zero()) / 2.0;
+        frame.push_frame(Point::with_x(size.x - legend.width()), legend);
+
+        let stroke = match self.stroke(styles) {
+            Smart::Auto => Some(FixedStroke::from_pair(Color::WHITE, Abs::pt(1.0))),
+            Smart::Custom(stroke) => stroke.map(Stroke::unwrap_or_default),
+        };
+
+        let total: f64 = values.iter().sum();
+        let center = Point::new(available.x / 2.0, size.y / 2.0);
+        let mut start = Angle::deg(-90.0);
+        for (i, value) in values.iter().enumerate() {
+            if total <= 0.0 || *value <= 0.0 {
+                continue;
+            }
+            let end = start + Angle::deg(360.0 * value / total);
+            let mut path = Path::new();
+            if *value < total {
+                path.move_to(center);
+                path.line_to(arc_point(center, radius, start));
+            } else {
+                path.move_to(arc_point(center, radius, start));
+            }
+            arc_to(&mut path, center, radius, start, end);
+            path.close_path();
+
+            let mut shape = Geometry::Path(path).filled(colors.get(i, values.len()));
+            shape.stroke.clone_from(&stroke);
+            frame.push(Point::zero(), FrameItem::Shape(shape, span));
+            start = end;
+        }
+
+        Ok(frame)
+    }
+}
+
+/// Labelled values for bar and pie charts.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct ChartCategories(Vec<(EcoString, Vec<Scalar>)>);
+
+cast! {
+    ChartCategories,
+    self => self
+        .0
+        .into_iter()
+        .map(|(label, values)| array![label, values_into_value(values)].into_value())
+        .collect::<Array>()
+        .into_value(),
+    dict: Dict => Self(dict
+        .into_iter()
+        .map(|(key, value)| Ok((key.into(), values_from_value(value)?)))
+        .collect::<StrResult<_>>()?),
+    array: Array => Self(array
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| match item {
+            Value::Array(pair) => {
+                let mut iter = pair.into_iter();
+                match (iter.next(), iter.next(), iter.next()) {
+                    (Some(label), Some(value), None) => {
+                        Ok((label.cast::<Str>()?.into(), values_from_value(value)?))
+                    }
+                    _ => bail!("labelled value must contain exactly two entries"),
+                }
+            }
+            value => Ok((eco_format!("{}", i + 1), vec![Scalar::new(value.cast()?)])),
+        })
+        .collect::<StrResult<_>>()?),
+}
+
+/// Convert a number or an array of numbers into values.
+fn values_from_value(value: Value) -> StrResult<Vec<Scalar>> {
+    match value {
+        Value::Array(array) => {
+            array.into_iter().map(|v| v.cast::<f64>().map(Scalar::new)).collect()
+        }
+        value => Ok(vec![Scalar::new(value.cast()?)]),
+    }
+}
+
+/// Convert values back into a number or an array of numbers.
+fn values_into_value(values: Vec<Scalar>) -> Value {
+    match values.as_slice() {
+        [value] => value.get().into_value(),
+        _ => values
+            .into_iter()
+            .map(|v| v.get().into_value())
+            .collect::<Array>()
+            .into_value(),
+    }
+}
+
+/// The points of a series in a line or scatter chart.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct ChartSeries(pub Vec<(Scalar, Scalar)>);
+
+cast! {
+    ChartSeries,
+    self => self
+        .0
+        .into_iter()
+        .map(|(x, y)| array![x.get(), y.get()].into_value())
+        .collect::<Array>()
+        .into_value(),
+    array: Array => Self(array
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| match item {
+            Value::Array(pair) => {
+                let mut iter = pair.into_iter();
+                match (iter.next(), iter.next(), iter.next()) {
+                    (Some(x), Some(y), None) => {
+                        Ok((Scalar::new(x.cast()?), Scalar::new(y.cast()?)))
+                    }
+                    _ => bail!("point must contain exactly two entries"),
+                }
+            }
+            value => Ok((Scalar::new(i as f64), Scalar::new(value.cast()?))),
+        })
+        .collect::<StrResult<_>>()?),
+}
+
+/// The colors of the series or categories in a chart.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum ChartColors {
+    /// Colors that are used in turn.
+    Colors(Vec<Color>),
+    /// A gradient that is sampled evenly.
+    Gradient(Gradient),
+}
+
+impl ChartColors {
+    /// The color of the `i`-th of `n` series.
+    fn get(&self, i: usize, n: usize) -> Paint {
+        match self {
+            Self::Colors(colors) => colors[i % colors.len()].into(),
+            Self::Gradient(gradient) => {
+                let t = if n > 1 { i as f64 / (n - 1) as f64 } else { 0.5 };
+                gradient.sample(RatioOrAngle::Ratio(Ratio::new(t))).into()
+            }
+        }
+    }
+}
+
+impl Default for ChartColors {
+    fn default() -> Self {
+        Self::Colors(vec![
+            Color::BLUE,
+            Color::RED,
+            Color::GREEN,
+            Color::ORANGE,
+            Color::PURPLE,
+            Color::TEAL,
+            Color::MAROON,
+            Color::OLIVE,
+        ])
+    }
+}
+
+cast! {
+    ChartColors,
+    self => match self {
+        Self::Colors(colors) => colors.into_value(),
+        Self::Gradient(gradient) => gradient.into_value(),
+    },
+    colors: Vec<Color> => {
+        if colors.is_empty() {
+            bail!("chart colors must not be empty");
+        }
+        Self::Colors(colors)
+    },
+    gradient: Gradient => Self::Gradient(gradient),
+}
+
+/// A numeric axis with evenly spaced ticks at round numbers.
+struct Axis {
+    min: f64,
+    max: f64,
+    step: f64,
+    ticks: Vec<f64>,
+}
+
+impl Axis {
+    /// Create an axis that covers the given range with about five ticks.
+    fn new(mut min: f64, mut max: f64) -> Self {
+        if max - min < f64::EPSILON * min.abs().max(1.0) {
+            min -= 1.0;
+            max += 1.0;
+        }
+
+        let raw = (max - min) / 5.0;
+        let magnitude = 10_f64.powf(raw.log10().floor());
+        let step = magnitude
+            * match raw / magnitude {
+                n if n <= 1.0 => 1.0,
+                n if n <= 2.0 => 2.0,
+                n if n <= 2.5 => 2.5,
+                n if n <= 5.0 => 5.0,
+                _ => 10.0,
+            };
+
+        let first = (min / step + 1e-9).floor() as i64;
+        let last = (max / step - 1e-9).ceil() as i64;
+        let ticks = (first..=last).map(|k| k as f64 * step).collect();
+        Self {
+            min: first as f64 * step,
+            max: last as f64 * step,
+            step,
+            ticks,
+        }
+    }
+
+    /// The distance of a value from the start of an axis of the given length.
+    fn offset(&self, value: f64, length: Abs) -> Abs {
+        length * ((value - self.min) / (self.max - self.min))
+    }
+
+    /// The vertical position of a value on an axis of the given height.
+    fn position(&self, value: f64, height: Abs) -> Abs {
+        height - self.offset(value, height)
+    }
+
+    /// The text for the tick at the given value.
+    fn label(&self, value: f64) -> EcoString {
+        let decimals = (0..10)
+            .find(|&d| {
+                let scaled = self.step * 10_f64.powi(d);
+                (scaled - scaled.round()).abs() < 1e-6
+            })
+            .unwrap_or(10);
+        let factor = 10_f64.powi(decimals);
+        let rounded = (value * factor).round() / factor;
+        repr::display_float(if rounded == 0.0 { 0.0 } else { rounded })
+    }
+}
+
+/// The horizontal axis of a chart.
+enum XAxis {
+    /// Labelled categories that evenly share the space.
+    Categories(Vec<EcoString>),
+    /// A numeric axis.
+    Numeric(Axis),
+}
+
+/// Resolve the size of a chart.
+fn chart_size(
+    width: Rel<Abs>,
+    height: Rel<Abs>,
+    regions: Regions,
+    span: Span,
+) -> SourceResult<Size> {
+    let size = Size::new(
+        width.relative_to(regions.base().x),
+        height.relative_to(regions.base().y),
+    );
+    if !size.is_finite() {
+        bail!(span, "cannot create chart with infinite size");
+    }
+    Ok(size)
+}
+
+/// The smallest and largest of the values, ignoring non-finite ones. Zero if
+/// there are no values.
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values
+        .filter(|v| v.is_finite())
+        .fold(None, |acc, v| match acc {
+            None => Some((v, v)),
+            Some((min, max)) => Some((v.min(min), v.max(max))),
+        })
+        .unwrap_or((0.0, 0.0))
+}
+
+/// The stroke of the axes, which is thinner than the default stroke.
+fn axis_stroke(stroke: Stroke<Abs>) -> FixedStroke {
+    stroke.unwrap_or(FixedStroke::from_pair(Color::BLACK, Abs::pt(0.5)))
+}
+
+/// The base spacing between the parts of a chart.
+fn spacing(styles: StyleChain) -> Abs {
+    Length::from(crate::layout::Em::new(0.3)).resolve(styles)
+}
+
+/// Pair series names with their colors.
+fn legend(names: Vec<Str>, colors: &ChartColors) -> Vec<(EcoString, Paint)> {
+    let n = names.len();
+    names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| (name.into(), colors.get(i, n)))
+        .collect()
+}
+
+/// Lay out a line or scatter chart, whose data is drawn by `draw` given
+/// functions mapping data coordinates to positions in the plot area.
+///
+/// The ranges of the data are extended by the `padding` fraction on each side
+/// before determining the axes.
+#[allow(clippy::too_many_arguments)]
+fn layout_xy_chart(
+    engine: &mut Engine,
+    styles: StyleChain,
+    span: Span,
+    size: Size,
+    stroke: &FixedStroke,
+    series: &[ChartSeries],
+    padding: f64,
+    legend: &[(EcoString, Paint)],
+    draw: impl FnOnce(&dyn Fn(f64) -> Abs, &dyn Fn(f64) -> Abs) -> Frame,
+) -> SourceResult<Frame> {
+    let points = || series.iter().flat_map(|s| &s.0);
+    let pad = |(min, max): (f64, f64)| {
+        let extra = (max - min) * padding;
+        (min - extra, max + extra)
+    };
+    let (x_min, x_max) = pad(bounds(points().map(|(x, _)| x.get())));
+    let (y_min, y_max) = pad(bounds(points().map(|(_, y)| y.get())));
+    let x = Axis::new(x_min, x_max);
+    let y = Axis::new(y_min, y_max);
+
+    let x_axis = XAxis::Numeric(x);
+    let (mut frame, origin, plot) =
+        layout_axes(engine, styles, span, size, stroke, &x_axis, &y, legend)?;
+    let XAxis::Numeric(x) = &x_axis else { unreachable!() };
+
+    let data = draw(&|v| x.offset(v, plot.x), &|v| y.position(v, plot.y));
+    frame.push_frame(origin, data);
+    Ok(frame)
+}
+
+/// Lay out the axes, ticks, and legend of a chart.
+///
+/// Returns the frame along with the position and size of the plot area.
+#[allow(clippy::too_many_arguments)]
+fn layout_axes(
+    engine: &mut Engine,
+    styles: StyleChain,
+    span: Span,
+    size: Size,
+    stroke: &FixedStroke,
+    x: &XAxis,
+    y: &Axis,
+    legend: &[(EcoString, Paint)],
+) -> SourceResult<(Frame, Point, Size)> {
+    let gap = spacing(styles);
+    let tick = gap;
+
+    let y_labels = y
+        .ticks
+        .iter()
+        .map(|&v| layout_text(engine, styles, span, y.label(v)))
+        .collect::<SourceResult<Vec<_>>>()?;
+    let x_labels = match x {
+        XAxis::Categories(labels) => labels
+            .iter()
+            .map(|label| layout_text(engine, styles, span, label.clone()))
+            .collect::<SourceResult<Vec<_>>>()?,
+        XAxis::Numeric(axis) => axis
+            .ticks
+            .iter()
+            .map(|&v| layout_text(engine, styles, span, axis.label(v)))
+            .collect::<SourceResult<Vec<_>>>()?,
+    };
+    let legend = layout_legend(engine, styles, span, legend)?;
+
+    let max_height =
+        |frames: &[Frame]| frames.iter().map(Frame::height).max().unwrap_or_default();
+    let y_label_width = y_labels.iter().map(Frame::width).max().unwrap_or_default();
+    let x_label_height = max_height(&x_labels);
+
+    // Leave room for the labels that overhang the plot area.
+    let top = max_height(&y_labels) / 2.0;
+    let left = y_label_width + gap + tick;
+    let mut right = Abs::zero();
+    if let (XAxis::Numeric(_), Some(last)) = (x, x_labels.last()) {
+        right = last.width() / 2.0;
+    }
+    if !legend.width().is_zero() {
+        right += 2.0 * gap + legend.width();
+    }
+    let bottom = tick + gap + x_label_height;
+
+    let origin = Point::new(left, top);
+    let plot = Size::new(size.x - left - right, size.y - top - bottom).max(Size::zero());
+
+    let mut frame = Frame::soft(size);
+    let line = |frame: &mut Frame, from: Point, delta: Point| {
+        let shape = Geometry::Line(delta).stroked(stroke.clone());
+        frame.push(from, FrameItem::Shape(shape, span));
+    };
+
+    // The vertical axis with its ticks.
+    line(&mut frame, origin, Point::with_y(plot.y));
+    for (&v, label) in y.ticks.iter().zip(y_labels) {
+        let pos = origin + Point::with_y(y.position(v, plot.y));
+        line(&mut frame, pos - Point::with_x(tick), Point::with_x(tick));
+        let at = pos - Point::new(tick + gap + label.width(), label.height() / 2.0);
+        frame.push_frame(at, label);
+    }
+
+    // The horizontal axis with its ticks.
+    let bottom_left = origin + Point::with_y(plot.y);
+    line(&mut frame, bottom_left, Point::with_x(plot.x));
+    let ticks: Vec<Abs> = match x {
+        XAxis::Categories(labels) => {
+            let slot = plot.x / labels.len().max(1) as f64;
+            (0..labels.len()).map(|i| slot * (i as f64 + 0.5)).collect()
+        }
+        XAxis::Numeric(axis) => {
+            axis.ticks.iter().map(|&v| axis.offset(v, plot.x)).collect()
+        }
+    };
+    for (x, label) in ticks.into_iter().zip(x_labels) {
+        let pos = bottom_left + Point::with_x(x);
+        line(&mut frame, pos, Point::with_y(tick));
+        let at = pos + Point::new(-label.width() / 2.0, tick + gap);
+        frame.push_frame(at, label);
+    }
+
+    frame.push_frame(Point::new(size.x - legend.width(), top), legend);
+    Ok((frame, origin, plot))
+}
+
+/// Lay out a legend with a color swatch in front of each name.
+fn layout_legend(
+    engine: &mut Engine,
+    styles: StyleChain,
+    span: Span,
+    entries: &[(EcoString, Paint)],
+) -> SourceResult<Frame> {
+    let gap = spacing(styles);
+    let swatch = Length::from(crate::layout::Em::new(0.7)).resolve(styles);
+
+    let mut rows = vec![];
+    let mut width = Abs::zero();
+    for (name, paint) in entries {
+        let label = layout_text(engine, styles, span, name.clone())?;
+        width.set_max(swatch + gap + label.width());
+        rows.push((label, paint));
+    }
+
+    let mut frame = Frame::soft(Size::with_x(width));
+    let mut y = Abs::zero();
+    for (label, paint) in rows {
+        let height = label.height().max(swatch);
+        let shape = Geometry::Rect(Size::splat(swatch)).filled(paint.clone());
+        frame.push(
+            Point::with_y(y + (height - swatch) / 2.0),
+            FrameItem::Shape(shape, span),
+        );
+        let at = Point::new(swatch + gap, y + (height - label.height()) / 2.0);
+        frame.push_frame(at, label);
+        y += height + gap;
+    }
+
+    frame.size_mut().y = (y - gap).max(Abs::zero());
+    Ok(frame)
+}
+
+/// Lay out a single line of text.
+fn layout_text(
+    engine: &mut Engine,
+    styles: StyleChain,
+    span: Span,
+    text: EcoString,
+) -> SourceResult<Frame> {
+    let pod = Regions::one(Axes::splat(Abs::inf()), Axes::splat(false));
+    let text = TextElem::packed(text).spanned(span);
+    Ok(text.layout(engine, styles, pod)?.into_frame())
+}
@@ -1,22 +1,32 @@
+use std::f32::consts::PI;
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
+use comemo::Tracked;
 use ecow::{eco_format, EcoString, EcoVec};
 use once_cell::sync::Lazy;
+use palette::convert::FromColorUnclamped;
 use palette::encoding::{self, Linear};
+use palette::white_point::D50;
 use palette::{
-    Alpha, Darken, Desaturate, FromColor, Lighten, OklabHue, RgbHue, Saturate, ShiftHue,
+    Alpha, Darken, Desaturate, FromColor, LabHue, Lighten, OklabHue, RgbHue, Saturate,
+    ShiftHue, Xyza,
 };
 use qcms::Profile;
 
 use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
 use crate::foundations::{
-    array, cast, func, repr, scope, ty, Args, Array, IntoValue, Module, Repr, Scope, Str,
-    Value,
+    array, cast, func, repr, scope, ty, Args, Array, Bytes, Cast, Context, Dict,
+    IntoValue, Module, Repr, Scope, Str, Value,
 };
 use crate::layout::{Angle, Ratio};
 use crate::syntax::{Span, Spanned};
+use crate::visualize::gradient::{process_stops, sample_stops};
+use crate::visualize::{
+    BlendMode, Easing, GradientStop, IccProfile, IccSpace, RenderingIntent,
+};
 
 // Type aliases for `palette` internal types in f32.
 pub type Oklab = palette::oklab::Oklaba<f32>;
@@ -25,7 +35,55 @@ pub type LinearRgb = palette::rgb::Rgba<Linear<encoding::Srgb>, f32>;
 pub type Rgb = palette::rgb::Rgba<encoding::Srgb, f32>;
 pub type Hsl = palette::hsl::Hsla<encoding::Srgb, f32>;
 pub type Hsv = palette::hsv::Hsva<encoding::Srgb, f32>;
+pub type Hwb = palette::hwb::Hwba<encoding::Srgb, f32>;
+pub type Okhsl = palette::okhsl::Okhsla<f32>;
+pub type Okhsv = palette::okhsv::Okhsva<f32>;
 pub type Luma = palette::luma::Lumaa<encoding::Srgb, f32>;
+pub type Lab = palette::lab::Laba<D50, f32>;
+pub type Lch = palette::lch::Lcha<D50, f32>;
+
+/// The matrix from linear sRGB to CIE XYZ relative to the D50 white point,
+/// including the Bradford chromatic adaptation from D65.
+const LINEAR_RGB_TO_XYZ_D50: [[f32; 3]; 3] = [
+    [0.4360657, 0.3851515, 0.1430785],
+    [0.2224932, 0.7168871, 0.0606198],
+    [0.0139239, 0.0970813, 0.7140994],
+];
+
+/// The inverse of [`LINEAR_RGB_TO_XYZ_D50`].
+const XYZ_D50_TO_LINEAR_RGB: [[f32; 3]; 3] = [
+    [3.1341358, -1.6173857, -0.4906624],
+    [-0.9787955, 1.9162542, 0.033443],
+    [0.0719554, -0.2289767, 1.405386],
+];
+
+/// The matrix from linear sRGB to CIE XYZ relative to the D65 white point.
+const LINEAR_RGB_TO_XYZ_D65: [[f32; 3]; 3] = [
+    [0.4124564, 0.3575761, 0.1804375],
+    [0.2126729, 0.7151522, 0.072175],
+    [0.0193339, 0.119192, 0.9503041],
+];
+
+/// The inverse of [`LINEAR_RGB_TO_XYZ_D65`].
+const XYZ_D65_TO_LINEAR_RGB: [[f32; 3]; 3] = [
+    [3.2404542, -1.5371385, -0.4985314],
+    [-0.969266, 1.8760108, 0.041556],
+    [0.0556434, -0.2040259, 1.0572252],
+];
+
+/// The matrix from linear Display P3 to linear sRGB.
+const LINEAR_P3_TO_LINEAR_RGB: [[f32; 3]; 3] = [
+    [1.2249401, -0.2249404, 0.0],
+    [-0.0420569, 1.0420571, 0.0],
+    [-0.0196376, -0.0786361, 1.0982735],
+];
+
+/// The inverse of [`LINEAR_P3_TO_LINEAR_RGB`].
+const LINEAR_RGB_TO_LINEAR_P3: [[f32; 3]; 3] = [
+    [0.8224621, 0.177538, 0.0],
+    [0.0331941, 0.9668058, 0.0],
+    [0.0170827, 0.0723974, 0.9105199],
+];
 
 /// The ICC profile used to convert from CMYK to RGB.
 ///
@@ -37,7 +95,7 @@ static CMYK_TO_XYZ: Lazy<Box<Profile>> =
     Lazy::new(|| Profile::new_from_slice(typst_assets::icc::CMYK_TO_XYZ, false).unwrap());
 
 /// The target sRGB profile.
-static SRGB_PROFILE: Lazy<Box<Profile>> = Lazy::new(|| {
+pub(super) static SRGB_PROFILE: Lazy<Box<Profile>> = Lazy::new(|| {
     let mut out = Profile::new_sRGB();
     out.precache_output_transform();
     out
@@ -59,13 +117,21 @@ static TO_SRGB: Lazy<qcms::Transform> = Lazy::new(|| {
 ///
 /// Typst supports:
 /// - sRGB through the [`rgb` function]($color.rgb)
+/// - Display P3 through the [`color.p3` function]($color.p3)
 /// - Device CMYK through [`cmyk` function]($color.cmyk)
 /// - D65 Gray through the [`luma` function]($color.luma)
 /// - Oklab through the [`oklab` function]($color.oklab)
 /// - Oklch through the [`oklch` function]($color.oklch)
+/// - CIELAB through the [`color.lab` function]($color.lab)
+/// - CIELCh through the [`color.lch` function]($color.lch)
+/// - CIE XYZ through the [`color.xyz` function]($color.xyz)
 /// - Linear RGB through the [`color.linear-rgb` function]($color.linear-rgb)
 /// - HSL through the [`color.hsl` function]($color.hsl)
 /// - HSV through the [`color.hsv` function]($color.hsv)
+/// - HWB through the [`color.hwb` function]($color.hwb)
+/// - OkHSL through the [`color.okhsl` function]($color.okhsl)
+/// - OkHSV through the [`color.okhsv` function]($color.okhsv)
+/// - ICC profiles through the [`color.icc` function]($color.icc)
 ///
 ///
 /// # Example
@@ -74,6 +140,30 @@ static TO_SRGB: Lazy<qcms::Transform> = Lazy::new(|| {
 /// #rect(fill: aqua)
 /// ```
 ///
+/// # Extended range
+/// Colors in spaces without a bounded gamut, like [Oklab]($color.oklab),
+/// [Oklch]($color.oklch), [CIELAB]($color.lab), and [CIE XYZ]($color.xyz),
+/// can describe colors that no screen or printer can reproduce. Typst keeps
+/// such colors in their extended range as long as they stay in these spaces,
+/// so that mixing them or building gradients with them loses no information.
+/// Their components must be finite, though.
+///
+/// Extended-range colors are only clamped at the following points:
+/// - When they are converted to a space with a bounded gamut, for example
+///   with [`rgb`]($color.rgb) or [`to-space`]($color.to-space). The latter
+///   lets you choose how the color is mapped into the gamut.
+/// - When they are exported. PDF files keep colors in wide-gamut spaces where
+///   possible, while PNG and SVG output is limited to sRGB.
+///
+/// You can check whether a color fits into a space with
+/// [`in-gamut`]($color.in-gamut).
+///
+/// ```example
+/// #let c = oklch(60%, 0.35, 30deg)
+/// #c.in-gamut(rgb) \
+/// #c.to-space(rgb, gamut-map: "css").in-gamut(rgb)
+/// ```
+///
 /// # Predefined colors
 /// Typst defines the following built-in colors:
 ///
@@ -127,6 +217,17 @@ static TO_SRGB: Lazy<qcms::Transform> = Lazy::new(|| {
 /// )
 /// ```
 ///
+/// # Web colors
+/// Typst's predefined colors differ from the identically named colors of the
+/// web. All 148 named colors of CSS are available in the module `color.web`
+/// with their CSS values, which is useful when porting themes from HTML and
+/// CSS.
+///
+/// ```example
+/// #square(fill: color.web.cornflowerblue)
+/// #(color.web.red == rgb("#ff0000"))
+/// ```
+///
 /// # Predefined color maps
 /// Typst also includes a number of preset color maps that can be used for
 /// [gradients]($gradient.linear). These are simply arrays of colors defined in
@@ -159,6 +260,19 @@ static TO_SRGB: Lazy<qcms::Transform> = Lazy::new(|| {
 /// are not included because they are not color blind friendly. Feel free to use
 /// or create a package with other presets that are useful to you!
 ///
+/// You can also build your own color map from a few stops with
+/// [`color.map.custom`]($color.map.custom). The result is an array just like
+/// the presets, so you can reuse it by binding it to a name, for instance in
+/// your template.
+///
+/// ```example
+/// #let sunset = color.map.custom(navy, purple, orange, yellow)
+/// #rect(width: 100%, fill: gradient.linear(..sunset))
+/// ```
+///
+/// To pick a single color from a map, for instance to color a table cell by
+/// its value, use [`color.map.sample`]($color.map.sample).
+///
 /// ```preview
 /// #set page(width: auto, height: auto)
 /// #set text(font: "PT Sans", size: 8pt)
@@ -196,8 +310,16 @@ pub enum Color {
     Oklab(Oklab),
     /// A 32-bit LCh color in the Oklab color space.
     Oklch(Oklch),
+    /// A 32-bit L\*a\*b\* color in the CIELAB color space.
+    Lab(Lab),
+    /// A 32-bit LCh color in the CIELAB color space.
+    Lch(Lch),
+    /// A 32-bit color in the CIE XYZ color space.
+    Xyz(Xyz),
     /// A 32-bit RGB color.
     Rgb(Rgb),
+    /// A 32-bit RGB color in the Display P3 color space.
+    P3(P3),
     /// A 32-bit linear RGB color.
     LinearRgb(LinearRgb),
     /// A 32-bit CMYK color.
@@ -206,6 +328,14 @@ pub enum Color {
     Hsl(Hsl),
     /// A 32-bit HSV color.
     Hsv(Hsv),
+    /// A 32-bit HWB color.
+    Hwb(Hwb),
+    /// A 32-bit HSL color in the Oklab color space.
+    Okhsl(Okhsl),
+    /// A 32-bit HSV color in the Oklab color space.
+    Okhsv(Okhsv),
+    /// A 32-bit color in the color space of an ICC profile.
+    Icc(Icc),
 }
 
 #[scope]
@@ -217,6 +347,13 @@ impl Color {
         MODULE.clone()
     };
 
+    /// The module of named CSS colors.
+    pub const WEB: fn() -> Module = || {
+        // Lazy to avoid re-allocating.
+        static MODULE: Lazy<Module> = Lazy::new(web);
+        MODULE.clone()
+    };
+
     pub const BLACK: Self = Self::Luma(Luma::new(0.0, 1.0));
     pub const GRAY: Self = Self::Luma(Luma::new(0.6666666, 1.0));
     pub const WHITE: Self = Self::Luma(Luma::new(1.0, 1.0));
@@ -248,6 +385,10 @@ impl Color {
     /// #for x in range(250, step: 50) {
     ///   box(square(fill: luma(x)))
     /// }
+    ///
+    /// #for x in range(0, 101, step: 20) {
+    ///   box(square(fill: luma(x * 1%, linear: true)))
+    /// }
     /// ```
     #[func]
     pub fn luma(
@@ -265,7 +406,21 @@ impl Color {
         /// If this is given, the `lightness` should not be given.
         #[external]
         color: Color,
+        /// Whether the lightness is given as linear light instead of being
+        /// gamma-encoded.
+        ///
+        /// Linear lightness is proportional to the emitted amount of light, so
+        /// `{luma(50%, linear: true)}` emits half as much light as white. This
+        /// is useful for grayscale ramps in scientific figures. The lightness
+        /// is converted to the regular gamma-encoded representation.
+        ///
+        /// Has no effect if a color is given.
+        #[external]
+        #[named]
+        #[default(false)]
+        linear: bool,
     ) -> SourceResult<Color> {
+        let linear = args.named::<bool>("linear")?.unwrap_or(false);
         Ok(if let Some(color) = args.find::<Color>()? {
             color.to_luma()
         } else {
@@ -273,7 +428,11 @@ impl Color {
                 args.expect("gray component").unwrap_or(Component(Ratio::one()));
             let RatioComponent(alpha) =
                 args.eat()?.unwrap_or(RatioComponent(Ratio::one()));
-            Self::Luma(Luma::new(gray.get() as f32, alpha.get() as f32))
+            let mut gray = gray.get() as f32;
+            if linear {
+                gray = srgb_encode(gray);
+            }
+            Self::Luma(Luma::new(gray, alpha.get() as f32))
         })
     }
 
@@ -387,7 +546,7 @@ impl Color {
         } else {
             let RatioComponent(l) = args.expect("lightness component")?;
             let ChromaComponent(c) = args.expect("chroma component")?;
-            let h: Angle = args.expect("hue component")?;
+            let HueComponent(h) = args.expect("hue component")?;
             let RatioComponent(alpha) =
                 args.eat()?.unwrap_or(RatioComponent(Ratio::one()));
             Self::Oklch(Oklch::new(
@@ -399,6 +558,200 @@ impl Color {
         })
     }
 
+    /// Create a [CIELAB](https://en.wikipedia.org/wiki/CIELAB_color_space)
+    /// color.
+    ///
+    /// This color space is widely used in print production and color science,
+    /// where colors are often specified by their L\*a\*b\* values. Like in CSS,
+    /// the values are relative to the D50 white point.
+    ///
+    /// A CIELAB color is represented internally by an array of four
+    /// components:
+    /// - lightness ([`ratio`])
+    /// - a ([`float`] or [`ratio`].
+    ///   Ratios are relative to `{125}`; meaning `{50%}` is equal to `{62.5}`)
+    /// - b ([`float`] or [`ratio`].
+    ///   Ratios are relative to `{125}`; meaning `{50%}` is equal to `{62.5}`)
+    /// - alpha ([`ratio`])
+    ///
+    /// These components are also available using the
+    /// [`components`]($color.components) method.
+    ///
+    /// ```example
+    /// #square(
+    ///   fill: color.lab(54%, 81, 70)
+    /// )
+    /// ```
+    #[func]
+    pub fn lab(
+        /// The real arguments (the other arguments are just for the docs, this
+        /// function is a bit involved, so we parse the arguments manually).
+        args: &mut Args,
+        /// The lightness component.
+        #[external]
+        lightness: RatioComponent,
+        /// The a ("green/red") component.
+        #[external]
+        a: LabComponent,
+        /// The b ("blue/yellow") component.
+        #[external]
+        b: LabComponent,
+        /// The alpha component.
+        #[external]
+        alpha: RatioComponent,
+        /// Alternatively: The color to convert to CIELAB.
+        ///
+        /// If this is given, the individual components should not be given.
+        #[external]
+        color: Color,
+    ) -> SourceResult<Color> {
+        Ok(if let Some(color) = args.find::<Color>()? {
+            color.to_lab()
+        } else {
+            let RatioComponent(l) = args.expect("lightness component")?;
+            let LabComponent(a) = args.expect("A component")?;
+            let LabComponent(b) = args.expect("B component")?;
+            let RatioComponent(alpha) =
+                args.eat()?.unwrap_or(RatioComponent(Ratio::one()));
+            Self::Lab(Lab::new(l.get() as f32 * 100.0, a, b, alpha.get() as f32))
+        })
+    }
+
+    /// Create a [CIELCh](https://en.wikipedia.org/wiki/CIELAB_color_space#Cylindrical_model)
+    /// color.
+    ///
+    /// This is the cylindrical form of [CIELAB]($color.lab), which describes a
+    /// color by its lightness, chroma, and hue.
+    ///
+    /// A CIELCh color is represented internally by an array of four
+    /// components:
+    /// - lightness ([`ratio`])
+    /// - chroma ([`float`] or [`ratio`].
+    ///   Ratios are relative to `{150}`; meaning `{50%}` is equal to `{75}`)
+    /// - hue ([`angle`])
+    /// - alpha ([`ratio`])
+    ///
+    /// These components are also available using the
+    /// [`components`]($color.components) method.
+    ///
+    /// ```example
+    /// #square(
+    ///   fill: color.lch(54%, 107, 41deg)
+    /// )
+    /// ```
+    #[func]
+    pub fn lch(
+        /// The real arguments (the other arguments are just for the docs, this
+        /// function is a bit involved, so we parse the arguments manually).
+        args: &mut Args,
+        /// The lightness component.
+        #[external]
+        lightness: RatioComponent,
+        /// The chroma component.
+        #[external]
+        chroma: LabChromaComponent,
+        /// The hue component.
+        #[external]
+        hue: Angle,
+        /// The alpha component.
+        #[external]
+        alpha: RatioComponent,
+        /// Alternatively: The color to convert to CIELCh.
+        ///
+        /// If this is given, the individual components should not be given.
+        #[external]
+        color: Color,
+    ) -> SourceResult<Color> {
+        Ok(if let Some(color) = args.find::<Color>()? {
+            color.to_lch()
+        } else {
+            let RatioComponent(l) = args.expect("lightness component")?;
+            let LabChromaComponent(c) = args.expect("chroma component")?;
+            let HueComponent(h) = args.expect("hue component")?;
+            let RatioComponent(alpha) =
+                args.eat()?.unwrap_or(RatioComponent(Ratio::one()));
+            Self::Lch(Lch::new(
+                l.get() as f32 * 100.0,
+                c,
+                LabHue::from_degrees(h.to_deg() as f32),
+                alpha.get() as f32,
+            ))
+        })
+    }
+
+    /// Create a [CIE XYZ](https://en.wikipedia.org/wiki/CIE_1931_color_space)
+    /// color.
+    ///
+    /// This is the color space in which all other color spaces are defined,
+    /// which makes it the natural interchange space for measured colorimetric
+    /// data. The `y` component is the luminance, where `{1.0}` is the
+    /// luminance of the reference white.
+    ///
+    /// A CIE XYZ color is represented internally by an array of four
+    /// components:
+    /// - x ([`float`] or [`ratio`].
+    ///   Ratios are relative to `{1.0}`; meaning `{50%}` is equal to `{0.5}`)
+    /// - y ([`float`] or [`ratio`].
+    ///   Ratios are relative to `{1.0}`; meaning `{50%}` is equal to `{0.5}`)
+    /// - z ([`float`] or [`ratio`].
+    ///   Ratios are relative to `{1.0}`; meaning `{50%}` is equal to `{0.5}`)
+    /// - alpha ([`ratio`])
+    ///
+    /// These components are also available using the
+    /// [`components`]($color.components) method.
+    ///
+    /// ```example
+    /// #square(
+    ///   fill: color.xyz(0.18, 0.11, 0.5)
+    /// )
+    /// #square(
+    ///   fill: color.xyz(0.18, 0.11, 0.5, white-point: "d50")
+    /// )
+    /// ```
+    #[func]
+    pub fn xyz(
+        /// The real arguments (the other arguments are just for the docs, this
+        /// function is a bit involved, so we parse the arguments manually).
+        args: &mut Args,
+        /// The x component.
+        #[external]
+        x: XyzComponent,
+        /// The y (luminance) component.
+        #[external]
+        y: XyzComponent,
+        /// The z component.
+        #[external]
+        z: XyzComponent,
+        /// The alpha component.
+        #[external]
+        alpha: RatioComponent,
+        /// The reference white point the components are relative to.
+        ///
+        /// Measured data is usually relative to D50 (as used in print) or D65
+        /// (as used for screens).
+        #[external]
+        #[named]
+        #[default(WhitePoint::D65)]
+        white_point: WhitePoint,
+        /// Alternatively: The color to convert to CIE XYZ.
+        ///
+        /// If this is given, the individual components should not be given.
+        #[external]
+        color: Color,
+    ) -> SourceResult<Color> {
+        let white = args.named::<WhitePoint>("white-point")?.unwrap_or_default();
+        Ok(if let Some(color) = args.find::<Color>()? {
+            color.to_xyz_with(white)
+        } else {
+            let XyzComponent(x) = args.expect("x component")?;
+            let XyzComponent(y) = args.expect("y component")?;
+            let XyzComponent(z) = args.expect("z component")?;
+            let RatioComponent(alpha) =
+                args.eat()?.unwrap_or(RatioComponent(Ratio::one()));
+            Self::Xyz(Xyz::new(x, y, z, alpha.get() as f32, white))
+        })
+    }
+
     /// Create an RGB(A) color with linear luma.
     ///
     /// This color space is similar to sRGB, but with the distinction that the
@@ -533,6 +886,68 @@ impl Color {
         })
     }
 
+    /// Create a [Display P3](https://en.wikipedia.org/wiki/DCI-P3#Display_P3)
+    /// color.
+    ///
+    /// This color space uses the same white point and transfer function as
+    /// sRGB, but has a wider gamut that covers more saturated colors. Many
+    /// modern screens can display it. Colors outside of the sRGB gamut are
+    /// kept in PDF and SVG output and mapped into the sRGB gamut when
+    /// rendering to raster images.
+    ///
+    /// A Display P3 color is represented internally by an array of four
+    /// components:
+    /// - red ([`ratio`])
+    /// - green ([`ratio`])
+    /// - blue ([`ratio`])
+    /// - alpha ([`ratio`])
+    ///
+    /// These components are also available using the
+    /// [`components`]($color.components) method.
+    ///
+    /// ```example
+    /// #square(fill: color.p3(100%, 0%, 0%))
+    /// #square(fill: color.p3(rgb("#4a90d9")))
+    /// ```
+    #[func(title = "Display P3")]
+    pub fn p3(
+        /// The real arguments (the other arguments are just for the docs, this
+        /// function is a bit involved, so we parse the arguments manually).
+        args: &mut Args,
+        /// The red component.
+        #[external]
+        red: Component,
+        /// The green component.
+        #[external]
+        green: Component,
+        /// The blue component.
+        #[external]
+        blue: Component,
+        /// The alpha component.
+        #[external]
+        alpha: Component,
+        /// Alternatively: The color to convert to Display P3.
+        ///
+        /// If this is given, the individual components should not be given.
+        #[external]
+        color: Color,
+    ) -> SourceResult<Color> {
+        Ok(if let Some(color) = args.find::<Color>()? {
+            color.to_p3()
+        } else {
+            let Component(r) = args.expect("red component")?;
+            let Component(g) = args.expect("green component")?;
+            let Component(b) = args.expect("blue component")?;
+            let Component(a) = args.eat()?.unwrap_or(Component(Ratio::one()));
+            Self::P3(P3::new(
+                r.get() as f32,
+                g.get() as f32,
+                b.get() as f32,
+                a.get() as f32,
+            ))
+        })
+    }
+
     /// Create a CMYK color.
     ///
     /// This is useful if you want to target a specific printer. The conversion
@@ -575,21 +990,30 @@ impl Color {
         /// If this is given, the individual components should not be given.
         #[external]
         color: Color,
+        /// Whether the color overprints the colors below it instead of
+        /// knocking them out. See [`overprint`]($color.overprint) for details.
+        #[external]
+        #[named]
+        #[default(false)]
+        overprint: bool,
     ) -> SourceResult<Color> {
-        Ok(if let Some(color) = args.find::<Color>()? {
-            color.to_cmyk()
+        let overprint = args.named::<bool>("overprint")?.unwrap_or(false);
+        let mut cmyk = if let Some(color) = args.find::<Color>()? {
+            match color.to_cmyk() {
+                Self::Cmyk(c) => c,
+                _ => unreachable!(),
+            }
         } else {
             let RatioComponent(c) = args.expect("cyan component")?;
             let RatioComponent(m) = args.expect("magenta component")?;
             let RatioComponent(y) = args.expect("yellow component")?;
             let RatioComponent(k) = args.expect("key/black component")?;
-            Self::Cmyk(Cmyk::new(
-                c.get() as f32,
-                m.get() as f32,
-                y.get() as f32,
-                k.get() as f32,
-            ))
-        })
+            Cmyk::new(c.get() as f32, m.get() as f32, y.get() as f32, k.get() as f32)
+        };
+        if overprint {
+            cmyk.overprint = true;
+        }
+        Ok(Self::Cmyk(cmyk))
     }
 
     /// Create an HSL color.
@@ -638,7 +1062,7 @@ impl Color {
         Ok(if let Some(color) = args.find::<Color>()? {
             color.to_hsl()
         } else {
-            let h: Angle = args.expect("hue component")?;
+            let HueComponent(h) = args.expect("hue component")?;
             let Component(s) = args.expect("saturation component")?;
             let Component(l) = args.expect("lightness component")?;
             let Component(a) = args.eat()?.unwrap_or(Component(Ratio::one()));
@@ -697,7 +1121,7 @@ impl Color {
         Ok(if let Some(color) = args.find::<Color>()? {
             color.to_hsv()
         } else {
-            let h: Angle = args.expect("hue component")?;
+            let HueComponent(h) = args.expect("hue component")?;
             let Component(s) = args.expect("saturation component")?;
             let Component(v) = args.expect("value component")?;
             let Component(a) = args.eat()?.unwrap_or(Component(Ratio::one()));
@@ -710,148 +1134,781 @@ impl Color {
         })
     }
 
-    /// Extracts the components of this color.
+    /// Create an HWB color.
     ///
-    /// The size and values of this array depends on the color space. You can
-    /// obtain the color space using [`space`]($color.space). Below is a table
-    /// of the color spaces and their components:
+    /// This color space describes a color by its hue and the amount of white
+    /// and black mixed into it, which makes it intuitive to create tints and
+    /// shades of a color. If whiteness and blackness add up to more than
+    /// `{100%}`, they are scaled down proportionally and the result is a gray.
     ///
-    /// |       Color space       |     C1    |     C2     |     C3    |   C4   |
-    /// |-------------------------|-----------|------------|-----------|--------|
-    /// | [`luma`]($color.luma)   | Lightness |            |           |        |
-    /// | [`oklab`]($color.oklab) | Lightness |    `a`     |    `b`    |  Alpha |
-    /// | [`oklch`]($color.oklch) | Lightness |   Chroma   |    Hue    |  Alpha |
-    /// | [`linear-rgb`]($color.linear-rgb) | Red  |   Green |    Blue |  Alpha |
-    /// | [`rgb`]($color.rgb)     |    Red    |   Green    |    Blue   |  Alpha |
-    /// | [`cmyk`]($color.cmyk)   |    Cyan   |   Magenta  |   Yellow  |  Key   |
-    /// | [`hsl`]($color.hsl)     |     Hue   | Saturation | Lightness |  Alpha |
-    /// | [`hsv`]($color.hsv)     |     Hue   | Saturation |   Value   |  Alpha |
+    /// An HWB color is represented internally by an array of four components:
+    /// - hue ([`angle`])
+    /// - whiteness ([`ratio`])
+    /// - blackness ([`ratio`])
+    /// - alpha ([`ratio`])
     ///
-    /// For the meaning and type of each individual value, see the documentation
-    /// of the corresponding color space. The alpha component is optional and
-    /// only included if the `alpha` argument is `true`. The length of the
-    /// returned array depends on the number of components and whether the alpha
-    /// component is included.
+    /// These components are also available using the
+    /// [`components`]($color.components) method.
     ///
     /// ```example
-    /// // note that the alpha component is included by default
-    /// #rgb(40%, 60%, 80%).components()
+    /// #square(
+    ///   fill: color.hwb(30deg, 20%, 30%)
+    /// )
     /// ```
-    #[func]
-    pub fn components(
-        self,
-        /// Whether to include the alpha component.
-        #[named]
-        #[default(true)]
-        alpha: bool,
-    ) -> Array {
-        let mut components = match self {
-            Self::Luma(c) => {
-                array![Ratio::new(c.luma.into()), Ratio::new(c.alpha.into())]
-            }
-            Self::Oklab(c) => {
-                array![
-                    Ratio::new(c.l.into()),
-                    f64::from(c.a),
-                    f64::from(c.b),
-                    Ratio::new(c.alpha.into())
-                ]
+    #[func(title = "HWB")]
+    pub fn hwb(
+        /// The real arguments (the other arguments are just for the docs, this
+        /// function is a bit involved, so we parse the arguments manually).
+        args: &mut Args,
+        /// The hue angle.
+        #[external]
+        hue: Angle,
+        /// The whiteness component.
+        #[external]
+        whiteness: Component,
+        /// The blackness component.
+        #[external]
+        blackness: Component,
+        /// The alpha component.
+        #[external]
+        alpha: Component,
+        /// Alternatively: The color to convert to HWB.
+        ///
+        /// If this is given, the individual components should not be given.
+        #[external]
+        color: Color,
+    ) -> SourceResult<Color> {
+        Ok(if let Some(color) = args.find::<Color>()? {
+            color.to_hwb()
+        } else {
+            let HueComponent(h) = args.expect("hue component")?;
+            let Component(w) = args.expect("whiteness component")?;
+            let Component(b) = args.expect("blackness component")?;
+            let Component(a) = args.eat()?.unwrap_or(Component(Ratio::one()));
+            Self::Hwb(Hwb::new(
+                RgbHue::from_degrees(h.to_deg() as f32),
+                w.get() as f32,
+                b.get() as f32,
+                a.get() as f32,
+            ))
+        })
+    }
+
+    /// Create an [OkHSL](https://bottosson.github.io/posts/colorpicker/)
+    /// color.
+    ///
+    /// This color space has the same structure as HSL, but is built on top of
+    /// [Oklab]($color.oklab). Colors with the same lightness appear equally
+    /// bright and the hue stays perceptually constant when the saturation
+    /// changes, which makes it well suited for generating palettes. Like in
+    /// HSL, all combinations of components lie within the sRGB gamut.
+    ///
+    /// An OkHSL color is represented internally by an array of four
+    /// components:
+    /// - hue ([`angle`])
+    /// - saturation ([`ratio`])
+    /// - lightness ([`ratio`])
+    /// - alpha ([`ratio`])
+    ///
+    /// These components are also available using the
+    /// [`components`]($color.components) method.
+    ///
+    /// ```example
+    /// #for hue in range(0, 360, step: 45) {
+    ///   box(square(size: 15pt, fill: color.okhsl(hue * 1deg, 80%, 60%)))
+    /// }
+    /// ```
+    #[func(title = "OkHSL")]
+    pub fn okhsl(
+        /// The real arguments (the other arguments are just for the docs, this
+        /// function is a bit involved, so we parse the arguments manually).
+        args: &mut Args,
+        /// The hue angle.
+        #[external]
+        hue: Angle,
+        /// The saturation component.
+        #[external]
+        saturation: Component,
+        /// The lightness component.
+        #[external]
+        lightness: Component,
+        /// The alpha component.
+        #[external]
+        alpha: Component,
+        /// Alternatively: The color to convert to OkHSL.
+        ///
+        /// If this is given, the individual components should not be given.
+        #[external]
+        color: Color,
+    ) -> SourceResult<Color> {
+        Ok(if let Some(color) = args.find::<Color>()? {
+            color.to_okhsl()
+        } else {
+            let HueComponent(h) = args.expect("hue component")?;
+            let Component(s) = args.expect("saturation component")?;
+            let Component(l) = args.expect("lightness component")?;
+            let Component(a) = args.eat()?.unwrap_or(Component(Ratio::one()));
+            Self::Okhsl(Okhsl::new(
+                OklabHue::from_degrees(h.to_deg() as f32),
+                s.get() as f32,
+                l.get() as f32,
+                a.get() as f32,
+            ))
+        })
+    }
+
+    /// Create an [OkHSV](https://bottosson.github.io/posts/colorpicker/)
+    /// color.
+    ///
+    /// This color space has the same structure as HSV, but is built on top of
+    /// [Oklab]($color.oklab), which gives it the same perceptual benefits as
+    /// [OkHSL]($color.okhsl). All combinations of components lie within the
+    /// sRGB gamut.
+    ///
+    /// An OkHSV color is represented internally by an array of four
+    /// components:
+    /// - hue ([`angle`])
+    /// - saturation ([`ratio`])
+    /// - value ([`ratio`])
+    /// - alpha ([`ratio`])
+    ///
+    /// These components are also available using the
+    /// [`components`]($color.components) method.
+    ///
+    /// ```example
+    /// #for value in range(20, 101, step: 20) {
+    ///   box(square(size: 15pt, fill: color.okhsv(250deg, 70%, value * 1%)))
+    /// }
+    /// ```
+    #[func(title = "OkHSV")]
+    pub fn okhsv(
+        /// The real arguments (the other arguments are just for the docs, this
+        /// function is a bit involved, so we parse the arguments manually).
+        args: &mut Args,
+        /// The hue angle.
+        #[external]
+        hue: Angle,
+        /// The saturation component.
+        #[external]
+        saturation: Component,
+        /// The value component.
+        #[external]
+        value: Component,
+        /// The alpha component.
+        #[external]
+        alpha: Component,
+        /// Alternatively: The color to convert to OkHSV.
+        ///
+        /// If this is given, the individual components should not be given.
+        #[external]
+        color: Color,
+    ) -> SourceResult<Color> {
+        Ok(if let Some(color) = args.find::<Color>()? {
+            color.to_okhsv()
+        } else {
+            let HueComponent(h) = args.expect("hue component")?;
+            let Component(s) = args.expect("saturation component")?;
+            let Component(v) = args.expect("value component")?;
+            let Component(a) = args.eat()?.unwrap_or(Component(Ratio::one()));
+            Self::Okhsv(Okhsv::new(
+                OklabHue::from_degrees(h.to_deg() as f32),
+                s.get() as f32,
+                v.get() as f32,
+                a.get() as f32,
+            ))
+        })
+    }
+
+    /// Create a color from a CSS color string.
+    ///
+    /// This accepts the color syntax of
+    /// [CSS Color Level 4](https://www.w3.org/TR/css-color-4/), which makes it
+    /// easy to reuse colors from web design tools. Supported are hexadecimal
+    /// colors, named colors like `{"rebeccapurple"}`, and the functions `rgb`,
+    /// `rgba`, `hsl`, `hsla`, `hwb`, `lab`, `lch`, `oklab`, `oklch`, and
+    /// `color`. The latter supports the `srgb`, `srgb-linear`, `display-p3`,
+    /// `xyz`, `xyz-d50`, and `xyz-d65` color spaces.
+    ///
+    /// The color is created in the color space of the CSS function. Hexadecimal
+    /// and named colors are in the sRGB color space.
+    ///
+    /// ```example
+    /// #square(fill: color.css("rebeccapurple"))
+    /// #square(fill: color.css("rgb(255 99 71 / 50%)"))
+    /// #square(fill: color.css("hsl(200deg, 60%, 45%)"))
+    /// #square(fill: color.css("oklch(70% 0.15 150)"))
+    /// ```
+    #[func(title = "CSS")]
+    pub fn css(
+        /// The CSS color string.
+        text: Spanned<Str>,
+    ) -> SourceResult<Color> {
+        Self::from_css(&text.v).at(text.span)
+    }
+
+    /// Create a color in the color space of an ICC profile.
+    ///
+    /// This is useful for print production, where colors are often specified
+    /// relative to a specific output condition like FOGRA39. The profile can
+    /// describe a gray, RGB, or CMYK color space. When exporting to PDF, the
+    /// profile is embedded into the document and the color's components are
+    /// written as they are. For display and for conversions to other color
+    /// spaces, the color is converted to sRGB with the given rendering intent.
+    ///
+    /// A color in an ICC profile is represented internally by one component per
+    /// channel of the profile, followed by an alpha component unless the
+    /// profile is for a CMYK color space. All components are
+    /// [ratios]($ratio).
+    ///
+    /// ```typ
+    /// #let fogra39 = read("fogra39.icc", encoding: none)
+    /// #square(fill: color.icc(fogra39, (100%, 0%, 0%, 0%)))
+    /// ```
+    #[func(title = "ICC")]
+    pub fn icc(
+        /// The raw data of the ICC profile.
+        profile: Spanned<Bytes>,
+        /// The components of the color, one per channel of the profile.
+        ///
+        /// For gray and RGB profiles, an alpha component may be given in
+        /// addition.
+        components: Spanned<Array>,
+        /// The rendering intent for conversions from the profile.
+        ///
+        /// To proof how a color will look on paper, use
+        /// `{"absolute-colorimetric"}`, which also simulates the paper white of
+        /// the profile's press condition. The intent is also passed on to PDF
+        /// viewers and printers.
+        #[named]
+        #[default(RenderingIntent::Perceptual)]
+        intent: RenderingIntent,
+    ) -> SourceResult<Color> {
+        let profile = IccProfile::new(profile.v, intent).at(profile.span)?;
+        let channels = profile.space().channels();
+        let mut values = [0.0; 4];
+        let mut alpha = 1.0;
+        let len = components.v.len();
+        if len != channels && (len != channels + 1 || profile.space() == IccSpace::Cmyk) {
+            bail!(components.span, "expected {channels} color components, found {len}");
+        }
+
+        for (i, value) in components.v.into_iter().enumerate() {
+            let RatioComponent(v) = value.cast().at(components.span)?;
+            if i < channels {
+                values[i] = v.get() as f32;
+            } else {
+                alpha = v.get() as f32;
             }
-            Self::Oklch(c) => {
-                array![
-                    Ratio::new(c.l.into()),
-                    f64::from(c.chroma),
-                    hue_angle(c.hue.into_degrees()),
-                    Ratio::new(c.alpha.into()),
-                ]
+        }
+
+        Ok(Self::Icc(Icc { profile, components: values, alpha }))
+    }
+
+    /// Extracts the components of this color.
+    ///
+    /// The size and values of this array depends on the color space. You can
+    /// obtain the color space using [`space`]($color.space). Below is a table
+    /// of the color spaces and their components:
+    ///
+    /// |       Color space       |     C1    |     C2     |     C3    |   C4   |
+    /// |-------------------------|-----------|------------|-----------|--------|
+    /// | [`luma`]($color.luma)   | Lightness |            |           |        |
+    /// | [`oklab`]($color.oklab) | Lightness |    `a`     |    `b`    |  Alpha |
+    /// | [`oklch`]($color.oklch) | Lightness |   Chroma   |    Hue    |  Alpha |
+    /// | [`lab`]($color.lab)     | Lightness |    `a`     |    `b`    |  Alpha |
+    /// | [`lch`]($color.lch)     | Lightness |   Chroma   |    Hue    |  Alpha |
+    /// | [`xyz`]($color.xyz)     |    `x`    |    `y`     |    `z`    |  Alpha |
+    /// | [`linear-rgb`]($color.linear-rgb) | Red  |   Green |    Blue |  Alpha |
+    /// | [`rgb`]($color.rgb)     |    Red    |   Green    |    Blue   |  Alpha |
+    /// | [`p3`]($color.p3)       |    Red    |   Green    |    Blue   |  Alpha |
+    /// | [`cmyk`]($color.cmyk)   |    Cyan   |   Magenta  |   Yellow  |  Key   |
+    /// | [`hsl`]($color.hsl)     |     Hue   | Saturation | Lightness |  Alpha |
+    /// | [`hsv`]($color.hsv)     |     Hue   | Saturation |   Value   |  Alpha |
+    /// | [`hwb`]($color.hwb)     |     Hue   | Whiteness  | Blackness |  Alpha |
+    /// | [`okhsl`]($color.okhsl) |     Hue   | Saturation | Lightness |  Alpha |
+    /// | [`okhsv`]($color.okhsv) |     Hue   | Saturation |   Value   |  Alpha |
+    ///
+    /// Colors created with [`color.icc`]($color.icc) have one component per
+    /// channel of their profile, followed by alpha for gray and RGB profiles.
+    ///
+    /// For the meaning and type of each individual value, see the documentation
+    /// of the corresponding color space. The alpha component is optional and
+    /// only included if the `alpha` argument is `true`. The length of the
+    /// returned array depends on the number of components and whether the alpha
+    /// component is included.
+    ///
+    /// ```example
+    /// // note that the alpha component is included by default
+    /// #rgb(40%, 60%, 80%).components()
+    /// ```
+    ///
+    /// With `named: true`, the components are instead returned as a dictionary
+    /// keyed by the lowercase names from the table above, e.g. `hue`, `key`,
+    /// or `a`. This way, code that works with colors from different spaces
+    /// doesn't need to know the order of their components.
+    ///
+    /// ```example
+    /// #let c = color.hsl(120deg, 30%, 50%)
+    /// #c.components(named: true) \
+    /// #c.components(named: true).hue
+    /// ```
+    #[func(name = "components")]
+    pub fn to_components(
+        self,
+        /// Whether to include the alpha component.
+        #[named]
+        #[default(true)]
+        alpha: bool,
+        /// Whether to return a dictionary keyed by component name instead of
+        /// an array.
+        #[named]
+        #[default(false)]
+        named: bool,
+    ) -> Value {
+        if named {
+            self.named_components(alpha).into_value()
+        } else {
+            self.components(alpha).into_value()
+        }
+    }
+
+    /// Returns the constructor function for this color's space:
+    /// - [`luma`]($color.luma)
+    /// - [`oklab`]($color.oklab)
+    /// - [`oklch`]($color.oklch)
+    /// - [`lab`]($color.lab)
+    /// - [`lch`]($color.lch)
+    /// - [`xyz`]($color.xyz)
+    /// - [`linear-rgb`]($color.linear-rgb)
+    /// - [`rgb`]($color.rgb)
+    /// - [`p3`]($color.p3)
+    /// - [`cmyk`]($color.cmyk)
+    /// - [`hsl`]($color.hsl)
+    /// - [`hsv`]($color.hsv)
+    /// - [`hwb`]($color.hwb)
+    /// - [`okhsl`]($color.okhsl)
+    /// - [`okhsv`]($color.okhsv)
+    ///
+    /// For colors in an ICC profile, this returns the device color space with
+    /// the same components, that is [`luma`]($color.luma), [`rgb`]($color.rgb),
+    /// or [`cmyk`]($color.cmyk).
+    ///
+    /// ```example
+    /// #let color = cmyk(1%, 2%, 3%, 4%)
+    /// #(color.space() == cmyk)
+    /// ```
+    #[func]
+    pub fn space(self) -> ColorSpace {
+        match self {
+            Self::Luma(_) => ColorSpace::D65Gray,
+            Self::Oklab(_) => ColorSpace::Oklab,
+            Self::Oklch(_) => ColorSpace::Oklch,
+            Self::Lab(_) => ColorSpace::Lab,
+            Self::Lch(_) => ColorSpace::Lch,
+            Self::Xyz(c) => ColorSpace::Xyz(c.white),
+            Self::LinearRgb(_) => ColorSpace::LinearRgb,
+            Self::Rgb(_) => ColorSpace::Srgb,
+            Self::P3(_) => ColorSpace::DisplayP3,
+            Self::Cmyk(_) => ColorSpace::Cmyk,
+            Self::Icc(c) => match c.profile.space() {
+                IccSpace::Gray => ColorSpace::D65Gray,
+                IccSpace::Rgb => ColorSpace::Srgb,
+                IccSpace::Cmyk => ColorSpace::Cmyk,
+            },
+            Self::Hsl(_) => ColorSpace::Hsl,
+            Self::Hsv(_) => ColorSpace::Hsv,
+            Self::Hwb(_) => ColorSpace::Hwb,
+            Self::Okhsl(_) => ColorSpace::Okhsl,
+            Self::Okhsv(_) => ColorSpace::Okhsv,
+        }
+    }
+
+    /// Converts the color to another color space.
+    ///
+    /// Colors from wide-gamut spaces like [`oklch`]($color.oklch) or
+    /// [`p3`]($color.p3) can lie outside of the gamut of the target space.
+    /// The `gamut-map` argument determines how they are brought into it.
+    /// Clipping each component is fast, but can noticeably shift the hue of
+    /// saturated colors, so gradients built in Oklch may be better served by
+    /// one of the chroma-reducing strategies. Targets without a bounded gamut,
+    /// such as [`oklab`]($color.oklab), are never mapped.
+    ///
+    /// ```example
+    /// #let c = oklch(70%, 0.3, 145deg)
+    /// #c.to-space(rgb).to-hex() \
+    /// #c.to-space(rgb, gamut-map: "css").to-hex() \
+    /// #c.to-space(rgb, gamut-map: "chroma-reduce").to-hex()
+    /// ```
+    #[func(name = "to-space")]
+    pub fn convert(
+        self,
+        /// The color space to convert to.
+        space: ColorSpace,
+        /// How to map colors outside of the target's gamut into it.
+        #[named]
+        #[default(GamutMapping::Clip)]
+        gamut_map: GamutMapping,
+    ) -> Color {
+        let Some(gamut) = Gamut::of(space) else {
+            return self.to_space(space);
+        };
+
+        let mapped = map_into_gamut(self.to_unbounded_linear_rgb(), gamut, gamut_map);
+        match gamut {
+            Gamut::Srgb => Color::LinearRgb(mapped).to_space(space),
+            Gamut::DisplayP3 => Color::P3(P3::from_linear_rgb(mapped)),
+        }
+    }
+
+    /// Checks whether the color lies within the gamut of a color space, that
+    /// is, whether it can be converted to the space without clamping.
+    ///
+    /// Colors always fit into spaces without a bounded gamut, like
+    /// [`oklab`]($color.oklab). For [`luma`]($color.luma), only grays fit.
+    ///
+    /// ```example
+    /// #oklch(70%, 0.1, 145deg).in-gamut(rgb) \
+    /// #oklch(70%, 0.3, 145deg).in-gamut(rgb) \
+    /// #oklch(70%, 0.3, 145deg).in-gamut(color.p3)
+    /// ```
+    #[func]
+    pub fn in_gamut(
+        self,
+        /// The color space whose gamut to check.
+        space: ColorSpace,
+    ) -> bool {
+        const EPSILON: f32 = 1e-4;
+        let rgb = self.to_unbounded_linear_rgb();
+        match space {
+            ColorSpace::D65Gray => {
+                Gamut::Srgb.contains(rgb)
+                    && (rgb.red - rgb.green).abs() < EPSILON
+                    && (rgb.green - rgb.blue).abs() < EPSILON
             }
-            Self::LinearRgb(c) => {
-                array![
-                    Ratio::new(c.red.into()),
-                    Ratio::new(c.green.into()),
-                    Ratio::new(c.blue.into()),
-                    Ratio::new(c.alpha.into()),
-                ]
+            _ => Gamut::of(space).map_or(true, |gamut| gamut.contains(rgb)),
+        }
+    }
+
+    /// Returns the relative luminance of the color, that is, the brightness
+    /// of its linear light relative to a reference white.
+    ///
+    /// The luminance is the same no matter which space the color is in. Unlike
+    /// the component of a [`luma`]($color.luma) color, it is not
+    /// gamma-encoded, so it is the right measure for contrast computations.
+    /// The alpha component is ignored.
+    ///
+    /// ```example
+    /// #let contrast(a, b) = {
+    ///   let (l1, l2) = (a.luminance(), b.luminance())
+    ///   (calc.max(l1, l2) + 0.05) / (calc.min(l1, l2) + 0.05)
+    /// }
+    ///
+    /// #for c in (white, yellow, blue, black) [
+    ///   #c.luminance() \
+    /// ]
+    /// Contrast: #calc.round(contrast(navy, white), digits: 2)
+    /// ```
+    #[func]
+    pub fn luminance(self) -> f64 {
+        let rgb = self.to_unbounded_linear_rgb();
+        0.2126 * f64::from(rgb.red)
+            + 0.7152 * f64::from(rgb.green)
+            + 0.0722 * f64::from(rgb.blue)
+    }
+
+    /// Returns the color's RGB(A) hex representation (such as `#ffaa32` or
+    /// `#020304fe`). The alpha component (last two digits in `#020304fe`) is
+    /// omitted if it is equal to `ff` (255 / 100%).
+    #[func]
+    pub fn to_hex(self) -> EcoString {
+        let [r, g, b, a] = self.to_rgb().to_vec4_u8();
+        if a != 255 {
+            eco_format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+        } else {
+            eco_format!("#{:02x}{:02x}{:02x}", r, g, b)
+        }
+    }
+
+    /// Returns the color as a CSS color string in the color's own space (such
+    /// as `{"oklch(62% 0.2 30deg / 80%)"}` or
+    /// `{"color(display-p3 1 0.5 0)"}`). The alpha component is omitted if
+    /// it is 100%.
+    ///
+    /// Colors in a space that CSS does not support are converted first: HSV
+    /// colors are written as `hsl(..)`, OkHSL and OkHSV colors as `oklch(..)`,
+    /// while luma, CMYK, and ICC-based colors are written as `rgb(..)`. The result can be read back with
+    /// [`color.css`]($color.css).
+    ///
+    /// ```example
+    /// #rgb("#336699").to-css() \
+    /// #oklch(62%, 0.2, 30deg, 80%).to-css() \
+    /// #color.p3(100%, 50%, 0%).to-css()
+    /// ```
+    #[func]
+    pub fn to_css(self) -> EcoString {
+        let num =
+            |v: f32, digits: u8| repr::format_float(v.into(), Some(digits), false, "");
+        let pct = |v: f32| repr::format_float(f64::from(v) * 100.0, Some(2), false, "%");
+        let deg = |v: f32| {
+            repr::format_float(f64::from(v).rem_euclid(360.0), Some(2), false, "deg")
+        };
+
+        let (body, alpha) = match self {
+            Self::Luma(_) | Self::Cmyk(_) | Self::Icc(_) => {
+                return self.to_rgb().to_css()
             }
+            Self::Hsv(_) => return self.to_hsl().to_css(),
+            Self::Okhsl(_) | Self::Okhsv(_) => return self.to_oklch().to_css(),
             Self::Rgb(c) => {
-                array![
-                    Ratio::new(c.red.into()),
-                    Ratio::new(c.green.into()),
-                    Ratio::new(c.blue.into()),
-                    Ratio::new(c.alpha.into()),
-                ]
-            }
-            Self::Cmyk(c) => {
-                array![
-                    Ratio::new(c.c.into()),
-                    Ratio::new(c.m.into()),
-                    Ratio::new(c.y.into()),
-                    Ratio::new(c.k.into())
-                ]
+                let [r, g, b] = [c.red, c.green, c.blue].map(|v| num(v * 255.0, 2));
+                (eco_format!("rgb({r} {g} {b}"), c.alpha)
             }
-            Self::Hsl(c) => {
-                array![
-                    hue_angle(c.hue.into_degrees()),
-                    Ratio::new(c.saturation.into()),
-                    Ratio::new(c.lightness.into()),
-                    Ratio::new(c.alpha.into()),
-                ]
+            Self::Oklab(c) => (
+                eco_format!("oklab({} {} {}", pct(c.l), num(c.a, 4), num(c.b, 4)),
+                c.alpha,
+            ),
+            Self::Oklch(c) => (
+                eco_format!(
+                    "oklch({} {} {}",
+                    pct(c.l),
+                    num(c.chroma, 4),
+                    deg(c.hue.into_degrees())
+                ),
+                c.alpha,
+            ),
+            Self::Lab(c) => (
+                eco_format!("lab({} {} {}", pct(c.l / 100.0), num(c.a, 2), num(c.b, 2)),
+                c.alpha,
+            ),
+            Self::Lch(c) => (
+                eco_format!(
+                    "lch({} {} {}",
+                    pct(c.l / 100.0),
+                    num(c.chroma, 2),
+                    deg(c.hue.into_degrees())
+                ),
+                c.alpha,
+            ),
+            Self::Hsl(c) => (
+                eco_format!(
+                    "hsl({} {} {}",
+                    deg(c.hue.into_degrees()),
+                    pct(c.saturation),
+                    pct(c.lightness)
+                ),
+                c.alpha,
+            ),
+            Self::Hwb(c) => (
+                eco_format!(
+                    "hwb({} {} {}",
+                    deg(c.hue.into_degrees()),
+                    pct(c.whiteness),
+                    pct(c.blackness)
+                ),
+                c.alpha,
+            ),
+            Self::P3(c) => (
+                eco_format!(
+                    "color(display-p3 {} {} {}",
+                    num(c.red, 4),
+                    num(c.green, 4),
+                    num(c.blue, 4)
+                ),
+                c.alpha,
+            ),
+            Self::LinearRgb(c) => (
+                eco_format!(
+                    "color(srgb-linear {} {} {}",
+                    num(c.red, 4),
+                    num(c.green, 4),
+                    num(c.blue, 4)
+                ),
+                c.alpha,
+            ),
+            Self::Xyz(c) => {
+                let space = match c.white {
+                    WhitePoint::D50 => "xyz-d50",
+                    WhitePoint::D65 => "xyz-d65",
+                };
+                (
+                    eco_format!(
+                        "color({space} {} {} {}",
+                        num(c.x, 4),
+                        num(c.y, 4),
+                        num(c.z, 4)
+                    ),
+                    c.alpha,
+                )
             }
-            Self::Hsv(c) => {
-                array![
-                    hue_angle(c.hue.into_degrees()),
-                    Ratio::new(c.saturation.into()),
-                    Ratio::new(c.value.into()),
-                    Ratio::new(c.alpha.into()),
-                ]
+        };
+
+        if alpha < 1.0 {
+            eco_format!("{body} / {})", pct(alpha))
+        } else {
+            eco_format!("{body})")
+        }
+    }
+
+    /// Computes the contrast between this color and another one.
+    ///
+    /// This color is treated as the text color and the other one as the
+    /// background. Both are converted to sRGB and their alpha components are
+    /// ignored.
+    ///
+    /// ```example
+    /// #black.contrast(white) \
+    /// #calc.round(gray.contrast(white, method: "apca"), digits: 1) \
+    /// #calc.round(white.contrast(gray, method: "apca"), digits: 1)
+    /// ```
+    #[func]
+    pub fn contrast(
+        self,
+        /// The background color.
+        other: Color,
+        /// How to compute the contrast.
+        #[named]
+        #[default(ContrastMethod::Wcag)]
+        method: ContrastMethod,
+    ) -> f64 {
+        match method {
+            ContrastMethod::Wcag => {
+                let a = self.wcag_luminance();
+                let b = other.wcag_luminance();
+                (a.max(b) + 0.05) / (a.min(b) + 0.05)
+            }
+            ContrastMethod::Apca => {
+                apca_contrast(self.apca_luminance(), other.apca_luminance())
             }
-        };
-        // Remove the alpha component if the corresponding argument was set.
-        if !alpha && !matches!(self, Self::Cmyk(_)) {
-            let _ = components.pop();
         }
-        components
     }
 
-    /// Returns the constructor function for this color's space:
-    /// - [`luma`]($color.luma)
-    /// - [`oklab`]($color.oklab)
-    /// - [`oklch`]($color.oklch)
-    /// - [`linear-rgb`]($color.linear-rgb)
-    /// - [`rgb`]($color.rgb)
-    /// - [`cmyk`]($color.cmyk)
-    /// - [`hsl`]($color.hsl)
-    /// - [`hsv`]($color.hsv)
+    /// Computes the perceptual difference (Delta E) between this color and
+    /// another one.
+    ///
+    /// A difference of about `{1}` in CIE Lab (or `{0.01}` in Oklab) is just
+    /// noticeable, so this is useful to find near-identical colors, for
+    /// example in a chart's palette. Alpha components are ignored.
     ///
     /// ```example
-    /// #let color = cmyk(1%, 2%, 3%, 4%)
-    /// #(color.space() == cmyk)
+    /// #calc.round(red.delta-e(maroon), digits: 2) \
+    /// #calc.round(red.delta-e(maroon, method: "76"), digits: 2) \
+    /// #calc.round(red.delta-e(maroon, method: "ok"), digits: 3) \
+    /// #(rgb("#336699").delta-e(rgb("#336698")) < 1)
     /// ```
     #[func]
-    pub fn space(self) -> ColorSpace {
-        match self {
-            Self::Luma(_) => ColorSpace::D65Gray,
-            Self::Oklab(_) => ColorSpace::Oklab,
-            Self::Oklch(_) => ColorSpace::Oklch,
-            Self::LinearRgb(_) => ColorSpace::LinearRgb,
-            Self::Rgb(_) => ColorSpace::Srgb,
-            Self::Cmyk(_) => ColorSpace::Cmyk,
-            Self::Hsl(_) => ColorSpace::Hsl,
-            Self::Hsv(_) => ColorSpace::Hsv,
+    pub fn delta_e(
+        self,
+        /// The color to compare with.
+        other: Color,
+        /// The formula with which to compute the difference.
+        #[named]
+        #[default(DeltaEMethod::Ciede2000)]
+        method: DeltaEMethod,
+    ) -> f64 {
+        match method {
+            DeltaEMethod::Ciede2000 => {
+                ciede2000(self.lab_components(), other.lab_components())
+            }
+            DeltaEMethod::Cie76 => {
+                distance(self.lab_components(), other.lab_components())
+            }
+            DeltaEMethod::Ok => {
+                let [l1, a1, b1, _] = self.to_oklab().to_vec4().map(f64::from);
+                let [l2, a2, b2, _] = other.to_oklab().to_vec4().map(f64::from);
+                distance([l1, a1, b1], [l2, a2, b2])
+            }
         }
     }
 
-    /// Returns the color's RGB(A) hex representation (such as `#ffaa32` or
-    /// `#020304fe`). The alpha component (last two digits in `#020304fe`) is
-    /// omitted if it is equal to `ff` (255 / 100%).
+    /// Finds the color in a palette that is perceptually closest to this one.
+    ///
+    /// The difference is measured with [`delta-e`]($color.delta-e). If several
+    /// colors are equally close, the first one wins. This is useful to map
+    /// arbitrary colors, e.g. from data, onto a fixed palette.
+    ///
+    /// ```example
+    /// #let palette = (navy, teal, maroon, olive)
+    /// #let swatch(c) = box(square(size: 8pt, fill: c))
+    /// #for c in (blue, red, green) [
+    ///   #swatch(c) → #swatch(c.closest(palette)) \
+    /// ]
+    /// #rgb("#ee3311").closest(palette, index: true)
+    /// ```
     #[func]
-    pub fn to_hex(self) -> EcoString {
-        let [r, g, b, a] = self.to_rgb().to_vec4_u8();
-        if a != 255 {
-            eco_format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
-        } else {
-            eco_format!("#{:02x}{:02x}{:02x}", r, g, b)
-        }
+    pub fn closest(
+        self,
+        /// The colors to choose from.
+        palette: Vec<Color>,
+        /// The formula with which to compute the difference.
+        #[named]
+        #[default(DeltaEMethod::Ciede2000)]
+        method: DeltaEMethod,
+        /// Whether to return the index of the closest color in the palette
+        /// instead of the color itself.
+        #[named]
+        #[default(false)]
+        index: bool,
+    ) -> StrResult<Value> {
+        let (i, color) = palette
+            .iter()
+            .map(|&color| self.delta_e(color, method))
+            .enumerate()
+            .fold(None, |closest: Option<(usize, f64)>, (i, d)| match closest {
+                Some((_, min)) if min <= d => closest,
+                _ => Some((i, d)),
+            })
+            .map(|(i, _)| (i, palette[i]))
+            .ok_or("palette must not be empty")?;
+        Ok(if index { (i as i64).into_value() } else { color.into_value() })
+    }
+
+    /// Reduces the number of distinct colors.
+    ///
+    /// When called on a single color, each of its components is snapped to
+    /// one of `count` evenly spaced levels. This is also known as
+    /// posterization. Luma, RGB and CMYK colors are quantized in their own
+    /// color space, all others are quantized in sRGB and then converted back.
+    ///
+    /// When called with an array of colors, this instead finds a palette of at
+    /// most `count` colors that represents them well, using k-means clustering
+    /// in [Oklab]($color.oklab). The palette is sorted by how many of the
+    /// given colors each entry represents, most common first. This is useful to
+    /// derive a small, print-friendly palette from the colors of a chart or an
+    /// image.
+    ///
+    /// ```example
+    /// #let colors = color.map.turbo
+    /// #let palette = color.quantize(colors, 5)
+    /// #stack(dir: ltr, ..palette.map(c => square(size: 20pt, fill: c)))
+    ///
+    /// #let c = rgb("#7a3fc9")
+    /// #stack(dir: ltr, ..(2, 3, 4, 8).map(n => square(
+    ///   size: 20pt, fill: c.quantize(n),
+    /// )))
+    /// ```
+    #[func]
+    pub fn quantize(
+        /// The color to posterize or the array of colors to reduce to a
+        /// palette.
+        target: Quantizable,
+        /// For a single color, the number of levels per component. For an
+        /// array of colors, the maximum size of the palette.
+        count: Spanned<usize>,
+    ) -> SourceResult<Value> {
+        Ok(match target {
+            Quantizable::Color(color) => {
+                if count.v < 2 {
+                    bail!(count.span, "number of levels must be at least 2");
+                }
+                color.posterize(count.v).into_value()
+            }
+            Quantizable::Colors(colors) => {
+                if count.v == 0 {
+                    bail!(count.span, "palette must have at least one color");
+                }
+                kmeans_palette(&colors, count.v).into_value()
+            }
+        })
     }
 
     /// Lightens a color by a given factor.
@@ -866,11 +1923,25 @@ impl Color {
             Self::Luma(c) => Self::Luma(c.lighten(factor)),
             Self::Oklab(c) => Self::Oklab(c.lighten(factor)),
             Self::Oklch(c) => Self::Oklch(c.lighten(factor)),
+            Self::Lab(c) => Self::Lab(c.lighten(factor)),
+            Self::Lch(c) => Self::Lch(c.lighten(factor)),
+            Self::Xyz(c) => Self::Xyz(Xyz::from_lab(c.to_lab().lighten(factor), c.white)),
             Self::LinearRgb(c) => Self::LinearRgb(c.lighten(factor)),
             Self::Rgb(c) => Self::Rgb(c.lighten(factor)),
+            Self::P3(c) => Self::P3(c.lighten(factor)),
             Self::Cmyk(c) => Self::Cmyk(c.lighten(factor)),
             Self::Hsl(c) => Self::Hsl(c.lighten(factor)),
             Self::Hsv(c) => Self::Hsv(c.lighten(factor)),
+            Self::Hwb(c) => Self::Hwb(c.lighten(factor)),
+            Self::Okhsl(mut c) => {
+                c.lightness += (1.0 - c.lightness) * factor;
+                Self::Okhsl(c)
+            }
+            Self::Okhsv(mut c) => {
+                c.value += (1.0 - c.value) * factor;
+                Self::Okhsv(c)
+            }
+            Self::Icc(c) => Self::Icc(c.lighten(factor)),
         }
     }
 
@@ -886,14 +1957,104 @@ impl Color {
             Self::Luma(c) => Self::Luma(c.darken(factor)),
             Self::Oklab(c) => Self::Oklab(c.darken(factor)),
             Self::Oklch(c) => Self::Oklch(c.darken(factor)),
+            Self::Lab(c) => Self::Lab(c.darken(factor)),
+            Self::Lch(c) => Self::Lch(c.darken(factor)),
+            Self::Xyz(c) => Self::Xyz(Xyz::from_lab(c.to_lab().darken(factor), c.white)),
             Self::LinearRgb(c) => Self::LinearRgb(c.darken(factor)),
             Self::Rgb(c) => Self::Rgb(c.darken(factor)),
+            Self::P3(c) => Self::P3(c.darken(factor)),
             Self::Cmyk(c) => Self::Cmyk(c.darken(factor)),
             Self::Hsl(c) => Self::Hsl(c.darken(factor)),
             Self::Hsv(c) => Self::Hsv(c.darken(factor)),
+            Self::Hwb(c) => Self::Hwb(c.darken(factor)),
+            Self::Okhsl(mut c) => {
+                c.lightness *= 1.0 - factor;
+                Self::Okhsl(c)
+            }
+            Self::Okhsv(mut c) => {
+                c.value *= 1.0 - factor;
+                Self::Okhsv(c)
+            }
+            Self::Icc(c) => Self::Icc(c.darken(factor)),
         }
     }
 
+    /// Tints a color by mixing it with white.
+    ///
+    /// Unlike [`lighten`]($color.lighten), which behaves differently depending
+    /// on the color's space, this always blends toward white by the given
+    /// factor, like Sass's `tint` function. The result is in the same space
+    /// and keeps the opacity of the original color.
+    ///
+    /// ```example
+    /// #let base = rgb("#239dad")
+    /// #stack(dir: ltr, ..(0%, 25%, 50%, 75%).map(f => square(
+    ///   size: 20pt, fill: base.tint(f),
+    /// )))
+    /// ```
+    #[func]
+    pub fn tint(
+        self,
+        /// How far to move toward white, from `{0%}` (unchanged) to `{100%}`
+        /// (white).
+        factor: Ratio,
+        /// The color space in which to mix. Use `{rgb}` to get the same results
+        /// as Sass.
+        #[named]
+        #[default(ColorSpace::Oklab)]
+        space: ColorSpace,
+    ) -> StrResult<Color> {
+        self.mix_toward(Color::WHITE, factor, space)
+    }
+
+    /// Shades a color by mixing it with black.
+    ///
+    /// This is the counterpart to [`tint`]($color.tint).
+    ///
+    /// ```example
+    /// #let base = rgb("#239dad")
+    /// #stack(dir: ltr, ..(0%, 25%, 50%, 75%).map(f => square(
+    ///   size: 20pt, fill: base.shade(f),
+    /// )))
+    /// ```
+    #[func]
+    pub fn shade(
+        self,
+        /// How far to move toward black, from `{0%}` (unchanged) to `{100%}`
+        /// (black).
+        factor: Ratio,
+        /// The color space in which to mix.
+        #[named]
+        #[default(ColorSpace::Oklab)]
+        space: ColorSpace,
+    ) -> StrResult<Color> {
+        self.mix_toward(Color::BLACK, factor, space)
+    }
+
+    /// Tones a color by mixing it with a medium gray (`{luma(50%)}`).
+    ///
+    /// This mutes a color while keeping its lightness roughly in place.
+    ///
+    /// ```example
+    /// #let base = rgb("#239dad")
+    /// #stack(dir: ltr, ..(0%, 25%, 50%, 75%).map(f => square(
+    ///   size: 20pt, fill: base.tone(f),
+    /// )))
+    /// ```
+    #[func]
+    pub fn tone(
+        self,
+        /// How far to move toward gray, from `{0%}` (unchanged) to `{100%}`
+        /// (gray).
+        factor: Ratio,
+        /// The color space in which to mix.
+        #[named]
+        #[default(ColorSpace::Oklab)]
+        space: ColorSpace,
+    ) -> StrResult<Color> {
+        self.mix_toward(Color::Luma(Luma::new(0.5, 1.0)), factor, space)
+    }
+
     /// Increases the saturation of a color by a given factor.
     #[func]
     pub fn saturate(
@@ -912,11 +2073,25 @@ impl Color {
             }
             Self::Oklab(_) => self.to_hsv().saturate(span, factor)?.to_oklab(),
             Self::Oklch(_) => self.to_hsv().saturate(span, factor)?.to_oklch(),
+            Self::Lab(_) => self.to_hsv().saturate(span, factor)?.to_lab(),
+            Self::Lch(_) => self.to_hsv().saturate(span, factor)?.to_lch(),
+            Self::Xyz(c) => self.to_hsv().saturate(span, factor)?.to_xyz_with(c.white),
             Self::LinearRgb(_) => self.to_hsv().saturate(span, factor)?.to_linear_rgb(),
             Self::Rgb(_) => self.to_hsv().saturate(span, factor)?.to_rgb(),
+            Self::P3(_) => self.to_hsv().saturate(span, factor)?.to_p3(),
             Self::Cmyk(_) => self.to_hsv().saturate(span, factor)?.to_cmyk(),
             Self::Hsl(c) => Self::Hsl(c.saturate(factor.get() as f32)),
             Self::Hsv(c) => Self::Hsv(c.saturate(factor.get() as f32)),
+            Self::Hwb(_) => self.to_hsv().saturate(span, factor)?.to_hwb(),
+            Self::Okhsl(mut c) => {
+                c.saturation += (1.0 - c.saturation) * factor.get() as f32;
+                Self::Okhsl(c)
+            }
+            Self::Okhsv(mut c) => {
+                c.saturation += (1.0 - c.saturation) * factor.get() as f32;
+                Self::Okhsv(c)
+            }
+            Self::Icc(_) => self.to_hsv().saturate(span, factor)?.to_space(self.space()),
         })
     }
 
@@ -938,11 +2113,27 @@ impl Color {
             }
             Self::Oklab(_) => self.to_hsv().desaturate(span, factor)?.to_oklab(),
             Self::Oklch(_) => self.to_hsv().desaturate(span, factor)?.to_oklch(),
+            Self::Lab(_) => self.to_hsv().desaturate(span, factor)?.to_lab(),
+            Self::Lch(_) => self.to_hsv().desaturate(span, factor)?.to_lch(),
+            Self::Xyz(c) => self.to_hsv().desaturate(span, factor)?.to_xyz_with(c.white),
             Self::LinearRgb(_) => self.to_hsv().desaturate(span, factor)?.to_linear_rgb(),
             Self::Rgb(_) => self.to_hsv().desaturate(span, factor)?.to_rgb(),
+            Self::P3(_) => self.to_hsv().desaturate(span, factor)?.to_p3(),
             Self::Cmyk(_) => self.to_hsv().desaturate(span, factor)?.to_cmyk(),
             Self::Hsl(c) => Self::Hsl(c.desaturate(factor.get() as f32)),
             Self::Hsv(c) => Self::Hsv(c.desaturate(factor.get() as f32)),
+            Self::Hwb(_) => self.to_hsv().desaturate(span, factor)?.to_hwb(),
+            Self::Okhsl(mut c) => {
+                c.saturation *= 1.0 - factor.get() as f32;
+                Self::Okhsl(c)
+            }
+            Self::Okhsv(mut c) => {
+                c.saturation *= 1.0 - factor.get() as f32;
+                Self::Okhsv(c)
+            }
+            Self::Icc(_) => {
+                self.to_hsv().desaturate(span, factor)?.to_space(self.space())
+            }
         })
     }
 
@@ -971,6 +2162,17 @@ impl Color {
                 OklabHue::from_degrees(c.hue.into_degrees() + 180.0),
                 c.alpha,
             )),
+            Self::Lab(c) => Self::Lab(Lab::new(100.0 - c.l, -c.a, -c.b, c.alpha)),
+            Self::Lch(c) => Self::Lch(Lch::new(
+                100.0 - c.l,
+                c.chroma,
+                LabHue::from_degrees(c.hue.into_degrees() + 180.0),
+                c.alpha,
+            )),
+            Self::Xyz(c) => {
+                let [x, y, z] = c.white.xyz();
+                Self::Xyz(Xyz::new(x - c.x, y - c.y, z - c.z, c.alpha, c.white))
+            }
             Self::LinearRgb(c) => Self::LinearRgb(LinearRgb::new(
                 1.0 - c.red,
                 1.0 - c.green,
@@ -980,7 +2182,13 @@ impl Color {
             Self::Rgb(c) => {
                 Self::Rgb(Rgb::new(1.0 - c.red, 1.0 - c.green, 1.0 - c.blue, c.alpha))
             }
-            Self::Cmyk(c) => Self::Cmyk(Cmyk::new(1.0 - c.c, 1.0 - c.m, 1.0 - c.y, c.k)),
+            Self::P3(c) => {
+                Self::P3(P3::new(1.0 - c.red, 1.0 - c.green, 1.0 - c.blue, c.alpha))
+            }
+            Self::Cmyk(c) => Self::Cmyk(Cmyk {
+                overprint: c.overprint,
+                ..Cmyk::new(1.0 - c.c, 1.0 - c.m, 1.0 - c.y, c.k)
+            }),
             Self::Hsl(c) => Self::Hsl(Hsl::new(
                 RgbHue::from_degrees(c.hue.into_degrees() + 180.0),
                 c.saturation,
@@ -993,6 +2201,26 @@ impl Color {
                 c.value,
                 c.alpha,
             )),
+            Self::Hwb(c) => Self::Hwb(Hwb::new(
+                RgbHue::from_degrees(c.hue.into_degrees() + 180.0),
+                c.whiteness,
+                c.blackness,
+                c.alpha,
+            )),
+            Self::Okhsl(c) => Self::Okhsl(Okhsl::new(
+                OklabHue::from_degrees(c.hue.into_degrees() + 180.0),
+                c.saturation,
+                c.lightness,
+                c.alpha,
+            )),
+            Self::Okhsv(c) => Self::Okhsv(Okhsv::new(
+                OklabHue::from_degrees(c.hue.into_degrees() + 180.0),
+                c.saturation,
+                c.value,
+                c.alpha,
+            )),
+            // Conversions never produce colors in an ICC profile.
+            Self::Icc(_) => unreachable!(),
         };
         result.to_space(self.space())
     }
@@ -1019,6 +2247,13 @@ impl Color {
                 let rotated = oklch.shift_hue(angle.to_deg() as f32);
                 Self::Oklch(rotated).to_space(self.space())
             }
+            ColorSpace::Lch => {
+                let Self::Lch(lch) = self.to_lch() else {
+                    unreachable!();
+                };
+                let rotated = lch.shift_hue(angle.to_deg() as f32);
+                Self::Lch(rotated).to_space(self.space())
+            }
             ColorSpace::Hsl => {
                 let Self::Hsl(hsl) = self.to_hsl() else {
                     unreachable!();
@@ -1026,46 +2261,371 @@ impl Color {
                 let rotated = hsl.shift_hue(angle.to_deg() as f32);
                 Self::Hsl(rotated).to_space(self.space())
             }
-            ColorSpace::Hsv => {
-                let Self::Hsv(hsv) = self.to_hsv() else {
-                    unreachable!();
-                };
-                let rotated = hsv.shift_hue(angle.to_deg() as f32);
-                Self::Hsv(rotated).to_space(self.space())
+            ColorSpace::Hsv => {
+                let Self::Hsv(hsv) = self.to_hsv() else {
+                    unreachable!();
+                };
+                let rotated = hsv.shift_hue(angle.to_deg() as f32);
+                Self::Hsv(rotated).to_space(self.space())
+            }
+            ColorSpace::Hwb => {
+                let Self::Hwb(hwb) = self.to_hwb() else {
+                    unreachable!();
+                };
+                let rotated = hwb.shift_hue(angle.to_deg() as f32);
+                Self::Hwb(rotated).to_space(self.space())
+            }
+            ColorSpace::Okhsl => {
+                let Self::Okhsl(mut okhsl) = self.to_okhsl() else {
+                    unreachable!();
+                };
+                okhsl.hue = OklabHue::from_degrees(
+                    okhsl.hue.into_degrees() + angle.to_deg() as f32,
+                );
+                Self::Okhsl(okhsl).to_space(self.space())
+            }
+            ColorSpace::Okhsv => {
+                let Self::Okhsv(mut okhsv) = self.to_okhsv() else {
+                    unreachable!();
+                };
+                okhsv.hue = OklabHue::from_degrees(
+                    okhsv.hue.into_degrees() + angle.to_deg() as f32,
+                );
+                Self::Okhsv(okhsv).to_space(self.space())
+            }
+            _ => bail!(span, "this colorspace does not support hue rotation"),
+        })
+    }
+
+    /// Creates an array of colors with neighbouring hues.
+    ///
+    /// The colors are spread evenly around this color, which lies in the
+    /// middle of the array (for an odd count). Like [`rotate`]($color.rotate),
+    /// this rotates the hue in [`oklch`]($color.oklch) by default.
+    ///
+    /// ```example
+    /// #for c in teal.analogous(5) {
+    ///   box(square(size: 1em, fill: c))
+    /// }
+    /// ```
+    #[func]
+    pub fn analogous(
+        self,
+        /// The call span.
+        span: Span,
+        /// How many colors to create, including this one.
+        #[default(3)]
+        count: usize,
+        /// The hue difference between neighbouring colors.
+        #[named]
+        #[default(Angle::deg(30.0))]
+        angle: Angle,
+        /// The color space used to rotate the hue.
+        #[named]
+        #[default(ColorSpace::Oklch)]
+        space: ColorSpace,
+    ) -> SourceResult<Vec<Color>> {
+        let center = count.saturating_sub(1) as f64 / 2.0;
+        self.harmony(span, space, (0..count).map(|i| angle * (i as f64 - center)))
+    }
+
+    /// Creates an array of three colors whose hues are evenly spaced around
+    /// the color wheel, starting with this color.
+    ///
+    /// ```example
+    /// #for c in purple.triadic() {
+    ///   box(square(size: 1em, fill: c))
+    /// }
+    /// ```
+    #[func]
+    pub fn triadic(
+        self,
+        /// The call span.
+        span: Span,
+        /// The color space used to rotate the hue.
+        #[named]
+        #[default(ColorSpace::Oklch)]
+        space: ColorSpace,
+    ) -> SourceResult<Vec<Color>> {
+        self.harmony(span, space, [0.0, 120.0, 240.0].map(Angle::deg))
+    }
+
+    /// Creates an array of this color and the two colors adjacent to its
+    /// complement.
+    ///
+    /// ```example
+    /// #for c in orange.split-complementary() {
+    ///   box(square(size: 1em, fill: c))
+    /// }
+    /// ```
+    #[func]
+    pub fn split_complementary(
+        self,
+        /// The call span.
+        span: Span,
+        /// How far the two colors are from the exact complement.
+        #[named]
+        #[default(Angle::deg(30.0))]
+        angle: Angle,
+        /// The color space used to rotate the hue.
+        #[named]
+        #[default(ColorSpace::Oklch)]
+        space: ColorSpace,
+    ) -> SourceResult<Vec<Color>> {
+        let complement = Angle::deg(180.0);
+        self.harmony(span, space, [Angle::zero(), complement - angle, complement + angle])
+    }
+
+    /// Create a color by mixing two or more colors.
+    ///
+    /// In color spaces with a hue component (hsl, hsv, hwb, okhsl, okhsv,
+    /// oklch, lch), only two colors can be mixed at once. Mixing more than two
+    /// colors in such a space will result in an error!
+    ///
+    /// ```example
+    /// #set block(height: 20pt, width: 100%)
+    /// #block(fill: red.mix(blue))
+    /// #block(fill: red.mix(blue, space: rgb))
+    /// #block(fill: color.mix(red, blue, white))
+    /// #block(fill: color.mix((red, 70%), (blue, 30%)))
+    /// ```
+    #[func]
+    pub fn mix(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The call span.
+        span: Span,
+        /// The colors, optionally with weights, specified as a pair (array of
+        /// length two) of color and weight (float or ratio).
+        ///
+        /// The weights do not need to add to `{100%}`, they are relative to the
+        /// sum of all weights.
+        #[variadic]
+        colors: Vec<WeightedColor>,
+        /// The color space to mix in. By default, this happens in a perceptual
+        /// color space ([`oklab`]($color.oklab)).
+        #[named]
+        #[default(ColorSpace::Oklab)]
+        space: ColorSpace,
+        /// The direction in which to interpolate hues when mixing two colors
+        /// in a hue-based color space, following CSS Color 4.
+        ///
+        /// - `{"shorter"}` takes the short way around the hue circle.
+        /// - `{"longer"}` takes the long way around the hue circle.
+        /// - `{"increasing"}` always moves to larger hue angles.
+        /// - `{"decreasing"}` always moves to smaller hue angles.
+        ///
+        /// ```example
+        /// #set block(height: 20pt, width: 100%)
+        /// #block(fill: red.mix(blue, space: oklch))
+        /// #block(fill: red.mix(blue, space: oklch, hue: "longer"))
+        /// ```
+        #[named]
+        #[default(HueInterpolation::Shorter)]
+        hue: HueInterpolation,
+        /// How to warp the weight of the second color when mixing two colors.
+        ///
+        /// The easing receives the second color's share of the total weight
+        /// as a float between `{0}` and `{1}` and determines the share that is
+        /// actually used. It can be one of the CSS keywords `{"linear"}`,
+        /// `{"ease"}`, `{"ease-in"}`, `{"ease-out"}`, and `{"ease-in-out"}`,
+        /// an array `{(x1, y1, x2, y2)}` with the control points of a CSS-style
+        /// cubic Bézier curve, or a function mapping one float to another.
+        /// The result is clamped between `{0}` and `{1}`.
+        ///
+        /// ```example
+        /// #set block(height: 20pt, width: 100%)
+        /// #block(fill: color.mix((red, 25%), (blue, 75%)))
+        /// #block(fill: color.mix((red, 25%), (blue, 75%), easing: "ease-in"))
+        /// #block(fill: color.mix((red, 25%), (blue, 75%), easing: t => t * t))
+        /// ```
+        #[named]
+        #[default(Easing::Linear)]
+        easing: Easing,
+    ) -> SourceResult<Color> {
+        if easing.is_linear() {
+            return Self::mix_iter(colors, space, hue).at(span);
+        }
+
+        let Ok([first, second]) = <[WeightedColor; 2]>::try_from(colors) else {
+            bail!(span, "easing can only be used when mixing two colors");
+        };
+
+        let total = first.weight + second.weight;
+        if total <= 0.0 {
+            bail!(span, "sum of weights must be positive");
+        }
+
+        let t = easing.apply_with(engine, context, span, second.weight / total)?;
+        Self::mix_iter(
+            [
+                WeightedColor::new(first.color, 1.0 - t),
+                WeightedColor::new(second.color, t),
+            ],
+            space,
+            hue,
+        )
+        .at(span)
+    }
+
+    /// Composites this color onto a backdrop color, taking the opacity of
+    /// both into account.
+    ///
+    /// This computes the color that results from drawing this color on top
+    /// of the backdrop, just like a renderer would do for two overlapping
+    /// fills. Unlike [`mix`]($color.mix), which averages the components of
+    /// the colors, this correctly handles translucent colors: A fully
+    /// transparent color leaves the backdrop unchanged and a fully opaque one
+    /// replaces it.
+    ///
+    /// ```example
+    /// #let glass = blue.transparentize(60%)
+    /// #box(square(size: 20pt, fill: glass.composite(yellow)))
+    /// #box(square(size: 20pt, fill: glass.composite(white)))
+    /// #box(square(size: 20pt, fill: glass.composite(yellow, mode: "xor")))
+    /// ```
+    #[func]
+    pub fn composite(
+        self,
+        /// The color below this one.
+        backdrop: Color,
+        /// The Porter-Duff operator with which to combine the colors.
+        #[named]
+        #[default]
+        mode: CompositeMode,
+        /// The color space to composite in. The result is a color in this
+        /// space. Compositing is not possible in hue-based spaces and in CMYK.
+        #[named]
+        #[default(ColorSpace::Srgb)]
+        space: ColorSpace,
+    ) -> StrResult<Color> {
+        if space.hue_index().is_some() {
+            bail!("cannot composite colors in a hue-based space");
+        } else if space == ColorSpace::Cmyk {
+            bail!("cannot composite colors in CMYK, as it has no alpha component");
+        }
+
+        let source = self.to_space(space).to_vec4();
+        let backdrop = backdrop.to_space(space).to_vec4();
+        let (a_s, a_b) = (source[3], backdrop[3]);
+        let (f_s, f_b) = mode.factors(a_s, a_b);
+
+        let alpha = (a_s * f_s + a_b * f_b).clamp(0.0, 1.0);
+        let mut m = [0.0; 4];
+        if alpha > 0.0 {
+            for i in 0..3 {
+                m[i] = (a_s * source[i] * f_s + a_b * backdrop[i] * f_b) / alpha;
+            }
+        }
+        m[3] = alpha;
+
+        Ok(Self::from_space_vec4(space, m))
+    }
+
+    /// Blends this color with a backdrop color using a blend mode.
+    ///
+    /// This computes the color that a renderer produces when this color is
+    /// drawn on top of the backdrop with [`blend`]($blend). The result takes
+    /// the opacity of both colors into account, so blending a fully
+    /// transparent color leaves the backdrop unchanged.
+    ///
+    /// ```example
+    /// #for mode in ("multiply", "screen", "overlay", "difference") {
+    ///   box(square(size: 20pt, fill: orange.blend(blue, mode: mode)))
+    /// }
+    /// ```
+    #[func]
+    pub fn blend(
+        self,
+        /// The color below this one.
+        backdrop: Color,
+        /// How to combine the colors. See the [blend
+        /// function's documentation]($blend.mode) for the available modes.
+        #[named]
+        #[default(BlendMode::Multiply)]
+        mode: BlendMode,
+        /// The color space to blend in. The result is a color in this space.
+        /// Blending is only possible in RGB spaces and in grayscale.
+        #[named]
+        #[default(ColorSpace::Srgb)]
+        space: ColorSpace,
+    ) -> StrResult<Color> {
+        if !matches!(
+            space,
+            ColorSpace::Srgb
+                | ColorSpace::LinearRgb
+                | ColorSpace::DisplayP3
+                | ColorSpace::D65Gray
+        ) {
+            bail!("colors can only be blended in RGB and grayscale spaces");
+        }
+
+        let [rs, gs, bs, a_s] = self.to_space(space).to_vec4().map(clamp_component);
+        let [rb, gb, bb, a_b] = backdrop.to_space(space).to_vec4().map(clamp_component);
+        let blended = mode.apply([rb, gb, bb], [rs, gs, bs]);
+
+        // Mix the blended color into the source where the backdrop is opaque
+        // and composite the result over the backdrop.
+        let alpha = a_s + a_b * (1.0 - a_s);
+        let (source, backdrop) = ([rs, gs, bs], [rb, gb, bb]);
+        let mut m = [0.0; 4];
+        if alpha > 0.0 {
+            for i in 0..3 {
+                let mixed = (1.0 - a_b) * source[i] + a_b * blended[i];
+                m[i] = (a_s * mixed + a_b * backdrop[i] * (1.0 - a_s)) / alpha;
             }
-            _ => bail!(span, "this colorspace does not support hue rotation"),
-        })
+        }
+        m[3] = alpha;
+
+        Ok(Self::from_space_vec4(space, m))
     }
 
-    /// Create a color by mixing two or more colors.
+    /// Sorts colors by one of their components in a color space.
     ///
-    /// In color spaces with a hue component (hsl, hsv, oklch), only two colors
-    /// can be mixed at once. Mixing more than two colors in such a space will
-    /// result in an error!
+    /// The colors are returned in ascending order of the chosen component.
+    /// Colors with equal components keep their relative order. This is useful
+    /// to order the entries of a palette for a legend or a set of swatches.
     ///
     /// ```example
-    /// #set block(height: 20pt, width: 100%)
-    /// #block(fill: red.mix(blue))
-    /// #block(fill: red.mix(blue, space: rgb))
-    /// #block(fill: color.mix(red, blue, white))
-    /// #block(fill: color.mix((red, 70%), (blue, 30%)))
+    /// #let palette = (yellow, navy, red, aqua, gray)
+    /// #for c in color.sort(palette, by: "lightness") {
+    ///   box(square(size: 9pt, fill: c))
+    /// }
+    ///
+    /// #for c in color.sort(palette, by: "hue") {
+    ///   box(square(size: 9pt, fill: c))
+    /// }
     /// ```
     #[func]
-    pub fn mix(
-        /// The colors, optionally with weights, specified as a pair (array of
-        /// length two) of color and weight (float or ratio).
+    pub fn sort(
+        /// The colors to sort.
+        colors: Vec<Color>,
+        /// The component to sort by.
         ///
-        /// The weights do not need to add to `{100%}`, they are relative to the
-        /// sum of all weights.
-        #[variadic]
-        colors: Vec<WeightedColor>,
-        /// The color space to mix in. By default, this happens in a perceptual
-        /// color space ([`oklab`]($color.oklab)).
+        /// The color space must have a component of this name. For example,
+        /// colors can be sorted by `{"lightness"}` in [`oklch`]($color.oklch)
+        /// or [`hsl`]($color.hsl), but not in [`rgb`]($color.rgb).
         #[named]
-        #[default(ColorSpace::Oklab)]
+        #[default]
+        by: ColorSortKey,
+        /// The color space in which to compare the colors.
+        #[named]
+        #[default(ColorSpace::Oklch)]
         space: ColorSpace,
-    ) -> StrResult<Color> {
-        Self::mix_iter(colors, space)
+    ) -> StrResult<Vec<Color>> {
+        let name = by.component_name();
+        let Some(index) = space.component_names().iter().position(|&n| n == name) else {
+            bail!("color space does not have a {name} component");
+        };
+
+        let mut keyed: Vec<_> = colors
+            .into_iter()
+            .map(|color| (color.to_space(space).to_vec4()[index], color))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Ok(keyed.into_iter().map(|(_, color)| color).collect())
     }
 
     /// Makes a color more transparent by a given factor.
@@ -1108,9 +2668,200 @@ impl Color {
     ) -> StrResult<Color> {
         self.scale_alpha(scale)
     }
+
+    /// Sets whether a CMYK color overprints the colors below it.
+    ///
+    /// By default, a color knocks out everything that is printed below it.
+    /// When overprinting, only the inks the color actually uses replace the
+    /// ones below, while the other inks stay. Print shops often require this
+    /// for black text on colored backgrounds, so that small misalignments
+    /// between the printing plates don't leave white gaps.
+    ///
+    /// Overprinting only affects PDF export and is typically only visible in
+    /// a print preview. It is only supported for CMYK colors.
+    ///
+    /// ```example
+    /// #let black = cmyk(0%, 0%, 0%, 100%).overprint()
+    /// #rect(fill: cmyk(60%, 0%, 10%, 0%), inset: 8pt)[
+    ///   #text(fill: black)[Overprinted]
+    /// ]
+    /// ```
+    #[func]
+    pub fn overprint(
+        self,
+        /// Whether to overprint.
+        #[default(true)]
+        enabled: bool,
+    ) -> StrResult<Color> {
+        match self {
+            Self::Cmyk(c) => Ok(Self::Cmyk(Cmyk { overprint: enabled, ..c })),
+            _ => bail!("only CMYK colors can overprint"),
+        }
+    }
 }
 
 impl Color {
+    /// Extracts the components of this color, in the order listed in the
+    /// documentation of [`to_components`](Self::to_components).
+    pub fn components(self, alpha: bool) -> Array {
+        let mut components = match self {
+            Self::Luma(c) => {
+                array![Ratio::new(c.luma.into()), Ratio::new(c.alpha.into())]
+            }
+            Self::Oklab(c) => {
+                array![
+                    Ratio::new(c.l.into()),
+                    f64::from(c.a),
+                    f64::from(c.b),
+                    Ratio::new(c.alpha.into())
+                ]
+            }
+            Self::Oklch(c) => {
+                array![
+                    Ratio::new(c.l.into()),
+                    f64::from(c.chroma),
+                    hue_angle(c.hue.into_degrees()),
+                    Ratio::new(c.alpha.into()),
+                ]
+            }
+            Self::Lab(c) => {
+                array![
+                    Ratio::new(f64::from(c.l) / 100.0),
+                    f64::from(c.a),
+                    f64::from(c.b),
+                    Ratio::new(c.alpha.into())
+                ]
+            }
+            Self::Lch(c) => {
+                array![
+                    Ratio::new(f64::from(c.l) / 100.0),
+                    f64::from(c.chroma),
+                    hue_angle(c.hue.into_degrees()),
+                    Ratio::new(c.alpha.into()),
+                ]
+            }
+            Self::Xyz(c) => {
+                array![
+                    f64::from(c.x),
+                    f64::from(c.y),
+                    f64::from(c.z),
+                    Ratio::new(c.alpha.into()),
+                ]
+            }
+            Self::LinearRgb(c) => {
+                array![
+                    Ratio::new(c.red.into()),
+                    Ratio::new(c.green.into()),
+                    Ratio::new(c.blue.into()),
+                    Ratio::new(c.alpha.into()),
+                ]
+            }
+            Self::Rgb(c) => {
+                array![
+                    Ratio::new(c.red.into()),
+                    Ratio::new(c.green.into()),
+                    Ratio::new(c.blue.into()),
+                    Ratio::new(c.alpha.into()),
+                ]
+            }
+            Self::P3(c) => {
+                array![
+                    Ratio::new(c.red.into()),
+                    Ratio::new(c.green.into()),
+                    Ratio::new(c.blue.into()),
+                    Ratio::new(c.alpha.into()),
+                ]
+            }
+            Self::Cmyk(c) => {
+                array![
+                    Ratio::new(c.c.into()),
+                    Ratio::new(c.m.into()),
+                    Ratio::new(c.y.into()),
+                    Ratio::new(c.k.into())
+                ]
+            }
+            Self::Hsl(c) => {
+                array![
+                    hue_angle(c.hue.into_degrees()),
+                    Ratio::new(c.saturation.into()),
+                    Ratio::new(c.lightness.into()),
+                    Ratio::new(c.alpha.into()),
+                ]
+            }
+            Self::Hsv(c) => {
+                array![
+                    hue_angle(c.hue.into_degrees()),
+                    Ratio::new(c.saturation.into()),
+                    Ratio::new(c.value.into()),
+                    Ratio::new(c.alpha.into()),
+                ]
+            }
+            Self::Hwb(c) => {
+                array![
+                    hue_angle(c.hue.into_degrees()),
+                    Ratio::new(c.whiteness.into()),
+                    Ratio::new(c.blackness.into()),
+                    Ratio::new(c.alpha.into()),
+                ]
+            }
+            Self::Okhsl(c) => {
+                array![
+                    hue_angle(c.hue.into_degrees()),
+                    Ratio::new(c.saturation.into()),
+                    Ratio::new(c.lightness.into()),
+                    Ratio::new(c.alpha.into()),
+                ]
+            }
+            Self::Okhsv(c) => {
+                array![
+                    hue_angle(c.hue.into_degrees()),
+                    Ratio::new(c.saturation.into()),
+                    Ratio::new(c.value.into()),
+                    Ratio::new(c.alpha.into()),
+                ]
+            }
+            Self::Icc(c) => {
+                let mut components: Array = c
+                    .channels()
+                    .iter()
+                    .map(|&v| Ratio::new(v.into()).into_value())
+                    .collect();
+                if let Some(alpha) = c.alpha() {
+                    components.push(Ratio::new(alpha.into()).into_value());
+                }
+                components
+            }
+        };
+        // Remove the alpha component if the corresponding argument was set.
+        if !alpha && self.alpha().is_some() {
+            let _ = components.pop();
+        }
+        components
+    }
+
+    /// Extracts the components of this color, keyed by their names.
+    pub fn named_components(self, alpha: bool) -> Dict {
+        self.space()
+            .component_names()
+            .iter()
+            .map(|&name| name.into())
+            .zip(self.components(alpha))
+            .collect()
+    }
+
+    /// Rotates the hue of the color by each of the given angles.
+    fn harmony(
+        self,
+        span: Span,
+        space: ColorSpace,
+        angles: impl IntoIterator<Item = Angle>,
+    ) -> SourceResult<Vec<Color>> {
+        angles
+            .into_iter()
+            .map(|angle| self.rotate(span, angle, space))
+            .collect()
+    }
+
     /// Same as [`Color::mix`], but takes an iterator instead of a vector.
     pub fn mix_iter(
         colors: impl IntoIterator<
@@ -1118,6 +2869,7 @@ impl Color {
             IntoIter = impl ExactSizeIterator<Item = WeightedColor>,
         >,
         space: ColorSpace,
+        hue: HueInterpolation,
     ) -> StrResult<Color> {
         let mut colors = colors.into_iter();
         if space.hue_index().is_some() && colors.len() > 2 {
@@ -1143,16 +2895,10 @@ impl Color {
                 m[i] = (w0 * c0[i] + w1 * c1[i]) / (w0 + w1);
             }
 
-            // Ensure that the hue circle is traversed in the short direction.
+            // Ensure that the hue circle is traversed in the right direction.
             if let Some(index) = space.hue_index() {
-                if (c0[index] - c1[index]).abs() > 180.0 {
-                    let (h0, h1) = if c0[index] < c1[index] {
-                        (c0[index] + 360.0, c1[index])
-                    } else {
-                        (c0[index], c1[index] + 360.0)
-                    };
-                    m[index] = (w0 * h0 + w1 * h1) / (w0 + w1);
-                }
+                let (h0, h1) = hue.adjust(c0[index], c1[index]);
+                m[index] = (w0 * h0 + w1 * h1) / (w0 + w1);
             }
 
             m
@@ -1177,10 +2923,22 @@ impl Color {
             acc.map(|v| v / total)
         };
 
-        Ok(match space {
+        Ok(Self::from_space_vec4(space, m))
+    }
+
+    /// Creates a color in the given space from a vec of its four components,
+    /// as returned by [`to_vec4`](Self::to_vec4).
+    ///
+    /// For grayscale colors, the second and third components are ignored.
+    pub fn from_space_vec4(space: ColorSpace, m: [f32; 4]) -> Self {
+        match space {
             ColorSpace::Oklab => Color::Oklab(Oklab::new(m[0], m[1], m[2], m[3])),
             ColorSpace::Oklch => Color::Oklch(Oklch::new(m[0], m[1], m[2], m[3])),
+            ColorSpace::Lab => Color::Lab(Lab::new(m[0], m[1], m[2], m[3])),
+            ColorSpace::Lch => Color::Lch(Lch::new(m[0], m[1], m[2], m[3])),
+            ColorSpace::Xyz(white) => Color::Xyz(Xyz::new(m[0], m[1], m[2], m[3], white)),
             ColorSpace::Srgb => Color::Rgb(Rgb::new(m[0], m[1], m[2], m[3])),
+            ColorSpace::DisplayP3 => Color::P3(P3::new(m[0], m[1], m[2], m[3])),
             ColorSpace::LinearRgb => {
                 Color::LinearRgb(LinearRgb::new(m[0], m[1], m[2], m[3]))
             }
@@ -1190,9 +2948,18 @@ impl Color {
             ColorSpace::Hsv => {
                 Color::Hsv(Hsv::new(RgbHue::from_degrees(m[0]), m[1], m[2], m[3]))
             }
+            ColorSpace::Hwb => {
+                Color::Hwb(Hwb::new(RgbHue::from_degrees(m[0]), m[1], m[2], m[3]))
+            }
+            ColorSpace::Okhsl => {
+                Color::Okhsl(Okhsl::new(OklabHue::from_degrees(m[0]), m[1], m[2], m[3]))
+            }
+            ColorSpace::Okhsv => {
+                Color::Okhsv(Okhsv::new(OklabHue::from_degrees(m[0]), m[1], m[2], m[3]))
+            }
             ColorSpace::Cmyk => Color::Cmyk(Cmyk::new(m[0], m[1], m[2], m[3])),
-            ColorSpace::D65Gray => Color::Luma(Luma::new(m[0], m[1])),
-        })
+            ColorSpace::D65Gray => Color::Luma(Luma::new(m[0], m[3])),
+        }
     }
 
     /// Construct a new RGBA color from 8-bit values.
@@ -1215,6 +2982,126 @@ impl Color {
         )
     }
 
+    /// Parses a color from a CSS color string.
+    pub fn from_css(text: &str) -> StrResult<Self> {
+        let text = text.trim().to_ascii_lowercase();
+        if text.starts_with('#') {
+            return Self::from_str(&text).map_err(Into::into);
+        }
+
+        let Some((name, args)) = text.split_once('(') else {
+            if text == "transparent" {
+                return Ok(Self::from_u32(0x00000000));
+            }
+            return match CSS_COLORS.iter().find(|&&(key, _)| key == text) {
+                Some(&(_, color)) => Ok(Self::from_u32(color)),
+                None => bail!("unknown color name `{text}`"),
+            };
+        };
+
+        let Some(args) = args.strip_suffix(')') else {
+            bail!("expected closing parenthesis");
+        };
+
+        let (mut components, alpha) = css_components(args)?;
+        let space = match name.trim_end() {
+            "color" if components.is_empty() => bail!("expected color space"),
+            "color" => match components.remove(0) {
+                CssComponent::Ident(space) => Some(space),
+                _ => bail!("expected color space"),
+            },
+            _ => None,
+        };
+
+        let [c1, c2, c3] = match <[CssComponent; 3]>::try_from(components) {
+            Ok(components) => components,
+            Err(components) => {
+                bail!("expected 3 color components, found {}", components.len())
+            }
+        };
+
+        let alpha = match alpha {
+            Some(alpha) => alpha.number(1.0)?.clamp(0.0, 1.0),
+            None => 1.0,
+        };
+
+        Ok(match (name.trim_end(), space.as_deref()) {
+            ("rgb" | "rgba", _) => Self::Rgb(Rgb::new(
+                (c1.number(255.0)? / 255.0).clamp(0.0, 1.0),
+                (c2.number(255.0)? / 255.0).clamp(0.0, 1.0),
+                (c3.number(255.0)? / 255.0).clamp(0.0, 1.0),
+                alpha,
+            )),
+            ("hsl" | "hsla", _) => Self::Hsl(Hsl::new(
+                RgbHue::from_degrees(c1.hue()?),
+                (c2.number(100.0)? / 100.0).clamp(0.0, 1.0),
+                (c3.number(100.0)? / 100.0).clamp(0.0, 1.0),
+                alpha,
+            )),
+            ("hwb", _) => Self::Hwb(Hwb::new(
+                RgbHue::from_degrees(c1.hue()?),
+                (c2.number(100.0)? / 100.0).clamp(0.0, 1.0),
+                (c3.number(100.0)? / 100.0).clamp(0.0, 1.0),
+                alpha,
+            )),
+            ("lab", _) => Self::Lab(Lab::new(
+                c1.number(100.0)?.clamp(0.0, 100.0),
+                c2.number(125.0)?,
+                c3.number(125.0)?,
+                alpha,
+            )),
+            ("lch", _) => Self::Lch(Lch::new(
+                c1.number(100.0)?.clamp(0.0, 100.0),
+                c2.number(150.0)?.max(0.0),
+                LabHue::from_degrees(c3.hue()?),
+                alpha,
+            )),
+            ("oklab", _) => Self::Oklab(Oklab::new(
+                c1.number(1.0)?.clamp(0.0, 1.0),
+                c2.number(0.4)?,
+                c3.number(0.4)?,
+                alpha,
+            )),
+            ("oklch", _) => Self::Oklch(Oklch::new(
+                c1.number(1.0)?.clamp(0.0, 1.0),
+                c2.number(0.4)?.max(0.0),
+                OklabHue::from_degrees(c3.hue()?),
+                alpha,
+            )),
+            ("color", Some("srgb")) => Self::Rgb(Rgb::new(
+                c1.number(1.0)?.clamp(0.0, 1.0),
+                c2.number(1.0)?.clamp(0.0, 1.0),
+                c3.number(1.0)?.clamp(0.0, 1.0),
+                alpha,
+            )),
+            ("color", Some("srgb-linear")) => Self::LinearRgb(LinearRgb::new(
+                c1.number(1.0)?.clamp(0.0, 1.0),
+                c2.number(1.0)?.clamp(0.0, 1.0),
+                c3.number(1.0)?.clamp(0.0, 1.0),
+                alpha,
+            )),
+            ("color", Some("display-p3")) => Self::P3(P3::new(
+                c1.number(1.0)?.clamp(0.0, 1.0),
+                c2.number(1.0)?.clamp(0.0, 1.0),
+                c3.number(1.0)?.clamp(0.0, 1.0),
+                alpha,
+            )),
+            ("color", Some(space @ ("xyz" | "xyz-d50" | "xyz-d65"))) => {
+                let white =
+                    if space == "xyz-d50" { WhitePoint::D50 } else { WhitePoint::D65 };
+                Self::Xyz(Xyz::new(
+                    c1.number(1.0)?,
+                    c2.number(1.0)?,
+                    c3.number(1.0)?,
+                    alpha,
+                    white,
+                ))
+            }
+            ("color", Some(space)) => bail!("unsupported color space `{space}`"),
+            (name, _) => bail!("unknown color function `{name}`"),
+        })
+    }
+
     /// Returns the alpha channel of the color, if it has one.
     pub fn alpha(&self) -> Option<f32> {
         match self {
@@ -1222,11 +3109,65 @@ impl Color {
             Color::Luma(c) => Some(c.alpha),
             Color::Oklab(c) => Some(c.alpha),
             Color::Oklch(c) => Some(c.alpha),
+            Color::Lab(c) => Some(c.alpha),
+            Color::Lch(c) => Some(c.alpha),
+            Color::Xyz(c) => Some(c.alpha),
             Color::Rgb(c) => Some(c.alpha),
+            Color::P3(c) => Some(c.alpha),
             Color::LinearRgb(c) => Some(c.alpha),
             Color::Hsl(c) => Some(c.alpha),
             Color::Hsv(c) => Some(c.alpha),
+            Color::Hwb(c) => Some(c.alpha),
+            Color::Okhsl(c) => Some(c.alpha),
+            Color::Okhsv(c) => Some(c.alpha),
+            Color::Icc(c) => c.alpha(),
+        }
+    }
+
+    /// Snaps each component to one of `levels` evenly spaced values.
+    fn posterize(self, levels: usize) -> Color {
+        let steps = (levels - 1) as f32;
+        let q = |v: f32| (v * steps).round() / steps;
+        match self {
+            Self::Luma(c) => Self::Luma(Luma::new(q(c.luma), c.alpha)),
+            Self::Rgb(c) => Self::Rgb(Rgb::new(q(c.red), q(c.green), q(c.blue), c.alpha)),
+            Self::LinearRgb(c) => {
+                Self::LinearRgb(LinearRgb::new(q(c.red), q(c.green), q(c.blue), c.alpha))
+            }
+            Self::Cmyk(c) => Self::Cmyk(Cmyk::new(q(c.c), q(c.m), q(c.y), q(c.k))),
+            other => Self::Rgb(match other.to_rgb() {
+                Self::Rgb(c) => Rgb::new(q(c.red), q(c.green), q(c.blue), c.alpha),
+                _ => unreachable!(),
+            })
+            .to_space(other.space()),
+        }
+    }
+
+    /// Mixes this color toward another one by the given factor in the given
+    /// space, keeping this color's space and opacity.
+    fn mix_toward(
+        self,
+        other: Color,
+        factor: Ratio,
+        space: ColorSpace,
+    ) -> StrResult<Color> {
+        if !(0.0..=1.0).contains(&factor.get()) {
+            bail!("factor must be between 0% and 100%");
         }
+
+        let other = match self.alpha() {
+            Some(alpha) => other.with_alpha(alpha),
+            None => other,
+        };
+
+        let t = factor.get();
+        let mixed = Color::mix_iter(
+            [WeightedColor::new(self, 1.0 - t), WeightedColor::new(other, t)],
+            space,
+            HueInterpolation::Shorter,
+        )?;
+
+        Ok(mixed.to_space(self.space()))
     }
 
     /// Sets the alpha channel of the color, if it has one.
@@ -1236,10 +3177,18 @@ impl Color {
             Color::Luma(c) => c.alpha = alpha,
             Color::Oklab(c) => c.alpha = alpha,
             Color::Oklch(c) => c.alpha = alpha,
+            Color::Lab(c) => c.alpha = alpha,
+            Color::Lch(c) => c.alpha = alpha,
+            Color::Xyz(c) => c.alpha = alpha,
             Color::Rgb(c) => c.alpha = alpha,
+            Color::P3(c) => c.alpha = alpha,
             Color::LinearRgb(c) => c.alpha = alpha,
             Color::Hsl(c) => c.alpha = alpha,
             Color::Hsv(c) => c.alpha = alpha,
+            Color::Hwb(c) => c.alpha = alpha,
+            Color::Okhsl(c) => c.alpha = alpha,
+            Color::Okhsv(c) => c.alpha = alpha,
+            Color::Icc(c) => c.alpha = alpha,
         }
 
         self
@@ -1262,11 +3211,31 @@ impl Color {
             Color::Luma(c) => Color::Luma(transform(c, scale)),
             Color::Oklab(c) => Color::Oklab(transform(c, scale)),
             Color::Oklch(c) => Color::Oklch(transform(c, scale)),
+            Color::Lab(c) => Color::Lab(transform(c, scale)),
+            Color::Lch(c) => Color::Lch(transform(c, scale)),
+            Color::Xyz(c) => Color::Xyz(Xyz {
+                alpha: transform(Alpha { color: (), alpha: c.alpha }, scale).alpha,
+                ..c
+            }),
             Color::Rgb(c) => Color::Rgb(transform(c, scale)),
+            Color::P3(c) => Color::P3(P3 {
+                alpha: transform(Alpha { color: (), alpha: c.alpha }, scale).alpha,
+                ..c
+            }),
             Color::LinearRgb(c) => Color::LinearRgb(transform(c, scale)),
             Color::Cmyk(_) => bail!("CMYK does not have an alpha component"),
             Color::Hsl(c) => Color::Hsl(transform(c, scale)),
             Color::Hsv(c) => Color::Hsv(transform(c, scale)),
+            Color::Hwb(c) => Color::Hwb(transform(c, scale)),
+            Color::Okhsl(c) => Color::Okhsl(transform(c, scale)),
+            Color::Okhsv(c) => Color::Okhsv(transform(c, scale)),
+            Color::Icc(c) if c.profile.space() == IccSpace::Cmyk => {
+                bail!("CMYK does not have an alpha component")
+            }
+            Color::Icc(c) => Color::Icc(Icc {
+                alpha: transform(Alpha { color: (), alpha: c.alpha }, scale).alpha,
+                ..c
+            }),
         })
     }
 
@@ -1278,18 +3247,79 @@ impl Color {
             Color::Oklch(c) => {
                 [c.l, c.chroma, c.hue.into_degrees().rem_euclid(360.0), c.alpha]
             }
-            Color::Rgb(c) => [c.red, c.green, c.blue, c.alpha],
-            Color::LinearRgb(c) => [c.red, c.green, c.blue, c.alpha],
-            Color::Cmyk(c) => [c.c, c.m, c.y, c.k],
-            Color::Hsl(c) => [
+            Color::Lab(c) => [c.l, c.a, c.b, c.alpha],
+            Color::Lch(c) => {
+                [c.l, c.chroma, c.hue.into_degrees().rem_euclid(360.0), c.alpha]
+            }
+            Color::Xyz(c) => [c.x, c.y, c.z, c.alpha],
+            Color::Rgb(c) => [c.red, c.green, c.blue, c.alpha],
+            Color::P3(c) => [c.red, c.green, c.blue, c.alpha],
+            Color::LinearRgb(c) => [c.red, c.green, c.blue, c.alpha],
+            Color::Cmyk(c) => [c.c, c.m, c.y, c.k],
+            Color::Hsl(c) => [
+                c.hue.into_degrees().rem_euclid(360.0),
+                c.saturation,
+                c.lightness,
+                c.alpha,
+            ],
+            Color::Hsv(c) => {
+                [c.hue.into_degrees().rem_euclid(360.0), c.saturation, c.value, c.alpha]
+            }
+            Color::Hwb(c) => [
+                c.hue.into_degrees().rem_euclid(360.0),
+                c.whiteness,
+                c.blackness,
+                c.alpha,
+            ],
+            Color::Okhsl(c) => [
                 c.hue.into_degrees().rem_euclid(360.0),
                 c.saturation,
                 c.lightness,
                 c.alpha,
             ],
-            Color::Hsv(c) => {
+            Color::Okhsv(c) => {
                 [c.hue.into_degrees().rem_euclid(360.0), c.saturation, c.value, c.alpha]
             }
+            Color::Icc(c) => c.to_vec4(),
+        }
+    }
+
+    /// The relative luminance of the color as defined by WCAG 2.
+    fn wcag_luminance(self) -> f64 {
+        let [r, g, b, _] = self.to_linear_rgb().to_vec4().map(f64::from);
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// The CIE Lab components of the color, with lightness ranging from 0 to
+    /// 100.
+    fn lab_components(self) -> [f64; 3] {
+        let [l, a, b, _] = self.to_lab().to_vec4().map(f64::from);
+        [l, a, b]
+    }
+
+    /// The screen luminance of the color as estimated by APCA, which uses a
+    /// simple power curve instead of the piecewise sRGB transfer function.
+    fn apca_luminance(self) -> f64 {
+        let [r, g, b, _] = self.to_rgb().to_vec4().map(|v| f64::from(v).powf(2.4));
+        0.2126729 * r + 0.7151522 * g + 0.0721750 * b
+    }
+
+    /// Converts the color to linear sRGB without clamping colors from
+    /// wide-gamut spaces to the sRGB gamut.
+    fn to_unbounded_linear_rgb(self) -> LinearRgb {
+        match self {
+            Self::Oklab(c) => LinearRgb::from_color_unclamped(c),
+            Self::Oklch(c) => LinearRgb::from_color_unclamped(c),
+            Self::Lab(c) => lab_to_linear_rgb(c),
+            Self::Lch(c) => lab_to_linear_rgb(Lab::from_color(c)),
+            Self::Xyz(c) => c.to_linear_rgb(),
+            Self::P3(c) => c.to_linear_rgb(),
+            _ => {
+                let Self::LinearRgb(c) = self.to_linear_rgb() else {
+                    unreachable!();
+                };
+                c
+            }
         }
     }
 
@@ -1298,14 +3328,104 @@ impl Color {
         self.to_vec4().map(|x| (x * 255.0).round() as u8)
     }
 
+    /// Converts the color to sRGB with premultiplied alpha, as a vec of four
+    /// floats.
+    ///
+    /// Missing components, which are represented as `NaN`, are treated as
+    /// zero, like in CSS.
+    pub fn to_premultiplied(&self) -> [f32; 4] {
+        Self::premultiply_vec4(self.to_rgb().to_vec4())
+    }
+
+    /// Converts the color to sRGB with premultiplied alpha, as a vec of four
+    /// [`u8`]s.
+    pub fn to_premultiplied_u8(&self) -> [u8; 4] {
+        self.to_premultiplied().map(|x| (x * 255.0).round() as u8)
+    }
+
+    /// Creates an sRGB color from a vec of four floats with premultiplied
+    /// alpha.
+    ///
+    /// Missing components are treated as zero and a zero alpha results in
+    /// transparent black.
+    pub fn from_premultiplied(vec: [f32; 4]) -> Self {
+        let [r, g, b, a] = Self::unpremultiply_vec4(vec);
+        Self::Rgb(Rgb::new(r, g, b, a))
+    }
+
+    /// Creates an sRGB color from a vec of four [`u8`]s with premultiplied
+    /// alpha.
+    pub fn from_premultiplied_u8(vec: [u8; 4]) -> Self {
+        Self::from_premultiplied(vec.map(|x| x as f32 / 255.0))
+    }
+
+    /// Multiplies the first three components of a vec with its fourth, the
+    /// alpha.
+    ///
+    /// Missing components are treated as zero and all components are clamped
+    /// between zero and one.
+    pub fn premultiply_vec4(vec: [f32; 4]) -> [f32; 4] {
+        let [r, g, b, a] = vec.map(clamp_component);
+        [r * a, g * a, b * a, a]
+    }
+
+    /// Divides the first three components of a vec with premultiplied alpha by
+    /// its fourth, the alpha.
+    ///
+    /// Missing components are treated as zero and all components are clamped
+    /// between zero and one. A zero alpha results in transparent black.
+    pub fn unpremultiply_vec4(vec: [f32; 4]) -> [f32; 4] {
+        let [r, g, b, a] = vec.map(clamp_component);
+        if a == 0.0 {
+            return [0.0; 4];
+        }
+        [r / a, g / a, b / a, a].map(|x| x.min(1.0))
+    }
+
+    /// Converts a slice of colors, given as vecs of their components, from one
+    /// color space into another in place.
+    ///
+    /// This is equivalent to converting each color with
+    /// [`to_space`](Self::to_space), but the conversion is only selected once
+    /// for the whole slice. Conversions between sRGB, linear RGB, and Oklab
+    /// work directly on the components, which makes converting large numbers
+    /// of colors, like the samples of a gradient, much faster.
+    pub fn convert_slice(
+        colors: &mut [impl ColorRepr],
+        from: ColorSpace,
+        to: ColorSpace,
+    ) {
+        if from == to {
+            return;
+        }
+
+        if let Some(convert) = fast_conversion(from, to) {
+            for color in colors {
+                color.set_vec4(convert(color.vec4()));
+            }
+        } else {
+            for color in colors {
+                let converted = Self::from_space_vec4(from, color.vec4()).to_space(to);
+                color.set_vec4(converted.to_vec4());
+            }
+        }
+    }
+
     pub fn to_space(self, space: ColorSpace) -> Self {
         match space {
             ColorSpace::Oklab => self.to_oklab(),
             ColorSpace::Oklch => self.to_oklch(),
+            ColorSpace::Lab => self.to_lab(),
+            ColorSpace::Lch => self.to_lch(),
+            ColorSpace::Xyz(white) => self.to_xyz_with(white),
             ColorSpace::Srgb => self.to_rgb(),
+            ColorSpace::DisplayP3 => self.to_p3(),
             ColorSpace::LinearRgb => self.to_linear_rgb(),
             ColorSpace::Hsl => self.to_hsl(),
             ColorSpace::Hsv => self.to_hsv(),
+            ColorSpace::Hwb => self.to_hwb(),
+            ColorSpace::Okhsl => self.to_okhsl(),
+            ColorSpace::Okhsv => self.to_okhsv(),
             ColorSpace::Cmyk => self.to_cmyk(),
             ColorSpace::D65Gray => self.to_luma(),
         }
@@ -1316,11 +3436,19 @@ impl Color {
             Self::Luma(c) => c,
             Self::Oklab(c) => Luma::from_color(c),
             Self::Oklch(c) => Luma::from_color(c),
+            Self::Lab(c) => Luma::from_color(lab_to_linear_rgb(c)),
+            Self::Lch(c) => Luma::from_color(lab_to_linear_rgb(Lab::from_color(c))),
+            Self::Xyz(c) => Luma::from_color(c.to_linear_rgb()),
+            Self::P3(c) => Luma::from_color(c.to_gamut_mapped_rgb()),
             Self::Rgb(c) => Luma::from_color(c),
             Self::LinearRgb(c) => Luma::from_color(c),
             Self::Cmyk(c) => Luma::from_color(c.to_rgba()),
+            Self::Icc(c) => Luma::from_color(c.to_rgba()),
             Self::Hsl(c) => Luma::from_color(c),
             Self::Hsv(c) => Luma::from_color(c),
+            Self::Hwb(c) => Luma::from_color(normalize_hwb(c)),
+            Self::Okhsl(c) => Luma::from_color(Oklab::from_color_unclamped(c)),
+            Self::Okhsv(c) => Luma::from_color(Oklab::from_color_unclamped(c)),
         })
     }
 
@@ -1329,11 +3457,19 @@ impl Color {
             Self::Luma(c) => Oklab::from_color(c),
             Self::Oklab(c) => c,
             Self::Oklch(c) => Oklab::from_color(c),
+            Self::Lab(c) => Oklab::from_color(lab_to_linear_rgb(c)),
+            Self::Lch(c) => Oklab::from_color(lab_to_linear_rgb(Lab::from_color(c))),
+            Self::Xyz(c) => Oklab::from_color(c.to_linear_rgb()),
+            Self::P3(c) => Oklab::from_color_unclamped(c.to_linear_rgb()),
             Self::Rgb(c) => Oklab::from_color(c),
             Self::LinearRgb(c) => Oklab::from_color(c),
             Self::Cmyk(c) => Oklab::from_color(c.to_rgba()),
+            Self::Icc(c) => Oklab::from_color(c.to_rgba()),
             Self::Hsl(c) => Oklab::from_color(c),
             Self::Hsv(c) => Oklab::from_color(c),
+            Self::Hwb(c) => Oklab::from_color(normalize_hwb(c)),
+            Self::Okhsl(c) => Oklab::from_color_unclamped(c),
+            Self::Okhsv(c) => Oklab::from_color_unclamped(c),
         })
     }
 
@@ -1342,11 +3478,72 @@ impl Color {
             Self::Luma(c) => Oklch::from_color(c),
             Self::Oklab(c) => Oklch::from_color(c),
             Self::Oklch(c) => c,
+            Self::Lab(c) => Oklch::from_color(lab_to_linear_rgb(c)),
+            Self::Lch(c) => Oklch::from_color(lab_to_linear_rgb(Lab::from_color(c))),
+            Self::Xyz(c) => Oklch::from_color(c.to_linear_rgb()),
+            Self::P3(c) => Oklch::from_color_unclamped(c.to_linear_rgb()),
             Self::Rgb(c) => Oklch::from_color(c),
             Self::LinearRgb(c) => Oklch::from_color(c),
             Self::Cmyk(c) => Oklch::from_color(c.to_rgba()),
+            Self::Icc(c) => Oklch::from_color(c.to_rgba()),
             Self::Hsl(c) => Oklch::from_color(c),
             Self::Hsv(c) => Oklch::from_color(c),
+            Self::Hwb(c) => Oklch::from_color(normalize_hwb(c)),
+            Self::Okhsl(c) => Oklch::from_color(Oklab::from_color_unclamped(c)),
+            Self::Okhsv(c) => Oklch::from_color(Oklab::from_color_unclamped(c)),
+        })
+    }
+
+    pub fn to_lab(self) -> Self {
+        Self::Lab(match self {
+            Self::Lab(c) => c,
+            Self::Lch(c) => Lab::from_color(c),
+            Self::Xyz(c) => c.to_lab(),
+            Self::P3(c) => linear_rgb_to_lab(c.to_linear_rgb()),
+            _ => {
+                let Self::LinearRgb(c) = self.to_linear_rgb() else {
+                    unreachable!();
+                };
+                linear_rgb_to_lab(c)
+            }
+        })
+    }
+
+    pub fn to_lch(self) -> Self {
+        Self::Lch(match self {
+            Self::Lch(c) => c,
+            _ => {
+                let Self::Lab(c) = self.to_lab() else {
+                    unreachable!();
+                };
+                Lch::from_color(c)
+            }
+        })
+    }
+
+    /// Converts the color to CIE XYZ. Colors that already are in CIE XYZ keep
+    /// their white point, all others use D65.
+    pub fn to_xyz(self) -> Self {
+        match self {
+            Self::Xyz(_) => self,
+            _ => self.to_xyz_with(WhitePoint::D65),
+        }
+    }
+
+    /// Converts the color to CIE XYZ relative to the given white point.
+    pub fn to_xyz_with(self, white: WhitePoint) -> Self {
+        Self::Xyz(match self {
+            Self::Xyz(c) if c.white == white => c,
+            Self::Xyz(c) => Xyz::from_linear_rgb(c.to_linear_rgb(), white),
+            Self::Lab(c) => Xyz::from_lab(c, white),
+            Self::Lch(c) => Xyz::from_lab(Lab::from_color(c), white),
+            Self::P3(c) => Xyz::from_linear_rgb(c.to_linear_rgb(), white),
+            _ => {
+                let Self::LinearRgb(c) = self.to_linear_rgb() else {
+                    unreachable!();
+                };
+                Xyz::from_linear_rgb(c, white)
+            }
         })
     }
 
@@ -1355,11 +3552,36 @@ impl Color {
             Self::Luma(c) => Rgb::from_color(c),
             Self::Oklab(c) => Rgb::from_color(c),
             Self::Oklch(c) => Rgb::from_color(c),
+            Self::Lab(c) => Rgb::from_color(lab_to_linear_rgb(c)),
+            Self::Lch(c) => Rgb::from_color(lab_to_linear_rgb(Lab::from_color(c))),
+            Self::Xyz(c) => Rgb::from_color(c.to_linear_rgb()),
+            Self::P3(c) => c.to_gamut_mapped_rgb(),
             Self::Rgb(c) => c,
             Self::LinearRgb(c) => Rgb::from_linear(c),
             Self::Cmyk(c) => Rgb::from_color(c.to_rgba()),
+            Self::Icc(c) => Rgb::from_color(c.to_rgba()),
             Self::Hsl(c) => Rgb::from_color(c),
             Self::Hsv(c) => Rgb::from_color(c),
+            Self::Hwb(c) => Rgb::from_color(normalize_hwb(c)),
+            Self::Okhsl(c) => Rgb::from_color(Oklab::from_color_unclamped(c)),
+            Self::Okhsv(c) => Rgb::from_color(Oklab::from_color_unclamped(c)),
+        })
+    }
+
+    pub fn to_p3(self) -> Self {
+        Self::P3(match self {
+            Self::P3(c) => c,
+            Self::Oklab(c) => P3::from_linear_rgb(LinearRgb::from_color_unclamped(c)),
+            Self::Oklch(c) => P3::from_linear_rgb(LinearRgb::from_color_unclamped(c)),
+            Self::Lab(c) => P3::from_linear_rgb(lab_to_linear_rgb(c)),
+            Self::Lch(c) => P3::from_linear_rgb(lab_to_linear_rgb(Lab::from_color(c))),
+            Self::Xyz(c) => P3::from_linear_rgb(c.to_linear_rgb()),
+            _ => {
+                let Self::LinearRgb(c) = self.to_linear_rgb() else {
+                    unreachable!();
+                };
+                P3::from_linear_rgb(c)
+            }
         })
     }
 
@@ -1368,11 +3590,19 @@ impl Color {
             Self::Luma(c) => LinearRgb::from_color(c),
             Self::Oklab(c) => LinearRgb::from_color(c),
             Self::Oklch(c) => LinearRgb::from_color(c),
+            Self::Lab(c) => LinearRgb::from_color(lab_to_linear_rgb(c)),
+            Self::Lch(c) => LinearRgb::from_color(lab_to_linear_rgb(Lab::from_color(c))),
+            Self::Xyz(c) => LinearRgb::from_color(c.to_linear_rgb()),
+            Self::P3(c) => c.to_gamut_mapped_rgb().into_linear(),
             Self::Rgb(c) => LinearRgb::from_color(c),
             Self::LinearRgb(c) => c,
             Self::Cmyk(c) => LinearRgb::from_color(c.to_rgba()),
+            Self::Icc(c) => LinearRgb::from_color(c.to_rgba()),
             Self::Hsl(c) => Rgb::from_color(c).into_linear(),
             Self::Hsv(c) => Rgb::from_color(c).into_linear(),
+            Self::Hwb(c) => Rgb::from_color(normalize_hwb(c)).into_linear(),
+            Self::Okhsl(c) => LinearRgb::from_color(Oklab::from_color_unclamped(c)),
+            Self::Okhsv(c) => LinearRgb::from_color(Oklab::from_color_unclamped(c)),
         })
     }
 
@@ -1381,11 +3611,25 @@ impl Color {
             Self::Luma(c) => Cmyk::from_luma(c),
             Self::Oklab(c) => Cmyk::from_rgba(Rgb::from_color(c)),
             Self::Oklch(c) => Cmyk::from_rgba(Rgb::from_color(c)),
+            Self::Lab(c) => Cmyk::from_rgba(Rgb::from_color(lab_to_linear_rgb(c))),
+            Self::Lch(c) => {
+                Cmyk::from_rgba(Rgb::from_color(lab_to_linear_rgb(Lab::from_color(c))))
+            }
+            Self::Xyz(c) => Cmyk::from_rgba(Rgb::from_color(c.to_linear_rgb())),
+            Self::P3(c) => Cmyk::from_rgba(c.to_gamut_mapped_rgb()),
             Self::Rgb(c) => Cmyk::from_rgba(c),
             Self::LinearRgb(c) => Cmyk::from_rgba(Rgb::from_linear(c)),
             Self::Cmyk(c) => c,
+            Self::Icc(c) => Cmyk::from_rgba(c.to_rgba()),
             Self::Hsl(c) => Cmyk::from_rgba(Rgb::from_color(c)),
             Self::Hsv(c) => Cmyk::from_rgba(Rgb::from_color(c)),
+            Self::Hwb(c) => Cmyk::from_rgba(Rgb::from_color(normalize_hwb(c))),
+            Self::Okhsl(c) => {
+                Cmyk::from_rgba(Rgb::from_color(Oklab::from_color_unclamped(c)))
+            }
+            Self::Okhsv(c) => {
+                Cmyk::from_rgba(Rgb::from_color(Oklab::from_color_unclamped(c)))
+            }
         })
     }
 
@@ -1394,11 +3638,21 @@ impl Color {
             Self::Luma(c) => Hsl::from_color(c),
             Self::Oklab(c) => Hsl::from_color(c),
             Self::Oklch(c) => Hsl::from_color(c),
+            Self::Lab(c) => Hsl::from_color(Rgb::from_linear(lab_to_linear_rgb(c))),
+            Self::Lch(c) => {
+                Hsl::from_color(Rgb::from_linear(lab_to_linear_rgb(Lab::from_color(c))))
+            }
+            Self::Xyz(c) => Hsl::from_color(Rgb::from_linear(c.to_linear_rgb())),
+            Self::P3(c) => Hsl::from_color(c.to_gamut_mapped_rgb()),
             Self::Rgb(c) => Hsl::from_color(c),
             Self::LinearRgb(c) => Hsl::from_color(Rgb::from_linear(c)),
             Self::Cmyk(c) => Hsl::from_color(c.to_rgba()),
+            Self::Icc(c) => Hsl::from_color(c.to_rgba()),
             Self::Hsl(c) => c,
             Self::Hsv(c) => Hsl::from_color(c),
+            Self::Hwb(c) => Hsl::from_color(normalize_hwb(c)),
+            Self::Okhsl(c) => Hsl::from_color(Oklab::from_color_unclamped(c)),
+            Self::Okhsv(c) => Hsl::from_color(Oklab::from_color_unclamped(c)),
         })
     }
 
@@ -1407,11 +3661,57 @@ impl Color {
             Self::Luma(c) => Hsv::from_color(c),
             Self::Oklab(c) => Hsv::from_color(c),
             Self::Oklch(c) => Hsv::from_color(c),
+            Self::Lab(c) => Hsv::from_color(Rgb::from_linear(lab_to_linear_rgb(c))),
+            Self::Lch(c) => {
+                Hsv::from_color(Rgb::from_linear(lab_to_linear_rgb(Lab::from_color(c))))
+            }
+            Self::Xyz(c) => Hsv::from_color(Rgb::from_linear(c.to_linear_rgb())),
+            Self::P3(c) => Hsv::from_color(c.to_gamut_mapped_rgb()),
             Self::Rgb(c) => Hsv::from_color(c),
             Self::LinearRgb(c) => Hsv::from_color(Rgb::from_linear(c)),
             Self::Cmyk(c) => Hsv::from_color(c.to_rgba()),
+            Self::Icc(c) => Hsv::from_color(c.to_rgba()),
             Self::Hsl(c) => Hsv::from_color(c),
             Self::Hsv(c) => c,
+            Self::Hwb(c) => Hsv::from_color(normalize_hwb(c)),
+            Self::Okhsl(c) => Hsv::from_color(Oklab::from_color_unclamped(c)),
+            Self::Okhsv(c) => Hsv::from_color(Oklab::from_color_unclamped(c)),
+        })
+    }
+
+    pub fn to_hwb(self) -> Self {
+        Self::Hwb(match self {
+            Self::Hwb(c) => c,
+            _ => {
+                let Self::Hsv(c) = self.to_hsv() else {
+                    unreachable!();
+                };
+                Hwb::from_color(c)
+            }
+        })
+    }
+
+    pub fn to_okhsl(self) -> Self {
+        Self::Okhsl(match self {
+            Self::Okhsl(c) => c,
+            _ => {
+                let Self::Oklab(c) = self.to_oklab() else {
+                    unreachable!();
+                };
+                Okhsl::from_color_unclamped(c)
+            }
+        })
+    }
+
+    pub fn to_okhsv(self) -> Self {
+        Self::Okhsv(match self {
+            Self::Okhsv(c) => c,
+            _ => {
+                let Self::Oklab(c) = self.to_oklab() else {
+                    unreachable!();
+                };
+                Okhsv::from_color_unclamped(c)
+            }
         })
     }
 }
@@ -1431,12 +3731,29 @@ impl Debug for Color {
                     v.alpha
                 )
             }
+            Self::Lab(v) => write!(f, "Lab({}, {}, {}, {})", v.l, v.a, v.b, v.alpha),
+            Self::Lch(v) => {
+                write!(
+                    f,
+                    "Lch({}, {}, {:?}, {})",
+                    v.l,
+                    v.chroma,
+                    hue_angle(v.hue.into_degrees()),
+                    v.alpha
+                )
+            }
+            Self::Xyz(v) => {
+                write!(f, "Xyz({}, {}, {}, {}, {:?})", v.x, v.y, v.z, v.alpha, v.white)
+            }
             Self::Rgb(v) => {
                 write!(f, "Rgb({}, {}, {}, {})", v.red, v.green, v.blue, v.alpha)
             }
             Self::LinearRgb(v) => {
                 write!(f, "LinearRgb({}, {}, {}, {})", v.red, v.green, v.blue, v.alpha)
             }
+            Self::P3(v) => {
+                write!(f, "P3({}, {}, {}, {})", v.red, v.green, v.blue, v.alpha)
+            }
             Self::Cmyk(v) => write!(f, "Cmyk({}, {}, {}, {})", v.c, v.m, v.y, v.k),
             Self::Hsl(v) => write!(
                 f,
@@ -1454,6 +3771,33 @@ impl Debug for Color {
                 v.value,
                 v.alpha
             ),
+            Self::Hwb(v) => write!(
+                f,
+                "Hwb({:?}, {}, {}, {})",
+                hue_angle(v.hue.into_degrees()),
+                v.whiteness,
+                v.blackness,
+                v.alpha
+            ),
+            Self::Okhsl(v) => write!(
+                f,
+                "Okhsl({:?}, {}, {}, {})",
+                hue_angle(v.hue.into_degrees()),
+                v.saturation,
+                v.lightness,
+                v.alpha
+            ),
+            Self::Okhsv(v) => write!(
+                f,
+                "Okhsv({:?}, {}, {}, {})",
+                hue_angle(v.hue.into_degrees()),
+                v.saturation,
+                v.value,
+                v.alpha
+            ),
+            Self::Icc(v) => {
+                write!(f, "Icc({:?}, {:?}, {})", v.profile, v.channels(), v.alpha)
+            }
         }
     }
 }
@@ -1491,13 +3835,32 @@ impl Repr for Color {
                     )
                 }
             }
+            Self::P3(c) => {
+                if c.alpha == 1.0 {
+                    eco_format!(
+                        "color.p3({}, {}, {})",
+                        Ratio::new(c.red.into()).repr(),
+                        Ratio::new(c.green.into()).repr(),
+                        Ratio::new(c.blue.into()).repr(),
+                    )
+                } else {
+                    eco_format!(
+                        "color.p3({}, {}, {}, {})",
+                        Ratio::new(c.red.into()).repr(),
+                        Ratio::new(c.green.into()).repr(),
+                        Ratio::new(c.blue.into()).repr(),
+                        Ratio::new(c.alpha.into()).repr(),
+                    )
+                }
+            }
             Self::Cmyk(c) => {
                 eco_format!(
-                    "cmyk({}, {}, {}, {})",
+                    "cmyk({}, {}, {}, {}{})",
                     Ratio::new(c.c.into()).repr(),
                     Ratio::new(c.m.into()).repr(),
                     Ratio::new(c.y.into()).repr(),
                     Ratio::new(c.k.into()).repr(),
+                    if c.overprint { ", overprint: true" } else { "" },
                 )
             }
             Self::Oklab(c) => {
@@ -1536,6 +3899,59 @@ impl Repr for Color {
                     )
                 }
             }
+            Self::Lab(c) => {
+                if c.alpha == 1.0 {
+                    eco_format!(
+                        "color.lab({}, {}, {})",
+                        Ratio::new(f64::from(c.l) / 100.0).repr(),
+                        repr::format_float_component(c.a.into()),
+                        repr::format_float_component(c.b.into()),
+                    )
+                } else {
+                    eco_format!(
+                        "color.lab({}, {}, {}, {})",
+                        Ratio::new(f64::from(c.l) / 100.0).repr(),
+                        repr::format_float_component(c.a.into()),
+                        repr::format_float_component(c.b.into()),
+                        Ratio::new(c.alpha.into()).repr(),
+                    )
+                }
+            }
+            Self::Lch(c) => {
+                if c.alpha == 1.0 {
+                    eco_format!(
+                        "color.lch({}, {}, {})",
+                        Ratio::new(f64::from(c.l) / 100.0).repr(),
+                        repr::format_float_component(c.chroma.into()),
+                        hue_angle(c.hue.into_degrees()).repr(),
+                    )
+                } else {
+                    eco_format!(
+                        "color.lch({}, {}, {}, {})",
+                        Ratio::new(f64::from(c.l) / 100.0).repr(),
+                        repr::format_float_component(c.chroma.into()),
+                        hue_angle(c.hue.into_degrees()).repr(),
+                        Ratio::new(c.alpha.into()).repr(),
+                    )
+                }
+            }
+            Self::Xyz(c) => {
+                let mut args = vec![
+                    repr::format_float_component(c.x.into()),
+                    repr::format_float_component(c.y.into()),
+                    repr::format_float_component(c.z.into()),
+                ];
+                if c.alpha != 1.0 {
+                    args.push(Ratio::new(c.alpha.into()).repr());
+                }
+                if c.white != WhitePoint::D65 {
+                    args.push(eco_format!(
+                        "white-point: {}",
+                        c.white.into_value().repr()
+                    ));
+                }
+                eco_format!("color.xyz({})", args.join(", "))
+            }
             Self::Hsl(c) => {
                 if c.alpha == 1.0 {
                     eco_format!(
@@ -1545,35 +3961,288 @@ impl Repr for Color {
                         Ratio::new(c.lightness.into()).repr(),
                     )
                 } else {
-                    eco_format!(
-                        "color.hsl({}, {}, {}, {})",
-                        hue_angle(c.hue.into_degrees()).repr(),
-                        Ratio::new(c.saturation.into()).repr(),
-                        Ratio::new(c.lightness.into()).repr(),
-                        Ratio::new(c.alpha.into()).repr(),
-                    )
+                    eco_format!(
+                        "color.hsl({}, {}, {}, {})",
+                        hue_angle(c.hue.into_degrees()).repr(),
+                        Ratio::new(c.saturation.into()).repr(),
+                        Ratio::new(c.lightness.into()).repr(),
+                        Ratio::new(c.alpha.into()).repr(),
+                    )
+                }
+            }
+            Self::Hsv(c) => {
+                if c.alpha == 1.0 {
+                    eco_format!(
+                        "color.hsv({}, {}, {})",
+                        hue_angle(c.hue.into_degrees()).repr(),
+                        Ratio::new(c.saturation.into()).repr(),
+                        Ratio::new(c.value.into()).repr(),
+                    )
+                } else {
+                    eco_format!(
+                        "color.hsv({}, {}, {}, {})",
+                        hue_angle(c.hue.into_degrees()).repr(),
+                        Ratio::new(c.saturation.into()).repr(),
+                        Ratio::new(c.value.into()).repr(),
+                        Ratio::new(c.alpha.into()).repr(),
+                    )
+                }
+            }
+            Self::Hwb(c) => {
+                if c.alpha == 1.0 {
+                    eco_format!(
+                        "color.hwb({}, {}, {})",
+                        hue_angle(c.hue.into_degrees()).repr(),
+                        Ratio::new(c.whiteness.into()).repr(),
+                        Ratio::new(c.blackness.into()).repr(),
+                    )
+                } else {
+                    eco_format!(
+                        "color.hwb({}, {}, {}, {})",
+                        hue_angle(c.hue.into_degrees()).repr(),
+                        Ratio::new(c.whiteness.into()).repr(),
+                        Ratio::new(c.blackness.into()).repr(),
+                        Ratio::new(c.alpha.into()).repr(),
+                    )
+                }
+            }
+            Self::Okhsl(c) => {
+                if c.alpha == 1.0 {
+                    eco_format!(
+                        "color.okhsl({}, {}, {})",
+                        hue_angle(c.hue.into_degrees()).repr(),
+                        Ratio::new(c.saturation.into()).repr(),
+                        Ratio::new(c.lightness.into()).repr(),
+                    )
+                } else {
+                    eco_format!(
+                        "color.okhsl({}, {}, {}, {})",
+                        hue_angle(c.hue.into_degrees()).repr(),
+                        Ratio::new(c.saturation.into()).repr(),
+                        Ratio::new(c.lightness.into()).repr(),
+                        Ratio::new(c.alpha.into()).repr(),
+                    )
+                }
+            }
+            Self::Okhsv(c) => {
+                if c.alpha == 1.0 {
+                    eco_format!(
+                        "color.okhsv({}, {}, {})",
+                        hue_angle(c.hue.into_degrees()).repr(),
+                        Ratio::new(c.saturation.into()).repr(),
+                        Ratio::new(c.value.into()).repr(),
+                    )
+                } else {
+                    eco_format!(
+                        "color.okhsv({}, {}, {}, {})",
+                        hue_angle(c.hue.into_degrees()).repr(),
+                        Ratio::new(c.saturation.into()).repr(),
+                        Ratio::new(c.value.into()).repr(),
+                        Ratio::new(c.alpha.into()).repr(),
+                    )
+                }
+            }
+            Self::Icc(c) => {
+                let mut components: Vec<EcoString> =
+                    c.channels().iter().map(|&v| Ratio::new(v.into()).repr()).collect();
+                if c.alpha != 1.0 {
+                    components.push(Ratio::new(c.alpha.into()).repr());
+                }
+                eco_format!(
+                    "color.icc({}, {})",
+                    c.profile.data().repr(),
+                    repr::pretty_array_like(&components, components.len() == 1)
+                )
+            }
+        }
+    }
+}
+
+/// Converts a CIELAB color to linear sRGB, without clamping to the gamut.
+fn lab_to_linear_rgb(lab: Lab) -> LinearRgb {
+    let xyz = Xyza::<D50, f32>::from_color_unclamped(lab);
+    let [r, g, b] = mat_vec(&XYZ_D50_TO_LINEAR_RGB, [xyz.x, xyz.y, xyz.z]);
+    LinearRgb::new(r, g, b, xyz.alpha)
+}
+
+/// Converts a linear sRGB color to CIELAB.
+fn linear_rgb_to_lab(rgb: LinearRgb) -> Lab {
+    let [x, y, z] = mat_vec(&LINEAR_RGB_TO_XYZ_D50, [rgb.red, rgb.green, rgb.blue]);
+    Lab::from_color_unclamped(Xyza::<D50, f32>::new(x, y, z, rgb.alpha))
+}
+
+/// Applies the sRGB transfer function, which Display P3 shares, to a linear
+/// component. Negative values are mirrored.
+fn srgb_encode(v: f32) -> f32 {
+    let a = v.abs();
+    let encoded =
+        if a <= 0.0031308 { a * 12.92 } else { 1.055 * a.powf(1.0 / 2.4) - 0.055 };
+    encoded.copysign(v)
+}
+
+/// The inverse of [`srgb_encode`].
+fn srgb_decode(v: f32) -> f32 {
+    let a = v.abs();
+    let decoded = if a <= 0.04045 { a / 12.92 } else { ((a + 0.055) / 1.055).powf(2.4) };
+    decoded.copysign(v)
+}
+
+/// Maps a linear sRGB color, which may lie outside of the sRGB gamut, into the
+/// given gamut.
+fn map_into_gamut(rgb: LinearRgb, gamut: Gamut, mapping: GamutMapping) -> LinearRgb {
+    if mapping == GamutMapping::Clip || gamut.contains(rgb) {
+        return gamut.clip(rgb);
+    }
+
+    let oklch = Oklch::from_color_unclamped(rgb);
+    if oklch.l >= 1.0 {
+        return LinearRgb::new(1.0, 1.0, 1.0, rgb.alpha);
+    } else if oklch.l <= 0.0 {
+        return LinearRgb::new(0.0, 0.0, 0.0, rgb.alpha);
+    }
+
+    let with_chroma = |chroma| {
+        let mut candidate = oklch;
+        candidate.chroma = chroma;
+        LinearRgb::from_color_unclamped(candidate)
+    };
+
+    match mapping {
+        GamutMapping::Clip => unreachable!(),
+        GamutMapping::ChromaReduce => {
+            // Binary search for the largest chroma that is still in gamut.
+            let (mut lo, mut hi) = (0.0, oklch.chroma);
+            for _ in 0..24 {
+                let mid = (lo + hi) / 2.0;
+                if gamut.contains(with_chroma(mid)) {
+                    lo = mid;
+                } else {
+                    hi = mid;
                 }
             }
-            Self::Hsv(c) => {
-                if c.alpha == 1.0 {
-                    eco_format!(
-                        "color.hsv({}, {}, {})",
-                        hue_angle(c.hue.into_degrees()).repr(),
-                        Ratio::new(c.saturation.into()).repr(),
-                        Ratio::new(c.value.into()).repr(),
-                    )
+            gamut.clip(with_chroma(lo))
+        }
+        GamutMapping::Css => {
+            // The algorithm from CSS Color 4, section 13.2: Binary search for
+            // the chroma at which clipping the color changes it by just less
+            // than a noticeable difference.
+            const JND: f32 = 0.02;
+            const EPSILON: f32 = 0.0001;
+            let delta_e = |a: LinearRgb, b: LinearRgb| {
+                let a = Oklab::from_color_unclamped(a);
+                let b = Oklab::from_color_unclamped(b);
+                ((a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)).sqrt()
+            };
+
+            let mut clipped = gamut.clip(rgb);
+            if delta_e(clipped, rgb) < JND {
+                return clipped;
+            }
+
+            let (mut lo, mut hi) = (0.0, oklch.chroma);
+            let mut lo_in_gamut = true;
+            while hi - lo > EPSILON {
+                let chroma = (lo + hi) / 2.0;
+                let current = with_chroma(chroma);
+                if lo_in_gamut && gamut.contains(current) {
+                    lo = chroma;
+                    continue;
+                }
+
+                clipped = gamut.clip(current);
+                let e = delta_e(clipped, current);
+                if e < JND {
+                    if JND - e < EPSILON {
+                        break;
+                    }
+                    lo_in_gamut = false;
+                    lo = chroma;
                 } else {
-                    eco_format!(
-                        "color.hsv({}, {}, {}, {})",
-                        hue_angle(c.hue.into_degrees()).repr(),
-                        Ratio::new(c.saturation.into()).repr(),
-                        Ratio::new(c.value.into()).repr(),
-                        Ratio::new(c.alpha.into()).repr(),
-                    )
+                    hi = chroma;
                 }
             }
+            clipped
+        }
+    }
+}
+
+/// A bounded gamut that colors can be mapped into.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Gamut {
+    /// The gamut of sRGB and the spaces derived from it.
+    Srgb,
+    /// The gamut of Display P3.
+    DisplayP3,
+}
+
+impl Gamut {
+    /// The gamut of a color space, if it is bounded.
+    ///
+    /// Spaces derived from sRGB share its gamut. CMYK colors are converted
+    /// through sRGB, so they are limited to its gamut, too.
+    fn of(space: ColorSpace) -> Option<Self> {
+        match space {
+            ColorSpace::DisplayP3 => Some(Self::DisplayP3),
+            ColorSpace::Srgb
+            | ColorSpace::LinearRgb
+            | ColorSpace::D65Gray
+            | ColorSpace::Hsl
+            | ColorSpace::Hsv
+            | ColorSpace::Hwb
+            | ColorSpace::Okhsl
+            | ColorSpace::Okhsv
+            | ColorSpace::Cmyk => Some(Self::Srgb),
+            ColorSpace::Oklab
+            | ColorSpace::Oklch
+            | ColorSpace::Lab
+            | ColorSpace::Lch
+            | ColorSpace::Xyz(_) => None,
+        }
+    }
+
+    /// The linear components of a linear sRGB color in this gamut's space.
+    fn components(self, rgb: LinearRgb) -> [f32; 3] {
+        let v = [rgb.red, rgb.green, rgb.blue];
+        match self {
+            Self::Srgb => v,
+            Self::DisplayP3 => mat_vec(&LINEAR_RGB_TO_LINEAR_P3, v),
         }
     }
+
+    /// Whether the gamut contains the color, with some tolerance for rounding
+    /// errors.
+    fn contains(self, rgb: LinearRgb) -> bool {
+        const EPSILON: f32 = 1e-4;
+        self.components(rgb)
+            .iter()
+            .all(|v| (-EPSILON..=1.0 + EPSILON).contains(v))
+    }
+
+    /// Clamps each of the color's components to the gamut.
+    fn clip(self, rgb: LinearRgb) -> LinearRgb {
+        let [r, g, b] = self.components(rgb).map(|v| v.clamp(0.0, 1.0));
+        let [r, g, b] = match self {
+            Self::Srgb => [r, g, b],
+            Self::DisplayP3 => mat_vec(&LINEAR_P3_TO_LINEAR_RGB, [r, g, b]),
+        };
+        LinearRgb::new(r, g, b, rgb.alpha)
+    }
+}
+
+/// Multiplies a 3x3 matrix with a vector.
+fn mat_vec(m: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+/// Scales down whiteness and blackness if they add up to more than one, as
+/// required by CSS Color 4, so that the color becomes a gray.
+fn normalize_hwb(c: Hwb) -> Hwb {
+    let sum = c.whiteness + c.blackness;
+    if sum > 1.0 {
+        Hwb::new(c.hue, c.whiteness / sum, c.blackness / sum, c.alpha)
+    } else {
+        c
+    }
 }
 
 fn hue_angle(degrees: f32) -> Angle {
@@ -1591,10 +4260,18 @@ impl PartialEq for Color {
             }
             (Self::Oklab(a), Self::Oklab(b)) => a == b,
             (Self::Oklch(a), Self::Oklch(b)) => a == b,
+            (Self::Lab(a), Self::Lab(b)) => a == b,
+            (Self::Lch(a), Self::Lch(b)) => a == b,
+            (Self::Xyz(a), Self::Xyz(b)) => a == b,
+            (Self::P3(a), Self::P3(b)) => a == b,
             (Self::LinearRgb(a), Self::LinearRgb(b)) => a == b,
             (Self::Cmyk(a), Self::Cmyk(b)) => a == b,
             (Self::Hsl(a), Self::Hsl(b)) => a == b,
             (Self::Hsv(a), Self::Hsv(b)) => a == b,
+            (Self::Hwb(a), Self::Hwb(b)) => a == b,
+            (Self::Okhsl(a), Self::Okhsl(b)) => a == b,
+            (Self::Okhsv(a), Self::Okhsv(b)) => a == b,
+            (Self::Icc(a), Self::Icc(b)) => a == b,
             _ => false,
         }
     }
@@ -1610,6 +4287,11 @@ impl Hash for Color {
         y.to_bits().hash(state);
         z.to_bits().hash(state);
         w.to_bits().hash(state);
+        match self {
+            Self::Cmyk(c) => c.overprint.hash(state),
+            Self::Icc(c) => c.profile.hash(state),
+            _ => {}
+        }
     }
 }
 
@@ -1672,6 +4354,24 @@ impl From<Oklch> for Color {
     }
 }
 
+impl From<Lab> for Color {
+    fn from(c: Lab) -> Self {
+        Self::Lab(c)
+    }
+}
+
+impl From<Lch> for Color {
+    fn from(c: Lch) -> Self {
+        Self::Lch(c)
+    }
+}
+
+impl From<Xyz> for Color {
+    fn from(c: Xyz) -> Self {
+        Self::Xyz(c)
+    }
+}
+
 impl From<Rgb> for Color {
     fn from(c: Rgb) -> Self {
         Self::Rgb(c)
@@ -1684,6 +4384,12 @@ impl From<LinearRgb> for Color {
     }
 }
 
+impl From<P3> for Color {
+    fn from(c: P3) -> Self {
+        Self::P3(c)
+    }
+}
+
 impl From<Cmyk> for Color {
     fn from(c: Cmyk) -> Self {
         Self::Cmyk(c)
@@ -1702,6 +4408,30 @@ impl From<Hsv> for Color {
     }
 }
 
+impl From<Hwb> for Color {
+    fn from(c: Hwb) -> Self {
+        Self::Hwb(c)
+    }
+}
+
+impl From<Okhsl> for Color {
+    fn from(c: Okhsl) -> Self {
+        Self::Okhsl(c)
+    }
+}
+
+impl From<Okhsv> for Color {
+    fn from(c: Okhsv) -> Self {
+        Self::Okhsv(c)
+    }
+}
+
+impl From<Icc> for Color {
+    fn from(c: Icc) -> Self {
+        Self::Icc(c)
+    }
+}
+
 /// An 8-bit CMYK color.
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Cmyk {
@@ -1713,11 +4443,13 @@ pub struct Cmyk {
     pub y: f32,
     /// The key (black) component.
     pub k: f32,
+    /// Whether the color overprints the colors below it.
+    pub overprint: bool,
 }
 
 impl Cmyk {
     fn new(c: f32, m: f32, y: f32, k: f32) -> Self {
-        Self { c, m, y, k }
+        Self { c, m, y, k, overprint: false }
     }
 
     fn from_luma(luma: Luma) -> Self {
@@ -1732,47 +4464,602 @@ impl Cmyk {
         let g = rgba.green;
         let b = rgba.blue;
 
-        let k = 1.0 - r.max(g).max(b);
-        if k == 1.0 {
-            return Cmyk::new(0.0, 0.0, 0.0, 1.0);
+        let k = 1.0 - r.max(g).max(b);
+        if k == 1.0 {
+            return Cmyk::new(0.0, 0.0, 0.0, 1.0);
+        }
+
+        let c = (1.0 - r - k) / (1.0 - k);
+        let m = (1.0 - g - k) / (1.0 - k);
+        let y = (1.0 - b - k) / (1.0 - k);
+
+        Cmyk::new(c, m, y, k)
+    }
+
+    fn to_rgba(self) -> Rgb {
+        let mut dest: [u8; 3] = [0; 3];
+        TO_SRGB.convert(
+            &[
+                (self.c * 255.0).round() as u8,
+                (self.m * 255.0).round() as u8,
+                (self.y * 255.0).round() as u8,
+                (self.k * 255.0).round() as u8,
+            ],
+            &mut dest,
+        );
+
+        Rgb::new(
+            f32::from(dest[0]) / 255.0,
+            f32::from(dest[1]) / 255.0,
+            f32::from(dest[2]) / 255.0,
+            1.0,
+        )
+    }
+
+    fn lighten(self, factor: f32) -> Self {
+        let lighten = |u: f32| (u - u * factor).clamp(0.0, 1.0);
+        Self {
+            overprint: self.overprint,
+            ..Self::new(
+                lighten(self.c),
+                lighten(self.m),
+                lighten(self.y),
+                lighten(self.k),
+            )
+        }
+    }
+
+    fn darken(self, factor: f32) -> Self {
+        let darken = |u: f32| (u + (1.0 - u) * factor).clamp(0.0, 1.0);
+        Self {
+            overprint: self.overprint,
+            ..Self::new(darken(self.c), darken(self.m), darken(self.y), darken(self.k))
+        }
+    }
+}
+
+/// A color in the color space of an ICC profile.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Icc {
+    /// The profile that defines the color space.
+    pub profile: IccProfile,
+    /// The components, of which only the first `channels` are used.
+    pub components: [f32; 4],
+    /// The alpha component, which is ignored for CMYK profiles.
+    pub alpha: f32,
+}
+
+impl Icc {
+    /// The components of the color, one per channel of the profile.
+    pub fn channels(&self) -> &[f32] {
+        &self.components[..self.profile.space().channels()]
+    }
+
+    /// The alpha component, if the profile's color space has one.
+    fn alpha(&self) -> Option<f32> {
+        (self.profile.space() != IccSpace::Cmyk).then_some(self.alpha)
+    }
+
+    fn to_rgba(self) -> Rgb {
+        let [r, g, b] = self.profile.to_srgb(self.channels());
+        Rgb::new(
+            f32::from(r) / 255.0,
+            f32::from(g) / 255.0,
+            f32::from(b) / 255.0,
+            self.alpha().unwrap_or(1.0),
+        )
+    }
+
+    fn to_vec4(self) -> [f32; 4] {
+        match self.components {
+            [v, _, _, _] if self.profile.space() == IccSpace::Gray => {
+                [v, v, v, self.alpha]
+            }
+            [r, g, b, _] if self.profile.space() == IccSpace::Rgb => {
+                [r, g, b, self.alpha]
+            }
+            components => components,
+        }
+    }
+
+    // Colorants in a CMYK space add up to darker colors, while light adds up
+    // to brighter colors in all other spaces.
+    fn lighten(self, factor: f32) -> Self {
+        let lighten = |u: f32| match self.profile.space() {
+            IccSpace::Cmyk => (u - u * factor).clamp(0.0, 1.0),
+            _ => (u + (1.0 - u) * factor).clamp(0.0, 1.0),
+        };
+        self.map(lighten)
+    }
+
+    fn darken(self, factor: f32) -> Self {
+        let darken = |u: f32| match self.profile.space() {
+            IccSpace::Cmyk => (u + (1.0 - u) * factor).clamp(0.0, 1.0),
+            _ => (u - u * factor).clamp(0.0, 1.0),
+        };
+        self.map(darken)
+    }
+
+    /// Applies a function to all used components.
+    fn map(mut self, f: impl Fn(f32) -> f32) -> Self {
+        let channels = self.profile.space().channels();
+        for v in &mut self.components[..channels] {
+            *v = f(*v);
+        }
+        self
+    }
+}
+
+/// A 32-bit color in the CIE XYZ color space.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Xyz {
+    /// The x component.
+    pub x: f32,
+    /// The y (luminance) component.
+    pub y: f32,
+    /// The z component.
+    pub z: f32,
+    /// The alpha component.
+    pub alpha: f32,
+    /// The reference white point the components are relative to.
+    pub white: WhitePoint,
+}
+
+impl Xyz {
+    fn new(x: f32, y: f32, z: f32, alpha: f32, white: WhitePoint) -> Self {
+        Self { x, y, z, alpha, white }
+    }
+
+    fn from_linear_rgb(rgb: LinearRgb, white: WhitePoint) -> Self {
+        let matrix = match white {
+            WhitePoint::D50 => &LINEAR_RGB_TO_XYZ_D50,
+            WhitePoint::D65 => &LINEAR_RGB_TO_XYZ_D65,
+        };
+        let [x, y, z] = mat_vec(matrix, [rgb.red, rgb.green, rgb.blue]);
+        Self::new(x, y, z, rgb.alpha, white)
+    }
+
+    fn to_linear_rgb(self) -> LinearRgb {
+        let matrix = match self.white {
+            WhitePoint::D50 => &XYZ_D50_TO_LINEAR_RGB,
+            WhitePoint::D65 => &XYZ_D65_TO_LINEAR_RGB,
+        };
+        let [r, g, b] = mat_vec(matrix, [self.x, self.y, self.z]);
+        LinearRgb::new(r, g, b, self.alpha)
+    }
+
+    fn from_lab(lab: Lab, white: WhitePoint) -> Self {
+        match white {
+            WhitePoint::D50 => {
+                let xyz = Xyza::<D50, f32>::from_color_unclamped(lab);
+                Self::new(xyz.x, xyz.y, xyz.z, xyz.alpha, white)
+            }
+            WhitePoint::D65 => Self::from_linear_rgb(lab_to_linear_rgb(lab), white),
+        }
+    }
+
+    fn to_lab(self) -> Lab {
+        match self.white {
+            WhitePoint::D50 => Lab::from_color_unclamped(Xyza::<D50, f32>::new(
+                self.x, self.y, self.z, self.alpha,
+            )),
+            WhitePoint::D65 => linear_rgb_to_lab(self.to_linear_rgb()),
+        }
+    }
+}
+
+/// A 32-bit RGB color in the Display P3 color space.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct P3 {
+    /// The red component.
+    pub red: f32,
+    /// The green component.
+    pub green: f32,
+    /// The blue component.
+    pub blue: f32,
+    /// The alpha component.
+    pub alpha: f32,
+}
+
+impl P3 {
+    fn new(red: f32, green: f32, blue: f32, alpha: f32) -> Self {
+        Self { red, green, blue, alpha }
+    }
+
+    /// Converts a linear sRGB color, which may lie outside of the sRGB gamut,
+    /// to Display P3. Colors outside of the Display P3 gamut are clipped.
+    fn from_linear_rgb(rgb: LinearRgb) -> Self {
+        let linear = mat_vec(&LINEAR_RGB_TO_LINEAR_P3, [rgb.red, rgb.green, rgb.blue]);
+        let [r, g, b] = linear.map(srgb_encode);
+        Self::new(r.clamp(0.0, 1.0), g.clamp(0.0, 1.0), b.clamp(0.0, 1.0), rgb.alpha)
+    }
+
+    /// Converts to linear sRGB without clamping to the sRGB gamut.
+    fn to_linear_rgb(self) -> LinearRgb {
+        let linear = [self.red, self.green, self.blue].map(srgb_decode);
+        let [r, g, b] = mat_vec(&LINEAR_P3_TO_LINEAR_RGB, linear);
+        LinearRgb::new(r, g, b, self.alpha)
+    }
+
+    /// Converts to sRGB, mapping colors outside of the sRGB gamut into it.
+    fn to_gamut_mapped_rgb(self) -> Rgb {
+        Rgb::from_linear(map_into_gamut(
+            self.to_linear_rgb(),
+            Gamut::Srgb,
+            GamutMapping::ChromaReduce,
+        ))
+    }
+
+    fn lighten(self, factor: f32) -> Self {
+        let lighten = |u: f32| (u + (1.0 - u) * factor).clamp(0.0, 1.0);
+        Self::new(lighten(self.red), lighten(self.green), lighten(self.blue), self.alpha)
+    }
+
+    fn darken(self, factor: f32) -> Self {
+        let darken = |u: f32| (u - u * factor).clamp(0.0, 1.0);
+        Self::new(darken(self.red), darken(self.green), darken(self.blue), self.alpha)
+    }
+}
+
+/// The direction in which to interpolate between two hues.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum HueInterpolation {
+    /// Take the shorter arc between the hues.
+    #[default]
+    Shorter,
+    /// Take the longer arc between the hues.
+    Longer,
+    /// Move to larger hue angles.
+    Increasing,
+    /// Move to smaller hue angles.
+    Decreasing,
+}
+
+impl HueInterpolation {
+    /// Adjusts two hues in degrees such that linearly interpolating between
+    /// them traverses the hue circle in this direction, as specified by CSS
+    /// Color 4.
+    pub(super) fn adjust(self, h0: f32, h1: f32) -> (f32, f32) {
+        let delta = h1 - h0;
+        match self {
+            Self::Shorter if delta > 180.0 => (h0 + 360.0, h1),
+            Self::Shorter if delta < -180.0 => (h0, h1 + 360.0),
+            Self::Longer if 0.0 < delta && delta < 180.0 => (h0 + 360.0, h1),
+            Self::Longer if -180.0 < delta && delta <= 0.0 => (h0, h1 + 360.0),
+            Self::Increasing if delta < 0.0 => (h0, h1 + 360.0),
+            Self::Decreasing if delta > 0.0 => (h0 + 360.0, h1),
+            _ => (h0, h1),
+        }
+    }
+}
+
+/// A component by which to sort colors.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum ColorSortKey {
+    /// Sort by hue angle.
+    #[default]
+    Hue,
+    /// Sort from dark to light.
+    Lightness,
+    /// Sort from dull to vivid.
+    Chroma,
+}
+
+impl ColorSortKey {
+    /// The name of the component in [`ColorSpace::component_names`].
+    fn component_name(self) -> &'static str {
+        match self {
+            Self::Hue => "hue",
+            Self::Lightness => "lightness",
+            Self::Chroma => "chroma",
+        }
+    }
+}
+
+/// How to map colors that lie outside of a color space's gamut into it.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum GamutMapping {
+    /// Clamp each component to the gamut. This is fast, but can shift the
+    /// hue of saturated colors.
+    #[default]
+    Clip,
+    /// Reduce the chroma in Oklch as described by CSS Color 4, accepting
+    /// imperceptible clipping errors to keep as much chroma as possible.
+    Css,
+    /// Reduce the chroma in Oklch until the color is within the gamut,
+    /// preserving its lightness and hue.
+    ChromaReduce,
+}
+
+/// A Porter-Duff operator to composite two colors.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum CompositeMode {
+    /// The color is drawn on top of the backdrop.
+    #[default]
+    Over,
+    /// Only the part of the color that overlaps the backdrop is kept.
+    In,
+    /// Only the part of the color that doesn't overlap the backdrop is kept.
+    Out,
+    /// The color is drawn on top of the backdrop, but only where the backdrop
+    /// is.
+    Atop,
+    /// Only the parts of the color and the backdrop that don't overlap are
+    /// kept.
+    Xor,
+    /// The color and the backdrop are added.
+    Plus,
+}
+
+impl CompositeMode {
+    /// The fractions of the color and the backdrop that contribute to the
+    /// result, given their alpha values.
+    fn factors(self, a_s: f32, a_b: f32) -> (f32, f32) {
+        match self {
+            Self::Over => (1.0, 1.0 - a_s),
+            Self::In => (a_b, 0.0),
+            Self::Out => (1.0 - a_b, 0.0),
+            Self::Atop => (a_b, 1.0 - a_s),
+            Self::Xor => (1.0 - a_b, 1.0 - a_s),
+            Self::Plus => (1.0, 1.0),
+        }
+    }
+}
+
+/// A method to compute the contrast between two colors.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum ContrastMethod {
+    /// The contrast ratio of WCAG 2, which ranges from 1 to 21 and doesn't
+    /// depend on which color is the text and which is the background.
+    #[default]
+    Wcag,
+    /// The lightness contrast (Lc) of the Accessible Perceptual Contrast
+    /// Algorithm, which ranges from about -108 to 106. It is positive for
+    /// dark text on a light background and negative for light text on a dark
+    /// background. Unlike WCAG 2 ratios, it also predicts readability well for
+    /// dark themes.
+    Apca,
+}
+
+/// Computes the APCA lightness contrast (Lc) from the APCA luminances of the
+/// text and the background.
+///
+/// This implements version 0.0.98G-4g of APCA-W3.
+fn apca_contrast(text: f64, background: f64) -> f64 {
+    // Soft-clamps luminances near black to model flare.
+    let clamp = |y: f64| if y < 0.022 { y + (0.022 - y).powf(1.414) } else { y };
+    let text = clamp(text);
+    let background = clamp(background);
+    if (background - text).abs() < 0.0005 {
+        return 0.0;
+    }
+
+    let lc = if background > text {
+        // Dark text on a light background.
+        let sapc = (background.powf(0.56) - text.powf(0.57)) * 1.14;
+        if sapc < 0.1 {
+            0.0
+        } else {
+            sapc - 0.027
+        }
+    } else {
+        // Light text on a dark background.
+        let sapc = (background.powf(0.65) - text.powf(0.62)) * 1.14;
+        if sapc > -0.1 {
+            0.0
+        } else {
+            sapc + 0.027
+        }
+    };
+
+    lc * 100.0
+}
+
+/// A formula to compute the perceptual difference between two colors.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum DeltaEMethod {
+    /// The CIEDE2000 formula, which corrects the perceptual non-uniformities
+    /// of CIE Lab, especially for blues and saturated colors.
+    #[default]
+    #[string("2000")]
+    Ciede2000,
+    /// The original CIE76 formula, i.e. the euclidean distance in CIE Lab.
+    #[string("76")]
+    Cie76,
+    /// The euclidean distance in Oklab, which is perceptually more uniform
+    /// than CIE Lab to begin with. Ranges from 0 to about 1.
+    Ok,
+}
+
+/// The euclidean distance between two colors' components.
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt()
+}
+
+/// Computes the CIEDE2000 color difference between two CIE Lab colors.
+fn ciede2000([l1, a1, b1]: [f64; 3], [l2, a2, b2]: [f64; 3]) -> f64 {
+    use std::f64::consts::PI;
+
+    // Stretch the a-axis to compensate for the low chroma of neutral colors.
+    let c_mean = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let g = 0.5 * (1.0 - (c_mean.powi(7) / (c_mean.powi(7) + 25.0_f64.powi(7))).sqrt());
+    let (a1, a2) = (a1 * (1.0 + g), a2 * (1.0 + g));
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let hue = |a: f64, b: f64| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).rem_euclid(2.0 * PI)
+        }
+    };
+    let (h1, h2) = (hue(a1, b1), hue(a2, b2));
+
+    // Differences in lightness, chroma, and hue.
+    let dl = l2 - l1;
+    let dc = c2 - c1;
+    let dh = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= PI {
+        h2 - h1
+    } else if h2 <= h1 {
+        h2 - h1 + 2.0 * PI
+    } else {
+        h2 - h1 - 2.0 * PI
+    };
+    let dh = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).sin();
+
+    // Means of lightness, chroma, and hue.
+    let l_mean = (l1 + l2) / 2.0;
+    let c_mean = (c1 + c2) / 2.0;
+    let h_mean = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= PI {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 2.0 * PI {
+        (h1 + h2 + 2.0 * PI) / 2.0
+    } else {
+        (h1 + h2 - 2.0 * PI) / 2.0
+    };
+
+    // Weighting functions and the rotation term for blues.
+    let t = 1.0 - 0.17 * (h_mean - PI / 6.0).cos()
+        + 0.24 * (2.0 * h_mean).cos()
+        + 0.32 * (3.0 * h_mean + PI / 30.0).cos()
+        - 0.20 * (4.0 * h_mean - 63.0 * PI / 180.0).cos();
+    let sl =
+        1.0 + 0.015 * (l_mean - 50.0).powi(2) / (20.0 + (l_mean - 50.0).powi(2)).sqrt();
+    let sc = 1.0 + 0.045 * c_mean;
+    let sh = 1.0 + 0.015 * c_mean * t;
+    let rotation =
+        30.0_f64.to_radians() * (-((h_mean.to_degrees() - 275.0) / 25.0).powi(2)).exp();
+    let rt = -2.0
+        * (c_mean.powi(7) / (c_mean.powi(7) + 25.0_f64.powi(7))).sqrt()
+        * (2.0 * rotation).sin();
+
+    let (l, c, h) = (dl / sl, dc / sc, dh / sh);
+    (l * l + c * c + h * h + rt * c * h).sqrt()
+}
+
+/// A standard illuminant that serves as the reference white of a color.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum WhitePoint {
+    /// Horizon light, as used in print production and by CIELAB.
+    D50,
+    /// Noon daylight, as used by sRGB and most screens.
+    #[default]
+    D65,
+}
+
+impl WhitePoint {
+    /// The CIE XYZ coordinates of the white point, normalized to a luminance
+    /// of one.
+    pub fn xyz(self) -> [f32; 3] {
+        match self {
+            Self::D50 => [0.96422, 1.0, 0.82521],
+            Self::D65 => [0.95047, 1.0, 1.08883],
         }
+    }
+}
 
-        let c = (1.0 - r - k) / (1.0 - k);
-        let m = (1.0 - g - k) / (1.0 - k);
-        let y = (1.0 - b - k) / (1.0 - k);
+/// Either a single color or an array of colors to quantize.
+pub enum Quantizable {
+    Color(Color),
+    Colors(Vec<Color>),
+}
 
-        Cmyk::new(c, m, y, k)
+cast! {
+    Quantizable,
+    self => match self {
+        Self::Color(color) => color.into_value(),
+        Self::Colors(colors) => colors.into_value(),
+    },
+    color: Color => Self::Color(color),
+    colors: Vec<Color> => Self::Colors(colors),
+}
+
+/// Finds a palette of at most `k` colors that represents the given colors,
+/// using k-means clustering in Oklab.
+///
+/// The initial centroids are chosen deterministically by starting with the
+/// color closest to the mean and repeatedly adding the color that is farthest
+/// from all centroids chosen so far.
+fn kmeans_palette(colors: &[Color], k: usize) -> Vec<Color> {
+    const MAX_ITERATIONS: usize = 64;
+
+    let points: Vec<[f32; 4]> = colors.iter().map(|c| c.to_oklab().to_vec4()).collect();
+    if points.is_empty() {
+        return vec![];
     }
 
-    fn to_rgba(self) -> Rgb {
-        let mut dest: [u8; 3] = [0; 3];
-        TO_SRGB.convert(
-            &[
-                (self.c * 255.0).round() as u8,
-                (self.m * 255.0).round() as u8,
-                (self.y * 255.0).round() as u8,
-                (self.k * 255.0).round() as u8,
-            ],
-            &mut dest,
-        );
+    let dist =
+        |a: &[f32; 4], b: &[f32; 4]| (0..3).map(|i| (a[i] - b[i]).powi(2)).sum::<f32>();
+    let nearest = |p: &[f32; 4], centroids: &[[f32; 4]]| {
+        (0..centroids.len())
+            .min_by(|&i, &j| dist(p, &centroids[i]).total_cmp(&dist(p, &centroids[j])))
+            .unwrap()
+    };
 
-        Rgb::new(
-            f32::from(dest[0]) / 255.0,
-            f32::from(dest[1]) / 255.0,
-            f32::from(dest[2]) / 255.0,
-            1.0,
-        )
+    let mut mean = [0.0; 4];
+    for p in &points {
+        for i in 0..4 {
+            mean[i] += p[i] / points.len() as f32;
+        }
     }
 
-    fn lighten(self, factor: f32) -> Self {
-        let lighten = |u: f32| (u - u * factor).clamp(0.0, 1.0);
-        Self::new(lighten(self.c), lighten(self.m), lighten(self.y), lighten(self.k))
+    let mut centroids = vec![points[nearest(&mean, &points)]];
+    while centroids.len() < k {
+        let (i, d) = points
+            .iter()
+            .map(|p| centroids.iter().map(|c| dist(p, c)).fold(f32::INFINITY, f32::min))
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+        if d <= 0.0 {
+            // There are fewer distinct colors than requested.
+            break;
+        }
+        centroids.push(points[i]);
     }
 
-    fn darken(self, factor: f32) -> Self {
-        let darken = |u: f32| (u + (1.0 - u) * factor).clamp(0.0, 1.0);
-        Self::new(darken(self.c), darken(self.m), darken(self.y), darken(self.k))
+    let mut assignment = vec![0; points.len()];
+    let mut counts = vec![0; centroids.len()];
+    for iteration in 0..MAX_ITERATIONS {
+        let mut changed = false;
+        for (p, a) in points.iter().zip(&mut assignment) {
+            let best = nearest(p, &centroids);
+            changed |= best != *a;
+            *a = best;
+        }
+
+        let mut sums = vec![[0.0; 4]; centroids.len()];
+        counts.fill(0);
+        for (p, &a) in points.iter().zip(&assignment) {
+            for i in 0..4 {
+                sums[a][i] += p[i];
+            }
+            counts[a] += 1;
+        }
+
+        for ((centroid, sum), &n) in centroids.iter_mut().zip(&sums).zip(&counts) {
+            if n > 0 {
+                *centroid = sum.map(|v| v / n as f32);
+            }
+        }
+
+        if !changed && iteration > 0 {
+            break;
+        }
     }
+
+    let mut order: Vec<usize> = (0..centroids.len()).filter(|&i| counts[i] > 0).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(counts[i]));
+    order
+        .into_iter()
+        .map(|i| {
+            let [l, a, b, alpha] = centroids[i];
+            Color::Oklab(Oklab::new(l, a, b, alpha))
+        })
+        .collect()
 }
 
 /// A color with a weight.
@@ -1820,8 +5107,16 @@ pub enum ColorSpace {
     Oklab,
     /// The perceptual Oklch color space.
     Oklch,
+    /// The CIELAB color space, relative to the D50 white point.
+    Lab,
+    /// The cylindrical CIELCh color space, relative to the D50 white point.
+    Lch,
+    /// The CIE XYZ color space, relative to the given white point.
+    Xyz(WhitePoint),
     /// The standard RGB color space.
     Srgb,
+    /// The wide-gamut Display P3 color space.
+    DisplayP3,
     /// The D65-gray color space.
     D65Gray,
     /// The linear RGB color space.
@@ -1830,6 +5125,12 @@ pub enum ColorSpace {
     Hsl,
     /// The HSV color space.
     Hsv,
+    /// The HWB color space.
+    Hwb,
+    /// The OkHSL color space.
+    Okhsl,
+    /// The OkHSV color space.
+    Okhsv,
     /// The CMYK color space.
     Cmyk,
 }
@@ -1839,11 +5140,29 @@ impl ColorSpace {
     /// one.
     pub fn hue_index(&self) -> Option<usize> {
         match self {
-            Self::Hsl | Self::Hsv => Some(0),
-            Self::Oklch => Some(2),
+            Self::Hsl | Self::Hsv | Self::Hwb | Self::Okhsl | Self::Okhsv => Some(0),
+            Self::Oklch | Self::Lch => Some(2),
             _ => None,
         }
     }
+
+    /// The names of the components of colors in this space, including alpha
+    /// if the space has it.
+    pub fn component_names(&self) -> &'static [&'static str] {
+        match self {
+            Self::D65Gray => &["lightness", "alpha"],
+            Self::Oklab | Self::Lab => &["lightness", "a", "b", "alpha"],
+            Self::Oklch | Self::Lch => &["lightness", "chroma", "hue", "alpha"],
+            Self::Xyz(_) => &["x", "y", "z", "alpha"],
+            Self::Srgb | Self::DisplayP3 | Self::LinearRgb => {
+                &["red", "green", "blue", "alpha"]
+            }
+            Self::Cmyk => &["cyan", "magenta", "yellow", "key"],
+            Self::Hsl | Self::Okhsl => &["hue", "saturation", "lightness", "alpha"],
+            Self::Hsv | Self::Okhsv => &["hue", "saturation", "value", "alpha"],
+            Self::Hwb => &["hue", "whiteness", "blackness", "alpha"],
+        }
+    }
 }
 
 cast! {
@@ -1851,15 +5170,22 @@ cast! {
     self => match self {
         Self::Oklab => Color::oklab_data(),
         Self::Oklch => Color::oklch_data(),
+        Self::Lab => Color::lab_data(),
+        Self::Lch => Color::lch_data(),
+        Self::Xyz(_) => Color::xyz_data(),
         Self::Srgb => Color::rgb_data(),
+        Self::DisplayP3 => Color::p3_data(),
         Self::D65Gray => Color::luma_data(),
         Self::LinearRgb => Color::linear_rgb_data(),
         Self::Hsl => Color::hsl_data(),
         Self::Hsv => Color::hsv_data(),
+        Self::Hwb => Color::hwb_data(),
+        Self::Okhsl => Color::okhsl_data(),
+        Self::Okhsv => Color::okhsv_data(),
         Self::Cmyk => Color::cmyk_data(),
     }.into_value(),
     v: Value => {
-        let expected = "expected `rgb`, `luma`, `cmyk`, `oklab`, `oklch`, `color.linear-rgb`, `color.hsl`, or `color.hsv`";
+        let expected = "expected `rgb`, `luma`, `cmyk`, `oklab`, `oklch`, `color.lab`, `color.lch`, `color.xyz`, `color.p3`, `color.linear-rgb`, `color.hsl`, `color.hsv`, `color.hwb`, `color.okhsl`, or `color.okhsv`";
         let Value::Func(func) = v else {
             bail!("{expected}, found {}", v.ty());
         };
@@ -1870,8 +5196,16 @@ cast! {
             Self::Oklab
         } else if func == Color::oklch_data() {
             Self::Oklch
+        } else if func == Color::lab_data() {
+            Self::Lab
+        } else if func == Color::lch_data() {
+            Self::Lch
+        } else if func == Color::xyz_data() {
+            Self::Xyz(WhitePoint::D65)
         } else if func == Color::rgb_data() {
             Self::Srgb
+        } else if func == Color::p3_data() {
+            Self::DisplayP3
         } else if func == Color::luma_data() {
             Self::D65Gray
         } else if func == Color::linear_rgb_data() {
@@ -1880,6 +5214,12 @@ cast! {
             Self::Hsl
         } else if func == Color::hsv_data() {
             Self::Hsv
+        } else if func == Color::hwb_data() {
+            Self::Hwb
+        } else if func == Color::okhsl_data() {
+            Self::Okhsl
+        } else if func == Color::okhsv_data() {
+            Self::Okhsv
         } else if func == Color::cmyk_data() {
             Self::Cmyk
         } else {
@@ -1910,8 +5250,69 @@ pub struct ChromaComponent(f32);
 
 cast! {
     ChromaComponent,
-    v: f64 => Self(v as f32),
-    v: Ratio => Self((v.get() * 0.4) as f32),
+    v: f64 => Self(finite(v)?),
+    v: Ratio => Self(finite(v.get() * 0.4)?),
+}
+
+/// A CIELAB `a` or `b` component.
+///
+/// Must either be:
+/// - a ratio, in which case it is relative to 125.
+/// - a float, in which case it is taken literally.
+pub struct LabComponent(f32);
+
+cast! {
+    LabComponent,
+    v: f64 => Self(finite(v)?),
+    v: Ratio => Self(finite(v.get() * 125.0)?),
+}
+
+/// A CIELCh chroma component.
+///
+/// Must either be:
+/// - a ratio, in which case it is relative to 150.
+/// - a float, in which case it is taken literally.
+pub struct LabChromaComponent(f32);
+
+cast! {
+    LabChromaComponent,
+    v: f64 => Self(finite(v)?),
+    v: Ratio => Self(finite(v.get() * 150.0)?),
+}
+
+/// A CIE XYZ component.
+///
+/// Must either be:
+/// - a ratio, in which case it is relative to 1.
+/// - a float, in which case it is taken literally.
+pub struct XyzComponent(f32);
+
+cast! {
+    XyzComponent,
+    v: f64 => Self(finite(v)?),
+    v: Ratio => Self(finite(v.get())?),
+}
+
+/// A hue component.
+///
+/// Must be a finite angle.
+pub struct HueComponent(Angle);
+
+cast! {
+    HueComponent,
+    v: Angle => {
+        finite(v.to_deg())?;
+        Self(v)
+    },
+}
+
+/// Ensures that an unbounded component is finite.
+fn finite(v: f64) -> StrResult<f32> {
+    let v = v as f32;
+    if !v.is_finite() {
+        bail!("component must be finite");
+    }
+    Ok(v)
 }
 
 /// An integer or ratio component.
@@ -1948,9 +5349,208 @@ fn map() -> Module {
     scope.define("icefire", icefire());
     scope.define("flare", flare());
     scope.define("crest", crest());
+    scope.define_func::<custom>();
+    scope.define_func::<sample>();
+    scope.define_func::<resample>();
+    scope.define_func::<reverse>();
     Module::new("map", scope)
 }
 
+/// Creates a custom color map from a series of color stops.
+///
+/// The stops are interpolated in the given color space and the resulting path
+/// is resampled so that neighbouring samples are equally far apart in terms of
+/// perceived difference. This avoids the banding that occurs when mixing the
+/// stops by hand. The result is an array of colors that can be used anywhere a
+/// preset map can.
+///
+/// ```example
+/// #let heat = color.map.custom(black, red, yellow, white, samples: 32)
+/// #heat.len() \
+/// #rect(width: 100%, fill: gradient.linear(..heat))
+/// ```
+#[func]
+fn custom(
+    /// The call site of this function.
+    span: Span,
+    /// The color stops of the map, in the same format as the stops of a
+    /// [gradient]($gradient/#stops).
+    #[variadic]
+    stops: Vec<Spanned<GradientStop>>,
+    /// The number of colors in the resulting map.
+    #[named]
+    #[default(Spanned::new(256, Span::detached()))]
+    samples: Spanned<usize>,
+    /// The color space in which to interpolate between the stops.
+    #[named]
+    #[default(ColorSpace::Oklab)]
+    space: ColorSpace,
+    /// Whether to space the samples evenly by perceived color difference. If
+    /// this is `{false}`, the samples are spaced evenly along the stop offsets
+    /// instead.
+    #[named]
+    #[default(true)]
+    uniform: bool,
+) -> SourceResult<Array> {
+    if stops.len() < 2 {
+        bail!(
+            span, "a color map must have at least two stops";
+            hint: "try filling the map with a single color instead"
+        );
+    }
+
+    if samples.v < 2 {
+        bail!(samples.span, "a color map must have at least two samples");
+    }
+
+    let stops = process_stops(&stops)?;
+    let hue = HueInterpolation::Shorter;
+    let at = |t: f64| sample_stops(&stops, space, hue, &Easing::Linear, t);
+    let n = samples.v;
+
+    // Walk along the interpolated path in small steps, measuring the distance
+    // in Oklab, and then pick the offsets at which the accumulated distance
+    // is evenly spaced.
+    let mut offsets: Vec<f64> = (0..n).map(|i| i as f64 / (n - 1) as f64).collect();
+    if uniform {
+        const STEPS: usize = 1024;
+        let mut lengths = Vec::with_capacity(STEPS + 1);
+        let mut total = 0.0;
+        let mut prev = at(0.0).to_oklab().to_vec4();
+        lengths.push(0.0);
+        for i in 1..=STEPS {
+            let next = at(i as f64 / STEPS as f64).to_oklab().to_vec4();
+            total +=
+                (0..3).map(|k| (next[k] - prev[k]).powi(2)).sum::<f32>().sqrt() as f64;
+            lengths.push(total);
+            prev = next;
+        }
+
+        if total > 0.0 {
+            let mut j = 0;
+            for offset in &mut offsets {
+                let target = *offset * total;
+                while j + 1 < STEPS && lengths[j + 1] < target {
+                    j += 1;
+                }
+                let (l0, l1) = (lengths[j], lengths[j + 1]);
+                let frac = if l1 > l0 { (target - l0) / (l1 - l0) } else { 0.0 };
+                *offset = (j as f64 + frac.clamp(0.0, 1.0)) / STEPS as f64;
+            }
+        }
+    }
+
+    Ok(offsets.into_iter().map(|t| at(t).into_value()).collect())
+}
+
+/// Samples a color map at a given position.
+///
+/// The map is interpolated just like a [gradient]($gradient) with the map's
+/// colors as its stops, but without having to construct one. Positions outside
+/// of the range from `{0%}` to `{100%}` are clamped.
+///
+/// ```example
+/// #let values = (3, 9, 4, 1, 7)
+/// #table(
+///   columns: values.len(),
+///   fill: (x, _) => color.map.sample(color.map.viridis, values.at(x) * 10%),
+///   ..values.map(v => text(white, str(v))),
+/// )
+/// ```
+#[func]
+fn sample(
+    /// The color map to sample, e.g. one of the presets or a map created with
+    /// [`color.map.custom`]($color.map.custom). Its entries may also be color
+    /// stops with explicit offsets.
+    map: Spanned<Vec<GradientStop>>,
+    /// The position at which to sample the map.
+    t: Ratio,
+    /// The color space in which to interpolate between the colors of the map.
+    #[named]
+    #[default(ColorSpace::Oklab)]
+    space: ColorSpace,
+) -> SourceResult<Color> {
+    let Spanned { v: map, span } = map;
+    if map.len() < 2 {
+        bail!(span, "a color map must have at least two stops");
+    }
+
+    let stops: Vec<_> = map.into_iter().map(|stop| Spanned::new(stop, span)).collect();
+    let stops = process_stops(&stops)?;
+    Ok(sample_stops(&stops, space, HueInterpolation::Shorter, &Easing::Linear, t.get()))
+}
+
+/// Resamples a color map to a given number of colors.
+///
+/// The map is sampled at evenly spaced positions, just like with
+/// [`color.map.sample`]($color.map.sample). This is useful to turn one of the
+/// large preset maps into a handful of distinct colors, for instance for a
+/// legend with discrete steps, or to reduce the number of stops of a gradient.
+///
+/// ```example
+/// #let steps = color.map.resample(color.map.viridis, 5)
+/// #for c in steps {
+///   box(square(size: 12pt, fill: c))
+/// }
+/// ```
+#[func]
+fn resample(
+    /// The color map to resample. Its entries may also be color stops with
+    /// explicit offsets.
+    map: Spanned<Vec<GradientStop>>,
+    /// The number of colors in the resulting map.
+    n: Spanned<usize>,
+    /// The color space in which to interpolate between the colors of the map.
+    #[named]
+    #[default(ColorSpace::Oklab)]
+    space: ColorSpace,
+) -> SourceResult<Array> {
+    let Spanned { v: map, span } = map;
+    if map.len() < 2 {
+        bail!(span, "a color map must have at least two stops");
+    }
+
+    if n.v < 2 {
+        bail!(n.span, "a color map must have at least two samples");
+    }
+
+    let stops: Vec<_> = map.into_iter().map(|stop| Spanned::new(stop, span)).collect();
+    let stops = process_stops(&stops)?;
+    let hue = HueInterpolation::Shorter;
+    Ok((0..n.v)
+        .map(|i| {
+            let t = i as f64 / (n.v - 1) as f64;
+            sample_stops(&stops, space, hue, &Easing::Linear, t).into_value()
+        })
+        .collect())
+}
+
+/// Reverses a color map.
+///
+/// The colors are returned in reverse order. If the entries of the map are
+/// color stops with explicit offsets, the offsets are mirrored, too, so that
+/// the reversed map has the same shape.
+///
+/// ```example
+/// #rect(width: 100%, fill: gradient.linear(
+///   ..color.map.reverse(color.map.rocket),
+/// ))
+/// ```
+#[func]
+fn reverse(
+    /// The color map to reverse.
+    map: Vec<GradientStop>,
+) -> Array {
+    map.into_iter()
+        .rev()
+        .map(|stop| GradientStop {
+            offset: stop.offset.map(|offset| Ratio::one() - offset),
+            ..stop
+        })
+        .map(IntoValue::into_value)
+        .collect()
+}
+
 /// Defines a tradient preset as a series of colors expressed as u32s.
 macro_rules! preset {
     ($name:ident; $($colors:literal),* $(,)*) => {
@@ -1980,6 +5580,354 @@ preset!(icefire; 0xbde7dbff, 0xbae5daff, 0xb7e3d9ff, 0xb4e1d9ff, 0xb2dfd8ff, 0xa
 preset!(flare; 0xedb081ff, 0xedaf80ff, 0xedae7fff, 0xedad7fff, 0xedac7eff, 0xedab7eff, 0xecaa7dff, 0xeca97cff, 0xeca87cff, 0xeca77bff, 0xeca67bff, 0xeca57aff, 0xeca479ff, 0xeca379ff, 0xeca278ff, 0xeca178ff, 0xeca077ff, 0xec9f76ff, 0xeb9e76ff, 0xeb9d75ff, 0xeb9c75ff, 0xeb9b74ff, 0xeb9a73ff, 0xeb9973ff, 0xeb9972ff, 0xeb9872ff, 0xeb9771ff, 0xea9671ff, 0xea9570ff, 0xea946fff, 0xea936fff, 0xea926eff, 0xea916eff, 0xea906dff, 0xea8f6cff, 0xea8e6cff, 0xe98d6bff, 0xe98c6bff, 0xe98b6aff, 0xe98a6aff, 0xe98969ff, 0xe98868ff, 0xe98768ff, 0xe98667ff, 0xe88567ff, 0xe88466ff, 0xe88366ff, 0xe88265ff, 0xe88165ff, 0xe88064ff, 0xe87f64ff, 0xe77e63ff, 0xe77d63ff, 0xe77c63ff, 0xe77b62ff, 0xe77a62ff, 0xe67961ff, 0xe67861ff, 0xe67760ff, 0xe67660ff, 0xe67560ff, 0xe5745fff, 0xe5735fff, 0xe5725fff, 0xe5715eff, 0xe5705eff, 0xe46f5eff, 0xe46e5eff, 0xe46d5dff, 0xe46c5dff, 0xe36b5dff, 0xe36a5dff, 0xe3695dff, 0xe3685cff, 0xe2675cff, 0xe2665cff, 0xe2655cff, 0xe1645cff, 0xe1635cff, 0xe1625cff, 0xe0615cff, 0xe0605cff, 0xe05f5cff, 0xdf5f5cff, 0xdf5e5cff, 0xde5d5cff, 0xde5c5cff, 0xde5b5cff, 0xdd5a5cff, 0xdd595cff, 0xdc585cff, 0xdc575cff, 0xdb565dff, 0xdb565dff, 0xda555dff, 0xda545dff, 0xd9535dff, 0xd9525eff, 0xd8525eff, 0xd7515eff, 0xd7505eff, 0xd64f5fff, 0xd64f5fff, 0xd54e5fff, 0xd44d60ff, 0xd44c60ff, 0xd34c60ff, 0xd24b60ff, 0xd24a61ff, 0xd14a61ff, 0xd04962ff, 0xd04962ff, 0xcf4862ff, 0xce4763ff, 0xcd4763ff, 0xcc4663ff, 0xcc4664ff, 0xcb4564ff, 0xca4564ff, 0xc94465ff, 0xc84465ff, 0xc84365ff, 0xc74366ff, 0xc64366ff, 0xc54266ff, 0xc44267ff, 0xc34167ff, 0xc24167ff, 0xc14168ff, 0xc14068ff, 0xc04068ff, 0xbf4069ff, 0xbe3f69ff, 0xbd3f69ff, 0xbc3f69ff, 0xbb3f6aff, 0xba3e6aff, 0xb93e6aff, 0xb83e6bff, 0xb73d6bff, 0xb63d6bff, 0xb53d6bff, 0xb43d6bff, 0xb33c6cff, 0xb23c6cff, 0xb13c6cff, 0xb13c6cff, 0xb03b6dff, 0xaf3b6dff, 0xae3b6dff, 0xad3b6dff, 0xac3a6dff, 0xab3a6dff, 0xaa3a6eff, 0xa93a6eff, 0xa8396eff, 0xa7396eff, 0xa6396eff, 0xa5396eff, 0xa4386fff, 0xa3386fff, 0xa2386fff, 0xa1386fff, 0xa1376fff, 0xa0376fff, 0x9f376fff, 0x9e3770ff, 0x9d3670ff, 0x9c3670ff, 0x9b3670ff, 0x9a3670ff, 0x993570ff, 0x983570ff, 0x973570ff, 0x963570ff, 0x953470ff, 0x943470ff, 0x943471ff, 0x933471ff, 0x923371ff, 0x913371ff, 0x903371ff, 0x8f3371ff, 0x8e3271ff, 0x8d3271ff, 0x8c3271ff, 0x8b3271ff, 0x8a3171ff, 0x893171ff, 0x883171ff, 0x873171ff, 0x873171ff, 0x863071ff, 0x853071ff, 0x843071ff, 0x833070ff, 0x822f70ff, 0x812f70ff, 0x802f70ff, 0x7f2f70ff, 0x7e2f70ff, 0x7d2e70ff, 0x7c2e70ff, 0x7b2e70ff, 0x7a2e70ff, 0x792e6fff, 0x782e6fff, 0x772d6fff, 0x762d6fff, 0x752d6fff, 0x752d6fff, 0x742d6eff, 0x732c6eff, 0x722c6eff, 0x712c6eff, 0x702c6eff, 0x6f2c6dff, 0x6e2c6dff, 0x6d2b6dff, 0x6c2b6dff, 0x6b2b6cff, 0x6a2b6cff, 0x692b6cff, 0x682a6cff, 0x672a6bff, 0x662a6bff, 0x652a6bff, 0x642a6aff, 0x642a6aff, 0x63296aff, 0x62296aff, 0x612969ff, 0x602969ff, 0x5f2969ff, 0x5e2868ff, 0x5d2868ff, 0x5c2868ff, 0x5b2867ff, 0x5a2767ff, 0x592767ff, 0x582766ff, 0x582766ff, 0x572766ff, 0x562666ff, 0x552665ff, 0x542665ff, 0x532665ff, 0x522564ff, 0x512564ff, 0x502564ff, 0x4f2463ff, 0x4f2463ff, 0x4e2463ff, 0x4d2463ff, 0x4c2362ff, 0x4b2362ff);
 preset!(crest; 0xa5cd90ff, 0xa4cc90ff, 0xa3cc91ff, 0xa2cb91ff, 0xa0cb91ff, 0x9fca91ff, 0x9eca91ff, 0x9dc991ff, 0x9cc891ff, 0x9bc891ff, 0x9ac791ff, 0x99c791ff, 0x98c691ff, 0x96c691ff, 0x95c591ff, 0x94c591ff, 0x93c491ff, 0x92c491ff, 0x91c391ff, 0x90c391ff, 0x8fc291ff, 0x8ec291ff, 0x8dc191ff, 0x8bc191ff, 0x8ac091ff, 0x89bf91ff, 0x88bf91ff, 0x87be91ff, 0x86be91ff, 0x85bd91ff, 0x84bd91ff, 0x82bc91ff, 0x81bc91ff, 0x80bb91ff, 0x7fbb91ff, 0x7eba91ff, 0x7dba91ff, 0x7cb991ff, 0x7bb991ff, 0x79b891ff, 0x78b891ff, 0x77b791ff, 0x76b791ff, 0x75b690ff, 0x74b690ff, 0x73b590ff, 0x72b490ff, 0x71b490ff, 0x70b390ff, 0x6fb390ff, 0x6eb290ff, 0x6db290ff, 0x6cb190ff, 0x6bb190ff, 0x6ab090ff, 0x69b090ff, 0x68af90ff, 0x67ae90ff, 0x66ae90ff, 0x65ad90ff, 0x64ad90ff, 0x63ac90ff, 0x62ac90ff, 0x62ab90ff, 0x61aa90ff, 0x60aa90ff, 0x5fa990ff, 0x5ea990ff, 0x5da890ff, 0x5ca890ff, 0x5ba790ff, 0x5ba690ff, 0x5aa690ff, 0x59a590ff, 0x58a590ff, 0x57a490ff, 0x57a490ff, 0x56a390ff, 0x55a290ff, 0x54a290ff, 0x53a190ff, 0x53a190ff, 0x52a090ff, 0x519f90ff, 0x509f90ff, 0x509e90ff, 0x4f9e90ff, 0x4e9d90ff, 0x4e9d90ff, 0x4d9c90ff, 0x4c9b90ff, 0x4b9b90ff, 0x4b9a8fff, 0x4a9a8fff, 0x49998fff, 0x49988fff, 0x48988fff, 0x47978fff, 0x47978fff, 0x46968fff, 0x45958fff, 0x45958fff, 0x44948fff, 0x43948fff, 0x43938fff, 0x42928fff, 0x41928fff, 0x41918fff, 0x40918fff, 0x40908eff, 0x3f8f8eff, 0x3e8f8eff, 0x3e8e8eff, 0x3d8e8eff, 0x3c8d8eff, 0x3c8c8eff, 0x3b8c8eff, 0x3a8b8eff, 0x3a8b8eff, 0x398a8eff, 0x388a8eff, 0x38898eff, 0x37888eff, 0x37888dff, 0x36878dff, 0x35878dff, 0x35868dff, 0x34858dff, 0x33858dff, 0x33848dff, 0x32848dff, 0x31838dff, 0x31828dff, 0x30828dff, 0x2f818dff, 0x2f818dff, 0x2e808dff, 0x2d808cff, 0x2d7f8cff, 0x2c7e8cff, 0x2c7e8cff, 0x2b7d8cff, 0x2a7d8cff, 0x2a7c8cff, 0x297b8cff, 0x287b8cff, 0x287a8cff, 0x277a8cff, 0x27798cff, 0x26788cff, 0x25788cff, 0x25778cff, 0x24778bff, 0x24768bff, 0x23758bff, 0x23758bff, 0x22748bff, 0x22748bff, 0x21738bff, 0x21728bff, 0x20728bff, 0x20718bff, 0x20718bff, 0x1f708bff, 0x1f6f8aff, 0x1e6f8aff, 0x1e6e8aff, 0x1e6d8aff, 0x1e6d8aff, 0x1d6c8aff, 0x1d6c8aff, 0x1d6b8aff, 0x1d6a8aff, 0x1d6a8aff, 0x1c6989ff, 0x1c6889ff, 0x1c6889ff, 0x1c6789ff, 0x1c6689ff, 0x1c6689ff, 0x1c6589ff, 0x1c6488ff, 0x1c6488ff, 0x1c6388ff, 0x1d6388ff, 0x1d6288ff, 0x1d6188ff, 0x1d6187ff, 0x1d6087ff, 0x1d5f87ff, 0x1d5f87ff, 0x1e5e87ff, 0x1e5d86ff, 0x1e5d86ff, 0x1e5c86ff, 0x1e5b86ff, 0x1f5b86ff, 0x1f5a85ff, 0x1f5985ff, 0x1f5985ff, 0x205885ff, 0x205784ff, 0x205784ff, 0x205684ff, 0x215584ff, 0x215583ff, 0x215483ff, 0x225383ff, 0x225283ff, 0x225282ff, 0x225182ff, 0x235082ff, 0x235081ff, 0x234f81ff, 0x244e81ff, 0x244e80ff, 0x244d80ff, 0x254c80ff, 0x254c7fff, 0x254b7fff, 0x254a7fff, 0x26497eff, 0x26497eff, 0x26487eff, 0x27477dff, 0x27477dff, 0x27467cff, 0x27457cff, 0x28457cff, 0x28447bff, 0x28437bff, 0x28427aff, 0x29427aff, 0x29417aff, 0x294079ff, 0x294079ff, 0x2a3f78ff, 0x2a3e78ff, 0x2a3d78ff, 0x2a3d77ff, 0x2a3c77ff, 0x2a3b76ff, 0x2b3b76ff, 0x2b3a76ff, 0x2b3975ff, 0x2b3875ff, 0x2b3875ff, 0x2b3774ff, 0x2b3674ff, 0x2c3574ff, 0x2c3573ff, 0x2c3473ff, 0x2c3373ff, 0x2c3272ff, 0x2c3172ff, 0x2c3172ff);
 
+/// Clamps a color component between zero and one, treating a missing
+/// component (`NaN`) as zero.
+fn clamp_component(x: f32) -> f32 {
+    if x.is_nan() {
+        0.0
+    } else {
+        x.clamp(0.0, 1.0)
+    }
+}
+
+/// A color given as a vec of its four components, as returned by
+/// [`Color::to_vec4`]. The color space is not part of the representation.
+pub trait ColorRepr {
+    /// The components of the color.
+    fn vec4(&self) -> [f32; 4];
+
+    /// Replaces the components of the color.
+    fn set_vec4(&mut self, vec: [f32; 4]);
+}
+
+impl ColorRepr for [f32; 4] {
+    fn vec4(&self) -> [f32; 4] {
+        *self
+    }
+
+    fn set_vec4(&mut self, vec: [f32; 4]) {
+        *self = vec;
+    }
+}
+
+/// A conversion between two color spaces that works directly on the
+/// components of the colors.
+type Conversion = fn([f32; 4]) -> [f32; 4];
+
+/// Selects a direct conversion between two color spaces, if there is one.
+///
+/// The conversions produce the same results as [`Color::to_space`].
+fn fast_conversion(from: ColorSpace, to: ColorSpace) -> Option<Conversion> {
+    fn rgb([r, g, b, a]: [f32; 4]) -> Rgb {
+        Rgb::new(r, g, b, a)
+    }
+
+    fn linear([r, g, b, a]: [f32; 4]) -> LinearRgb {
+        LinearRgb::new(r, g, b, a)
+    }
+
+    fn oklab([l, a, b, alpha]: [f32; 4]) -> Oklab {
+        Oklab::new(l, a, b, alpha)
+    }
+
+    fn from_rgb(c: Rgb) -> [f32; 4] {
+        [c.red, c.green, c.blue, c.alpha]
+    }
+
+    fn from_linear(c: LinearRgb) -> [f32; 4] {
+        [c.red, c.green, c.blue, c.alpha]
+    }
+
+    fn from_oklab(c: Oklab) -> [f32; 4] {
+        [c.l, c.a, c.b, c.alpha]
+    }
+
+    Some(match (from, to) {
+        (ColorSpace::Srgb, ColorSpace::LinearRgb) => {
+            |v| from_linear(LinearRgb::from_color(rgb(v)))
+        }
+        (ColorSpace::Srgb, ColorSpace::Oklab) => {
+            |v| from_oklab(Oklab::from_color(rgb(v)))
+        }
+        (ColorSpace::LinearRgb, ColorSpace::Srgb) => {
+            |v| from_rgb(Rgb::from_linear(linear(v)))
+        }
+        (ColorSpace::LinearRgb, ColorSpace::Oklab) => {
+            |v| from_oklab(Oklab::from_color(linear(v)))
+        }
+        (ColorSpace::Oklab, ColorSpace::Srgb) => |v| from_rgb(Rgb::from_color(oklab(v))),
+        (ColorSpace::Oklab, ColorSpace::LinearRgb) => {
+            |v| from_linear(LinearRgb::from_color(oklab(v)))
+        }
+        (ColorSpace::D65Gray, ColorSpace::Srgb) => {
+            |[l, _, _, a]| from_rgb(Rgb::from_color(Luma::new(l, a)))
+        }
+        _ => return None,
+    })
+}
+
+/// A component of a CSS color function.
+enum CssComponent {
+    /// A plain number.
+    Number(f32),
+    /// A percentage.
+    Percent(f32),
+    /// An angle in degrees.
+    Angle(f32),
+    /// The `none` keyword, which stands for a missing component.
+    None,
+    /// Any other identifier, like the color space of the `color` function.
+    Ident(String),
+}
+
+impl CssComponent {
+    /// Parses a single component.
+    fn parse(text: &str) -> StrResult<Self> {
+        if text == "none" {
+            return Ok(Self::None);
+        }
+
+        let number = |digits: &str| {
+            digits
+                .parse::<f32>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| eco_format!("invalid color component `{text}`"))
+        };
+
+        if let Some(digits) = text.strip_suffix('%') {
+            return Ok(Self::Percent(number(digits)?));
+        }
+
+        for (unit, factor) in
+            [("deg", 1.0), ("grad", 0.9), ("rad", 180.0 / PI), ("turn", 360.0)]
+        {
+            if let Some(digits) = text.strip_suffix(unit) {
+                if digits.ends_with(|c: char| c.is_ascii_digit() || c == '.') {
+                    return Ok(Self::Angle(number(digits)? * factor));
+                }
+            }
+        }
+
+        if text.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return Ok(Self::Ident(text.into()));
+        }
+
+        Ok(Self::Number(number(text)?))
+    }
+
+    /// Resolves the component to a number, where `100%` corresponds to
+    /// `reference`.
+    fn number(&self, reference: f32) -> StrResult<f32> {
+        match self {
+            Self::Number(v) => Ok(*v),
+            Self::Percent(v) => Ok(v / 100.0 * reference),
+            Self::None => Ok(0.0),
+            Self::Angle(_) => bail!("expected number or percentage, found angle"),
+            Self::Ident(ident) => bail!("invalid color component `{ident}`"),
+        }
+    }
+
+    /// Resolves the component to a hue in degrees.
+    fn hue(&self) -> StrResult<f32> {
+        match self {
+            Self::Number(v) | Self::Angle(v) => Ok(*v),
+            Self::None => Ok(0.0),
+            Self::Percent(_) => bail!("expected number or angle, found percentage"),
+            Self::Ident(ident) => bail!("invalid color component `{ident}`"),
+        }
+    }
+}
+
+/// Splits the arguments of a CSS color function into its components and
+/// the optional alpha component.
+///
+/// Both the modern space-separated syntax with a slash before the alpha
+/// component and the legacy comma-separated syntax are supported.
+fn css_components(args: &str) -> StrResult<(Vec<CssComponent>, Option<CssComponent>)> {
+    if args.contains(',') {
+        let mut components = args
+            .split(',')
+            .map(|part| CssComponent::parse(part.trim()))
+            .collect::<StrResult<Vec<_>>>()?;
+        let alpha = (components.len() == 4).then(|| components.pop().unwrap());
+        return Ok((components, alpha));
+    }
+
+    let (components, alpha) = match args.split_once('/') {
+        Some((components, alpha)) => (components, Some(alpha.trim())),
+        None => (args, None),
+    };
+
+    let components = components
+        .split_whitespace()
+        .map(CssComponent::parse)
+        .collect::<StrResult<Vec<_>>>()?;
+    let alpha = alpha.map(CssComponent::parse).transpose()?;
+    Ok((components, alpha))
+}
+
+/// Defines the named colors of CSS.
+fn web() -> Module {
+    let mut scope = Scope::new();
+    for &(name, color) in CSS_COLORS {
+        scope.define(name, Color::from_u32(color));
+    }
+    Module::new("web", scope)
+}
+
+/// The named colors of CSS, expressed as u32s.
+const CSS_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ffff),
+    ("antiquewhite", 0xfaebd7ff),
+    ("aqua", 0x00ffffff),
+    ("aquamarine", 0x7fffd4ff),
+    ("azure", 0xf0ffffff),
+    ("beige", 0xf5f5dcff),
+    ("bisque", 0xffe4c4ff),
+    ("black", 0x000000ff),
+    ("blanchedalmond", 0xffebcdff),
+    ("blue", 0x0000ffff),
+    ("blueviolet", 0x8a2be2ff),
+    ("brown", 0xa52a2aff),
+    ("burlywood", 0xdeb887ff),
+    ("cadetblue", 0x5f9ea0ff),
+    ("chartreuse", 0x7fff00ff),
+    ("chocolate", 0xd2691eff),
+    ("coral", 0xff7f50ff),
+    ("cornflowerblue", 0x6495edff),
+    ("cornsilk", 0xfff8dcff),
+    ("crimson", 0xdc143cff),
+    ("cyan", 0x00ffffff),
+    ("darkblue", 0x00008bff),
+    ("darkcyan", 0x008b8bff),
+    ("darkgoldenrod", 0xb8860bff),
+    ("darkgray", 0xa9a9a9ff),
+    ("darkgreen", 0x006400ff),
+    ("darkgrey", 0xa9a9a9ff),
+    ("darkkhaki", 0xbdb76bff),
+    ("darkmagenta", 0x8b008bff),
+    ("darkolivegreen", 0x556b2fff),
+    ("darkorange", 0xff8c00ff),
+    ("darkorchid", 0x9932ccff),
+    ("darkred", 0x8b0000ff),
+    ("darksalmon", 0xe9967aff),
+    ("darkseagreen", 0x8fbc8fff),
+    ("darkslateblue", 0x483d8bff),
+    ("darkslategray", 0x2f4f4fff),
+    ("darkslategrey", 0x2f4f4fff),
+    ("darkturquoise", 0x00ced1ff),
+    ("darkviolet", 0x9400d3ff),
+    ("deeppink", 0xff1493ff),
+    ("deepskyblue", 0x00bfffff),
+    ("dimgray", 0x696969ff),
+    ("dimgrey", 0x696969ff),
+    ("dodgerblue", 0x1e90ffff),
+    ("firebrick", 0xb22222ff),
+    ("floralwhite", 0xfffaf0ff),
+    ("forestgreen", 0x228b22ff),
+    ("fuchsia", 0xff00ffff),
+    ("gainsboro", 0xdcdcdcff),
+    ("ghostwhite", 0xf8f8ffff),
+    ("gold", 0xffd700ff),
+    ("goldenrod", 0xdaa520ff),
+    ("gray", 0x808080ff),
+    ("green", 0x008000ff),
+    ("greenyellow", 0xadff2fff),
+    ("grey", 0x808080ff),
+    ("honeydew", 0xf0fff0ff),
+    ("hotpink", 0xff69b4ff),
+    ("indianred", 0xcd5c5cff),
+    ("indigo", 0x4b0082ff),
+    ("ivory", 0xfffff0ff),
+    ("khaki", 0xf0e68cff),
+    ("lavender", 0xe6e6faff),
+    ("lavenderblush", 0xfff0f5ff),
+    ("lawngreen", 0x7cfc00ff),
+    ("lemonchiffon", 0xfffacdff),
+    ("lightblue", 0xadd8e6ff),
+    ("lightcoral", 0xf08080ff),
+    ("lightcyan", 0xe0ffffff),
+    ("lightgoldenrodyellow", 0xfafad2ff),
+    ("lightgray", 0xd3d3d3ff),
+    ("lightgreen", 0x90ee90ff),
+    ("lightgrey", 0xd3d3d3ff),
+    ("lightpink", 0xffb6c1ff),
+    ("lightsalmon", 0xffa07aff),
+    ("lightseagreen", 0x20b2aaff),
+    ("lightskyblue", 0x87cefaff),
+    ("lightslategray", 0x778899ff),
+    ("lightslategrey", 0x778899ff),
+    ("lightsteelblue", 0xb0c4deff),
+    ("lightyellow", 0xffffe0ff),
+    ("lime", 0x00ff00ff),
+    ("limegreen", 0x32cd32ff),
+    ("linen", 0xfaf0e6ff),
+    ("magenta", 0xff00ffff),
+    ("maroon", 0x800000ff),
+    ("mediumaquamarine", 0x66cdaaff),
+    ("mediumblue", 0x0000cdff),
+    ("mediumorchid", 0xba55d3ff),
+    ("mediumpurple", 0x9370dbff),
+    ("mediumseagreen", 0x3cb371ff),
+    ("mediumslateblue", 0x7b68eeff),
+    ("mediumspringgreen", 0x00fa9aff),
+    ("mediumturquoise", 0x48d1ccff),
+    ("mediumvioletred", 0xc71585ff),
+    ("midnightblue", 0x191970ff),
+    ("mintcream", 0xf5fffaff),
+    ("mistyrose", 0xffe4e1ff),
+    ("moccasin", 0xffe4b5ff),
+    ("navajowhite", 0xffdeadff),
+    ("navy", 0x000080ff),
+    ("oldlace", 0xfdf5e6ff),
+    ("olive", 0x808000ff),
+    ("olivedrab", 0x6b8e23ff),
+    ("orange", 0xffa500ff),
+    ("orangered", 0xff4500ff),
+    ("orchid", 0xda70d6ff),
+    ("palegoldenrod", 0xeee8aaff),
+    ("palegreen", 0x98fb98ff),
+    ("paleturquoise", 0xafeeeeff),
+    ("palevioletred", 0xdb7093ff),
+    ("papayawhip", 0xffefd5ff),
+    ("peachpuff", 0xffdab9ff),
+    ("peru", 0xcd853fff),
+    ("pink", 0xffc0cbff),
+    ("plum", 0xdda0ddff),
+    ("powderblue", 0xb0e0e6ff),
+    ("purple", 0x800080ff),
+    ("rebeccapurple", 0x663399ff),
+    ("red", 0xff0000ff),
+    ("rosybrown", 0xbc8f8fff),
+    ("royalblue", 0x4169e1ff),
+    ("saddlebrown", 0x8b4513ff),
+    ("salmon", 0xfa8072ff),
+    ("sandybrown", 0xf4a460ff),
+    ("seagreen", 0x2e8b57ff),
+    ("seashell", 0xfff5eeff),
+    ("sienna", 0xa0522dff),
+    ("silver", 0xc0c0c0ff),
+    ("skyblue", 0x87ceebff),
+    ("slateblue", 0x6a5acdff),
+    ("slategray", 0x708090ff),
+    ("slategrey", 0x708090ff),
+    ("snow", 0xfffafaff),
+    ("springgreen", 0x00ff7fff),
+    ("steelblue", 0x4682b4ff),
+    ("tan", 0xd2b48cff),
+    ("teal", 0x008080ff),
+    ("thistle", 0xd8bfd8ff),
+    ("tomato", 0xff6347ff),
+    ("turquoise", 0x40e0d0ff),
+    ("violet", 0xee82eeff),
+    ("wheat", 0xf5deb3ff),
+    ("white", 0xffffffff),
+    ("whitesmoke", 0xf5f5f5ff),
+    ("yellow", 0xffff00ff),
+    ("yellowgreen", 0x9acd32ff),
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1998,6 +5946,113 @@ mod tests {
         test("111b", 0x11, 0x11, 0x11, 0xbb);
     }
 
+    #[test]
+    fn test_parse_css_colors() {
+        #[track_caller]
+        fn test(css: &str, hex: &str) {
+            assert_eq!(Color::from_css(css).unwrap().to_hex(), hex);
+        }
+
+        test("#336699", "#336699");
+        test("RebeccaPurple", "#663399");
+        test("transparent", "#00000000");
+        test("rgb(255 0 0 / 50%)", "#ff000080");
+        test("rgba(0, 128, 255, 0.5)", "#0080ff80");
+        test("rgb(100% 50% none)", "#ff8000");
+        test("hsl(120deg 100% 25%)", "#008000");
+        test("hsla(0.5turn, 100%, 50%, 1)", "#00ffff");
+        test("hwb(0 0% 0%)", "#ff0000");
+        test("color(srgb 1 0.5 0)", "#ff8000");
+        test("oklch(100% 0 0)", "#ffffff");
+    }
+
+    #[test]
+    fn test_parse_invalid_css_colors() {
+        #[track_caller]
+        fn test(css: &str, message: &str) {
+            assert_eq!(Color::from_css(css), Err(message.into()));
+        }
+
+        test("notacolor", "unknown color name `notacolor`");
+        test("rgb(1 2 3", "expected closing parenthesis");
+        test("rgb(1 2)", "expected 3 color components, found 2");
+        test("hsl(10% 20% 30%)", "expected number or angle, found percentage");
+        test("rgb(1deg 2 3)", "expected number or percentage, found angle");
+        test("color(rec2020 1 0 0)", "unsupported color space `rec2020`");
+        test("foo(1 2 3)", "unknown color function `foo`");
+    }
+
+    #[test]
+    fn test_icc_colors() {
+        #[track_caller]
+        fn icc(data: &'static [u8], components: [f32; 4], alpha: f32) -> Color {
+            let data = Bytes::from_static(data);
+            let profile = IccProfile::new(data, RenderingIntent::Perceptual).unwrap();
+            Color::Icc(Icc { profile, components, alpha })
+        }
+
+        let gray = icc(typst_assets::icc::S_GREY_V4, [0.5, 0.0, 0.0, 0.0], 1.0);
+        let rgb = icc(typst_assets::icc::S_RGB_V4, [0.2, 0.4, 0.6, 0.0], 0.5);
+        let cmyk = icc(typst_assets::icc::CMYK_TO_XYZ, [0.5, 0.64, 0.16, 0.17], 1.0);
+        assert_eq!(gray.to_hex(), "#808080");
+        assert_eq!(rgb.to_hex(), "#33669980");
+        assert_eq!(cmyk.to_hex(), Color::Cmyk(Cmyk::new(0.5, 0.64, 0.16, 0.17)).to_hex());
+
+        assert_eq!(gray.space(), ColorSpace::D65Gray);
+        assert_eq!(rgb.components(true).len(), 4);
+        assert_eq!(cmyk.components(true).len(), 4);
+        assert_eq!(cmyk.alpha(), None);
+        assert!(matches!(rgb.lighten(Ratio::new(0.5)), Color::Icc(_)));
+        assert_eq!(
+            gray.lighten(Ratio::new(0.5)),
+            icc(typst_assets::icc::S_GREY_V4, [0.75, 0.0, 0.0, 0.0], 1.0),
+        );
+        assert_ne!(gray, icc(typst_assets::icc::S_RGB_V4, [0.5, 0.0, 0.0, 0.0], 1.0));
+    }
+
+    #[test]
+    fn test_icc_rendering_intents() {
+        let data = Bytes::from_static(typst_assets::icc::S_RGB_V4);
+        let perceptual = IccProfile::new(data.clone(), RenderingIntent::Perceptual);
+        let relative = IccProfile::new(data, RenderingIntent::RelativeColorimetric);
+        let (perceptual, relative) = (perceptual.unwrap(), relative.unwrap());
+        assert_eq!(relative.intent(), RenderingIntent::RelativeColorimetric);
+        assert_eq!(perceptual.name(), relative.name());
+        assert_ne!(perceptual, relative);
+    }
+
+    #[test]
+    fn test_parse_invalid_icc_profiles() {
+        let data = Bytes::from_static(&[0; 128]);
+        assert_eq!(
+            IccProfile::new(data, RenderingIntent::Perceptual).unwrap_err(),
+            "ICC profile must be for a gray, RGB, or CMYK color space",
+        );
+        let data = Bytes::from_static(b"RGB ");
+        assert_eq!(
+            IccProfile::new(data, RenderingIntent::Perceptual).unwrap_err(),
+            "failed to parse ICC profile",
+        );
+    }
+
+    #[test]
+    fn test_ciede2000() {
+        // Reference pairs from Sharma et al., "The CIEDE2000 Color-Difference
+        // Formula: Implementation Notes, Supplementary Test Data, and
+        // Mathematical Observations".
+        #[track_caller]
+        fn test(a: [f64; 3], b: [f64; 3], expected: f64) {
+            assert!((ciede2000(a, b) - expected).abs() < 1e-4);
+            assert!((ciede2000(b, a) - expected).abs() < 1e-4);
+        }
+
+        test([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425);
+        test([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492);
+        test([50.0, 2.5, 0.0], [50.0, 0.0, -2.5], 4.3065);
+        test([60.2574, -34.0099, 36.2677], [60.4626, -34.1751, 39.4387], 1.2644);
+        test([2.0776, 0.0795, -1.135], [0.9033, -0.0636, -0.5514], 0.9082);
+    }
+
     #[test]
     fn test_parse_invalid_colors() {
         #[track_caller]
@@ -2011,4 +6066,64 @@ mod tests {
         test("hmmm", "color string contains non-hexadecimal letters");
         test("14B2AH", "color string contains non-hexadecimal letters");
     }
+
+    #[test]
+    fn test_premultiplied_alpha() {
+        assert_eq!(Color::premultiply_vec4([1.0, 0.5, 0.0, 0.5]), [0.5, 0.25, 0.0, 0.5]);
+        assert_eq!(
+            Color::unpremultiply_vec4([0.5, 0.25, 0.0, 0.5]),
+            [1.0, 0.5, 0.0, 0.5]
+        );
+        assert_eq!(Color::unpremultiply_vec4([0.5, 0.5, 0.5, 0.0]), [0.0; 4]);
+        assert_eq!(Color::unpremultiply_vec4([0.8, 0.0, 0.0, 0.4]), [1.0, 0.0, 0.0, 0.4]);
+        assert_eq!(
+            Color::premultiply_vec4([f32::NAN, 2.0, -1.0, 1.0]),
+            [0.0, 1.0, 0.0, 1.0]
+        );
+
+        let color = Color::from_u8(255, 128, 0, 128);
+        assert_eq!(color.to_premultiplied_u8(), [128, 64, 0, 128]);
+        assert_eq!(
+            Color::from_premultiplied_u8([128, 64, 0, 128]).to_vec4_u8(),
+            [255, 128, 0, 128]
+        );
+        assert_eq!(Color::from_premultiplied_u8([0, 0, 0, 0]).to_vec4_u8(), [0; 4]);
+    }
+
+    #[test]
+    fn test_convert_slice() {
+        let spaces = [
+            ColorSpace::Oklab,
+            ColorSpace::Oklch,
+            ColorSpace::Lab,
+            ColorSpace::Xyz(WhitePoint::D50),
+            ColorSpace::Srgb,
+            ColorSpace::DisplayP3,
+            ColorSpace::D65Gray,
+            ColorSpace::LinearRgb,
+            ColorSpace::Hsl,
+            ColorSpace::Cmyk,
+        ];
+        let colors = [
+            Color::from_u8(255, 65, 54, 255),
+            Color::from_u8(0, 116, 217, 128),
+            Color::from_u8(230, 230, 230, 0),
+            Color::BLACK,
+        ];
+
+        for from in spaces {
+            for to in spaces {
+                let mut vecs: Vec<[f32; 4]> =
+                    colors.iter().map(|c| c.to_space(from).to_vec4()).collect();
+                Color::convert_slice(&mut vecs, from, to);
+                for (color, vec) in colors.iter().zip(vecs) {
+                    let expected =
+                        Color::from_space_vec4(from, color.to_space(from).to_vec4())
+                            .to_space(to)
+                            .to_vec4();
+                    assert_eq!(vec, expected, "{from:?} -> {to:?}");
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,614 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::diag::{bail, At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, elem, select_where, Cast, Label, Packed, Repr, Smart, Str, StyleChain,
+};
+use crate::introspection::{AnchorElem, Locatable};
+use crate::layout::{
+    Abs, Alignment, FixedAlignment, Frame, FrameItem, LayoutSingle, Length, Point,
+    Position, Regions, Size,
+};
+use crate::syntax::Span;
+use crate::text::TextElem;
+use crate::util::Scalar;
+use crate::visualize::{direction, FixedStroke, Geometry, Marker, Path, Stroke};
+
+/// A connector between two labelled elements.
+///
+/// The connector looks up where the elements with the given labels ended up
+/// on the page and draws a line between them. Orthogonal and curved
+/// connectors are routed around the connected elements and any additional
+/// [obstacles]($connector.obstacles). This makes connectors a building block
+/// for flowcharts and other diagrams.
+///
+/// The connector itself has a size of zero and can be put anywhere on the same
+/// page as the elements it connects. To prevent it from creating an empty
+/// paragraph, you can [place] it.
+///
+/// # Example
+/// ```example
+/// #set page(width: 180pt, height: 100pt, margin: 5pt)
+/// #place(top + left)[#rect[Start] <start>]
+/// #place(bottom + right)[#rect[End] <end>]
+/// #place(center + horizon)[#rect[Wall] <wall>]
+///
+/// #connector(<start>, <end>, obstacles: (<wall>,))
+/// ```
+#[elem(Locatable, LayoutSingle)]
+pub struct ConnectorElem {
+    /// The label of the element the connector starts at.
+    #[required]
+    pub start: Label,
+
+    /// The label of the element the connector ends at.
+    #[required]
+    pub end: Label,
+
+    /// How to route the connector.
+    ///
+    /// ```example
+    /// #set page(width: 180pt, height: 80pt, margin: 5pt)
+    /// #for (i, route) in ("straight", "orthogonal", "curved").enumerate() {
+    ///   let (a, b) = (label("a" + str(i)), label("b" + str(i)))
+    ///   place(dx: i * 60pt)[#square(size: 12pt) #a]
+    ///   place(dx: i * 60pt + 30pt, dy: 50pt)[#square(size: 12pt) #b]
+    ///   place(connector(a, b, route: route))
+    /// }
+    /// ```
+    #[default(Routing::Orthogonal)]
+    pub route: Routing,
+
+    /// The point on the start element the connector is attached to.
+    ///
+    /// If set to `{auto}`, the connector is attached to the middle of the side
+    /// facing the end element. An alignment selects a point on the element's
+    /// bounding box. If only one component is given, the other one is
+    /// centered, such that `{top}` refers to the middle of the top side. A
+    /// string selects one of the element's named [anchors]($anchor).
+    ///
+    /// ```example
+    /// #set page(width: 180pt, height: 60pt, margin: 5pt)
+    /// #place(top + left)[#box(
+    ///   width: 40pt, height: 30pt, stroke: 1pt,
+    ///   anchors: (port: (100%, 25%)),
+    /// ) <a>]
+    /// #place(bottom + right)[#rect[B] <b>]
+    /// #place(connector(<a>, <b>, start-anchor: "port"))
+    /// ```
+    pub start_anchor: Smart<ConnectorAnchor>,
+
+    /// The point on the end element the connector is attached to. See
+    /// [`start-anchor`]($connector.start-anchor) for details.
+    pub end_anchor: Smart<ConnectorAnchor>,
+
+    /// Labels of additional elements that orthogonal and curved connectors
+    /// should avoid.
+    ///
+    /// The bounding boxes of these elements are treated as obstacles in
+    /// addition to the connected elements themselves. Elements that are on a
+    /// different page than the connector are ignored.
+    pub obstacles: Vec<Label>,
+
+    /// The minimum distance between the connector and obstacles.
+    #[resolve]
+    #[default(Abs::pt(8.0).into())]
+    pub margin: Length,
+
+    /// How to [stroke] the connector.
+    #[resolve]
+    #[fold]
+    pub stroke: Stroke,
+
+    /// The marker to place at the start of the connector. See
+    /// [`line.marker-start`]($line.marker-start) for the available markers.
+    pub marker_start: Option<Marker>,
+
+    /// The marker to place at the end of the connector.
+    #[default(Some(Marker::Arrow))]
+    pub marker_end: Option<Marker>,
+}
+
+impl LayoutSingle for Packed<ConnectorElem> {
+    #[typst_macros::time(name = "connector", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Frame> {
+        // The targets may not be known in early layout iterations, so errors
+        // are only reported if they persist.
+        Ok(engine.delayed(|engine| self.draw(engine, styles)))
+    }
+}
+
+impl Packed<ConnectorElem> {
+    /// Route the connector and draw it into a zero-sized frame.
+    fn draw(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Frame> {
+        let span = self.span();
+        let here = engine.introspector.position(self.location().unwrap());
+
+        // Find the bounding box of a labelled element relative to the
+        // connector.
+        let bounds =
+            |engine: &mut Engine, label: Label| -> SourceResult<Option<Bounds>> {
+                let elem = engine.introspector.query_label(label).at(span)?;
+                let location = elem.location().unwrap();
+                let Position { page, point } = engine.introspector.position(location);
+                if page != here.page {
+                    return Ok(None);
+                }
+                let min = point - here.point;
+                let size = engine.introspector.size(location);
+                Ok(Some(Bounds { min, max: min + size.to_point() }))
+            };
+
+        let Some(start) = bounds(engine, *self.start())? else {
+            bail!(span, "connected elements must be on the same page as the connector");
+        };
+        let Some(end) = bounds(engine, *self.end())? else {
+            bail!(span, "connected elements must be on the same page as the connector");
+        };
+
+        let mut obstacles = vec![];
+        for label in self.obstacles(styles) {
+            obstacles.extend(bounds(engine, label)?);
+        }
+
+        let route = self.route(styles);
+        let dir = TextElem::dir_in(styles);
+        let fix = |anchor: Alignment| {
+            let fixed = anchor.fix(dir);
+            Anchor {
+                x: anchor.x().map_or(FixedAlignment::Center, |_| fixed.x),
+                y: anchor.y().map_or(FixedAlignment::Center, |_| fixed.y),
+            }
+        };
+
+        let attach =
+            |bounds: Bounds, anchor: Smart<Alignment>, other: Bounds| match anchor {
+                Smart::Custom(anchor) => bounds.anchor(fix(anchor)),
+                Smart::Auto if route == Routing::Straight => {
+                    (bounds.clip(other.center()), None)
+                }
+                Smart::Auto => bounds.anchor(bounds.facing(other.center())),
+            };
+
+        // Find a named anchor of a labelled element relative to the
+        // connector.
+        let named =
+            |engine: &mut Engine, label: Label, name: Str| -> SourceResult<Point> {
+                let selector =
+                    select_where!(AnchorElem, Of => Some(label), Name => name.clone());
+                let Some(anchor) =
+                    engine.introspector.query(&selector).into_iter().next()
+                else {
+                    bail!(
+                        span,
+                        "element {} has no anchor named {}",
+                        label.repr(),
+                        name.repr()
+                    );
+                };
+                let Position { page, point } =
+                    engine.introspector.position(anchor.location().unwrap());
+                if page != here.page {
+                    bail!(
+                        span,
+                        "connected elements must be on the same page as the connector"
+                    );
+                }
+                Ok(point - here.point)
+            };
+
+        let mut resolve = |label: Label, bounds: Bounds, anchor, other: Bounds| {
+            SourceResult::Ok(match anchor {
+                Smart::Custom(ConnectorAnchor::Named(name)) => {
+                    (named(engine, label, name)?, None)
+                }
+                Smart::Custom(ConnectorAnchor::Align(align)) => {
+                    attach(bounds, Smart::Custom(align), other)
+                }
+                Smart::Auto => attach(bounds, Smart::Auto, other),
+            })
+        };
+
+        let (a, da) = resolve(*self.start(), start, self.start_anchor(styles), end)?;
+        let (b, db) = resolve(*self.end(), end, self.end_anchor(styles), start)?;
+
+        // Connected elements are only obstacles if the connector attaches to
+        // their boundary. Otherwise, it could never leave them.
+        obstacles.extend(da.map(|_| start));
+        obstacles.extend(db.map(|_| end));
+
+        let margin = self.margin(styles);
+        let points = match route {
+            Routing::Straight => vec![a, b],
+            Routing::Orthogonal | Routing::Curved => {
+                route_orthogonal(a, da, b, db, &obstacles, margin)
+            }
+        };
+
+        let stroke = self.stroke(styles).unwrap_or_default();
+        let mut frame = Frame::soft(Size::zero());
+        draw_connector(
+            &mut frame,
+            &points,
+            route == Routing::Curved,
+            &stroke,
+            self.marker_start(styles),
+            self.marker_end(styles),
+            span,
+        );
+
+        Ok(frame)
+    }
+}
+
+/// A point on a connected element.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum ConnectorAnchor {
+    /// A point on the element's bounding box.
+    Align(Alignment),
+    /// One of the element's named anchors.
+    Named(Str),
+}
+
+cast! {
+    ConnectorAnchor,
+    self => match self {
+        Self::Align(v) => v.into_value(),
+        Self::Named(v) => v.into_value(),
+    },
+    v: Alignment => Self::Align(v),
+    v: Str => Self::Named(v),
+}
+
+/// How a connector is routed between its endpoints.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Routing {
+    /// A straight line that ignores obstacles.
+    Straight,
+    /// Horizontal and vertical segments that go around obstacles.
+    Orthogonal,
+    /// Like `orthogonal`, but with the corners smoothed into curves.
+    Curved,
+}
+
+/// An axis-aligned bounding box.
+#[derive(Debug, Copy, Clone)]
+struct Bounds {
+    min: Point,
+    max: Point,
+}
+
+/// A point on a bounding box, in global coordinates.
+#[derive(Debug, Copy, Clone)]
+struct Anchor {
+    x: FixedAlignment,
+    y: FixedAlignment,
+}
+
+/// A unit direction along one of the axes.
+type Heading = (i8, i8);
+
+impl Bounds {
+    /// The center of the box.
+    fn center(self) -> Point {
+        (self.min + self.max) / 2.0
+    }
+
+    /// Grow the box by `amount` in all directions.
+    fn inflate(self, amount: Abs) -> Self {
+        let delta = Point::splat(amount);
+        Self { min: self.min - delta, max: self.max + delta }
+    }
+
+    /// Whether the point lies strictly inside the box.
+    fn contains(self, p: Point) -> bool {
+        let eps = Abs::raw(1e-6);
+        p.x > self.min.x + eps
+            && p.x < self.max.x - eps
+            && p.y > self.min.y + eps
+            && p.y < self.max.y - eps
+    }
+
+    /// Whether the axis-aligned segment between `p` and `q` passes through
+    /// the interior of the box.
+    fn blocks(self, p: Point, q: Point) -> bool {
+        self.contains((p + q) / 2.0)
+            || self.contains(p)
+            || self.contains(q)
+            || (p.x.approx_eq(q.x)
+                && self.contains(Point::new(p.x, self.center().y))
+                && p.y.min(q.y) <= self.min.y
+                && p.y.max(q.y) >= self.max.y)
+            || (p.y.approx_eq(q.y)
+                && self.contains(Point::new(self.center().x, p.y))
+                && p.x.min(q.x) <= self.min.x
+                && p.x.max(q.x) >= self.max.x)
+    }
+
+    /// The side of the box that faces the given point.
+    fn facing(self, target: Point) -> Anchor {
+        let center = self.center();
+        let delta = target - center;
+        let size = self.max - self.min;
+        let side = |d: Abs| {
+            if d < Abs::zero() {
+                FixedAlignment::Start
+            } else {
+                FixedAlignment::End
+            }
+        };
+
+        // Compare the offsets relative to the box's aspect ratio, such that
+        // the side the line between the centers passes through is chosen.
+        if (delta.x / size.x.max(Abs::pt(1.0))).abs()
+            >= (delta.y / size.y.max(Abs::pt(1.0))).abs()
+        {
+            Anchor { x: side(delta.x), y: FixedAlignment::Center }
+        } else {
+            Anchor { x: FixedAlignment::Center, y: side(delta.y) }
+        }
+    }
+
+    /// The position of an anchor and, if it lies on a side of the box, the
+    /// direction pointing away from that side.
+    fn anchor(self, anchor: Anchor) -> (Point, Option<Heading>) {
+        let size = self.max - self.min;
+        let point =
+            self.min + Point::new(anchor.x.position(size.x), anchor.y.position(size.y));
+        let heading = |align: FixedAlignment| match align {
+            FixedAlignment::Start => -1,
+            FixedAlignment::Center => 0,
+            FixedAlignment::End => 1,
+        };
+        let heading = match (heading(anchor.x), heading(anchor.y)) {
+            (0, 0) => None,
+            (x, 0) => Some((x, 0)),
+            (_, y) if anchor.x == FixedAlignment::Center => Some((0, y)),
+            (x, _) => Some((x, 0)),
+        };
+        (point, heading)
+    }
+
+    /// The point where the line from the center to `target` leaves the box.
+    fn clip(self, target: Point) -> Point {
+        let center = self.center();
+        let delta = target - center;
+        let half = (self.max - self.min) / 2.0;
+        let mut t: f64 = 1.0;
+        if !delta.x.approx_eq(Abs::zero()) {
+            t = t.min((half.x / delta.x).abs());
+        }
+        if !delta.y.approx_eq(Abs::zero()) {
+            t = t.min((half.y / delta.y).abs());
+        }
+        center + delta * t
+    }
+}
+
+/// Find a short orthogonal route with few bends from `a` to `b` that avoids
+/// the obstacles.
+///
+/// The route leaves `a` and enters `b` perpendicular to the sides they lie on
+/// (if any). It is searched for on a grid spanned by the obstacles' edges,
+/// expanded by the margin.
+fn route_orthogonal(
+    a: Point,
+    da: Option<Heading>,
+    b: Point,
+    db: Option<Heading>,
+    obstacles: &[Bounds],
+    margin: Abs,
+) -> Vec<Point> {
+    let step = |p: Point, d: Option<Heading>| match d {
+        Some((x, y)) => p + Point::new(margin * f64::from(x), margin * f64::from(y)),
+        None => p,
+    };
+
+    let (a1, b1) = (step(a, da), step(b, db));
+    let obstacles: Vec<_> = obstacles.iter().map(|o| o.inflate(margin)).collect();
+
+    // Collect the grid lines. The midpoints between adjacent lines let the
+    // route pass centered through gaps between obstacles.
+    let lines = |f: fn(Point) -> Abs| {
+        let mut lines = vec![f(a1), f(b1)];
+        for o in &obstacles {
+            lines.extend([f(o.min), f(o.max)]);
+        }
+        lines.sort();
+        lines.dedup_by(|x, y| x.approx_eq(*y));
+        let mids: Vec<_> = lines.windows(2).map(|w| (w[0] + w[1]) / 2.0).collect();
+        lines.extend(mids);
+        lines.sort();
+        lines
+    };
+
+    let xs = lines(|p| p.x);
+    let ys = lines(|p| p.y);
+    let index = |lines: &[Abs], v: Abs| lines.iter().position(|l| l.approx_eq(v));
+    let (Some(ax), Some(ay), Some(bx), Some(by)) =
+        (index(&xs, a1.x), index(&ys, a1.y), index(&xs, b1.x), index(&ys, b1.y))
+    else {
+        return vec![a, a1, b1, b];
+    };
+
+    let point = |(i, j): (usize, usize)| Point::new(xs[i], ys[j]);
+    let free = |p: Point| !obstacles.iter().any(|o| o.contains(p));
+    let headings: [Heading; 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+    // Bends are penalized so that routes prefer fewer, longer segments.
+    let bend = 2.0 * margin.to_raw() + 10.0;
+    let node = |(i, j): (usize, usize), h: usize| (j * xs.len() + i) * 4 + h;
+
+    let mut dist = vec![f64::INFINITY; xs.len() * ys.len() * 4];
+    let mut prev = vec![usize::MAX; dist.len()];
+    let mut queue = BinaryHeap::new();
+
+    // The route may start in any direction, but turning away from the
+    // starting direction costs a bend.
+    for (h, &heading) in headings.iter().enumerate() {
+        let cost = match da {
+            Some(d) if d == heading => 0.0,
+            Some(d) if d == (-heading.0, -heading.1) => continue,
+            Some(_) => bend,
+            None => 0.0,
+        };
+        let n = node((ax, ay), h);
+        dist[n] = cost;
+        queue.push(Reverse((Scalar::new(cost), n)));
+    }
+
+    let mut best = None;
+    while let Some(Reverse((cost, n))) = queue.pop() {
+        let cost = cost.get();
+        if cost > dist[n] {
+            continue;
+        }
+
+        let h = n % 4;
+        let (i, j) = (n / 4 % xs.len(), n / 4 / xs.len());
+        if (i, j) == (bx, by) {
+            // Entering the end point against its direction costs a bend.
+            let arrival = match db {
+                Some(d) if headings[h] == (-d.0, -d.1) => 0.0,
+                Some(d) if headings[h] == d => bend * 2.0,
+                Some(_) => bend,
+                None => 0.0,
+            };
+            if best.map_or(true, |(c, _)| cost + arrival < c) {
+                best = Some((cost + arrival, n));
+            }
+        }
+
+        for (k, &(dx, dy)) in headings.iter().enumerate() {
+            let Some(ni) = i.checked_add_signed(isize::from(dx)) else { continue };
+            let Some(nj) = j.checked_add_signed(isize::from(dy)) else { continue };
+            if ni >= xs.len()
+                || nj >= ys.len()
+                || (dx, dy) == (-headings[h].0, -headings[h].1)
+            {
+                continue;
+            }
+
+            let (p, q) = (point((i, j)), point((ni, nj)));
+            if !free(q) || obstacles.iter().any(|o| o.blocks(p, q)) {
+                continue;
+            }
+
+            let length = (q - p).hypot().to_raw();
+            let next = cost + length + if k == h { 0.0 } else { bend };
+            let m = node((ni, nj), k);
+            if next < dist[m] {
+                dist[m] = next;
+                prev[m] = n;
+                queue.push(Reverse((Scalar::new(next), m)));
+            }
+        }
+    }
+
+    let Some((_, mut n)) = best else {
+        // There is no way around the obstacles, so we take the direct route.
+        return vec![a, a1, b1, b];
+    };
+
+    let mut points = vec![b];
+    loop {
+        points.push(point((n / 4 % xs.len(), n / 4 / xs.len())));
+        if prev[n] == usize::MAX {
+            break;
+        }
+        n = prev[n];
+    }
+    points.push(a);
+    points.reverse();
+    simplify(points)
+}
+
+/// Remove duplicate points and points in the middle of straight segments.
+fn simplify(points: Vec<Point>) -> Vec<Point> {
+    let mut out: Vec<Point> = Vec::with_capacity(points.len());
+    for p in points {
+        if out.last().is_some_and(|&q| (p - q).hypot().approx_eq(Abs::zero())) {
+            continue;
+        }
+        if let [.., u, v] = out.as_slice() {
+            let collinear = (u.x.approx_eq(v.x) && v.x.approx_eq(p.x))
+                || (u.y.approx_eq(v.y) && v.y.approx_eq(p.y));
+            if collinear {
+                out.pop();
+            }
+        }
+        out.push(p);
+    }
+    out
+}
+
+/// Draw a connector along the given points, optionally smoothing its corners.
+fn draw_connector(
+    frame: &mut Frame,
+    points: &[Point],
+    smooth: bool,
+    stroke: &FixedStroke,
+    marker_start: Option<Marker>,
+    marker_end: Option<Marker>,
+    span: Span,
+) {
+    let [first, .., last] = points else { return };
+    let (second, before_last) = (points[1], points[points.len() - 2]);
+    let start_angle = direction(*first - second);
+    let end_angle = direction(*last - before_last);
+
+    // Shorten the ends so that the line ends within the markers.
+    let retract = |p: Point, q: Point, marker: Option<Marker>| {
+        let amount = marker.map_or(Abs::zero(), |m| m.retraction(stroke.thickness));
+        let length = (q - p).hypot();
+        if length.approx_eq(Abs::zero()) {
+            return p;
+        }
+        p + (q - p) * (amount / length).min(0.5)
+    };
+
+    let mut points = points.to_vec();
+    let n = points.len();
+    points[0] = retract(*first, second, marker_start);
+    points[n - 1] = retract(*last, before_last, marker_end);
+
+    let mut path = Path::new();
+    path.move_to(points[0]);
+    if smooth && n > 2 {
+        // Curve around each corner from the middle of the previous segment to
+        // the middle of the next one.
+        for i in 1..n - 1 {
+            let corner = points[i];
+            let from = if i == 1 { points[0] } else { (points[i - 1] + corner) / 2.0 };
+            let to =
+                if i == n - 2 { points[n - 1] } else { (corner + points[i + 1]) / 2.0 };
+            path.cubic_to(
+                from + (corner - from) * (2.0 / 3.0),
+                to + (corner - to) * (2.0 / 3.0),
+                to,
+            );
+        }
+    } else {
+        for &p in &points[1..] {
+            path.line_to(p);
+        }
+    }
+
+    let shape = Geometry::Path(path).stroked(stroke.clone());
+    frame.push(Point::zero(), FrameItem::Shape(shape, span));
+
+    let markers = [(marker_start, *first, start_angle), (marker_end, *last, end_angle)];
+    for (marker, pos, angle) in markers {
+        if let Some(marker) = marker {
+            let shape = marker.shape(stroke, pos, angle);
+            frame.push(Point::zero(), FrameItem::Shape(shape, span));
+        }
+    }
+}
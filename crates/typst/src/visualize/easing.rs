@@ -0,0 +1,185 @@
+use std::sync::Arc;
+
+use comemo::Tracked;
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{array, cast, Array, Context, Func, Str, Value};
+use crate::syntax::Span;
+use crate::util::Scalar;
+
+/// How the progress between two colors is warped when interpolating them.
+///
+/// An easing maps the linear progress between two colors, ranging from `0` to
+/// `1`, to the weight of the second color. It can be given as:
+/// - one of the CSS keywords `{"linear"}`, `{"ease"}`, `{"ease-in"}`,
+///   `{"ease-out"}`, and `{"ease-in-out"}`,
+/// - an array `{(x1, y1, x2, y2)}` with the two control points of a CSS-style
+///   cubic Bézier curve, or
+/// - a function that maps the progress (a float) to the weight.
+///
+/// The resulting weight is clamped between `0` and `1`.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum Easing {
+    /// Progress at a constant rate.
+    Linear,
+    /// A cubic Bézier curve from `(0, 0)` to `(1, 1)` with the given two
+    /// control points.
+    CubicBezier([Scalar; 4]),
+    /// A user-defined function and its values at evenly spaced positions, as
+    /// computed by [`Easing::resolve`].
+    Func(Func, Arc<Vec<Scalar>>),
+}
+
+// Functions are compared by identity, which is reflexive.
+impl Eq for Easing {}
+
+impl Easing {
+    /// The number of intervals at which user-defined functions are sampled.
+    const SAMPLES: usize = 256;
+
+    /// Whether the easing leaves the progress unchanged.
+    pub fn is_linear(&self) -> bool {
+        matches!(self, Self::Linear)
+    }
+
+    /// Samples a user-defined function, so that the easing can later be
+    /// applied without an engine.
+    pub fn resolve(
+        self,
+        engine: &mut Engine,
+        context: Tracked<Context>,
+        span: Span,
+    ) -> SourceResult<Self> {
+        let Self::Func(func, _) = self else { return Ok(self) };
+        let samples = (0..=Self::SAMPLES)
+            .map(|i| {
+                let t = i as f64 / Self::SAMPLES as f64;
+                call(&func, engine, context, span, t).map(Scalar::new)
+            })
+            .collect::<SourceResult<_>>()?;
+        Ok(Self::Func(func, Arc::new(samples)))
+    }
+
+    /// Applies the easing to the progress `t`, calling a user-defined function
+    /// directly instead of using its samples.
+    pub fn apply_with(
+        &self,
+        engine: &mut Engine,
+        context: Tracked<Context>,
+        span: Span,
+        t: f64,
+    ) -> SourceResult<f64> {
+        match self {
+            Self::Func(func, _) => {
+                let t = t.clamp(0.0, 1.0);
+                Ok(call(func, engine, context, span, t)?.clamp(0.0, 1.0))
+            }
+            _ => Ok(self.apply(t)),
+        }
+    }
+
+    /// Applies the easing to the progress `t`.
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        let eased = match self {
+            Self::Linear => t,
+            Self::CubicBezier(points) => cubic_bezier(points.map(Scalar::get), t),
+            Self::Func(_, samples) if samples.len() >= 2 => {
+                let pos = t * (samples.len() - 1) as f64;
+                let i = (pos.floor() as usize).min(samples.len() - 2);
+                let frac = pos - i as f64;
+                samples[i].get() * (1.0 - frac) + samples[i + 1].get() * frac
+            }
+            Self::Func(..) => t,
+        };
+        eased.clamp(0.0, 1.0)
+    }
+
+    /// Creates a cubic Bézier easing from its control points.
+    fn cubic(points: [f64; 4]) -> Self {
+        Self::CubicBezier(points.map(Scalar::new))
+    }
+}
+
+/// The CSS easing keywords and their control points.
+const KEYWORDS: [(&str, [f64; 4]); 4] = [
+    ("ease", [0.25, 0.1, 0.25, 1.0]),
+    ("ease-in", [0.42, 0.0, 1.0, 1.0]),
+    ("ease-out", [0.0, 0.0, 0.58, 1.0]),
+    ("ease-in-out", [0.42, 0.0, 0.58, 1.0]),
+];
+
+cast! {
+    Easing,
+    self => match self {
+        Self::Linear => "linear".into_value(),
+        Self::CubicBezier(points) => {
+            let points = points.map(Scalar::get);
+            match KEYWORDS.iter().find(|(_, other)| *other == points) {
+                Some((name, _)) => name.into_value(),
+                None => array![points[0], points[1], points[2], points[3]].into_value(),
+            }
+        }
+        Self::Func(func, _) => func.into_value(),
+    },
+    v: Str => match v.as_str() {
+        "linear" => Self::Linear,
+        name => match KEYWORDS.iter().find(|(other, _)| *other == name) {
+            Some(&(_, points)) => Self::cubic(points),
+            None => bail!(
+                "expected \"linear\", \"ease\", \"ease-in\", \"ease-out\", or \"ease-in-out\""
+            ),
+        },
+    },
+    v: Array => {
+        let points = v
+            .into_iter()
+            .map(Value::cast::<f64>)
+            .collect::<StrResult<Vec<_>>>()?;
+        let Ok(points) = <[f64; 4]>::try_from(points) else {
+            bail!("cubic Bézier easing must have four components");
+        };
+        if !(0.0..=1.0).contains(&points[0]) || !(0.0..=1.0).contains(&points[2]) {
+            bail!("x-coordinates of control points must be between 0 and 1");
+        }
+        Self::cubic(points)
+    },
+    v: Func => Self::Func(v, Arc::new(vec![])),
+}
+
+/// Calls a user-defined easing function.
+fn call(
+    func: &Func,
+    engine: &mut Engine,
+    context: Tracked<Context>,
+    span: Span,
+    t: f64,
+) -> SourceResult<f64> {
+    func.call(engine, context, [t])?.cast::<f64>().at(span)
+}
+
+/// Evaluates a CSS-style cubic Bézier curve, given its two control points, at
+/// the x-coordinate `t`.
+fn cubic_bezier([x1, y1, x2, y2]: [f64; 4], t: f64) -> f64 {
+    // One coordinate of the curve, which goes from 0 to 1 with the given
+    // control values.
+    let curve = |a: f64, b: f64, s: f64| {
+        let r = 1.0 - s;
+        3.0 * a * s * r * r + 3.0 * b * s * s * r + s * s * s
+    };
+
+    // The x-coordinate is monotonic since the control points' x-coordinates
+    // are between 0 and 1, so bisection finds the curve parameter.
+    let (mut lo, mut hi) = (0.0, 1.0);
+    for _ in 0..32 {
+        let mid = (lo + hi) / 2.0;
+        if curve(x1, x2, mid) < t {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    curve(y1, y2, (lo + hi) / 2.0)
+}
@@ -0,0 +1,604 @@
+//! Maps of geographic data.
+
+use comemo::Track;
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    array, cast, elem, func, Array, Cast, CastInfo, Context, Dict, FromValue, Func,
+    IntoValue, Module, Packed, Reflect, Resolve, Scope, Smart, Str, StyleChain, Value,
+};
+use crate::layout::{
+    Abs, Frame, FrameItem, LayoutSingle, Length, Point, Regions, Rel, Size,
+};
+use crate::util::{Numeric, Scalar};
+use crate::visualize::{
+    ellipse, Color, FixedStroke, Geometry, Paint, Path, Shape, Stroke,
+};
+
+/// A module with definitions for geographic maps.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define_elem::<MapElem>();
+    scope.define_func::<project>();
+    Module::new("geo", scope)
+}
+
+/// A map of geographic features.
+///
+/// The features are projected onto the plane with the chosen
+/// [projection]($geo.map.projection) and scaled to fit the map's size. Each
+/// feature can be styled individually by passing a function as its `fill` or
+/// `stroke`, which makes it easy to create choropleth maps.
+///
+/// Polygons are filled and stroked, lines are only stroked, and points are
+/// drawn as small dots.
+///
+/// # Example
+/// ```example
+/// #let features = geojson.decode(`{
+///   "type": "FeatureCollection",
+///   "features": [
+///     {
+///       "type": "Feature",
+///       "properties": { "name": "West", "density": 40 },
+///       "geometry": {
+///         "type": "Polygon",
+///         "coordinates": [[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]]]
+///       }
+///     },
+///     {
+///       "type": "Feature",
+///       "properties": { "name": "East", "density": 90 },
+///       "geometry": {
+///         "type": "Polygon",
+///         "coordinates": [[[10, 0], [20, 0], [20, 10], [10, 10], [10, 0]]]
+///       }
+///     }
+///   ]
+/// }`.text)
+///
+/// #geo.map(
+///   features,
+///   width: 4cm,
+///   fill: f => color.mix(
+///     (blue, f.properties.density),
+///     (white, 100 - f.properties.density),
+///   ),
+/// )
+/// ```
+#[elem(LayoutSingle)]
+pub struct MapElem {
+    /// The features to draw.
+    ///
+    /// Can be an array of features, as returned by [`geojson`]($geojson), or
+    /// a GeoJSON feature collection, feature, or geometry given as a
+    /// dictionary.
+    #[required]
+    pub features: GeoFeatures,
+
+    /// How to project longitudes and latitudes onto the plane.
+    pub projection: Projection,
+
+    /// The width of the map.
+    #[resolve]
+    #[default(Rel::one())]
+    pub width: Rel<Length>,
+
+    /// The height of the map.
+    ///
+    /// If set to `{auto}`, the height is chosen such that the projected
+    /// features fit the width without distortion. Otherwise, the features are
+    /// scaled uniformly to fit into both the width and height and centered.
+    #[resolve]
+    pub height: Smart<Rel<Length>>,
+
+    /// How to fill polygons and points.
+    ///
+    /// Can be a paint, `{none}`, or a function that receives a feature's
+    /// dictionary with its `id`, `geometry`, and `properties` and returns a
+    /// paint or `{none}`.
+    #[default(FeatureStyle::Value(Some(Color::SILVER.into())))]
+    pub fill: FeatureStyle<Option<Paint>>,
+
+    /// How to stroke the outlines of polygons and points and the lines.
+    ///
+    /// Like the fill, this can also be a function of a feature.
+    #[default(FeatureStyle::Value(Some(Stroke::from_pair(
+        Color::BLACK,
+        Abs::pt(0.5).into()
+    ))))]
+    pub stroke: FeatureStyle<Option<Stroke>>,
+
+    /// The radius of the dots drawn for points.
+    #[resolve]
+    #[default(Abs::pt(2.0).into())]
+    pub radius: Length,
+}
+
+impl LayoutSingle for Packed<MapElem> {
+    #[typst_macros::time(name = "geo.map", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Frame> {
+        let span = self.span();
+        let projection = self.projection(styles);
+        let features = &self.features().0;
+
+        // Determine the extent of the projected features.
+        let (mut min, mut max) = ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]);
+        for geometry in features.iter().filter_map(|f| f.geometry.as_ref()) {
+            geometry.visit(&mut |position| {
+                let [x, y] = projection.project(position);
+                min = [min[0].min(x), min[1].min(y)];
+                max = [max[0].max(x), max[1].max(y)];
+            });
+        }
+        let extent = if min[0] <= max[0] {
+            [max[0] - min[0], max[1] - min[1]]
+        } else {
+            min = [0.0; 2];
+            max = [0.0; 2];
+            [0.0; 2]
+        };
+
+        let width = self.width(styles).relative_to(regions.base().x);
+        let height = match self.height(styles) {
+            Smart::Auto if extent[0] > 0.0 => width * (extent[1] / extent[0]),
+            Smart::Auto => Abs::zero(),
+            Smart::Custom(height) => height.relative_to(regions.base().y),
+        };
+        let size = Size::new(width, height);
+        if !size.is_finite() {
+            bail!(span, "cannot create map with infinite size");
+        }
+
+        // Fit the features into the map uniformly and center them.
+        let fit = |length: Abs, extent: f64| {
+            if extent > 0.0 {
+                length / extent
+            } else {
+                Abs::inf()
+            }
+        };
+        let mut scale = fit(size.x, extent[0]).min(fit(size.y, extent[1]));
+        if !scale.is_finite() {
+            scale = Abs::zero();
+        }
+        let offset = Point::new(
+            (size.x - scale * extent[0]) / 2.0,
+            (size.y - scale * extent[1]) / 2.0,
+        );
+        let point = |position: [f64; 2]| {
+            let [x, y] = projection.project(position);
+            offset + Point::new(scale * (x - min[0]), scale * (max[1] - y))
+        };
+
+        let fill = self.fill(styles);
+        let stroke = self.stroke(styles);
+        let radius = self.radius(styles);
+        let mut frame = Frame::soft(size);
+        for feature in features {
+            let Some(geometry) = &feature.geometry else { continue };
+            let fill = fill.resolve(engine, styles, &feature.dict)?;
+            let stroke = stroke
+                .resolve(engine, styles, &feature.dict)?
+                .map(|stroke| stroke.resolve(styles).unwrap_or_default());
+            let mut shapes = vec![];
+            geometry.draw(&point, radius, &fill, &stroke, &mut shapes);
+            for (pos, shape) in shapes {
+                frame.push(pos, FrameItem::Shape(shape, span));
+            }
+        }
+
+        Ok(frame)
+    }
+}
+
+/// Projects geographic coordinates onto the plane.
+///
+/// Takes a position given as an array of its longitude and latitude in
+/// degrees and returns the projected `(x, y)` coordinates. The `x` coordinate
+/// grows towards the east and the `y` coordinate towards the north. One unit
+/// corresponds to the earth's radius at the equator.
+///
+/// Instead of a single position, a GeoJSON geometry can also be given as a
+/// dictionary, in which case all of its positions are projected.
+///
+/// ```example
+/// #let (x, y) = geo.project((13.4, 52.5))
+/// Berlin is at #calc.round(x, digits: 3) and #calc.round(y, digits: 3).
+/// ```
+#[func(title = "Project Coordinates")]
+pub fn project(
+    /// The position or geometry to project.
+    value: Value,
+    /// The projection to use. See the [map's
+    /// documentation]($geo.map.projection) for details.
+    #[named]
+    #[default]
+    projection: Projection,
+) -> StrResult<Value> {
+    let single = |value: Value| -> StrResult<Value> {
+        let [x, y] = projection.project(position(value)?);
+        Ok(array![x, y].into_value())
+    };
+
+    let Value::Dict(mut dict) = value else { return single(value) };
+    GeoGeometry::from_dict(&dict)?;
+    if let Ok(Value::Array(geometries)) = dict.get("geometries") {
+        let projected = geometries
+            .iter()
+            .map(|geometry| project(geometry.clone(), projection))
+            .collect::<StrResult<Array>>()?;
+        dict.insert("geometries".into(), projected.into_value());
+    } else {
+        let coordinates = dict.get("coordinates")?.clone();
+        dict.insert("coordinates".into(), map_positions(coordinates, &single)?);
+    }
+    Ok(Value::Dict(dict))
+}
+
+/// A projection from the sphere onto the plane.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Projection {
+    /// The Mercator projection, which preserves angles and shapes but
+    /// enlarges areas far from the equator. Latitudes beyond about 85° are
+    /// clamped, as the poles lie at infinity.
+    #[default]
+    Mercator,
+    /// Lambert's cylindrical equal-area projection, which preserves areas
+    /// and is thus best suited for choropleth maps.
+    EqualArea,
+    /// The equirectangular projection, which maps longitudes and latitudes
+    /// linearly.
+    Equirectangular,
+}
+
+impl Projection {
+    /// Project a position given as longitude and latitude in degrees.
+    pub fn project(self, [lon, lat]: [f64; 2]) -> [f64; 2] {
+        let x = lon.to_radians();
+        let y = match self {
+            Self::Mercator => {
+                let lat = lat.clamp(-MAX_MERCATOR_LATITUDE, MAX_MERCATOR_LATITUDE);
+                lat.to_radians().tan().asinh()
+            }
+            Self::EqualArea => lat.to_radians().sin(),
+            Self::Equirectangular => lat.to_radians(),
+        };
+        [x, y]
+    }
+}
+
+/// The latitude beyond which the Mercator projection is clamped, chosen such
+/// that the projected world is a square.
+const MAX_MERCATOR_LATITUDE: f64 = 85.05112878;
+
+/// A style of the features of a map, which is either fixed or computed for
+/// each feature.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum FeatureStyle<T> {
+    /// A bare value, the same for all features.
+    Value(T),
+    /// A closure mapping from a feature to a value.
+    Func(Func),
+}
+
+impl<T: Clone + FromValue> FeatureStyle<T> {
+    /// Resolve the value for the given feature.
+    fn resolve(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        feature: &Dict,
+    ) -> SourceResult<T> {
+        Ok(match self {
+            Self::Value(value) => value.clone(),
+            Self::Func(func) => func
+                .call(
+                    engine,
+                    Context::new(None, Some(styles)).track(),
+                    [feature.clone()],
+                )?
+                .cast()
+                .at(func.span())?,
+        })
+    }
+}
+
+impl<T: Reflect> Reflect for FeatureStyle<T> {
+    fn input() -> CastInfo {
+        T::input() + Func::input()
+    }
+
+    fn output() -> CastInfo {
+        T::output() + Func::output()
+    }
+
+    fn castable(value: &Value) -> bool {
+        Func::castable(value) || T::castable(value)
+    }
+}
+
+impl<T: IntoValue> IntoValue for FeatureStyle<T> {
+    fn into_value(self) -> Value {
+        match self {
+            Self::Value(value) => value.into_value(),
+            Self::Func(func) => func.into_value(),
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for FeatureStyle<T> {
+    fn from_value(value: Value) -> StrResult<Self> {
+        match value {
+            Value::Func(v) => Ok(Self::Func(v)),
+            v if T::castable(&v) => Ok(Self::Value(T::from_value(v)?)),
+            v => Err(Self::error(&v)),
+        }
+    }
+}
+
+/// The features of a map.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct GeoFeatures(Vec<GeoFeature>);
+
+cast! {
+    GeoFeatures,
+    self => self
+        .0
+        .into_iter()
+        .map(|feature| feature.dict.into_value())
+        .collect::<Array>()
+        .into_value(),
+    v: Array => Self(decode_features(v.into_value())?),
+    v: Dict => Self(decode_features(v.into_value())?),
+}
+
+/// A geographic feature.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub(crate) struct GeoFeature {
+    /// The feature's dictionary with its `id`, `geometry`, and `properties`.
+    pub dict: Dict,
+    /// The feature's parsed geometry, if any.
+    geometry: Option<GeoGeometry>,
+}
+
+/// Decode GeoJSON data into its features.
+///
+/// Accepts a feature collection, a single feature, a bare geometry, or an
+/// array of any of these.
+pub(crate) fn decode_features(value: Value) -> StrResult<Vec<GeoFeature>> {
+    let dict = match value {
+        Value::Array(array) => {
+            let mut features = vec![];
+            for item in array {
+                features.extend(decode_features(item)?);
+            }
+            return Ok(features);
+        }
+        Value::Dict(dict) => dict,
+        v => bail!("expected GeoJSON object, found {}", v.ty()),
+    };
+
+    // Features that were already decoded have no type.
+    let kind: Str = match dict.get("type") {
+        Ok(kind) => kind.clone().cast()?,
+        Err(_) => "Feature".into(),
+    };
+
+    match kind.as_str() {
+        "FeatureCollection" => {
+            let features: Array = dict.get("features")?.clone().cast()?;
+            features
+                .into_iter()
+                .map(|feature| decode_feature(&feature.cast()?))
+                .collect()
+        }
+        "Feature" => Ok(vec![decode_feature(&dict)?]),
+        _ => {
+            let geometry = GeoGeometry::from_dict(&dict)?;
+            let mut normalized = Dict::new();
+            normalized.insert("id".into(), Value::None);
+            normalized.insert("geometry".into(), dict.into_value());
+            normalized.insert("properties".into(), Dict::new().into_value());
+            Ok(vec![GeoFeature { dict: normalized, geometry: Some(geometry) }])
+        }
+    }
+}
+
+/// Decode a single feature.
+fn decode_feature(dict: &Dict) -> StrResult<GeoFeature> {
+    let (geometry, parsed) = match dict.get("geometry") {
+        Ok(Value::Dict(geometry)) => {
+            let parsed = GeoGeometry::from_dict(geometry)?;
+            (geometry.clone().into_value(), Some(parsed))
+        }
+        Ok(Value::None) | Err(_) => (Value::None, None),
+        Ok(v) => bail!("expected dictionary or none for geometry, found {}", v.ty()),
+    };
+
+    let properties = match dict.get("properties") {
+        Ok(Value::Dict(properties)) => properties.clone(),
+        Ok(Value::None) | Err(_) => Dict::new(),
+        Ok(v) => bail!("expected dictionary or none for properties, found {}", v.ty()),
+    };
+
+    let mut normalized = Dict::new();
+    normalized.insert("id".into(), dict.get("id").cloned().unwrap_or_default());
+    normalized.insert("geometry".into(), geometry);
+    normalized.insert("properties".into(), properties.into_value());
+    Ok(GeoFeature { dict: normalized, geometry: parsed })
+}
+
+/// A parsed GeoJSON geometry.
+#[derive(Debug, Clone, PartialEq, Hash)]
+enum GeoGeometry {
+    /// Points drawn as dots.
+    Points(Vec<Position>),
+    /// Lines through positions.
+    Lines(Vec<Vec<Position>>),
+    /// Polygons, each consisting of an outer ring and any number of holes.
+    Polygons(Vec<Vec<Vec<Position>>>),
+    /// A collection of other geometries.
+    Collection(Vec<GeoGeometry>),
+}
+
+/// A longitude and latitude in degrees.
+type Position = [Scalar; 2];
+
+impl GeoGeometry {
+    /// Parse a geometry from its GeoJSON dictionary.
+    fn from_dict(dict: &Dict) -> StrResult<Self> {
+        let kind: Str = dict.get("type")?.clone().cast()?;
+        if kind.as_str() == "GeometryCollection" {
+            let geometries: Array = dict.get("geometries")?.clone().cast()?;
+            return Ok(Self::Collection(
+                geometries
+                    .into_iter()
+                    .map(|geometry| Self::from_dict(&geometry.cast()?))
+                    .collect::<StrResult<_>>()?,
+            ));
+        }
+
+        let coordinates = || dict.get("coordinates").cloned();
+        let point = |v: Value| position(v).map(|[x, y]| [Scalar::new(x), Scalar::new(y)]);
+        let line = |v: Value| list(v, point);
+        let ring = |v: Value| -> StrResult<Vec<Position>> {
+            let ring = line(v)?;
+            if ring.len() < 3 {
+                bail!("polygon ring must contain at least three positions");
+            }
+            Ok(ring)
+        };
+
+        Ok(match kind.as_str() {
+            "Point" => Self::Points(vec![point(coordinates()?)?]),
+            "MultiPoint" => Self::Points(line(coordinates()?)?),
+            "LineString" => Self::Lines(vec![line(coordinates()?)?]),
+            "MultiLineString" => Self::Lines(list(coordinates()?, line)?),
+            "Polygon" => Self::Polygons(vec![list(coordinates()?, ring)?]),
+            "MultiPolygon" => Self::Polygons(list(coordinates()?, |v| list(v, ring))?),
+            other => bail!("unknown geometry type `{other}`"),
+        })
+    }
+
+    /// Call `f` for each position in the geometry.
+    fn visit(&self, f: &mut impl FnMut([f64; 2])) {
+        let get = |p: &Position| [p[0].get(), p[1].get()];
+        match self {
+            Self::Points(points) => points.iter().map(get).for_each(f),
+            Self::Lines(lines) => lines.iter().flatten().map(get).for_each(f),
+            Self::Polygons(polygons) => {
+                polygons.iter().flatten().flatten().map(get).for_each(f)
+            }
+            Self::Collection(geometries) => {
+                for geometry in geometries {
+                    geometry.visit(&mut *f);
+                }
+            }
+        }
+    }
+
+    /// Produce the shapes that draw the geometry.
+    fn draw(
+        &self,
+        point: &impl Fn([f64; 2]) -> Point,
+        radius: Abs,
+        fill: &Option<Paint>,
+        stroke: &Option<FixedStroke>,
+        shapes: &mut Vec<(Point, Shape)>,
+    ) {
+        let get = |p: &Position| point([p[0].get(), p[1].get()]);
+        let trace = |path: &mut Path, positions: &[Position]| {
+            for (i, p) in positions.iter().enumerate() {
+                if i == 0 {
+                    path.move_to(get(p));
+                } else {
+                    path.line_to(get(p));
+                }
+            }
+        };
+
+        match self {
+            Self::Points(points) => {
+                for p in points {
+                    let size = Size::splat(2.0 * radius);
+                    let shape = ellipse(size, fill.clone(), stroke.clone());
+                    shapes.push((get(p) - Point::splat(radius), shape));
+                }
+            }
+            Self::Lines(lines) => {
+                let Some(stroke) = stroke else { return };
+                let mut path = Path::new();
+                for line in lines {
+                    trace(&mut path, line);
+                }
+                let shape = Geometry::Path(path).stroked(stroke.clone());
+                shapes.push((Point::zero(), shape));
+            }
+            Self::Polygons(polygons) => {
+                let mut path = Path::new();
+                for ring in polygons.iter().flatten() {
+                    trace(&mut path, ring);
+                    path.close_path();
+                }
+                let shape = Shape {
+                    geometry: Geometry::Path(path),
+                    fill: fill.clone(),
+                    stroke: stroke.clone(),
+                };
+                shapes.push((Point::zero(), shape));
+            }
+            Self::Collection(geometries) => {
+                for geometry in geometries {
+                    geometry.draw(point, radius, fill, stroke, shapes);
+                }
+            }
+        }
+    }
+}
+
+/// Parse a GeoJSON position into its longitude and latitude. An altitude is
+/// ignored.
+fn position(value: Value) -> StrResult<[f64; 2]> {
+    let array: Array = value.cast()?;
+    let mut iter = array.into_iter();
+    match (iter.next(), iter.next()) {
+        (Some(lon), Some(lat)) => {
+            let (lon, lat): (f64, f64) = (lon.cast()?, lat.cast()?);
+            if !lon.is_finite() || !lat.is_finite() {
+                bail!("position must be finite");
+            }
+            Ok([lon, lat])
+        }
+        _ => bail!("position must contain at least two numbers"),
+    }
+}
+
+/// Parse an array of values.
+fn list<T>(value: Value, f: impl Fn(Value) -> StrResult<T>) -> StrResult<Vec<T>> {
+    value.cast::<Array>()?.into_iter().map(f).collect()
+}
+
+/// Replace the positions in nested GeoJSON coordinates.
+fn map_positions(
+    value: Value,
+    f: &impl Fn(Value) -> StrResult<Value>,
+) -> StrResult<Value> {
+    let Value::Array(array) = value else {
+        bail!("expected array, found {}", value.ty());
+    };
+    if array.iter().all(|v| matches!(v, Value::Array(_))) && !array.is_empty() {
+        let mapped = array
+            .into_iter()
+            .map(|v| map_positions(v, f))
+            .collect::<StrResult<Array>>()?;
+        return Ok(mapped.into_value());
+    }
+    f(Value::Array(array))
+}
@@ -3,16 +3,23 @@ use std::fmt::{self, Debug, Formatter};
 use std::hash::Hash;
 use std::sync::Arc;
 
+use comemo::Tracked;
 use ecow::EcoString;
+use image::GenericImageView;
 use kurbo::Vec2;
 
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, At, SourceResult};
+use crate::engine::Engine;
 use crate::foundations::{
-    array, cast, func, scope, ty, Args, Array, Cast, Func, IntoValue, Repr, Smart,
+    array, cast, dict, func, scope, ty, Args, Array, Cast, Content, Context, Dict, Func,
+    IntoValue, Repr, Smart, StyleChain, Value,
 };
-use crate::layout::{Angle, Axes, Dir, Quadrant, Ratio};
+use crate::layout::{Abs, Angle, Axes, Dir, Point, Quadrant, Ratio, Size, Transform};
 use crate::syntax::{Span, Spanned};
-use crate::visualize::{Color, ColorSpace, WeightedColor};
+use crate::visualize::{
+    Color, ColorSpace, Easing, HueInterpolation, Image, ImageElem, ImageKind,
+    WeightedColor,
+};
 
 /// A color gradient.
 ///
@@ -70,6 +77,20 @@ use crate::visualize::{Color, ColorSpace, WeightedColor};
 /// the offsets when defining a gradient. In this case, Typst will space all
 /// stops evenly.
 ///
+/// A stop can also be given as a dictionary with a `color` and optionally an
+/// `offset`, a `space`, and an `easing`. The `space` and `easing` then override
+/// the gradient's [interpolation](#color-spaces-and-interpolation) between
+/// this stop and the next one. This makes it possible to combine hard and
+/// smooth transitions in a single gradient.
+///
+/// ```example
+/// #rect(width: 100%, fill: gradient.linear(
+///   (color: red, offset: 0%, easing: t => 0),
+///   (color: blue, offset: 50%, space: oklch),
+///   (color: green, offset: 100%),
+/// ))
+/// ```
+///
 /// # Relativeness
 /// The location of the `{0%}` and `{100%}` stops depends on the dimensions
 /// of a container. This container can either be the shape that it is being
@@ -199,6 +220,10 @@ impl Gradient {
     /// ```
     #[func(title = "Linear Gradient")]
     pub fn linear(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
         /// The args of this function.
         args: &mut Args,
         /// The call site of this function.
@@ -213,6 +238,17 @@ impl Gradient {
         #[named]
         #[default(ColorSpace::Oklab)]
         space: ColorSpace,
+        /// The direction in which to interpolate hues if the color space is
+        /// hue-based. See [`color.mix`]($color.mix) for details.
+        #[named]
+        #[default(HueInterpolation::Shorter)]
+        hue: HueInterpolation,
+        /// How to interpolate between two neighbouring stops, for example
+        /// `{"ease-in-out"}`. See [`color.mix`]($color.mix) for the possible
+        /// values.
+        #[named]
+        #[default(Easing::Linear)]
+        easing: Easing,
         /// The [relative placement](#relativeness) of the gradient.
         ///
         /// For an element placed at the root/top level of the document, the
@@ -254,7 +290,11 @@ impl Gradient {
             stops: process_stops(&stops)?,
             angle,
             space,
+            hue,
+            easing: easing.resolve(engine, context, span)?,
+            segments: process_segments(engine, context, &stops)?,
             relative,
+            transform: GradientTransform::default(),
             anti_alias: true,
         })))
     }
@@ -291,6 +331,10 @@ impl Gradient {
     /// ```
     #[func]
     fn radial(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
         /// The call site of this function.
         span: Span,
         /// The color [stops](#stops) of the gradient.
@@ -303,6 +347,17 @@ impl Gradient {
         #[named]
         #[default(ColorSpace::Oklab)]
         space: ColorSpace,
+        /// The direction in which to interpolate hues if the color space is
+        /// hue-based. See [`color.mix`]($color.mix) for details.
+        #[named]
+        #[default(HueInterpolation::Shorter)]
+        hue: HueInterpolation,
+        /// How to interpolate between two neighbouring stops, for example
+        /// `{"ease-in-out"}`. See [`color.mix`]($color.mix) for the possible
+        /// values.
+        #[named]
+        #[default(Easing::Linear)]
+        easing: Easing,
         /// The [relative placement](#relativeness) of the gradient.
         ///
         /// For an element placed at the root/top level of the document, the parent
@@ -379,7 +434,11 @@ impl Gradient {
             focal_center,
             focal_radius: focal_radius.v,
             space,
+            hue,
+            easing: easing.resolve(engine, context, span)?,
+            segments: process_segments(engine, context, &stops)?,
             relative,
+            transform: GradientTransform::default(),
             anti_alias: true,
         })))
     }
@@ -406,6 +465,10 @@ impl Gradient {
     /// ```
     #[func]
     pub fn conic(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
         /// The call site of this function.
         span: Span,
         /// The color [stops](#stops) of the gradient.
@@ -422,6 +485,17 @@ impl Gradient {
         #[named]
         #[default(ColorSpace::Oklab)]
         space: ColorSpace,
+        /// The direction in which to interpolate hues if the color space is
+        /// hue-based. See [`color.mix`]($color.mix) for details.
+        #[named]
+        #[default(HueInterpolation::Shorter)]
+        hue: HueInterpolation,
+        /// How to interpolate between two neighbouring stops, for example
+        /// `{"ease-in-out"}`. See [`color.mix`]($color.mix) for the possible
+        /// values.
+        #[named]
+        #[default(Easing::Linear)]
+        easing: Easing,
         /// The [relative placement](#relativeness) of the gradient.
         ///
         /// For an element placed at the root/top level of the document, the parent
@@ -450,7 +524,98 @@ impl Gradient {
             angle,
             center: center.map(From::from),
             space,
+            hue,
+            easing: easing.resolve(engine, context, span)?,
+            segments: process_segments(engine, context, &stops)?,
             relative,
+            transform: GradientTransform::default(),
+            anti_alias: true,
+        })))
+    }
+
+    /// Creates a linear gradient from a row or column of pixels of a raster
+    /// image.
+    ///
+    /// Each pixel of the row or column becomes a stop of the gradient, with
+    /// the stops spread evenly from `{0%}` to `{100%}`. This is useful for
+    /// color maps that are only distributed as image strips. To create a
+    /// different kind of gradient, pass the resulting gradient's
+    /// [stops]($gradient.stops) to another constructor.
+    ///
+    /// ```example
+    /// #let grad = gradient.from-image(image("glacier.jpg"), row: 80%)
+    /// #rect(width: 100%, height: 20pt, fill: grad)
+    /// #circle(radius: 20pt, fill: gradient.radial(..grad.stops()))
+    /// ```
+    #[func(title = "Gradient from Image")]
+    pub fn from_image(
+        /// The call site of this function.
+        span: Span,
+        /// The raster image to sample, as created by the [`image`]($image)
+        /// function.
+        image: Spanned<Content>,
+        /// The row of pixels to sample, from `{0%}` (the top) to `{100%}` (the
+        /// bottom). If neither a row nor a column is given, the middle row is
+        /// sampled.
+        #[named]
+        row: Option<Spanned<Ratio>>,
+        /// The column of pixels to sample, from `{0%}` (the left) to `{100%}`
+        /// (the right). The column's pixels are ordered from top to bottom.
+        #[named]
+        column: Option<Spanned<Ratio>>,
+    ) -> SourceResult<Gradient> {
+        let Some(elem) = image.v.to_packed::<ImageElem>() else {
+            bail!(image.span, "expected an image");
+        };
+
+        let format = elem.determine_format(StyleChain::default()).at(image.span)?;
+        let decoded =
+            Image::new(elem.data().clone().into(), format, None).at(image.span)?;
+        let ImageKind::Raster(raster) = decoded.kind() else {
+            bail!(image.span, "can only create gradients from raster images");
+        };
+
+        let pixels = raster.dynamic();
+        let (width, height) = pixels.dimensions();
+        let (line, vertical) = match (row, column) {
+            (Some(_), Some(column)) => {
+                bail!(column.span, "cannot sample both a row and a column")
+            }
+            (row, None) => (row.unwrap_or(Spanned::new(Ratio::new(0.5), span)), false),
+            (None, Some(column)) => (column, true),
+        };
+
+        if !(0.0..=1.0).contains(&line.v.get()) {
+            bail!(line.span, "must be between 0% and 100%");
+        }
+
+        let (len, across) = if vertical { (height, width) } else { (width, height) };
+        if len < 2 {
+            bail!(
+                image.span,
+                "image must be at least two pixels long along the gradient"
+            );
+        }
+
+        let fixed = (line.v.get() * (across - 1) as f64).round() as u32;
+        let stops = (0..len)
+            .map(|i| {
+                let (x, y) = if vertical { (fixed, i) } else { (i, fixed) };
+                let [r, g, b, a] = pixels.get_pixel(x, y).0;
+                let offset = Ratio::new(i as f64 / (len - 1) as f64);
+                (Color::from_u8(r, g, b, a), offset)
+            })
+            .collect();
+
+        Ok(Self::Linear(Arc::new(LinearGradient {
+            stops,
+            angle: Angle::zero(),
+            space: ColorSpace::Oklab,
+            hue: HueInterpolation::Shorter,
+            easing: Easing::Linear,
+            segments: Vec::new(),
+            relative: Smart::Auto,
+            transform: GradientTransform::default(),
             anti_alias: true,
         })))
     }
@@ -528,7 +693,11 @@ impl Gradient {
                 stops,
                 angle: linear.angle,
                 space: linear.space,
+                hue: linear.hue,
+                easing: Easing::Linear,
+                segments: Vec::new(),
                 relative: linear.relative,
+                transform: linear.transform,
                 anti_alias: false,
             })),
             Self::Radial(radial) => Self::Radial(Arc::new(RadialGradient {
@@ -538,7 +707,11 @@ impl Gradient {
                 focal_center: radial.focal_center,
                 focal_radius: radial.focal_radius,
                 space: radial.space,
+                hue: radial.hue,
+                easing: Easing::Linear,
+                segments: Vec::new(),
                 relative: radial.relative,
+                transform: radial.transform,
                 anti_alias: false,
             })),
             Self::Conic(conic) => Self::Conic(Arc::new(ConicGradient {
@@ -546,7 +719,11 @@ impl Gradient {
                 angle: conic.angle,
                 center: conic.center,
                 space: conic.space,
+                hue: conic.hue,
+                easing: Easing::Linear,
+                segments: Vec::new(),
                 relative: conic.relative,
+                transform: conic.transform,
                 anti_alias: false,
             })),
         })
@@ -578,24 +755,29 @@ impl Gradient {
         }
 
         let n = repetitions.v;
-        let mut stops = std::iter::repeat(self.stops_ref())
-            .take(n)
-            .enumerate()
-            .flat_map(|(i, stops)| {
-                let mut stops = stops
+        let segments = self.segments();
+        let mut stops = (0..n)
+            .flat_map(|i| {
+                let mirrored = i % 2 == 1 && mirror;
+                let mut stops = self
+                    .stops_ref()
                     .iter()
-                    .map(move |&(color, offset)| {
+                    .enumerate()
+                    .map(move |(j, &(color, offset))| {
                         let t = i as f64 / n as f64;
                         let r = offset.get();
-                        if i % 2 == 1 && mirror {
-                            (color, Ratio::new(t + (1.0 - r) / n as f64))
+                        // Each stop carries the segment that starts at it,
+                        // which changes sides when the stops are mirrored.
+                        if mirrored {
+                            let segment = j.checked_sub(1).and_then(|j| segments.get(j));
+                            (color, Ratio::new(t + (1.0 - r) / n as f64), segment)
                         } else {
-                            (color, Ratio::new(t + r / n as f64))
+                            (color, Ratio::new(t + r / n as f64), segments.get(j))
                         }
                     })
                     .collect::<Vec<_>>();
 
-                if i % 2 == 1 && mirror {
+                if mirrored {
                     stops.reverse();
                 }
 
@@ -603,14 +785,39 @@ impl Gradient {
             })
             .collect::<Vec<_>>();
 
-        stops.dedup();
+        // Keep the segment of the later stop, as the earlier one only starts
+        // an empty segment.
+        stops.dedup_by(|later, earlier| {
+            let same = (later.0, later.1) == (earlier.0, earlier.1);
+            if same {
+                earlier.2 = later.2;
+            }
+            same
+        });
+
+        let segments = if segments.is_empty() {
+            Vec::new()
+        } else {
+            stops[..stops.len() - 1]
+                .iter()
+                .map(|&(.., segment)| segment.cloned().unwrap_or_default())
+                .collect()
+        };
+        let stops = stops
+            .into_iter()
+            .map(|(color, offset, _)| (color, offset))
+            .collect::<Vec<_>>();
 
         Ok(match self {
             Self::Linear(linear) => Self::Linear(Arc::new(LinearGradient {
                 stops,
                 angle: linear.angle,
                 space: linear.space,
+                hue: linear.hue,
+                easing: linear.easing.clone(),
+                segments: segments.clone(),
                 relative: linear.relative,
+                transform: linear.transform,
                 anti_alias: linear.anti_alias,
             })),
             Self::Radial(radial) => Self::Radial(Arc::new(RadialGradient {
@@ -620,7 +827,11 @@ impl Gradient {
                 focal_center: radial.focal_center,
                 focal_radius: radial.focal_radius,
                 space: radial.space,
+                hue: radial.hue,
+                easing: radial.easing.clone(),
+                segments: segments.clone(),
                 relative: radial.relative,
+                transform: radial.transform,
                 anti_alias: radial.anti_alias,
             })),
             Self::Conic(conic) => Self::Conic(Arc::new(ConicGradient {
@@ -628,12 +839,67 @@ impl Gradient {
                 angle: conic.angle,
                 center: conic.center,
                 space: conic.space,
+                hue: conic.hue,
+                easing: conic.easing.clone(),
+                segments: segments.clone(),
                 relative: conic.relative,
+                transform: conic.transform,
                 anti_alias: conic.anti_alias,
             })),
         })
     }
 
+    /// Transforms the gradient independently of the shape it fills.
+    ///
+    /// The gradient is rotated and scaled around the center of the box it is
+    /// [relative](#relativeness) to and then moved. Together with a
+    /// `{"parent"}`-relative gradient, this makes it possible to align a
+    /// single rotated or enlarged gradient across multiple shapes.
+    ///
+    /// When a gradient is transformed multiple times, the angles add up, the
+    /// scales multiply, and the offsets add up.
+    ///
+    /// ```example
+    /// #set rect(width: 100%, height: 20pt)
+    /// #let grad = gradient.linear(..color.map.rainbow)
+    /// #rect(fill: grad)
+    /// #rect(fill: grad.transformed(rotate: 20deg, scale: 150%))
+    /// #rect(fill: grad.transformed(offset: (25%, 0%)))
+    /// ```
+    #[func]
+    pub fn transformed(
+        &self,
+        /// How much to rotate the gradient.
+        #[named]
+        #[default(Angle::zero())]
+        rotate: Angle,
+        /// How much to scale the gradient. A negative scale mirrors it.
+        #[named]
+        #[default(Spanned::new(Ratio::one(), Span::detached()))]
+        scale: Spanned<Ratio>,
+        /// How far to move the gradient, relative to the size of the box it is
+        /// relative to.
+        #[named]
+        #[default(Axes::splat(Ratio::zero()))]
+        offset: Axes<Ratio>,
+    ) -> SourceResult<Gradient> {
+        if scale.v.is_zero() {
+            bail!(scale.span, "scale must not be zero");
+        }
+
+        let mut gradient = self.clone();
+        let transform = match &mut gradient {
+            Self::Linear(linear) => &mut Arc::make_mut(linear).transform,
+            Self::Radial(radial) => &mut Arc::make_mut(radial).transform,
+            Self::Conic(conic) => &mut Arc::make_mut(conic).transform,
+        };
+
+        transform.rotate += rotate;
+        transform.scale *= scale.v.get();
+        transform.offset = transform.offset.zip_map(offset, |a, b| a + b);
+        Ok(gradient)
+    }
+
     /// Returns the kind of this gradient.
     #[func]
     pub fn kind(&self) -> Func {
@@ -647,32 +913,16 @@ impl Gradient {
     /// Returns the stops of this gradient.
     #[func]
     pub fn stops(&self) -> Vec<GradientStop> {
-        match self {
-            Self::Linear(linear) => linear
-                .stops
-                .iter()
-                .map(|(color, offset)| GradientStop {
-                    color: *color,
-                    offset: Some(*offset),
-                })
-                .collect(),
-            Self::Radial(radial) => radial
-                .stops
-                .iter()
-                .map(|(color, offset)| GradientStop {
-                    color: *color,
-                    offset: Some(*offset),
-                })
-                .collect(),
-            Self::Conic(conic) => conic
-                .stops
-                .iter()
-                .map(|(color, offset)| GradientStop {
-                    color: *color,
-                    offset: Some(*offset),
-                })
-                .collect(),
-        }
+        let segments = self.segments();
+        self.stops_ref()
+            .iter()
+            .enumerate()
+            .map(|(i, &(color, offset))| GradientStop {
+                color,
+                offset: Some(offset),
+                segment: segments.get(i).cloned().unwrap_or_default(),
+            })
+            .collect()
     }
 
     /// Returns the mixing space of this gradient.
@@ -685,6 +935,26 @@ impl Gradient {
         }
     }
 
+    /// Returns the direction in which this gradient interpolates hues.
+    #[func]
+    pub fn hue(&self) -> HueInterpolation {
+        match self {
+            Self::Linear(linear) => linear.hue,
+            Self::Radial(radial) => radial.hue,
+            Self::Conic(conic) => conic.hue,
+        }
+    }
+
+    /// Returns how this gradient interpolates between neighbouring stops.
+    #[func]
+    pub fn easing(&self) -> Easing {
+        match self {
+            Self::Linear(linear) => linear.easing.clone(),
+            Self::Radial(radial) => radial.easing.clone(),
+            Self::Conic(conic) => conic.easing.clone(),
+        }
+    }
+
     /// Returns the relative placement of this gradient.
     #[func]
     pub fn relative(&self) -> Smart<RelativeTo> {
@@ -717,12 +987,20 @@ impl Gradient {
         t: RatioOrAngle,
     ) -> Color {
         let value: f64 = t.to_ratio().get();
-
-        match self {
-            Self::Linear(linear) => sample_stops(&linear.stops, linear.space, value),
-            Self::Radial(radial) => sample_stops(&radial.stops, radial.space, value),
-            Self::Conic(conic) => sample_stops(&conic.stops, conic.space, value),
-        }
+        let stops = self.stops_ref();
+        let (low, t) = locate_stop(stops, value);
+        let (space, easing) = self.interpolation(low - 1);
+        let t = easing.apply(t);
+
+        Color::mix_iter(
+            [
+                WeightedColor::new(stops[low - 1].0, 1.0 - t),
+                WeightedColor::new(stops[low].0, t),
+            ],
+            space,
+            self.hue(),
+        )
+        .unwrap()
     }
 
     /// Samples the gradient at multiple positions at once and returns the
@@ -764,9 +1042,113 @@ impl Gradient {
         }
     }
 
+    /// Returns the overrides of the interpolation between neighbouring stops.
+    ///
+    /// This is either empty or contains one segment for each pair of stops.
+    pub fn segments(&self) -> &[GradientSegment] {
+        match self {
+            Gradient::Linear(linear) => &linear.segments,
+            Gradient::Radial(radial) => &radial.segments,
+            Gradient::Conic(conic) => &conic.segments,
+        }
+    }
+
+    /// Returns the color space and easing with which the segment between the
+    /// stop at `index` and the next one is interpolated.
+    pub fn interpolation(&self, index: usize) -> (ColorSpace, &Easing) {
+        let (space, easing) = match self {
+            Gradient::Linear(linear) => (linear.space, &linear.easing),
+            Gradient::Radial(radial) => (radial.space, &radial.easing),
+            Gradient::Conic(conic) => (conic.space, &conic.easing),
+        };
+
+        match self.segments().get(index) {
+            Some(segment) => (
+                segment.space.unwrap_or(space),
+                segment.easing.as_ref().unwrap_or(easing),
+            ),
+            None => (space, easing),
+        }
+    }
+
+    /// Returns the additional transformation of this gradient.
+    pub fn transformation(&self) -> GradientTransform {
+        match self {
+            Gradient::Linear(linear) => linear.transform,
+            Gradient::Radial(radial) => radial.transform,
+            Gradient::Conic(conic) => conic.transform,
+        }
+    }
+
     /// Samples the gradient at a given position, in the given container.
     /// Handles the aspect ratio and angle directly.
-    pub fn sample_at(&self, (x, y): (f32, f32), (width, height): (f32, f32)) -> Color {
+    pub fn sample_at(&self, pos: (f32, f32), size: (f32, f32)) -> Color {
+        let t = self.position_at(pos, size);
+        self.sample(RatioOrAngle::Ratio(Ratio::new(t)))
+    }
+
+    /// Samples the gradient at multiple positions, in the given container, and
+    /// appends the resulting sRGB colors, as vecs of their components, to
+    /// `out`.
+    ///
+    /// This produces the same colors as [`sample_at`](Self::sample_at), but
+    /// converts the stops into the mixing space only once and all samples into
+    /// sRGB at once with [`Color::convert_slice`]. Prefer it when sampling
+    /// many positions, like when rasterizing the gradient.
+    pub fn sample_srgb_at(
+        &self,
+        positions: impl IntoIterator<Item = (f32, f32)>,
+        size: (f32, f32),
+        out: &mut Vec<[f32; 4]>,
+    ) {
+        // Segments may be interpolated in different color spaces, so their
+        // stops can't be converted into a single mixing space up front.
+        if !self.segments().is_empty() {
+            out.extend(positions.into_iter().map(|pos| {
+                self.sample_at(pos, size).to_space(ColorSpace::Srgb).to_vec4()
+            }));
+            return;
+        }
+
+        let (space, hue) = (self.space(), self.hue());
+        let easing = match self {
+            Self::Linear(linear) => &linear.easing,
+            Self::Radial(radial) => &radial.easing,
+            Self::Conic(conic) => &conic.easing,
+        };
+
+        let stops: Vec<_> = self
+            .stops_ref()
+            .iter()
+            .map(|&(color, offset)| (color.to_space(space).to_vec4(), offset))
+            .collect();
+
+        let start = out.len();
+        out.extend(positions.into_iter().map(|pos| {
+            let t = self.position_at(pos, size);
+            let (low, t) = locate_stop(&stops, t);
+            let t = easing.apply(t);
+            mix_vec4(stops[low - 1].0, stops[low].0, t, space, hue)
+        }));
+
+        Color::convert_slice(&mut out[start..], space, ColorSpace::Srgb);
+    }
+
+    /// Computes the position along the gradient, between zero and one, at
+    /// the given position in the given container.
+    fn position_at(&self, (x, y): (f32, f32), (width, height): (f32, f32)) -> f64 {
+        // Undo the additional transformation.
+        let transform = self.transformation();
+        let (x, y) = if transform.is_identity() {
+            (x, y)
+        } else {
+            let size = Size::new(Abs::pt(width as f64), Abs::pt(height as f64));
+            let inverse = transform.in_size(size).invert().unwrap_or_default();
+            let point =
+                Point::new(Abs::pt(x as f64), Abs::pt(y as f64)).transform(inverse);
+            (point.x.to_pt() as f32, point.y.to_pt() as f32)
+        };
+
         // Normalize the coordinates.
         let (mut x, mut y) = (x / width, y / height);
         let t = match self {
@@ -799,7 +1181,7 @@ impl Gradient {
                 let q =
                     Vec2::new(radial.focal_center.x.get(), radial.focal_center.y.get());
 
-                if (z - q).hypot() < fr {
+                if (z - q).hypot() <= fr {
                     0.0
                 } else if (z - p).hypot() > cr {
                     1.0
@@ -823,7 +1205,7 @@ impl Gradient {
             }
         };
 
-        self.sample(RatioOrAngle::Ratio(Ratio::new(t.clamp(0.0, 1.0))))
+        t.clamp(0.0, 1.0)
     }
 
     /// Does this gradient need to be anti-aliased?
@@ -874,11 +1256,26 @@ impl Debug for Gradient {
 
 impl Repr for Gradient {
     fn repr(&self) -> EcoString {
-        match self {
+        let mut r = match self {
             Self::Radial(radial) => radial.repr(),
             Self::Linear(linear) => linear.repr(),
             Self::Conic(conic) => conic.repr(),
+        };
+
+        let transform = self.transformation();
+        if !transform.is_identity() {
+            r.push_str(".transformed(rotate: ");
+            r.push_str(&transform.rotate.repr());
+            r.push_str(", scale: ");
+            r.push_str(&transform.scale.repr());
+            r.push_str(", offset: (");
+            r.push_str(&transform.offset.x.repr());
+            r.push_str(", ");
+            r.push_str(&transform.offset.y.repr());
+            r.push_str("))");
         }
+
+        r
     }
 }
 
@@ -891,8 +1288,17 @@ pub struct LinearGradient {
     pub angle: Angle,
     /// The color space in which to interpolate the gradient.
     pub space: ColorSpace,
+    /// The direction in which to interpolate hues.
+    pub hue: HueInterpolation,
+    /// How to interpolate between two neighbouring stops.
+    pub easing: Easing,
+    /// Overrides of the interpolation between neighbouring stops, either
+    /// empty or one for each pair of them.
+    pub segments: Vec<GradientSegment>,
     /// The relative placement of the gradient.
     pub relative: Smart<RelativeTo>,
+    /// An additional transformation, independent of the filled shape.
+    pub transform: GradientTransform,
     /// Whether to anti-alias the gradient (used for sharp gradients).
     pub anti_alias: bool,
 }
@@ -922,22 +1328,25 @@ impl Repr for LinearGradient {
             r.push_str(", ");
         }
 
+        if self.hue != HueInterpolation::Shorter {
+            r.push_str("hue: ");
+            r.push_str(&self.hue.into_value().repr());
+            r.push_str(", ");
+        }
+
+        if !self.easing.is_linear() {
+            r.push_str("easing: ");
+            r.push_str(&self.easing.clone().into_value().repr());
+            r.push_str(", ");
+        }
+
         if self.relative.is_custom() {
             r.push_str("relative: ");
             r.push_str(&self.relative.into_value().repr());
             r.push_str(", ");
         }
 
-        for (i, (color, offset)) in self.stops.iter().enumerate() {
-            r.push('(');
-            r.push_str(&color.repr());
-            r.push_str(", ");
-            r.push_str(&offset.repr());
-            r.push(')');
-            if i != self.stops.len() - 1 {
-                r.push_str(", ");
-            }
-        }
+        repr_stops(&mut r, &self.stops, &self.segments, |offset| offset.repr());
 
         r.push(')');
         r
@@ -959,8 +1368,17 @@ pub struct RadialGradient {
     pub focal_radius: Ratio,
     /// The color space in which to interpolate the gradient.
     pub space: ColorSpace,
+    /// The direction in which to interpolate hues.
+    pub hue: HueInterpolation,
+    /// How to interpolate between two neighbouring stops.
+    pub easing: Easing,
+    /// Overrides of the interpolation between neighbouring stops, either
+    /// empty or one for each pair of them.
+    pub segments: Vec<GradientSegment>,
     /// The relative placement of the gradient.
     pub relative: Smart<RelativeTo>,
+    /// An additional transformation, independent of the filled shape.
+    pub transform: GradientTransform,
     /// Whether to anti-alias the gradient (used for sharp gradients).
     pub anti_alias: bool,
 }
@@ -1003,22 +1421,25 @@ impl Repr for RadialGradient {
             r.push_str(", ");
         }
 
+        if self.hue != HueInterpolation::Shorter {
+            r.push_str("hue: ");
+            r.push_str(&self.hue.into_value().repr());
+            r.push_str(", ");
+        }
+
+        if !self.easing.is_linear() {
+            r.push_str("easing: ");
+            r.push_str(&self.easing.clone().into_value().repr());
+            r.push_str(", ");
+        }
+
         if self.relative.is_custom() {
             r.push_str("relative: ");
             r.push_str(&self.relative.into_value().repr());
             r.push_str(", ");
         }
 
-        for (i, (color, offset)) in self.stops.iter().enumerate() {
-            r.push('(');
-            r.push_str(&color.repr());
-            r.push_str(", ");
-            r.push_str(&offset.repr());
-            r.push(')');
-            if i != self.stops.len() - 1 {
-                r.push_str(", ");
-            }
-        }
+        repr_stops(&mut r, &self.stops, &self.segments, |offset| offset.repr());
 
         r.push(')');
         r
@@ -1037,8 +1458,17 @@ pub struct ConicGradient {
     pub center: Axes<Ratio>,
     /// The color space in which to interpolate the gradient.
     pub space: ColorSpace,
+    /// The direction in which to interpolate hues.
+    pub hue: HueInterpolation,
+    /// How to interpolate between two neighbouring stops.
+    pub easing: Easing,
+    /// Overrides of the interpolation between neighbouring stops, either
+    /// empty or one for each pair of them.
+    pub segments: Vec<GradientSegment>,
     /// The relative placement of the gradient.
     pub relative: Smart<RelativeTo>,
+    /// An additional transformation, independent of the filled shape.
+    pub transform: GradientTransform,
     /// Whether to anti-alias the gradient (used for sharp gradients).
     pub anti_alias: bool,
 }
@@ -1068,22 +1498,27 @@ impl Repr for ConicGradient {
             r.push_str(", ");
         }
 
+        if self.hue != HueInterpolation::Shorter {
+            r.push_str("hue: ");
+            r.push_str(&self.hue.into_value().repr());
+            r.push_str(", ");
+        }
+
+        if !self.easing.is_linear() {
+            r.push_str("easing: ");
+            r.push_str(&self.easing.clone().into_value().repr());
+            r.push_str(", ");
+        }
+
         if self.relative.is_custom() {
             r.push_str("relative: ");
             r.push_str(&self.relative.into_value().repr());
             r.push_str(", ");
         }
 
-        for (i, (color, offset)) in self.stops.iter().enumerate() {
-            r.push('(');
-            r.push_str(&color.repr());
-            r.push_str(", ");
-            r.push_str(&Angle::deg(offset.get() * 360.0).repr());
-            r.push(')');
-            if i != self.stops.len() - 1 {
-                r.push_str(", ");
-            }
-        }
+        repr_stops(&mut r, &self.stops, &self.segments, |offset| {
+            Angle::deg(offset.get() * 360.0).repr()
+        });
 
         r.push(')');
         r
@@ -1099,39 +1534,122 @@ pub enum RelativeTo {
     Parent,
 }
 
-/// A color stop.
+/// An additional transformation of a gradient, independent of the shape it
+/// fills.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct GradientTransform {
+    /// How much the gradient is rotated around the center of its box.
+    pub rotate: Angle,
+    /// How much the gradient is scaled around the center of its box.
+    pub scale: Ratio,
+    /// How far the gradient is moved, relative to the size of its box.
+    pub offset: Axes<Ratio>,
+}
+
+impl GradientTransform {
+    /// Whether this transformation leaves the gradient unchanged.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The transformation for a gradient whose box has the given size.
+    pub fn in_size(&self, size: Size) -> Transform {
+        let center = size.to_point() / 2.0;
+        let offset = Point::new(self.offset.x.of(size.x), self.offset.y.of(size.y));
+        let origin = center + offset;
+        Transform::translate(origin.x, origin.y)
+            .pre_concat(Transform::rotate(self.rotate))
+            .pre_concat(Transform::scale(self.scale, self.scale))
+            .pre_concat(Transform::translate(-center.x, -center.y))
+    }
+}
+
+impl Default for GradientTransform {
+    fn default() -> Self {
+        Self {
+            rotate: Angle::zero(),
+            scale: Ratio::one(),
+            offset: Axes::splat(Ratio::zero()),
+        }
+    }
+}
+
+/// A color stop.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct GradientStop {
     /// The color for this stop.
     pub color: Color,
     /// The offset of the stop along the gradient.
     pub offset: Option<Ratio>,
+    /// How to interpolate between this stop and the next one.
+    pub segment: GradientSegment,
 }
 
 impl GradientStop {
     /// Create a new stop from a `color` and an `offset`.
     pub fn new(color: Color, offset: Ratio) -> Self {
-        Self { color, offset: Some(offset) }
+        Self {
+            color,
+            offset: Some(offset),
+            segment: GradientSegment::default(),
+        }
     }
 }
 
 cast! {
     GradientStop,
-    self => if let Some(offset) = self.offset {
+    self => if !self.segment.is_default() {
+        let mut dict = dict! { "color" => self.color };
+        if let Some(offset) = self.offset {
+            dict.insert("offset".into(), offset.into_value());
+        }
+        if let Some(space) = self.segment.space {
+            dict.insert("space".into(), space.into_value());
+        }
+        if let Some(easing) = self.segment.easing {
+            dict.insert("easing".into(), easing.into_value());
+        }
+        dict.into_value()
+    } else if let Some(offset) = self.offset {
         array![self.color.into_value(), offset].into_value()
     } else {
         self.color.into_value()
     },
-    color: Color => Self { color, offset: None },
+    color: Color => Self { color, offset: None, segment: GradientSegment::default() },
     array: Array => {
         let mut iter = array.into_iter();
         match (iter.next(), iter.next(), iter.next()) {
             (Some(a), Some(b), None) => Self {
                 color: a.cast()?,
-                offset: Some(b.cast()?)
+                offset: Some(b.cast()?),
+                segment: GradientSegment::default(),
             },
             _ => Err("a color stop must contain exactly two entries")?,
         }
+    },
+    mut dict: Dict => {
+        let color = dict.take("color")?.cast()?;
+        let offset = dict.take("offset").ok().map(Value::cast).transpose()?;
+        let space = dict.take("space").ok().map(Value::cast).transpose()?;
+        let easing = dict.take("easing").ok().map(Value::cast).transpose()?;
+        dict.finish(&["color", "offset", "space", "easing"])?;
+        Self { color, offset, segment: GradientSegment { space, easing } }
+    },
+}
+
+/// Overrides of how a gradient interpolates between two neighbouring stops.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct GradientSegment {
+    /// The color space in which to interpolate, instead of the gradient's.
+    pub space: Option<ColorSpace>,
+    /// The easing of the interpolation, instead of the gradient's.
+    pub easing: Option<Easing>,
+}
+
+impl GradientSegment {
+    /// Whether this segment is interpolated like the rest of the gradient.
+    pub fn is_default(&self) -> bool {
+        self.space.is_none() && self.easing.is_none()
     }
 }
 
@@ -1162,6 +1680,46 @@ cast! {
     angle: Angle => Self::Angle(angle),
 }
 
+/// Writes the stops of a gradient, with the segments that start at them, into
+/// its representation.
+fn repr_stops(
+    r: &mut EcoString,
+    stops: &[(Color, Ratio)],
+    segments: &[GradientSegment],
+    offset_repr: impl Fn(Ratio) -> EcoString,
+) {
+    for (i, &(color, offset)) in stops.iter().enumerate() {
+        match segments.get(i).filter(|segment| !segment.is_default()) {
+            Some(segment) => {
+                r.push_str("(color: ");
+                r.push_str(&color.repr());
+                r.push_str(", offset: ");
+                r.push_str(&offset_repr(offset));
+                if let Some(space) = segment.space {
+                    r.push_str(", space: ");
+                    r.push_str(&space.into_value().repr());
+                }
+                if let Some(easing) = &segment.easing {
+                    r.push_str(", easing: ");
+                    r.push_str(&easing.clone().into_value().repr());
+                }
+                r.push(')');
+            }
+            None => {
+                r.push('(');
+                r.push_str(&color.repr());
+                r.push_str(", ");
+                r.push_str(&offset_repr(offset));
+                r.push(')');
+            }
+        }
+
+        if i != stops.len() - 1 {
+            r.push_str(", ");
+        }
+    }
+}
+
 /// Pre-processes the stops, checking that they are valid and computing the
 /// offsets if necessary.
 ///
@@ -1170,7 +1728,9 @@ cast! {
 /// This is split into its own function because it is used by all of the
 /// different gradient types.
 #[comemo::memoize]
-fn process_stops(stops: &[Spanned<GradientStop>]) -> SourceResult<Vec<(Color, Ratio)>> {
+pub(super) fn process_stops(
+    stops: &[Spanned<GradientStop>],
+) -> SourceResult<Vec<(Color, Ratio)>> {
     let has_offset = stops.iter().any(|stop| stop.v.offset.is_some());
     if has_offset {
         let mut last_stop = f64::NEG_INFINITY;
@@ -1191,7 +1751,7 @@ fn process_stops(stops: &[Spanned<GradientStop>]) -> SourceResult<Vec<(Color, Ra
 
         let out = stops
             .iter()
-            .map(|Spanned { v: GradientStop { color, offset }, span }| {
+            .map(|Spanned { v: GradientStop { color, offset, .. }, span }| {
                 if offset.unwrap().get() > 1.0 || offset.unwrap().get() < 0.0 {
                     bail!(*span, "offset must be between 0 and 1");
                 }
@@ -1228,8 +1788,66 @@ fn process_stops(stops: &[Spanned<GradientStop>]) -> SourceResult<Vec<(Color, Ra
         .collect())
 }
 
+/// Resolves the interpolation overrides of the stops.
+///
+/// Returns no segments if none of the stops overrides the interpolation.
+fn process_segments(
+    engine: &mut Engine,
+    context: Tracked<Context>,
+    stops: &[Spanned<GradientStop>],
+) -> SourceResult<Vec<GradientSegment>> {
+    if stops.iter().all(|stop| stop.v.segment.is_default()) {
+        return Ok(Vec::new());
+    }
+
+    let (last, stops) = stops.split_last().unwrap();
+    if !last.v.segment.is_default() {
+        bail!(
+            last.span, "the last stop cannot override the interpolation";
+            hint: "the interpolation of a stop applies between it and the next stop"
+        );
+    }
+
+    stops
+        .iter()
+        .map(|Spanned { v: stop, span }| {
+            Ok(GradientSegment {
+                space: stop.segment.space,
+                easing: stop
+                    .segment
+                    .easing
+                    .clone()
+                    .map(|easing| easing.resolve(engine, context, *span))
+                    .transpose()?,
+            })
+        })
+        .collect()
+}
+
 /// Sample the stops at a given position.
-fn sample_stops(stops: &[(Color, Ratio)], mixing_space: ColorSpace, t: f64) -> Color {
+pub(super) fn sample_stops(
+    stops: &[(Color, Ratio)],
+    mixing_space: ColorSpace,
+    hue: HueInterpolation,
+    easing: &Easing,
+    t: f64,
+) -> Color {
+    let (low, t) = locate_stop(stops, t);
+    let t = easing.apply(t);
+    let (col_0, _) = stops[low - 1];
+    let (col_1, _) = stops[low];
+
+    Color::mix_iter(
+        [WeightedColor::new(col_0, 1.0 - t), WeightedColor::new(col_1, t)],
+        mixing_space,
+        hue,
+    )
+    .unwrap()
+}
+
+/// Finds the stop at which the segment of the gradient containing `t` ends
+/// and the position within that segment.
+fn locate_stop<C>(stops: &[(C, Ratio)], t: f64) -> (usize, f64) {
     let t = t.clamp(0.0, 1.0);
     let mut low = 0;
     let mut high = stops.len();
@@ -1247,13 +1865,32 @@ fn sample_stops(stops: &[(Color, Ratio)], mixing_space: ColorSpace, t: f64) -> C
         low = 1;
     }
 
-    let (col_0, pos_0) = stops[low - 1];
-    let (col_1, pos_1) = stops[low];
-    let t = (t - pos_0.get()) / (pos_1.get() - pos_0.get());
+    let pos_0 = stops[low - 1].1.get();
+    let pos_1 = stops[low].1.get();
+    (low, (t - pos_0) / (pos_1 - pos_0))
+}
 
-    Color::mix_iter(
-        [WeightedColor::new(col_0, 1.0 - t), WeightedColor::new(col_1, t)],
-        mixing_space,
-    )
-    .unwrap()
+/// Mixes two colors given as vecs of their components in the mixing space,
+/// like [`Color::mix_iter`] does for two colors.
+fn mix_vec4(
+    c0: [f32; 4],
+    c1: [f32; 4],
+    t: f64,
+    space: ColorSpace,
+    hue: HueInterpolation,
+) -> [f32; 4] {
+    let (w0, w1) = ((1.0 - t) as f32, t as f32);
+    let total = w0 + w1;
+    let mut m = [0.0; 4];
+    for i in 0..4 {
+        m[i] = (w0 * c0[i] + w1 * c1[i]) / total;
+    }
+
+    // Ensure that the hue circle is traversed in the right direction.
+    if let Some(index) = space.hue_index() {
+        let (h0, h1) = hue.adjust(c0[index], c1[index]);
+        m[index] = (w0 * h0 + w1 * h1) / total;
+    }
+
+    m
 }
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use qcms::{DataType, Intent, Profile, Transform};
+
+use crate::diag::{bail, StrResult};
+use crate::foundations::{cast, Bytes, Cast};
+use crate::util::hash128;
+use crate::visualize::color::SRGB_PROFILE;
+
+/// All profiles that were loaded so far, by the hash of their data and
+/// rendering intent.
+static PROFILES: Lazy<RwLock<HashMap<u128, &'static Repr>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// A user-supplied ICC profile.
+///
+/// Profiles are interned, so that colors referencing them stay `Copy`. This is
+/// fine because documents only use a handful of distinct profiles.
+#[derive(Copy, Clone)]
+pub struct IccProfile(&'static Repr);
+
+/// The internal representation of an ICC profile.
+struct Repr {
+    /// The raw profile data.
+    data: Bytes,
+    /// The hash of the profile data and the rendering intent.
+    hash: u128,
+    /// A unique name for the profile.
+    name: String,
+    /// The color space of the profile's device side.
+    space: IccSpace,
+    /// The rendering intent used when converting from the profile.
+    intent: RenderingIntent,
+    /// The transform from the profile to 8-bit sRGB.
+    to_srgb: Transform,
+}
+
+impl IccProfile {
+    /// Parse an ICC profile from its raw data.
+    ///
+    /// Conversions from the profile use the given rendering intent.
+    pub fn new(data: Bytes, intent: RenderingIntent) -> StrResult<Self> {
+        let hash = hash128(&(&data, intent));
+        if let Some(&repr) = PROFILES.read().unwrap().get(&hash) {
+            return Ok(Self(repr));
+        }
+
+        let space = match data.get(16..20) {
+            Some(b"GRAY") => IccSpace::Gray,
+            Some(b"RGB ") => IccSpace::Rgb,
+            Some(b"CMYK") => IccSpace::Cmyk,
+            Some(_) => bail!("ICC profile must be for a gray, RGB, or CMYK color space"),
+            None => bail!("failed to parse ICC profile"),
+        };
+
+        let Some(profile) = Profile::new_from_slice(&data, false) else {
+            bail!("failed to parse ICC profile");
+        };
+
+        let Some(to_srgb) = Transform::new_to(
+            &profile,
+            &SRGB_PROFILE,
+            space.data_type(),
+            DataType::RGB8,
+            intent.to_qcms(),
+        ) else {
+            bail!("ICC profile does not support conversion to sRGB");
+        };
+
+        // Create a new entry forever by leaking it. Just like for interned
+        // strings, this is okay because profiles aren't created en masse. The
+        // name only depends on the data, so that a profile used with different
+        // intents is embedded only once.
+        let name = format!("icc{:032x}", hash128(&data));
+        let repr = Box::leak(Box::new(Repr { data, hash, name, space, intent, to_srgb }));
+        PROFILES.write().unwrap().insert(hash, repr);
+        Ok(Self(repr))
+    }
+
+    /// The raw profile data.
+    pub fn data(&self) -> &Bytes {
+        &self.0.data
+    }
+
+    /// A unique name for the profile, derived from its data.
+    pub fn name(&self) -> &'static str {
+        &self.0.name
+    }
+
+    /// The color space of the profile's device side.
+    pub fn space(&self) -> IccSpace {
+        self.0.space
+    }
+
+    /// The rendering intent used when converting from the profile.
+    pub fn intent(&self) -> RenderingIntent {
+        self.0.intent
+    }
+
+    /// Convert components in this profile's color space to 8-bit sRGB.
+    pub fn to_srgb(&self, components: &[f32]) -> [u8; 3] {
+        let src: Vec<u8> =
+            components.iter().map(|&v| (v * 255.0).round() as u8).collect();
+        let mut dest = [0; 3];
+        self.0.to_srgb.convert(&src, &mut dest);
+        dest
+    }
+}
+
+impl Debug for IccProfile {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "IccProfile({:?}, {:?}, {} bytes)",
+            self.0.space,
+            self.0.intent,
+            self.0.data.len()
+        )
+    }
+}
+
+impl Eq for IccProfile {}
+
+impl PartialEq for IccProfile {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.hash == other.0.hash
+    }
+}
+
+impl Hash for IccProfile {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u128(self.0.hash);
+    }
+}
+
+/// The color space of an ICC profile.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IccSpace {
+    /// A gray color space with one component.
+    Gray,
+    /// An RGB color space with three components.
+    Rgb,
+    /// A CMYK color space with four components.
+    Cmyk,
+}
+
+impl IccSpace {
+    /// The number of components of colors in this space.
+    pub fn channels(self) -> usize {
+        match self {
+            Self::Gray => 1,
+            Self::Rgb => 3,
+            Self::Cmyk => 4,
+        }
+    }
+
+    /// The qcms data type for a single color in this space.
+    fn data_type(self) -> DataType {
+        match self {
+            Self::Gray => DataType::Gray8,
+            Self::Rgb => DataType::RGB8,
+            Self::Cmyk => DataType::CMYK,
+        }
+    }
+}
+
+cast! {
+    IccProfile,
+    self => self.data().clone().into_value(),
+    v: Bytes => Self::new(v, RenderingIntent::Perceptual)?,
+}
+
+/// How colors are mapped when converting from one color space to another.
+///
+/// Color spaces cover different ranges of colors. The rendering intent
+/// decides how colors that the target can't reproduce are handled.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum RenderingIntent {
+    /// Compresses all colors to preserve their visual relationship. This is
+    /// usually best for photos.
+    #[default]
+    Perceptual,
+    /// Keeps colors that can be reproduced exactly and clips the others,
+    /// relative to the white point of the target. This is usually best for
+    /// proofs of spot colors and logos.
+    RelativeColorimetric,
+    /// Keeps colors vivid at the expense of accuracy. This is usually best
+    /// for charts.
+    Saturation,
+    /// Like relative colorimetric, but also simulates the white point of the
+    /// source, e.g. the paper color of a press condition.
+    AbsoluteColorimetric,
+}
+
+impl RenderingIntent {
+    /// The corresponding qcms intent.
+    fn to_qcms(self) -> Intent {
+        match self {
+            Self::Perceptual => Intent::Perceptual,
+            Self::RelativeColorimetric => Intent::RelativeColorimetric,
+            Self::Saturation => Intent::Saturation,
+            Self::AbsoluteColorimetric => Intent::AbsoluteColorimetric,
+        }
+    }
+}
@@ -11,13 +11,13 @@ use std::fmt::{self, Debug, Formatter};
 use std::sync::Arc;
 
 use comemo::Tracked;
-use ecow::EcoString;
+use ecow::{eco_format, EcoString};
 
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, func, scope, Bytes, Cast, Content, NativeElement, Packed, Resolve, Smart,
-    StyleChain,
+    cast, elem, func, scope, Bytes, Cast, Content, Context, Func, NativeElement, Packed,
+    Resolve, Smart, StyleChain,
 };
 use crate::layout::{
     Abs, Axes, FixedAlignment, Frame, FrameItem, LayoutSingle, Length, Point, Regions,
@@ -28,7 +28,7 @@ use crate::model::Figurable;
 use crate::syntax::{Span, Spanned};
 use crate::text::{families, Lang, LocalName, Region};
 use crate::util::{option_eq, LazyHash};
-use crate::visualize::Path;
+use crate::visualize::{Color, Path};
 use crate::World;
 
 /// A raster or vector graphic.
@@ -99,6 +99,7 @@ pub struct ImageElem {
 }
 
 #[scope]
+#[allow(clippy::too_many_arguments)]
 impl ImageElem {
     /// Decode a raster or vector graphic from bytes or a string.
     ///
@@ -152,6 +153,140 @@ impl ImageElem {
         }
         Ok(elem.pack().spanned(span))
     }
+
+    /// Generate a raster graphic by computing the color of each pixel.
+    ///
+    /// The function is called once for each pixel with its horizontal and
+    /// vertical position (starting at zero in the top-left corner) and must
+    /// return the pixel's [color]($color). This is useful for procedural
+    /// textures, fractals, or heatmaps.
+    ///
+    /// ```example
+    /// #image.generate(
+    ///   64, 64,
+    ///   (x, y) => color.mix(
+    ///     (red, x), (blue, y), (white, 1),
+    ///     space: rgb,
+    ///   ),
+    ///   width: 50%,
+    /// )
+    /// ```
+    #[func]
+    pub fn generate(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The call span of this function.
+        span: Span,
+        /// The width of the image in pixels.
+        pixel_width: Spanned<u32>,
+        /// The height of the image in pixels.
+        pixel_height: Spanned<u32>,
+        /// A function that receives the position of a pixel as two integers
+        /// and returns its color.
+        pixel: Func,
+        /// The width of the image.
+        #[named]
+        width: Option<Smart<Rel<Length>>>,
+        /// The height of the image.
+        #[named]
+        height: Option<Smart<Rel<Length>>>,
+        /// A text describing the image.
+        #[named]
+        alt: Option<Option<EcoString>>,
+        /// How the image should adjust itself to a given area.
+        #[named]
+        fit: Option<ImageFit>,
+    ) -> SourceResult<Content> {
+        for size in [pixel_width, pixel_height] {
+            if size.v == 0 {
+                bail!(size.span, "image must be at least one pixel wide and high");
+            }
+        }
+
+        let (w, h) = (pixel_width.v, pixel_height.v);
+        let Some(len) = (w as usize)
+            .checked_mul(h as usize)
+            .filter(|&len| len <= Self::MAX_GENERATED_PIXELS)
+        else {
+            bail!(span, "image is too large");
+        };
+
+        let mut pixels = Vec::with_capacity(len * 4);
+        for y in 0..h {
+            for x in 0..w {
+                let color =
+                    pixel.call(engine, context, [x, y])?.cast::<Color>().at(span)?;
+                pixels.extend(color.to_rgb().to_vec4_u8());
+            }
+        }
+
+        let data = encode_png(w, h, &pixels).at(span)?;
+        let mut elem = ImageElem::new(EcoString::new(), Readable::Bytes(data));
+        elem.push_format(Smart::Custom(ImageFormat::Raster(RasterFormat::Png)));
+        if let Some(width) = width {
+            elem.push_width(width);
+        }
+        if let Some(height) = height {
+            elem.push_height(height);
+        }
+        if let Some(alt) = alt {
+            elem.push_alt(alt);
+        }
+        if let Some(fit) = fit {
+            elem.push_fit(fit);
+        }
+        Ok(elem.pack().spanned(span))
+    }
+}
+
+impl ImageElem {
+    /// The maximum number of pixels of a generated image.
+    const MAX_GENERATED_PIXELS: usize = 1 << 24;
+}
+
+/// Encode RGBA pixels as a PNG image.
+fn encode_png(width: u32, height: u32, pixels: &[u8]) -> StrResult<Bytes> {
+    let mut data = vec![];
+    let mut encoder = png::Encoder::new(&mut data, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(pixels))
+        .map_err(|err| eco_format!("failed to encode image ({err})"))?;
+    Ok(data.into())
+}
+
+impl Packed<ImageElem> {
+    /// Takes the format that was explicitly defined, or parses the extension,
+    /// or tries to detect the format.
+    pub fn determine_format(&self, styles: StyleChain) -> StrResult<ImageFormat> {
+        if let Smart::Custom(format) = self.format(styles) {
+            return Ok(format);
+        }
+
+        let ext = std::path::Path::new(self.path().as_str())
+            .extension()
+            .and_then(OsStr::to_str)
+            .unwrap_or_default()
+            .to_lowercase();
+
+        Ok(match ext.as_str() {
+            "png" => ImageFormat::Raster(RasterFormat::Png),
+            "jpg" | "jpeg" => ImageFormat::Raster(RasterFormat::Jpg),
+            "gif" => ImageFormat::Raster(RasterFormat::Gif),
+            "svg" | "svgz" => ImageFormat::Vector(VectorFormat::Svg),
+            _ => match self.data() {
+                Readable::Str(_) => ImageFormat::Vector(VectorFormat::Svg),
+                Readable::Bytes(bytes) => match RasterFormat::detect(bytes) {
+                    Some(f) => ImageFormat::Raster(f),
+                    None => bail!("unknown image format"),
+                },
+            },
+        })
+    }
 }
 
 impl LayoutSingle for Packed<ImageElem> {
@@ -162,34 +297,8 @@ impl LayoutSingle for Packed<ImageElem> {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Frame> {
-        // Take the format that was explicitly defined, or parse the extension,
-        // or try to detect the format.
         let data = self.data();
-        let format = match self.format(styles) {
-            Smart::Custom(v) => v,
-            Smart::Auto => {
-                let ext = std::path::Path::new(self.path().as_str())
-                    .extension()
-                    .and_then(OsStr::to_str)
-                    .unwrap_or_default()
-                    .to_lowercase();
-
-                match ext.as_str() {
-                    "png" => ImageFormat::Raster(RasterFormat::Png),
-                    "jpg" | "jpeg" => ImageFormat::Raster(RasterFormat::Jpg),
-                    "gif" => ImageFormat::Raster(RasterFormat::Gif),
-                    "svg" | "svgz" => ImageFormat::Vector(VectorFormat::Svg),
-                    _ => match &data {
-                        Readable::Str(_) => ImageFormat::Vector(VectorFormat::Svg),
-                        Readable::Bytes(bytes) => match RasterFormat::detect(bytes) {
-                            Some(f) => ImageFormat::Raster(f),
-                            None => bail!(self.span(), "unknown image format"),
-                        },
-                    },
-                }
-            }
-        };
-
+        let format = self.determine_format(styles).at(self.span())?;
         let image = Image::with_fonts(
             data.clone().into(),
             format,
@@ -2,10 +2,10 @@ use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{elem, Packed, StyleChain};
 use crate::layout::{
-    Abs, Angle, Axes, Frame, FrameItem, LayoutSingle, Length, Regions, Rel, Size,
+    Abs, Angle, Axes, Frame, FrameItem, LayoutSingle, Length, Point, Regions, Rel, Size,
 };
 use crate::util::Numeric;
-use crate::visualize::{Geometry, Stroke};
+use crate::visualize::{direction, Geometry, Marker, Stroke};
 
 /// A line from one point to another.
 ///
@@ -56,6 +56,31 @@ pub struct LineElem {
     #[resolve]
     #[fold]
     pub stroke: Stroke,
+
+    /// The marker to place at the start of the line.
+    ///
+    /// The marker is drawn with the stroke's paint and scales with its
+    /// thickness. For arrowheads, the line is shortened so that it does not
+    /// poke through the tip.
+    ///
+    /// ```example
+    /// #set line(length: 100%)
+    /// #stack(
+    ///   spacing: 1em,
+    ///   line(marker-end: "arrow"),
+    ///   line(stroke: 2pt + blue, marker-start: "dot", marker-end: "stealth"),
+    ///   line(marker-start: "bar", marker-mid: "diamond", marker-end: "bar"),
+    /// )
+    /// ```
+    pub marker_start: Option<Marker>,
+
+    /// The marker to place at the middle of the line. See
+    /// [`marker-start`]($line.marker-start) for the available markers.
+    pub marker_mid: Option<Marker>,
+
+    /// The marker to place at the end of the line. See
+    /// [`marker-start`]($line.marker-start) for the available markers.
+    pub marker_end: Option<Marker>,
 }
 
 impl LayoutSingle for Packed<LineElem> {
@@ -87,8 +112,41 @@ impl LayoutSingle for Packed<LineElem> {
         }
 
         let mut frame = Frame::soft(target);
-        let shape = Geometry::Line(delta.to_point()).stroked(stroke);
-        frame.push(start.to_point(), FrameItem::Shape(shape, self.span()));
+        let marker_start = self.marker_start(styles);
+        let marker_mid = self.marker_mid(styles);
+        let marker_end = self.marker_end(styles);
+
+        // Shorten the line so that it ends within the markers.
+        let start = start.to_point();
+        let delta = delta.to_point();
+        let length = delta.hypot();
+        let retract = |marker: Option<Marker>| {
+            marker.map_or(Abs::zero(), |m| m.retraction(stroke.thickness))
+        };
+        let (front, back) = (retract(marker_start), retract(marker_end));
+        let (from, line) = if length > front + back {
+            let unit = delta / length.to_raw();
+            (start + unit * front.to_raw(), delta - unit * (front + back).to_raw())
+        } else {
+            (start, delta)
+        };
+
+        let angle = direction(delta);
+        let markers = [
+            (marker_start, start, angle + Angle::deg(180.0)),
+            (marker_mid, start + delta / 2.0, angle),
+            (marker_end, start + delta, angle),
+        ];
+
+        let shape = Geometry::Line(line).stroked(stroke.clone());
+        frame.push(from, FrameItem::Shape(shape, self.span()));
+        for (marker, pos, angle) in markers {
+            if let Some(marker) = marker {
+                let shape = marker.shape(&stroke, pos, angle);
+                frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+            }
+        }
+
         Ok(frame)
     }
 }
@@ -0,0 +1,111 @@
+use crate::foundations::Cast;
+use crate::layout::{Abs, Angle, Point, Transform};
+use crate::visualize::{FixedStroke, Geometry, Path, PathItem, Shape};
+
+/// A marker that can be placed at the start, the end, or the vertices of a
+/// stroked [`line`] or [`path`].
+///
+/// Markers are drawn with the paint of the stroke and scale with its
+/// thickness.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Marker {
+    /// A filled triangular arrowhead.
+    Arrow,
+    /// A filled arrowhead with a notched back.
+    Stealth,
+    /// A filled circle centered on the point.
+    Dot,
+    /// A filled square centered on the point.
+    Square,
+    /// A filled diamond centered on the point.
+    Diamond,
+    /// A bar perpendicular to the line.
+    Bar,
+}
+
+impl Marker {
+    /// How much the stroke should be shortened at a point carrying this marker
+    /// so that it does not protrude through the marker's tip.
+    pub fn retraction(self, thickness: Abs) -> Abs {
+        match self {
+            Self::Arrow => 3.0 * thickness,
+            Self::Stealth => 4.0 * thickness,
+            Self::Dot | Self::Square | Self::Diamond | Self::Bar => Abs::zero(),
+        }
+    }
+
+    /// Create the marker's shape for a marker at `pos` that points into the
+    /// direction of `angle`.
+    pub fn shape(self, stroke: &FixedStroke, pos: Point, angle: Angle) -> Shape {
+        let u = stroke.thickness;
+        let point = |x: f64, y: f64| Point::new(x * u, y * u);
+
+        let mut path = Path::new();
+        match self {
+            Self::Arrow => {
+                path.move_to(point(0.0, 0.0));
+                path.line_to(point(-6.0, 2.5));
+                path.line_to(point(-6.0, -2.5));
+            }
+            Self::Stealth => {
+                path.move_to(point(0.0, 0.0));
+                path.line_to(point(-6.0, 3.0));
+                path.line_to(point(-4.0, 0.0));
+                path.line_to(point(-6.0, -3.0));
+            }
+            Self::Dot => {
+                // https://stackoverflow.com/a/2007782
+                let r = 2.0;
+                let m = 0.551784 * r;
+                path.move_to(point(-r, 0.0));
+                path.cubic_to(point(-r, -m), point(-m, -r), point(0.0, -r));
+                path.cubic_to(point(m, -r), point(r, -m), point(r, 0.0));
+                path.cubic_to(point(r, m), point(m, r), point(0.0, r));
+                path.cubic_to(point(-m, r), point(-r, m), point(-r, 0.0));
+            }
+            Self::Square => {
+                path.move_to(point(-2.0, -2.0));
+                path.line_to(point(2.0, -2.0));
+                path.line_to(point(2.0, 2.0));
+                path.line_to(point(-2.0, 2.0));
+            }
+            Self::Diamond => {
+                path.move_to(point(-3.0, 0.0));
+                path.line_to(point(0.0, -2.5));
+                path.line_to(point(3.0, 0.0));
+                path.line_to(point(0.0, 2.5));
+            }
+            Self::Bar => {
+                path.move_to(point(-0.5, -3.5));
+                path.line_to(point(0.5, -3.5));
+                path.line_to(point(0.5, 3.5));
+                path.line_to(point(-0.5, 3.5));
+            }
+        }
+        path.close_path();
+
+        let ts = Transform::translate(pos.x, pos.y).pre_concat(Transform::rotate(angle));
+        let path = Path(
+            path.0
+                .into_iter()
+                .map(|item| match item {
+                    PathItem::MoveTo(p) => PathItem::MoveTo(p.transform(ts)),
+                    PathItem::LineTo(p) => PathItem::LineTo(p.transform(ts)),
+                    PathItem::CubicTo(a, b, c) => PathItem::CubicTo(
+                        a.transform(ts),
+                        b.transform(ts),
+                        c.transform(ts),
+                    ),
+                    PathItem::ClosePath => PathItem::ClosePath,
+                })
+                .collect(),
+        );
+
+        Geometry::Path(path).filled(stroke.paint.clone())
+    }
+}
+
+/// The angle of the direction given by a vector.
+pub(crate) fn direction(vector: Point) -> Angle {
+    Angle::rad(vector.y.to_raw().atan2(vector.x.to_raw()))
+}
@@ -1,25 +1,51 @@
 //! Drawing and visualization.
 
+pub mod chart;
+pub mod geo;
+pub mod music;
+
+mod arc;
+mod barcode;
+mod blend;
+mod blur;
 mod color;
+mod connector;
+mod easing;
 mod gradient;
+mod icc;
 mod image;
 mod line;
+mod marker;
+mod opacity;
 mod paint;
 mod path;
 mod pattern;
 mod polygon;
+mod qrcode;
 mod shape;
+mod sparkline;
 mod stroke;
 
+pub use self::arc::*;
+pub use self::barcode::*;
+pub use self::blend::*;
+pub use self::blur::*;
 pub use self::color::*;
+pub use self::connector::*;
+pub use self::easing::*;
 pub use self::gradient::*;
+pub use self::icc::*;
 pub use self::image::*;
 pub use self::line::*;
+pub use self::marker::*;
+pub use self::opacity::*;
 pub use self::paint::*;
 pub use self::path::*;
 pub use self::pattern::*;
 pub use self::polygon::*;
+pub use self::qrcode::*;
 pub use self::shape::*;
+pub use self::sparkline::*;
 pub use self::stroke::*;
 
 use crate::foundations::{category, Category, Scope};
@@ -47,4 +73,19 @@ pub(super) fn define(global: &mut Scope) {
     global.define_elem::<CircleElem>();
     global.define_elem::<PolygonElem>();
     global.define_elem::<PathElem>();
+    global.define_elem::<ArcElem>();
+    global.define_elem::<PieElem>();
+    global.define_elem::<RingElem>();
+    global.define_elem::<BlendElem>();
+    global.define_elem::<BlurElem>();
+    global.define_elem::<OpacityElem>();
+    global.define_elem::<ConnectorElem>();
+    global.define_elem::<QrCodeElem>();
+    global.define_elem::<BarcodeElem>();
+    global.define_elem::<SparklineElem>();
+    global.define_elem::<BulletElem>();
+    global.define_elem::<ProgressElem>();
+    global.define_module(chart::module());
+    global.define_module(geo::module());
+    global.define_module(music::module());
 }
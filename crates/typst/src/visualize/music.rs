@@ -0,0 +1,570 @@
+//! Music notation.
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, dict, elem, Cast, Content, Dict, Module, Packed, Scope, Str, StyleChain, Styles,
+};
+use crate::layout::{
+    Abs, Axes, Frame, FrameItem, LayoutMultiple, LayoutSingle, Length, Point, Regions,
+    Size,
+};
+use crate::syntax::Span;
+use crate::text::{
+    BottomEdge, BottomEdgeMetric, FontFamily, FontList, TextElem, TextSize, TopEdge,
+    TopEdgeMetric,
+};
+use crate::visualize::{Geometry, Paint};
+use crate::World;
+
+/// A module with definitions for music notation.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define_elem::<StaffElem>();
+    Module::new("music", scope)
+}
+
+/// A staff with notes, rests, and bar lines.
+///
+/// The staff lines, ledger lines, stems, and bar lines are drawn directly,
+/// while clefs, noteheads, accidentals, flags, and rests are taken from a
+/// [SMuFL](https://www.smufl.org/) font like Bravura. Such a font must be
+/// available, otherwise laying out the staff fails with an error.
+///
+/// Each item is a string:
+/// - A note consists of a letter from `c` to `b`, an optional accidental (`#`
+///   for sharp, `b` for flat, or `n` for natural), and an octave, where `c4`
+///   is the middle C. A duration of `1` (whole), `2` (half), `4` (quarter),
+///   `8` (eighth), or `16` (sixteenth) can follow after a slash. It defaults
+///   to a quarter.
+/// - A rest is written as `r`, optionally followed by a duration.
+/// - A bar line is written as `|`.
+///
+/// To print a chord symbol above a note or rest, pass a dictionary with the
+/// `note` and the `chord`.
+///
+/// # Example
+/// ```typ
+/// #music.staff(
+///   (note: "c4/2", chord: [C]), "e4", "g4", "|",
+///   (note: "f4", chord: [F]), "a4/8", "c5/8", "bb4", "r", "|",
+///   (note: "c5/1", chord: [C]),
+/// )
+/// ```
+#[elem(LayoutSingle)]
+pub struct StaffElem {
+    /// The notes, rests, and bar lines on the staff.
+    #[variadic]
+    pub items: Vec<StaffItem>,
+
+    /// The clef at the start of the staff.
+    ///
+    /// ```typ
+    /// #music.staff(clef: "bass", "c3", "g2", "c2/2")
+    /// ```
+    #[default(Some(Clef::Treble))]
+    pub clef: Option<Clef>,
+
+    /// The distance between two adjacent staff lines.
+    ///
+    /// The glyphs are scaled accordingly, so this determines the size of the
+    /// whole staff.
+    #[resolve]
+    #[default(Abs::pt(5.0).into())]
+    pub space: Length,
+
+    /// The horizontal space after a quarter note or rest.
+    ///
+    /// Longer durations get more space and shorter ones less.
+    #[resolve]
+    #[default(Abs::pt(10.0).into())]
+    pub spacing: Length,
+
+    /// The SMuFL font to take the glyphs from.
+    #[default(FontList(vec![FontFamily::new("Bravura")]))]
+    pub font: FontList,
+}
+
+impl LayoutSingle for Packed<StaffElem> {
+    #[typst_macros::time(name = "music.staff", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Frame> {
+        let span = self.span();
+        let sp = self.space(styles);
+        let spacing = self.spacing(styles);
+        let clef = self.clef(styles);
+        let paint = TextElem::fill_in(styles).as_decoration();
+        let font = self.font(styles);
+
+        // Without a SMuFL font, the glyphs would fall back to other fonts,
+        // which usually don't cover them or have unrelated glyphs at their
+        // private use code points.
+        let book = engine.world.book();
+        let available = font.into_iter().any(|family| {
+            book.select_family(family.as_str()).any(|id| {
+                book.info(id).is_some_and(|info| {
+                    SMUFL_PROBE.chars().all(|c| info.coverage.contains(c as u32))
+                })
+            })
+        });
+        if !available {
+            bail!(
+                span, "no SMuFL font is available";
+                hint: "install a SMuFL font like Bravura or set `font` to one",
+            );
+        }
+
+        // In SMuFL fonts, the em size equals the height of the staff.
+        let mut local = Styles::new();
+        local.set(TextElem::set_font(font));
+        local.set(TextElem::set_size(TextSize((sp * 4.0).into())));
+        local.set(TextElem::set_top_edge(TopEdge::Metric(TopEdgeMetric::Bounds)));
+        local
+            .set(TextElem::set_bottom_edge(BottomEdge::Metric(BottomEdgeMetric::Bounds)));
+        let glyph_styles = styles.chain(&local);
+
+        let mut staff = Staff::new(sp, paint);
+        let glyph = |engine: &mut Engine, c: char| {
+            layout_content(engine, glyph_styles, TextElem::packed(c).spanned(span))
+        };
+
+        let mut x = sp;
+        if let Some(clef) = clef {
+            let frame = glyph(engine, clef.glyph())?;
+            let width = frame.width();
+            staff.glyph(x, clef.line(), frame);
+            x += width + sp;
+        }
+
+        let bottom = clef.unwrap_or(Clef::Treble).bottom();
+        let mut chords = vec![];
+        for item in self.items() {
+            if let Some(chord) = &item.chord {
+                chords.push((x, layout_content(engine, styles, chord.clone())?));
+            }
+
+            match item.entry {
+                StaffEntry::Bar => {
+                    staff.rect(Point::with_x(x), Size::new(sp * BAR_THICKNESS, sp * 4.0));
+                    x += sp;
+                }
+                StaffEntry::Rest(duration) => {
+                    let frame = glyph(engine, duration.rest())?;
+                    let width = frame.width();
+                    let line = if duration == Duration::Whole { 6 } else { 4 };
+                    staff.glyph(x, line, frame);
+                    x += width + spacing * duration.spacing();
+                }
+                StaffEntry::Note(pitch, duration) => {
+                    let pos = pitch.step() - bottom;
+                    if let Some(accidental) = pitch.accidental {
+                        let frame = glyph(engine, accidental.glyph())?;
+                        let width = frame.width();
+                        staff.glyph(x, pos, frame);
+                        x += width + sp * 0.2;
+                    }
+
+                    let head = glyph(engine, duration.notehead())?;
+                    let width = head.width();
+                    staff.glyph(x, pos, head);
+                    staff.ledger_lines(x, width, pos);
+
+                    if duration != Duration::Whole {
+                        // Stems point away from the middle line and reach at
+                        // least to it.
+                        let up = pos < 4;
+                        let y = staff.y(pos);
+                        let length = (sp * STEM_LENGTH).max((y - staff.y(4)).abs());
+                        let thickness = sp * STEM_THICKNESS;
+                        let (stem_x, end) = if up {
+                            (x + width - thickness, y - length)
+                        } else {
+                            (x, y + length)
+                        };
+                        staff.rect(
+                            Point::new(stem_x, y.min(end)),
+                            Size::new(thickness, length),
+                        );
+                        if let Some(flag) = duration.flag(up) {
+                            let frame = glyph(engine, flag)?;
+                            staff.glyph_at(Point::new(stem_x, end), frame);
+                        }
+                    }
+
+                    x += width + spacing * duration.spacing();
+                }
+            }
+        }
+
+        // Chord symbols are placed on a common baseline above the staff.
+        let baseline = staff.top.min(Abs::zero()) - sp;
+        for (x, frame) in chords {
+            staff.glyph_at(Point::new(x, baseline), frame);
+        }
+
+        Ok(staff.finish(x.max(sp)))
+    }
+}
+
+/// Glyphs that every SMuFL font has, namely a G clef, a black notehead, a
+/// sharp, and a quarter rest.
+const SMUFL_PROBE: &str = "\u{E050}\u{E0A4}\u{E262}\u{E4E5}";
+
+/// Length of a stem, in staff spaces.
+const STEM_LENGTH: f64 = 3.5;
+
+/// Thickness of a stem, in staff spaces.
+const STEM_THICKNESS: f64 = 0.12;
+
+/// Thickness of a staff line or ledger line, in staff spaces.
+const LINE_THICKNESS: f64 = 0.13;
+
+/// Thickness of a bar line, in staff spaces.
+const BAR_THICKNESS: f64 = 0.16;
+
+/// How far a ledger line extends beyond a notehead, in staff spaces.
+const LEDGER_EXTENSION: f64 = 0.4;
+
+/// Collects the marks of a staff relative to its top line.
+struct Staff {
+    /// The distance between two staff lines.
+    sp: Abs,
+    /// The paint of lines and stems.
+    paint: Paint,
+    /// The marks, which may extend beyond the staff.
+    frame: Frame,
+    /// The topmost extent of the marks.
+    top: Abs,
+    /// The bottommost extent of the marks.
+    bottom: Abs,
+}
+
+impl Staff {
+    fn new(sp: Abs, paint: Paint) -> Self {
+        Self {
+            sp,
+            paint,
+            frame: Frame::soft(Size::zero()),
+            top: Abs::zero(),
+            bottom: 4.0 * sp,
+        }
+    }
+
+    /// The vertical offset of a position, given in half spaces above the
+    /// bottom line.
+    fn y(&self, pos: i32) -> Abs {
+        self.sp * (f64::from(8 - pos) / 2.0)
+    }
+
+    /// Place a glyph with its baseline at the given position.
+    fn glyph(&mut self, x: Abs, pos: i32, frame: Frame) {
+        let y = self.y(pos);
+        self.glyph_at(Point::new(x, y), frame);
+    }
+
+    /// Place a frame with its baseline origin at the given point.
+    fn glyph_at(&mut self, origin: Point, frame: Frame) {
+        let pos = origin - Point::with_y(frame.baseline());
+        self.top.set_min(pos.y);
+        self.bottom.set_max(pos.y + frame.height());
+        self.frame.push_frame(pos, frame);
+    }
+
+    /// Draw a filled rectangle.
+    fn rect(&mut self, pos: Point, size: Size) {
+        self.top.set_min(pos.y);
+        self.bottom.set_max(pos.y + size.y);
+        let shape = Geometry::Rect(size).filled(self.paint.clone());
+        self.frame.push(pos, FrameItem::Shape(shape, Span::detached()));
+    }
+
+    /// Draw the ledger lines needed for a note at the given position.
+    fn ledger_lines(&mut self, x: Abs, width: Abs, pos: i32) {
+        let lines = if pos <= -2 {
+            (pos..=-2).filter(|p| p % 2 == 0).collect()
+        } else if pos >= 10 {
+            (10..=pos).filter(|p| p % 2 == 0).collect()
+        } else {
+            vec![]
+        };
+
+        let thickness = LINE_THICKNESS * self.sp;
+        let extension = LEDGER_EXTENSION * self.sp;
+        for line in lines {
+            let y = self.y(line) - thickness / 2.0;
+            let size = Size::new(width + 2.0 * extension, thickness);
+            self.rect(Point::new(x - extension, y), size);
+        }
+    }
+
+    /// Draw the staff lines and produce the final frame.
+    fn finish(mut self, width: Abs) -> Frame {
+        let thickness = LINE_THICKNESS * self.sp;
+        for line in 0..5 {
+            let y = self.y(2 * line) - thickness / 2.0;
+            self.rect(Point::with_y(y), Size::new(width, thickness));
+        }
+
+        let mut frame = Frame::soft(Size::new(width, self.bottom - self.top));
+        frame.push_frame(Point::with_y(-self.top), self.frame);
+        frame
+    }
+}
+
+/// Lay out content on a single line.
+fn layout_content(
+    engine: &mut Engine,
+    styles: StyleChain,
+    content: Content,
+) -> SourceResult<Frame> {
+    let pod = Regions::one(Axes::splat(Abs::inf()), Axes::splat(false));
+    Ok(content.layout(engine, styles, pod)?.into_frame())
+}
+
+/// A clef.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Clef {
+    /// The G clef on the second line, where `e4` lies on the bottom line.
+    Treble,
+    /// The F clef on the fourth line, where `g2` lies on the bottom line.
+    Bass,
+    /// The C clef on the middle line, where `f3` lies on the bottom line.
+    Alto,
+}
+
+impl Clef {
+    /// The SMuFL glyph of the clef.
+    fn glyph(self) -> char {
+        match self {
+            Self::Treble => '\u{E050}',
+            Self::Bass => '\u{E062}',
+            Self::Alto => '\u{E05C}',
+        }
+    }
+
+    /// The position the clef is registered on, in half spaces above the
+    /// bottom line.
+    fn line(self) -> i32 {
+        match self {
+            Self::Treble => 2,
+            Self::Bass => 6,
+            Self::Alto => 4,
+        }
+    }
+
+    /// The diatonic step of the note on the bottom line.
+    fn bottom(self) -> i32 {
+        match self {
+            Self::Treble => 4 * 7 + 2,
+            Self::Bass => 2 * 7 + 4,
+            Self::Alto => 3 * 7 + 3,
+        }
+    }
+}
+
+/// An item on a staff.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct StaffItem {
+    /// The note, rest, or bar line.
+    entry: StaffEntry,
+    /// The chord symbol to print above the item.
+    chord: Option<Content>,
+}
+
+cast! {
+    StaffItem,
+    self => match self.chord {
+        Some(chord) => dict! {
+            "note" => self.entry.notation(),
+            "chord" => chord,
+        }.into_value(),
+        None => self.entry.notation().into_value(),
+    },
+    v: Str => Self { entry: StaffEntry::parse(&v)?, chord: None },
+    mut v: Dict => {
+        let note: Str = v.take("note")?.cast()?;
+        let chord: Content = v.take("chord")?.cast()?;
+        v.finish(&["note", "chord"])?;
+        Self { entry: StaffEntry::parse(&note)?, chord: Some(chord) }
+    },
+}
+
+/// A note, rest, or bar line.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum StaffEntry {
+    Note(Pitch, Duration),
+    Rest(Duration),
+    Bar,
+}
+
+impl StaffEntry {
+    /// Parse an entry from its notation, like `f#5/8`.
+    fn parse(text: &str) -> StrResult<Self> {
+        if text == "|" {
+            return Ok(Self::Bar);
+        }
+
+        let (head, duration) = match text.split_once('/') {
+            Some((head, duration)) => (head, Duration::parse(duration)?),
+            None => (text, Duration::Quarter),
+        };
+
+        if head == "r" {
+            return Ok(Self::Rest(duration));
+        }
+
+        let Some(pitch) = Pitch::parse(head) else {
+            bail!("invalid note `{text}`, expected a note like `c4` or `f#5/8`");
+        };
+
+        Ok(Self::Note(pitch, duration))
+    }
+
+    /// The notation of the entry.
+    fn notation(self) -> EcoString {
+        let (head, duration) = match self {
+            Self::Bar => return "|".into(),
+            Self::Rest(duration) => ("r".into(), duration),
+            Self::Note(pitch, duration) => (pitch.notation(), duration),
+        };
+        match duration {
+            Duration::Quarter => head,
+            _ => eco_format!("{head}/{}", duration as u8),
+        }
+    }
+}
+
+/// The pitch of a note.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+struct Pitch {
+    /// The letter, from 0 for `c` to 6 for `b`.
+    letter: u8,
+    /// The accidental in front of the note.
+    accidental: Option<Accidental>,
+    /// The octave, where 4 is the one starting at the middle C.
+    octave: u8,
+}
+
+impl Pitch {
+    /// The letters of the notes in an octave.
+    const LETTERS: &'static str = "cdefgab";
+
+    /// Parse a pitch like `c4`, `f#5`, or `bb3`.
+    fn parse(text: &str) -> Option<Self> {
+        let mut chars = text.chars();
+        let letter = Self::LETTERS.find(chars.next()?.to_ascii_lowercase())? as u8;
+        let rest = chars.as_str();
+        let (accidental, octave) = match rest.chars().next()? {
+            '#' => (Some(Accidental::Sharp), &rest[1..]),
+            'b' => (Some(Accidental::Flat), &rest[1..]),
+            'n' => (Some(Accidental::Natural), &rest[1..]),
+            _ => (None, rest),
+        };
+        let octave = octave.parse().ok().filter(|&octave: &u8| octave <= 9)?;
+        Some(Self { letter, accidental, octave })
+    }
+
+    /// The number of diatonic steps above the lowest C.
+    fn step(self) -> i32 {
+        7 * i32::from(self.octave) + i32::from(self.letter)
+    }
+
+    /// The notation of the pitch.
+    fn notation(self) -> EcoString {
+        let letter = Self::LETTERS.as_bytes()[usize::from(self.letter)] as char;
+        let accidental = match self.accidental {
+            Some(Accidental::Sharp) => "#",
+            Some(Accidental::Flat) => "b",
+            Some(Accidental::Natural) => "n",
+            None => "",
+        };
+        eco_format!("{letter}{accidental}{}", self.octave)
+    }
+}
+
+/// An accidental in front of a note.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Accidental {
+    Sharp,
+    Flat,
+    Natural,
+}
+
+impl Accidental {
+    /// The SMuFL glyph of the accidental.
+    fn glyph(self) -> char {
+        match self {
+            Self::Flat => '\u{E260}',
+            Self::Natural => '\u{E261}',
+            Self::Sharp => '\u{E262}',
+        }
+    }
+}
+
+/// The duration of a note or rest.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Duration {
+    Whole = 1,
+    Half = 2,
+    Quarter = 4,
+    Eighth = 8,
+    Sixteenth = 16,
+}
+
+impl Duration {
+    /// Parse a duration from its denominator.
+    fn parse(text: &str) -> StrResult<Self> {
+        Ok(match text {
+            "1" => Self::Whole,
+            "2" => Self::Half,
+            "4" => Self::Quarter,
+            "8" => Self::Eighth,
+            "16" => Self::Sixteenth,
+            _ => bail!("note duration must be 1, 2, 4, 8, or 16"),
+        })
+    }
+
+    /// The space after a note of this duration, relative to a quarter note.
+    ///
+    /// Doubling the duration multiplies the space by the square root of two.
+    fn spacing(self) -> f64 {
+        (4.0 / f64::from(self as u8)).sqrt()
+    }
+
+    /// The SMuFL glyph of a notehead of this duration.
+    fn notehead(self) -> char {
+        match self {
+            Self::Whole => '\u{E0A2}',
+            Self::Half => '\u{E0A3}',
+            _ => '\u{E0A4}',
+        }
+    }
+
+    /// The SMuFL glyph of the flag at the end of a stem, if any.
+    fn flag(self, up: bool) -> Option<char> {
+        match (self, up) {
+            (Self::Eighth, true) => Some('\u{E240}'),
+            (Self::Eighth, false) => Some('\u{E241}'),
+            (Self::Sixteenth, true) => Some('\u{E242}'),
+            (Self::Sixteenth, false) => Some('\u{E243}'),
+            _ => None,
+        }
+    }
+
+    /// The SMuFL glyph of a rest of this duration.
+    fn rest(self) -> char {
+        match self {
+            Self::Whole => '\u{E4E3}',
+            Self::Half => '\u{E4E4}',
+            Self::Quarter => '\u{E4E5}',
+            Self::Eighth => '\u{E4E6}',
+            Self::Sixteenth => '\u{E4E7}',
+        }
+    }
+}
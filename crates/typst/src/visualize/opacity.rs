@@ -0,0 +1,55 @@
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, Packed, StyleChain};
+use crate::layout::{Axes, Frame, LayoutMultiple, LayoutSingle, Ratio, Regions};
+
+/// Makes content translucent as a whole.
+///
+/// Unlike colors with an alpha component, this applies to everything in the
+/// content at once: The content is first drawn into a single layer, which is
+/// then drawn with the given opacity. This makes it easy to create watermarks
+/// or to fade out parts of a mockup without recoloring them.
+///
+/// # Example
+/// ```example
+/// #box({
+///   place(rect(width: 60pt, height: 30pt, fill: aqua))
+///   opacity(50%, {
+///     place(dx: 10pt, dy: 5pt, circle(radius: 10pt, fill: red))
+///     place(dx: 25pt, dy: 5pt, circle(radius: 10pt, fill: blue))
+///   })
+///   h(60pt)
+///   v(30pt)
+/// })
+/// ```
+#[elem(LayoutSingle)]
+pub struct OpacityElem {
+    /// How opaque the content is, between `{0%}` (invisible) and `{100%}`
+    /// (unchanged).
+    #[required]
+    pub opacity: Ratio,
+
+    /// The content to make translucent.
+    #[required]
+    pub body: Content,
+}
+
+impl LayoutSingle for Packed<OpacityElem> {
+    #[typst_macros::time(name = "opacity", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Frame> {
+        let opacity = *self.opacity();
+        if !(0.0..=1.0).contains(&opacity.get()) {
+            bail!(self.span(), "opacity must be between 0% and 100%");
+        }
+
+        let pod = Regions::one(regions.base(), Axes::splat(false));
+        let mut frame = self.body().layout(engine, styles, pod)?.into_frame();
+        frame.opacity(opacity);
+        Ok(frame)
+    }
+}
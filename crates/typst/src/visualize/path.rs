@@ -1,15 +1,24 @@
-use kurbo::{CubicBez, ParamCurveExtrema};
+use ecow::{eco_format, EcoString};
+use kurbo::{
+    BezPath, CubicBez, ParamCurve, ParamCurveArclen, ParamCurveDeriv, ParamCurveExtrema,
+    PathEl, SvgParseError,
+};
+
+use comemo::Tracked;
 
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    array, cast, elem, Array, Packed, Reflect, Resolve, Smart, StyleChain,
+    array, cast, elem, func, scope, Array, Content, Context, Func, NativeElement, Packed,
+    Reflect, Repr, Resolve, Smart, Str, StyleChain,
 };
 use crate::layout::{
-    Abs, Axes, Fragment, Frame, FrameItem, LayoutMultiple, Length, Point, Regions, Rel,
-    Size,
+    Abs, Axes, Em, Fragment, Frame, FrameItem, LayoutMultiple, Length, Point, Regions,
+    Rel, Size,
 };
-use crate::visualize::{FixedStroke, Geometry, Paint, Shape, Stroke};
+use crate::syntax::{Span, Spanned};
+use crate::util::Numeric;
+use crate::visualize::{direction, FixedStroke, Geometry, Marker, Paint, Shape, Stroke};
 
 use PathVertex::{AllControlPoints, MirroredControlPoint, Vertex};
 
@@ -26,7 +35,7 @@ use PathVertex::{AllControlPoints, MirroredControlPoint, Vertex};
 ///   ((50%, 0pt), (40pt, 0pt)),
 /// )
 /// ```
-#[elem(LayoutMultiple)]
+#[elem(scope, LayoutMultiple)]
 pub struct PathElem {
     /// How to fill the path.
     ///
@@ -68,6 +77,340 @@ pub struct PathElem {
     ///   respectively).
     #[variadic]
     pub vertices: Vec<PathVertex>,
+
+    /// The marker to place at the start of the path. See the
+    /// [line's documentation]($line.marker-start) for more details.
+    ///
+    /// Markers are only drawn if the path is stroked. Closed paths have no
+    /// start and end, so only their mid marker is used.
+    ///
+    /// ```example
+    /// #path(
+    ///   stroke: 1.5pt + blue,
+    ///   marker-start: "bar",
+    ///   marker-mid: "dot",
+    ///   marker-end: "arrow",
+    ///   (0pt, 30pt),
+    ///   ((40pt, 0pt), (-15pt, 0pt)),
+    ///   (80pt, 30pt),
+    /// )
+    /// ```
+    pub marker_start: Option<Marker>,
+
+    /// The marker to place at each inner vertex of the path.
+    pub marker_mid: Option<Marker>,
+
+    /// The marker to place at the end of the path.
+    pub marker_end: Option<Marker>,
+}
+
+#[scope]
+#[allow(clippy::too_many_arguments)]
+impl PathElem {
+    /// Interpolates between two paths.
+    ///
+    /// Corresponding vertices and control points of the two paths are
+    /// interpolated linearly. If the paths have a different number of
+    /// vertices, the segments of the path with fewer vertices are subdivided
+    /// until both have the same number of vertices. The subdivision does not
+    /// change the path's shape.
+    ///
+    /// All other properties, like the fill and stroke, are taken from the
+    /// first path.
+    ///
+    /// ```example
+    /// #let a = path(
+    ///   closed: true,
+    ///   fill: blue,
+    ///   (0pt, 0pt), (30pt, 0pt), (30pt, 30pt), (0pt, 30pt),
+    /// )
+    /// #let b = path(
+    ///   closed: true,
+    ///   fill: blue,
+    ///   ((15pt, 0pt), (-8pt, 0pt)),
+    ///   ((30pt, 15pt), (0pt, -8pt)),
+    ///   ((15pt, 30pt), (8pt, 0pt)),
+    ///   ((0pt, 15pt), (0pt, 8pt)),
+    /// )
+    /// #for t in (0, 0.25, 0.5, 0.75, 1) {
+    ///   path.lerp(a, b, t)
+    ///   h(5pt)
+    /// }
+    /// ```
+    #[func(title = "Interpolate Paths")]
+    pub fn lerp(
+        /// The call span of this function.
+        span: Span,
+        /// The path to start from.
+        a: Content,
+        /// The path to end at.
+        b: Content,
+        /// How far to interpolate from the first to the second path. At `{0}`,
+        /// the result is the first path and at `{1}`, it is the second path.
+        /// Values outside of this range extrapolate.
+        t: f64,
+    ) -> StrResult<Content> {
+        let [a, b] = [&a, &b].map(|content| {
+            content.to_packed::<PathElem>().ok_or_else(|| {
+                eco_format!("expected path, found {}", content.elem().name())
+            })
+        });
+        let (a, b) = (a?, b?);
+
+        let closed = a.closed(StyleChain::default());
+        if closed != b.closed(StyleChain::default()) {
+            bail!("cannot interpolate between an open and a closed path");
+        }
+
+        let mut from = knots(a.vertices());
+        let mut to = knots(b.vertices());
+        let count = from.len().max(to.len());
+        for knots in [&mut from, &mut to] {
+            if knots.len() < count {
+                if knots.len() < 2 {
+                    bail!("cannot interpolate path with fewer than two vertices");
+                }
+                *knots = subdivide(knots, closed, count);
+            }
+        }
+
+        let vertices = from
+            .iter()
+            .zip(&to)
+            .map(|(x, y)| {
+                let vertex = mix(x.vertex, y.vertex, t);
+                let to = mix(x.to, y.to, t);
+                let from = mix(x.from, y.from, t);
+                AllControlPoints(
+                    vertex,
+                    to.zip_map(vertex, |a, b| a - b),
+                    from.zip_map(vertex, |a, b| a - b),
+                )
+            })
+            .collect();
+
+        let mut elem = a.as_ref().clone();
+        elem.push_vertices(vertices);
+        Ok(elem.pack().spanned(span))
+    }
+
+    /// Plots a function as a smooth path.
+    ///
+    /// The function is sampled adaptively: Regions where it curves strongly
+    /// are sampled more densely than regions where it is nearly straight. The
+    /// samples are then connected by Bézier curves that follow the function's
+    /// slope, so that even a few samples give an accurate and compact curve.
+    ///
+    /// The domain is mapped onto the path's `width` and the `range` of
+    /// function values onto its `height`, with larger values at the top.
+    /// Values outside of the range extend beyond the path's height.
+    ///
+    /// ```example
+    /// #set path(stroke: blue)
+    /// #path.plot(calc.sin, (0, 2 * calc.pi), width: 80pt, height: 30pt)
+    /// #path.plot(x => x * x, (-1, 1), width: 40pt, height: 30pt)
+    /// ```
+    #[func(title = "Plot Function")]
+    pub fn plot(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The call span of this function.
+        span: Span,
+        /// The function to plot. It receives a float and must return a
+        /// float.
+        function: Func,
+        /// The interval of inputs to plot, as an array of its start and end.
+        domain: Spanned<Interval>,
+        /// How many evenly spaced samples to start with before refining.
+        ///
+        /// If set to `{auto}`, a small number of samples is used, which is
+        /// enough for most functions. Functions with features narrower than
+        /// the spacing of the initial samples need more.
+        #[named]
+        #[default(Spanned::new(Smart::Auto, Span::detached()))]
+        samples: Spanned<Smart<usize>>,
+        /// The interval of function values to show, as an array of the values
+        /// at the bottom and at the top.
+        ///
+        /// If set to `{auto}`, the smallest and largest sampled values are
+        /// used.
+        #[named]
+        #[default(Spanned::new(Smart::Auto, Span::detached()))]
+        range: Spanned<Smart<Interval>>,
+        /// The width of the plot.
+        #[named]
+        #[default(Rel::one())]
+        width: Rel<Length>,
+        /// The height of the plot.
+        #[named]
+        #[default(Em::new(4.0).into())]
+        height: Rel<Length>,
+        /// How to fill the path. See the general
+        /// [path's documentation]($path.fill) for more details.
+        #[named]
+        fill: Option<Option<Paint>>,
+        /// How to stroke the path. See the general
+        /// [path's documentation]($path.stroke) for more details.
+        #[named]
+        stroke: Option<Smart<Option<Stroke>>>,
+    ) -> SourceResult<Content> {
+        let Interval(start, end) = domain.v;
+        if start == end {
+            bail!(domain.span, "domain must not be empty");
+        }
+
+        let samples = match samples.v {
+            Smart::Auto => DEFAULT_PLOT_SAMPLES,
+            Smart::Custom(0) => bail!(samples.span, "number of samples must be positive"),
+            Smart::Custom(v) if v > MAX_PLOT_POINTS => {
+                bail!(samples.span, "number of samples must be at most {MAX_PLOT_POINTS}")
+            }
+            Smart::Custom(v) => v,
+        };
+
+        let mut eval = |x: f64| -> SourceResult<f64> {
+            let y = function.call(engine, context, [x])?.cast::<f64>().at(span)?;
+            if !y.is_finite() {
+                bail!(span, "function returned {} for {}", y.repr(), x.repr());
+            }
+            Ok(y)
+        };
+
+        let points = sample_plot(&mut eval, start, end, samples)?;
+        let (bottom, top) = match range.v {
+            Smart::Auto => {
+                points.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+                    (lo.min(p.y), hi.max(p.y))
+                })
+            }
+            Smart::Custom(Interval(bottom, top)) => {
+                if bottom == top {
+                    bail!(range.span, "range must not be empty");
+                }
+                (bottom, top)
+            }
+        };
+
+        // Map from function coordinates to the path's coordinate system. A
+        // flat function is placed in the vertical middle.
+        let flat = top == bottom;
+        let map = |dx: f64, dy: f64| {
+            let dy = if flat { 0.0 } else { -dy / (top - bottom) };
+            Axes::new(width * (dx / (end - start)), height * dy)
+        };
+        let vertices = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let y = if flat { 0.5 } else { (top - p.y) / (top - bottom) };
+                let vertex =
+                    Axes::new(width * ((p.x - start) / (end - start)), height * y);
+                let before = if i > 0 { p.x - points[i - 1].x } else { 0.0 };
+                let after = points.get(i + 1).map_or(0.0, |q| q.x - p.x);
+                AllControlPoints(
+                    vertex,
+                    map(-before / 3.0, -p.slope * before / 3.0),
+                    map(after / 3.0, p.slope * after / 3.0),
+                )
+            })
+            .collect();
+
+        let mut elem = PathElem::new(vertices);
+        if let Some(fill) = fill {
+            elem.push_fill(fill);
+        }
+        if let Some(stroke) = stroke {
+            elem.push_stroke(stroke);
+        }
+        Ok(elem.pack().spanned(span))
+    }
+
+    /// Creates a path from [SVG path data](https://www.w3.org/TR/SVG2/paths.html#PathData).
+    ///
+    /// All commands of the path data mini-language are supported. Quadratic
+    /// Bézier curves and elliptical arcs are converted into cubic Bézier
+    /// curves. One unit in the path data corresponds to one point.
+    ///
+    /// As a path consists of a single sequence of vertices, the path data
+    /// must not contain more than one subpath.
+    ///
+    /// ```example
+    /// #path.from-svg-path(
+    ///   "M 0 20 C 10 0 30 0 40 20 Q 50 40 60 20 A 10 10 0 0 1 80 20",
+    ///   stroke: blue,
+    /// )
+    /// ```
+    #[func(title = "Path from SVG Path Data")]
+    pub fn from_svg_path(
+        /// The call span of this function.
+        span: Span,
+        /// The SVG path data, as found in the `d` attribute of an SVG `path`
+        /// element.
+        data: Spanned<Str>,
+        /// How to fill the path. See the general
+        /// [path's documentation]($path.fill) for more details.
+        #[named]
+        fill: Option<Option<Paint>>,
+        /// How to stroke the path. See the general
+        /// [path's documentation]($path.stroke) for more details.
+        #[named]
+        stroke: Option<Smart<Option<Stroke>>>,
+    ) -> SourceResult<Content> {
+        let (vertices, closed) = parse_svg_path(&data.v).at(data.span)?;
+        let mut elem = PathElem::new(vertices);
+        if closed {
+            elem.push_closed(true);
+        }
+        if let Some(fill) = fill {
+            elem.push_fill(fill);
+        }
+        if let Some(stroke) = stroke {
+            elem.push_stroke(stroke);
+        }
+        Ok(elem.pack().spanned(span))
+    }
+
+    /// Converts a path into [SVG path data](https://www.w3.org/TR/SVG2/paths.html#PathData).
+    ///
+    /// The resulting string can be used as the `d` attribute of an SVG `path`
+    /// element. One point corresponds to one unit in the path data. Only paths
+    /// whose vertices are given in absolute lengths can be converted.
+    ///
+    /// ```example
+    /// #let p = path(closed: true, (0pt, 0pt), ((20pt, 10pt), (0pt, -5pt)), (0pt, 20pt))
+    /// #raw(path.to-svg-path(p))
+    /// ```
+    #[func(title = "Path to SVG Path Data")]
+    pub fn to_svg_path(
+        /// The path to convert.
+        path: Content,
+    ) -> StrResult<Str> {
+        let Some(path) = path.to_packed::<PathElem>() else {
+            bail!("expected path, found {}", path.elem().name());
+        };
+
+        let knots = knots(path.vertices());
+        let Some(first) = knots.first() else { return Ok(Str::new()) };
+
+        let mut data = eco_format!("M {}", svg_point(first.vertex)?);
+        for pair in knots.windows(2) {
+            data.push(' ');
+            data.push_str(&svg_segment(&pair[0], &pair[1])?);
+        }
+
+        if path.closed(StyleChain::default()) {
+            let last = knots.last().unwrap();
+            if last.from != last.vertex || first.to != first.vertex {
+                data.push(' ');
+                data.push_str(&svg_segment(last, first)?);
+            }
+            data.push_str(" Z");
+        }
+
+        Ok(data.into())
+    }
 }
 
 impl LayoutMultiple for Packed<PathElem> {
@@ -93,29 +436,23 @@ impl LayoutMultiple for Packed<PathElem> {
         }
 
         // Only create a path if there are more than zero points.
-        // Construct a closed path given all points.
-        let mut path = Path::new();
-        path.move_to(points[0]);
-
+        // Collect the bezier segments between all points.
+        let mut segments = vec![];
         let mut add_cubic =
             |from_point: Point, to_point: Point, from: PathVertex, to: PathVertex| {
                 let from_control_point = resolve(from.control_point_from()) + from_point;
                 let to_control_point = resolve(to.control_point_to()) + to_point;
-                path.cubic_to(from_control_point, to_control_point, to_point);
-
-                let p0 = kurbo::Point::new(from_point.x.to_raw(), from_point.y.to_raw());
-                let p1 = kurbo::Point::new(
-                    from_control_point.x.to_raw(),
-                    from_control_point.y.to_raw(),
-                );
-                let p2 = kurbo::Point::new(
-                    to_control_point.x.to_raw(),
-                    to_control_point.y.to_raw(),
+                let cubic = CubicBez::new(
+                    to_kurbo(from_point),
+                    to_kurbo(from_control_point),
+                    to_kurbo(to_control_point),
+                    to_kurbo(to_point),
                 );
-                let p3 = kurbo::Point::new(to_point.x.to_raw(), to_point.y.to_raw());
-                let extrema = CubicBez::new(p0, p1, p2, p3).bounding_box();
+
+                let extrema = cubic.bounding_box();
                 size.x.set_max(Abs::raw(extrema.x1));
                 size.y.set_max(Abs::raw(extrema.y1));
+                segments.push(cubic);
             };
 
         for (vertex_window, point_window) in vertices.windows(2).zip(points.windows(2)) {
@@ -127,14 +464,14 @@ impl LayoutMultiple for Packed<PathElem> {
             add_cubic(from_point, to_point, from, to);
         }
 
-        if self.closed(styles) {
+        let closed = self.closed(styles);
+        if closed {
             let from = *vertices.last().unwrap(); // We checked that we have at least one element.
             let to = vertices[0];
             let from_point = *points.last().unwrap();
             let to_point = points[0];
 
             add_cubic(from_point, to_point, from, to);
-            path.close_path();
         }
 
         // Prepare fill and stroke.
@@ -145,14 +482,125 @@ impl LayoutMultiple for Packed<PathElem> {
             Smart::Custom(stroke) => stroke.map(Stroke::unwrap_or_default),
         };
 
+        // Determine the markers and their placement. Closed paths have no
+        // start and end, so only the mid marker is placed on all vertices.
+        let mut markers = vec![];
+        if let Some(stroke) = &stroke {
+            let marker_mid = self.marker_mid(styles);
+            let (marker_start, marker_end) = if closed {
+                (None, None)
+            } else {
+                (self.marker_start(styles), self.marker_end(styles))
+            };
+
+            if let (Some(marker), Some(first)) = (marker_start, segments.first_mut()) {
+                markers.push((marker, points[0], direction(-start_tangent(first))));
+                *first = retract_start(*first, marker.retraction(stroke.thickness));
+            }
+
+            if let Some(marker) = marker_mid {
+                let count = segments.len();
+                let range = if closed { 0..count } else { 1..count };
+                for i in range {
+                    let incoming = end_tangent(&segments[(i + count - 1) % count]);
+                    let outgoing = start_tangent(&segments[i]);
+                    let normalize = |p: Point| p / p.hypot().to_raw().max(f64::EPSILON);
+                    let tangent = normalize(incoming) + normalize(outgoing);
+                    markers.push((marker, points[i], direction(tangent)));
+                }
+            }
+
+            if let (Some(marker), Some(last)) = (marker_end, segments.last_mut()) {
+                markers.push((
+                    marker,
+                    *points.last().unwrap(),
+                    direction(end_tangent(last)),
+                ));
+                *last = retract_end(*last, marker.retraction(stroke.thickness));
+            }
+        }
+
+        // Construct the path given all segments.
+        let mut path = Path::new();
+        path.move_to(segments.first().map_or(points[0], |seg| from_kurbo(seg.p0)));
+        for seg in &segments {
+            path.cubic_to(from_kurbo(seg.p1), from_kurbo(seg.p2), from_kurbo(seg.p3));
+        }
+        if closed {
+            path.close_path();
+        }
+
         let mut frame = Frame::soft(size);
-        let shape = Shape { geometry: Geometry::Path(path), stroke, fill };
+        let shape = Shape {
+            geometry: Geometry::Path(path),
+            stroke: stroke.clone(),
+            fill,
+        };
         frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
 
+        if let Some(stroke) = &stroke {
+            for (marker, pos, angle) in markers {
+                let shape = marker.shape(stroke, pos, angle);
+                frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+            }
+        }
+
         Ok(Fragment::frame(frame))
     }
 }
 
+/// Convert a point to a kurbo point.
+fn to_kurbo(point: Point) -> kurbo::Point {
+    kurbo::Point::new(point.x.to_raw(), point.y.to_raw())
+}
+
+/// Convert a kurbo point to a point.
+fn from_kurbo(point: kurbo::Point) -> Point {
+    Point::new(Abs::raw(point.x), Abs::raw(point.y))
+}
+
+/// The direction in which a segment leaves its start point.
+fn start_tangent(cubic: &CubicBez) -> Point {
+    let tangent = cubic.deriv().eval(0.0) - kurbo::Point::ORIGIN;
+    if tangent.hypot() > 1e-9 {
+        return from_kurbo(tangent.to_point());
+    }
+    from_kurbo((cubic.p3 - cubic.p0).to_point())
+}
+
+/// The direction in which a segment arrives at its end point.
+fn end_tangent(cubic: &CubicBez) -> Point {
+    let tangent = cubic.deriv().eval(1.0) - kurbo::Point::ORIGIN;
+    if tangent.hypot() > 1e-9 {
+        return from_kurbo(tangent.to_point());
+    }
+    from_kurbo((cubic.p3 - cubic.p0).to_point())
+}
+
+/// Cut off the given length from the start of a segment.
+fn retract_start(cubic: CubicBez, amount: Abs) -> CubicBez {
+    let amount = amount.to_raw();
+    if amount <= 0.0 || cubic.arclen(ARCLEN_ACCURACY) <= amount {
+        return cubic;
+    }
+    let t = cubic.inv_arclen(amount, ARCLEN_ACCURACY);
+    cubic.subsegment(t..1.0)
+}
+
+/// Cut off the given length from the end of a segment.
+fn retract_end(cubic: CubicBez, amount: Abs) -> CubicBez {
+    let amount = amount.to_raw();
+    let length = cubic.arclen(ARCLEN_ACCURACY);
+    if amount <= 0.0 || length <= amount {
+        return cubic;
+    }
+    let t = cubic.inv_arclen(length - amount, ARCLEN_ACCURACY);
+    cubic.subsegment(0.0..t)
+}
+
+/// The accuracy with which arc lengths of segments are computed.
+const ARCLEN_ACCURACY: f64 = 1e-3;
+
 /// A component used for path creation.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum PathVertex {
@@ -215,6 +663,312 @@ cast! {
     },
 }
 
+/// A path vertex with absolute control points.
+#[derive(Copy, Clone)]
+struct Knot {
+    /// The control point for the curve coming into the vertex.
+    to: Axes<Rel<Length>>,
+    /// The vertex itself.
+    vertex: Axes<Rel<Length>>,
+    /// The control point for the curve going out of the vertex.
+    from: Axes<Rel<Length>>,
+}
+
+/// Convert path vertices into knots.
+fn knots(vertices: &[PathVertex]) -> Vec<Knot> {
+    let add = |a: Axes<Rel<Length>>, b: Axes<Rel<Length>>| a.zip_map(b, |x, y| x + y);
+    vertices
+        .iter()
+        .map(|v| Knot {
+            to: add(v.vertex(), v.control_point_to()),
+            vertex: v.vertex(),
+            from: add(v.vertex(), v.control_point_from()),
+        })
+        .collect()
+}
+
+/// Subdivide the segments between the knots until there are `count` knots,
+/// distributing the new knots evenly across the segments.
+fn subdivide(knots: &[Knot], closed: bool, count: usize) -> Vec<Knot> {
+    let n = knots.len();
+    let segments = if closed { n } else { n - 1 };
+    let extra = count - n;
+
+    let mut knots = knots.to_vec();
+    let mut out = Vec::with_capacity(count);
+    for i in 0..segments {
+        out.push(knots[i]);
+        let mut end = if i + 1 < n { knots[i + 1] } else { out[0] };
+        let parts = 1 + extra / segments + usize::from(i < extra % segments);
+        for j in 0..parts - 1 {
+            let s = 1.0 / (parts - j) as f64;
+            let start = out.last_mut().unwrap();
+            let mid = split(start, &mut end, s);
+            out.push(mid);
+        }
+
+        if i + 1 < n {
+            knots[i + 1] = end;
+        } else {
+            out[0] = end;
+        }
+    }
+
+    if !closed {
+        out.push(knots[n - 1]);
+    }
+
+    out
+}
+
+/// Split the cubic segment between two knots at the parameter `t` and return
+/// the new knot in between.
+fn split(start: &mut Knot, end: &mut Knot, t: f64) -> Knot {
+    let p01 = mix(start.vertex, start.from, t);
+    let p12 = mix(start.from, end.to, t);
+    let p23 = mix(end.to, end.vertex, t);
+    let p012 = mix(p01, p12, t);
+    let p123 = mix(p12, p23, t);
+    start.from = p01;
+    end.to = p23;
+    Knot { to: p012, vertex: mix(p012, p123, t), from: p123 }
+}
+
+/// Linearly interpolate between two points.
+fn mix(a: Axes<Rel<Length>>, b: Axes<Rel<Length>>, t: f64) -> Axes<Rel<Length>> {
+    a.zip_map(b, |x, y| x * (1.0 - t) + y * t)
+}
+
+/// Convert a knot back into a path vertex.
+fn unknot(knot: &Knot) -> PathVertex {
+    if knot.to == knot.vertex && knot.from == knot.vertex {
+        return Vertex(knot.vertex);
+    }
+    let sub = |a: Axes<Rel<Length>>| a.zip_map(knot.vertex, |x, y| x - y);
+    AllControlPoints(knot.vertex, sub(knot.to), sub(knot.from))
+}
+
+/// Parse SVG path data into path vertices and whether the path is closed.
+fn parse_svg_path(data: &str) -> StrResult<(Vec<PathVertex>, bool)> {
+    let bez = BezPath::from_svg(data).map_err(|err| match err {
+        SvgParseError::UnknownCommand(c) => eco_format!("unknown path command `{c}`"),
+        _ => "invalid SVG path data".into(),
+    })?;
+
+    let point = |p: kurbo::Point| Axes::new(Abs::pt(p.x).into(), Abs::pt(p.y).into());
+    let plain = |p| Knot { to: p, vertex: p, from: p };
+
+    let mut knots: Vec<Knot> = vec![];
+    let mut closed = false;
+    for el in bez.elements() {
+        if closed {
+            bail!("path data must contain a single subpath");
+        } else if knots.is_empty() && !matches!(el, PathEl::MoveTo(_)) {
+            bail!("path data must start with a move command");
+        }
+
+        match *el {
+            PathEl::MoveTo(p) => {
+                if !knots.is_empty() {
+                    bail!("path data must contain a single subpath");
+                }
+                knots.push(plain(point(p)));
+            }
+            PathEl::LineTo(p) => knots.push(plain(point(p))),
+            PathEl::QuadTo(q, p) => {
+                // A quadratic curve is a cubic one whose control points lie
+                // two thirds of the way towards the quadratic control point.
+                let (q, p) = (point(q), point(p));
+                let last = knots.last_mut().unwrap();
+                last.from = mix(last.vertex, q, 2.0 / 3.0);
+                knots.push(Knot { to: mix(p, q, 2.0 / 3.0), ..plain(p) });
+            }
+            PathEl::CurveTo(c1, c2, p) => {
+                knots.last_mut().unwrap().from = point(c1);
+                knots.push(Knot { to: point(c2), ..plain(point(p)) });
+            }
+            PathEl::ClosePath => closed = true,
+        }
+    }
+
+    // If the path returns to its start before closing, the closing segment
+    // has no length and the last vertex coincides with the first.
+    if closed && knots.len() > 1 && knots[0].vertex == knots[knots.len() - 1].vertex {
+        let last = knots.pop().unwrap();
+        knots[0].to = last.to;
+    }
+
+    Ok((knots.iter().map(unknot).collect(), closed))
+}
+
+/// Format the segment between two knots as an SVG path command.
+fn svg_segment(start: &Knot, end: &Knot) -> StrResult<EcoString> {
+    if start.from == start.vertex && end.to == end.vertex {
+        return Ok(eco_format!("L {}", svg_point(end.vertex)?));
+    }
+    Ok(eco_format!(
+        "C {} {} {}",
+        svg_point(start.from)?,
+        svg_point(end.to)?,
+        svg_point(end.vertex)?,
+    ))
+}
+
+/// Format a point as SVG path coordinates.
+fn svg_point(point: Axes<Rel<Length>>) -> StrResult<EcoString> {
+    let coordinate = |v: Rel<Length>| -> StrResult<EcoString> {
+        if !v.rel.is_zero() || !v.abs.em.is_zero() {
+            bail!("cannot convert path with relative lengths to SVG path data");
+        }
+        // Round to avoid noise from floating point arithmetic and normalize
+        // negative zero.
+        let pt = (v.abs.abs.to_pt() * 1e4).round() / 1e4 + 0.0;
+        Ok(eco_format!("{pt}"))
+    };
+    Ok(eco_format!("{} {}", coordinate(point.x)?, coordinate(point.y)?))
+}
+
+/// An interval of floats, given by its start and end.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Interval(pub f64, pub f64);
+
+cast! {
+    Interval,
+    self => array![self.0, self.1].into_value(),
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => {
+                let (a, b): (f64, f64) = (a.cast()?, b.cast()?);
+                if !a.is_finite() || !b.is_finite() {
+                    bail!("interval must be finite");
+                }
+                Self(a, b)
+            }
+            _ => bail!("interval must contain exactly two entries"),
+        }
+    },
+}
+
+/// The number of initial samples of a plotted function if none is given.
+const DEFAULT_PLOT_SAMPLES: usize = 16;
+
+/// The maximum number of points a plotted function is sampled at.
+const MAX_PLOT_POINTS: usize = 10_000;
+
+/// How often the samples of a plotted function are refined at most.
+const MAX_PLOT_REFINEMENTS: usize = 10;
+
+/// The maximum deviation of a plotted curve from the function, relative to the
+/// extent of the function's values.
+const PLOT_TOLERANCE: f64 = 1e-4;
+
+/// A sample of a plotted function.
+#[derive(Debug, Copy, Clone)]
+struct PlotPoint {
+    x: f64,
+    y: f64,
+    /// The estimated derivative at `x`.
+    slope: f64,
+}
+
+/// Sample a function adaptively.
+///
+/// Starting from evenly spaced samples, each interval whose midpoint deviates
+/// too much from the cubic Hermite curve through its ends is split in half,
+/// until the curve is accurate enough.
+fn sample_plot(
+    eval: &mut impl FnMut(f64) -> SourceResult<f64>,
+    start: f64,
+    end: f64,
+    samples: usize,
+) -> SourceResult<Vec<PlotPoint>> {
+    let mut xs: Vec<f64> = (0..=samples)
+        .map(|i| start + (end - start) * i as f64 / samples as f64)
+        .collect();
+    let mut ys = xs.iter().map(|&x| eval(x)).collect::<SourceResult<Vec<_>>>()?;
+
+    // The function's values at the midpoints of the intervals, if known.
+    let mut mids: Vec<Option<f64>> = vec![None; samples];
+
+    for _ in 0..MAX_PLOT_REFINEMENTS {
+        let slopes = plot_slopes(&xs, &ys);
+        let (lo, hi) =
+            ys.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &y| {
+                (lo.min(y), hi.max(y))
+            });
+        let tolerance = PLOT_TOLERANCE * (hi - lo);
+
+        let mut refined = false;
+        let mut next_xs = vec![xs[0]];
+        let mut next_ys = vec![ys[0]];
+        let mut next_mids = vec![];
+        for k in 0..xs.len() - 1 {
+            let h = xs[k + 1] - xs[k];
+            let x = xs[k] + h / 2.0;
+            let y = match mids[k] {
+                Some(y) => y,
+                None => eval(x)?,
+            };
+
+            // The middle of the cubic Hermite curve. As its control points
+            // are spaced evenly along the x-axis, the curve's middle lies
+            // exactly above the interval's midpoint.
+            let predicted =
+                (ys[k] + ys[k + 1]) / 2.0 + h * (slopes[k] - slopes[k + 1]) / 8.0;
+            let remaining = xs.len() - k;
+            if (y - predicted).abs() > tolerance
+                && next_xs.len() + remaining < MAX_PLOT_POINTS
+            {
+                next_xs.push(x);
+                next_ys.push(y);
+                next_mids.extend([None, None]);
+                refined = true;
+            } else {
+                next_mids.push(Some(y));
+            }
+
+            next_xs.push(xs[k + 1]);
+            next_ys.push(ys[k + 1]);
+        }
+
+        xs = next_xs;
+        ys = next_ys;
+        mids = next_mids;
+        if !refined {
+            break;
+        }
+    }
+
+    let slopes = plot_slopes(&xs, &ys);
+    Ok(xs
+        .into_iter()
+        .zip(ys)
+        .zip(slopes)
+        .map(|((x, y), slope)| PlotPoint { x, y, slope })
+        .collect())
+}
+
+/// Estimate the derivatives of a function at its samples from the parabolas
+/// through neighbouring samples.
+fn plot_slopes(xs: &[f64], ys: &[f64]) -> Vec<f64> {
+    let n = xs.len();
+    let h: Vec<f64> = xs.windows(2).map(|w| w[1] - w[0]).collect();
+    let s: Vec<f64> = ys.windows(2).zip(&h).map(|(w, h)| (w[1] - w[0]) / h).collect();
+    if n == 2 {
+        return vec![s[0]; 2];
+    }
+
+    let mut slopes = Vec::with_capacity(n);
+    slopes.push(((2.0 * h[0] + h[1]) * s[0] - h[0] * s[1]) / (h[0] + h[1]));
+    for i in 1..n - 1 {
+        slopes.push((h[i] * s[i - 1] + h[i - 1] * s[i]) / (h[i - 1] + h[i]));
+    }
+    let (a, b) = (n - 3, n - 2);
+    slopes.push(((2.0 * h[b] + h[a]) * s[b] - h[b] * s[a]) / (h[a] + h[b]));
+    slopes
+}
+
 /// A bezier path.
 #[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
 pub struct Path(pub Vec<PathItem>);
@@ -1,12 +1,13 @@
 use std::f64::consts::PI;
 
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
     elem, func, scope, Content, NativeElement, Packed, Resolve, Smart, StyleChain,
 };
 use crate::layout::{
-    Axes, Em, Frame, FrameItem, LayoutSingle, Length, Point, Regions, Rel,
+    Abs, Angle, Axes, Em, Frame, FrameItem, LayoutSingle, Length, Point, Ratio, Regions,
+    Rel,
 };
 use crate::syntax::Span;
 use crate::util::Numeric;
@@ -46,6 +47,21 @@ pub struct PolygonElem {
     #[fold]
     pub stroke: Smart<Option<Stroke>>,
 
+    /// How much to round the polygon's corners.
+    ///
+    /// Each corner is replaced by a circular arc with this radius. If the
+    /// adjacent edges are too short, the radius is reduced accordingly.
+    ///
+    /// ```example
+    /// #polygon(
+    ///   fill: blue.lighten(80%),
+    ///   radius: 5pt,
+    ///   (0pt, 0pt), (60pt, 10pt), (30pt, 40pt),
+    /// )
+    /// ```
+    #[resolve]
+    pub radius: Length,
+
     /// The vertices of the polygon. Each point is specified as an array of two
     /// [relative lengths]($relative).
     #[variadic]
@@ -53,6 +69,7 @@ pub struct PolygonElem {
 }
 
 #[scope]
+#[allow(clippy::too_many_arguments)]
 impl PolygonElem {
     /// A regular polygon, defined by its size and number of vertices.
     ///
@@ -64,6 +81,17 @@ impl PolygonElem {
     ///   vertices: 3,
     /// )
     /// ```
+    ///
+    /// By setting an inner radius or a step, the function can also create
+    /// star shapes.
+    ///
+    /// ```example
+    /// #set polygon(fill: orange)
+    /// #polygon.regular(size: 30pt, vertices: 5, inner: 40%)
+    /// #polygon.regular(size: 30pt, vertices: 5, step: 2)
+    /// #polygon.regular(size: 30pt, vertices: 6, inner: 60%, radius: 2pt)
+    /// #polygon.regular(size: 30pt, vertices: 4, rotation: 45deg)
+    /// ```
     #[func(title = "Regular Polygon")]
     pub fn regular(
         /// The call span of this function.
@@ -78,6 +106,11 @@ impl PolygonElem {
         #[named]
         stroke: Option<Smart<Option<Stroke>>>,
 
+        /// How much to round the polygon's corners. See the general
+        /// [polygon's documentation]($polygon.radius) for more details.
+        #[named]
+        radius: Option<Length>,
+
         /// The diameter of the [circumcircle](https://en.wikipedia.org/wiki/Circumcircle)
         /// of the regular polygon.
         #[named]
@@ -88,29 +121,66 @@ impl PolygonElem {
         #[named]
         #[default(3)]
         vertices: u64,
-    ) -> Content {
-        let radius = size / 2.0;
-        let angle = |i: f64| {
-            2.0 * PI * i / (vertices as f64) + PI * (1.0 / 2.0 - 1.0 / vertices as f64)
+
+        /// The radius of the circle on which the inner vertices of a star
+        /// lie, relative to the circumcircle's radius.
+        ///
+        /// If set, an additional vertex is placed halfway between each pair of
+        /// consecutive outer vertices, turning the polygon into a star.
+        #[named]
+        inner: Option<Ratio>,
+
+        /// How many vertices to advance to reach the next vertex.
+        ///
+        /// A step of `{1}` connects neighbouring vertices, while larger steps
+        /// create [star polygons](https://en.wikipedia.org/wiki/Star_polygon)
+        /// like the pentagram. The step must not share a common divisor with
+        /// the number of vertices, so that all vertices are reached.
+        #[named]
+        #[default(1)]
+        step: u64,
+
+        /// How much to rotate the polygon clockwise.
+        ///
+        /// By default, the polygon's bottom edge is horizontal.
+        #[named]
+        #[default(Angle::zero())]
+        rotation: Angle,
+    ) -> StrResult<Content> {
+        if gcd(vertices, step) != 1 {
+            bail!("step must not share a common divisor with the number of vertices");
+        }
+
+        let radius_of = |r: Length, t: f64| {
+            let angle = Angle::rad(2.0 * PI * t / vertices as f64)
+                + Angle::rad(PI * (1.0 / 2.0 - 1.0 / vertices as f64))
+                + rotation;
+            Axes::new(r * angle.cos(), r * angle.sin())
         };
-        let (horizontal_offset, vertical_offset) = (0..=vertices)
-            .map(|v| {
-                (
-                    (radius * angle(v as f64).cos()) + radius,
-                    (radius * angle(v as f64).sin()) + radius,
-                )
-            })
-            .fold((radius, radius), |(min_x, min_y), (v_x, v_y)| {
-                (
-                    if min_x < v_x { min_x } else { v_x },
-                    if min_y < v_y { min_y } else { v_y },
-                )
-            });
-        let vertices = (0..=vertices)
-            .map(|v| {
-                let x = (radius * angle(v as f64).cos()) + radius - horizontal_offset;
-                let y = (radius * angle(v as f64).sin()) + radius - vertical_offset;
-                Axes::new(x, y).map(Rel::from)
+
+        let outer = size / 2.0;
+        let mut points = vec![];
+        for i in 0..vertices {
+            let t = (i * step) as f64;
+            points.push(radius_of(outer, t));
+            if let Some(inner) = inner {
+                points.push(radius_of(outer * inner.get(), t + step as f64 / 2.0));
+            }
+        }
+
+        let (horizontal_offset, vertical_offset) =
+            points
+                .iter()
+                .fold((Length::zero(), Length::zero()), |(min_x, min_y), p| {
+                    (
+                        if min_x < p.x { min_x } else { p.x },
+                        if min_y < p.y { min_y } else { p.y },
+                    )
+                });
+        let vertices = points
+            .into_iter()
+            .map(|p| {
+                Axes::new(p.x - horizontal_offset, p.y - vertical_offset).map(Rel::from)
             })
             .collect();
 
@@ -121,7 +191,19 @@ impl PolygonElem {
         if let Some(stroke) = stroke {
             elem.push_stroke(stroke);
         }
-        elem.pack().spanned(span)
+        if let Some(radius) = radius {
+            elem.push_radius(radius);
+        }
+        Ok(elem.pack().spanned(span))
+    }
+}
+
+/// The greatest common divisor of two numbers.
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
     }
 }
 
@@ -162,15 +244,73 @@ impl LayoutSingle for Packed<PolygonElem> {
         };
 
         // Construct a closed path given all points.
-        let mut path = Path::new();
-        path.move_to(points[0]);
-        for &point in &points[1..] {
-            path.line_to(point);
-        }
-        path.close_path();
+        let radius = self.radius(styles);
+        let path = if radius > Abs::zero() {
+            rounded_path(&points, radius)
+        } else {
+            let mut path = Path::new();
+            path.move_to(points[0]);
+            for &point in &points[1..] {
+                path.line_to(point);
+            }
+            path.close_path();
+            path
+        };
 
         let shape = Shape { geometry: Geometry::Path(path), stroke, fill };
         frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
         Ok(frame)
     }
 }
+
+/// Construct a closed path through the given points with rounded corners.
+fn rounded_path(points: &[Point], radius: Abs) -> Path {
+    let n = points.len();
+    let mut path = Path::new();
+    for (i, &corner) in points.iter().enumerate() {
+        let prev = points[(i + n - 1) % n] - corner;
+        let next = points[(i + 1) % n] - corner;
+        let (prev_len, next_len) = (prev.hypot(), next.hypot());
+
+        // The interior angle at the corner.
+        let cos = (prev.x.to_raw() * next.x.to_raw() + prev.y.to_raw() * next.y.to_raw())
+            / (prev_len.to_raw() * next_len.to_raw());
+        let half_tan = (cos.clamp(-1.0, 1.0).acos() / 2.0).tan();
+
+        // Keep sharp corners for degenerate edges and angles.
+        if prev_len.is_zero()
+            || next_len.is_zero()
+            || half_tan.is_nan()
+            || half_tan <= 1e-6
+        {
+            if i == 0 {
+                path.move_to(corner);
+            } else {
+                path.line_to(corner);
+            }
+            continue;
+        }
+
+        // The arc must not take up more than half of an adjacent edge.
+        let radius = radius.min(prev_len.min(next_len) / 2.0 * half_tan);
+        let dist = radius / half_tan;
+        let start = corner + prev * (dist / prev_len);
+        let end = corner + next * (dist / next_len);
+
+        // Approximate the arc with a cubic bezier curve.
+        let turn = PI - 2.0 * half_tan.atan();
+        let handle = 4.0 / 3.0 * (turn / 4.0).tan() * (radius / dist);
+        if i == 0 {
+            path.move_to(start);
+        } else {
+            path.line_to(start);
+        }
+        path.cubic_to(
+            start + (corner - start) * handle,
+            end + (corner - end) * handle,
+            end,
+        );
+    }
+    path.close_path();
+    path
+}
@@ -0,0 +1,739 @@
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{elem, Bytes, Cast, Packed, StyleChain};
+use crate::layout::{
+    Abs, Frame, FrameItem, LayoutSingle, Length, Point, Regions, Rel, Size,
+};
+use crate::loading::Readable;
+use crate::util::Numeric;
+use crate::visualize::{Color, Geometry, Paint, Path};
+
+/// A QR code.
+///
+/// The code is drawn with crisp vector shapes, so it stays sharp in print at
+/// any size. Depending on the data, the most compact encoding (numeric,
+/// alphanumeric, or binary) and the smallest possible version are chosen
+/// automatically.
+///
+/// # Example
+/// ```example
+/// #qrcode("https://typst.app", size: 3cm)
+/// ```
+#[elem(name = "qrcode", title = "QR Code", LayoutSingle)]
+pub struct QrCodeElem {
+    /// The data to encode, either as a string or as raw bytes.
+    #[required]
+    pub data: Readable,
+
+    /// How much redundancy to add to the code, such that it can still be
+    /// read when damaged or partially covered. Higher levels make the code
+    /// larger.
+    ///
+    /// ```example
+    /// #for ec in ("l", "m", "q", "h") {
+    ///   qrcode("Typst", ec: ec, size: 1.5cm)
+    /// }
+    /// ```
+    #[default(QrErrorCorrection::M)]
+    pub ec: QrErrorCorrection,
+
+    /// The width and height of the code, including the quiet zone (if any).
+    #[resolve]
+    #[default(Abs::cm(2.0).into())]
+    pub size: Rel<Length>,
+
+    /// Whether to surround the code with a four modules wide empty border, as
+    /// required by the QR code specification for reliable scanning.
+    #[default(true)]
+    pub quiet_zone: bool,
+
+    /// How to fill the dark modules of the code.
+    #[default(Color::BLACK.into())]
+    pub fill: Paint,
+}
+
+impl LayoutSingle for Packed<QrCodeElem> {
+    #[typst_macros::time(name = "qrcode", span = self.span())]
+    fn layout(
+        &self,
+        _: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Frame> {
+        let data = Bytes::from(self.data().clone());
+        let code = QrCode::encode(&data, self.ec(styles)).at(self.span())?;
+
+        let size = self.size(styles).relative_to(regions.base().x);
+        if !size.is_finite() {
+            bail!(self.span(), "cannot create QR code with infinite size");
+        }
+
+        let border = if self.quiet_zone(styles) { 4 } else { 0 };
+        let module = size / (code.size + 2 * border) as f64;
+
+        // Merge horizontal runs of dark modules into rectangles and draw all of
+        // them as a single path, so that there are no seams between them.
+        let mut path = Path::new();
+        for y in 0..code.size {
+            let mut x = 0;
+            while x < code.size {
+                if !code.get(x, y) {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < code.size && code.get(x, y) {
+                    x += 1;
+                }
+                let point = |x: usize, y: usize| {
+                    Point::new(module * (x + border) as f64, module * (y + border) as f64)
+                };
+                path.move_to(point(start, y));
+                path.line_to(point(x, y));
+                path.line_to(point(x, y + 1));
+                path.line_to(point(start, y + 1));
+                path.close_path();
+            }
+        }
+
+        let mut frame = Frame::soft(Size::splat(size));
+        let shape = Geometry::Path(path).filled(self.fill(styles));
+        frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+        Ok(frame)
+    }
+}
+
+/// The error correction level of a QR code.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum QrErrorCorrection {
+    /// About 7% of the code can be restored.
+    L,
+    /// About 15% of the code can be restored.
+    M,
+    /// About 25% of the code can be restored.
+    Q,
+    /// About 30% of the code can be restored.
+    H,
+}
+
+impl QrErrorCorrection {
+    /// The index into the capacity tables.
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The two bits identifying the level in the format information.
+    fn format_bits(self) -> u32 {
+        match self {
+            Self::L => 1,
+            Self::M => 0,
+            Self::Q => 3,
+            Self::H => 2,
+        }
+    }
+}
+
+/// The number of error correction codewords per block, indexed by error
+/// correction level and version.
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28,
+        28, 28, 30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26,
+        26, 26, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+        28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26,
+        30, 28, 30, 30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+        30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26,
+        28, 30, 24, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+        30,
+    ],
+];
+
+/// The number of error correction blocks, indexed by error correction level
+/// and version.
+const ECC_BLOCKS: [[u8; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12,
+        12, 12, 13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18,
+        20, 21, 23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23,
+        25, 27, 29, 34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34,
+        30, 32, 35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+/// The characters that can be encoded in alphanumeric mode, in the order of
+/// their values.
+const ALPHANUMERIC: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// How the data of a QR code is encoded.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Mode {
+    Numeric,
+    Alphanumeric,
+    Byte,
+}
+
+impl Mode {
+    /// Choose the most compact mode that can encode the data.
+    fn detect(data: &[u8]) -> Self {
+        if data.iter().all(u8::is_ascii_digit) {
+            Self::Numeric
+        } else if data.iter().all(|c| ALPHANUMERIC.contains(c)) {
+            Self::Alphanumeric
+        } else {
+            Self::Byte
+        }
+    }
+
+    /// The four bits indicating the mode.
+    fn indicator(self) -> u32 {
+        match self {
+            Self::Numeric => 0b0001,
+            Self::Alphanumeric => 0b0010,
+            Self::Byte => 0b0100,
+        }
+    }
+
+    /// The number of bits of the character count in the given version.
+    fn count_bits(self, version: usize) -> usize {
+        let i = match version {
+            1..=9 => 0,
+            10..=26 => 1,
+            _ => 2,
+        };
+        match self {
+            Self::Numeric => [10, 12, 14][i],
+            Self::Alphanumeric => [9, 11, 13][i],
+            Self::Byte => [8, 16, 16][i],
+        }
+    }
+
+    /// The number of bits the data takes up in this mode.
+    fn data_bits(self, len: usize) -> usize {
+        match self {
+            Self::Numeric => len / 3 * 10 + [0, 4, 7][len % 3],
+            Self::Alphanumeric => len / 2 * 11 + len % 2 * 6,
+            Self::Byte => len * 8,
+        }
+    }
+}
+
+/// A sequence of bits.
+#[derive(Default)]
+struct BitBuffer(Vec<bool>);
+
+impl BitBuffer {
+    /// Append the lowest `len` bits of `value`, most significant first.
+    fn push(&mut self, value: u32, len: usize) {
+        self.0.extend((0..len).rev().map(|i| (value >> i) & 1 == 1));
+    }
+}
+
+/// An encoded QR code.
+struct QrCode {
+    /// The version of the code, between 1 and 40.
+    version: usize,
+    /// The number of modules per side.
+    size: usize,
+    /// Whether each module is dark, row by row.
+    modules: Vec<bool>,
+    /// Whether each module belongs to a function pattern, which is not
+    /// subject to masking.
+    function: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encode data into a QR code with the smallest possible version.
+    fn encode(data: &[u8], ecl: QrErrorCorrection) -> StrResult<Self> {
+        let mode = Mode::detect(data);
+        let Some(version) = (1..=40).find(|&version| {
+            let capacity = data_codewords(version, ecl) * 8;
+            4 + mode.count_bits(version) + mode.data_bits(data.len()) <= capacity
+        }) else {
+            bail!("data is too long for a QR code");
+        };
+
+        let mut bits = BitBuffer::default();
+        bits.push(mode.indicator(), 4);
+        bits.push(data.len() as u32, mode.count_bits(version));
+        match mode {
+            Mode::Numeric => {
+                for chunk in data.chunks(3) {
+                    let value =
+                        chunk.iter().fold(0, |acc, c| acc * 10 + u32::from(c - b'0'));
+                    bits.push(value, chunk.len() * 3 + 1);
+                }
+            }
+            Mode::Alphanumeric => {
+                let value =
+                    |c: &u8| ALPHANUMERIC.iter().position(|a| a == c).unwrap() as u32;
+                for chunk in data.chunks(2) {
+                    match chunk {
+                        [a, b] => bits.push(value(a) * 45 + value(b), 11),
+                        [a] => bits.push(value(a), 6),
+                        _ => unreachable!(),
+                    }
+                }
+            }
+            Mode::Byte => {
+                for &byte in data {
+                    bits.push(byte.into(), 8);
+                }
+            }
+        }
+
+        // Terminate the data and pad it to the full capacity.
+        let capacity = data_codewords(version, ecl) * 8;
+        bits.push(0, (capacity - bits.0.len()).min(4));
+        bits.push(0, (8 - bits.0.len() % 8) % 8);
+        for pad in [0xEC, 0x11].into_iter().cycle() {
+            if bits.0.len() >= capacity {
+                break;
+            }
+            bits.push(pad, 8);
+        }
+
+        let codewords: Vec<u8> = bits
+            .0
+            .chunks(8)
+            .map(|byte| byte.iter().fold(0, |acc, &bit| acc << 1 | u8::from(bit)))
+            .collect();
+
+        let size = version * 4 + 17;
+        let mut code = Self {
+            version,
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        };
+
+        code.draw_function_patterns(ecl);
+        code.draw_codewords(&add_error_correction(&codewords, version, ecl));
+
+        // Choose the mask with the lowest penalty.
+        let mask = (0..8)
+            .min_by_key(|&mask| {
+                code.apply_mask(mask);
+                code.draw_format_bits(ecl, mask);
+                let penalty = code.penalty();
+                code.apply_mask(mask);
+                penalty
+            })
+            .unwrap();
+
+        code.apply_mask(mask);
+        code.draw_format_bits(ecl, mask);
+        Ok(code)
+    }
+
+    /// Whether the module at the given position is dark.
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// Set a module that belongs to a function pattern.
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    /// Draw the finder, timing, and alignment patterns as well as the version
+    /// information. The format information is reserved with dummy values.
+    fn draw_function_patterns(&mut self, ecl: QrErrorCorrection) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4..=4_isize {
+                for dx in -4..=4_isize {
+                    let (Some(xx), Some(yy)) =
+                        (x.checked_add_signed(dx), y.checked_add_signed(dy))
+                    else {
+                        continue;
+                    };
+                    if xx < size && yy < size {
+                        let dist = dx.abs().max(dy.abs());
+                        self.set_function(xx, yy, dist != 2 && dist != 4);
+                    }
+                }
+            }
+        }
+
+        let positions = self.alignment_positions();
+        let n = positions.len();
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                // Skip the corners with finder patterns.
+                let corner = |k: usize| k == 0 || k == n - 1;
+                if corner(i) && corner(j) && !(i == n - 1 && j == n - 1) {
+                    continue;
+                }
+                for dy in -2..=2_isize {
+                    for dx in -2..=2_isize {
+                        let dark = dx.abs().max(dy.abs()) != 1;
+                        self.set_function(
+                            x.wrapping_add_signed(dx),
+                            y.wrapping_add_signed(dy),
+                            dark,
+                        );
+                    }
+                }
+            }
+        }
+
+        self.draw_format_bits(ecl, 0);
+        self.draw_version();
+    }
+
+    /// The coordinates of the centers of the alignment patterns along each
+    /// axis.
+    fn alignment_positions(&self) -> Vec<usize> {
+        if self.version == 1 {
+            return vec![];
+        }
+        let n = self.version / 7 + 2;
+        let step = (self.version * 8 + n * 3 + 5) / (n * 4 - 4) * 2;
+        let mut positions: Vec<_> =
+            (0..n - 1).map(|i| self.size - 7 - i * step).collect();
+        positions.push(6);
+        positions.reverse();
+        positions
+    }
+
+    /// Draw the error correction level and mask, protected by a BCH code.
+    fn draw_format_bits(&mut self, ecl: QrErrorCorrection, mask: u32) {
+        let data = ecl.format_bits() << 3 | mask;
+        let mut rem = data;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        let bits = (data << 10 | rem) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 == 1;
+
+        // The copy around the top left finder pattern.
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        // The copy split between the other two finder patterns.
+        let size = self.size;
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Draw the version information, protected by a BCH code. Only versions
+    /// 7 and up have it.
+    fn draw_version(&mut self) {
+        if self.version < 7 {
+            return;
+        }
+        let mut rem = self.version as u32;
+        for _ in 0..12 {
+            rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+        }
+        let bits = (self.version as u32) << 12 | rem;
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 == 1;
+            let a = self.size - 11 + i % 3;
+            let b = i / 3;
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    /// Draw the codewords in a zigzag pattern from the bottom right corner,
+    /// skipping function patterns.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size - 1;
+        loop {
+            // Skip the vertical timing pattern.
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vert in 0..size {
+                let y = if upward { size - 1 - vert } else { vert };
+                for x in [right, right - 1] {
+                    if !self.function[y * size + x] && i < codewords.len() * 8 {
+                        self.modules[y * size + x] =
+                            (codewords[i / 8] >> (7 - i % 8)) & 1 == 1;
+                        i += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// Invert the non-function modules selected by the mask. Applying the
+    /// same mask twice undoes it.
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let i = y * self.size + x;
+                self.modules[i] ^= invert && !self.function[i];
+            }
+        }
+    }
+
+    /// Compute the penalty score of the code as defined by the specification.
+    /// Lower is better.
+    fn penalty(&self) -> i64 {
+        let size = self.size;
+        let mut penalty = 0;
+
+        // Runs of same-colored modules and patterns that look like finders,
+        // first in rows and then in columns.
+        for transposed in [false, true] {
+            for a in 0..size {
+                let mut color = false;
+                let mut run = 0;
+                let mut history = [0; 7];
+                for b in 0..size {
+                    let module = if transposed { self.get(a, b) } else { self.get(b, a) };
+                    if module == color {
+                        run += 1;
+                        if run == 5 {
+                            penalty += 3;
+                        } else if run > 5 {
+                            penalty += 1;
+                        }
+                    } else {
+                        self.add_history(run, &mut history);
+                        if !color {
+                            penalty += finder_patterns(&history) * 40;
+                        }
+                        color = module;
+                        run = 1;
+                    }
+                }
+
+                // Terminate the run with the light border.
+                if color {
+                    self.add_history(run, &mut history);
+                    run = 0;
+                }
+                self.add_history(run + size as i64, &mut history);
+                penalty += finder_patterns(&history) * 40;
+            }
+        }
+
+        // Blocks of 2x2 same-colored modules.
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = self.get(x, y);
+                if color == self.get(x + 1, y)
+                    && color == self.get(x, y + 1)
+                    && color == self.get(x + 1, y + 1)
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        // Imbalance between dark and light modules.
+        let dark = self.modules.iter().filter(|&&m| m).count() as i64;
+        let total = (size * size) as i64;
+        let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+        penalty += k * 10;
+
+        penalty
+    }
+
+    /// Push the length of a finished run to the history. The first run is
+    /// extended by the light border around the code.
+    fn add_history(&self, mut run: i64, history: &mut [i64; 7]) {
+        if history[0] == 0 {
+            run += self.size as i64;
+        }
+        history.copy_within(0..6, 1);
+        history[0] = run;
+    }
+}
+
+/// Count the finder-like patterns (dark-light-dark-light-dark in the ratio
+/// 1:1:3:1:1 with light space on one side) at the end of a run history.
+fn finder_patterns(history: &[i64; 7]) -> i64 {
+    let n = history[1];
+    let core = n > 0
+        && history[2] == n
+        && history[3] == n * 3
+        && history[4] == n
+        && history[5] == n;
+    i64::from(core && history[0] >= n * 4 && history[6] >= n)
+        + i64::from(core && history[6] >= n * 4 && history[0] >= n)
+}
+
+/// The number of data codewords in a code of the given version and error
+/// correction level.
+fn data_codewords(version: usize, ecl: QrErrorCorrection) -> usize {
+    let e = ecl.index();
+    raw_data_modules(version) / 8
+        - usize::from(ECC_CODEWORDS_PER_BLOCK[e][version])
+            * usize::from(ECC_BLOCKS[e][version])
+}
+
+/// The number of modules available for data and error correction in a code
+/// of the given version.
+fn raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let n = version / 7 + 2;
+        result -= (25 * n - 10) * n - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+/// Split the data codewords into blocks, add Reed-Solomon error correction to
+/// each block, and interleave the results.
+fn add_error_correction(data: &[u8], version: usize, ecl: QrErrorCorrection) -> Vec<u8> {
+    let e = ecl.index();
+    let blocks = usize::from(ECC_BLOCKS[e][version]);
+    let ecc_len = usize::from(ECC_CODEWORDS_PER_BLOCK[e][version]);
+    let raw = raw_data_modules(version) / 8;
+    let short_blocks = blocks - raw % blocks;
+    let short_len = raw / blocks;
+
+    let divisor = reed_solomon_divisor(ecc_len);
+    let mut split = vec![];
+    let mut k = 0;
+    for i in 0..blocks {
+        let len = short_len - ecc_len + usize::from(i >= short_blocks);
+        let mut block = data[k..k + len].to_vec();
+        k += len;
+        let ecc = reed_solomon_remainder(&block, &divisor);
+        if i < short_blocks {
+            // Placeholder to align the error correction codewords of all
+            // blocks. It is skipped during interleaving.
+            block.push(0);
+        }
+        block.extend(ecc);
+        split.push(block);
+    }
+
+    let mut result = Vec::with_capacity(raw);
+    for i in 0..split[0].len() {
+        for (j, block) in split.iter().enumerate() {
+            if i != short_len - ecc_len || j >= short_blocks {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+/// Compute the generator polynomial for Reed-Solomon codes of the given
+/// degree, with the coefficients from highest to lowest power, excluding the
+/// leading one.
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0; degree];
+    result[degree - 1] = 1;
+    let mut root = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+/// Compute the Reed-Solomon error correction codewords for the data.
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (x, &y) in result.iter_mut().zip(divisor) {
+            *x ^= gf_multiply(y, factor);
+        }
+    }
+    result
+}
+
+/// Multiply two elements of GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1.
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u8 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x1D);
+        z ^= ((y >> i) & 1) * x;
+    }
+    z
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qrcode_error_correction() {
+        // The "HELLO WORLD" example from https://www.thonky.com/qr-code-tutorial/.
+        let code = QrCode::encode(b"HELLO WORLD", QrErrorCorrection::Q).unwrap();
+        assert_eq!(code.version, 1);
+
+        let data = [32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236];
+        let ecc = reed_solomon_remainder(&data, &reed_solomon_divisor(13));
+        assert_eq!(ecc, [168, 72, 22, 82, 217, 54, 156, 0, 46, 15, 180, 122, 16]);
+    }
+
+    #[test]
+    fn test_qrcode_capacity() {
+        assert_eq!(data_codewords(1, QrErrorCorrection::L), 19);
+        assert_eq!(data_codewords(1, QrErrorCorrection::H), 9);
+        assert_eq!(data_codewords(10, QrErrorCorrection::M), 216);
+        assert_eq!(data_codewords(40, QrErrorCorrection::L), 2956);
+        assert_eq!(data_codewords(40, QrErrorCorrection::H), 1276);
+    }
+}
@@ -8,7 +8,7 @@ use crate::layout::{
     Point, Ratio, Regions, Rel, Sides, Size,
 };
 use crate::syntax::Span;
-use crate::util::Get;
+use crate::util::{Get, Numeric};
 use crate::visualize::{FixedStroke, Paint, Path, Stroke};
 
 /// A rectangle with optional content.
@@ -603,6 +603,21 @@ impl Geometry {
             Self::Path(p) => p.bbox_size(),
         }
     }
+
+    /// The size of the box relative to which a self-relative paint of the
+    /// geometry is positioned, given the thickness of its stroke.
+    ///
+    /// This is the bounding box, except that a horizontal or vertical line
+    /// extends as far as its stroke across its direction. Otherwise, such a
+    /// line's box would be empty.
+    pub fn paint_bbox_size(&self, thickness: Abs) -> Size {
+        match self {
+            Self::Line(line) => {
+                line.map(|v| if v.is_zero() { thickness } else { v }).to_size()
+            }
+            _ => self.bbox_size(),
+        }
+    }
 }
 
 /// Produce a shape that approximates an axis-aligned ellipse.
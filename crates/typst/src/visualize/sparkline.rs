@@ -0,0 +1,253 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, NativeElement, Packed, Show, Smart, StyleChain};
+use crate::layout::{Axes, BoxElem, Corners, Em, Length, PlaceElem, Ratio, Rel, Sizing};
+use crate::text::TextElem;
+use crate::util::Scalar;
+use crate::visualize::chart::ChartSeries;
+use crate::visualize::{
+    Color, LineElem, Luma, Paint, PathElem, PathVertex, RectElem, Stroke,
+};
+
+/// A small line chart that fits into a line of text.
+///
+/// Sparklines show the trend of a series of values at a glance, without any
+/// axes or labels.
+///
+/// # Example
+/// ```example
+/// Visitors this week:
+/// #sparkline((3, 5, 4, 8, 6, 9, 12))
+///
+/// Stock: #sparkline(
+///   (10, 8, 9, 6, 7, 4),
+///   stroke: red,
+///   fill: red.lighten(80%),
+/// )
+/// ```
+#[elem(Show)]
+pub struct SparklineElem {
+    /// The values to display. See the [line chart's
+    /// documentation]($chart.line.series) for the supported formats.
+    #[required]
+    pub values: ChartSeries,
+
+    /// The width of the sparkline.
+    #[default(Em::new(4.0).into())]
+    pub width: Rel<Length>,
+
+    /// The height of the sparkline.
+    #[default(Em::new(0.8).into())]
+    pub height: Rel<Length>,
+
+    /// How to stroke the line. Defaults to a thin line in the current text
+    /// color.
+    #[fold]
+    pub stroke: Stroke,
+
+    /// How to fill the area below the line.
+    pub fill: Option<Paint>,
+}
+
+impl Show for Packed<SparklineElem> {
+    #[typst_macros::time(name = "sparkline", span = self.span())]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let points = &self.values().0;
+        let bounds = |coord: fn(&(Scalar, Scalar)) -> f64| {
+            points
+                .iter()
+                .map(coord)
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), v| {
+                    (a.min(v), b.max(v))
+                })
+        };
+        let (x_min, x_max) = bounds(|p| p.0.get());
+        let (y_min, y_max) = bounds(|p| p.1.get());
+        let ratio = |v: f64, min: f64, max: f64| {
+            Ratio::new(if max > min { (v - min) / (max - min) } else { 0.5 })
+        };
+
+        let vertices: Vec<_> = points
+            .iter()
+            .map(|(x, y)| {
+                let x = ratio(x.get(), x_min, x_max);
+                let y = Ratio::one() - ratio(y.get(), y_min, y_max);
+                PathVertex::Vertex(Axes::new(x.into(), y.into()))
+            })
+            .collect();
+
+        let mut body = Content::empty();
+        if let (Some(fill), Some(first), Some(last)) =
+            (self.fill(styles), vertices.first(), vertices.last())
+        {
+            let bottom =
+                |v: &PathVertex| PathVertex::Vertex(Axes::new(v.vertex().x, Rel::one()));
+            let mut area = vertices.clone();
+            area.push(bottom(last));
+            area.push(bottom(first));
+            let path = PathElem::new(area).with_closed(true).with_fill(Some(fill));
+            body += PlaceElem::new(path.pack()).pack();
+        }
+
+        let stroke = self.stroke(styles);
+        let stroke = Stroke {
+            paint: stroke.paint.or(Smart::Custom(TextElem::fill_in(styles))),
+            thickness: stroke.thickness.or(Smart::Custom(Em::new(0.08).into())),
+            ..stroke
+        };
+        let line = PathElem::new(vertices).with_stroke(Smart::Custom(Some(stroke)));
+        body += PlaceElem::new(line.pack()).pack();
+
+        Ok(micro_box(self.width(styles), self.height(styles), body).pack())
+    }
+}
+
+/// A bullet graph that fits into a line of text.
+///
+/// A bullet graph compares a value, shown as a bar, with a target, shown as
+/// a vertical mark. Optionally, qualitative ranges (such as "poor", "okay",
+/// and "good") can be shaded in the background.
+///
+/// # Example
+/// ```example
+/// Revenue: #bullet(270, 250, ranges: (150, 225, 300)) \
+/// Profit: #bullet(18, 26, ranges: (10, 20, 30))
+/// ```
+#[elem(Show)]
+pub struct BulletElem {
+    /// The value to display as a bar.
+    #[required]
+    pub value: Scalar,
+
+    /// The target to display as a mark.
+    #[required]
+    pub target: Scalar,
+
+    /// The upper bounds of the qualitative ranges, which are shaded from dark
+    /// to light.
+    pub ranges: Vec<Scalar>,
+
+    /// The value at the right end of the graph. Defaults to the largest of
+    /// the value, the target, and the ranges.
+    pub max: Smart<Scalar>,
+
+    /// The width of the bullet graph.
+    #[default(Em::new(6.0).into())]
+    pub width: Rel<Length>,
+
+    /// The height of the bullet graph.
+    #[default(Em::new(0.8).into())]
+    pub height: Rel<Length>,
+
+    /// How to fill the bar and the target mark. Defaults to the current text
+    /// color.
+    pub fill: Smart<Paint>,
+}
+
+impl Show for Packed<BulletElem> {
+    #[typst_macros::time(name = "bullet", span = self.span())]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let value = self.value().get();
+        let target = self.target().get();
+        let mut ranges: Vec<f64> = self.ranges(styles).iter().map(|r| r.get()).collect();
+        ranges.sort_by(f64::total_cmp);
+
+        let max = self
+            .max(styles)
+            .map(Scalar::get)
+            .unwrap_or_else(|| ranges.iter().copied().fold(value.max(target), f64::max));
+        let ratio =
+            |v: f64| Ratio::new(if max > 0.0 { (v / max).clamp(0.0, 1.0) } else { 0.0 });
+        let fill = self.fill(styles).unwrap_or_else(|| TextElem::fill_in(styles));
+
+        // Shade the ranges from largest to smallest, getting darker, so that
+        // the smaller ranges are drawn on top.
+        let mut body = Content::empty();
+        let n = ranges.len();
+        for (i, range) in ranges.iter().enumerate().rev() {
+            let shade = 0.9 - 0.25 * i as f32 / n.max(2) as f32;
+            let rect = RectElem::new()
+                .with_width(Smart::Custom(ratio(*range).into()))
+                .with_height(Smart::Custom(Rel::one()))
+                .with_fill(Some(Color::Luma(Luma::new(shade, 1.0)).into()));
+            body += PlaceElem::new(rect.pack()).pack();
+        }
+
+        let bar = RectElem::new()
+            .with_width(Smart::Custom(ratio(value).into()))
+            .with_height(Smart::Custom(Ratio::new(1.0 / 3.0).into()))
+            .with_fill(Some(fill.clone()));
+        body += PlaceElem::new(bar.pack())
+            .with_dy(Ratio::new(1.0 / 3.0).into())
+            .pack();
+
+        let stroke = Stroke {
+            paint: Smart::Custom(fill),
+            thickness: Smart::Custom(Em::new(0.12).into()),
+            ..Default::default()
+        };
+        let mark = LineElem::new()
+            .with_start(Axes::new(ratio(target).into(), Ratio::new(1.0 / 6.0).into()))
+            .with_end(Some(Axes::new(ratio(target).into(), Ratio::new(5.0 / 6.0).into())))
+            .with_stroke(stroke);
+        body += PlaceElem::new(mark.pack()).pack();
+
+        Ok(micro_box(self.width(styles), self.height(styles), body).pack())
+    }
+}
+
+/// A progress bar that fits into a line of text.
+///
+/// # Example
+/// ```example
+/// Upload: #progress(35%) \
+/// Download: #progress(80%, fill: green)
+/// ```
+#[elem(Show)]
+pub struct ProgressElem {
+    /// How much progress has been made.
+    #[required]
+    pub ratio: Ratio,
+
+    /// The width of the progress bar.
+    #[default(Em::new(5.0).into())]
+    pub width: Rel<Length>,
+
+    /// The height of the progress bar.
+    #[default(Em::new(0.5).into())]
+    pub height: Rel<Length>,
+
+    /// How to fill the completed part of the progress bar. Defaults to the
+    /// current text color.
+    pub fill: Smart<Paint>,
+
+    /// How to fill the remaining part of the progress bar.
+    #[default(Color::SILVER.into())]
+    pub track: Paint,
+}
+
+impl Show for Packed<ProgressElem> {
+    #[typst_macros::time(name = "progress", span = self.span())]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let ratio = Ratio::new(self.ratio().get().clamp(0.0, 1.0));
+        let fill = self.fill(styles).unwrap_or_else(|| TextElem::fill_in(styles));
+        let height = self.height(styles);
+        let bar = RectElem::new()
+            .with_width(Smart::Custom(ratio.into()))
+            .with_height(Smart::Custom(Rel::one()))
+            .with_fill(Some(fill));
+        Ok(micro_box(self.width(styles), height, bar.pack())
+            .with_fill(Some(self.track(styles)))
+            .with_radius(Corners::splat(Some((height.abs / 2.0).into())))
+            .with_clip(true)
+            .pack())
+    }
+}
+
+/// Create an inline box of the given size around the body.
+fn micro_box(width: Rel<Length>, height: Rel<Length>, body: Content) -> BoxElem {
+    BoxElem::new()
+        .with_width(Sizing::Rel(width))
+        .with_height(Smart::Custom(height))
+        .with_body(Some(body))
+}
@@ -1,11 +1,11 @@
 use ecow::EcoString;
 
-use crate::diag::{SourceResult, StrResult};
+use crate::diag::{bail, SourceResult, StrResult};
 use crate::foundations::{
     cast, dict, func, scope, ty, Args, Cast, Dict, Fold, FromValue, NoneValue, Repr,
     Resolve, Smart, StyleChain, Value,
 };
-use crate::layout::{Abs, Length};
+use crate::layout::{Abs, Length, Rel};
 use crate::util::{Numeric, Scalar};
 use crate::visualize::{Color, Gradient, Paint, Pattern};
 
@@ -60,7 +60,7 @@ pub struct Stroke<T: Numeric = Length> {
     /// The stroke's line join.
     pub join: Smart<LineJoin>,
     /// The stroke's line dash pattern.
-    pub dash: Smart<Option<DashPattern<T>>>,
+    pub dash: Smart<Option<DashPattern<DashLength<T>, Rel<T>>>>,
     /// The miter limit.
     pub miter_limit: Smart<Scalar>,
 }
@@ -115,6 +115,10 @@ impl Stroke {
 
         /// How the ends of the stroke are rendered.
         ///
+        /// For a dashed stroke, the cap is also used at the ends of each dash.
+        /// The dash ends can't be given a different cap than the ends of the
+        /// stroke, as neither PDF nor SVG can represent that.
+        ///
         /// If set to `{auto}`, the value is inherited, defaulting to `{"butt"}`.
         #[external]
         cap: Smart<LineCap>,
@@ -138,12 +142,20 @@ impl Stroke {
         ///   - `{"dash-dotted"}`
         ///   - `{"densely-dash-dotted"}`
         ///   - `{"loosely-dash-dotted"}`
+        ///   - `{"dash-dot-dotted"}`
+        ///   - `{"densely-dash-dot-dotted"}`
+        ///   - `{"loosely-dash-dot-dotted"}`
         /// - An [array] with alternating lengths for dashes and gaps. You can
         ///   also use the string `{"dot"}` for a length equal to the line
         ///   thickness.
         /// - A [dictionary] with the keys `array` (same as the array above),
-        ///   and `phase` (of type [length]), which defines where in the pattern
-        ///   to start drawing.
+        ///   and `phase`, which defines where in the pattern to start drawing.
+        ///   The phase can be a [length] or a [ratio] of the total length of
+        ///   the pattern, which makes it easy to shift a pattern by a fraction
+        ///   of its period, for instance to create animation frames.
+        ///
+        /// Dash lengths must not be negative. A pattern whose lengths are all
+        /// zero is drawn as a solid line.
         ///
         /// If set to `{auto}`, the value is inherited, defaulting to `{none}`.
         ///
@@ -154,6 +166,7 @@ impl Stroke {
         ///   line(stroke: (dash: "dashed")),
         ///   line(stroke: (dash: (10pt, 5pt, "dot", 5pt))),
         ///   line(stroke: (dash: (array: (10pt, 5pt, "dot", 5pt), phase: 10pt))),
+        ///   line(stroke: (dash: (array: (10pt, 5pt, "dot", 5pt), phase: 50%))),
         /// )
         /// ```
         #[external]
@@ -223,7 +236,7 @@ impl<T: Numeric> Stroke<T> {
                             DashLength::LineWidth => DashLength::LineWidth,
                         })
                         .collect(),
-                    phase: f(pattern.phase),
+                    phase: pattern.phase.map(&f),
                 })
             }),
             miter_limit: self.miter_limit,
@@ -237,16 +250,7 @@ impl Stroke<Abs> {
         let thickness = self.thickness.unwrap_or(default.thickness);
         let dash = self
             .dash
-            .map(|pattern| {
-                pattern.map(|pattern| DashPattern {
-                    array: pattern
-                        .array
-                        .into_iter()
-                        .map(|l| l.finish(thickness))
-                        .collect(),
-                    phase: pattern.phase,
-                })
-            })
+            .map(|pattern| pattern.and_then(|pattern| pattern.finish(thickness)))
             .unwrap_or(default.dash);
 
         FixedStroke {
@@ -453,14 +457,40 @@ impl Repr for LineJoin {
 
 /// A line dash pattern.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub struct DashPattern<T: Numeric = Length, DT = DashLength<T>> {
+pub struct DashPattern<DT = DashLength, PT = Rel<Length>> {
     /// The dash array.
     pub array: Vec<DT>,
     /// The dash phase.
-    pub phase: T,
+    pub phase: PT,
 }
 
-impl<T: Numeric + Repr, DT: Repr> Repr for DashPattern<T, DT> {
+impl DashPattern<DashLength<Abs>, Rel<Abs>> {
+    /// Compute the final dash array and phase for a line of the given
+    /// thickness.
+    ///
+    /// Returns `None` if the pattern would not produce any gaps, in which case
+    /// the line should be drawn solid. Odd arrays are repeated to obtain an
+    /// even number of entries and the phase is normalized into the pattern's
+    /// period so that all exporters interpret the pattern in the same way.
+    pub fn finish(self, thickness: Abs) -> Option<DashPattern<Abs, Abs>> {
+        let mut array: Vec<Abs> =
+            self.array.into_iter().map(|l| l.finish(thickness)).collect();
+        if array.len() % 2 == 1 {
+            array.extend_from_within(..);
+        }
+
+        let period: Abs = array.iter().copied().sum();
+        if array.is_empty() || period <= Abs::zero() || !period.is_finite() {
+            return None;
+        }
+
+        let phase = self.phase.relative_to(period);
+        let phase = Abs::raw(phase.to_raw().rem_euclid(period.to_raw()));
+        Some(DashPattern { array, phase })
+    }
+}
+
+impl<DT: Repr, PT: Repr> Repr for DashPattern<DT, PT> {
     fn repr(&self) -> EcoString {
         let mut r = EcoString::from("(array: (");
         for (i, elem) in self.array.iter().enumerate() {
@@ -476,14 +506,14 @@ impl<T: Numeric + Repr, DT: Repr> Repr for DashPattern<T, DT> {
     }
 }
 
-impl<T: Numeric + Default> From<Vec<DashLength<T>>> for DashPattern<T> {
+impl<T: Numeric> From<Vec<DashLength<T>>> for DashPattern<DashLength<T>, Rel<T>> {
     fn from(array: Vec<DashLength<T>>) -> Self {
-        Self { array, phase: T::default() }
+        Self { array, phase: Rel::zero() }
     }
 }
 
 impl Resolve for DashPattern {
-    type Output = DashPattern<Abs>;
+    type Output = DashPattern<DashLength<Abs>, Rel<Abs>>;
 
     fn resolve(self, styles: StyleChain) -> Self::Output {
         DashPattern {
@@ -509,12 +539,15 @@ cast! {
     "dash-dotted" => vec![Abs::pt(3.0).into(), Abs::pt(2.0).into(), DashLength::LineWidth, Abs::pt(2.0).into()].into(),
     "densely-dash-dotted" => vec![Abs::pt(3.0).into(), Abs::pt(1.0).into(), DashLength::LineWidth, Abs::pt(1.0).into()].into(),
     "loosely-dash-dotted" => vec![Abs::pt(3.0).into(), Abs::pt(4.0).into(), DashLength::LineWidth, Abs::pt(4.0).into()].into(),
+    "dash-dot-dotted" => vec![Abs::pt(3.0).into(), Abs::pt(2.0).into(), DashLength::LineWidth, Abs::pt(2.0).into(), DashLength::LineWidth, Abs::pt(2.0).into()].into(),
+    "densely-dash-dot-dotted" => vec![Abs::pt(3.0).into(), Abs::pt(1.0).into(), DashLength::LineWidth, Abs::pt(1.0).into(), DashLength::LineWidth, Abs::pt(1.0).into()].into(),
+    "loosely-dash-dot-dotted" => vec![Abs::pt(3.0).into(), Abs::pt(4.0).into(), DashLength::LineWidth, Abs::pt(4.0).into(), DashLength::LineWidth, Abs::pt(4.0).into()].into(),
 
-    array: Vec<DashLength> => Self { array, phase: Length::zero() },
+    array: Vec<DashLength> => Self { array, phase: Rel::zero() },
     mut dict: Dict => {
         let array: Vec<DashLength> = dict.take("array")?.cast()?;
         let phase = dict.take("phase").ok().map(Value::cast)
-            .transpose()?.unwrap_or(Length::zero());
+            .transpose()?.unwrap_or(Rel::zero());
         dict.finish(&["array", "phase"])?;
         Self {
             array,
@@ -572,7 +605,11 @@ cast! {
         Self::Length(v) => v.into_value(),
     },
     "dot" => Self::LineWidth,
-    v: Length => Self::Length(v),
+    v: Length => if v.abs < Abs::zero() || v.em.get() < 0.0 {
+        bail!("dash length must not be negative");
+    } else {
+        Self::Length(v)
+    },
 }
 
 /// A fully specified stroke of a geometric shape.
@@ -27,6 +27,7 @@ use std::{env, fs};
 
 use clap::Parser;
 use comemo::{Prehashed, Track};
+use ecow::EcoString;
 use oxipng::{InFile, Options, OutFile};
 use rayon::iter::{ParallelBridge, ParallelIterator};
 use tiny_skia as sk;
@@ -329,6 +330,19 @@ impl World for TestWorld {
     fn today(&self, _: Option<i64>) -> Option<Datetime> {
         Some(Datetime::from_ymd(1970, 1, 1).unwrap())
     }
+
+    fn modified(&self, id: FileId) -> Option<Datetime> {
+        self.file(id).ok()?;
+        Datetime::from_ymd_hms(1970, 1, 1, 12, 30, 0)
+    }
+
+    fn execute(&self, lang: &str, code: &str) -> Option<StrResult<EcoString>> {
+        match lang {
+            "shout" => Some(Ok(code.to_uppercase().into())),
+            "fail" => Some(Err("exit code 1".into())),
+            _ => None,
+        }
+    }
 }
 
 impl TestWorld {
@@ -0,0 +1,77 @@
+// Test the bits type.
+// Ref: false
+
+---
+#let b = bytes((0xa1, 0x0f)).bits()
+#test(b.len(), 16)
+#test(b.at(0), true)
+#test(b.at(1), false)
+#test(b.at(-1), true)
+#test(b.at(16, default: none), none)
+#test(repr(b), "bits(\"10100001 00001111\")")
+#test(repr(b.slice(4, 10)), "bits(\"000100\")")
+#test(b.slice(4, count: 8).int(), 0x10)
+#test(b.slice(12).bytes(), bytes((0xf0,)))
+#test(b.bytes(), bytes((0xa1, 0x0f)))
+
+---
+// Test the constructor and equality.
+#test(bits("1010_0001 0000_1111"), bytes((0xa1, 0x0f)).bits())
+#test(bits(bytes((1,))), bits("00000001"))
+#test(bits("0110").slice(1, 3), bits("11"))
+#test(bits("").len(), 0)
+#test(type(bits("1")), bits)
+
+---
+// Test integer decoding.
+#let data = bytes((0x01, 0x80, 0xff, 0xfe))
+#test(data.bits().slice(0, 16).int(), 384)
+#test(data.bits().slice(0, 16).int(endian: "little"), 0x8001)
+#test(data.bits().slice(16).int(signed: true), -2)
+#test(data.bits().slice(16).int(signed: true, endian: "little"), -257)
+#test(bits("1111").int(signed: true), -1)
+#test(bits("0111").int(signed: true), 7)
+#test(bits("").int(), 0)
+#test(data.bits().ints(16), (384, 65534))
+#test(data.bits().ints(16, endian: "little", signed: true), (-32767, -257))
+#test(data.bits().ints(8), (1, 128, 255, 254))
+
+---
+// Test bitfields.
+#let (version, ihl) = bytes((0x45,)).bits().fields(4, 4)
+#test((version, ihl), (4, 5))
+#test(bits("101 00110 1").fields(3, 5, 1), (5, 6, 1))
+#test(bits("111 10").fields(3, 2, signed: true), (-1, -2))
+#test(bits("1111").fields(2), (3,))
+
+---
+// Error: 2-20 bit index out of bounds (index: 4, len: 4) and no default value was specified
+#bits("1010").at(4)
+
+---
+// Error: 2-26 bit index out of bounds (index: 5, len: 4)
+#bits("1010").slice(0, 5)
+
+---
+// Error: 7-13 bits must only contain zeros and ones
+#bits("1021")
+
+---
+// Error: 2-36 little-endian decoding requires a whole number of bytes
+#bits("1010").int(endian: "little")
+
+---
+// Error: 2-42 cannot decode more than 64 bits into an integer
+#bytes(range(9)).bits().int(signed: true)
+
+---
+// Error: 2-32 integer is too large
+#bytes((255,) * 8).bits().int()
+
+---
+// Error: 2-22 number of bits (4) is not a multiple of the width (3)
+#bits("1010").ints(3)
+
+---
+// Error: 2-27 fields are wider than the bits (5 > 4)
+#bits("1010").fields(2, 3)
@@ -25,11 +25,16 @@
 #box(square(size: 9pt, fill: rgb(col)))
 #box(square(size: 9pt, fill: oklab(col)))
 #box(square(size: 9pt, fill: oklch(col)))
+#box(square(size: 9pt, fill: color.lab(col)))
+#box(square(size: 9pt, fill: color.lch(col)))
+#box(square(size: 9pt, fill: color.xyz(col)))
+#box(square(size: 9pt, fill: color.p3(col)))
 #box(square(size: 9pt, fill: luma(col)))
 #box(square(size: 9pt, fill: cmyk(col)))
 #box(square(size: 9pt, fill: color.linear-rgb(col)))
 #box(square(size: 9pt, fill: color.hsl(col)))
 #box(square(size: 9pt, fill: color.hsv(col)))
+#box(square(size: 9pt, fill: color.hwb(col)))
 
 ---
 // Colors outside the sRGB gamut.
@@ -99,3 +104,460 @@
 #test-repr(luma(100%, 100%).opacify(250%), luma(100%, 100%))
 #test-repr(luma(100%, 50%).opacify(-50%), luma(100%, 25%))
 #test-repr(luma(100%, 0%).opacify(0%), luma(100%, 0%))
+
+---
+// Test CIELAB and CIELCh conversion.
+// Ref: false
+#test(rgb(color.lab(54.29%, 80.8, 69.89)).to-hex(), "#ff0000")
+#test(rgb(color.lch(54.29%, 106.84, 40.85deg)).to-hex(), "#ff0000")
+#test(color.lab(rgb("#336699")).to-hex(), "#336699")
+#test(color.lch(rgb("#336699")).to-hex(), "#336699")
+#test(color.lab(50%, 50%, -100%).components(), (50%, 62.5, -125.0, 100%))
+#test(color.lch(50%, 50%, 30deg).components(alpha: false), (50%, 75.0, 30deg))
+#test-repr(color.lab(50%, 20, -30.5, 50%), color.lab(50%, 20, -30.5, 50%))
+#test-repr(color.lch(20%, 30, 90deg).negate(space: color.lch), color.lch(80%, 30, 270deg))
+
+---
+// Test CIE XYZ conversion.
+// Ref: false
+#test(rgb(color.xyz(0.4124, 0.2126, 0.0193)).to-hex(), "#ff0000")
+#test(rgb(color.xyz(0.4361, 0.2225, 0.0139, white-point: "d50")).to-hex(), "#ff0000")
+#test(color.xyz(rgb("#336699")).to-hex(), "#336699")
+#test(color.xyz(rgb("#336699"), white-point: "d50").to-hex(), "#336699")
+#test(color.xyz(0.25, 50%, 0.5).components(alpha: false), (0.25, 0.5, 0.5))
+#test-repr(color.xyz(luma(100%), white-point: "d50"), color.xyz(0.964, 1, 0.825, white-point: "d50"))
+#test-repr(color.xyz(0.2, 0.3, 0.4, 50%, white-point: "d50"), color.xyz(0.2, 0.3, 0.4, 50%, white-point: "d50"))
+#test-repr(color.xyz(0.2, 0.3, 0.4).negate(space: color.xyz), color.xyz(0.75, 0.7, 0.689))
+#test-repr(color.mix(color.xyz(0.2, 0.2, 0.2), color.xyz(0.4, 0.4, 0.4), space: color.xyz), color.xyz(0.3, 0.3, 0.3))
+#test(color.xyz(0.2, 0.3, 0.4, white-point: "d50").lighten(10%).space(), color.xyz)
+#test(repr(color.xyz(0.2, 0.3, 0.4, white-point: "d50").rotate(20deg)).ends-with("white-point: \"d50\")"), true)
+
+---
+// Test Display P3 conversion.
+// Ref: false
+#test(color.p3(rgb("#336699")).to-hex(), "#336699")
+#test-repr(color.p3(rgb("#ff0000")), color.p3(91.75%, 20.03%, 13.86%))
+#test(rgb(color.p3(91.75%, 20.03%, 13.86%)).to-hex(), "#ff0000")
+#test(rgb(color.p3(100%, 0%, 0%)).to-hex(), "#ff3428")
+#test(color.p3(100%, 0%, 0%).lighten(50%), color.p3(100%, 50%, 50%))
+#test-repr(color.p3(20%, 40%, 60%, 50%), color.p3(20%, 40%, 60%, 50%))
+#test-repr(color.p3(20%, 40%, 60%).negate(space: color.p3), color.p3(80%, 60%, 40%))
+#test(color.p3(10%, 20%, 30%).darken(10%).space(), color.p3)
+
+---
+// Error: 40-45 expected "d50" or "d65"
+#color.xyz(0.1, 0.2, 0.3, white-point: "d55")
+
+---
+// Test HWB conversion.
+// Ref: false
+#test(rgb(color.hwb(0deg, 0%, 0%)).to-hex(), "#ff0000")
+#test(rgb(color.hwb(120deg, 20%, 30%)).to-hex(), "#33b333")
+#test(rgb(color.hwb(90deg, 60%, 60%)).to-hex(), "#808080")
+#test(color.hwb(rgb("#336699")).to-hex(), "#336699")
+#test(color.hwb(30deg, 25%, 50%).components(), (30deg, 25%, 50%, 100%))
+#test-repr(color.hwb(30deg, 20%, 30%, 50%), color.hwb(30deg, 20%, 30%, 50%))
+#test-repr(color.hwb(30deg, 20%, 30%).negate(space: color.hwb), color.hwb(210deg, 20%, 30%))
+#test-repr(color.hwb(30deg, 20%, 30%).rotate(90deg, space: color.hwb), color.hwb(120deg, 20%, 30%))
+#test(color.hwb(30deg, 20%, 30%).saturate(10%).space(), color.hwb)
+
+---
+// Test OkHSL and OkHSV conversion.
+// Ref: false
+#test(rgb(color.okhsl(0deg, 0%, 100%)).to-hex(), "#ffffff")
+#test(rgb(color.okhsv(0deg, 0%, 0%)).to-hex(), "#000000")
+#test(color.okhsl(rgb("#336699")).to-hex(), "#336699")
+#test(color.okhsv(rgb("#336699")).to-hex(), "#336699")
+#test(color.okhsl(30deg, 25%, 50%).components(), (30deg, 25%, 50%, 100%))
+#test(color.okhsv(30deg, 25%, 50%, 40%).components(alpha: false), (30deg, 25%, 50%))
+#test-repr(color.okhsl(30deg, 20%, 30%, 50%), color.okhsl(30deg, 20%, 30%, 50%))
+#test-repr(color.okhsv(30deg, 20%, 30%).negate(space: color.okhsv), color.okhsv(210deg, 20%, 30%))
+#test-repr(color.okhsl(30deg, 20%, 30%).rotate(90deg, space: color.okhsl), color.okhsl(120deg, 20%, 30%))
+#test(color.okhsl(30deg, 20%, 30%).lighten(10%).space(), color.okhsl)
+#test(color.okhsv(30deg, 20%, 30%).saturate(10%).space(), color.okhsv)
+#test(color.okhsl(30deg, 20%, 50%).components(named: true).lightness, 50%)
+#test(color.okhsv(30deg, 20%, 50%).components(named: true).value, 50%)
+
+---
+// Test contrast.
+// Ref: false
+#test(black.contrast(white), 21.0)
+#test(white.contrast(black), 21.0)
+#test(red.contrast(red), 1.0)
+#test(calc.round(rgb("#777777").contrast(white), digits: 2), 4.48)
+#test(calc.round(black.contrast(white, method: "apca"), digits: 2), 106.04)
+#test(calc.round(white.contrast(black, method: "apca"), digits: 2), -107.88)
+#test(calc.round(rgb("#888888").contrast(white, method: "apca"), digits: 2), 63.06)
+#test(calc.round(white.contrast(rgb("#888888"), method: "apca"), digits: 2), -68.54)
+#test(gray.contrast(gray, method: "apca"), 0.0)
+
+---
+// Error: 32-37 expected "wcag" or "apca"
+#black.contrast(white, method: "lab")
+
+---
+// Test delta E.
+// Ref: false
+#test(red.delta-e(red), 0.0)
+#test(calc.round(black.delta-e(white)), 100.0)
+#test(calc.round(black.delta-e(white, method: "76")), 100.0)
+#test(calc.round(black.delta-e(white, method: "ok"), digits: 3), 1.0)
+#test(calc.round(red.delta-e(maroon), digits: 2), 35.31)
+#test(calc.round(red.delta-e(maroon, method: "76"), digits: 2), 64.77)
+#test(calc.round(red.delta-e(maroon, method: "ok"), digits: 3), 0.277)
+#test(rgb("#336699").delta-e(rgb("#336698")) < 1, true)
+#test(blue.delta-e(navy), navy.delta-e(blue))
+
+---
+// Error: 28-32 expected "2000", "76", or "ok"
+#red.delta-e(blue, method: "94")
+
+---
+// Test closest color in a palette.
+// Ref: false
+#let palette = (navy, teal, maroon, olive)
+#test(blue.closest(palette), navy)
+#test(red.closest(palette), maroon)
+#test(maroon.closest(palette), maroon)
+#test(red.closest(palette, index: true), 2)
+#test(green.closest(palette, method: "ok"), olive)
+#test(gray.closest((white, black, white)), white)
+#test(gray.closest((black, black), index: true), 0)
+
+---
+// Error: 2-17 palette must not be empty
+#red.closest(())
+
+---
+// Test gamut mapping.
+// Ref: false
+#let c = oklch(70%, 0.3, 145deg)
+#test(c.to-space(rgb), rgb("#00c800"))
+#test(c.to-space(rgb, gamut-map: "clip"), rgb("#00c800"))
+#test(c.to-space(rgb, gamut-map: "css"), rgb("#00c300"))
+#test(c.to-space(rgb, gamut-map: "chroma-reduce"), rgb("#00bf34"))
+#let (_, _, hue, _) = c.to-space(rgb, gamut-map: "chroma-reduce").to-space(oklch).components()
+#test(calc.round(hue.deg()), 145.0)
+#test(c.to-space(oklab, gamut-map: "css"), c.to-space(oklab))
+#test(repr(c.to-space(color.p3, gamut-map: "css")), "color.p3(10.1%, 77.12%, 0%)")
+#test(color.p3(100%, 0%, 0%).to-space(rgb), rgb("#ff0000"))
+#test(color.p3(100%, 0%, 0%).to-space(rgb, gamut-map: "chroma-reduce"), rgb("#ff3428"))
+#test(color.p3(100%, 0%, 0%).to-space(cmyk, gamut-map: "css").space(), cmyk)
+#test(red.to-space(rgb, gamut-map: "css"), red)
+
+---
+// Error: 31-37 expected "clip", "css", or "chroma-reduce"
+#red.to-space(rgb, gamut-map: "none")
+
+---
+// Test hue interpolation direction when mixing.
+// Ref: false
+#let a = oklch(70%, 0.1, 30deg)
+#let b = oklch(70%, 0.1, 90deg)
+#test(color.mix(a, b, space: oklch), oklch(70%, 0.1, 60deg))
+#test(color.mix(a, b, space: oklch, hue: "shorter"), oklch(70%, 0.1, 60deg))
+#test(color.mix(a, b, space: oklch, hue: "longer"), oklch(70%, 0.1, 240deg))
+#test(color.mix(a, b, space: oklch, hue: "increasing"), oklch(70%, 0.1, 60deg))
+#test(color.mix(a, b, space: oklch, hue: "decreasing"), oklch(70%, 0.1, 240deg))
+#test(color.mix(b, a, space: oklch, hue: "increasing"), oklch(70%, 0.1, 240deg))
+#let c = color.hsl(350deg, 50%, 50%)
+#let d = color.hsl(10deg, 50%, 50%)
+#test(color.mix(c, d, space: color.hsl), color.hsl(0deg, 50%, 50%))
+#test(color.mix(c, d, space: color.hsl, hue: "longer"), color.hsl(180deg, 50%, 50%))
+
+---
+// Error: 28-39 expected "shorter", "longer", "increasing", or "decreasing"
+#color.mix(red, blue, hue: "clockwise")
+
+---
+// Test gamut checks.
+// Ref: false
+#test(red.in-gamut(rgb), true)
+#test(red.in-gamut(luma), false)
+#test(luma(40%).in-gamut(luma), true)
+#test(oklch(70%, 0.1, 145deg).in-gamut(rgb), true)
+#test(oklch(70%, 0.3, 145deg).in-gamut(rgb), false)
+#test(oklch(70%, 0.3, 145deg).in-gamut(oklab), true)
+#test(oklch(70%, 0.3, 145deg).in-gamut(color.xyz), true)
+#test(color.p3(100%, 0%, 0%).in-gamut(color.p3), true)
+#test(color.p3(100%, 0%, 0%).in-gamut(rgb), false)
+#test(oklch(60%, 0.35, 30deg).to-space(rgb, gamut-map: "css").in-gamut(rgb), true)
+
+---
+// Error: 18-33 component must be finite
+#oklch(50%, 0.1, calc.inf * 1deg)
+
+---
+// Error: 13-21 component must be finite
+#oklab(50%, calc.inf, 0%)
+
+---
+// Test relative luminance.
+// Ref: false
+#test(white.luminance(), 1.0)
+#test(black.luminance(), 0.0)
+#test(calc.round(rgb(100%, 0%, 0%).luminance(), digits: 4), 0.2126)
+#test(calc.round(luma(50%).luminance(), digits: 4), 0.214)
+#test(luma(50%).luminance(), rgb(50%, 50%, 50%).luminance())
+#test(calc.round(oklab(100%, 0, 0).luminance(), digits: 4), 1.0)
+#test(calc.round(color.xyz(95.047%, 100%, 108.883%).luminance(), digits: 4), 1.0)
+#test(rgb(0, 0, 0, 50%).luminance(), 0.0)
+
+---
+// Test color harmonies.
+// Ref: false
+#let c = oklch(60%, 0.1, 30deg)
+#test(c.analogous(), (c.rotate(-30deg), c, c.rotate(30deg)))
+#test(c.analogous(4, angle: 20deg), (-30deg, -10deg, 10deg, 30deg).map(a => c.rotate(a)))
+#test(c.analogous(1), (c,))
+#test(c.analogous(0), ())
+#test(c.triadic(), (c, c.rotate(120deg), c.rotate(240deg)))
+#test(c.split-complementary(), (c, c.rotate(150deg), c.rotate(210deg)))
+#test(c.split-complementary(angle: 10deg).at(1), c.rotate(170deg))
+#test(red.triadic().first(), red)
+#test(red.triadic(space: color.hsl).at(1), red.rotate(120deg, space: color.hsl))
+#test(red.triadic().map(c => c.space()), (rgb, rgb, rgb))
+
+---
+// Error: 2-27 this colorspace does not support hue rotation
+#red.triadic(space: oklab)
+
+---
+// Test the web color module.
+// Ref: false
+#test(color.web.cornflowerblue, rgb("#6495ed"))
+#test(color.web.red, rgb("#ff0000"))
+#test(color.web.rebeccapurple, color.css("rebeccapurple"))
+#test(color.web.grey, color.web.gray)
+#test(color.web.red != red, true)
+#test(dictionary(color.web).len(), 148)
+
+---
+// Error: 12-18 module `web` does not contain `bluish`
+#color.web.bluish
+
+---
+// Test custom color maps.
+// Ref: false
+#let lightness(c) = oklab(c).components().first()
+#let close(a, b) = calc.abs(float(a) - float(b)) < 0.01
+#test(color.map.custom(black, white).len(), 256)
+#test(color.map.custom(red, blue, samples: 2), (oklab(red), oklab(blue)))
+#test(color.map.custom(red, blue, samples: 3, space: rgb).map(c => c.space()), (rgb,) * 3)
+
+// Samples are spaced by perceived difference, regardless of the offsets.
+#let skewed = ((black, 0%), (white, 10%), (white, 100%))
+#assert(close(lightness(color.map.custom(..skewed, samples: 3).at(1)), 50%))
+#test(color.map.custom(..skewed, samples: 3, uniform: false).at(1), oklab(white))
+
+// A map with two identical stops is flat.
+#test(color.map.custom(red, red, samples: 4), (oklab(red),) * 4)
+
+---
+// Error: 2-23 a color map must have at least two stops
+// Hint: 2-23 try filling the map with a single color instead
+#color.map.custom(red)
+
+---
+// Error: 39-40 a color map must have at least two samples
+#color.map.custom(red, blue, samples: 1)
+
+---
+// Test sampling color maps.
+// Ref: false
+#test(color.map.sample((red, blue), 0%), oklab(red))
+#test(color.map.sample((red, blue), 50%), color.mix(red, blue))
+#test(color.map.sample((red, blue), 150%), oklab(blue))
+#test(color.map.sample((red, blue), 50%, space: rgb), color.mix(red, blue, space: rgb))
+#test(color.map.sample(((red, 0%), (green, 10%), (blue, 100%)), 10%), oklab(green))
+#test(color.map.sample(color.map.viridis, 100%), oklab(color.map.viridis.last()))
+#test(color.map.sample(color.map.custom(red, blue, samples: 8), 0%), oklab(red))
+
+---
+// Error: 19-25 a color map must have at least two stops
+#color.map.sample((red,), 50%)
+
+---
+// Error: 19-27 expected color, array, or dictionary, found string
+#color.map.sample(("a", 1), 50%)
+
+---
+// Test resampling and reversing color maps.
+// Ref: false
+#test(color.map.resample((red, blue), 3), (oklab(red), color.mix(red, blue), oklab(blue)))
+#test(color.map.resample(color.map.viridis, 2), (oklab(color.map.viridis.first()), oklab(color.map.viridis.last())))
+#test(color.map.resample((red, blue), 2, space: rgb).map(c => c.space()), (rgb,) * 2)
+#test(color.map.resample(((red, 0%), (red, 50%), (blue, 100%)), 3), (oklab(red), oklab(red), oklab(blue)))
+#test(color.map.reverse((red, green, blue)), (blue, green, red))
+#test(color.map.reverse(((red, 0%), (blue, 25%))), ((blue, 75%), (red, 100%)))
+#test(color.map.reverse(color.map.reverse(color.map.mako)), color.map.mako)
+
+---
+// Error: 34-35 a color map must have at least two samples
+#color.map.resample((red, blue), 1)
+
+---
+// Error: 21-27 a color map must have at least two stops
+#color.map.resample((red,), 3)
+
+---
+// Test tinting, shading and toning.
+// Ref: false
+#test(rgb(0%, 50%, 100%).tint(50%, space: rgb), rgb(50%, 75%, 100%))
+#test(rgb(50%, 50%, 100%).shade(50%, space: rgb), rgb(25%, 25%, 50%))
+#test(rgb(100%, 0%, 0%).tone(50%, space: rgb), rgb(75%, 25%, 25%))
+#test(rgb(0%, 0%, 0%, 50%).tint(100%, space: rgb), rgb(100%, 100%, 100%, 50%))
+#test(red.tint(0%, space: rgb), red)
+#test(oklch(50%, 0.1, 30deg).shade(20%).space(), oklch)
+#test(cmyk(10%, 20%, 30%, 40%).tone(50%).space(), cmyk)
+#test(luma(50%).tint(50%, space: luma), luma(75%))
+
+---
+// Error: 2-16 factor must be between 0% and 100%
+#red.tint(150%)
+
+---
+// Test quantizing colors.
+// Ref: false
+#test(rgb(30%, 60%, 90%).quantize(2), rgb(0%, 100%, 100%))
+#test(rgb(20%, 40%, 90%, 50%).quantize(3), rgb(0%, 50%, 100%, 50%))
+#test(luma(40%).quantize(3), luma(50%))
+#test(cmyk(10%, 40%, 60%, 90%).quantize(2), cmyk(0%, 0%, 100%, 100%))
+#test(oklab(40%, 0.1, 0.1).quantize(4).space(), oklab)
+#test(color.quantize(red, 2), rgb(100%, 0%, 0%))
+
+// Test reducing colors to a palette.
+#test(color.quantize((red, blue, red), 5), (oklab(red), oklab(blue)))
+#test(color.quantize((), 3), ())
+#let palette = color.quantize((red, rgb(90%, 0%, 0%), blue, rgb(0%, 0%, 90%), blue), 2)
+#test(palette.len(), 2)
+#test(color.closest(blue, palette, index: true), 0)
+#test(color.closest(red, palette, index: true), 1)
+
+---
+// Error: 15-16 number of levels must be at least 2
+#red.quantize(1)
+
+---
+// Error: 25-26 palette must have at least one color
+#color.quantize((red,), 0)
+
+---
+// Test overprinting CMYK colors.
+#let key = cmyk(0%, 0%, 0%, 100%)
+#test(repr(key.overprint()), "cmyk(0%, 0%, 0%, 100%, overprint: true)")
+#test(cmyk(0%, 0%, 0%, 100%, overprint: true), key.overprint())
+#test(key.overprint().overprint(false), key)
+#test(key.overprint() == key, false)
+#test(cmyk(key, overprint: true), key.overprint())
+#test(key.overprint().lighten(50%), key.lighten(50%).overprint())
+#rect(fill: cmyk(60%, 0%, 10%, 0%), inset: 4pt, text(fill: key.overprint())[Over])
+
+---
+// Error: 2-17 only CMYK colors can overprint
+#red.overprint()
+
+---
+// Test serializing colors to CSS.
+// Ref: false
+#test(rgb("#336699").to-css(), "rgb(51 102 153)")
+#test(rgb(255, 0, 0, 50%).to-css(), "rgb(255 0 0 / 50%)")
+#test(rgb("#ff000080").to-css(), "rgb(255 0 0 / 50.2%)")
+#test(oklch(62%, 0.2, 30deg, 80%).to-css(), "oklch(62% 0.2 30deg / 80%)")
+#test(oklab(50%, 0.1, -0.05).to-css(), "oklab(50% 0.1 -0.05)")
+#test(color.lab(54.29%, 80.8, 69.89).to-css(), "lab(54.29% 80.8 69.89)")
+#test(color.lch(50%, 75, 450deg).to-css(), "lch(50% 75 90deg)")
+#test(color.hsl(210deg, 50%, 40%).to-css(), "hsl(210deg 50% 40%)")
+#test(color.hwb(120deg, 20%, 30%).to-css(), "hwb(120deg 20% 30%)")
+#test(color.p3(100%, 50%, 0%).to-css(), "color(display-p3 1 0.5 0)")
+#test(color.linear-rgb(50%, 25%, 0%).to-css(), "color(srgb-linear 0.5 0.25 0)")
+#test(color.xyz(0.2, 0.3, 0.4, white-point: "d50").to-css(), "color(xyz-d50 0.2 0.3 0.4)")
+#test(color.hsv(rgb("#336699")).to-css(), "hsl(210deg 50% 40%)")
+#test(luma(0%).to-css(), "rgb(0 0 0)")
+#test(cmyk(10%, 20%, 30%, 40%).to-css().starts-with("rgb("), true)
+#for c in (rgb("#336699"), oklch(62%, 0.2, 30deg), color.p3(10%, 20%, 30%)) {
+  test(color.css(c.to-css()), c)
+}
+
+---
+// Test easing when mixing two colors.
+// Ref: false
+#test(color.mix((red, 25%), (blue, 75%), easing: t => t), color.mix((red, 25%), (blue, 75%)))
+#test(color.mix(red, blue, easing: t => 1), color.mix((red, 0%), (blue, 100%)))
+#test(color.mix(red, blue, easing: t => -1), color.mix((red, 100%), (blue, 0%)))
+#test(color.mix((red, 25%), (blue, 75%), easing: "ease-in") != color.mix((red, 25%), (blue, 75%)), true)
+#test(color.mix(red, blue, white, easing: "linear"), color.mix(red, blue, white))
+
+---
+// Error: 2-48 easing can only be used when mixing two colors
+#color.mix(red, blue, white, easing: "ease-in")
+
+---
+// Test linear-light luma.
+// Ref: false
+#test(luma(0%, linear: true), luma(0%))
+#test(luma(100%, linear: true), luma(100%))
+#test(luma(50%, 40%, linear: true).components(alpha: false).len(), 1)
+#test(calc.round(luma(50%, linear: true).components().first() / 1%, digits: 2), 73.54)
+#test(calc.round(luma(20%, linear: true).components().first() / 1%, digits: 2), 48.45)
+#test(luma(red, linear: true), luma(red))
+
+---
+// Test that mixing in grayscale keeps the alpha component.
+// Ref: false
+#test(color.mix(luma(0%), luma(40%), space: luma), luma(20%))
+#test(color.mix(luma(0%, 50%), luma(40%), space: luma), luma(20%, 75%))
+
+---
+// Test sorting colors.
+// Ref: false
+#test(color.sort((blue, red, green)), (red, green, blue))
+#test(color.sort((white, black, gray), by: "lightness"), (black, gray, white))
+#test(color.sort((red, gray, blue), by: "chroma"), (gray, blue, red))
+#test(color.sort((white, black), by: "lightness", space: color.hsl), (black, white))
+#test(color.sort(()), ())
+
+---
+// Error: 2-49 color space does not have a lightness component
+#color.sort((red,), by: "lightness", space: rgb)
+
+---
+// Test compositing colors.
+// Ref: false
+#test(rgb(0%, 0%, 0%, 50%).composite(white), rgb(50%, 50%, 50%))
+#test(red.composite(blue), rgb(red))
+#test(rgb(0%, 0%, 0%, 0%).composite(blue), rgb(blue))
+#test(rgb(100%, 0%, 0%, 50%).composite(rgb(0%, 0%, 100%, 50%), mode: "in"), rgb(100%, 0%, 0%, 25%))
+#test(rgb(100%, 0%, 0%, 50%).composite(rgb(0%, 0%, 100%, 50%), mode: "out"), rgb(100%, 0%, 0%, 25%))
+#test(rgb(100%, 0%, 0%, 50%).composite(rgb(0%, 0%, 100%, 50%), mode: "xor"), rgb(50%, 0%, 50%, 50%))
+#test(rgb(100%, 0%, 0%, 50%).composite(rgb(0%, 0%, 100%, 50%), mode: "atop"), rgb(50%, 0%, 50%, 50%))
+#test(rgb(0%, 0%, 0%, 0%).composite(rgb(0%, 0%, 0%, 0%)), rgb(0%, 0%, 0%, 0%))
+#test(luma(0%, 50%).composite(luma(100%), space: luma), luma(50%))
+#test(red.composite(blue, space: oklab).space(), oklab)
+
+---
+// Error: 2-39 cannot composite colors in a hue-based space
+#red.composite(blue, space: color.hsl)
+
+---
+// Error: 2-34 cannot composite colors in CMYK, as it has no alpha component
+#red.composite(blue, space: cmyk)
+
+---
+// Test blending colors.
+// Ref: false
+#let gray = rgb(50%, 50%, 50%)
+#let lime = rgb(50%, 100%, 0%)
+#test(gray.blend(lime), rgb(25%, 50%, 0%))
+#test(gray.blend(lime, mode: "screen"), rgb(75%, 100%, 50%))
+#test(gray.blend(lime, mode: "difference"), rgb(0%, 50%, 50%))
+#test(gray.blend(lime, mode: "darken"), rgb(50%, 50%, 0%))
+#test(gray.blend(lime, mode: "lighten"), rgb(50%, 100%, 50%))
+#test(blue.blend(red, mode: "normal"), rgb(blue))
+#test(rgb(0%, 0%, 0%, 0%).blend(red), rgb(red))
+#test(luma(50%).blend(luma(50%), space: luma), luma(25%))
+#test(red.blend(blue, mode: "luminosity").space(), rgb)
+
+---
+// Error: 2-31 colors can only be blended in RGB and grayscale spaces
+#red.blend(blue, space: oklab)
@@ -153,6 +153,21 @@
 #test-components(color.hsv(10deg, 20%, 30%), (10deg, 20%, 30%, 100%))
 #test-components(color.hsl(10deg, 20%, 30%), (10deg, 20%, 30%, 100%))
 
+---
+// Test named color components.
+#test(
+  color.hsl(90deg, 25%, 50%).components(named: true),
+  (hue: 90deg, saturation: 25%, lightness: 50%, alpha: 100%),
+)
+#test(rgb(0%, 25%, 50%, 75%).components(named: true, alpha: false), (red: 0%, green: 25%, blue: 50%))
+#test(luma(50%).components(named: true), (lightness: 50%, alpha: 100%))
+#test(cmyk(0%, 25%, 50%, 75%).components(named: true, alpha: false).key, 75%)
+#test(oklch(10%, 0.2, 90deg).components(named: true).keys(), ("lightness", "chroma", "hue", "alpha"))
+#test(color.lab(50%, 20, -30).components(named: true).b, -30.0)
+#test(color.xyz(0.25, 0.5, 0.75).components(named: true).y, 0.5)
+#test(color.hwb(30deg, 25%, 50%).components(named: true).blackness, 50%)
+#test(color.hsv(30deg, 25%, 50%).components(named: true).value, 50%)
+
 ---
 // Test color conversions.
 #test(rgb(1, 2, 3).to-hex(), "#010203")
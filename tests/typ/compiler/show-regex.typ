@@ -0,0 +1,22 @@
+// Test capture groups in regex show rules.
+
+---
+#show regex("(\d+)\.(\d+)\.(\d+)"): it => {
+  let (major, minor, patch) = it.captures
+  [v#(major).#(minor)]
+  if patch != "0" [.#(patch)]
+}
+#show regex("(\d+)(?:(cm)|(kg))"): it => {
+  let (value, cm, kg) = it.captures
+  [#value#sym.space.thin]
+  if cm != none { text(blue, cm) }
+  if kg != none { text(red, kg) }
+}
+
+Released 1.4.0, patched in 1.4.2.
+Bring 5kg for every 20cm.
+
+---
+// Text matched by a string has no capture groups.
+#show "hello": it => test(it.captures, ())
+hello
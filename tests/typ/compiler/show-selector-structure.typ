@@ -0,0 +1,71 @@
+// Test selectors over ancestry.
+
+---
+// Test `within` with a labelled ancestor.
+#set page(width: 150pt)
+#show figure.within(<appendix>): set figure(numbering: "A")
+#show heading.within(<appendix>): set text(red)
+
+#figure(rect(height: 10pt), caption: [Main])
+#block[
+  = Appendix
+  #figure(rect(height: 10pt), caption: [Extra])
+] <appendix>
+#figure(rect(height: 10pt), caption: [Main again])
+
+---
+// Test `at-depth` with nested figures.
+#set page(width: 150pt)
+#show figure.at-depth(1): set figure(numbering: "1")
+#show figure.at-depth(2): set figure(numbering: "(a)")
+
+#figure(
+  grid(
+    columns: 2,
+    gutter: 1em,
+    figure(rect(width: 20pt, height: 10pt), caption: [Left]),
+    figure(rect(width: 20pt, height: 10pt), caption: [Right]),
+  ),
+  caption: [Both],
+)
+
+---
+// Test `within` combined with other selectors.
+#show strong.within(heading.where(level: 2)): set text(blue)
+#show heading.within(figure): it => [Nested: #it.body]
+= Hello *World*
+== Hello *World*
+#figure(block(heading(outlined: false)[Inner]), caption: [Outer])
+
+---
+// Test `first-on-page` in queries.
+#set page(width: 120pt, height: 80pt)
+#set heading(outlined: false)
+#context query(heading.first-on-page()).map(it => it.body).join[, ]
+= One
+= Two
+#pagebreak()
+= Three
+= Four
+
+---
+// Ref: false
+#test(repr(figure.within(<a>)), "figure.within(<a>)")
+#test(repr(selector(heading).at-depth(2)), "heading.at-depth(2)")
+#test(repr(heading.where(level: 1).first-on-page()), "heading.where(level: 1).first-on-page()")
+
+---
+// Error: 8-26 this selector can only be used with show rules
+#query(figure.within(<a>))
+
+---
+// Error: 7-30 this selector cannot be used with show
+#show heading.first-on-page(): none
+
+---
+// Error: 2-22 `within()` can only be called on element functions
+#calc.abs.within(<a>)
+
+---
+// Error: 19-20 number must be positive
+#heading.at-depth(0)
@@ -53,11 +53,11 @@
 #test(calc.round(calc.pi, digits: 2), 3.14)
 
 ---
-// Error: 6-10 expected integer, boolean, float, or string, found length
+// Error: 6-10 expected integer, boolean, float, decimal, or string, found length
 #int(10pt)
 
 ---
-// Error: 8-13 expected float, boolean, integer, ratio, or string, found type
+// Error: 8-13 expected float, boolean, integer, decimal, ratio, or string, found type
 #float(float)
 
 ---
@@ -79,7 +79,7 @@
 #test(calc.abs(-25%), 25%)
 
 ---
-// Error: 11-22 expected integer, float, length, angle, ratio, or fraction, found string
+// Error: 11-22 expected integer, float, decimal, length, angle, ratio, or fraction, found string
 #calc.abs("no number")
 
 ---
@@ -46,6 +46,12 @@
 #test(oklch(rgb(10, 20, 30)).space(), oklch)
 #test(color.hsl(rgb(10, 20, 30)).space(), color.hsl)
 #test(color.hsv(rgb(10, 20, 30)).space(), color.hsv)
+#test(color.hwb(rgb(10, 20, 30)).space(), color.hwb)
+#test(rgb(color.hwb(10deg, 20%, 30%)).space(), rgb)
+#test(oklab(color.hwb(10deg, 20%, 30%)).space(), oklab)
+#test(color.okhsl(rgb(10, 20, 30)).space(), color.okhsl)
+#test(color.okhsv(rgb(10, 20, 30)).space(), color.okhsv)
+#test(rgb(color.okhsl(10deg, 20%, 30%)).space(), rgb)
 #test(cmyk(rgb(10, 20, 30)).space(), cmyk)
 #test(luma(rgb(10, 20, 30)).space(), luma)
 
@@ -112,6 +118,68 @@
 #test(cmyk(luma(10%)).space(), cmyk)
 #test(luma(luma(10%)).space(), luma)
 
+#test(rgb(color.lab(50%, 20, -30)).space(), rgb)
+#test(oklab(color.lab(50%, 20, -30)).space(), oklab)
+#test(color.lab(color.lab(50%, 20, -30)).space(), color.lab)
+#test(color.lch(color.lab(50%, 20, -30)).space(), color.lch)
+#test(cmyk(color.lab(50%, 20, -30)).space(), cmyk)
+#test(luma(color.lab(50%, 20, -30)).space(), luma)
+
+#test(rgb(color.lch(50%, 30, 120deg)).space(), rgb)
+#test(color.lab(color.lch(50%, 30, 120deg)).space(), color.lab)
+#test(color.lch(color.lch(50%, 30, 120deg)).space(), color.lch)
+#test(color.lab(rgb(10, 20, 30)).space(), color.lab)
+#test(color.lch(rgb(10, 20, 30)).space(), color.lch)
+
+#test(rgb(color.xyz(0.2, 0.3, 0.4)).space(), rgb)
+#test(color.lab(color.xyz(0.2, 0.3, 0.4)).space(), color.lab)
+#test(color.xyz(color.xyz(0.2, 0.3, 0.4)).space(), color.xyz)
+#test(color.xyz(rgb(10, 20, 30)).space(), color.xyz)
+#test(color.xyz(color.lch(50%, 30, 120deg), white-point: "d50").space(), color.xyz)
+
+#test(rgb(color.p3(10%, 20%, 30%)).space(), rgb)
+#test(oklab(color.p3(10%, 20%, 30%)).space(), oklab)
+#test(color.xyz(color.p3(10%, 20%, 30%)).space(), color.xyz)
+#test(color.p3(color.p3(10%, 20%, 30%)).space(), color.p3)
+#test(color.p3(rgb(10, 20, 30)).space(), color.p3)
+#test(color.p3(color.lab(50%, 20, -30)).space(), color.p3)
+
+---
+// Test CSS color strings.
+#test(color.css("cornflowerblue"), rgb("#6495ed"))
+#test(color.css(" #ABC "), rgb("#aabbcc"))
+#test(color.css("rgb(255 0 0 / 50%)"), rgb(255, 0, 0, 50%))
+#test(color.css("rgba(0, 0, 255, 0.25)"), rgb(0, 0, 255, 25%))
+#test(color.css("hsl(120 50% 25%)").space(), color.hsl)
+#test(color.css("hwb(120deg 50% 25%)").space(), color.hwb)
+#test(color.css("lab(50% 40 -20)"), color.lab(50%, 40, -20))
+#test(color.css("lch(50% 75 90deg)"), color.lch(50%, 75, 90deg))
+#test(color.css("oklab(50% 0.25 none)"), oklab(50%, 0.25, 0))
+#test(color.css("oklch(50% 25% 0.5turn)"), oklch(50%, 0.1, 180deg))
+#test(color.css("color(display-p3 1 0 0)"), color.p3(100%, 0%, 0%))
+#test(color.css("color(srgb-linear 50% 25% 0)"), color.linear-rgb(50%, 25%, 0%))
+#test(color.css("color(xyz-d50 0.5 0.25 0.75)"), color.xyz(0.5, 0.25, 0.75, white-point: "d50"))
+
+---
+// Error: 12-20 unknown color name `bluish`
+#color.css("bluish")
+
+---
+// Error: 12-34 expected 3 color components, found 4
+#color.css("rgb(10 20 30 40 / 5)")
+
+---
+// Error: 12-28 failed to parse ICC profile
+#color.icc(bytes((1, 2, 3)), (50%,))
+
+---
+// Error: 12-29 ICC profile must be for a gray, RGB, or CMYK color space
+#color.icc(bytes(range(128)), (50%,))
+
+---
+// Error: 47-54 expected "perceptual", "relative-colorimetric", "saturation", or "absolute-colorimetric"
+#color.icc(bytes(range(128)), (50%,), intent: "vivid")
+
 ---
 // Test gray color conversion.
 // Ref: true
@@ -152,15 +220,15 @@
 #color.mix((red, 1, 2))
 
 ---
-// Error: 31-38 expected `rgb`, `luma`, `cmyk`, `oklab`, `oklch`, `color.linear-rgb`, `color.hsl`, or `color.hsv`, found string
+// Error: 31-38 expected `rgb`, `luma`, `cmyk`, `oklab`, `oklch`, `color.lab`, `color.lch`, `color.xyz`, `color.p3`, `color.linear-rgb`, `color.hsl`, `color.hsv`, `color.hwb`, `color.okhsl`, or `color.okhsv`, found string
 #color.mix(red, green, space: "cyber")
 
 ---
-// Error: 31-36 expected `rgb`, `luma`, `cmyk`, `oklab`, `oklch`, `color.linear-rgb`, `color.hsl`, or `color.hsv`
+// Error: 31-36 expected `rgb`, `luma`, `cmyk`, `oklab`, `oklch`, `color.lab`, `color.lch`, `color.xyz`, `color.p3`, `color.linear-rgb`, `color.hsl`, `color.hsv`, `color.hwb`, `color.okhsl`, or `color.okhsv`
 #color.mix(red, green, space: image)
 
 ---
-// Error: 31-41 expected `rgb`, `luma`, `cmyk`, `oklab`, `oklch`, `color.linear-rgb`, `color.hsl`, or `color.hsv`
+// Error: 31-41 expected `rgb`, `luma`, `cmyk`, `oklab`, `oklch`, `color.lab`, `color.lch`, `color.xyz`, `color.p3`, `color.linear-rgb`, `color.hsl`, `color.hsv`, `color.hwb`, `color.okhsl`, or `color.okhsv`
 #color.mix(red, green, space: calc.round)
 
 ---
@@ -194,7 +262,7 @@
 #test(str(10 / 3).len() > 10, true)
 
 ---
-// Error: 6-8 expected integer, float, version, bytes, label, type, or string, found content
+// Error: 6-8 expected integer, float, decimal, version, bytes, label, type, or string, found content
 #str([])
 
 ---
@@ -283,6 +351,10 @@
 #test(datetime.today(offset: auto).display(), "1970-01-01")
 #test(datetime.today(offset: 2).display(), "1970-01-01")
 
+// Test modified
+#test(datetime.modified().display(), "1970-01-01 12:30:00")
+#test(datetime.modified("construct.typ").hour(), 12)
+
 ---
 // Error: 2-12 at least one of date or time must be fully specified
 #datetime()
@@ -295,6 +367,10 @@
 // Error: 2-41 date is invalid
 #datetime(year: 2000, month: 2, day: 30)
 
+---
+// Error: 2-34 unable to get the modification date
+#datetime.modified("missing.txt")
+
 ---
 // Error: 27-34 missing closing bracket for bracket at index 0
 #datetime.today().display("[year")
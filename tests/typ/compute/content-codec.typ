@@ -0,0 +1,109 @@
+// Test encoding and decoding of content.
+// Ref: false
+
+---
+#let roundtrip(body) = test(content.decode(body.encode()), body)
+
+#roundtrip[Hello *world*!]
+#roundtrip[= Heading <intro>]
+#roundtrip[- A\ - B\ + C\ / Term: Description]
+#roundtrip[#raw("raw") and "quotes" and #link("https://typst.app")[links]]
+#roundtrip[$ x^2 + root(3, y) / 2 $]
+#roundtrip(rect(width: 2cm, fill: red, stroke: 2pt + blue)[Box])
+#roundtrip(align(center + horizon)[Centered])
+#roundtrip(grid(columns: (1fr, 50%, auto), [A], [B], [C]))
+#roundtrip(text(size: 12pt, fill: gradient.linear(red, blue))[Styled])
+#roundtrip({
+  set text(lang: "de")
+  set par(leading: 1em)
+  [Gestylt]
+})
+
+---
+// Values survive the roundtrip without losing precision.
+#let roundtrip(value) = {
+  let body = content.decode(metadata(value).encode())
+  test(body.value, value)
+  test(repr(body.value), repr(value))
+}
+
+#roundtrip(rgb(12.345%, 50.5%, 0.1%, 66.6%))
+#roundtrip(oklch(54.321%, 0.1234, 123.456deg))
+#roundtrip(color.hsl(301.5deg, 12.5%, 98.76%))
+#roundtrip(cmyk(1.5%, 22.2%, 33.3%, 44.4%))
+#roundtrip(luma(33.3%))
+#roundtrip(color.xyz(0.5, 0.25, 0.75, white-point: "d50"))
+#roundtrip(33.3333333333%)
+#roundtrip(gradient.linear((red, 0%), (blue, 33.333333333%), (green, 100%)))
+#roundtrip(gradient.radial(focal-center: (12.5%, 40%), space: color.hsl, red, blue))
+#roundtrip(gradient.conic(angle: 12.345deg, hue: "longer", easing: "ease-in", red, blue))
+#roundtrip(gradient.linear(red, blue).sharp(3))
+#roundtrip(version(1, 2, 3, 4))
+#roundtrip(datetime(year: 2024, month: 2, day: 29, hour: 13, minute: 37, second: 5))
+#roundtrip(datetime(hour: 1, minute: 2, second: 3))
+#roundtrip(duration(days: 3, seconds: 15))
+#roundtrip(decimal("-12345678901234.5678901234"))
+#roundtrip(center + horizon)
+#roundtrip(end)
+#roundtrip(rtl)
+#roundtrip(stroke(paint: gradient.linear(red, blue), thickness: 1.5pt, dash: "dash-dotted"))
+#roundtrip(stroke(cap: "round", join: "bevel", miter-limit: 3.5))
+
+---
+// Colors from ICC profiles keep their profile.
+// A minimal gray profile, whose only tag is a linear tone curve.
+#let u32(n) = range(4).map(i => calc.rem(calc.quo(n, calc.pow(256, 3 - i)), 256))
+#let sig(s) = array(bytes(s))
+#let profile = bytes(
+  u32(156) + (0,) * 4 + (2, 16, 0, 0) + sig("mntrGRAYXYZ ") + (0,) * 12
+    + sig("acsp") + (0,) * 88 + u32(1) + sig("kTRC") + u32(144) + u32(12)
+    + sig("curv") + u32(0) + u32(0)
+)
+#let color = color.icc(profile, (12.345%,), intent: "saturation")
+#let decoded = content.decode(metadata(color).encode()).value
+#test(decoded, color)
+#test(decoded.components(), color.components())
+
+---
+// Error: 2-67 cannot encode gradient with an easing function in field `value` of metadata
+#metadata(gradient.linear(red, blue, easing: t => t * t)).encode()
+
+---
+// Labels and styles survive the roundtrip.
+#let body = content.decode([#strong(delta: 200)[A] <a>].encode())
+#test(body.children.first().label, <a>)
+#test(body.children.first().delta, 200)
+
+---
+// Error: 2-33 cannot encode content with show rules
+#{ show "a": "b"; [a] }.encode()
+
+---
+// Error: 2-27 cannot encode function in field `value` of metadata
+#metadata(x => x).encode()
+
+---
+// Error: 2-34 data is not encoded content
+#content.decode(bytes("invalid"))
+
+---
+// The encoding of a format version never changes, so that content encoded by
+// an earlier compiler can still be decoded.
+#let body = [#strong(delta: 200)[Hi] <a>]
+#let golden = bytes((
+  116, 121, 112, 115, 116, 45, 99, 116, 1, 0, 8, 0, 0, 0, 115, 101,
+  113, 117, 101, 110, 99, 101, 1, 0, 0, 0, 8, 0, 0, 0, 99, 104,
+  105, 108, 100, 114, 101, 110, 13, 2, 0, 0, 0, 15, 1, 1, 0, 0,
+  0, 97, 6, 0, 0, 0, 115, 116, 114, 111, 110, 103, 2, 0, 0, 0,
+  5, 0, 0, 0, 100, 101, 108, 116, 97, 3, 200, 0, 0, 0, 0, 0,
+  0, 0, 4, 0, 0, 0, 98, 111, 100, 121, 15, 0, 4, 0, 0, 0,
+  116, 101, 120, 116, 1, 0, 0, 0, 4, 0, 0, 0, 116, 101, 120, 116,
+  5, 2, 0, 0, 0, 72, 105, 15, 0, 5, 0, 0, 0, 115, 112, 97,
+  99, 101, 0, 0, 0, 0,
+))
+#test(body.encode(), golden)
+#test(content.decode(golden), body)
+
+---
+// Error: 2-69 unsupported content encoding version 2
+#content.decode(bytes((116, 121, 112, 115, 116, 45, 99, 116, 2, 0)))
@@ -142,3 +142,249 @@
 ---
 // Error: 6-28 failed to parse XML (found closing tag 'data' instead of 'hello' in line 3)
 #xml("/assets/data/bad.xml")
+
+---
+// Test reading an SQLite database.
+#let runs = sqlite("data.db", table: "runs")
+#test(runs.len(), 4)
+#test(runs.at(0), (id: 1, name: "alpha", score: 0.75, notes: none, raw: bytes((1, 2))))
+#test(runs.at(2).notes.len(), 2000)
+#test(runs.at(3).score, none)
+
+---
+// Test filtering and sorting rows with array methods.
+#let runs = sqlite("data.db", table: "runs")
+#test(
+  runs.filter(run => run.score != none and run.score >= 0.5).sorted(key: run => -run.score).map(run => run.name),
+  ("alpha", "gamma"),
+)
+
+---
+// Test reading a table that spans multiple pages.
+#let rows = sqlite("data.db", table: "sample data")
+#test(rows.len(), 300)
+#test(rows.map(row => row.value).sorted().rev().slice(1, 3), (88754, 88159))
+#test(rows.filter(row => row.value < 0).len(), 8)
+
+---
+// Test decoding a database from bytes.
+#let data = read("data.db", encoding: none)
+#test(sqlite.decode(data, table: "sqlite_schema").filter(row => row.type == "table").len(), 3)
+
+---
+// Test reading the only table of a database.
+#test(sqlite("single.db"), ((name: "red", hex: "#ff0000"), (name: "green", hex: "#00ff00")))
+
+---
+// Error: 9-18 database contains 3 tables
+// Hint: 9-18 select one of them with the `table` argument
+#sqlite("data.db")
+
+---
+// Error: 27-36 unknown table `missing`
+#sqlite("data.db", table: "missing")
+
+---
+// Error: 9-18 failed to read SQLite database (tables without row IDs are not supported)
+#sqlite("data.db", table: "pairs")
+
+---
+// Error: 16-30 failed to read SQLite database (not an SQLite database)
+#sqlite.decode(bytes("hello"), table: "runs")
+
+---
+// Test reading a Parquet file.
+#let rows = parquet("data.parquet")
+#test(rows.len(), 5)
+#test(rows.at(0), (
+  id: 1,
+  name: "alpha",
+  score: 0.75,
+  passed: true,
+  day: datetime(year: 2024, month: 3, day: 1),
+  raw: bytes((1, 2)),
+))
+#test(rows.at(2).name, none)
+#test(rows.at(3).score, none)
+#test(rows.map(row => row.id), (1, 2, 3, 4, 5))
+
+---
+// Test selecting columns and limiting rows.
+#test(
+  parquet("data.parquet", columns: ("name", "id"), limit: 3),
+  ((name: "alpha", id: 1), (name: "beta", id: 2), (name: none, id: 3)),
+)
+#test(parquet("data.parquet", limit: 0), ())
+
+---
+// Test reading an Arrow IPC file.
+#test(arrow("data.arrow"), parquet("data.parquet"))
+#test(
+  arrow("data.arrow", columns: ("day",), limit: 1),
+  ((day: datetime(year: 2024, month: 3, day: 1)),),
+)
+
+---
+// Test decoding a Parquet file from bytes.
+#let data = read("data.parquet", encoding: none)
+#test(parquet.decode(data, columns: ("passed",)).filter(row => row.passed).len(), 3)
+
+---
+// Error: 35-45 unknown column `title`
+#parquet("data.parquet", columns: ("title",))
+
+---
+// Error: 17-31 failed to read Parquet file (not a Parquet file)
+#parquet.decode(bytes("hello"))
+
+---
+// Error: 15-29 failed to read Arrow file (not an Arrow IPC file)
+#arrow.decode(bytes("hello"))
+
+---
+// Test reading an iCalendar file.
+#let cal = icalendar("data.ics")
+#test(cal.version, "2.0")
+#test(cal.events.len(), 3)
+#test(cal.todos.len(), 1)
+#test(cal.journals, ())
+#let keynote = cal.events.first()
+#test(keynote.summary, "Keynote: Typesetting, then and now")
+#test(keynote.description, "Opening talk.\nFollowed by coffee.")
+#test(keynote.dtstart, datetime(year: 2024, month: 3, day: 4, hour: 9, minute: 0, second: 0))
+#test(keynote.dtend - keynote.dtstart, duration(minutes: 90))
+#test(keynote.categories, ("Talk", "Plenary"))
+#test(keynote.geo, (52.52, 13.405))
+#test(keynote.priority, 1)
+#test(keynote.organizer, (name: "Ada Lovelace", email: "ada@example.com"))
+#test(keynote.attendee.map(person => person.name), ("Doe, Jane", none))
+#test(keynote.alarms.first().trigger, duration(minutes: -15))
+#test(cal.events.at(1).duration, duration(minutes: 15))
+#test(cal.events.at(2).dtstart, datetime(year: 2024, month: 3, day: 6))
+#test(cal.events.at(2).summary.ends-with("folded onto a second line"), true)
+#test(cal.todos.first().percent-complete, 40)
+
+---
+// Test expanding recurring events.
+#let standup = icalendar("data.ics").events.at(1)
+#test(standup.rrule, (freq: "daily", byday: ("MO", "TU", "WE", "TH", "FR"), count: 6))
+#let occurrences = icalendar.occurrences(standup)
+#test(occurrences.map(event => event.dtstart.day()), (4, 5, 7, 8, 11))
+#test(occurrences.first().keys().contains("rrule"), false)
+#test(icalendar.occurrences(standup, limit: 2).len(), 2)
+#test(icalendar.occurrences(standup, until: datetime(year: 2024, month: 3, day: 7)).len(), 2)
+#test(icalendar.occurrences(icalendar("data.ics").events.first()).len(), 1)
+
+---
+// Test expanding monthly recurrences from a string.
+#let cal = icalendar.decode("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20240101\r\nDTEND;VALUE=DATE:20240102\r\nRRULE:FREQ=MONTHLY;BYDAY=-1FR\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n")
+#let fridays = icalendar.occurrences(cal.events.first(), limit: 4)
+#test(fridays.map(event => event.dtstart.day()), (1, 26, 23, 29))
+#test(fridays.at(1).dtend, datetime(year: 2024, month: 1, day: 27))
+
+---
+// Error: 24-97 recurrence is unbounded (specify `until` or `limit`)
+#icalendar.occurrences((dtstart: datetime(year: 2024, month: 1, day: 1), rrule: (freq: "daily")))
+
+---
+// Error: 19-33 failed to parse iCalendar (expected BEGIN:VCALENDAR at line 1)
+#icalendar.decode("BEGIN:VEVENT")
+
+---
+// Error: 12-22 failed to parse iCalendar (expected BEGIN:VCALENDAR at line 1)
+#icalendar("data.vcf")
+
+---
+// Test reading a vCard file.
+#let (ada, charles) = vcard("data.vcf")
+#test(ada.fn, "Ada Lovelace")
+#test(ada.n.family, "Lovelace")
+#test(ada.nickname, ("Ada", "Enchantress of Numbers"))
+#test(ada.org, ("Analytical Engines Ltd.", "Research"))
+#test(ada.email.map(email => email.value), ("ada@example.com", "ada@home.example"))
+#test(ada.email.at(1).types, ("home", "pref"))
+#test(ada.tel.first().types, ("voice", "work"))
+#test(ada.adr.first().locality, "London")
+#test(ada.bday, datetime(year: 1815, month: 12, day: 10))
+#test(ada.note, "Wrote the first program, arguably.\nLoves poetry.")
+#test(ada.photo.len(), 8)
+#test(charles.tel, ((value: "+44 20 7946 0001", types: ("work", "voice")),))
+#test(charles.bday, "--1226")
+#test(charles.photo, bytes((0xff, 0xd8, 0xff, 0xe0)))
+#test(vcard.decode("BEGIN:VCARD\r\nFN:Folded\r\n  name\r\nEND:VCARD\r\n").first().fn, "Folded name")
+
+---
+// Error: 15-26 failed to parse vCard (expected BEGIN:VCARD at line 1)
+#vcard.decode("FN:Nobody")
+
+---
+// Test reading an XLSX spreadsheet.
+#let rows = xlsx("data.xlsx", sheet: "Data")
+#test(rows.len(), 5)
+#test(rows.at(0), ("Name", "Amount", "Date", "Paid"))
+#test(rows.at(1), ("Über GmbH", 1200, datetime(year: 2024, month: 3, day: 1), true))
+#test(rows.at(2).at(1), 300.5)
+#test(rows.at(2).at(2), datetime(year: 2024, month: 3, day: 2, hour: 9, minute: 0, second: 0))
+#test(rows.at(3), (none, none, none, none))
+#test(rows.at(4), ("Note", none, "#DIV/0!", none))
+#test(xlsx("data.xlsx"), (("Total", 1500.5),))
+#test(xlsx("data.xlsx", sheet: 1, range: "B2:C3"), ((1200, datetime(year: 2024, month: 3, day: 1)), (300.5, datetime(year: 2024, month: 3, day: 2, hour: 9, minute: 0, second: 0))))
+#test(xlsx("data.xlsx", sheet: "Data", range: "$D$3"), ((false,),))
+#test(xlsx("data.xlsx", sheet: "Data", range: "a:a").map(row => row.first()), ("Name", "Über GmbH", "ACME", none, "Note"))
+#test(xlsx.decode(read("data.xlsx", encoding: none), range: "A1:B3"), ((none, none), (none, "Total"), (none, none)))
+
+---
+// Test reading an ODS spreadsheet.
+#let rows = xlsx("data.ods")
+#test(rows.len(), 5)
+#test(rows.at(1), ("Über  GmbH", 1200, datetime(year: 2024, month: 3, day: 1), true))
+#test(rows.at(2), ("ACME\nSecond line", 0.25, datetime(hour: 9, minute: 30, second: 0), none))
+#test(rows.at(3), (none, none, 7, none))
+#test(rows.at(4), (none, none, 7, none))
+#test(xlsx("data.ods", range: "C:C").len(), 5)
+#test(xlsx("data.ods", sheet: "Empty"), ())
+
+---
+// Error: 27-36 unknown sheet `Missing`
+#xlsx("data.xlsx", sheet: "Missing")
+
+---
+// Error: 26-27 sheet index out of bounds (index: 2, len: 2)
+#xlsx("data.ods", sheet: 2)
+
+---
+// Error: 27-33 expected a cell range like "A1:F100"
+#xlsx("data.xlsx", range: "A1:1")
+
+---
+// Error: 27-33 cell range must either specify both rows or none
+#xlsx("data.xlsx", range: "A1:B")
+
+---
+// Error: 7-16 failed to read spreadsheet (file is not a spreadsheet)
+#xlsx("data.db")
+
+---
+// Test reading GeoJSON.
+#let features = geojson("data.geojson")
+#test(features.len(), 3)
+#test(features.at(0).id, "north")
+#test(features.at(0).properties, (name: "North", visited: true))
+#test(features.at(0).geometry.type, "Polygon")
+#test(features.at(1).id, none)
+#test(features.at(1).properties, (:))
+#test(features.at(1).geometry.coordinates.at(1), (5, 5, 100))
+#test(features.at(2).geometry, none)
+
+---
+// Test decoding bare GeoJSON geometries.
+#let features = geojson.decode(`{"type": "Point", "coordinates": [1.5, 2]}`.text)
+#test(features, ((id: none, geometry: (type: "Point", coordinates: (1.5, 2)), properties: (:)),))
+
+---
+// Error: 17-65 failed to parse GeoJSON (unknown geometry type `Circle`)
+#geojson.decode(`{"type": "Circle", "coordinates": [0, 0]}`.text)
+
+---
+// Error: 17-76 failed to parse GeoJSON (position must contain at least two numbers)
+#geojson.decode(`{"type": "LineString", "coordinates": [[0, 0], [1]]}`.text)
@@ -0,0 +1,111 @@
+// Test decimals.
+// Ref: false
+
+---
+// Test construction and conversion.
+#test(type(decimal(1)), decimal)
+#test(repr(decimal("1.50")), "decimal(\"1.50\")")
+#test(repr(decimal(-3)), "decimal(\"-3\")")
+#test(repr(decimal(0.1)), "decimal(\"0.1\")")
+#test(repr(decimal("1.5e3")), "decimal(\"1500\")")
+#test(repr(decimal("\u{2212}2.5e\u{2212}2")), "decimal(\"-0.025\")")
+#test(repr(decimal("1e-30")), "decimal(\"0.0000000000000000000000000000\")")
+#test(str(decimal("-12.30")), "\u{2212}12.30")
+#test(int(decimal("-2.7")), -2)
+#test(float(decimal("2.5")), 2.5)
+#test(decimal(decimal("4.2")), decimal("4.2"))
+
+---
+// Test arithmetic.
+#test(decimal("0.1") + decimal("0.2"), decimal("0.3"))
+#test(decimal("19.99") * 3, decimal("59.97"))
+#test(2 - decimal("0.01"), decimal("1.99"))
+#test(-decimal("1.5"), decimal("-1.5"))
+#test(decimal(1) / 4, decimal("0.25"))
+#test(decimal(2) / decimal(3), decimal("0.6666666666666666666666666667"))
+#test(1 / decimal(8), decimal("0.125"))
+
+---
+// Test comparison.
+#test(decimal("1.50") == decimal("1.5"), true)
+#test(decimal("2") == 2, true)
+#test(decimal("2.0") < 3, true)
+#test(decimal("-0.1") < decimal("0.01"), true)
+#test(calc.max(decimal("1.1"), 1, decimal("1.05")), decimal("1.1"))
+
+---
+// Test rounding.
+#test(decimal("2.5").round(), decimal("3"))
+#test(decimal("2.5").round(mode: "half-even"), decimal("2"))
+#test(decimal("3.5").round(mode: "half-even"), decimal("4"))
+#test(decimal("-2.5").round(mode: "half-toward-zero"), decimal("-2"))
+#test(decimal("2.125").round(digits: 2, mode: "half-even"), decimal("2.12"))
+#test(decimal("-2.1").round(mode: "floor"), decimal("-3"))
+#test(decimal("2.1").round(mode: "ceil"), decimal("3"))
+#test(decimal("2.9").round(mode: "toward-zero"), decimal("2"))
+#test(decimal("2.1").round(mode: "away-from-zero"), decimal("3"))
+#test(decimal("1250").round(digits: -2, mode: "half-even"), decimal("1200"))
+
+---
+// Test formatting.
+#test(decimal("1234.5").display(), "1234.5")
+#test(decimal("1234.5").display(digits: 2), "1234.50")
+#test(decimal("1234.5").display(digits: 0), "1235")
+#test(decimal("1234.5").display(digits: 0, mode: "half-even"), "1234")
+#test(decimal("-0.5").display(digits: 0), "\u{2212}1")
+#test(decimal("1234.5").display(exponent: true), "1.2345e3")
+#test(decimal("1234.5").display(digits: 1, exponent: true), "1.2e3")
+#test(decimal("9.96").display(digits: 1, exponent: true), "1.0e1")
+#test(decimal("0.00125").display(exponent: true), "1.25e\u{2212}3")
+#test(decimal("0").display(digits: 2, exponent: true), "0.00e0")
+#test(decimal("1500").display(exponent: true), "1.5e3")
+
+---
+// Test interoperation with `calc`.
+#test(calc.abs(decimal("-1.5")), decimal("1.5"))
+#test(calc.pow(decimal("1.1"), 2), decimal("1.21"))
+#test(calc.pow(decimal("2"), -2), decimal("0.25"))
+#test(calc.floor(decimal("-1.5")), -2)
+#test(calc.ceil(decimal("1.2")), 2)
+#test(calc.trunc(decimal("-1.7")), -1)
+#test(calc.fract(decimal("-1.75")), decimal("-0.75"))
+#test(calc.round(decimal("2.675"), digits: 2), decimal("2.68"))
+#test(calc.clamp(decimal("5.5"), 0, decimal("5")), decimal("5"))
+#test(calc.rem(decimal("7.5"), 2), decimal("1.5"))
+#test(calc.rem-euclid(decimal("-7.5"), 2), decimal("0.5"))
+#test(calc.div-euclid(decimal("-7.5"), 2), decimal("-4"))
+#test(calc.quo(decimal("7.5"), decimal("2.5")), 3)
+#test(calc.quo(decimal("-7.5"), 2), -4)
+#test(calc.sqrt(decimal("2.25")), 1.5)
+
+---
+// Error: 10-17 invalid decimal: 1.2.3
+#decimal("1.2.3")
+
+---
+// Error: 10-18 cannot convert NaN to a decimal
+#decimal(calc.nan)
+
+---
+// Error: 3-21 cannot add decimal and float
+#(decimal("1") + 0.5)
+
+---
+// Error: 3-28 cannot divide by zero
+#(decimal("1") / decimal(0))
+
+---
+// Error: 3-36 value is too large
+#(decimal("1e20") * decimal("1e20"))
+
+---
+// Error: 27-30 cannot combine decimals with floats
+#calc.rem(decimal("7.5"), 2.0)
+
+---
+// Error: 27-30 decimals can only be raised to integer powers
+#calc.pow(decimal("1.5"), 0.5)
+
+---
+// Error: 29-37 expected "half-away-from-zero", "half-toward-zero", "half-even", "toward-zero", "away-from-zero", "floor", or "ceil"
+#decimal("1.5").round(mode: "banker")
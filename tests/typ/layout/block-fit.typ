@@ -0,0 +1,50 @@
+// Test shrinking text to fit into blocks and cells.
+
+---
+// Test badges with a fixed size.
+#let badge(body) = block(
+  width: 60pt,
+  height: 20pt,
+  inset: 4pt,
+  fill: aqua,
+  fit: "shrink",
+  align(horizon, body),
+)
+
+#badge[Typst]
+#badge[Typesetting system]
+#badge[A much longer label that needs to wrap]
+
+---
+// Test the minimum text size.
+#block(
+  width: 40pt,
+  height: 12pt,
+  fill: aqua,
+  fit: "shrink",
+  min-text-size: 8pt,
+)[Does not fit at all]
+
+---
+// Test shrinking in table cells with fixed rows.
+#table(
+  columns: (50pt, 50pt),
+  rows: 20pt,
+  [Overflowing content],
+  table.cell(fit: "shrink")[Shrunk content],
+  table.cell(fit: "shrink")[Short],
+  table.cell(fit: "shrink", rotate: -90deg)[Rotated],
+)
+
+---
+// Error: 26-32 expected "overflow" or "shrink"
+#block(width: 10pt, fit: "grow")[]
+
+---
+// Test that text with an absolute size is shrunk, too.
+#block(
+  width: 60pt,
+  height: 24pt,
+  fill: aqua,
+  fit: "shrink",
+)[Nested #text(size: 12pt)[absolute #text(size: 14pt)[sizes]] shrink]
@@ -0,0 +1,87 @@
+// Test the calendar element.
+
+---
+#set page(width: 12cm, height: auto)
+#calendar(
+  datetime(year: 2024, month: 5, day: 1),
+  events: (
+    (datetime(year: 2024, month: 5, day: 8), [Launch]),
+    (
+      date: datetime(year: 2024, month: 5, day: 20),
+      end: datetime(year: 2024, month: 5, day: 22),
+      body: [Conference],
+      fill: red.lighten(70%),
+    ),
+  ),
+)
+
+---
+// Test week view, week numbers, and the first day from the region.
+#set page(width: 12cm, height: auto)
+#set text(region: "us")
+#calendar(
+  datetime(year: 2024, month: 3, day: 13),
+  view: "week",
+  week-numbers: true,
+  events: ((datetime(year: 2024, month: 3, day: 14), [Pi day]),),
+)
+
+---
+// Test localized names.
+#set page(width: 12cm, height: auto)
+#set text(lang: "de")
+#calendar(datetime(year: 2023, month: 2, day: 1), day-height: 1.5em, first-day: "wednesday")
+
+---
+// Test recurring dates.
+#let start = datetime(year: 2024, month: 1, day: 31)
+#test(
+  calendar.recurring(start, "month", count: 4),
+  (1, 3, 5, 7).map(m => datetime(year: 2024, month: m, day: 31)),
+)
+#test(
+  calendar.recurring(start, "week", until: datetime(year: 2024, month: 2, day: 14)).len(),
+  3,
+)
+#test(
+  calendar.recurring(datetime(year: 2024, month: 2, day: 29), "year", count: 2).last().year(),
+  2028,
+)
+#test(calendar.recurring(start, duration(days: 2), count: 2).last().day(), 2)
+
+---
+// Test recurring dates at the end of the supported range.
+// Ref: false
+#let night = datetime(hour: 22, minute: 0, second: 0)
+#test(
+  calendar.recurring(night, duration(hours: 1), until: datetime(hour: 23, minute: 30, second: 0)).len(),
+  2,
+)
+#test(
+  calendar.recurring(datetime(year: 9999, month: 12, day: 30), "day", until: datetime(year: 9999, month: 12, day: 31)).len(),
+  2,
+)
+
+---
+// Error: 2-79 recurrence exceeds the supported range of dates
+#calendar.recurring(datetime(year: 9999, month: 12, day: 31), "day", count: 2)
+
+---
+// Error: 2-82 recurrence exceeds the supported range of dates
+#calendar.recurring(datetime(year: 2024, month: 2, day: 29), "year", count: 5000)
+
+---
+// Error: 2-92 recurrence exceeds the supported range of dates
+#calendar.recurring(datetime(hour: 23, minute: 0, second: 0), duration(hours: 2), count: 2)
+
+---
+// Error: 2-45 must specify either `until` or `count`
+#calendar.recurring(datetime.today(), "day")
+
+---
+// Error: 2-68 recurrence interval must be positive
+#calendar.recurring(datetime.today(), duration(days: -1), count: 2)
+
+---
+// Error: 37-59 event array must contain exactly two entries
+#calendar(datetime.today(), events: ((datetime.today(),),))
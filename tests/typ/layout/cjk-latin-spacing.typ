@@ -27,3 +27,21 @@ abc字#linebreak()
 
 abc字\
 母
+
+---
+// Test configuring the width and exceptions.
+#set page(width: 80pt, margin: (x: 5pt))
+#set text(lang: "zh", font: "Noto Serif CJK SC")
+
+#set text(cjk-latin-spacing: 0.5em)
+中文，中12文1中，文12中文
+
+#set text(cjk-latin-spacing: (width: 2pt, except: "%"))
+增长了12%左右
+
+#set text(cjk-latin-spacing: (except: "0123456789"))
+中文，中12文1中，文ab中文
+
+---
+// Error: 30-44 unexpected key "space", valid keys are "width" and "except"
+#set text(cjk-latin-spacing: (space: 0.5em))
@@ -0,0 +1,28 @@
+// Test rotated cells.
+
+---
+#set page(width: 160pt)
+#table(
+  columns: 4,
+  align: bottom,
+  [],
+  ..([Red], [Green], [Blue]).map(table.cell.with(rotate: -90deg)),
+  [Apple], [Yes], [Yes], [No],
+  [Sky], [No], [No], [Yes],
+)
+
+---
+// Multi-line content and other angles.
+#set page(width: 160pt)
+#grid(
+  columns: 3,
+  gutter: 4pt,
+  grid.cell(rotate: 90deg, fill: aqua)[Two \ lines],
+  grid.cell(rotate: 45deg, fill: yellow)[Tilted],
+  grid.cell(rotate: 180deg, fill: green)[Upside],
+)
+
+---
+// Test show rules on the field.
+#show table.cell.where(rotate: 90deg): set text(red)
+#table(columns: 2, table.cell(rotate: 90deg)[A], [B])
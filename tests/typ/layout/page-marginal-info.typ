@@ -0,0 +1,60 @@
+// Test page background and foreground functions.
+
+---
+// Thumb tabs on the outside of two-sided pages.
+#set page(
+  width: 100pt,
+  height: 80pt,
+  margin: (inside: 10pt, outside: 20pt, y: 10pt),
+  background: page => {
+    let heading = page.headings.last
+    if heading != none {
+      let dx = if page.outside == left { 0pt } else { 100% - 12pt }
+      let dy = 8pt + 16pt * (counter(heading.func()).at(heading.location()).first() - 1)
+      place(top + left, dx: dx, dy: dy, rect(width: 12pt, height: 14pt, fill: aqua))
+    }
+  },
+  foreground: page => place(bottom + center, dy: -2pt, text(6pt)[#page.number]),
+)
+#set heading(numbering: "1.")
+#set text(8pt)
+
+= Intro
+#lorem(10)
+
+= Method
+#lorem(12)
+
+= Results
+#lorem(4)
+
+---
+// Test the page information.
+// Ref: false
+#set page(
+  width: 100pt,
+  height: 120pt,
+  margin: (left: 5pt, right: 15pt, y: 10pt),
+  binding: right,
+  background: page => {
+    test(page.margin, (top: 10pt, right: 15pt, bottom: 10pt, left: 5pt))
+    test(page.outside, left)
+    if page.number == 1 {
+      test(page.headings.first.body, [A])
+      test(page.headings.last.body, [B])
+      test(page.headings.previous, none)
+      test(page.labels, (<a>, <x>, <b>))
+    } else {
+      test(page.headings.first, none)
+      test(page.headings.last, none)
+      test(page.headings.previous.body, [B])
+      test(page.labels, (<y>,))
+    }
+  },
+)
+
+= A <a>
+#box[X] <x>
+= B <b>
+#pagebreak()
+#box[Y] <y>
@@ -0,0 +1,33 @@
+// Test isolating marginals from the body's styles.
+
+---
+// Body styles leak into the marginals by default.
+#set page(height: 80pt, header: [Typst Academy], footer: [Page footer])
+#set text(fill: blue)
+#show "Typst": smallcaps
+Typst is a markup-based typesetting system.
+
+---
+// With isolation, only the page's own properties and marginal rules apply.
+#set page(
+  height: 80pt,
+  header: [Typst Academy],
+  footer: [Page footer],
+  isolate-marginals: true,
+)
+#set text(fill: blue)
+#show "Typst": smallcaps
+#show page.marginal: set text(8pt)
+#show page.marginal.where(kind: "footer"): set text(fill: red)
+Typst is a markup-based typesetting system.
+
+---
+// Test styling the page numbering and foreground through marginal rules.
+#set page(
+  height: 80pt,
+  numbering: "1",
+  foreground: place(top + right, dx: -5pt, dy: 5pt)[Draft],
+)
+#show page.marginal.where(kind: "footer"): set text(fill: green)
+#show page.marginal.where(kind: "foreground"): set text(fill: red, 8pt)
+#lorem(10)
@@ -0,0 +1,19 @@
+// Test that page runs, which are laid out in parallel, keep distinct
+// locations for equal elements and get the right page numbers.
+
+---
+#set page(width: 120pt, height: 60pt, numbering: "1")
+#let chapter(title) = {
+  pagebreak(weak: true)
+  counter("chapter").step()
+  metadata("chapter")
+  context [#title: #counter("chapter").display() on page #here().page()]
+}
+
+#chapter[A]
+#chapter[B]
+#set page(fill: aqua)
+#chapter[C]
+
+#context test(query(metadata).map(m => m.location().page()), (1, 2, 3))
+
@@ -0,0 +1,38 @@
+// Test laying out CJK text on a character grid.
+
+---
+// Ref: false
+#set text(size: 10pt)
+#set par(char-grid: 12pt)
+#let approx(a, b) = assert(calc.abs((a - b).pt()) < 0.01, message: repr(a) + " != " + repr(b))
+#let width(body) = measure(body).width
+#let height(body) = measure(body).height
+
+#context {
+  // Every character takes up one cell.
+  approx(width[漢字漢字], 48pt)
+
+  // Latin text is centered in whole cells.
+  approx(width[漢ab字], 36pt)
+  approx(width[漢abcdefgh字], 24pt + 4 * 12pt)
+
+  // Consecutive punctuation is compressed.
+  approx(width[「漢」], 36pt)
+  approx(width[漢。」字], 42pt)
+  approx(width[漢」「字], 42pt)
+
+  // Lines hold whole cells only.
+  approx(height(block(width: 40pt)[漢字漢]), height[漢字漢])
+  assert(height(block(width: 40pt)[漢字漢字]) > height[漢字漢字])
+
+  // Full stops hang into the margin.
+  approx(height(block(width: 36pt)[漢字漢。]), height[漢字漢])
+  assert(height(block(width: 36pt)[漢字漢字。]) > height[漢字漢])
+}
+
+---
+// Ref: false
+// Without a grid, the characters keep their natural widths.
+#set par(char-grid: 2em)
+#context test(measure(par(char-grid: none)[漢字]).width < 40pt, true)
+#context test(measure[漢字].width, 40pt)
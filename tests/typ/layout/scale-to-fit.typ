@@ -0,0 +1,22 @@
+// Test scaling content to fit a box.
+
+---
+// Test fitting to the available width.
+#set page(width: 120pt, height: auto)
+#scale-to-fit[A headline that is far too long]
+#scale-to-fit[Short]
+#scale-to-fit(shrink-only: true)[Short]
+
+---
+// Test fitting to an explicit width and height.
+#set page(width: 120pt, height: auto)
+#box(stroke: 0.5pt, scale-to-fit(width: 60pt, height: 20pt)[Wide content])
+#box(stroke: 0.5pt, scale-to-fit(width: 60pt, height: 20pt, rect(height: 40pt)))
+#box(stroke: 0.5pt, scale-to-fit(width: 50%, table(columns: 6, ..range(12).map(str))))
+
+---
+// Test that tall content moves to the next page before being scaled down.
+#set page(width: 100pt, height: 100pt)
+#v(50pt)
+#block(scale-to-fit(shrink-only: true, rect(width: 40pt, height: 80pt, fill: aqua)))
+#block(scale-to-fit(shrink-only: true, rect(width: 40pt, height: 200pt, fill: teal)))
@@ -0,0 +1,46 @@
+// Test slides with stepwise revealed content.
+
+---
+#set page(height: 50pt, footer: context align(right, counter(page).display()))
+#slide[
+  First #pause() second
+  #pause()
+  Third
+]
+#slide[Done]
+
+---
+// Test incremental lists.
+#set page(height: 70pt)
+#set slide(incremental: true)
+#slide[
+  Before
+  - One
+  - Two
+  + Three
+]
+
+---
+// Test that pauses work within styled content.
+#set page(height: 50pt)
+#slide[
+  #set text(red)
+  A #text(blue)[B #pause() C]
+  #pause()
+  D
+]
+
+---
+// Test that introspection sees the content of each slide once.
+#set heading(numbering: "1.")
+#slide[
+  = Intro
+  #pause()
+  = Details
+  #context test(query(heading).len(), 2)
+  #context test(query(heading).last().location().page(), 1)
+]
+
+---
+// A pause outside of a slide has no effect.
+A #pause() B
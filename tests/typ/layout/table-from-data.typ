@@ -0,0 +1,47 @@
+// Test creating tables from data.
+
+---
+#set page(width: auto)
+#let data = (
+  (name: "Apples", price: 1.5, stock: 120),
+  (name: "Pears", price: 2.25, stock: 80),
+  (name: "Plums", price: -3, stock: 0),
+)
+
+#table.from-data(data)
+
+#table.from-data(
+  data,
+  columns: (
+    (key: "name", title: [*Fruit*], width: 2cm),
+    (key: "price", title: [*Price*], align: right, format: "{:.2} €"),
+    (key: "stock", title: [*In stock*], align: center,
+     format: n => if n == 0 [--] else [#n]),
+  ),
+  fill: (_, y) => if y == 0 { gray.lighten(60%) },
+)
+
+#table.from-data(data, columns: ("stock", "name", "missing"), header: false)
+
+---
+// CSV rows are arrays, whose first row is used for the header.
+#set page(width: auto)
+#let data = csv.decode("Name,Species,Weight\nDebby,Rhinoceros,1900kg\nFluffy,Tiger,115kg\n")
+#table.from-data(data, stroke: blue)
+#table.from-data(data.slice(1), columns: (0, (key: 2, title: [Weight])))
+
+---
+// Error: 18-27 cannot select a value from a dictionary row with an integer key
+#table.from-data(((a: 1),), columns: (0,))
+
+---
+// Error: 18-28 cannot format array with a template
+#table.from-data((((1,),),), columns: ((key: 0, format: "{}"),))
+
+---
+// Error: 18-24 expected array or dictionary, found integer
+#table.from-data((1, 2))
+
+---
+// Error: 38-53 dictionary does not contain key "key"
+#table.from-data(((a: 1),), columns: ((title: "A"),))
@@ -143,3 +143,26 @@ fill: silver)
 $ a &= b \
   - &- - $,
 fill: silver)
+
+---
+// Test numbers in the margin.
+#set page(margin: (x: 30pt))
+#set math.equation(numbering: "(1)", number-position: "margin")
+$ a + b = c $
+#set math.equation(number-align: start)
+$ a + b = c $
+
+---
+// Test inside and outside numbers on two-sided pages.
+#set page(height: 40pt, margin: (inside: 25pt, outside: 35pt, y: 10pt))
+#set math.equation(numbering: "(1)", number-position: "outside")
+$ a + b = c $
+#pagebreak()
+$ a + b = c $
+#pagebreak()
+#set math.equation(number-position: "inside")
+$ a + b = c $
+
+---
+// Error: 55-61 expected "text", "margin", "inside", or "outside"
+#set math.equation(numbering: "(1)", number-position: "left")
@@ -0,0 +1,34 @@
+// Test how inline equations fit into their line.
+
+---
+#set page(width: 100pt)
+#let body = par[Tall $sum_(k=0)^n 1/(1 + 1/x^2)$ math \ and more text.]
+#body
+#set math.equation(inline-fit: "cramp")
+#body
+#set math.equation(inline-fit: "scale")
+#body
+#set math.equation(inline-fit: "overlap")
+#body
+
+---
+// Scaled equations don't change the line height.
+// Ref: false
+#set math.equation(inline-fit: "scale")
+#context {
+  let plain = measure[a \ $x$ b]
+  let tall = measure[a \ $x/(y/z)$ b]
+  assert(calc.abs((plain.height - tall.height) / 1pt) < 0.01)
+}
+
+---
+// Test shifting the baseline.
+#box(fill: silver)[a $x + y$ b]
+#set math.equation(inline-baseline: -2pt)
+#box(fill: silver)[a $x + y$ b]
+#set math.equation(inline-baseline: 2pt)
+#box(fill: silver)[a $x + y$ b]
+
+---
+// Error: 32-40 expected "grow", "cramp", "scale", or "overlap"
+#set math.equation(inline-fit: "shrink")
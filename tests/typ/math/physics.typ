@@ -0,0 +1,37 @@
+// Test the physics helpers.
+
+---
+// Test vectors and differentials.
+$ vb(F) = m va(a) = F vu(r) $
+$ integral_0^1 f(x) dd(x) + integral.double g dd(x, y) $
+$ dd(t, order: 2) $
+
+---
+// Test derivatives.
+$ dv(f, x) = dv(, x) f quad dv(y, t, order: 2) quad dv(f, x, order: n) $
+$ pdv(f, x) quad pdv(f, x, order: 2) quad pdv(f, x, y) $
+$ pdv(f, x, y, order: 2) quad pdv(f, x, y, order: n) $
+
+---
+// Test Dirac notation.
+$ bra(psi) quad ket(psi) quad braket(phi) quad braket(phi, psi) $
+$ braket(m, hat(H), n) $
+$ ketbra(psi) quad ketbra(n, m) quad braket(1/2, psi) $
+
+---
+// Test tensors.
+$ tensor(T, +mu, -nu) = g^(mu alpha) tensor(T, -alpha, -nu) $
+$ tensor(R, +rho, -sigma, -mu, -nu) quad tensor(Gamma, +lambda, +mu, -nu) $
+$ tensor(A, -i) $
+
+---
+// Error: 13-15 tensor index must start with `+` or `-`
+$ tensor(T, mu) $
+
+---
+// Error: 3-21 expected one to three arguments
+$ braket(a, b, c, d) $
+
+---
+// Error: 3-9 missing variable to differentiate with respect to
+$ pdv(f) $
@@ -0,0 +1,32 @@
+// Test the script styling controls of equations.
+
+---
+// Test script sizes.
+$ e^(x^2) + y_(i_j) $
+#set math.equation(script-size: 50%, sscript-size: 40%)
+$ e^(x^2) + y_(i_j) $
+$e^(x^2) + y_(i_j)$
+
+---
+// Test turning off script alternates.
+#set text(size: 20pt)
+$e^(e^(e^e))$
+#set math.equation(script-alternates: false)
+$e^(e^(e^e))$
+
+---
+// Test cramped equations.
+$ x^2 a^b $
+#math.equation(block: true, cramped: true, $ x^2 a^b $)
+
+---
+// Test the fields.
+// Ref: false
+#set math.equation(script-size: 65%)
+#context test(math.equation.script-size, 65%)
+#context test(math.equation.sscript-size, auto)
+#context {
+  let plain = measure($x^2$).width
+  let big = measure[#set math.equation(script-size: 100%); $x^2$].width
+  test(big > plain, true)
+}
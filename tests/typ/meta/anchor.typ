@@ -0,0 +1,49 @@
+// Test named anchors.
+
+---
+// Ref: false
+#set page(width: 200pt, height: 100pt, margin: 10pt)
+#box(
+  width: 60pt,
+  height: 20pt,
+  inset: 5pt,
+  anchors: (input: (0%, 50%), output: (100%, 50%), low: (50%, 100% + 2pt)),
+)[Gate] <gate>
+#block(width: 40pt, height: 10pt, anchors: (mid: (50%, 50%)))
+
+#context {
+  let anchors = query(anchor.where(of: <gate>))
+  test(anchors.map(a => a.name), ("input", "output", "low"))
+  let pos(name) = query(anchor.where(of: <gate>, name: name)).first().location().position()
+  test(pos("input").x, 10pt)
+  test(pos("output").x, 70pt)
+  test(pos("output").y - pos("input").y, 0pt)
+  test(pos("low").y - pos("input").y, 12pt)
+  test(pos("low").page, 1)
+  let mid = query(anchor.where(name: "mid")).first()
+  test(mid.of, none)
+  test(mid.location().position().x, 30pt)
+}
+
+---
+// Test connecting to anchors.
+#set page(width: 160pt, height: 80pt, margin: 5pt)
+#place(top + left)[#box(
+  width: 40pt, height: 40pt, stroke: 1pt,
+  anchors: (port: (100%, 25%)),
+) <a>]
+#place(bottom + right)[#box(
+  width: 40pt, height: 40pt, stroke: 1pt,
+  anchors: (port: (0%, 75%)),
+) <b>]
+#place(connector(<a>, <b>, start-anchor: "port", end-anchor: "port"))
+
+---
+// Error: 2-43 element <a> has no anchor named "nope"
+#connector(<a>, <b>, start-anchor: "nope")
+#box[A] <a>
+#box[B] <b>
+
+---
+// Error: 15-31 expected array, found string
+#box(anchors: (port: "center"))
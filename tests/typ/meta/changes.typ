@@ -0,0 +1,40 @@
+// Test tracked changes.
+
+---
+The contract ends on
+#deletion(author: "Alice")[May 1]
+#insertion(author: "Alice")[June 1]
+and may be extended
+#insertion(author: "Bob")[twice].
+
+---
+// Test explicit fills and change bars.
+#set page(margin: (left: 24pt))
+#set insertion(fill: blue, bar: 1pt)
+#set deletion(bar: 1pt + red)
+Payment is due within #deletion[30] #insertion[60] days.
+
+#insertion[Late payments are charged with a fee that covers the costs of the reminder.]
+
+---
+// Test showing the final text.
+#set document(changes: "final")
+#set insertion(bar: 1pt)
+Hello #deletion[World] #insertion[Typst]!
+
+---
+// Test showing the original text.
+#set document(changes: "original")
+Hello #deletion[World] #insertion[Typst]!
+
+---
+// Changes are locatable.
+#deletion(author: "Alice")[A]
+#insertion(author: "Bob", date: datetime(year: 2024, month: 4, day: 1))[B]
+#context test(query(insertion).first().author, "Bob")
+#context test(query(insertion).first().date.year(), 2024)
+#context test(query(deletion).len(), 1)
+
+---
+// Error: 24-31 expected "markup", "final", or "original"
+#set document(changes: "draft")
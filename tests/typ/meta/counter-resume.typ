@@ -0,0 +1,35 @@
+// Test continuing counters from snapshots.
+// Ref: false
+
+---
+#let counters = (pages: page, headings: heading, theorems: "theorem")
+#counter.resume((pages: (12,), headings: (2, 1), theorems: 4), counters)
+#context test(counter(page).get(), (13,))
+#context test(counter(heading).get(), (2, 1))
+#counter("theorem").step()
+#set heading(numbering: "1.")
+= Chapter
+#context test(counter(heading).get(), (3,))
+#context test(counter("theorem").get(), (5,))
+#context test(
+  counter.snapshot(counters),
+  (pages: (13,), headings: (3,), theorems: (5,)),
+)
+
+---
+// Extra values are ignored.
+#counter.resume((figures: (3,), other: (1,)), (figures: figure.where(kind: image)))
+#figure([A], caption: [B])
+#context test(counter(figure.where(kind: image)).get(), (4,))
+
+---
+// Error: 2-54 missing value for counter `footnotes`
+#counter.resume((pages: (2,)), (footnotes: footnote))
+
+---
+// Error: 2-57 expected integer or array, found string
+#counter.resume((footnotes: "1"), (footnotes: footnote))
+
+---
+// Error: 10-42 expected string, label, function, location, or selector, found integer
+#context counter.snapshot((footnotes: 1))
@@ -20,6 +20,14 @@ What's up?
 #set document(author: (123,))
 What's up?
 
+---
+// Error: 30-46 failed to parse ICC profile
+#set document(output-intent: bytes((1, 2, 3)))
+
+---
+// Error: 30-36 expected bytes or none, found string
+#set document(output-intent: "CMYK")
+
 ---
 Hello
 
@@ -47,3 +55,22 @@ Hello
   // Error: 4-15 pagebreaks are not allowed inside of containers
   #pagebreak()
 ]
+
+---
+// Test custom anchors.
+// Ref: false
+#set document(anchors: it => "sec-" + str(it))
+= Introduction <intro>
+#link(<intro>)[Back to the start]
+
+---
+// Error: 24-26 labels `<a>` and `<b>` have the same anchor
+// Hint: 24-26 each label must map to a distinct anchor
+#set document(anchors: it => "x")
+= A <a>
+= B <b>
+
+---
+// Error: 24-26 expected string, found integer
+#set document(anchors: it => 1)
+= A <a>
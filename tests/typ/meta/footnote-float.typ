@@ -0,0 +1,41 @@
+// Test footnotes in floats, placed elements, and tables.
+
+---
+// A float is moved to the next page together with its footnote.
+#set page(height: 160pt, width: 150pt)
+#lorem(20) #footnote[Before]
+#place(top, float: true, block(height: 30pt, width: 100%, fill: aqua)[
+  Float #footnote[In float]
+])
+#lorem(10) #footnote[After]
+
+---
+// Footnotes in placed elements are listed, too.
+#set page(height: 100pt, width: 150pt)
+A #footnote[Before]
+#place(bottom + right)[Placed #footnote[In place]]
+
+---
+// Test table notes.
+#set page(height: 160pt, width: 150pt)
+A #footnote[Page note]
+#table(
+  columns: 2,
+  footnotes: "table",
+  [a #footnote[Table note]], [b #footnote[Another one]],
+)
+B
+
+---
+// Table notes that don't fit below a table that fills the page move to the
+// next page.
+#set page(height: 120pt, width: 150pt)
+#table(
+  footnotes: "table",
+  block(height: 80pt)[A #footnote[First] #footnote[Second]],
+)
+B
+
+---
+// Error: 31-37 expected "page" or "table"
+#table(columns: 2, footnotes: "none")
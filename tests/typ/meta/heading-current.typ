@@ -0,0 +1,47 @@
+// Test retrieving the current heading.
+
+---
+#set page(
+  paper: "a7",
+  margin: (y: 1cm, x: 0.5cm),
+  header: context {
+    let chapter = heading.current(level: 1)
+    let section = heading.current(level: 2)
+    if chapter != none {
+      emph(chapter.body)
+    }
+    h(1fr)
+    if section != none {
+      section.body
+    }
+  },
+)
+
+#lorem(20)
+
+= Introduction
+#lorem(35)
+
+== Motivation
+#lorem(20)
+
+= Background
+#lorem(35)
+
+---
+// Test that the first heading on the page is in effect.
+// Ref: false
+= First
+== Second
+#context test(heading.current().body, [First])
+#context test(heading.current(level: 2).body, [Second])
+#context test(heading.current(level: 3), none)
+#pagebreak()
+#context test(heading.current().body, [Second])
+#context test(heading.current(level: 1).body, [First])
+
+---
+// Error: 2-19 can only be used when context is known
+// Hint: 2-19 try wrapping this in a `context` expression
+// Hint: 2-19 the `context` expression should wrap everything that depends on this function
+#heading.current()
@@ -0,0 +1,18 @@
+// Test that synthesizing the displayed numbers of headings, which the
+// resolved document outline uses, leaves queries and counters unaffected.
+// Ref: false
+
+---
+#set heading(numbering: "1.a")
+= One
+== Two <two>
+#heading(numbering: none)[Three]
+#heading(level: 2, bookmarked: false)[Four]
+
+#context {
+  let headings = query(heading)
+  test(headings.len(), 4)
+  test(headings.map(it => it.body), ([One], [Two], [Three], [Four]))
+  test(counter(heading).at(<two>), (1, 1))
+  test("numbers" in query(<two>).first().fields(), false)
+}
@@ -0,0 +1,18 @@
+// Test the page count.
+
+---
+#set page(
+  height: 60pt,
+  footer: context [
+    #counter(page).display() of #page-count() (#numbering("I", page-count()))
+  ],
+)
+
+#counter(page).update(5)
+#lorem(20)
+
+---
+// Error: 2-14 can only be used when context is known
+// Hint: 2-14 try wrapping this in a `context` expression
+// Hint: 2-14 the `context` expression should wrap everything that depends on this function
+#page-count()
@@ -0,0 +1,59 @@
+// Test page marks for running headers.
+
+---
+#set page(
+  width: 120pt,
+  height: 90pt,
+  margin: (top: 25pt, rest: 10pt),
+  header-ascent: 8pt,
+  header: context {
+    let marks = page-marks()
+    let current = marks.first
+    if current == none { current = marks.previous }
+    set text(7pt)
+    if current != none [_ #current.body _]
+    h(1fr)
+    if marks.last != none [#marks.last.body]
+  },
+)
+#set text(8pt)
+
+Preface.
+
+= Alpha
+#lorem(10)
+
+= Beta
+= Gamma
+#lorem(30)
+
+---
+// Test custom marks.
+// Ref: false
+#set page(height: 60pt, header: context {
+  let marks = page-marks(<mark>)
+  let page = here().page()
+  if page == 1 {
+    test(marks.first.value, "a")
+    test(marks.last.value, "b")
+    test(marks.previous, none)
+  } else if page == 2 {
+    test(marks.first, none)
+    test(marks.previous.value, "b")
+  } else {
+    test(marks.first.value, "c")
+    test(marks.previous.value, "b")
+  }
+})
+
+#metadata("a") <mark>
+#metadata("b") <mark>
+#pagebreak()
+#pagebreak()
+#metadata("c") <mark>
+
+---
+// Error: 2-14 can only be used when context is known
+// Hint: 2-14 try wrapping this in a `context` expression
+// Hint: 2-14 the `context` expression should wrap everything that depends on this function
+#page-marks()
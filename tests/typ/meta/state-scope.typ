@@ -0,0 +1,41 @@
+// Test scoped state updates.
+// Ref: false
+
+---
+#let s = state("scoped", 1)
+#s.scope[
+  #s.update(x => x + 1)
+  #context test(s.get(), 2)
+  #s.scope[
+    #s.update(10)
+    #context test(s.get(), 10)
+  ]
+  #context test(s.get(), 2)
+]
+#context test(s.get(), 1)
+#context test(s.final(), 1)
+
+---
+// Other states are unaffected.
+#let a = state("a", 0)
+#let b = state("b", 0)
+#a.scope[
+  #a.update(1)
+  #b.update(1)
+]
+#context test((a.get(), b.get()), (0, 1))
+
+---
+#let s = state("restored", "a")
+#s.update("b") <first>
+#s.update("c")
+#context test(s.get(), "c")
+#s.restore(<first>)
+#context test(s.get(), "b")
+#context test(s.at(<first>), "b")
+
+---
+// Error: 2-29 cannot restore state from a later location
+#state("x").restore(<later>)
+#state("x").update(1) <later>
+#context state("x").final()
@@ -0,0 +1,32 @@
+// Test per-family size and baseline adjustments.
+
+---
+// Without and with adjustments.
+#let mono(..args) = (name: "DejaVu Sans Mono", ..args.named())
+#text(font: ("New Computer Modern", mono()))[Box ├─┤ drawing] \
+#text(font: ("New Computer Modern", mono(size-adjust: 80%)))[Box ├─┤ drawing] \
+#text(font: ("New Computer Modern", mono(size-adjust: 150%)))[Box ├─┤ drawing] \
+#text(font: ("New Computer Modern", mono(baseline-shift: 0.2em)))[Box ├─┤ drawing] \
+#text(font: ("New Computer Modern", mono(baseline-shift: -3pt)))[Box ├─┤ drawing]
+
+---
+// Adjustments also apply to the primary family and to tracking.
+#set text(tracking: 2pt)
+#text(font: (name: "Linux Libertine", size-adjust: 120%))[Bigger]
+#text(font: (name: "Linux Libertine", size-adjust: 120%), stroke: 0.5pt + red)[#underline[Bigger]]
+
+---
+// Test the font field.
+// Ref: false
+#set text(font: (name: "Roboto", size-adjust: 90%))
+#context test(text.font, (name: "roboto", size-adjust: 90%, baseline-shift: 0pt))
+#set text(font: (name: "Roboto"))
+#context test(text.font, "roboto")
+
+---
+// Error: 17-50 size adjustment must be positive
+#set text(font: (name: "Roboto", size-adjust: 0%))
+
+---
+// Error: 17-45 unexpected key "size", valid keys are "name", "size-adjust", and "baseline-shift"
+#set text(font: (name: "Roboto", size: 120%))
@@ -0,0 +1,18 @@
+// Test interpreting ANSI escape sequences in raw text.
+
+---
+#let esc = "\u{1b}"
+#raw(lang: "ansi", block: true, {
+  esc + "[1;32m   Compiling" + esc + "[0m typst v0.11.0\n"
+  esc + "[1;31merror" + esc + "[0m" + esc + "[1m: unused import" + esc + "[0m\n"
+  esc + "[33mwarn " + esc + "[3mitalic" + esc + "[23m " + esc + "[4munderline" + esc + "[24m " + esc + "[9mstrike" + esc + "[29m\n"
+  esc + "[2mdim " + esc + "[22m" + esc + "[7minverse" + esc + "[27m " + esc + "[44;97m bg " + esc + "[0m\n"
+  esc + "[38;5;208m256" + esc + "[39m " + esc + "[38;2;120;40;200mtruecolor" + esc + "[0m " + esc + "[2Kcleared\n"
+  esc + "[35mcarries over\n"
+  "to the next line" + esc + "]0;title" + "\u{7}" + esc + "[0m done"
+})
+
+---
+// The plain text of the lines has no escape sequences.
+#show raw.line: it => it.text.len()
+#raw(lang: "ansi", "\u{1b}[1mab\u{1b}[0m")
@@ -0,0 +1,22 @@
+// Test embedding the output of executed code.
+
+---
+#set raw(execute: true)
+```shout
+hello world
+```
+
+Inline: `echo` is not executed, but ```shout hey``` is.
+
+---
+#show raw.where(execute: true): it => [#it.text → #it.output]
+#raw(lang: "shout", execute: true, "hi")
+
+---
+// Error: 2-45 failed to execute code (exit code 1)
+#raw(lang: "fail", execute: true, "exit(1)")
+
+---
+// Error: 2-45 cannot execute code in language "python"
+// Hint: 2-45 code execution must be supported by the environment
+#raw(lang: "python", execute: true, "1 + 2")
@@ -0,0 +1,44 @@
+// Test code highlighting with grammars given as source code.
+
+---
+#let calc = ```yaml
+name: Calc
+file_extensions: [calc]
+scope: source.calc
+contexts:
+  main:
+    - match: '\d+'
+      scope: constant.numeric.calc
+    - match: '[-+*/=]'
+      scope: keyword.operator.calc
+    - match: '#.*$'
+      scope: comment.line.calc
+```.text
+
+#raw(lang: "calc", block: true, grammar: calc, "1 + 2 * 3 = 7 # Precedence")
+
+// Registered grammars stay available for the rest of the document.
+#set raw(grammar: calc)
+```calc
+40 + 2 = 42
+```
+
+---
+// Grammars take precedence over the bundled ones.
+#set raw(grammar: ```yaml
+name: Shouting Rust
+file_extensions: [rs]
+scope: source.shout
+contexts:
+  main:
+    - match: '[A-Z]+'
+      scope: keyword.shout
+```.text)
+
+```rs
+fn MAIN() {}
+```
+
+---
+// Error: 19-35 failed to parse grammar (Missing mandatory key in YAML file: contexts)
+#raw("", grammar: "name: Broken\n")
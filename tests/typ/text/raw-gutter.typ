@@ -0,0 +1,46 @@
+// Test line numbers, highlighted lines, diff markers, and annotations in raw
+// blocks.
+
+---
+#set raw(numbers: true, highlight: (2, (4, 5)))
+```rust
+fn main() {
+    let x = 1;
+    let y = 2;
+    let z = x + y;
+    println!("{z}");
+}
+```
+
+---
+#set raw(diff: true)
+```typ
+ = Heading
+-Hello *World*
++Hello *Typst*
+```
+
+---
+#set raw(numbers: true, annotations: ((1, [Import]), (3, [Entry])))
+```py
+import sys
+
+def main():
+    print(sys.argv)
+```
+
+---
+// Lines break across pages with their gutters.
+#set page(height: 60pt)
+#set raw(numbers: true, diff: true)
+```
+ a
++b
+-c
+ d
+ e
+```
+
+---
+// Error: 21-33 line range must contain exactly two line numbers
+#set raw(highlight: ((1, 2, 3),))
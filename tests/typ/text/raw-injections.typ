@@ -0,0 +1,22 @@
+// Test highlighting embedded languages in raw text.
+
+---
+#set page(width: 200pt)
+#set raw(injections: ("string.quoted - punctuation": "sql"))
+
+```js
+db.query("SELECT name FROM users WHERE id = 1");
+const limit = 10;
+```
+
+---
+// Inner injections take precedence.
+#set raw(injections: ("string - punctuation": "sql"))
+#set raw(injections: ("string - punctuation": "rust"))
+```py
+code = "fn main() { let x = 1; }"
+```
+
+---
+// Error: 22-50 invalid scope selector: "a.b.c.d.e.f.g.h.i"
+#set raw(injections: ("a.b.c.d.e.f.g.h.i": "sql"))
@@ -0,0 +1,22 @@
+// Test that text runs which were already shaped are only reused when all
+// properties that affect shaping match.
+
+---
+// Ref: false
+#context {
+  let width(body) = measure(body).width
+  let plain = width[office]
+
+  // Shaping the same run again yields the same result.
+  test(width[office], plain)
+
+  // Features, fonts, and direction take part in the lookup.
+  test(width(text(ligatures: false)[office]) > plain, true)
+  test(width[office], plain)
+  test(width(text(features: ("smcp",))[office]) != plain, true)
+  test(width(text(font: "DejaVu Sans Mono")[office]) != plain, true)
+  test(width(text(dir: rtl)[office]), plain)
+
+  // The size is applied after shaping.
+  test(width(text(size: 22pt)[office]), 2 * width(text(size: 11pt)[office]))
+}
@@ -29,3 +29,29 @@ My text has spaces.
 // Test word spacing relative to the font's space width.
 #set text(spacing: 50% + 1pt)
 This is tight.
+
+---
+// Test optical tracking.
+#set text(tracking: "optical")
+#text(6pt)[Tiny tracked text.] \
+#text(11pt)[Body text.] \
+#text(28pt)[Display]
+
+---
+// Test extra tracking for capitals.
+#set text(caps-tracking: 0.1em)
+#smallcaps[Small caps] \
+#upper[Upper] \
+NASA and Nasa \
+#text(caps-tracking: 0em)[NASA]
+
+---
+// Test the resolved tracking.
+// Ref: false
+#context test(text.tracking, 0pt)
+#set text(tracking: "optical")
+#context test(text.tracking, "optical")
+
+---
+// Error: 21-28 expected length or "optical"
+#set text(tracking: "tight")
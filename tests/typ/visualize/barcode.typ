@@ -0,0 +1,20 @@
+// Test barcodes.
+
+---
+#set page(width: auto, height: auto, margin: 5pt)
+#barcode("Typst 0.11")
+#barcode("12345678 ABC", height: 1cm, text: true)
+#barcode(kind: "ean13", "400638133393", text: true)
+#barcode(kind: "ean13", "4006381333931", width: 3cm, fill: blue)
+
+---
+// Error: 2-23 barcode data must be printable ASCII, found "ä"
+#barcode("Käsekuchen")
+
+---
+// Error: 2-41 EAN-13 check digit is 2, but should be 1
+#barcode(kind: "ean13", "4006381333932")
+
+---
+// Error: 2-31 EAN-13 barcode data must consist of 12 or 13 digits
+#barcode(kind: "ean13", "123")
@@ -0,0 +1,38 @@
+// Test blend modes.
+
+---
+// Test the different blend modes.
+#set page(width: 150pt)
+#let modes = (
+  "normal", "multiply", "screen", "overlay", "darken", "lighten",
+  "color-dodge", "color-burn", "hard-light", "soft-light", "difference",
+  "exclusion", "hue", "saturation", "color", "luminosity",
+)
+#for mode in modes {
+  box(inset: 1pt, {
+    place(rect(width: 16pt, height: 16pt, fill: orange))
+    place(dx: 8pt, dy: 8pt, blend(mode: mode, rect(width: 16pt, height: 16pt, fill: eastern)))
+    h(24pt)
+    v(24pt)
+  })
+}
+
+---
+// Test isolation.
+#for isolate in (true, false) {
+  box(fill: aqua, inset: 4pt, blend(isolate: isolate, mode: "multiply", {
+    place(circle(radius: 10pt, fill: yellow))
+    place(dx: 10pt, circle(radius: 10pt, fill: red.lighten(50%)))
+    h(40pt)
+    v(20pt)
+  }))
+}
+
+---
+// Test blending text.
+#box(fill: yellow, inset: 4pt)[Highlighted] \
+#place(dy: -14pt, blend(mode: "multiply", rect(width: 48pt, height: 12pt, fill: aqua)))
+
+---
+// Error: 14-20 expected "normal", "multiply", "screen", "overlay", "darken", "lighten", "color-dodge", "color-burn", "hard-light", "soft-light", "difference", "exclusion", "hue", "saturation", "color", or "luminosity"
+#blend(mode: "burn")[]
@@ -0,0 +1,25 @@
+// Test blurring.
+
+---
+// Test blurring shapes with different radii.
+#for radius in (0pt, 1pt, 3pt, 6pt) {
+  box(inset: 8pt, blur(radius, rect(width: 20pt, height: 20pt, fill: blue)))
+}
+
+---
+// Test a soft glow behind text.
+#set text(16pt)
+#box(fill: black, inset: 10pt, {
+  place(blur(2pt)[#text(fill: yellow)[*Glow*]])
+  text(fill: white)[*Glow*]
+})
+
+---
+// Test a frosted background.
+#box(width: 100pt, height: 40pt, {
+  place(blur(4pt, stack(dir: ltr, ..range(5).map(i => circle(
+    radius: 10pt,
+    fill: color.hsv(i * 72deg, 100%, 100%),
+  )))))
+  place(center + horizon, rect(fill: white.transparentize(50%))[Frosted])
+})
@@ -0,0 +1,58 @@
+// Test charts.
+
+---
+#set page(width: 10cm, height: auto)
+#set text(8pt)
+#chart.bar((apples: 12, pears: 7, plums: 9.5), height: 3cm)
+#chart.bar(
+  (("2022", (3, -2)), ("2023", (4, 6)), ("2024", (6, 5))),
+  names: ("North", "South"),
+  colors: (aqua, navy),
+  height: 3cm,
+)
+
+---
+#set page(width: 10cm, height: auto)
+#set text(8pt)
+#chart.line(
+  (1, 4, 2, 5, 3),
+  ((0, 2), (2, 3.5), (4, 1)),
+  names: ("Measured", "Predicted"),
+  height: 3cm,
+)
+#chart.line(
+  ..range(6).map(i => range(8).map(x => x * i / 10)),
+  colors: gradient.linear(..color.map.viridis),
+  thickness: 1pt,
+  height: 3cm,
+)
+
+---
+#set page(width: 10cm, height: auto)
+#set text(8pt)
+#chart.scatter(
+  ((1, 2), (2, 3.5), (3, 3), (4, 5)),
+  ((1, 4), (2, 1), (3.5, 2)),
+  names: ("A", "B"),
+  radius: 3pt,
+  height: 3cm,
+  stroke: gray,
+)
+
+---
+#set page(width: 10cm, height: auto)
+#set text(8pt)
+#chart.pie((rent: 900, food: 400, travel: 250, other: 0), height: 3cm)
+#chart.pie((1, 2, 3), legend: false, stroke: none, width: 3cm, height: 3cm)
+
+---
+// Error: 2-26 pie chart values must be non-negative numbers
+#chart.pie((a: 1, b: -1))
+
+---
+// Error: 12-24 labelled value must contain exactly two entries
+#chart.bar(((1, 2, 3),))
+
+---
+// Error: 26-28 chart colors must not be empty
+#chart.bar((1,), colors: ())
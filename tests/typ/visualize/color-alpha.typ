@@ -0,0 +1,29 @@
+// Test compositing of colors with alpha, which are premultiplied when
+// rendering.
+
+---
+// Semi-transparent text over a filled block.
+#set page(width: 120pt, height: auto, margin: 10pt)
+#block(fill: aqua, inset: 5pt, width: 100%)[
+  #text(fill: red.transparentize(50%))[Half red]
+  #text(fill: luma(0, 30%))[Light luma]
+  #text(fill: oklch(50%, 0.2, 250deg, 60%))[Oklch]
+]
+
+---
+// Gradients with transparent stops on text and shapes.
+#set page(width: 120pt, height: auto, margin: 10pt)
+#set text(fill: gradient.linear(red, blue.transparentize(100%)))
+#rect(fill: yellow, inset: 5pt)[*Fading text*]
+#stack(
+  dir: ltr,
+  rect(width: 50pt, height: 20pt, fill: gradient.linear(black.transparentize(100%), black)),
+  rect(width: 50pt, height: 20pt, fill: gradient.radial(green, green.transparentize(100%))),
+)
+
+---
+// Fully transparent colors paint nothing.
+#set page(width: 120pt, height: auto, margin: 10pt)
+#rect(fill: green, inset: 5pt)[
+  #text(fill: rgb(255, 0, 0, 0))[Invisible]
+]
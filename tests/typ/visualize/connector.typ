@@ -0,0 +1,23 @@
+// Test connectors between labelled elements.
+
+---
+#set page(width: 180pt, height: 110pt, margin: 5pt)
+#set rect(inset: 4pt)
+#place(top + left)[#rect[Start] <start>]
+#place(bottom + right)[#rect[End] <end>]
+#place(center + horizon)[#rect(height: 40pt)[Wall] <wall>]
+#connector(<start>, <end>, obstacles: (<wall>,))
+#connector(<start>, <end>, route: "curved", stroke: blue, start-anchor: bottom, end-anchor: left, obstacles: (<wall>,))
+
+---
+#set page(width: 180pt, height: 80pt, margin: 5pt)
+#for (i, route) in ("straight", "orthogonal", "curved").enumerate() {
+  let (a, b) = (label("a" + str(i)), label("b" + str(i)))
+  place(dx: i * 60pt)[#square(size: 12pt) #a]
+  place(dx: i * 60pt + 30pt, dy: 50pt)[#square(size: 12pt) #b]
+  place(connector(a, b, route: route, marker-start: "dot"))
+}
+
+---
+// Error: 2-33 label `<missing>` does not exist in the document
+#connector(<missing>, <missing>)
@@ -0,0 +1,52 @@
+// Test geographic maps.
+
+---
+#let squares = (
+  type: "FeatureCollection",
+  features: range(4).map(i => (
+    type: "Feature",
+    properties: (value: i),
+    geometry: (
+      type: "Polygon",
+      coordinates: (((i * 10, 0), (i * 10 + 10, 0), (i * 10 + 10, 40), (i * 10, 40), (i * 10, 0)),),
+    ),
+  )),
+)
+#geo.map(squares, width: 3cm, fill: f => blue.lighten(100% - f.properties.value * 25%))
+#geo.map(squares, width: 3cm, projection: "equal-area", stroke: f => if f.properties.value == 2 { 2pt + red })
+
+---
+#geo.map(
+  (
+    type: "GeometryCollection",
+    geometries: (
+      (type: "LineString", coordinates: ((0, 0), (10, 10), (20, 0))),
+      (type: "MultiPoint", coordinates: ((0, 0), (10, 10), (20, 0))),
+    ),
+  ),
+  width: 3cm,
+  height: 1cm,
+  fill: red,
+  radius: 3pt,
+)
+
+---
+// Test projecting positions and geometries.
+// Ref: false
+#let round(p) = p.map(v => calc.round(v, digits: 4))
+#test(geo.project((0, 0)), (0.0, 0.0))
+#test(round(geo.project((90, 45), projection: "equirectangular")), (1.5708, 0.7854))
+#test(round(geo.project((0, 30), projection: "equal-area")), (0.0, 0.5))
+#test(geo.project((0, 90)), geo.project((0, 86)))
+#test(round(geo.project((0, -90))), (0.0, -3.1416))
+#let line = geo.project((type: "LineString", coordinates: ((0, 0), (180, 0))))
+#test(line.type, "LineString")
+#test(line.coordinates.map(round), ((0.0, 0.0), (3.1416, 0.0)))
+
+---
+// Error: 10-26 unknown geometry type `Circle`
+#geo.map((type: "Circle"))
+
+---
+// Error: 27-37 expected "mercator", "equal-area", or "equirectangular"
+#geo.map((:), projection: "robinson")
@@ -0,0 +1,39 @@
+// Test easing between gradient stops.
+
+---
+// Ref: false
+#let g = gradient.linear(red, blue, easing: "ease-in")
+#test(g.easing(), "ease-in")
+#test(g.repeat(2).easing(), "ease-in")
+#test(g.sharp(3).easing(), "linear")
+#test(gradient.radial(red, blue).easing(), "linear")
+#test(gradient.conic(red, blue, easing: (0.1, 0.2, 0.3, 0.4)).easing(), (0.1, 0.2, 0.3, 0.4))
+#test(repr(g).contains("easing: \"ease-in\""), true)
+#test(repr(gradient.linear(red, blue)).contains("easing"), false)
+
+---
+// Test that the easing warps the progress between two stops.
+// Ref: false
+#let g = gradient.linear(red, blue)
+#test(gradient.linear(red, blue, easing: t => t).sample(30%), g.sample(30%))
+#test(gradient.linear(red, blue, easing: t => 0).sample(70%), g.sample(0%))
+#test(gradient.linear(red, blue, easing: t => 2).sample(30%), g.sample(100%))
+#test(gradient.linear(red, blue, easing: "ease-in").sample(0%), g.sample(0%))
+#test(gradient.linear(red, blue, easing: "ease-in").sample(100%), g.sample(100%))
+#test(gradient.linear(red, blue, easing: "ease-in").sample(50%) != g.sample(50%), true)
+#test(
+  gradient.linear(red, green, blue, easing: t => t * t).sample(75%),
+  gradient.linear(green, blue).sample(25%),
+)
+
+---
+// Error: 37-47 expected "linear", "ease", "ease-in", "ease-out", or "ease-in-out"
+#gradient.linear(red, blue, easing: "ease-all")
+
+---
+// Error: 37-47 cubic Bézier easing must have four components
+#gradient.linear(red, blue, easing: (0.1, 0.2))
+
+---
+// Error: 37-51 x-coordinates of control points must be between 0 and 1
+#gradient.linear(red, blue, easing: (1.5, 0, 0, 1))
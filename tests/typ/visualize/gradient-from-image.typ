@@ -0,0 +1,43 @@
+// Test creating gradients from images.
+
+---
+// Test sampling rows and columns of a generated image.
+#let strip = image.generate(4, 2, (x, y) => if y == 0 {
+  (red, green, blue, black).at(x)
+} else {
+  (white, yellow, aqua, gray).at(x)
+})
+#let grad = gradient.from-image(strip, row: 0%)
+#test(grad.kind(), gradient.linear)
+#test(grad.stops().len(), 4)
+#test(grad.stops().first(), (rgb("#ff4136"), 0%))
+#test(grad.stops().last(), (rgb(0, 0, 0), 100%))
+#test(gradient.from-image(strip).stops().first().first(), rgb(255, 255, 255))
+#test(gradient.from-image(strip, column: 100%).stops(), ((rgb(0, 0, 0), 0%), (rgb(170, 170, 170), 100%)))
+#rect(width: 100%, height: 15pt, fill: grad)
+
+---
+// Test sampling a photo.
+#let grad = gradient.from-image(image("/assets/images/rhino.png"), row: 40%)
+#rect(width: 100%, height: 15pt, fill: grad)
+#circle(radius: 15pt, fill: gradient.radial(..grad.stops()))
+
+---
+// Error: 22-28 expected an image
+#gradient.from-image(rect())
+
+---
+// Error: 77-80 cannot sample both a row and a column
+#gradient.from-image(image.generate(2, 2, (x, y) => red), row: 10%, column: 20%)
+
+---
+// Error: 64-68 must be between 0% and 100%
+#gradient.from-image(image.generate(2, 2, (x, y) => red), row: 150%)
+
+---
+// Error: 22-57 image must be at least two pixels long along the gradient
+#gradient.from-image(image.generate(1, 2, (x, y) => red))
+
+---
+// Error: 22-56 can only create gradients from raster images
+#gradient.from-image(image("/assets/images/monkey.svg"))
@@ -0,0 +1,22 @@
+// Test the direction of hue interpolation in gradients.
+
+---
+#set page(width: 100pt, height: auto, margin: 0pt)
+#set block(width: 100%, height: 15pt, spacing: 0pt)
+#for hue in ("shorter", "longer", "increasing", "decreasing") {
+  block(fill: gradient.linear(red, purple, space: oklch, hue: hue))
+}
+
+---
+// Test conic gradients in HSL space.
+#set page(width: 100pt, height: auto, margin: 0pt)
+#square(size: 50pt, fill: gradient.conic(red, red, space: color.hsl, hue: "longer"))
+
+---
+// Ref: false
+#let g = gradient.linear(red, blue, space: oklch, hue: "decreasing")
+#test(g.hue(), "decreasing")
+#test(g.sharp(3).hue(), "decreasing")
+#test(g.repeat(2).hue(), "decreasing")
+#test(gradient.radial(red, blue).hue(), "shorter")
+#test(repr(g).contains("hue: \"decreasing\""), true)
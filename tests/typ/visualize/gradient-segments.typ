@@ -0,0 +1,60 @@
+// Test overriding the interpolation between individual stops.
+
+---
+#set rect(width: 100%, height: 20pt)
+#rect(fill: gradient.linear(
+  (color: red, offset: 0%, easing: t => 0),
+  (color: blue, offset: 50%, space: oklch),
+  (color: green, offset: 100%),
+))
+#rect(fill: gradient.linear(
+  (color: black, easing: t => 0),
+  (color: purple, easing: t => 0),
+  (color: orange, easing: t => 0),
+  white,
+))
+#rect(fill: gradient.conic(
+  (color: red, space: color.hsl),
+  blue,
+  (color: yellow, easing: "ease-in-out"),
+  red,
+))
+
+---
+// Test that segments only affect their own pair of stops.
+// Ref: false
+#let g = gradient.linear(
+  (color: red, offset: 0%, space: oklch),
+  (color: blue, offset: 50%, easing: t => 0),
+  (color: green, offset: 100%),
+)
+#test(g.sample(25%), gradient.linear(red, blue, space: oklch).sample(50%))
+#test(g.sample(75%), gradient.linear(blue, green).sample(0%))
+#test(g.sample(100%), g.sample(75%))
+#test(g.space(), oklab)
+#test(g.stops().at(0), (color: red, offset: 0%, space: oklch))
+#test(g.stops().at(2), (green, 100%))
+#test(repr(g).contains("(color: rgb(\"#ff4136\"), offset: 0%, space: oklch)"), true)
+#test(repr(gradient.linear(red, blue)).contains("color:"), false)
+
+---
+// Test that segments are kept when repeating and dropped when sharpening.
+// Ref: false
+#let g = gradient.linear((color: red, easing: t => 0), blue)
+#let (r, b) = (g.sample(0%), gradient.linear(blue, red).sample(0%))
+#test(g.repeat(2).stops().len(), 4)
+#test(g.repeat(2).sample(40%), r)
+#test(g.repeat(2).sample(90%), r)
+#test(g.repeat(2, mirror: true).stops().len(), 3)
+#test(g.repeat(2, mirror: true).sample(25%), r)
+#test(g.repeat(2, mirror: true).sample(75%), b)
+#test(g.sharp(2).stops().all(stop => type(stop) == array), true)
+
+---
+// Error: 44-85 the last stop cannot override the interpolation
+// Hint: 44-85 the interpolation of a stop applies between it and the next stop
+#gradient.linear((color: red, offset: 0%), (color: blue, offset: 100%, space: oklch))
+
+---
+// Error: 18-43 unexpected key "angle", valid keys are "color", "offset", "space", and "easing"
+#gradient.linear((color: red, angle: 0deg), blue)
@@ -29,3 +29,11 @@
 #line(length: 100%, stroke: 1pt + gradient.linear(red, blue))
 #line(length: 100%, angle: 10deg, stroke: 1pt + gradient.linear(red, blue))
 #line(length: 100%, angle: 10deg, stroke: 1pt + gradient.linear(red, blue, relative: "parent"))
+
+---
+// Test vertical gradients on horizontal lines, table lines and decorations.
+#set page(width: 100pt, height: auto)
+#let grad = gradient.linear(red, blue, angle: 90deg)
+#line(length: 100%, stroke: 6pt + grad)
+#table(columns: 2, stroke: 3pt + grad, [A], [B], [C], [D])
+#underline(stroke: 2pt + grad)[Underlined]
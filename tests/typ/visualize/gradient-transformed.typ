@@ -0,0 +1,43 @@
+// Test transforming gradients independently of the shapes they fill.
+
+---
+#set page(width: 200pt, height: auto)
+#set rect(width: 100%, height: 20pt)
+#let grad = gradient.linear(..color.map.rainbow)
+#rect(fill: grad)
+#rect(fill: grad.transformed(rotate: 20deg, scale: 150%))
+#rect(fill: grad.transformed(offset: (25%, 0%)))
+#rect(fill: gradient.radial(..color.map.viridis).transformed(scale: 50%))
+#rect(fill: gradient.conic(..color.map.crest).transformed(rotate: 90deg))
+
+---
+// Test that a transformed parent-relative gradient lines up across shapes.
+#let grad = gradient.linear(red, blue, relative: "parent")
+#let grad = grad.transformed(rotate: 45deg, scale: 200%)
+#box(width: 50pt, height: 50pt, {
+  place(rect(width: 25pt, height: 50pt, fill: grad))
+  place(dx: 25pt, rect(width: 25pt, height: 50pt, fill: grad))
+})
+
+---
+// Test that transformations accumulate.
+// Ref: false
+#let grad = gradient.linear(red, blue)
+#test(grad.transformed(), grad)
+#test(
+  grad.transformed(rotate: 45deg, scale: 200%, offset: (25%, 0%))
+    .transformed(rotate: 45deg, scale: 50%, offset: (25%, 5%)),
+  grad.transformed(rotate: 90deg, offset: (50%, 5%)),
+)
+#test(grad.sharp(2).transformed(rotate: 90deg), grad.transformed(rotate: 90deg).sharp(2))
+#test(repr(grad).contains("transformed"), false)
+#test(
+  repr(grad.transformed(rotate: 90deg)).ends-with(
+    ".transformed(rotate: 90deg, scale: 100%, offset: (0%, 0%))",
+  ),
+  true,
+)
+
+---
+// Error: 48-50 scale must not be zero
+#gradient.linear(red, blue).transformed(scale: 0%)
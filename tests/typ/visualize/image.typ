@@ -80,3 +80,26 @@ A #box(image("/assets/images/tiger.jpg", height: 1cm, width: 80%)) B
 ---
 // Error: 2-91 failed to decode image (Format error decoding Png: Invalid PNG signature.)
 #image.decode(read("/assets/images/tiger.jpg", encoding: none), format: "png", width: 80%)
+
+---
+// Test generating an image from a pixel function.
+// Ref: false
+#let img = image.generate(3, 2, (x, y) => if x == y { black } else { white }, width: 30pt)
+#test(img.func(), image)
+#test(img.width, 30pt)
+#test(img.format, "png")
+#test(image.generate(2, 2, (x, y) => red), image.generate(2, 2, (x, y) => rgb("#ff4136")))
+#test(image.generate(2, 2, (x, y) => luma(x * 255)) == image.generate(2, 2, (x, y) => luma(y * 255)), false)
+#box(image.generate(16, 16, (x, y) => color.mix((red, x), (blue, y), (white, 1))))
+
+---
+// Error: 21-22 image must be at least one pixel wide and high
+#image.generate(10, 0, (x, y) => red)
+
+---
+// Error: 2-39 expected color, found integer
+#image.generate(2, 2, (x, y) => x + y)
+
+---
+// Error: 2-47 image is too large
+#image.generate(100000, 100000, (x, y) => red)
@@ -0,0 +1,37 @@
+// Test line and path markers.
+
+---
+#set page(width: 120pt)
+#set line(length: 100%)
+#stack(
+  spacing: 10pt,
+  line(marker-end: "arrow"),
+  line(stroke: 2pt + blue, marker-start: "dot", marker-end: "stealth"),
+  line(stroke: 1.5pt, marker-start: "bar", marker-mid: "diamond", marker-end: "bar"),
+  line(stroke: 3pt + red, marker-start: "arrow", marker-end: "arrow"),
+  line(length: 3pt, marker-start: "square", marker-end: "arrow"),
+)
+#line(end: (40pt, 30pt), stroke: 2pt, marker-end: "stealth")
+
+---
+#set page(width: 120pt)
+#path(
+  stroke: 1.5pt + blue,
+  marker-start: "bar",
+  marker-mid: "dot",
+  marker-end: "arrow",
+  (0pt, 30pt),
+  ((40pt, 0pt), (-15pt, 0pt)),
+  (80pt, 30pt),
+)
+#path(
+  stroke: 1pt,
+  closed: true,
+  marker-start: "arrow",
+  marker-mid: "square",
+  (10pt, 0pt), (40pt, 0pt), (40pt, 20pt), (10pt, 20pt),
+)
+
+---
+// Error: 21-29 expected "arrow", "stealth", "dot", "square", "diamond", "bar", or none
+#line(marker-start: "arrows")
@@ -0,0 +1,26 @@
+// Test music staffs.
+// Ref: false
+
+---
+// The test fonts include no SMuFL font.
+// Error: 2-58 no SMuFL font is available
+// Hint: 2-58 install a SMuFL font like Bravura or set `font` to one
+#music.staff((note: "c4/2", chord: [C]), "e4", "g4", "|")
+
+---
+// A font without SMuFL glyphs is not enough.
+// Error: 2-44 no SMuFL font is available
+// Hint: 2-44 install a SMuFL font like Bravura or set `font` to one
+#music.staff(font: "Linux Libertine", "c4")
+
+---
+// Error: 14-18 invalid note `h4`, expected a note like `c4` or `f#5/8`
+#music.staff("h4")
+
+---
+// Error: 14-20 note duration must be 1, 2, 4, 8, or 16
+#music.staff("c4/3")
+
+---
+// Error: 14-50 unexpected key "lyric", valid keys are "note" and "chord"
+#music.staff((note: "c4", chord: [C], lyric: [a]))
@@ -0,0 +1,26 @@
+// Test opacity on arbitrary content.
+
+---
+// Test different opacities.
+#for value in (100%, 75%, 50%, 25%, 0%) {
+  box(inset: 4pt, opacity(value, rect(width: 16pt, height: 16pt, fill: blue)))
+}
+
+---
+// Overlapping parts don't shine through each other.
+#box(fill: yellow, inset: 6pt, opacity(50%, {
+  place(circle(radius: 12pt, fill: red))
+  place(dx: 12pt, circle(radius: 12pt, fill: blue))
+  h(36pt)
+  v(24pt)
+}))
+
+---
+// Test a watermark over text.
+#set page(width: 120pt, height: auto)
+#place(center + horizon, opacity(30%, rotate(-30deg, text(24pt, fill: red)[*DRAFT*])))
+#lorem(20)
+
+---
+// Error: 2-32 opacity must be between 0% and 100%
+#opacity(150%, rect(fill: red))
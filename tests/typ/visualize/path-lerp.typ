@@ -0,0 +1,66 @@
+// Test interpolating between paths.
+
+---
+// Test paths with the same number of vertices.
+#let a = path(
+  closed: true,
+  fill: blue,
+  (0pt, 0pt), (20pt, 0pt), (20pt, 20pt), (0pt, 20pt),
+)
+#let b = path(
+  closed: true,
+  fill: blue,
+  ((10pt, 0pt), (-6pt, 0pt)),
+  ((20pt, 10pt), (0pt, -6pt)),
+  ((10pt, 20pt), (6pt, 0pt)),
+  ((0pt, 10pt), (0pt, 6pt)),
+)
+#for t in (0, 0.25, 0.5, 0.75, 1) {
+  box(path.lerp(a, b, t))
+  h(4pt)
+}
+
+---
+// Test subdividing the path with fewer vertices.
+#let triangle = path(
+  closed: true,
+  stroke: blue,
+  (10pt, 0pt), (20pt, 20pt), (0pt, 20pt),
+)
+#let star = path(
+  closed: true,
+  stroke: blue,
+  ..range(10).map(i => {
+    let r = if calc.even(i) { 10pt } else { 4pt }
+    let angle = i * 36deg - 90deg
+    (10pt + r * calc.cos(angle), 10pt + r * calc.sin(angle))
+  }),
+)
+#for t in (0, 0.5, 1) {
+  box(path.lerp(triangle, star, t))
+  h(4pt)
+}
+
+// Subdividing keeps the shape intact.
+#box(path.lerp(star, triangle, 1))
+
+---
+// Test open paths.
+#let a = path(stroke: red, (0pt, 10pt), (40pt, 10pt))
+#let b = path(stroke: red, (0pt, 20pt), ((20pt, 0pt), (-10pt, 0pt)), (40pt, 20pt))
+#for t in (0, 0.5, 1) {
+  box(height: 20pt, path.lerp(a, b, t))
+  h(4pt)
+}
+
+---
+// Error: 2-54 cannot interpolate between an open and a closed path
+#path.lerp(path((0pt, 0pt)), path(closed: true), 0.5)
+
+---
+// Error: 2-64 cannot interpolate path with fewer than two vertices
+#path.lerp(path((0pt, 0pt)), path((0pt, 0pt), (1pt, 1pt)), 0.5)
+
+---
+// Error: 2-32 expected path, found rect
+#path.lerp(rect(), path(), 0.5)
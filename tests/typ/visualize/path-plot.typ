@@ -0,0 +1,50 @@
+// Test plotting functions as paths.
+
+---
+#set path(stroke: blue)
+#box(path.plot(calc.sin, (0, 2 * calc.pi), width: 80pt, height: 30pt))
+#h(4pt)
+#box(path.plot(x => x * x, (-1, 1), width: 40pt, height: 30pt, fill: blue.lighten(80%)))
+#h(4pt)
+#box(path.plot(x => calc.exp(-x * x), (-3, 3), range: (0, 1), width: 40pt, height: 30pt))
+
+---
+// Straight lines need no refinement.
+// Ref: false
+#let p = path.plot(x => 2 * x + 1, (0, 1), samples: 4, width: 40pt, height: 20pt)
+#test(p.vertices.len(), 5)
+#test(p.vertices.first().first(), (0pt, 20pt))
+#test(p.vertices.last().first(), (40pt, 0pt))
+
+---
+// Curved functions are sampled more densely.
+// Ref: false
+#let p = path.plot(calc.sin, (0, 2 * calc.pi), samples: 4)
+#test(p.vertices.len() > 5, true)
+#test(p.vertices.len() < 100, true)
+
+---
+// Constant functions are placed in the middle.
+// Ref: false
+#let p = path.plot(x => 1, (0, 1), samples: 2, width: 10pt, height: 10pt)
+#test(p.vertices.map(v => v.first()), ((0pt, 5pt), (5pt, 5pt), (10pt, 5pt)))
+
+---
+// Error: 20-26 domain must not be empty
+#path.plot(x => x, (1, 1))
+
+---
+// Error: 20-24 interval must contain exactly two entries
+#path.plot(x => x, (1,))
+
+---
+// Error: 37-38 number of samples must be positive
+#path.plot(x => x, (0, 1), samples: 0)
+
+---
+// Error: 35-41 range must not be empty
+#path.plot(x => x, (0, 1), range: (2, 2))
+
+---
+// Error: 2-34 function returned inf for 0.0
+#path.plot(x => calc.inf, (0, 1))
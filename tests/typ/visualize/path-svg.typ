@@ -0,0 +1,54 @@
+// Test conversion between paths and SVG path data.
+// Ref: false
+
+---
+// Straight segments become plain vertices.
+#let p = path.from-svg-path("M 0 0 L 10 0 L 10 10 Z")
+#test(p.closed, true)
+#test(p.vertices, ((0pt, 0pt), (10pt, 0pt), (10pt, 10pt)))
+#test(path.to-svg-path(p), "M 0 0 L 10 0 L 10 10 Z")
+
+---
+// Relative and shorthand commands.
+#let p = path.from-svg-path("m 10 10 l 10 0 v 10 h -10 z")
+#test(path.to-svg-path(p), "M 10 10 L 20 10 L 20 20 L 10 20 Z")
+
+---
+// Quadratic curves are converted into cubic ones.
+#let p = path.from-svg-path("M 0 0 Q 30 30 60 0")
+#test(path.to-svg-path(p), "M 0 0 C 20 20 40 20 60 0")
+
+---
+// A closed path that returns to its start keeps its closing curve.
+#let d = "M 0 0 C 0 -10 20 -10 20 0 C 20 10 0 10 0 0 Z"
+#let p = path.from-svg-path(d)
+#test(p.vertices.len(), 2)
+#test(path.to-svg-path(p), d)
+
+---
+// Arcs are approximated by cubic curves.
+#let p = path.from-svg-path("M 0 0 A 10 10 0 0 1 20 0")
+#test(p.vertices.len() > 1, true)
+#test(path.to-svg-path(p).ends-with(" 20 0"), true)
+
+---
+// Paths given in Typst can be exported.
+#let p = path(closed: true, (0pt, 0pt), ((20pt, 10pt), (0pt, -5pt)), (0pt, 20pt))
+#test(path.to-svg-path(p), "M 0 0 C 0 0 20 5 20 10 C 20 15 0 20 0 20 Z")
+#test(path.to-svg-path(path()), "")
+
+---
+// Error: 21-49 path data must contain a single subpath
+#path.from-svg-path("M 0 0 L 10 0 M 20 0 L 30 0")
+
+---
+// Error: 21-33 invalid SVG path data
+#path.from-svg-path("M 0 0 L 10")
+
+---
+// Error: 2-49 cannot convert path with relative lengths to SVG path data
+#path.to-svg-path(path((10%, 0pt), (10pt, 5pt)))
+
+---
+// Error: 2-26 expected path, found rect
+#path.to-svg-path(rect())
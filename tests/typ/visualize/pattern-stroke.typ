@@ -11,3 +11,12 @@
     )
   )
 )
+
+---
+// Test patterns on lines, table lines and decorations.
+#set page(width: 100pt, height: auto)
+#let pat = pattern(size: (4pt, 4pt), square(size: 2pt, fill: blue))
+#line(length: 100%, stroke: 4pt + pat)
+#table(columns: 2, stroke: 2pt + pat, [A], [B], [C], [D])
+#underline(stroke: 2pt + pat)[Underlined]
+#rect(width: 100%, height: 20pt, stroke: 4pt + pat, fill: pat)
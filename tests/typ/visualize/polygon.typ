@@ -31,6 +31,29 @@
 // Regular polygon; should have equal side lengths
 #for k in range(3, 9) {polygon.regular(size: 30pt, vertices: k,)}
 
+---
+// Stars, star polygons, rotation, and rounded corners.
+#set page(width: 130pt)
+#set polygon(stroke: 0.75pt, fill: blue.lighten(50%))
+#for shape in (
+  polygon.regular(size: 30pt, vertices: 5, inner: 40%),
+  polygon.regular(size: 30pt, vertices: 8, inner: 70%),
+  polygon.regular(size: 30pt, vertices: 5, step: 2),
+  polygon.regular(size: 30pt, vertices: 7, step: 3),
+  polygon.regular(size: 30pt, vertices: 4, rotation: 45deg),
+  polygon.regular(size: 30pt, vertices: 3, rotation: 180deg),
+  polygon.regular(size: 30pt, vertices: 6, radius: 4pt),
+  polygon.regular(size: 30pt, vertices: 5, inner: 50%, radius: 2pt),
+  polygon.regular(size: 30pt, vertices: 3, radius: 100pt),
+) {
+  box(shape)
+}
+#polygon(radius: 5pt, (0pt, 0pt), (60pt, 10pt), (30pt, 40pt))
+
+---
+// Error: 2-51 step must not share a common divisor with the number of vertices
+#polygon.regular(size: 30pt, vertices: 6, step: 2)
+
 ---
 // Error: 10-17 point array must contain exactly two entries
 #polygon((50pt,))
@@ -0,0 +1,16 @@
+// Test QR codes.
+
+---
+#set page(width: auto, height: auto, margin: 5pt)
+#qrcode("HELLO WORLD", ec: "q", size: 2cm)
+#qrcode("0123456789", size: 2cm, fill: blue)
+#qrcode("https://typst.app/docs", ec: "h", size: 3cm, quiet-zone: false)
+
+---
+// Binary data and larger versions with version information.
+#set page(width: auto, height: auto, margin: 5pt)
+#qrcode(bytes(range(256)), ec: "l", size: 4cm)
+
+---
+// Error: 2-20 data is too long for a QR code
+#qrcode("a" * 3000)
@@ -0,0 +1,39 @@
+// Test arcs, pies and rings.
+
+---
+#set page(width: 120pt)
+
+// These are not visible, but should also not give an error.
+#arc(radius: 0pt)
+#pie(radius: 0pt)
+#ring(inner: 0pt, outer: 0pt)
+
+#arc()
+#arc(start: -90deg, end: 180deg, stroke: 2pt + blue)
+#arc(end: 270deg, fill: aqua)
+#arc(start: 45deg, end: -45deg)
+
+#pie(fill: red)
+#pie(start: 0deg, end: 400deg, fill: green)
+#pie(start: -90deg, end: 30deg, fill: blue, stroke: 1pt)
+
+#ring()
+#ring(inner: 10pt, outer: 15pt, fill: eastern)
+#ring(inner: 5pt, start: -90deg, end: 120deg, fill: maroon, stroke: 0.5pt)
+
+---
+// Slices of a pie chart stack up into a full disk.
+#box({
+  let start = -90deg
+  for (share, fill) in ((35%, red), (25%, blue), (40%, green)) {
+    let end = start + share * 360deg
+    place(pie(radius: 20pt, start: start, end: end, fill: fill))
+    start = end
+  }
+  h(40pt)
+  v(40pt)
+})
+
+---
+// Error: 2-32 inner radius of ring must not exceed outer radius
+#ring(inner: 20pt, outer: 10pt)
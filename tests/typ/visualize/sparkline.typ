@@ -0,0 +1,22 @@
+// Test inline micro charts.
+
+---
+#set page(width: 150pt)
+Visitors: #sparkline((3, 5, 4, 8, 6, 9, 12)) \
+Stock: #sparkline((10, 8, 9, 6, 7, 4), stroke: red, fill: red.lighten(80%)) \
+Flat: #sparkline((2, 2, 2), width: 2em) \
+#text(16pt)[Big: #sparkline(((0, 1), (3, 2), (4, 0)))]
+
+---
+#set page(width: 150pt)
+Revenue: #bullet(270, 250, ranges: (150, 225, 300)) \
+Profit: #bullet(18, 26, ranges: (30, 10, 20), fill: blue) \
+Plain: #bullet(4, 6, max: 10, width: 4em)
+
+---
+#set page(width: 150pt)
+Upload: #progress(35%) \
+Download: #progress(80%, fill: green, track: green.lighten(80%)) \
+Done: #progress(120%, width: 3em) \
+#set text(fill: purple)
+Tinted: #progress(50%)
@@ -41,6 +41,26 @@
 #v(3pt)
 #line(length: 60pt, stroke: (paint: red, thickness: 1pt, dash: (1pt, 3pt, 9pt)))
 
+---
+// Dash phase relative to the pattern's period and additional presets.
+#set line(length: 60pt)
+#stack(
+  spacing: 5pt,
+  ..(0%, 25%, 50%, 75%, 100%, -25%).map(phase => line(
+    stroke: (paint: red, thickness: 2pt, dash: (array: (6pt, 2pt), phase: phase)),
+  )),
+)
+#line(stroke: (paint: blue, dash: "dash-dot-dotted"))
+#line(stroke: (paint: blue, dash: "densely-dash-dot-dotted"))
+#line(stroke: (paint: blue, dash: "loosely-dash-dot-dotted"))
+
+// All-zero patterns are solid.
+#line(stroke: (paint: blue, cap: "round", dash: (0pt, 0pt)))
+
+---
+// Error: 29-48 dash length must not be negative
+#line(length: 60pt, stroke: (dash: (2pt, -1pt)))
+
 ---
 // Line joins
 #stack(
@@ -60,7 +80,7 @@
 #line(length: 60pt, stroke: (paint: red, thicknes: 1pt))
 
 ---
-// Error: 29-55 expected "solid", "dotted", "densely-dotted", "loosely-dotted", "dashed", "densely-dashed", "loosely-dashed", "dash-dotted", "densely-dash-dotted", "loosely-dash-dotted", array, dictionary, none, or auto
+// Error: 29-55 expected "solid", "dotted", "densely-dotted", "loosely-dotted", "dashed", "densely-dashed", "loosely-dashed", "dash-dotted", "densely-dash-dotted", "loosely-dash-dotted", "dash-dot-dotted", "densely-dash-dot-dotted", "loosely-dash-dot-dotted", array, dictionary, none, or auto
 #line(length: 60pt, stroke: (paint: red, dash: "dash"))
 
 ---